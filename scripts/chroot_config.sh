@@ -134,20 +134,27 @@ main() {
 
     # --- Phase 1: Basic System Configuration ---
     log_info "=== Phase 1: Basic System Configuration ==="
-    echo "PROGRESS: Configuring base system"
+    emit_step 1 5 "Configuring base system"
 
     configure_localization || log_error "Locale configuration failed"
     configure_hostname || log_error "Hostname configuration failed"
     create_user_account || log_error "User account creation failed"
     configure_sudoers || log_error "Sudoers configuration failed"
     enable_base_services || log_error "Base services configuration failed"
+    configure_zram || log_warn "zram configuration failed — continuing"
 
     # --- Phase 2: Bootloader & Initramfs ---
     log_info "=== Phase 2: Bootloader & Initramfs ==="
-    echo "PROGRESS: Configuring bootloader"
+    emit_step 2 5 "Configuring bootloader"
 
     # Enable multilib repository BEFORE GPU drivers (needed for lib32 packages)
-    if [[ "${MULTILIB:-No}" == "Yes" ]]; then
+    if is_offline_install; then
+        # pacman.conf points at the offline repository (install.sh restores it
+        # and enables multilib afterwards); lib32 packages must be in that repository
+        log_info "Offline install: syncing the offline repository..."
+        log_cmd "pacman -Sy"
+        pacman -Sy || log_warn "Failed to sync the offline repository"
+    elif [[ "${MULTILIB:-No}" == "Yes" ]]; then
         log_info "Enabling multilib repository in chroot..."
         sed -i '/^#\[multilib\]/,/^#Include/s/^#//' /etc/pacman.conf || log_warn "Failed to enable multilib in pacman.conf"
         if ! grep -q '^\[multilib\]' /etc/pacman.conf; then
@@ -180,7 +187,7 @@ main() {
 
     # --- Phase 3: Desktop Environment ---
     log_info "=== Phase 3: Desktop Environment ==="
-    echo "PROGRESS: Installing desktop environment"
+    emit_step 3 5 "Installing desktop environment"
 
     install_aur_helper || log_warn "AUR helper installation failed — continuing"
     install_desktop_environment || log_warn "Desktop environment installation had issues — continuing"
@@ -189,7 +196,7 @@ main() {
 
     # --- Phase 4: Additional Software (non-critical) ---
     log_info "=== Phase 4: Additional Software ==="
-    echo "PROGRESS: Installing additional software"
+    emit_step 4 5 "Installing additional software"
 
     install_flatpak || log_warn "Flatpak installation failed — continuing"
     install_additional_packages || log_warn "Additional packages had issues — continuing"
@@ -197,7 +204,7 @@ main() {
 
     # --- Phase 5: Final Configuration (non-critical) ---
     log_info "=== Phase 5: Final Configuration ==="
-    echo "PROGRESS: Running final configuration"
+    emit_step 5 5 "Running final configuration"
 
     configure_numlock || log_warn "Numlock configuration failed — continuing"
     deploy_dotfiles || log_warn "Dotfiles deployment failed — continuing"
@@ -407,6 +414,24 @@ enable_base_services() {
     log_success "Base services enabled"
 }
 
+# Compressed swap in RAM via zram-generator (installed by pacstrap)
+# With zram+swapfile, zram gets the higher priority so the swapfile is overflow
+configure_zram() {
+    case "${SWAP_MODE:-none}" in
+        zram|zram+swapfile) ;;
+        *) return 0 ;;
+    esac
+
+    log_info "Configuring zram swap..."
+    cat > /etc/systemd/zram-generator.conf <<'ZRAM_EOF'
+[zram0]
+zram-size = min(ram / 2, 8192)
+compression-algorithm = zstd
+swap-priority = 100
+ZRAM_EOF
+    log_success "zram swap configured (/etc/systemd/zram-generator.conf)"
+}
+
 # Insert mdns_minimal into the hosts: line of /etc/nsswitch.conf so that
 # .local hostname resolution actually works when avahi is installed.
 # Wiki: https://wiki.archlinux.org/title/Avahi#Hostname_resolution
@@ -523,8 +548,8 @@ configure_mkinitcpio() {
         log_info "Added lvm2 hook"
     fi
 
-    # Add resume hook for hibernation (if swap exists)
-    if [[ "${WANT_SWAP:-no}" == "yes" ]]; then
+    # Add resume hook for hibernation (the systemd hook resumes on its own)
+    if [[ -n "$(_build_resume_kernel_params)" && "$use_systemd_hooks" == false ]]; then
         hooks="$hooks resume"
         log_info "Added resume hook for hibernation support"
     fi
@@ -582,8 +607,7 @@ configure_mkinitcpio() {
             _lspci_kms=$(lspci 2>/dev/null) || log_warn "lspci failed — GPU auto-detection for early KMS skipped"
         fi
 
-        if [[ "$_gpu_drv" == "nvidia" || "$_gpu_drv" == "nvidia-open" ]] \
-           || { [[ "$_gpu_drv" == "auto" ]] && echo "$_lspci_kms" | grep -qi nvidia; }; then
+        if _gpu_uses_nvidia_driver; then
             # NVIDIA early KMS: required for Plymouth, DRM modeset, Wayland compositors
             # https://wiki.archlinux.org/title/NVIDIA#DRM_kernel_mode_setting
             modules="$modules nvidia nvidia_modeset nvidia_uvm nvidia_drm"
@@ -634,8 +658,8 @@ configure_mkinitcpio() {
             # Configure mkinitcpio preset for UKI output
             local preset="/etc/mkinitcpio.d/${KERNEL:-linux}.preset"
             if [[ -f "$preset" ]]; then
-                local efi_dir="/efi/EFI/Linux"
-                [[ -d "/boot/EFI" ]] && efi_dir="/boot/EFI/Linux"
+                local efi_dir
+                efi_dir="$(_esp_path || echo /efi)/EFI/Linux"
                 mkdir -p "$efi_dir"
                 # Add UKI output path to preset
                 if ! grep -q "default_uki" "$preset"; then
@@ -659,6 +683,23 @@ configure_mkinitcpio() {
     fi
 }
 
+# Whether the proprietary NVIDIA kernel driver is installed (nvidia/nvidia-open,
+# or Auto when the resolved GPU_PACKAGES pull it in; lspci without them)
+_gpu_uses_nvidia_driver() {
+    local drv="${GPU_DRIVERS:-Auto}"
+    drv="${drv,,}"
+    case "$drv" in
+        nvidia|nvidia-open) return 0 ;;
+        auto) ;;
+        *) return 1 ;;
+    esac
+    if [[ -n "${GPU_PACKAGES:-}" ]]; then
+        [[ " $GPU_PACKAGES " == *" nvidia-dkms "* || " $GPU_PACKAGES " == *" nvidia-open-dkms "* ]]
+    else
+        lspci 2>/dev/null | grep -qi nvidia
+    fi
+}
+
 # Build encryption kernel parameters based on hook type
 # FIDO2 uses systemd hooks (rd.luks.name=), standard LUKS uses traditional (cryptdevice=)
 _build_luks_kernel_params() {
@@ -674,6 +715,32 @@ _build_luks_kernel_params() {
     fi
 }
 
+# Print where the ESP is mounted: /efi (alongside), /boot/efi, or /boot (the
+# storage plans mount their own ESP there). Fails if none is a vfat mount
+_esp_path() {
+    local candidate
+    for candidate in /efi /boot/efi /boot; do
+        if [[ "$(findmnt -n -o FSTYPE "$candidate" 2>/dev/null)" == "vfat" ]]; then
+            echo "$candidate"
+            return 0
+        fi
+    done
+    return 1
+}
+
+# Build hibernation kernel parameters (empty unless HIBERNATION=Yes)
+# Swap partitions resume by UUID; swapfiles by filesystem UUID + resume_offset
+_build_resume_kernel_params() {
+    if [[ "${HIBERNATION:-No}" != "Yes" || -z "${RESUME_UUID:-}" ]]; then
+        return 0
+    fi
+    if [[ -n "${RESUME_OFFSET:-}" ]]; then
+        echo "resume=UUID=${RESUME_UUID} resume_offset=${RESUME_OFFSET}"
+    else
+        echo "resume=UUID=${RESUME_UUID}"
+    fi
+}
+
 install_bootloader() {
     log_info "Installing bootloader: ${BOOTLOADER:-grub}"
 
@@ -705,16 +772,11 @@ install_grub() {
 
     if [[ "${BOOT_MODE:-UEFI}" == "UEFI" ]]; then
         # UEFI installation
-        # Determine EFI directory (check both /efi and /boot/efi)
-        local efi_dir="/efi"
-        if [[ ! -d "$efi_dir" ]]; then
-            efi_dir="/boot/efi"
-        fi
-        if [[ ! -d "$efi_dir" ]]; then
-            # Create it if it doesn't exist but ESP is mounted
-            mkdir -p /efi
-            efi_dir="/efi"
-        fi
+        local efi_dir
+        efi_dir=$(_esp_path) || {
+            log_error "No ESP mounted at /efi, /boot/efi or /boot"
+            return 1
+        }
 
         log_info "Installing GRUB for UEFI to $efi_dir"
         log_cmd "grub-install --target=x86_64-efi --efi-directory=$efi_dir --bootloader-id=GRUB --modules=tpm --disable-shim-lock --recheck"
@@ -861,9 +923,9 @@ install_systemd_boot() {
     options="$options rw"
 
     # Resume for hibernation
-    if [[ "${WANT_SWAP:-no}" == "yes" && -n "${SWAP_UUID:-}" ]]; then
-        options="$options resume=UUID=${SWAP_UUID}"
-    fi
+    local resume_params
+    resume_params=$(_build_resume_kernel_params)
+    [[ -n "$resume_params" ]] && options="$options $resume_params"
 
     # RAID0 layout parameter (kernel 5.3.4+, Arch wiki RAID)
     if [[ "${RAID_LEVEL:-}" == "raid0" ]]; then
@@ -878,10 +940,7 @@ install_systemd_boot() {
     fi
 
     # NVIDIA DRM modeset
-    local _gpu_sdb="${GPU_DRIVERS:-Auto}"
-    _gpu_sdb="${_gpu_sdb,,}"
-    if [[ "$_gpu_sdb" == "nvidia" || "$_gpu_sdb" == "nvidia-open" ]] \
-       || { [[ "$_gpu_sdb" == "auto" ]] && lspci 2>/dev/null | grep -qi nvidia; }; then
+    if _gpu_uses_nvidia_driver; then
         options="$options nvidia-drm.modeset=1"
     fi
 
@@ -971,9 +1030,9 @@ _build_kernel_options() {
     options="$options rw"
 
     # Resume for hibernation
-    if [[ "${WANT_SWAP:-no}" == "yes" && -n "${SWAP_UUID:-}" ]]; then
-        options="$options resume=UUID=${SWAP_UUID}"
-    fi
+    local resume_params
+    resume_params=$(_build_resume_kernel_params)
+    [[ -n "$resume_params" ]] && options="$options $resume_params"
 
     # RAID0 layout parameter (kernel 5.3.4+, Arch wiki RAID)
     if [[ "${RAID_LEVEL:-}" == "raid0" ]]; then
@@ -988,10 +1047,7 @@ _build_kernel_options() {
     fi
 
     # NVIDIA DRM modeset
-    local _gpu_ko="${GPU_DRIVERS:-Auto}"
-    _gpu_ko="${_gpu_ko,,}"
-    if [[ "$_gpu_ko" == "nvidia" || "$_gpu_ko" == "nvidia-open" ]] \
-       || { [[ "$_gpu_ko" == "auto" ]] && lspci 2>/dev/null | grep -qi nvidia; }; then
+    if _gpu_uses_nvidia_driver; then
         options="$options nvidia-drm.modeset=1"
     fi
 
@@ -1007,9 +1063,11 @@ install_refind() {
     fi
 
     # Determine ESP path for refind-install
-    local esp_path="/efi"
-    [[ ! -d "$esp_path" ]] && esp_path="/boot/efi"
-    [[ ! -d "$esp_path" ]] && esp_path="/boot"
+    local esp_path
+    esp_path=$(_esp_path) || {
+        log_error "No ESP mounted at /efi, /boot/efi or /boot"
+        return 1
+    }
 
     log_cmd "refind-install --esp-path=$esp_path"
     refind-install --esp-path="$esp_path" || {
@@ -1053,8 +1111,8 @@ install_limine() {
 
     if [[ "${BOOT_MODE:-UEFI}" == "UEFI" ]]; then
         # UEFI: copy EFI binary to ESP
-        local esp_path="/efi"
-        [[ -d "/boot/EFI" ]] && esp_path="/boot"
+        local esp_path
+        esp_path=$(_esp_path) || { log_error "No ESP mounted at /efi, /boot/efi or /boot"; return 1; }
         mkdir -p "${esp_path}/EFI/BOOT" || { log_error "Failed to create EFI directory"; return 1; }
         log_cmd "cp /usr/share/limine/BOOTX64.EFI ${esp_path}/EFI/BOOT/"
         cp /usr/share/limine/BOOTX64.EFI "${esp_path}/EFI/BOOT/" || {
@@ -1119,7 +1177,9 @@ install_efistub() {
 
     # Get ESP disk and partition number from findmnt
     local esp_source
-    esp_source=$(findmnt -n -o SOURCE /efi 2>/dev/null || findmnt -n -o SOURCE /boot 2>/dev/null || echo "")
+    local esp_path
+    esp_path=$(_esp_path) || esp_path="/boot"
+    esp_source=$(findmnt -n -o SOURCE "$esp_path" 2>/dev/null || echo "")
     if [[ -z "$esp_source" ]]; then
         log_error "Cannot determine ESP device for efibootmgr"
         return 1
@@ -1239,10 +1299,10 @@ configure_grub_settings() {
         log_info "Added Btrfs subvolume rootflags"
     fi
 
-    # Add resume parameter for hibernation if swap exists
-    if [[ "${WANT_SWAP:-no}" == "yes" && -n "${SWAP_UUID:-}" ]]; then
-        cmdline="$cmdline resume=UUID=${SWAP_UUID}"
-    fi
+    # Add resume parameters for hibernation
+    local resume_params
+    resume_params=$(_build_resume_kernel_params)
+    [[ -n "$resume_params" ]] && cmdline="$cmdline $resume_params"
 
     # Add Plymouth parameters if enabled
     if [[ "${PLYMOUTH:-No}" == "Yes" ]]; then
@@ -1250,10 +1310,7 @@ configure_grub_settings() {
     fi
 
     # NVIDIA DRM modeset (required for Wayland compositors, Plymouth, early KMS)
-    local _gpu_grub="${GPU_DRIVERS:-Auto}"
-    _gpu_grub="${_gpu_grub,,}"
-    if [[ "$_gpu_grub" == "nvidia" || "$_gpu_grub" == "nvidia-open" ]] \
-       || { [[ "$_gpu_grub" == "auto" ]] && lspci 2>/dev/null | grep -qi nvidia; }; then
+    if _gpu_uses_nvidia_driver; then
         cmdline="$cmdline nvidia-drm.modeset=1"
         log_info "Added nvidia-drm.modeset=1 for NVIDIA DRM KMS"
     fi
@@ -1361,6 +1418,12 @@ _grub_theme_git_clone() {
     local repo_url="$1"
     local clone_dir="$2"
     local theme_name="$3"
+
+    if is_offline_install; then
+        log_offline_skip "GRUB theme download for $theme_name ($repo_url)"
+        return 0
+    fi
+
     local tmp_dir
     tmp_dir=$(mktemp -d "/tmp/grub-theme.XXXXXX") || { log_error "Failed to create GRUB theme temp directory"; return 1; }
 
@@ -2035,6 +2098,11 @@ install_de_aur_packages() {
         return 0
     fi
 
+    if is_offline_install; then
+        log_offline_skip "AUR packages for $de: ${aur_packages[*]}"
+        return 0
+    fi
+
     local helper="${AUR_HELPER:-none}"
     helper="${helper,,}"
 
@@ -2152,6 +2220,18 @@ install_gpu_drivers() {
 
     log_info "Installing GPU drivers: $gpu"
 
+    # The installer resolves the packages from the probed PCI IDs (GPU_PACKAGES,
+    # lib32-* already dropped without multilib); lspci below is only the
+    # fallback for install.sh run without it
+    if [[ -n "${GPU_PACKAGES:-}" ]]; then
+        local -a gpu_pkgs
+        read -ra gpu_pkgs <<< "$GPU_PACKAGES"
+        log_info "Resolved GPU packages: ${gpu_pkgs[*]}"
+        pacman -S "${gpu_pkgs[@]}" --noconfirm --needed || log_warn "Failed to install GPU drivers"
+        log_success "GPU driver installation complete"
+        return 0
+    fi
+
     # Only include lib32 packages if multilib repo is enabled
     local use_lib32="no"
     if [[ "$multilib" == "Yes" ]]; then
@@ -2231,6 +2311,11 @@ install_aur_helper() {
         return 0
     fi
 
+    if is_offline_install; then
+        log_offline_skip "AUR helper $helper (install it after first boot)"
+        return 0
+    fi
+
     log_info "Installing AUR helper: $helper"
 
     # AUR helpers must be built as non-root user (unique temp dir per invocation)
@@ -2312,7 +2397,11 @@ install_flatpak() {
     }
 
     # Add Flathub repository for the user (--user flag, no sudo needed)
-    runuser -u "$MAIN_USERNAME" -- flatpak remote-add --if-not-exists --user flathub https://flathub.org/repo/flathub.flatpakrepo || log_warn "Failed to add Flathub repository"
+    if is_offline_install; then
+        log_offline_skip "Flathub remote (add it after first boot: flatpak remote-add --user flathub https://flathub.org/repo/flathub.flatpakrepo)"
+    else
+        runuser -u "$MAIN_USERNAME" -- flatpak remote-add --if-not-exists --user flathub https://flathub.org/repo/flathub.flatpakrepo || log_warn "Failed to add Flathub repository"
+    fi
 
     log_success "Flatpak installed"
 }
@@ -2343,7 +2432,9 @@ install_additional_packages() {
     fi
 
     # Install additional AUR packages (if AUR helper is available)
-    if [[ -n "${ADDITIONAL_AUR_PACKAGES:-}" ]]; then
+    if [[ -n "${ADDITIONAL_AUR_PACKAGES:-}" ]] && is_offline_install; then
+        log_offline_skip "additional AUR packages: $ADDITIONAL_AUR_PACKAGES"
+    elif [[ -n "${ADDITIONAL_AUR_PACKAGES:-}" ]]; then
         local helper="${AUR_HELPER:-none}"
         helper="${helper,,}"
 
@@ -2650,6 +2741,11 @@ deploy_dotfiles() {
         return 0
     fi
 
+    if is_offline_install; then
+        log_offline_skip "dotfiles from $GIT_REPOSITORY_URL"
+        return 0
+    fi
+
     # Validate URL scheme — only allow https:// for security (no ssh://, git://, file://)
     if [[ "$GIT_REPOSITORY_URL" != https://* ]]; then
         log_warn "Dotfiles URL rejected: only https:// URLs are allowed (got: $GIT_REPOSITORY_URL)"
@@ -19,6 +19,20 @@ fi
 # NOTE: jq is only required for JSON config file mode, not for TUI mode
 # TUI mode passes all configuration as environment variables
 
+# Map a SWAP value to a swap mode (none/partition/swapfile/zram/zram+swapfile).
+# Accepts the Display names, the serde names in config files ("ZramSwapfile")
+# and the legacy Yes/No, which map to partition/none.
+normalize_swap_mode() {
+    local mode
+    mode="$(echo "${1:-}" | tr '[:upper:]' '[:lower:]')"
+    case "$mode" in
+        yes) echo "partition" ;;
+        zramswapfile) echo "zram+swapfile" ;;
+        partition|swapfile|zram|zram+swapfile) echo "$mode" ;;
+        *) echo "none" ;;
+    esac
+}
+
 # Check if jq is available (called only when loading JSON config files)
 check_jq_available() {
     if ! command -v jq >/dev/null 2>&1; then
@@ -70,8 +84,12 @@ load_config_from_json() {
     export ENCRYPTION_PASSWORD="$(jq -r '.encryption_password // ""' "$config_file")"
     export SWAP="$(jq -r '.swap // "No"' "$config_file")"
     export SWAP_SIZE="$(jq -r '.swap_size // "N/A"' "$config_file")"
+    export HIBERNATION="$(jq -r '.hibernation // "No"' "$config_file")"
     export ROOT_SIZE="$(jq -r '.root_size // "50GB"' "$config_file")"
     export HOME_SIZE="$(jq -r '.home_size // "Remaining"' "$config_file")"
+    export ALONGSIDE_ESP="$(jq -r '.alongside_esp // ""' "$config_file")"
+    export SHRINK_PARTITION="$(jq -r '.shrink_partition // ""' "$config_file")"
+    export SHRINK_SIZE="$(jq -r '.shrink_size // ""' "$config_file")"
     export TIMEZONE_REGION="$(jq -r '.timezone_region // "America"' "$config_file")"
     export TIMEZONE="$(jq -r '.timezone // "New_York"' "$config_file")"
     export LOCALE="$(jq -r '.locale // "en_US.UTF-8"' "$config_file")"
@@ -126,9 +144,9 @@ load_config_from_json() {
     WANT_HOME_PARTITION="$(echo "$SEPARATE_HOME" | tr '[:upper:]' '[:lower:]')"
     [[ "$WANT_HOME_PARTITION" == "yes" ]] || WANT_HOME_PARTITION="no"
     export WANT_HOME_PARTITION
-    WANT_SWAP="$(echo "$SWAP" | tr '[:upper:]' '[:lower:]')"
-    [[ "$WANT_SWAP" == "yes" ]] || WANT_SWAP="no"
-    export WANT_SWAP
+    SWAP_MODE="$(normalize_swap_mode "$SWAP")"
+    WANT_SWAP="$([[ "$SWAP_MODE" == "partition" ]] && echo "yes" || echo "no")"
+    export SWAP_MODE WANT_SWAP
     export WANT_SEPARATE_BOOT="$([ "$BOOT_MODE" = "UEFI" ] && echo "yes" || echo "no")"
 
     # Legacy compatibility aliases
@@ -183,7 +201,7 @@ validate_configuration() {
     fi
     
     # Check partitioning strategy
-    local valid_strategies=("auto_simple" "auto_simple_luks" "auto_lvm" "auto_luks_lvm" "auto_raid" "auto_raid_luks" "auto_raid_lvm" "auto_raid_lvm_luks" "manual" "pre_mounted")
+    local valid_strategies=("auto_simple" "auto_simple_luks" "auto_lvm" "auto_luks_lvm" "auto_raid" "auto_raid_luks" "auto_raid_lvm" "auto_raid_lvm_luks" "auto_alongside" "manual" "pre_mounted")
     if [[ -n "$PARTITIONING_STRATEGY" ]]; then
         local is_valid=false
         for strategy in "${valid_strategies[@]}"; do
@@ -220,7 +238,7 @@ display_configuration() {
     log_info "  Home Filesystem: $HOME_FILESYSTEM"
     log_info "  Separate Home: $SEPARATE_HOME"
     log_info "  Encryption: $ENCRYPTION"
-    log_info "  Swap: $SWAP"
+    log_info "  Swap: $SWAP_MODE (hibernation: $HIBERNATION)"
     log_info "  Hostname: $SYSTEM_HOSTNAME"
     log_info "  Username: $MAIN_USERNAME"
     log_info "  Desktop Environment: $DESKTOP_ENVIRONMENT"
@@ -50,52 +50,10 @@ execute_disk_strategy() {
 
 # --- Strategy Functions (Load and Execute) ---
 
-# Simple partitioning
-do_auto_simple_partitioning_efi_xbootldr() {
-    source_or_die "$_STRAT_DIR/strategies/simple.sh"
-    execute_simple_partitioning
-}
-
-# Simple LUKS partitioning
-do_auto_simple_luks_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/simple_luks.sh"
-    execute_simple_luks_partitioning
-}
-
-# LVM partitioning
-do_auto_lvm_partitioning_efi_xbootldr() {
-    source_or_die "$_STRAT_DIR/strategies/lvm.sh"
-    execute_lvm_partitioning
-}
-
-# LVM + LUKS partitioning
-do_auto_luks_lvm_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/lvm_luks.sh"
-    execute_lvm_luks_partitioning
-}
-
-# RAID partitioning
-do_auto_raid_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/raid.sh"
-    execute_raid_partitioning
-}
-
-# RAID + LUKS partitioning
-do_auto_raid_luks_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/raid_luks.sh"
-    execute_raid_luks_partitioning
-}
-
-# RAID + LVM partitioning
-do_auto_raid_lvm_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/raid_lvm.sh"
-    execute_raid_lvm_partitioning
-}
-
-# RAID + LVM + LUKS partitioning
-do_auto_raid_lvm_luks_partitioning() {
-    source_or_die "$_STRAT_DIR/strategies/raid_lvm_luks.sh"
-    execute_raid_lvm_luks_partitioning
+# Automatic strategies and declared layouts: execute the storage plan archtui computed
+do_storage_plan_partitioning() {
+    source_or_die "$_STRAT_DIR/strategies/storage_plan.sh"
+    execute_storage_plan_partitioning
 }
 
 # Manual partitioning
@@ -113,16 +71,6 @@ do_pre_mounted_partitioning() {
 # --- Legacy Functions (for backward compatibility) ---
 # These map to the new modular functions
 
-# Legacy simple partitioning
-do_auto_simple_partitioning() {
-    do_auto_simple_partitioning_efi_xbootldr
-}
-
-# Legacy LVM partitioning  
-do_auto_lvm_partitioning() {
-    do_auto_lvm_partitioning_efi_xbootldr
-}
-
 # Legacy manual partitioning
 do_manual_partitioning() {
     do_manual_partitioning_guided
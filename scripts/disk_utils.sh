@@ -50,6 +50,11 @@ if [[ -z "${EFI_PARTITION_TYPE:-}" ]]; then
     readonly DEFAULT_HOME_FILESYSTEM="ext4"
     readonly EFI_FILESYSTEM="vfat"
     readonly BOOT_FILESYSTEM="ext4"
+
+    # Btrfs subvolume layout used when BTRFS_SUBVOLUMES is unset
+    # (name|mountpoint|mount_options entries separated by ';'); mirrors
+    # default_btrfs_subvolumes in src/config_file.rs
+    readonly DEFAULT_BTRFS_SUBVOLUMES="@|/|compress=zstd,noatime;@home|/home|compress=zstd,noatime;@log|/var/log|compress=zstd,noatime;@pkg|/var/cache/pacman/pkg|compress=zstd,noatime;@snapshots|/.snapshots|compress=zstd,noatime"
 fi
 
 # --- Bootloader Capability Helpers ---
@@ -132,26 +137,67 @@ get_swap_size_mib() {
         fi
     fi
 
-    # Priority 2: RAM-based calculation (if parameter or RAM_GB env var set)
+    # Priority 2: RAM-based calculation ("Auto"/"N/A"): parameter, RAM_GB env var,
+    # or detected RAM
     local ram_gb="${1:-${RAM_GB:-}}"
 
     # Handle both "16" and "16G" formats
     local ram_val="${ram_gb%[Gg]*}"
+    local ram_mib=0
+    if [[ -n "$ram_val" && "$ram_val" =~ ^[0-9]+$ ]]; then
+        ram_mib=$(( ram_val * 1024 ))
+    elif [[ -z "$ram_gb" ]]; then
+        local ram_kb
+        ram_kb=$(awk '/MemTotal/ {print $2}' /proc/meminfo 2>/dev/null || echo "0")
+        ram_mib=$(( ${ram_kb:-0} / 1024 ))
+    fi
 
-    # Fallback if not a number or empty
-    if [[ -z "$ram_val" || ! "$ram_val" =~ ^[0-9]+$ ]]; then
+    # Fallback if RAM is unknown
+    if (( ram_mib == 0 )); then
         echo "$DEFAULT_SWAP_SIZE_MIB"
         return
     fi
 
     # Calculate swap size: Ram <= 4GB ? 2x RAM : 1x RAM (Capped at 16GB)
-    if (( ram_val <= 4 )); then
-        echo $(( ram_val * 1024 * 2 ))
-    elif (( ram_val <= 16 )); then
-         echo $(( ram_val * 1024 ))
+    local swap_mib
+    if (( ram_mib <= 4096 )); then
+        swap_mib=$(( ram_mib * 2 ))
+    elif (( ram_mib <= 16384 )); then
+        swap_mib="$ram_mib"
     else
-        echo "16384" # Cap at 16GB swap for large RAM
+        swap_mib=16384 # Cap at 16GB swap for large RAM
+    fi
+
+    # Hibernation writes all of RAM to swap — never size below it
+    if [[ "${HIBERNATION:-No}" == "Yes" ]] && (( swap_mib < ram_mib )); then
+        swap_mib="$ram_mib"
     fi
+    echo "$swap_mib"
+}
+
+# Convert a size like "50GB", "512MiB", "2TB" or "25%" to MiB (same rules as
+# DiskSize in src/types.rs). A bare number is GiB; percentages are of the
+# first INSTALL_DISK. Prints nothing if the size cannot be parsed.
+size_spec_to_mib() {
+    local spec="${1//[[:space:]]/}"
+
+    if [[ "$spec" =~ ^([0-9]+)%$ ]]; then
+        local pct="${BASH_REMATCH[1]}" disk_bytes
+        disk_bytes=$(blockdev --getsize64 "${INSTALL_DISK%%,*}" 2>/dev/null || echo 0)
+        if (( pct >= 1 && pct <= 100 && disk_bytes > 0 )); then
+            echo $(( disk_bytes / 1048576 * pct / 100 ))
+        fi
+        return
+    fi
+
+    [[ "$spec" =~ ^([0-9]+)([A-Za-z]*)$ ]] || return
+    local size_val="$(( 10#${BASH_REMATCH[1]} ))" size_unit="${BASH_REMATCH[2]^^}"
+    (( size_val > 0 )) || return
+    case "$size_unit" in
+        "TB"|"T"|"TIB") echo $(( size_val * 1024 * 1024 )) ;;
+        "GB"|"G"|"GIB"|"") echo $(( size_val * 1024 )) ;;
+        "MB"|"M"|"MIB") echo "$size_val" ;;
+    esac
 }
 
 get_root_size_mib() {
@@ -163,31 +209,11 @@ get_root_size_mib() {
         return
     fi
 
-    local size_val size_unit
-    size_val="${user_root//[^0-9]/}"
-    size_unit="${user_root//[0-9]/}"
-    size_unit="${size_unit^^}"
-
-    if [[ -n "$size_val" && "$size_val" =~ ^[0-9]+$ ]]; then
-        case "$size_unit" in
-            "TB"|"T")
-                echo $(( size_val * 1024 * 1024 ))
-                return
-                ;;
-            "GB"|"G")
-                echo $(( size_val * 1024 ))
-                return
-                ;;
-            "MB"|"M"|"MIB")
-                echo "$size_val"
-                return
-                ;;
-            "")
-                # No unit — assume GB
-                echo $(( size_val * 1024 ))
-                return
-                ;;
-        esac
+    local size_mib
+    size_mib=$(size_spec_to_mib "$user_root")
+    if [[ -n "$size_mib" ]]; then
+        echo "$size_mib"
+        return
     fi
 
     # Fallback: 100GB
@@ -209,31 +235,11 @@ get_home_size_mib() {
         return
     fi
 
-    local size_val size_unit
-    size_val="${user_home//[^0-9]/}"
-    size_unit="${user_home//[0-9]/}"
-    size_unit="${size_unit^^}"
-
-    if [[ -n "$size_val" && "$size_val" =~ ^[0-9]+$ ]]; then
-        case "$size_unit" in
-            "TB"|"T")
-                echo $(( size_val * 1024 * 1024 ))
-                return
-                ;;
-            "GB"|"G")
-                echo $(( size_val * 1024 ))
-                return
-                ;;
-            "MB"|"M"|"MIB")
-                echo "$size_val"
-                return
-                ;;
-            "")
-                # No unit — assume GB
-                echo $(( size_val * 1024 ))
-                return
-                ;;
-        esac
+    local size_mib
+    size_mib=$(size_spec_to_mib "$user_home")
+    if [[ -n "$size_mib" ]]; then
+        echo "$size_mib"
+        return
     fi
 
     # Fallback: remaining space
@@ -503,11 +509,11 @@ create_home_partition() {
 
 create_swapfile() {
     local swap_size_mib="$1"
-    local swapfile="/mnt/swapfile"
+    local swapfile="${2:-/mnt/swapfile}"
 
     log_info "Creating ${swap_size_mib}MiB swap file at $swapfile"
 
-    if [[ "$ROOT_FILESYSTEM_TYPE" == "btrfs" ]]; then
+    if [[ "$(findmnt -n -o FSTYPE -T "$(dirname "$swapfile")" 2>/dev/null)" == "btrfs" ]]; then
         # Btrfs requires special handling: use btrfs-specific swapfile creation
         log_cmd "btrfs filesystem mkswapfile --size ${swap_size_mib}m $swapfile"
         btrfs filesystem mkswapfile --size "${swap_size_mib}m" "$swapfile" || {
@@ -530,6 +536,66 @@ create_swapfile() {
     log_success "Swap file created and activated"
 }
 
+# Print the physical offset (in pages) of a swapfile, for resume_offset=.
+get_swapfile_resume_offset() {
+    local swapfile="$1"
+    if [[ "$(findmnt -n -o FSTYPE -T "$swapfile" 2>/dev/null)" == "btrfs" ]]; then
+        btrfs inspect-internal map-swapfile -r "$swapfile"
+    else
+        filefrag -v "$swapfile" | awk '$1 == "0:" { sub(/\.\.$/, "", $4); print $4; exit }'
+    fi
+}
+
+# Finish swap setup once the target is fully mounted.
+#
+# SWAP_MODE "swapfile" and "zram+swapfile" get a swapfile, as does
+# "partition" when the strategy made no swap partition (SWAP_UUID unset).
+# A swapfile the storage plan already created is kept as is.
+# On Btrfs the swapfile lives in its own subvolume at /mnt/swap so root
+# snapshots do not include it: the @swap entry of BTRFS_SUBVOLUMES, mounted
+# by setup_btrfs_subvolumes, or a nested subvolume if nothing is mounted there. With HIBERNATION=Yes, exports RESUME_UUID
+# (plus RESUME_OFFSET for swapfiles) for the bootloader and initramfs.
+setup_swap_target() {
+    local mode="${SWAP_MODE:-none}"
+    local swapfile=""
+
+    case "$mode" in
+        swapfile|zram+swapfile) swapfile="/mnt/swapfile" ;;
+        partition) [[ -z "${SWAP_UUID:-}" ]] && swapfile="/mnt/swapfile" ;;
+    esac
+
+    if [[ -n "$swapfile" ]]; then
+        if [[ "$(findmnt -n -o FSTYPE -T /mnt 2>/dev/null)" == "btrfs" ]]; then
+            if ! mountpoint -q /mnt/swap 2>/dev/null && [[ ! -d /mnt/swap ]]; then
+                log_cmd "btrfs subvolume create /mnt/swap"
+                btrfs subvolume create /mnt/swap || { log_error "Failed to create swap subvolume"; return 1; }
+            fi
+            swapfile="/mnt/swap/swapfile"
+        fi
+        if [[ -f "$swapfile" ]]; then
+            log_info "Swapfile $swapfile already created by the storage plan"
+        else
+            create_swapfile "$(get_swap_size_mib)" "$swapfile" || return 1
+        fi
+    fi
+
+    if [[ "${HIBERNATION:-No}" != "Yes" ]]; then
+        return 0
+    fi
+    if [[ -n "$swapfile" ]]; then
+        RESUME_UUID=$(findmnt -n -o UUID -T "$swapfile") || log_warn "Cannot determine swapfile filesystem UUID"
+        RESUME_OFFSET=$(get_swapfile_resume_offset "$swapfile") || log_warn "Cannot determine swapfile resume offset"
+    elif [[ -n "${SWAP_UUID:-}" ]]; then
+        RESUME_UUID="$SWAP_UUID"
+        RESUME_OFFSET=""
+    else
+        log_warn "Hibernation requested but no disk swap was set up — resume disabled"
+        return 0
+    fi
+    export RESUME_UUID RESUME_OFFSET
+    log_info "Hibernation resume: UUID=${RESUME_UUID:-?}${RESUME_OFFSET:+ offset=$RESUME_OFFSET}"
+}
+
 safe_mount() {
     local device="$1"
     local mountpoint="$2"
@@ -612,12 +678,41 @@ enroll_fido2() {
     log_success "FIDO2 device enrolled on $partition"
 }
 
+# Print the Btrfs subvolumes to create as "depth|name|mountpoint|mount_options",
+# shallowest first so parents are mounted before their children.
+# BTRFS_SUBVOLUMES (exported by InstallationConfig::to_env_vars) holds
+# name|mountpoint|mount_options entries separated by ';'. With include_home
+# other than "yes" the /home entry is dropped (separate home partition).
+btrfs_subvolume_entries() {
+    local include_home="${1:-no}"
+    local -a entries
+    IFS=';' read -ra entries <<< "${BTRFS_SUBVOLUMES:-$DEFAULT_BTRFS_SUBVOLUMES}"
+
+    local entry name mountpoint options depth
+    for entry in "${entries[@]}"; do
+        IFS='|' read -r name mountpoint options <<< "$entry"
+        [[ -n "$name" && -n "$mountpoint" ]] || continue
+        if [[ "$mountpoint" == "/home" && "$include_home" != "yes" ]]; then
+            continue
+        fi
+        depth=$(tr -cd '/' <<< "${mountpoint%/}" | wc -c)
+        printf '%s|%s|%s|%s\n' "$depth" "$name" "$mountpoint" "${options:-compress=zstd,noatime}"
+    done | sort -s -n -t'|' -k1,1
+}
+
 setup_btrfs_subvolumes() {
     local device="$1"
     local include_home="${2:-no}"
 
     log_info "Setting up Btrfs subvolumes on $device"
 
+    local subvolumes
+    subvolumes=$(btrfs_subvolume_entries "$include_home")
+    if ! grep -q '^0|' <<< "$subvolumes"; then
+        log_error "No Btrfs subvolume is mounted at / (check btrfs_subvolumes)"
+        return 1
+    fi
+
     # Mount the device first to create subvolumes
     log_cmd "mount $device /mnt"
     mount "$device" /mnt || {
@@ -625,19 +720,11 @@ setup_btrfs_subvolumes() {
         return 1
     }
 
-    # Create standard subvolume layout
-    log_cmd "btrfs subvolume create /mnt/@ @var @tmp @snapshots @cache @log"
-    btrfs subvolume create /mnt/@ || { umount /mnt; log_error "Failed to create @ subvolume"; return 1; }
-    btrfs subvolume create /mnt/@var || { umount /mnt; log_error "Failed to create @var subvolume"; return 1; }
-    btrfs subvolume create /mnt/@tmp || { umount /mnt; log_error "Failed to create @tmp subvolume"; return 1; }
-    btrfs subvolume create /mnt/@snapshots || { umount /mnt; log_error "Failed to create @snapshots subvolume"; return 1; }
-    btrfs subvolume create /mnt/@cache || { umount /mnt; log_error "Failed to create @cache subvolume"; return 1; }
-    btrfs subvolume create /mnt/@log || { umount /mnt; log_error "Failed to create @log subvolume"; return 1; }
-
-    if [[ "$include_home" == "yes" ]]; then
-        log_cmd "btrfs subvolume create /mnt/@home"
-        btrfs subvolume create /mnt/@home || { umount /mnt; log_error "Failed to create @home subvolume"; return 1; }
-    fi
+    local depth name mountpoint options
+    while IFS='|' read -r depth name mountpoint options; do
+        log_cmd "btrfs subvolume create /mnt/$name"
+        btrfs subvolume create "/mnt/$name" || { umount /mnt; log_error "Failed to create $name subvolume"; return 1; }
+    done <<< "$subvolumes"
 
     # Unmount to remount with proper subvolume
     umount /mnt || {
@@ -645,45 +732,19 @@ setup_btrfs_subvolumes() {
         return 1
     }
 
-    # Mount root subvolume with compression and noatime
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@ $device /mnt"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@ "$device" /mnt || {
-        log_error "Failed to mount @ subvolume"
-        return 1
-    }
+    # Mount shallowest-first; each mountpoint is created inside its parent
+    while IFS='|' read -r depth name mountpoint options; do
+        local target="/mnt${mountpoint%/}"
+        mkdir -p "$target" || { log_error "Failed to create mount point $target"; return 1; }
+        log_cmd "mount -o $options,subvol=$name $device $target"
+        mount -o "$options,subvol=$name" "$device" "$target" || { log_error "Failed to mount $name subvolume"; return 1; }
+    done <<< "$subvolumes"
 
-    # Create top-level mount point directories (inside @ subvolume)
-    mkdir -p /mnt/{var,tmp,.snapshots,boot,efi} || {
+    mkdir -p /mnt/boot /mnt/efi || {
         log_error "Failed to create mount point directories"
         return 1
     }
 
-    # Mount @var, @tmp, @snapshots first (top-level subvolumes)
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@var $device /mnt/var"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@var "$device" /mnt/var || { log_error "Failed to mount @var subvolume"; return 1; }
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@tmp $device /mnt/tmp"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@tmp "$device" /mnt/tmp || { log_error "Failed to mount @tmp subvolume"; return 1; }
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@snapshots $device /mnt/.snapshots"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@snapshots "$device" /mnt/.snapshots || { log_error "Failed to mount @snapshots subvolume"; return 1; }
-
-    # Create nested dirs inside @var (now a real mount, not hidden by @)
-    mkdir -p /mnt/var/cache /mnt/var/log || {
-        log_error "Failed to create nested mount point directories"
-        return 1
-    }
-
-    # Mount @cache, @log inside @var
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@cache $device /mnt/var/cache"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@cache "$device" /mnt/var/cache || { log_error "Failed to mount @cache subvolume"; return 1; }
-    log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@log $device /mnt/var/log"
-    mount -o compress=zstd,noatime,space_cache=v2,subvol=@log "$device" /mnt/var/log || { log_error "Failed to mount @log subvolume"; return 1; }
-
-    if [[ "$include_home" == "yes" ]]; then
-        mkdir -p /mnt/home
-        log_cmd "mount -o compress=zstd,noatime,space_cache=v2,subvol=@home $device /mnt/home"
-        mount -o compress=zstd,noatime,space_cache=v2,subvol=@home "$device" /mnt/home || { log_error "Failed to mount @home subvolume"; return 1; }
-    fi
-
     log_success "Btrfs subvolumes created and mounted"
 }
 
@@ -958,6 +1019,132 @@ mount_standard_partitions() {
     log_success "All partitions mounted"
 }
 
+# =============================================================================
+# FREE-SPACE PARTITIONING (install alongside)
+# =============================================================================
+
+# Shrink an NTFS or ext4 partition on a GPT disk to a new size (e.g. 200G).
+# The filesystem is shrunk first; the partition entry is then recreated with the
+# same start sector, type, unique GUID and name so no data moves.
+shrink_partition() {
+    local part="$1"
+    local size="$2"
+
+    [[ -b "$part" ]] || { log_error "Partition not found: $part"; return 1; }
+    if findmnt -n "$part" >/dev/null 2>&1; then
+        log_error "$part is mounted — unmount it before shrinking"
+        return 1
+    fi
+
+    local disk part_num
+    disk="/dev/$(lsblk -no PKNAME "$part" 2>/dev/null | head -1)"
+    part_num=$(cat "/sys/class/block/$(basename "$(readlink -f "$part")")/partition" 2>/dev/null || echo "")
+    if [[ -z "$part_num" || ! -b "$disk" ]]; then
+        log_error "Cannot determine the disk and partition number of $part"
+        return 1
+    fi
+    if [[ "$(blkid -o value -s PTTYPE "$disk" 2>/dev/null)" != "gpt" ]]; then
+        log_error "Shrinking is only supported on GPT disks ($disk)"
+        return 1
+    fi
+
+    local size_bytes current_bytes
+    size_bytes=$(numfmt --from=iec "${size%B}" 2>/dev/null) || { log_error "Invalid size: $size"; return 1; }
+    current_bytes=$(blockdev --getsize64 "$part")
+    if (( size_bytes >= current_bytes )); then
+        log_error "New size $size is not smaller than $part ($(( current_bytes / 1048576 ))MiB)"
+        return 1
+    fi
+
+    local fstype
+    fstype=$(blkid -o value -s TYPE "$part" 2>/dev/null || echo "")
+    log_info "Shrinking $fstype filesystem on $part to $size"
+    case "$fstype" in
+        ntfs)
+            # Dry run first, without --force, so a dirty or hibernated volume is refused
+            log_cmd "ntfsresize --no-action --size $size_bytes $part"
+            ntfsresize --no-action --size "$size_bytes" "$part" || {
+                log_error "NTFS on $part cannot be shrunk to $size — boot Windows, run chkdsk and disable Fast Startup"
+                return 1
+            }
+            log_cmd "ntfsresize --force --size $size_bytes $part"
+            ntfsresize --force --size "$size_bytes" "$part" || { log_error "ntfsresize failed on $part"; return 1; }
+            ;;
+        ext4)
+            # e2fsck exits 1 when it fixed errors; only >1 is fatal
+            log_cmd "e2fsck -f -y $part"
+            e2fsck -f -y "$part" || (( $? <= 1 )) || { log_error "e2fsck failed on $part"; return 1; }
+            log_cmd "resize2fs $part $(( size_bytes / 1024 ))K"
+            resize2fs "$part" "$(( size_bytes / 1024 ))K" || { log_error "resize2fs failed on $part"; return 1; }
+            ;;
+        *)
+            log_error "Cannot shrink $part: unsupported filesystem '${fstype:-none}' (ntfs or ext4 only)"
+            return 1
+            ;;
+    esac
+
+    local info first_sector sector_size type_guid unique_guid name attrs
+    info=$(sgdisk -i "$part_num" "$disk")
+    first_sector=$(awk '/^First sector:/ {print $3}' <<< "$info")
+    type_guid=$(awk '/^Partition GUID code:/ {print $4}' <<< "$info")
+    unique_guid=$(awk '/^Partition unique GUID:/ {print $4}' <<< "$info")
+    attrs=$(awk '/^Attribute flags:/ {print $3}' <<< "$info")
+    name=$(sed -n "s/^Partition name: '\(.*\)'$/\1/p" <<< "$info")
+    sector_size=$(blockdev --getss "$disk")
+    local last_sector=$(( first_sector + size_bytes / sector_size - 1 ))
+
+    if [[ -n "$attrs" && "$attrs" != "0000000000000000" ]]; then
+        log_warn "Attribute flags $attrs of $part are not preserved"
+    fi
+
+    log_cmd "sgdisk -d $part_num -n ${part_num}:${first_sector}:${last_sector} -t ${part_num}:${type_guid} -u ${part_num}:${unique_guid} -c ${part_num}:'${name}' $disk"
+    sgdisk -d "$part_num" \
+        -n "${part_num}:${first_sector}:${last_sector}" \
+        -t "${part_num}:${type_guid}" \
+        -u "${part_num}:${unique_guid}" \
+        -c "${part_num}:${name}" \
+        "$disk" || { log_error "Failed to resize partition entry $part_num on $disk"; return 1; }
+    sync_partitions "$disk"
+    log_success "Shrunk $part to $size"
+}
+
+# Create a partition at the start of the largest free block of a GPT disk,
+# using the first free partition number. The partition is named so it can be
+# found at /dev/disk/by-partlabel/<label>; an existing label is refused.
+# Usage: create_partition_in_free_space <disk> <size|remaining> <type> <label>
+create_partition_in_free_space() {
+    local disk="$1"
+    local size="$2"
+    local type_code="$3"
+    local label="$4"
+
+    if [[ "$(blkid -o value -s PTTYPE "$disk" 2>/dev/null)" != "gpt" ]]; then
+        log_error "Free-space partitioning is only supported on GPT disks ($disk)"
+        return 1
+    fi
+    if [[ -e "/dev/disk/by-partlabel/$label" ]]; then
+        log_error "A partition named '$label' already exists — remove or rename it first"
+        return 1
+    fi
+
+    local size_spec
+    if [[ "${size,,}" == "remaining" ]]; then
+        size_spec="0"
+    else
+        size_spec="+${size}"
+    fi
+
+    log_cmd "sgdisk -n 0:0:${size_spec} -t 0:${type_code} -c 0:${label} $disk"
+    sgdisk -n "0:0:${size_spec}" -t "0:${type_code}" -c "0:${label}" "$disk" || {
+        log_error "Failed to create '$label' in free space on $disk (not enough unallocated space?)"
+        return 1
+    }
+    sync_partitions "$disk"
+
+    [[ -b "/dev/disk/by-partlabel/$label" ]] || { log_error "Partition '$label' not visible after creation"; return 1; }
+    log_info "Created $(readlink -f "/dev/disk/by-partlabel/$label") ($label)"
+}
+
 # =============================================================================
 # FILESYSTEM FORMATTING
 # =============================================================================
@@ -18,9 +18,26 @@ cleanup_on_exit() {
 
     echo "=== CLEANUP ON EXIT (Code: $exit_code) ==="
 
+    # Past partitioning, keep the target mounted so the install can be resumed
+    if [[ -n "${ARCHTUI_CHECKPOINT_DIR:-}" ]] && stage_after_partitioning "${CURRENT_STAGE:-}"; then
+        echo "Leaving /mnt mounted so the installation can be resumed:"
+        echo "  archtui install --config <file> --resume"
+        echo "To abandon it instead: swapoff -a && umount -R /mnt"
+        echo "=== CLEANUP COMPLETE ==="
+        exit $exit_code
+    fi
+
     # Deactivate swap before unmounting (swapfiles on /mnt block umount)
     swapoff -a 2>/dev/null || true
 
+    # A storage plan is torn down by archtui from the ops apply-storage
+    # recorded as applied, once this script has exited
+    if [[ -n "${ARCHTUI_STORAGE_PLAN:-}" ]]; then
+        echo "Leaving the applied storage plan ops to archtui's teardown"
+        echo "=== CLEANUP COMPLETE ==="
+        exit $exit_code
+    fi
+
     # Try to unmount everything cleanly (in reverse order)
     for mount_point in /mnt/home /mnt/boot /mnt/efi /mnt; do
         if mountpoint -q "$mount_point" 2>/dev/null; then
@@ -195,8 +212,11 @@ ROOT_FILESYSTEM_TYPE="$ROOT_FILESYSTEM"
 HOME_FILESYSTEM_TYPE="$HOME_FILESYSTEM"
 WANT_HOME_PARTITION="$(echo "$SEPARATE_HOME" | tr '[:upper:]' '[:lower:]')"
 [[ "$WANT_HOME_PARTITION" == "yes" ]] || WANT_HOME_PARTITION="no"
-WANT_SWAP="$(echo "$SWAP" | tr '[:upper:]' '[:lower:]')"
-[[ "$WANT_SWAP" == "yes" ]] || WANT_SWAP="no"
+# SWAP is a swap mode (see normalize_swap_mode in config_loader.sh).
+# WANT_SWAP drives the swap partition/LV in the strategies.
+SWAP_MODE="$(normalize_swap_mode "$SWAP")"
+WANT_SWAP="$([[ "$SWAP_MODE" == "partition" ]] && echo "yes" || echo "no")"
+HIBERNATION="${HIBERNATION:-No}"
 
 # For RAID strategies, parse comma-separated INSTALL_DISK into RAID_DEVICES array
 if [[ "$PARTITIONING_STRATEGY" == *"raid"* ]]; then
@@ -209,6 +229,7 @@ fi
 
 # Export for strategy scripts
 export ROOT_FILESYSTEM_TYPE HOME_FILESYSTEM_TYPE WANT_HOME_PARTITION WANT_SWAP SWAP_SIZE
+export SWAP_MODE HIBERNATION
 export ROOT_SIZE HOME_SIZE
 # Suppress set -x tracing for ENCRYPTION_PASSWORD export
 { set +x; } 2>/dev/null
@@ -232,6 +253,8 @@ KERNEL="${KERNEL:-linux}"
 MULTILIB="${MULTILIB:-No}"
 ADDITIONAL_PACKAGES="${ADDITIONAL_PACKAGES:-}"
 GPU_DRIVERS="${GPU_DRIVERS:-Auto}"
+GPU_PACKAGES="${GPU_PACKAGES:-}"
+PACSTRAP_PACKAGES="${PACSTRAP_PACKAGES:-}"
 
 # User Setup
 SYSTEM_HOSTNAME="${SYSTEM_HOSTNAME:-archlinux}"
@@ -264,49 +287,140 @@ NUMLOCK_ON_BOOT="${NUMLOCK_ON_BOOT:-No}"
 GIT_REPOSITORY="${GIT_REPOSITORY:-No}"
 GIT_REPOSITORY_URL="${GIT_REPOSITORY_URL:-}"
 
+# --- Resume Support ---
+# ARCHTUI_RESUME_STAGE is set by `archtui install --resume` after it has
+# checked the checkpoint and the target mounts. ARCHTUI_CHECKPOINT_DIR is
+# where the Rust side keeps its checkpoint; storage state lives next to it.
+RESUME_STAGE="${ARCHTUI_RESUME_STAGE:-}"
+STORAGE_STATE_FILE="${ARCHTUI_CHECKPOINT_DIR:-}/storage-state.sh"
+
+# Variables set during partitioning that later stages rely on
+STORAGE_STATE_VARS=(
+    BOOT_MODE ROOT_UUID LUKS_UUID SWAP_UUID RESUME_UUID RESUME_OFFSET
+    ROOT_DEVICE BOOT_DEVICE EFI_DEVICE HOME_DEVICE SWAP_DEVICE LUKS_DEVICE
+    WINDOWS_DETECTED WINDOWS_EFI_PATH WINDOWS_ESP_DEVICE
+    OTHER_OS_DETECTED OTHER_LINUX_DETECTED OTHER_LINUX_NAME OTHER_LINUX_DEVICE
+    OTHER_LINUX_SAME_DISK
+)
+
+# Usage: should_run_stage <stage_id> — false for stages a resumed install already completed
+should_run_stage() {
+    [[ -z "$RESUME_STAGE" ]] && return 0
+    (( $(stage_index "$1") >= $(stage_index "$RESUME_STAGE") ))
+}
+
+save_storage_state() {
+    if [[ -z "${ARCHTUI_CHECKPOINT_DIR:-}" ]]; then
+        log_debug "No checkpoint directory — storage state not saved"
+        return 0
+    fi
+
+    mkdir -p "$ARCHTUI_CHECKPOINT_DIR" || return 1
+    local var
+    for var in "${STORAGE_STATE_VARS[@]}"; do
+        printf 'export %s=%q\n' "$var" "${!var:-}"
+    done > "$STORAGE_STATE_FILE" || return 1
+    log_info "Storage state saved to $STORAGE_STATE_FILE"
+}
+
+resume_installation() {
+    if ! stage_after_partitioning "$RESUME_STAGE"; then
+        log_error "Cannot resume at '$RESUME_STAGE': only stages after partitioning can be resumed"
+        return 1
+    fi
+    if [[ ! -f "$STORAGE_STATE_FILE" ]]; then
+        log_error "Storage state not found at $STORAGE_STATE_FILE"
+        return 1
+    fi
+
+    log_info "Resuming installation at stage: $RESUME_STAGE"
+    # shellcheck source=/dev/null
+    source "$STORAGE_STATE_FILE" || return 1
+    log_info "Loaded storage state from $STORAGE_STATE_FILE"
+
+    # Re-check credentials and re-derive settings without touching the disk
+    validate_configuration || return 1
+    if ! mountpoint -q /mnt; then
+        log_error "Target root is not mounted at /mnt"
+        return 1
+    fi
+}
+
 # --- Main Installation Function ---
 main() {
     echo "Starting Arch Linux installation..."
 
-    # Phase 1: Validate configuration
-    log_info "Phase 1: Validating configuration..."
-    validate_configuration || error_exit "Configuration validation failed"
-
-    # Phase 2: Prepare system
-    log_info "Phase 2: Preparing system..."
-    prepare_system || error_exit "System preparation failed"
+    if [[ -n "$RESUME_STAGE" ]]; then
+        # Resume: phases 1-4 already completed, the disk stays as partitioned.
+        # The live system may have rebooted since, so redo its preparation
+        # (clock, mirrors, package databases) without re-emitting the stage.
+        resume_installation || error_exit "Cannot resume installation"
+        log_info "Re-preparing the live system for the resumed stages..."
+        prepare_system || error_exit "System preparation failed"
+    else
+        # Phase 1: Validate configuration
+        emit_stage validating_config
+        log_info "Phase 1: Validating configuration..."
+        validate_configuration || error_exit "Configuration validation failed"
+
+        # Phase 2: Prepare system
+        emit_stage preparing_system
+        log_info "Phase 2: Preparing system..."
+        prepare_system || error_exit "System preparation failed"
+
+        # Phase 3: Check and install dependencies
+        emit_stage installing_dependencies
+        log_info "Phase 3: Installing dependencies..."
+        check_and_install_dependencies || error_exit "Dependency installation failed"
+
+        # Phase 4: Partition disk
+        emit_stage partitioning_disk
+        log_info "Phase 4: Partitioning disk..."
+        partition_disk || error_exit "Disk partitioning failed"
+        save_storage_state || error_exit "Failed to save storage state"
+    fi
 
-    # Phase 3: Check and install dependencies
-    log_info "Phase 3: Installing dependencies..."
-    check_and_install_dependencies || error_exit "Dependency installation failed"
+    if should_run_stage installing_base_system; then
+        # Phase 5: Install base system (pacstrap). The stage is emitted before
+        # the network pre-flight so a failed check is reported (and resumable)
+        # as installing_base_system, not as a partitioning failure.
+        emit_stage installing_base_system
+        log_info "Phase 5: Installing base system..."
 
-    # Phase 4: Partition disk
-    log_info "Phase 4: Partitioning disk..."
-    partition_disk || error_exit "Disk partitioning failed"
+        # Pre-flight: verify network connectivity before downloading packages
+        if is_offline_install; then
+            log_offline_skip "network check (archtui verified every package in ${OFFLINE_PKG_DIR})"
+        else
+            log_info "Verifying network connectivity..."
+            if ! curl -s --max-time 15 --head https://archlinux.org >/dev/null 2>&1 \
+               && ! ping -c 2 -W 5 archlinux.org >/dev/null 2>&1 \
+               && ! getent hosts archlinux.org >/dev/null 2>&1; then
+                error_exit "No network connectivity — pacstrap requires internet access. Check your connection and try again."
+            fi
+        fi
 
-    # Pre-flight: verify network connectivity before downloading packages
-    log_info "Verifying network connectivity..."
-    if ! curl -s --max-time 15 --head https://archlinux.org >/dev/null 2>&1 \
-       && ! ping -c 2 -W 5 archlinux.org >/dev/null 2>&1 \
-       && ! getent hosts archlinux.org >/dev/null 2>&1; then
-        error_exit "No network connectivity — pacstrap requires internet access. Check your connection and try again."
+        install_base_system || error_exit "Base system installation failed"
     fi
 
-    # Phase 5: Install base system (pacstrap)
-    log_info "Phase 5: Installing base system..."
-    install_base_system || error_exit "Base system installation failed"
-
-    # Phase 6: Generate fstab
-    log_info "Phase 6: Generating fstab..."
-    generate_fstab || error_exit "fstab generation failed"
+    if should_run_stage generating_fstab; then
+        # Phase 6: Generate fstab
+        emit_stage generating_fstab
+        log_info "Phase 6: Generating fstab..."
+        generate_fstab || error_exit "fstab generation failed"
+    fi
 
-    # Phase 7: Configure system in chroot
-    log_info "Phase 7: Configuring system in chroot..."
-    configure_chroot || error_exit "Chroot configuration failed"
+    if should_run_stage configuring_chroot; then
+        # Phase 7: Configure system in chroot
+        emit_stage configuring_chroot
+        log_info "Phase 7: Configuring system in chroot..."
+        configure_chroot || error_exit "Chroot configuration failed"
+    fi
 
     # Phase 8: Finalize installation
+    emit_stage finalizing
     log_info "Phase 8: Finalizing installation..."
     finalize_installation || error_exit "Installation finalization failed"
+    emit_stage completed
 
     echo "=========================================="
     echo "Installation complete!"
@@ -432,7 +546,7 @@ validate_configuration() {
 
     # Validate partitioning strategy
     case "$PARTITIONING_STRATEGY" in
-        auto_simple|auto_simple_luks|auto_lvm|auto_luks_lvm|auto_raid|auto_raid_luks|auto_raid_lvm|auto_raid_lvm_luks|manual|pre_mounted) ;;
+        auto_simple|auto_simple_luks|auto_lvm|auto_luks_lvm|auto_raid|auto_raid_luks|auto_raid_lvm|auto_raid_lvm_luks|auto_alongside|manual|pre_mounted) ;;
         *) log_error "Unknown partitioning strategy: $PARTITIONING_STRATEGY"; return 1 ;;
     esac
 
@@ -470,12 +584,11 @@ validate_configuration() {
     fi
 
     # systemd-boot and efistub require kernels on FAT32 (ESP at /boot)
-    # Non-RAID auto_* strategies create ESP at /efi + ext4 /boot — incompatible
-    # RAID strategies handle this themselves with a 2-partition layout (ESP at /boot)
+    # The storage plans mount their own ESP at /boot; only auto_alongside reuses
+    # the existing ESP at /efi with an ext4 /boot — incompatible
     if [[ ("$BOOTLOADER" == "systemd-boot" || "$BOOTLOADER" == "efistub") \
-          && "$PARTITIONING_STRATEGY" == auto_* \
-          && "$PARTITIONING_STRATEGY" != *raid* ]]; then
-        log_warn "$BOOTLOADER is incompatible with auto partitioning (ESP at /efi, ext4 /boot)"
+          && "$PARTITIONING_STRATEGY" == "auto_alongside" ]]; then
+        log_warn "$BOOTLOADER is incompatible with auto_alongside (ESP at /efi, ext4 /boot)"
         log_warn "$BOOTLOADER requires kernels on FAT32 — switching to GRUB"
         BOOTLOADER="grub"
         export BOOTLOADER
@@ -498,6 +611,13 @@ prepare_system() {
     log_info "Waiting for time sync..."
     sleep 2
 
+    # Offline installs read from the local repository via OFFLINE_PACMAN_CONF
+    if is_offline_install; then
+        log_offline_skip "mirror ranking and package database update"
+        log_success "System prepared"
+        return 0
+    fi
+
     # Configure mirrors
     configure_mirrors
 
@@ -602,41 +722,29 @@ partition_disk() {
     # Map TUI partitioning options to disk strategy functions
     local strategy_func=""
     case "$PARTITIONING_STRATEGY" in
-        "auto_simple")
-            strategy_func="do_auto_simple_partitioning_efi_xbootldr"
-            log_info "Using auto simple partitioning (EFI + XBOOTLDR)"
-            ;;
-        "auto_simple_luks")
-            strategy_func="do_auto_simple_luks_partitioning"
-            log_info "Using auto simple LUKS encrypted partitioning"
-            ;;
-        "auto_lvm")
-            strategy_func="do_auto_lvm_partitioning_efi_xbootldr"
-            log_info "Using auto LVM partitioning (EFI + XBOOTLDR)"
-            ;;
-        "auto_luks_lvm")
-            strategy_func="do_auto_luks_lvm_partitioning"
-            log_info "Using auto LUKS + LVM partitioning"
-            ;;
-        "auto_raid")
-            strategy_func="do_auto_raid_partitioning"
-            log_info "Using auto RAID partitioning"
-            ;;
-        "auto_raid_luks")
-            strategy_func="do_auto_raid_luks_partitioning"
-            log_info "Using auto RAID + LUKS partitioning"
-            ;;
-        "auto_raid_lvm")
-            strategy_func="do_auto_raid_lvm_partitioning"
-            log_info "Using auto RAID + LVM partitioning"
-            ;;
-        "auto_raid_lvm_luks")
-            strategy_func="do_auto_raid_lvm_luks_partitioning"
-            log_info "Using auto RAID + LVM + LUKS partitioning"
+        auto_*)
+            # The layout is planned by engine::storage; this is the same plan
+            # the dry-run report shows, executed op by op by archtui
+            if [[ -z "${ARCHTUI_STORAGE_PLAN:-}" ]]; then
+                log_error "Strategy $PARTITIONING_STRATEGY requires the storage plan from archtui (ARCHTUI_STORAGE_PLAN)"
+                return 1
+            fi
+            strategy_func="do_storage_plan_partitioning"
+            log_info "Using the archtui storage plan ($PARTITIONING_STRATEGY)"
             ;;
         "manual")
-            strategy_func="do_manual_partitioning_guided"
-            log_info "Using manual partitioning (guided)"
+            if [[ -n "${PARTITION_LAYOUT:-}" ]]; then
+                # The declared layout is planned by engine::storage too
+                if [[ -z "${ARCHTUI_STORAGE_PLAN:-}" ]]; then
+                    log_error "A declared partition_layout requires the storage plan from archtui (ARCHTUI_STORAGE_PLAN)"
+                    return 1
+                fi
+                strategy_func="do_storage_plan_partitioning"
+                log_info "Using manual partitioning (declared partition_layout, archtui storage plan)"
+            else
+                strategy_func="do_manual_partitioning_guided"
+                log_info "Using manual partitioning (guided)"
+            fi
             ;;
         "pre_mounted")
             strategy_func="do_pre_mounted_partitioning"
@@ -652,14 +760,19 @@ partition_disk() {
     log_info "Executing disk strategy: $strategy_func"
     execute_disk_strategy "$strategy_func"
 
+    # Swapfile and hibernation resume parameters need the target fully mounted
+    setup_swap_target || error_exit "Swap setup failed"
+
     log_success "Disk partitioning complete"
     return 0
 }
 
 # --- Base System Installation ---
-install_base_system() {
-    log_info "Installing base system with pacstrap..."
-
+# Print the pacstrap package list, one per line, for runs without archtui.
+# archtui passes its resolver's list as PACSTRAP_PACKAGES instead
+# (resolve_pacstrap_packages); test_bash_pacstrap_list_matches_resolver
+# keeps the two in step. Logs go to stderr.
+build_pacstrap_package_list() {
     # Build package list as array
     # Wiki: https://wiki.archlinux.org/title/Installation_guide#Install_essential_packages
     local -a base_packages=(
@@ -711,7 +824,7 @@ install_base_system() {
         "nano") essential_packages+=("nano") ;;
         "vim") essential_packages+=("vim") ;;
         "neovim") essential_packages+=("neovim") ;;
-        "none") log_info "No default editor selected — user can install one post-install" ;;
+        "none") log_info "No default editor selected — user can install one post-install" >&2 ;;
         *)
             log_warn "Unknown EDITOR='${EDITOR}', falling back to nano"
             essential_packages+=("nano")
@@ -833,22 +946,39 @@ install_base_system() {
         esac
     fi
 
+    # zram-generator for compressed swap in RAM (configured in chroot)
+    local -a swap_packages=()
+    if [[ "$SWAP_MODE" == zram* ]]; then
+        swap_packages+=("zram-generator")
+    fi
+
     # Add sbctl for Secure Boot (install via pacstrap so it's available in chroot)
     local -a secureboot_packages=()
     if [[ "${SECURE_BOOT:-No}" == "Yes" ]]; then
         secureboot_packages+=("sbctl")
     fi
 
-    # Combine all packages
-    local -a all_packages=(
-        "${base_packages[@]}"
-        "${essential_packages[@]}"
-        "${fs_packages[@]}"
-        "${bootloader_packages[@]}"
-        "${microcode_packages[@]}"
-        "${snapshot_packages[@]}"
+    printf '%s\n' \
+        "${base_packages[@]}" \
+        "${essential_packages[@]}" \
+        "${fs_packages[@]}" \
+        "${bootloader_packages[@]}" \
+        "${microcode_packages[@]}" \
+        "${snapshot_packages[@]}" \
+        "${swap_packages[@]}" \
         "${secureboot_packages[@]}"
-    )
+}
+
+install_base_system() {
+    log_info "Installing base system with pacstrap..."
+
+    # The resolver's list is what the package pre-flight and offline checks verified
+    local -a all_packages=()
+    if [[ -n "${PACSTRAP_PACKAGES:-}" ]]; then
+        read -ra all_packages <<< "$PACSTRAP_PACKAGES"
+    else
+        mapfile -t all_packages < <(build_pacstrap_package_list | sed '/^$/d')
+    fi
 
     log_info "Total packages to install: ${#all_packages[@]}"
     log_info "Package list: ${all_packages[*]}"
@@ -875,8 +1005,15 @@ install_base_system() {
     mkdir -p /mnt/etc
     echo "KEYMAP=${KEYMAP:-us}" > /mnt/etc/vconsole.conf
 
+    # Offline: -C reads the local repository, -c installs straight from its package directory
+    local -a pacstrap_args=(-K)
+    if is_offline_install; then
+        log_info "Installing from offline repository: $OFFLINE_PKG_DIR"
+        pacstrap_args+=(-c -C "$OFFLINE_PACMAN_CONF")
+    fi
+
     # Run pacstrap with array expansion and show output
-    pacstrap -K /mnt "${all_packages[@]}" --noconfirm --needed 2>&1 | while IFS= read -r line; do
+    pacstrap "${pacstrap_args[@]}" /mnt "${all_packages[@]}" --noconfirm --needed 2>&1 | while IFS= read -r line; do
         # Suppress harmless systemd chroot messages from post-install hooks
         # systemd prints these when pacman hooks try daemon-reload inside pacstrap's chroot
         [[ "$line" == *"Skipped: Running in chroot"* ]] && continue
@@ -946,7 +1083,9 @@ configure_chroot() {
     cp "$SCRIPT_DIR/utils.sh" /mnt/ || error_exit "Failed to copy utils.sh to /mnt/"
 
     # Download and install selected Plymouth theme to target system BEFORE chroot
-    if [[ "${PLYMOUTH:-No}" == "Yes" ]]; then
+    if [[ "${PLYMOUTH:-No}" == "Yes" ]] && is_offline_install; then
+        log_offline_skip "Plymouth theme download"
+    elif [[ "${PLYMOUTH:-No}" == "Yes" ]]; then
         local _pt="${PLYMOUTH_THEME:-bgrt}"
         case "$_pt" in
             arch-glow|arch-mac-style)
@@ -965,7 +1104,9 @@ configure_chroot() {
     fi
 
     # Download and install HyperFluent GRUB theme only when selected
-    if [[ "${GRUB_THEME_SELECTION:-}" =~ ^(HyperFluent|hyperfluent)$ ]]; then
+    if [[ "${GRUB_THEME_SELECTION:-}" =~ ^(HyperFluent|hyperfluent)$ ]] && is_offline_install; then
+        log_offline_skip "HyperFluent GRUB theme download"
+    elif [[ "${GRUB_THEME_SELECTION:-}" =~ ^(HyperFluent|hyperfluent)$ ]]; then
         log_info "Downloading HyperFluent GRUB theme..."
         log_cmd "curl -fsSL https://github.com/live4thamuzik/ArchTUI/releases/latest/download/HyperFluent-arch.tar.gz"
         if curl -fsSL -o "/tmp/HyperFluent-arch.tar.gz" \
@@ -1013,6 +1154,7 @@ configure_chroot() {
         printf 'export DESKTOP_ENVIRONMENT=%q\n' "$DESKTOP_ENVIRONMENT"
         printf 'export DISPLAY_MANAGER=%q\n' "$DISPLAY_MANAGER"
         printf 'export GPU_DRIVERS=%q\n' "$GPU_DRIVERS"
+        printf 'export GPU_PACKAGES=%q\n' "$GPU_PACKAGES"
         printf 'export AUR_HELPER=%q\n' "$AUR_HELPER"
         printf 'export ADDITIONAL_PACKAGES=%q\n' "$ADDITIONAL_PACKAGES"
         printf 'export ADDITIONAL_AUR_PACKAGES=%q\n' "$ADDITIONAL_AUR_PACKAGES"
@@ -1045,6 +1187,10 @@ configure_chroot() {
         printf 'export SWAP=%q\n' "$SWAP"
         printf 'export WANT_SWAP=%q\n' "$WANT_SWAP"
         printf 'export SWAP_UUID=%q\n' "${SWAP_UUID:-}"
+        printf 'export SWAP_MODE=%q\n' "$SWAP_MODE"
+        printf 'export HIBERNATION=%q\n' "$HIBERNATION"
+        printf 'export RESUME_UUID=%q\n' "${RESUME_UUID:-}"
+        printf 'export RESUME_OFFSET=%q\n' "${RESUME_OFFSET:-}"
         printf 'export ROOT_UUID=%q\n' "${ROOT_UUID:-}"
         printf 'export LUKS_UUID=%q\n' "${LUKS_UUID:-}"
         printf 'export ROOT_FILESYSTEM_TYPE=%q\n' "$ROOT_FILESYSTEM_TYPE"
@@ -1056,10 +1202,16 @@ configure_chroot() {
         printf 'export OTHER_LINUX_NAME=%q\n' "${OTHER_LINUX_NAME:-}"
         printf 'export OTHER_LINUX_SAME_DISK=%q\n' "${OTHER_LINUX_SAME_DISK:-}"
         printf 'export LOG_LEVEL=%q\n' "${LOG_LEVEL:-INFO}"
+        printf 'export OFFLINE_INSTALL=%q\n' "${OFFLINE_INSTALL:-No}"
     } > /mnt/install_config.sh
 
     chmod +x /mnt/install_config.sh
 
+    # Offline: pacman inside the chroot reads the bind-mounted local repository
+    if is_offline_install; then
+        attach_offline_repo || error_exit "Failed to attach offline repository to /mnt"
+    fi
+
     # Execute chroot configuration
     log_info "Entering chroot environment..."
     log_info "Running chroot_config.sh inside /mnt..."
@@ -1112,6 +1264,10 @@ configure_chroot() {
     local chroot_exit=${PIPESTATUS[0]}
     [[ "${LOG_LEVEL:-INFO}" == "VERBOSE" ]] && set -x
 
+    if is_offline_install; then
+        detach_offline_repo
+    fi
+
     # Clear password variables after chroot completes (no longer needed)
     { set +x; } 2>/dev/null
     unset MAIN_USER_PASSWORD ROOT_PASSWORD ENCRYPTION_PASSWORD
@@ -1131,6 +1287,48 @@ configure_chroot() {
     return 0
 }
 
+# --- Offline Repository ---
+# Bind-mount the local repository into the target at the paths the chroot
+# pacman.conf expects, and swap that pacman.conf in while chroot_config.sh runs
+attach_offline_repo() {
+    log_info "Attaching offline repository to the target..."
+    mkdir -p "/mnt$OFFLINE_CHROOT_REPO_DIR" "/mnt$OFFLINE_CHROOT_PKG_DIR" || return 1
+
+    log_cmd "mount --bind $OFFLINE_REPO_DIR /mnt$OFFLINE_CHROOT_REPO_DIR"
+    mount --bind "$OFFLINE_REPO_DIR" "/mnt$OFFLINE_CHROOT_REPO_DIR" || return 1
+    log_cmd "mount --bind $OFFLINE_PKG_DIR /mnt$OFFLINE_CHROOT_PKG_DIR"
+    mount --bind "$OFFLINE_PKG_DIR" "/mnt$OFFLINE_CHROOT_PKG_DIR" || return 1
+
+    # Keep an earlier backup: a resumed install finds the offline conf in place
+    if [[ ! -f /mnt/etc/pacman.conf.archtui ]]; then
+        cp /mnt/etc/pacman.conf /mnt/etc/pacman.conf.archtui || return 1
+    fi
+    cp "$OFFLINE_CHROOT_PACMAN_CONF" /mnt/etc/pacman.conf || return 1
+    log_success "Offline repository attached"
+}
+
+# Restore the target's own pacman.conf and remove the bind mounts
+detach_offline_repo() {
+    log_info "Detaching offline repository from the target..."
+    if [[ -f /mnt/etc/pacman.conf.archtui ]]; then
+        mv /mnt/etc/pacman.conf.archtui /mnt/etc/pacman.conf || log_warn "Failed to restore /mnt/etc/pacman.conf"
+    fi
+
+    # The offline pacman.conf has no multilib section; enable it in the real one
+    if [[ "${MULTILIB:-No}" == "Yes" ]]; then
+        sed -i '/^#\[multilib\]/,/^#Include/s/^#//' /mnt/etc/pacman.conf || log_warn "Failed to enable multilib in pacman.conf"
+    fi
+
+    local dir
+    for dir in "/mnt$OFFLINE_CHROOT_PKG_DIR" "/mnt$OFFLINE_CHROOT_REPO_DIR"; do
+        if mountpoint -q "$dir"; then
+            umount "$dir" || log_warn "Failed to unmount $dir"
+        fi
+    done
+    rmdir "/mnt$OFFLINE_CHROOT_PKG_DIR" "/mnt$OFFLINE_CHROOT_REPO_DIR" \
+        "$(dirname "/mnt$OFFLINE_CHROOT_REPO_DIR")" 2>/dev/null || true
+}
+
 # --- Finalization ---
 finalize_installation() {
     log_info "Finalizing installation..."
@@ -1,248 +0,0 @@
-#!/bin/bash
-# simple_luks.sh - Simple LUKS partitioning strategy (ESP + boot + encrypted root/home)
-set -euo pipefail
-
-# Source common utilities via source_or_die
-_STRATEGY_SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
-# shellcheck source=../disk_utils.sh
-source_or_die "$_STRATEGY_SCRIPT_DIR/../disk_utils.sh"
-
-# Execute simple LUKS partitioning strategy
-execute_simple_luks_partitioning() {
-    echo "=== PHASE 1: Simple LUKS Partitioning ==="
-    log_info "Starting simple LUKS partitioning for $INSTALL_DISK..."
-
-    # Setup cleanup trap for error recovery
-    setup_partitioning_trap
-
-    # Validate requirements
-    validate_partitioning_requirements
-
-    # --- Dual-boot detection ---
-    local use_existing_esp="no"
-    local existing_esp=""
-
-    if detect_other_os; then
-        log_warn "Other OS detected - enabling os-prober for dual-boot"
-        export OS_PROBER="yes"
-    fi
-
-    existing_esp=$(detect_existing_esp "$INSTALL_DISK")
-
-    # detect_other_os already called detect_windows_installation internally
-    if [[ "${WINDOWS_DETECTED:-}" == "yes" ]]; then
-        log_warn "Windows installation detected on ${WINDOWS_ESP_DEVICE:-unknown}"
-        if [[ -n "$existing_esp" && "${WINDOWS_ESP_DEVICE:-}" == "$existing_esp" ]]; then
-            log_warn "Will preserve existing ESP for dual-boot chainloading"
-            use_existing_esp="yes"
-        fi
-        export DUAL_BOOT_WINDOWS="yes"
-    fi
-
-    if [[ "$use_existing_esp" == "yes" && "$existing_esp" == "${INSTALL_DISK}"* ]]; then
-        log_error "Same-disk dual-boot detected: Windows ESP is on target disk!"
-        log_error "Automatic partitioning would destroy Windows. Use 'manual' strategy instead."
-        log_error "See: https://wiki.archlinux.org/title/Dual_boot_with_Windows"
-        return 1
-    fi
-
-    wipe_disk "$INSTALL_DISK" "CONFIRMED"
-
-    local current_start_mib=1
-    local part_num=1
-    local esp_part_num=0
-    local boot_part_num=0
-    local luks_part_num=0
-
-    # Create partition table
-    create_partition_table "$INSTALL_DISK"
-
-    # ESP Partition (for UEFI only) - 512MB FAT32 at /efi
-    if [ "$BOOT_MODE" = "UEFI" ]; then
-        esp_part_num=$part_num
-        create_esp_partition "$INSTALL_DISK" "$part_num" "512"
-        current_start_mib=$((current_start_mib + 512))
-        part_num=$((part_num + 1))
-
-        # Boot partition - 1GB ext4 at /boot (unencrypted for bootloader)
-        boot_part_num=$part_num
-        create_boot_partition "$INSTALL_DISK" "$part_num" "1024"
-        current_start_mib=$((current_start_mib + 1024))
-        part_num=$((part_num + 1))
-    else
-        # BIOS with GPT: Need BIOS boot partition for GRUB
-        create_bios_boot_partition "$INSTALL_DISK" "$part_num"
-        current_start_mib=$((current_start_mib + BIOS_BOOT_PART_SIZE_MIB))
-        part_num=$((part_num + 1))
-
-        # Boot partition - 1GB ext4 at /boot
-        boot_part_num=$part_num
-        create_boot_partition "$INSTALL_DISK" "$part_num" "1024"
-        current_start_mib=$((current_start_mib + 1024))
-        part_num=$((part_num + 1))
-    fi
-    
-    # Swap partition (if requested)
-    if [ "$WANT_SWAP" = "yes" ]; then
-        local swap_size_mib=$(get_swap_size_mib)
-        create_swap_partition "$INSTALL_DISK" "$part_num" "$swap_size_mib"
-        current_start_mib=$((current_start_mib + swap_size_mib))
-        part_num=$((part_num + 1))
-    fi
-    
-    # LUKS partition (for root and optionally home)
-    luks_part_num=$part_num
-    log_info "Creating LUKS partition..."
-
-    if [ "$WANT_HOME_PARTITION" = "yes" ]; then
-        # Root gets fixed size when home is separate
-        local root_size_mib
-        root_size_mib=$(get_root_size_mib)
-
-        if [[ "$root_size_mib" == "REMAINING" ]]; then
-            log_warn "Root=Remaining with separate home: falling back to ${DEFAULT_ROOT_SIZE_MIB}MiB root"
-            root_size_mib="$DEFAULT_ROOT_SIZE_MIB"
-        fi
-
-        log_cmd "sgdisk -n ${part_num}:0:+${root_size_mib}M -t ${part_num}:${LUKS_PARTITION_TYPE} -c ${part_num}:LUKS $INSTALL_DISK"
-        sgdisk -n "${part_num}:0:+${root_size_mib}M" \
-               -t "${part_num}:${LUKS_PARTITION_TYPE}" \
-               -c "${part_num}:LUKS" \
-               "$INSTALL_DISK" || error_exit "Failed to create LUKS partition"
-    else
-        # No home: root LUKS takes all remaining space
-        log_cmd "sgdisk -n ${part_num}:0:0 -t ${part_num}:${LUKS_PARTITION_TYPE} -c ${part_num}:LUKS $INSTALL_DISK"
-        sgdisk -n "${part_num}:0:0" \
-               -t "${part_num}:${LUKS_PARTITION_TYPE}" \
-               -c "${part_num}:LUKS" \
-               "$INSTALL_DISK" || error_exit "Failed to create LUKS partition"
-    fi
-
-    sync_partitions "$INSTALL_DISK"
-
-    local luks_dev
-    luks_dev=$(get_partition_path "$INSTALL_DISK" "$luks_part_num")
-
-    # Verify partition exists before proceeding
-    if [[ ! -b "$luks_dev" ]]; then
-        error_exit "LUKS partition $luks_dev not found after creation"
-    fi
-
-    # Set up LUKS encryption using helper function (non-interactive)
-    local encrypted_dev
-    encrypted_dev=$(setup_luks_encryption "$luks_dev" "cryptroot")
-
-    # FIDO2 enrollment (if configured)
-    if [[ "${ENCRYPTION_KEY_TYPE:-Password}" == *"FIDO2"* ]]; then
-        enroll_fido2 "$luks_dev" || log_warn "FIDO2 enrollment failed — password-only fallback"
-    fi
-
-    # Format root filesystem
-    log_info "Creating $ROOT_FILESYSTEM_TYPE filesystem on $encrypted_dev..."
-    format_filesystem "$encrypted_dev" "$ROOT_FILESYSTEM_TYPE"
-    capture_device_info "root" "$encrypted_dev"
-
-    # Handle Btrfs subvolumes if needed, otherwise simple mount
-    if [ "$ROOT_FILESYSTEM_TYPE" = "btrfs" ]; then
-        local include_home="no"
-        if [ "$WANT_HOME_PARTITION" != "yes" ]; then
-            include_home="yes"
-        fi
-        setup_btrfs_subvolumes "$encrypted_dev" "$include_home"
-    else
-        safe_mount "$encrypted_dev" "/mnt"
-    fi
-
-    # Separate home partition (if requested)
-    if [ "$WANT_HOME_PARTITION" = "yes" ]; then
-        part_num=$((part_num + 1))
-        log_info "Creating separate LUKS home partition..."
-
-        local home_size_mib
-        home_size_mib=$(get_home_size_mib)
-
-        if [[ "$home_size_mib" == "REMAINING" ]]; then
-            # Home takes all remaining space
-            log_cmd "sgdisk -n ${part_num}:0:0 -t ${part_num}:${LUKS_PARTITION_TYPE} -c ${part_num}:LUKS_HOME $INSTALL_DISK"
-            sgdisk -n "${part_num}:0:0" \
-                   -t "${part_num}:${LUKS_PARTITION_TYPE}" \
-                   -c "${part_num}:LUKS_HOME" \
-                   "$INSTALL_DISK" || error_exit "Failed to create LUKS home partition"
-        else
-            # Fixed size home
-            log_cmd "sgdisk -n ${part_num}:0:+${home_size_mib}M -t ${part_num}:${LUKS_PARTITION_TYPE} -c ${part_num}:LUKS_HOME $INSTALL_DISK"
-            sgdisk -n "${part_num}:0:+${home_size_mib}M" \
-                   -t "${part_num}:${LUKS_PARTITION_TYPE}" \
-                   -c "${part_num}:LUKS_HOME" \
-                   "$INSTALL_DISK" || error_exit "Failed to create LUKS home partition"
-        fi
-
-        sync_partitions "$INSTALL_DISK"
-
-        local luks_home_dev
-        luks_home_dev=$(get_partition_path "$INSTALL_DISK" "$part_num")
-
-        # Verify partition exists before proceeding
-        if [[ ! -b "$luks_home_dev" ]]; then
-            error_exit "LUKS home partition $luks_home_dev not found after creation"
-        fi
-
-        # Set up LUKS encryption for home using helper function (non-interactive)
-        local encrypted_home_dev
-        encrypted_home_dev=$(setup_luks_encryption "$luks_home_dev" "crypthome")
-
-        # Capture LUKS home UUID separately
-        local luks_home_uuid
-        luks_home_uuid=$(blkid -s UUID -o value "$luks_home_dev" 2>/dev/null) || true
-
-        # Format home filesystem
-        format_filesystem "$encrypted_home_dev" "$HOME_FILESYSTEM_TYPE"
-        capture_device_info "home" "$encrypted_home_dev"
-        mkdir -p /mnt/home
-        safe_mount "/dev/mapper/crypthome" "/mnt/home"
-    fi
-    
-    # Mount boot and ESP partitions
-    log_info "Mounting boot and ESP partitions..."
-    mkdir -p /mnt/boot /mnt/efi
-
-    local boot_device
-    boot_device=$(get_partition_path "$INSTALL_DISK" "$boot_part_num")
-    safe_mount "$boot_device" "/mnt/boot"
-    capture_device_info "boot" "$boot_device"
-
-    if [ "$BOOT_MODE" = "UEFI" ]; then
-        local esp_device
-        esp_device=$(get_partition_path "$INSTALL_DISK" "$esp_part_num")
-        safe_mount "$esp_device" "/mnt/efi"
-        capture_device_info "efi" "$esp_device"
-        export EFI_DEVICE="$esp_device"
-    fi
-
-    # Capture UUIDs for bootloader config
-    capture_device_info "luks" "$luks_dev"
-    ROOT_UUID=$(get_device_uuid "/dev/mapper/cryptroot") || error_exit "Cannot determine ROOT_UUID"
-    LUKS_UUID=$(get_device_uuid "$luks_dev") || error_exit "Cannot determine LUKS_UUID"
-    export ROOT_UUID LUKS_UUID
-
-    # Capture swap UUID if swap exists
-    if [ "$WANT_SWAP" = "yes" ]; then
-        # Find swap partition (it's before the LUKS partition)
-        local swap_part_num=$((luks_part_num - 1))
-        local swap_device
-        swap_device=$(get_partition_path "$INSTALL_DISK" "$swap_part_num")
-        capture_device_info "swap" "$swap_device"
-        SWAP_UUID=$(get_device_uuid "$swap_device") || log_warn "Cannot determine SWAP_UUID"
-        export SWAP_UUID
-    fi
-
-    # Generate crypttab entries for boot-time unlocking
-    log_info "Generating crypttab entries..."
-    mkdir -p /mnt/etc
-    generate_crypttab "$luks_dev" "cryptroot"
-    if [ "$WANT_HOME_PARTITION" = "yes" ]; then
-        generate_crypttab "$luks_home_dev" "crypthome"
-    fi
-
-    log_partitioning_complete "Simple LUKS (ESP + boot + encrypted root)"
-}
@@ -0,0 +1,108 @@
+#!/bin/bash
+# storage_plan.sh - Partition by executing the storage plan archtui computed
+# The layout is the one engine::storage planned for the config (the same plan
+# the dry-run report and the sandbox show). `archtui apply-storage` runs it op
+# by op and writes the resulting device roles for this script to source.
+set -euo pipefail
+
+# Source common utilities via source_or_die
+_STRATEGY_SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../disk_utils.sh
+source_or_die "$_STRATEGY_SCRIPT_DIR/../disk_utils.sh"
+
+# Execute the storage plan handed over in ARCHTUI_STORAGE_PLAN
+execute_storage_plan_partitioning() {
+    echo "=== PHASE 1: Storage Plan ($PARTITIONING_STRATEGY) ==="
+    log_info "Executing storage plan for $INSTALL_DISK..."
+
+    if [[ -z "${ARCHTUI_STORAGE_PLAN:-}" || ! -f "$ARCHTUI_STORAGE_PLAN" ]]; then
+        error_exit "No storage plan found (ARCHTUI_STORAGE_PLAN) — start the install through archtui"
+    fi
+    if [[ -z "${ARCHTUI_BIN:-}" || ! -x "$ARCHTUI_BIN" ]]; then
+        error_exit "archtui binary not found (ARCHTUI_BIN) — needed to apply the storage plan"
+    fi
+
+    # No partitioning trap: on failure archtui tears down exactly the ops
+    # apply-storage recorded as applied
+
+    # --- Dual-boot detection ---
+    if detect_other_os; then
+        log_warn "Other OS detected - enabling os-prober for dual-boot"
+        export OS_PROBER="yes"
+    fi
+    if [[ "${WINDOWS_DETECTED:-}" == "yes" ]]; then
+        log_warn "Windows installation detected on ${WINDOWS_ESP_DEVICE:-unknown}"
+        export DUAL_BOOT_WINDOWS="yes"
+    fi
+
+    # Same-disk dual-boot check: every strategy but alongside wipes its disks,
+    # which would destroy a Windows install on them
+    if [[ "$PARTITIONING_STRATEGY" != "auto_alongside" && -n "${WINDOWS_ESP_DEVICE:-}" ]]; then
+        local disk
+        local -a disks
+        IFS=',' read -ra disks <<< "$INSTALL_DISK"
+        for disk in "${disks[@]}"; do
+            if [[ "$WINDOWS_ESP_DEVICE" == "${disk}"* ]]; then
+                log_error "Same-disk dual-boot detected: Windows ESP is on target disk $disk!"
+                log_error ""
+                log_error "Automatic partitioning would destroy Windows. Options:"
+                log_error "  1. Select a different disk for Arch Linux installation"
+                log_error "  2. Use the 'auto_alongside' strategy to install in free space"
+                log_error "     (shrink the Windows partition first if there is none)"
+                log_error ""
+                log_error "See: https://wiki.archlinux.org/title/Dual_boot_with_Windows"
+                return 1
+            fi
+        done
+    fi
+
+    # --- Execute the plan ---
+    local state_file="${ARCHTUI_STORAGE_PLAN%.json}-devices.sh"
+    log_cmd "$ARCHTUI_BIN apply-storage --plan $ARCHTUI_STORAGE_PLAN --state $state_file"
+    "$ARCHTUI_BIN" apply-storage --plan "$ARCHTUI_STORAGE_PLAN" --state "$state_file" \
+        || error_exit "Storage plan failed — see the op that failed above"
+
+    # shellcheck source=/dev/null
+    source "$state_file"
+
+    # Resolve by-partlabel links so fstab/bootloader see real device paths
+    local var
+    for var in ROOT_DEVICE BOOT_DEVICE EFI_DEVICE HOME_DEVICE SWAP_DEVICE LUKS_DEVICE; do
+        if [[ -n "${!var}" ]]; then
+            export "$var=$(readlink -f "${!var}")"
+        fi
+    done
+
+    # --- Swap partition/LV (swapfiles are activated by the plan) ---
+    if [[ -n "$SWAP_DEVICE" ]]; then
+        log_cmd "swapon $SWAP_DEVICE"
+        swapon "$SWAP_DEVICE" || log_warn "Failed to activate swap on $SWAP_DEVICE"
+        SWAP_UUID=$(get_device_uuid "$SWAP_DEVICE") || log_warn "Cannot determine SWAP_UUID"
+        export SWAP_UUID
+    fi
+
+    # --- LUKS: FIDO2 enrollment and crypttab for every container ---
+    if [[ -n "$LUKS_MAPPERS" ]]; then
+        local entry luks_dev mapper_name
+        local -a entries
+        IFS=';' read -ra entries <<< "$LUKS_MAPPERS"
+        mkdir -p /mnt/etc
+        for entry in "${entries[@]}"; do
+            IFS='|' read -r luks_dev mapper_name <<< "$entry"
+            if [[ "${ENCRYPTION_KEY_TYPE:-Password}" == *"FIDO2"* ]]; then
+                enroll_fido2 "$luks_dev" || log_warn "FIDO2 enrollment failed — password-only fallback"
+            fi
+            generate_crypttab "$luks_dev" "$mapper_name"
+        done
+    fi
+    if [[ -n "$LUKS_DEVICE" ]]; then
+        LUKS_UUID=$(get_device_uuid "$LUKS_DEVICE") || error_exit "Cannot determine LUKS_UUID"
+        export LUKS_UUID
+    fi
+
+    # Capture UUIDs for bootloader config
+    ROOT_UUID=$(get_device_uuid "$ROOT_DEVICE") || error_exit "Cannot determine ROOT_UUID"
+    export ROOT_UUID
+
+    log_partitioning_complete "Storage plan ($PARTITIONING_STRATEGY)"
+}
@@ -181,6 +181,23 @@ teardown() {
     grep -q 'hooks=.*encrypt' "$SCRIPTS_DIR/chroot_config.sh"
 }
 
+# =============================================================================
+# GPU Driver Tests
+# =============================================================================
+
+@test "install_gpu_drivers installs the resolved GPU_PACKAGES" {
+    grep -q 'read -ra gpu_pkgs <<< "$GPU_PACKAGES"' "$SCRIPTS_DIR/chroot_config.sh"
+}
+
+@test "_gpu_uses_nvidia_driver follows GPU_PACKAGES for Auto" {
+    eval "$(sed -n '/^_gpu_uses_nvidia_driver() {/,/^}/p' "$SCRIPTS_DIR/chroot_config.sh")"
+    GPU_DRIVERS="Auto" GPU_PACKAGES="mesa nvidia-open-dkms nvidia-utils" _gpu_uses_nvidia_driver
+    GPU_DRIVERS="Auto" GPU_PACKAGES="mesa xf86-video-nouveau" run _gpu_uses_nvidia_driver
+    [ "$status" -ne 0 ]
+    GPU_DRIVERS="amd" GPU_PACKAGES="" run _gpu_uses_nvidia_driver
+    [ "$status" -ne 0 ]
+}
+
 # =============================================================================
 # Desktop Environment Tests
 # =============================================================================
@@ -348,6 +348,32 @@ teardown() {
     fi
 }
 
+@test "normalize_swap_mode accepts serde, display and legacy spellings" {
+    source "$SCRIPTS_DIR/config_loader.sh" 2>/dev/null || true
+
+    [ "$(normalize_swap_mode "ZramSwapfile")" = "zram+swapfile" ]
+    [ "$(normalize_swap_mode "zram+swapfile")" = "zram+swapfile" ]
+    [ "$(normalize_swap_mode "Swapfile")" = "swapfile" ]
+    [ "$(normalize_swap_mode "Yes")" = "partition" ]
+    [ "$(normalize_swap_mode "No")" = "none" ]
+    [ "$(normalize_swap_mode "")" = "none" ]
+}
+
+@test "config maps serde swap name ZramSwapfile to zram+swapfile" {
+    if command -v jq >/dev/null 2>&1; then
+        set +euo pipefail
+        source "$SCRIPTS_DIR/config_loader.sh" 2>/dev/null || true
+        set -euo pipefail
+
+        jq '.swap = "ZramSwapfile"' "$TEST_CONFIG" > "$TEST_TMP_DIR/zram.json"
+        load_config_from_json "$TEST_TMP_DIR/zram.json" 2>/dev/null || true
+        [ "$SWAP_MODE" = "zram+swapfile" ]
+        [ "$WANT_SWAP" = "no" ]
+    else
+        skip "jq not installed"
+    fi
+}
+
 @test "config sets WANT_HOME_PARTITION from SEPARATE_HOME" {
     if command -v jq >/dev/null 2>&1; then
         set +euo pipefail
@@ -97,12 +97,43 @@ teardown() {
     [ "$output" = "16384" ]  # Capped at 16GB
 }
 
+@test "get_swap_size_mib never sizes below RAM when hibernating" {
+    HIBERNATION=Yes run get_swap_size_mib "32"
+    [ "$status" -eq 0 ]
+    [ "$output" = "32768" ]  # Whole of RAM, above the 16GB cap
+}
+
 @test "get_swap_size_mib returns default for unknown format" {
     run get_swap_size_mib "unknown"
     [ "$status" -eq 0 ]
     [ "$output" = "2048" ]  # DEFAULT_SWAP_SIZE_MIB
 }
 
+# =============================================================================
+# Root/Home Size Tests
+# =============================================================================
+
+@test "get_root_size_mib accepts binary and decimal units" {
+    ROOT_SIZE=50GiB run get_root_size_mib
+    [ "$output" = "51200" ]
+    ROOT_SIZE=512MB run get_root_size_mib
+    [ "$output" = "512" ]
+    ROOT_SIZE=1TB run get_root_size_mib
+    [ "$output" = "1048576" ]
+}
+
+@test "get_home_size_mib resolves percentages against INSTALL_DISK" {
+    blockdev() { echo 107374182400; }  # 100 GiB
+    export -f blockdev
+    INSTALL_DISK=/dev/sda HOME_SIZE=25% run get_home_size_mib
+    [ "$output" = "25600" ]
+}
+
+@test "get_root_size_mib falls back to default for unparseable sizes" {
+    ROOT_SIZE=50.5GB run get_root_size_mib
+    [ "$output" = "51200" ]  # DEFAULT_ROOT_SIZE_MIB
+}
+
 # =============================================================================
 # Partition Type Constants Tests
 # =============================================================================
@@ -299,43 +330,42 @@ teardown() {
 }
 
 # =============================================================================
-# Btrfs Subvolume Tests (source pattern)
+# Btrfs Subvolume Tests
 # Function signature: setup_btrfs_subvolumes(device, include_home)
-# Uses hardcoded /mnt mountpoint — cannot be functionally tested without root
+# Mounting uses hardcoded /mnt — the layout itself is tested through
+# btrfs_subvolume_entries, which reads BTRFS_SUBVOLUMES
 # =============================================================================
 
-@test "setup_btrfs_subvolumes creates @ subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@' "$SCRIPTS_DIR/disk_utils.sh"
-}
-
-@test "setup_btrfs_subvolumes creates @var subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@var' "$SCRIPTS_DIR/disk_utils.sh"
-}
-
-@test "setup_btrfs_subvolumes creates @tmp subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@tmp' "$SCRIPTS_DIR/disk_utils.sh"
-}
-
-@test "setup_btrfs_subvolumes creates @snapshots subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@snapshots' "$SCRIPTS_DIR/disk_utils.sh"
-}
-
-@test "setup_btrfs_subvolumes creates @cache subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@cache' "$SCRIPTS_DIR/disk_utils.sh"
+@test "btrfs_subvolume_entries defaults to the standard layout" {
+    unset BTRFS_SUBVOLUMES
+    run btrfs_subvolume_entries "yes"
+    [ "$status" -eq 0 ]
+    [[ "${lines[0]}" == "0|@|/|compress=zstd,noatime" ]]
+    [ "${#lines[@]}" -eq 5 ]
+    for subvol in @home @log @pkg @snapshots; do
+        [[ "$output" == *"|$subvol|"* ]]
+    done
 }
 
-@test "setup_btrfs_subvolumes creates @log subvolume" {
-    grep -q 'btrfs subvolume create /mnt/@log' "$SCRIPTS_DIR/disk_utils.sh"
+@test "btrfs_subvolume_entries reads BTRFS_SUBVOLUMES shallowest-first" {
+    export BTRFS_SUBVOLUMES="@pkg|/var/cache/pacman/pkg|;@|/|compress=zstd,noatime;@log|/var/log|noatime"
+    run btrfs_subvolume_entries "yes"
+    [ "$status" -eq 0 ]
+    [ "${lines[0]}" = "0|@|/|compress=zstd,noatime" ]
+    [ "${lines[1]}" = "2|@log|/var/log|noatime" ]
+    # Missing options fall back to the engine's default
+    [ "${lines[2]}" = "4|@pkg|/var/cache/pacman/pkg|compress=zstd,noatime" ]
 }
 
-@test "setup_btrfs_subvolumes creates @home conditionally" {
-    # @home is only created when include_home is "yes"
-    grep -A30 'setup_btrfs_subvolumes()' "$SCRIPTS_DIR/disk_utils.sh" | grep -q 'include_home.*yes'
-    grep -q 'btrfs subvolume create /mnt/@home' "$SCRIPTS_DIR/disk_utils.sh"
+@test "btrfs_subvolume_entries drops /home without include_home" {
+    export BTRFS_SUBVOLUMES="@|/|compress=zstd;@home|/home|compress=zstd"
+    run btrfs_subvolume_entries "no"
+    [ "$status" -eq 0 ]
+    [ "$output" = "0|@|/|compress=zstd" ]
 }
 
-@test "setup_btrfs_subvolumes mounts with compress=zstd" {
-    grep -A60 'setup_btrfs_subvolumes()' "$SCRIPTS_DIR/disk_utils.sh" | grep -q 'compress=zstd'
+@test "setup_btrfs_subvolumes mounts each entry with its subvolume" {
+    grep -A40 'setup_btrfs_subvolumes()' "$SCRIPTS_DIR/disk_utils.sh" | grep -q 'subvol=\$name'
 }
 
 # =============================================================================
@@ -100,36 +100,27 @@ teardown() {
 # Strategy Script Existence Tests
 # =============================================================================
 
-@test "simple strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/simple.sh" ]
+@test "storage_plan strategy script exists" {
+    [ -f "$SCRIPTS_DIR/strategies/storage_plan.sh" ]
 }
 
-@test "simple_luks strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/simple_luks.sh" ]
+@test "planned strategies no longer ship their own scripts" {
+    local retired
+    for retired in simple simple_luks lvm lvm_luks raid raid_luks raid_lvm raid_lvm_luks alongside layout; do
+        [ ! -f "$SCRIPTS_DIR/strategies/$retired.sh" ]
+    done
 }
 
-@test "lvm strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/lvm.sh" ]
-}
-
-@test "lvm_luks strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/lvm_luks.sh" ]
-}
-
-@test "raid strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/raid.sh" ]
-}
-
-@test "raid_luks strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/raid_luks.sh" ]
-}
-
-@test "raid_lvm strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/raid_lvm.sh" ]
-}
-
-@test "raid_lvm_luks strategy script exists" {
-    [ -f "$SCRIPTS_DIR/strategies/raid_lvm_luks.sh" ]
+@test "partition_disk refuses automatic strategies without a storage plan" {
+    source "$SCRIPTS_DIR/utils.sh"
+    source "$SCRIPTS_DIR/disk_utils.sh"
+    source "$SCRIPTS_DIR/disk_strategies.sh"
+    eval "$(sed -n '/^partition_disk() {/,/^}/p' "$SCRIPTS_DIR/install.sh")"
+    unset ARCHTUI_STORAGE_PLAN
+    export INSTALL_DISK="/dev/sda" PARTITIONING_STRATEGY="auto_simple" BOOT_MODE="UEFI"
+    run partition_disk
+    [ "$status" -eq 1 ]
+    [[ "$output" == *"requires the storage plan"* ]]
 }
 
 @test "manual strategy script exists" {
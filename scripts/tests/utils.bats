@@ -57,6 +57,34 @@ teardown() {
     [[ ! "$output" =~ "Hidden message" ]]
 }
 
+# =============================================================================
+# Stage Event Tests
+# =============================================================================
+
+@test "emit_stage prints a tagged stage event" {
+    run emit_stage partitioning_disk
+    [ "$status" -eq 0 ]
+    [ "$output" = "@@ARCHTUI stage partitioning_disk" ]
+}
+
+@test "emit_step prints a tagged step event" {
+    run emit_step 2 5 "Configuring locale"
+    [ "$status" -eq 0 ]
+    [ "$output" = "@@ARCHTUI step 2/5 Configuring locale" ]
+}
+
+@test "stage_index returns the pipeline position" {
+    run stage_index installing_base_system
+    [ "$status" -eq 0 ]
+    [ "$output" = "4" ]
+}
+
+@test "stage_after_partitioning only accepts resumable stages" {
+    stage_after_partitioning generating_fstab
+    ! stage_after_partitioning partitioning_disk
+    ! stage_after_partitioning unknown_stage
+}
+
 # =============================================================================
 # Validation Helper Tests
 # =============================================================================
@@ -301,6 +329,31 @@ teardown() {
     [[ "$output" =~ "NON-CRITICAL: Failing op failed" ]]
 }
 
+# =============================================================================
+# Structured Event Tests (parsed by src/script_events.rs::ScriptEvent)
+# =============================================================================
+
+@test "json_escape escapes every control character as \u00XX" {
+    run json_escape $'a"b\\c\n\t\x01\x1f'
+    [ "$status" -eq 0 ]
+    [ "$output" = 'a\"b\\c\u000a\u0009\u0001\u001f' ]
+}
+
+@test "emit_finding writes one JSON line for a known severity" {
+    export ARCHTUI_EVENT_FD=1
+    run emit_finding warning ssh_root_login $'line one\nline two'
+    [ "$status" -eq 0 ]
+    [ "$output" = '{"type":"finding","severity":"warning","check":"ssh_root_login","message":"line one\u000aline two"}' ]
+}
+
+@test "emit_finding rejects severities outside ok/info/warning/critical" {
+    export ARCHTUI_EVENT_FD=1
+    run emit_finding error some_check "message"
+    [ "$status" -eq 1 ]
+    [[ "$output" =~ "invalid severity 'error'" ]]
+    [[ "$output" != *'"type":"finding"'* ]]
+}
+
 # =============================================================================
 # Secret Redaction Tests (mirror src/script_runner.rs::is_secret_env)
 # =============================================================================
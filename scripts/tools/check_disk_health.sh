@@ -79,6 +79,7 @@ fi
 log_info "🔍 Comprehensive Disk Reliability Test"
 echo "=================================================="
 log_info "Target Device: $DEVICE"
+emit_result device "$DEVICE"
 
 # Get basic device information
 log_info "📊 Device Information:"
@@ -95,11 +96,13 @@ fi
 if [[ -r "$DEVICE" ]]; then
     size=$(blockdev --getsize64 "$DEVICE" 2>/dev/null | numfmt --to=iec 2>/dev/null || echo "unknown")
     echo "  Size: $size"
+    emit_result size "$size"
 fi
 
 echo
 
 # Test 1: Filesystem Integrity Check
+emit_progress 1 4 "Filesystem integrity check"
 log_info "🧪 Test 1: Filesystem Integrity Check"
 echo "--------------------------------------------------"
 
@@ -123,8 +126,10 @@ if [[ -n "$mount_point" ]]; then
                 log_cmd "fsck -n $DEVICE"
                 if fsck_output=$(fsck -n "$DEVICE" 2>&1); then
                     log_success "✅ Filesystem integrity: GOOD"
+                    emit_finding ok filesystem "Filesystem integrity: GOOD"
                 else
                     log_warning "⚠️  Filesystem integrity: ISSUES DETECTED"
+                    emit_finding warning filesystem "Filesystem integrity: ISSUES DETECTED"
                     echo "$fsck_output" | head -20
                     echo "  Run 'fsck $DEVICE' to repair (after unmounting)"
                 fi
@@ -135,6 +140,7 @@ if [[ -n "$mount_point" ]]; then
                 fi
             else
                 log_warning "⚠️  Cannot unmount device for filesystem check"
+                emit_finding info filesystem "Device is in use - filesystem check skipped"
                 echo "  Device is in use - filesystem check skipped"
             fi
             ;;
@@ -143,8 +149,10 @@ if [[ -n "$mount_point" ]]; then
             btrfs_output=""
             if btrfs_output=$(btrfs check --readonly "$DEVICE" 2>&1); then
                 log_success "✅ Btrfs filesystem integrity: GOOD"
+                emit_finding ok filesystem "Btrfs filesystem integrity: GOOD"
             else
                 log_warning "⚠️  Btrfs filesystem integrity: ISSUES DETECTED"
+                emit_finding warning filesystem "Btrfs filesystem integrity: ISSUES DETECTED"
                 echo "$btrfs_output" | head -20
                 echo "  Run 'btrfs check --repair $DEVICE' to repair"
             fi
@@ -154,25 +162,30 @@ if [[ -n "$mount_point" ]]; then
             xfs_output=""
             if xfs_output=$(xfs_repair -n "$DEVICE" 2>&1); then
                 log_success "✅ XFS filesystem integrity: GOOD"
+                emit_finding ok filesystem "XFS filesystem integrity: GOOD"
             else
                 log_warning "⚠️  XFS filesystem integrity: ISSUES DETECTED"
+                emit_finding warning filesystem "XFS filesystem integrity: ISSUES DETECTED"
                 echo "$xfs_output" | head -20
                 echo "  Run 'xfs_repair $DEVICE' to repair"
             fi
             ;;
         *)
             log_warning "⚠️  Unknown filesystem type: $fs_type"
+            emit_finding info filesystem "Unsupported filesystem type: $fs_type"
             echo "  Filesystem check skipped for unsupported type"
             ;;
     esac
 else
     log_info "Device is not mounted"
     echo "  Filesystem integrity check skipped (device not mounted)"
+    emit_finding info filesystem "Filesystem integrity check skipped (device not mounted)"
 fi
 
 echo
 
 # Test 2: Bad Blocks Detection
+emit_progress 2 4 "Bad blocks detection"
 log_info "🧪 Test 2: Bad Blocks Detection"
 echo "--------------------------------------------------"
 
@@ -181,20 +194,24 @@ if command -v badblocks >/dev/null 2>&1; then
     # Run badblocks in read-only mode
     if badblocks -v -s "$DEVICE" 2>/dev/null; then
         log_success "✅ Bad blocks scan: PASSED"
+        emit_finding ok bad_blocks "Bad blocks scan: PASSED"
         echo "  No bad blocks detected"
     else
         log_warning "⚠️  Bad blocks scan: FAILED"
+        emit_finding critical bad_blocks "Bad blocks scan: FAILED"
         echo "  Bad blocks detected - consider replacing disk"
         echo "  Run 'badblocks -w $DEVICE' for destructive test (BACKUP FIRST!)"
     fi
 else
     log_warning "⚠️  badblocks not available"
+    emit_finding info bad_blocks "badblocks not available"
     echo "  Install e2fsprogs package for bad blocks testing"
 fi
 
 echo
 
 # Test 3: Performance Test
+emit_progress 3 4 "Performance test"
 log_info "🧪 Test 3: Performance Test"
 echo "--------------------------------------------------"
 
@@ -203,6 +220,7 @@ if command -v dd >/dev/null 2>&1; then
     # Test read speed (1MB test)
     read_speed=$(dd if="$DEVICE" of=/dev/null bs=1M count=1 2>&1 | grep -o '[0-9.]* MB/s' | tail -1 || echo "unknown")
     echo "  Read speed: $read_speed"
+    emit_result read_speed "$read_speed"
     
     # Write speed test — only safe on mounted filesystems via temp file
     if [[ -n "$mount_point" ]]; then
@@ -210,6 +228,7 @@ if command -v dd >/dev/null 2>&1; then
         test_file="${mount_point}/.disk_health_test_$$"
         write_speed=$(dd if=/dev/zero of="$test_file" bs=1M count=10 conv=fsync 2>&1 | grep -o '[0-9.]* MB/s' | tail -1 || echo "unknown")
         echo "  Write speed: $write_speed"
+        emit_result write_speed "$write_speed"
         rm -f "$test_file"
     else
         echo "  Write speed: Test skipped (device not mounted)"
@@ -222,21 +241,26 @@ if command -v dd >/dev/null 2>&1; then
         speed_num=$(echo "$speed_num" | tr -d '[:space:]')
         if (( $(echo "${speed_num} > 50" | bc -l 2>/dev/null || echo "0") )); then
             log_success "Performance: GOOD"
+            emit_finding ok performance "Performance: GOOD"
         elif (( $(echo "${speed_num} > 10" | bc -l 2>/dev/null || echo "0") )); then
             log_warning "Performance: SLOW"
+            emit_finding warning performance "Performance: SLOW"
             echo "  Disk may be aging or experiencing issues"
         else
             log_error "Performance: VERY SLOW"
+            emit_finding critical performance "Performance: VERY SLOW"
             echo "  Disk may have serious issues"
         fi
     fi
 else
     log_warning "⚠️  dd not available for performance testing"
+    emit_finding info performance "dd not available for performance testing"
 fi
 
 echo
 
 # Test 4: SMART Diagnostics (if supported)
+emit_progress 4 4 "SMART diagnostics"
 log_info "🧪 Test 4: SMART Diagnostics (if supported)"
 echo "--------------------------------------------------"
 
@@ -249,11 +273,14 @@ if command -v smartctl >/dev/null 2>&1; then
         health_status=$(smartctl -H "$DEVICE" 2>/dev/null | grep "SMART overall-health self-assessment test result" || true)
         if [[ "$health_status" == *"PASSED"* ]]; then
             log_success "✅ SMART Health: GOOD"
+            emit_finding ok smart "SMART Health: GOOD"
         elif [[ "$health_status" == *"FAILED"* ]]; then
             log_error "❌ SMART Health: BAD"
+            emit_finding critical smart "SMART Health: BAD"
             echo "  SMART indicates hardware issues"
         else
             log_warning "⚠️  SMART Health: UNKNOWN"
+            emit_finding warning smart "SMART Health: UNKNOWN"
         fi
         
         # Check critical attributes if detailed mode
@@ -263,11 +290,13 @@ if command -v smartctl >/dev/null 2>&1; then
         fi
     else
         log_info "SMART not supported on this device"
+        emit_finding info smart "SMART not supported on this device"
         echo "  This is normal for some USB drives and older devices"
         echo "  Other tests above provide reliability assessment"
     fi
 else
     log_info "smartctl not available"
+    emit_finding info smart "smartctl not available"
     echo "  Install smartmontools package for SMART diagnostics"
     echo "  Other tests above provide reliability assessment"
 fi
@@ -318,15 +347,18 @@ echo
 if [[ $issues -eq 0 ]]; then
     if [[ $warnings -eq 0 ]]; then
         log_success "🎉 Disk Reliability: EXCELLENT"
+        emit_result verdict excellent
         echo "  Your disk appears to be in excellent condition."
         echo "  No issues detected in any test."
     else
         log_success "✅ Disk Reliability: GOOD"
+        emit_result verdict good
         echo "  Your disk appears to be in good condition."
         echo "  Some diagnostic tools were unavailable, but core tests passed."
     fi
 else
     log_warning "⚠️  Disk Reliability: ISSUES DETECTED"
+    emit_result verdict issues
     echo "  Review the test results above for specific issues."
     echo "  Consider backing up important data."
 fi
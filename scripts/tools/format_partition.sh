@@ -48,7 +48,7 @@ while [[ $# -gt 0 ]]; do
             ;;
         --help)
             echo "Usage: $0 --device <partition> --filesystem <fs_type> [--label <label>] [--force]"
-            echo "Supported filesystems: ext4, xfs, btrfs, f2fs, fat32, ntfs"
+            echo "Supported filesystems: ext4, xfs, btrfs, f2fs, fat32, ntfs, swap"
             exit 0
             ;;
         *)
@@ -148,6 +148,15 @@ case "$FILESYSTEM" in
             mkfs.f2fs -f "$DEVICE" || error_exit "Failed to format $DEVICE as f2fs"
         fi
         ;;
+    swap)
+        if [[ -n "$LABEL" ]]; then
+            log_cmd "mkswap -f -L $LABEL $DEVICE"
+            mkswap -f -L "$LABEL" "$DEVICE" || error_exit "Failed to format $DEVICE as swap"
+        else
+            log_cmd "mkswap -f $DEVICE"
+            mkswap -f "$DEVICE" || error_exit "Failed to format $DEVICE as swap"
+        fi
+        ;;
     *)
         error_exit "Unsupported filesystem type: $FILESYSTEM"
         ;;
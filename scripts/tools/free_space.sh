@@ -0,0 +1,134 @@
+#!/bin/bash
+# free_space.sh - Make room for Linux next to an existing OS without wiping
+# Usage: ./free_space.sh --action <action> [options]
+#
+# Actions:
+#   shrink  - Shrink an NTFS/ext4 partition (filesystem + GPT entry)
+#   create  - Create a named partition in the largest free block of a GPT disk
+
+set -euo pipefail
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "$(basename "$0"): Received $sig, aborting..." >&2
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+source_or_die "$SCRIPT_DIR/../disk_utils.sh"
+
+require_root
+
+# Default values
+ACTION=""
+DEVICE=""
+PARTITION=""
+PART_SIZE=""
+PART_TYPE=""
+PART_LABEL=""
+
+# Parse arguments
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --action)
+            ACTION="$2"
+            shift 2
+            ;;
+        --device)
+            DEVICE="$2"
+            shift 2
+            ;;
+        --partition)
+            PARTITION="$2"
+            shift 2
+            ;;
+        --size)
+            PART_SIZE="$2"
+            shift 2
+            ;;
+        --type)
+            PART_TYPE="$2"
+            shift 2
+            ;;
+        --label)
+            PART_LABEL="$2"
+            shift 2
+            ;;
+        --help)
+            echo "Usage: $0 --action <action> [options]"
+            echo ""
+            echo "Actions:"
+            echo "  shrink  --partition PART --size SIZE"
+            echo "  create  --device DISK --size SIZE|remaining --type CODE --label LABEL"
+            exit 0
+            ;;
+        *)
+            log_error "Unknown option: $1"
+            exit 1
+            ;;
+    esac
+done
+
+if [[ -z "$ACTION" ]]; then
+    error_exit "Action is required (--action shrink|create)"
+fi
+
+if [[ -z "$PART_SIZE" ]]; then
+    error_exit "--size is required"
+fi
+
+# ENVIRONMENT CONTRACT: Require explicit confirmation for destructive operation
+if [[ "${CONFIRM_FREE_SPACE:-}" != "yes" ]]; then
+    error_exit "CONFIRM_FREE_SPACE=yes is required. This script refuses to run without explicit environment confirmation."
+fi
+
+case "$ACTION" in
+    shrink)
+        log_phase "Shrink Partition"
+        if [[ -z "$PARTITION" ]]; then
+            error_exit "--partition is required for shrink"
+        fi
+        if ! validate_device_path "$PARTITION"; then
+            error_exit "Invalid partition path: $PARTITION"
+        fi
+        if [[ ! "$PART_SIZE" =~ ^[0-9]+[KMGT]$ ]]; then
+            error_exit "Invalid size: $PART_SIZE (e.g. 200G)"
+        fi
+        shrink_partition "$PARTITION" "$PART_SIZE" || error_exit "Failed to shrink $PARTITION"
+        ;;
+    create)
+        log_phase "Create Partition in Free Space"
+        if [[ -z "$DEVICE" || -z "$PART_TYPE" || -z "$PART_LABEL" ]]; then
+            error_exit "--device, --type and --label are required for create"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid device path: $DEVICE"
+        fi
+        if [[ ! -b "$DEVICE" ]]; then
+            error_exit "Device does not exist: $DEVICE"
+        fi
+        if [[ ! "$PART_SIZE" =~ ^([0-9]+[KMGT]|remaining)$ ]]; then
+            error_exit "Invalid size: $PART_SIZE (e.g. 1024M or remaining)"
+        fi
+        if [[ ! "$PART_TYPE" =~ ^[0-9A-Fa-f]{4}$ ]]; then
+            error_exit "Invalid partition type code: $PART_TYPE"
+        fi
+        if [[ ! "$PART_LABEL" =~ ^[A-Za-z0-9_-]+$ ]]; then
+            error_exit "Invalid partition label: $PART_LABEL"
+        fi
+        create_partition_in_free_space "$DEVICE" "$PART_SIZE" "$PART_TYPE" "$PART_LABEL" \
+            || error_exit "Failed to create $PART_LABEL on $DEVICE"
+        ;;
+    *)
+        error_exit "Unknown action: $ACTION (valid: shrink, create)"
+        ;;
+esac
+
+log_success "Free-space action '$ACTION' complete"
@@ -0,0 +1,106 @@
+#!/bin/bash
+# manage_btrfs.sh - Btrfs subvolume operations
+#
+# ACTIONS:
+#   create-subvolumes  - Create subvolumes at the top level of a Btrfs device
+#
+# The top-level subvolume (subvolid=5) is mounted on a temporary directory
+# for the duration of the action and unmounted again before exit.
+#
+# This script is NON-INTERACTIVE.
+
+set -euo pipefail
+
+TOP_LEVEL_MOUNT=""
+
+# --- Cleanup ---
+release_top_level() {
+    if [[ -n "$TOP_LEVEL_MOUNT" ]]; then
+        umount "$TOP_LEVEL_MOUNT" 2>/dev/null || true
+        rmdir "$TOP_LEVEL_MOUNT" 2>/dev/null || true
+        TOP_LEVEL_MOUNT=""
+    fi
+}
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "MANAGE_BTRFS: Received $sig, aborting..." >&2
+    release_top_level
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+trap 'release_top_level' EXIT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+
+require_root
+
+# --- Argument Parsing ---
+ACTION=""
+DEVICE=""
+SUBVOLUMES=()
+
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --action)    ACTION="$2"; shift 2 ;;
+        --device)    DEVICE="$2"; shift 2 ;;
+        --subvolume) SUBVOLUMES+=("$2"); shift 2 ;;
+        *) error_exit "Unknown argument: $1" ;;
+    esac
+done
+
+# --- Validation ---
+if [[ -z "$ACTION" ]]; then
+    error_exit "Missing required argument: --action (create-subvolumes)"
+fi
+
+# --- Action Dispatch ---
+case "$ACTION" in
+    create-subvolumes)
+        if [[ -z "$DEVICE" ]]; then
+            error_exit "Missing required argument: --device"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid device path: $DEVICE"
+        fi
+        if [[ ${#SUBVOLUMES[@]} -eq 0 ]]; then
+            error_exit "At least one --subvolume is required"
+        fi
+        for subvol in "${SUBVOLUMES[@]}"; do
+            if [[ ! "$subvol" =~ ^[@a-zA-Z0-9._-]+$ || "$subvol" == "." || "$subvol" == ".." ]]; then
+                error_exit "Invalid subvolume name: $subvol"
+            fi
+        done
+
+        log_phase "Btrfs Subvolumes: $DEVICE (${SUBVOLUMES[*]})"
+        TOP_LEVEL_MOUNT="$(mktemp -d /tmp/archtui-btrfs.XXXXXX)"
+        log_cmd "mount -o subvolid=5 $DEVICE $TOP_LEVEL_MOUNT"
+        if ! mount -o subvolid=5 "$DEVICE" "$TOP_LEVEL_MOUNT"; then
+            error_exit "Failed to mount top-level subvolume of $DEVICE"
+        fi
+
+        for subvol in "${SUBVOLUMES[@]}"; do
+            if btrfs subvolume show "$TOP_LEVEL_MOUNT/$subvol" >/dev/null 2>&1; then
+                log_info "Subvolume $subvol already exists, skipping"
+                continue
+            fi
+            log_cmd "btrfs subvolume create $TOP_LEVEL_MOUNT/$subvol"
+            if ! btrfs subvolume create "$TOP_LEVEL_MOUNT/$subvol"; then
+                error_exit "Failed to create subvolume $subvol on $DEVICE"
+            fi
+        done
+
+        release_top_level
+        log_success "Created ${#SUBVOLUMES[@]} subvolume(s) on $DEVICE"
+        ;;
+
+    *)
+        error_exit "Unknown action: $ACTION (valid: create-subvolumes)"
+        ;;
+esac
@@ -0,0 +1,133 @@
+#!/bin/bash
+# manage_lvm.sh - LVM volume creation (physical volumes, volume groups, logical volumes)
+#
+# ACTIONS:
+#   pv-create  - Initialize a device as an LVM physical volume
+#   vg-create  - Create a volume group on a physical volume
+#   lv-create  - Create a logical volume in a volume group
+#
+# ENVIRONMENT CONTRACT:
+#   CONFIRM_LVM_CREATE=yes   Required for all actions.
+#
+# This script is NON-INTERACTIVE. All confirmation from environment.
+
+set -euo pipefail
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "MANAGE_LVM: Received $sig, aborting..." >&2
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+
+require_root
+
+# --- Argument Parsing ---
+ACTION=""
+DEVICE=""
+VG_NAME=""
+LV_NAME=""
+SIZE=""
+
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --action) ACTION="$2"; shift 2 ;;
+        --device) DEVICE="$2"; shift 2 ;;
+        --vg)     VG_NAME="$2"; shift 2 ;;
+        --lv)     LV_NAME="$2"; shift 2 ;;
+        --size)   SIZE="$2"; shift 2 ;;
+        *) error_exit "Unknown argument: $1" ;;
+    esac
+done
+
+# --- Validation ---
+if [[ -z "$ACTION" ]]; then
+    error_exit "Missing required argument: --action (pv-create|vg-create|lv-create)"
+fi
+
+# Environment contract enforcement
+if [[ "${CONFIRM_LVM_CREATE:-}" != "yes" ]]; then
+    error_exit "CONFIRM_LVM_CREATE=yes is required. Refusing to modify LVM without confirmation."
+fi
+
+# --- Action Dispatch ---
+case "$ACTION" in
+    pv-create)
+        if [[ -z "$DEVICE" ]]; then
+            error_exit "Missing required argument: --device"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid device path: $DEVICE"
+        fi
+
+        log_phase "LVM PV Create: $DEVICE"
+        log_cmd "pvcreate -ff -y $DEVICE"
+        if ! pvcreate -ff -y "$DEVICE"; then
+            error_exit "Failed to create physical volume on $DEVICE"
+        fi
+        log_success "Physical volume created: $DEVICE"
+        ;;
+
+    vg-create)
+        if [[ -z "$VG_NAME" ]]; then
+            error_exit "Missing required argument: --vg"
+        fi
+        if [[ -z "$DEVICE" ]]; then
+            error_exit "Missing required argument: --device"
+        fi
+        if ! validate_safe_string "$VG_NAME"; then
+            error_exit "Invalid volume group name: $VG_NAME"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid device path: $DEVICE"
+        fi
+
+        log_phase "LVM VG Create: $VG_NAME on $DEVICE"
+        log_cmd "vgcreate $VG_NAME $DEVICE"
+        if ! vgcreate "$VG_NAME" "$DEVICE"; then
+            error_exit "Failed to create volume group $VG_NAME"
+        fi
+        log_success "Volume group created: $VG_NAME"
+        ;;
+
+    lv-create)
+        if [[ -z "$VG_NAME" ]]; then
+            error_exit "Missing required argument: --vg"
+        fi
+        if [[ -z "$LV_NAME" ]]; then
+            error_exit "Missing required argument: --lv"
+        fi
+        if [[ -z "$SIZE" ]]; then
+            error_exit "Missing required argument: --size"
+        fi
+        if ! validate_safe_string "$VG_NAME"; then
+            error_exit "Invalid volume group name: $VG_NAME"
+        fi
+        if ! validate_safe_string "$LV_NAME"; then
+            error_exit "Invalid logical volume name: $LV_NAME"
+        fi
+
+        log_phase "LVM LV Create: $VG_NAME/$LV_NAME ($SIZE)"
+        # Percentage sizes (100%FREE, 50%VG) are extents (-l); others are absolute (-L)
+        if [[ "$SIZE" == *%* ]]; then
+            log_cmd "lvcreate -y -l $SIZE -n $LV_NAME $VG_NAME"
+            lvcreate -y -l "$SIZE" -n "$LV_NAME" "$VG_NAME" || error_exit "Failed to create logical volume $LV_NAME"
+        else
+            log_cmd "lvcreate -y -L $SIZE -n $LV_NAME $VG_NAME"
+            lvcreate -y -L "$SIZE" -n "$LV_NAME" "$VG_NAME" || error_exit "Failed to create logical volume $LV_NAME"
+        fi
+        log_success "Logical volume created: /dev/$VG_NAME/$LV_NAME"
+        ;;
+
+    *)
+        error_exit "Unknown action: $ACTION (valid: pv-create, vg-create, lv-create)"
+        ;;
+esac
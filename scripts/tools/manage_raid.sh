@@ -0,0 +1,128 @@
+#!/bin/bash
+# manage_raid.sh - mdadm software RAID operations
+#
+# ACTIONS:
+#   create      - Create an array from member partitions
+#   wait        - Wait for an array to finish assembly/resync
+#   write-conf  - Write array definitions (mdadm --detail --scan) to a config file
+#
+# ENVIRONMENT CONTRACT:
+#   CONFIRM_RAID_CREATE=yes   Required for create action only.
+#
+# This script is NON-INTERACTIVE. All confirmation from environment.
+
+set -euo pipefail
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "MANAGE_RAID: Received $sig, aborting..." >&2
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+source_or_die "$SCRIPT_DIR/../disk_utils.sh"
+
+require_root
+
+# --- Argument Parsing ---
+ACTION=""
+DEVICE=""
+LEVEL=""
+CONF_PATH=""
+MEMBERS=()
+
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --action) ACTION="$2"; shift 2 ;;
+        --device) DEVICE="$2"; shift 2 ;;
+        --level)  LEVEL="$2"; shift 2 ;;
+        --member) MEMBERS+=("$2"); shift 2 ;;
+        --path)   CONF_PATH="$2"; shift 2 ;;
+        *) error_exit "Unknown argument: $1" ;;
+    esac
+done
+
+# --- Validation ---
+if [[ -z "$ACTION" ]]; then
+    error_exit "Missing required argument: --action (create|wait|write-conf)"
+fi
+
+# --- Action Dispatch ---
+case "$ACTION" in
+    create)
+        if [[ -z "$DEVICE" ]]; then
+            error_exit "Missing required argument: --device"
+        fi
+        if [[ -z "$LEVEL" ]]; then
+            error_exit "Missing required argument: --level"
+        fi
+        if [[ ${#MEMBERS[@]} -lt 2 ]]; then
+            error_exit "At least two --member partitions are required"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid array device path: $DEVICE"
+        fi
+        for member in "${MEMBERS[@]}"; do
+            if ! validate_device_path "$member"; then
+                error_exit "Invalid member device path: $member"
+            fi
+        done
+        case "$LEVEL" in
+            raid0|raid1|raid5|raid6|raid10) ;;
+            *) error_exit "Unsupported RAID level: $LEVEL (valid: raid0, raid1, raid5, raid6, raid10)" ;;
+        esac
+
+        # Environment contract enforcement
+        if [[ "${CONFIRM_RAID_CREATE:-}" != "yes" ]]; then
+            error_exit "CONFIRM_RAID_CREATE=yes is required. Refusing to create array without confirmation."
+        fi
+
+        log_phase "RAID Create: $DEVICE ($LEVEL, ${#MEMBERS[@]} members)"
+        log_warn "THIS WILL DESTROY ALL DATA ON ${MEMBERS[*]}"
+        log_cmd "mdadm --create --run --verbose --level=$LEVEL --raid-devices=${#MEMBERS[@]} $DEVICE ${MEMBERS[*]}"
+        if ! mdadm --create --run --verbose --level="$LEVEL" --raid-devices=${#MEMBERS[@]} "$DEVICE" "${MEMBERS[@]}"; then
+            error_exit "Failed to create RAID array $DEVICE"
+        fi
+        log_success "RAID array created: $DEVICE"
+        ;;
+
+    wait)
+        if [[ -z "$DEVICE" ]]; then
+            error_exit "Missing required argument: --device"
+        fi
+        if ! validate_device_path "$DEVICE"; then
+            error_exit "Invalid array device path: $DEVICE"
+        fi
+
+        log_phase "RAID Wait: $DEVICE"
+        wait_for_raid_array "$DEVICE"
+        ;;
+
+    write-conf)
+        if [[ -z "$CONF_PATH" ]]; then
+            error_exit "Missing required argument: --path"
+        fi
+        if [[ "$CONF_PATH" != /* ]]; then
+            error_exit "Config path must be absolute: $CONF_PATH"
+        fi
+
+        log_phase "RAID Config: $CONF_PATH"
+        mkdir -p "$(dirname "$CONF_PATH")"
+        log_cmd "mdadm --detail --scan > $CONF_PATH"
+        if ! mdadm --detail --scan > "$CONF_PATH"; then
+            error_exit "Failed to write $CONF_PATH"
+        fi
+        log_success "Array definitions written to $CONF_PATH"
+        ;;
+
+    *)
+        error_exit "Unknown action: $ACTION (valid: create, wait, write-conf)"
+        ;;
+esac
@@ -0,0 +1,88 @@
+#!/bin/bash
+# manage_swap.sh - Swapfile operations on the mounted target
+#
+# ACTIONS:
+#   create-swapfile  - Allocate, format and activate a swapfile
+#
+# Btrfs swapfiles are created with `btrfs filesystem mkswapfile` (NOCOW,
+# no compression); other filesystems get a zero-filled file + mkswap.
+#
+# ENVIRONMENT CONTRACT:
+#   CONFIRM_SWAPFILE_CREATE=yes   Required for create-swapfile.
+#
+# This script is NON-INTERACTIVE. All confirmation from environment.
+
+set -euo pipefail
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "MANAGE_SWAP: Received $sig, aborting..." >&2
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+source_or_die "$SCRIPT_DIR/../disk_utils.sh"
+
+require_root
+
+# --- Argument Parsing ---
+ACTION=""
+SWAPFILE=""
+SIZE=""
+
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --action) ACTION="$2"; shift 2 ;;
+        --path)   SWAPFILE="$2"; shift 2 ;;
+        --size)   SIZE="$2"; shift 2 ;;
+        *) error_exit "Unknown argument: $1" ;;
+    esac
+done
+
+# --- Validation ---
+if [[ -z "$ACTION" ]]; then
+    error_exit "Missing required argument: --action (create-swapfile)"
+fi
+
+# --- Action Dispatch ---
+case "$ACTION" in
+    create-swapfile)
+        if [[ "${CONFIRM_SWAPFILE_CREATE:-}" != "yes" ]]; then
+            error_exit "CONFIRM_SWAPFILE_CREATE=yes is required. Refusing to create a swapfile without confirmation."
+        fi
+        if [[ -z "$SWAPFILE" || -z "$SIZE" ]]; then
+            error_exit "Missing required arguments: --path and --size"
+        fi
+        if [[ "$SWAPFILE" != /mnt/* || "$SWAPFILE" == *..* ]]; then
+            error_exit "Swapfile must be under /mnt: $SWAPFILE"
+        fi
+        if [[ -e "$SWAPFILE" ]]; then
+            error_exit "Swapfile already exists: $SWAPFILE"
+        fi
+
+        # Size is "<n>G" or "<n>M" (see engine::swap::swap_size_spec)
+        size_mib=""
+        case "$SIZE" in
+            *[Gg]) size_mib=$(( ${SIZE%[Gg]} * 1024 )) ;;
+            *[Mm]) size_mib="${SIZE%[Mm]}" ;;
+        esac
+        if [[ ! "$size_mib" =~ ^[1-9][0-9]*$ ]]; then
+            error_exit "Invalid swapfile size: $SIZE (expected e.g. 4G or 8192M)"
+        fi
+
+        log_phase "Swapfile: $SWAPFILE (${size_mib}MiB)"
+        mkdir -p "$(dirname "$SWAPFILE")" || error_exit "Cannot create $(dirname "$SWAPFILE")"
+        create_swapfile "$size_mib" "$SWAPFILE" || error_exit "Failed to create swapfile $SWAPFILE"
+        ;;
+
+    *)
+        error_exit "Unknown action: $ACTION (valid: create-swapfile)"
+        ;;
+esac
@@ -78,7 +78,7 @@ while [[ $# -gt 0 ]]; do
             echo "  cfdisk         (interactive editor)"
             echo ""
             echo "Type codes: EF00 (EFI), EF02 (BIOS Boot), 8300 (Linux),"
-            echo "            8200 (Swap), 8E00 (LVM), 8309 (LUKS)"
+            echo "            8200 (Swap), 8E00 (LVM), 8309 (LUKS), FD00 (RAID)"
             exit 0
             ;;
         *)
@@ -282,6 +282,7 @@ _add_partition_mbr() {
         8300|8309) mbr_type="83" ;;
         8200) mbr_type="82" ;;
         8E00) mbr_type="8e" ;;
+        FD00) mbr_type="fd" ;;
         EF02) mbr_type="ef" ;; # BIOS boot — best MBR approximation
         *) mbr_type="83" ;; # Default to Linux
     esac
@@ -251,7 +251,8 @@ case "$ACTION" in
         fi
         
         # Check if device is already mounted (findmnt works on block devices, mountpoint only works on directories)
-        if findmnt -n "$DEVICE" >/dev/null 2>&1; then
+        # Btrfs subvolume mounts (subvol=...) legitimately mount the same device several times
+        if [[ ",$OPTIONS," != *",subvol="* ]] && findmnt -n "$DEVICE" >/dev/null 2>&1; then
             log_warning "⚠️  Device $DEVICE is already mounted"
             findmnt -n "$DEVICE" | sed 's/^/  /'
 
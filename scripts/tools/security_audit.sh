@@ -52,8 +52,10 @@ check_file_permissions() {
         local perms=$(stat -c "%a" /etc/passwd)
         if [[ "$perms" != "644" ]]; then
             log_warning "/etc/passwd has incorrect permissions: $perms (should be 644)"
+            emit_finding warning passwd_permissions "/etc/passwd has permissions $perms (should be 644)"
         else
             log_success "/etc/passwd permissions OK: $perms"
+            emit_finding ok passwd_permissions "/etc/passwd permissions OK: $perms"
         fi
     fi
     
@@ -62,8 +64,10 @@ check_file_permissions() {
         local perms=$(stat -c "%a" /etc/shadow)
         if [[ "$perms" != "640" ]]; then
             log_warning "/etc/shadow has incorrect permissions: $perms (should be 640)"
+            emit_finding warning shadow_permissions "/etc/shadow has permissions $perms (should be 640)"
         else
             log_success "/etc/shadow permissions OK: $perms"
+            emit_finding ok shadow_permissions "/etc/shadow permissions OK: $perms"
         fi
     fi
     
@@ -72,8 +76,10 @@ check_file_permissions() {
         local perms=$(stat -c "%a" /etc/sudoers)
         if [[ "$perms" != "440" ]]; then
             log_warning "/etc/sudoers has incorrect permissions: $perms (should be 440)"
+            emit_finding warning sudoers_permissions "/etc/sudoers has permissions $perms (should be 440)"
         else
             log_success "/etc/sudoers permissions OK: $perms"
+            emit_finding ok sudoers_permissions "/etc/sudoers permissions OK: $perms"
         fi
     fi
 }
@@ -86,21 +92,26 @@ check_user_accounts() {
     local uid0_users=$(awk -F: '$3==0 {print $1}' /etc/passwd)
     if [[ $(echo "$uid0_users" | wc -l) -gt 1 ]] || [[ "$uid0_users" != "root" ]]; then
         log_warning "Multiple users with UID 0 found: $uid0_users"
+        emit_finding critical uid0_users "Multiple users with UID 0 found: $uid0_users"
     else
         log_success "Only root has UID 0"
+        emit_finding ok uid0_users "Only root has UID 0"
     fi
     
     # Check for users without passwords
     local no_password_users=$(awk -F: '($2 == "" || $2 == "!") {print $1}' /etc/shadow)
     if [[ -n "$no_password_users" ]]; then
         log_warning "Users without passwords: $no_password_users"
+        emit_finding warning empty_passwords "Users without passwords: $no_password_users"
     else
         log_success "All users have passwords set"
+        emit_finding ok empty_passwords "All users have passwords set"
     fi
     
     # Check for users with shell access
     local shell_users=$(awk -F: '$7 !~ /nologin|false/ {print $1}' /etc/passwd)
     log_info "Users with shell access: $shell_users"
+    emit_finding info shell_users "Users with shell access: $shell_users"
 }
 
 # Function to check network services
@@ -119,16 +130,20 @@ check_network_services() {
     # Check SSH configuration
     if systemctl is-active sshd >/dev/null 2>&1; then
         log_warning "SSH service is running"
+        emit_finding warning sshd_running "SSH service is running"
         if [[ -f /etc/ssh/sshd_config ]]; then
             local root_login=$(grep '^PermitRootLogin' /etc/ssh/sshd_config | awk '{print $2}')
             if [[ "$root_login" == "yes" ]]; then
                 log_warning "SSH root login is enabled"
+                emit_finding critical ssh_root_login "SSH root login is enabled"
             else
                 log_success "SSH root login is disabled"
+                emit_finding ok ssh_root_login "SSH root login is disabled"
             fi
         fi
     else
         log_success "SSH service is not running"
+        emit_finding ok sshd_running "SSH service is not running"
     fi
 }
 
@@ -141,8 +156,10 @@ check_packages() {
     for tool in "${security_tools[@]}"; do
         if pacman -Qi "$tool" >/dev/null 2>&1; then
             log_success "Security tool installed: $tool"
+            emit_finding ok "security_tool_$tool" "Security tool installed: $tool"
         else
             log_info "Security tool not installed: $tool"
+            emit_finding info "security_tool_$tool" "Security tool not installed: $tool"
         fi
     done
 }
@@ -156,15 +173,18 @@ check_updates() {
         local update_count=$(pacman -Qu | wc -l)
         if [[ "$update_count" -gt 0 ]]; then
             log_warning "$update_count packages have updates available"
+            emit_finding warning pending_updates "$update_count packages have updates available"
             if [[ "$ACTION" == "full" ]]; then
                 log_info "Available updates:"
                 pacman -Qu | head -10
             fi
         else
             log_success "System is up to date"
+            emit_finding ok pending_updates "System is up to date"
         fi
     else
         log_info "Could not check for updates (pacman database might be locked)"
+        emit_finding info pending_updates "Could not check for updates"
     fi
 }
 
@@ -175,12 +195,16 @@ check_firewall() {
     # Check for active firewall
     if systemctl is-active firewalld >/dev/null 2>&1; then
         log_success "Firewalld is active"
+        emit_finding ok firewall "Firewalld is active"
     elif systemctl is-active ufw >/dev/null 2>&1; then
         log_success "UFW is active"
+        emit_finding ok firewall "UFW is active"
     elif iptables -L INPUT | grep -q "DROP\|REJECT"; then
         log_success "iptables rules are configured"
+        emit_finding ok firewall "iptables rules are configured"
     else
         log_warning "No active firewall detected"
+        emit_finding warning firewall "No active firewall detected"
     fi
 }
 
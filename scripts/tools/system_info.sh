@@ -325,6 +325,18 @@ output_json() {
     echo "}"
 }
 
+# Structured results for `archtui tools system info --json` (see emit_result in utils.sh)
+emit_system_results() {
+    local key
+    for key in hostname distribution kernel architecture boot_mode cpu_model \
+               memory_total memory_used memory_available swap_usage \
+               network_interfaces timezone; do
+        emit_result "$key" "$(get_system_info "$key")"
+    done
+    emit_result_int cpu_cores "$(get_system_info cpu_cores)"
+    emit_result_int mounted_filesystems "$(get_system_info mounted_filesystems)"
+}
+
 # Main execution
 log_info "🔍 System Information Tool (ISO Compatible)"
 echo "=================================================="
@@ -372,4 +384,6 @@ else
     echo "💡 Use --json for machine-readable output"
 fi
 
+[[ -n "${ARCHTUI_EVENT_FD:-}" ]] && emit_system_results
+
 log_success "✅ System information retrieved successfully!"
\ No newline at end of file
@@ -0,0 +1,135 @@
+#!/bin/bash
+# teardown_storage.sh - Release storage left active by a partially-applied install
+#
+# Runs teardown steps in the order given on the command line (the Rust side
+# derives this order from StoragePlan::teardown). Steps whose target is already
+# released are skipped; failing steps are logged and the remaining steps still
+# run. Exits non-zero if any step failed.
+#
+# STEPS (repeatable, order-preserving):
+#   --umount <path>     Recursively unmount a mountpoint
+#   --swapoff <device>  Deactivate a swap device
+#   --vg <name>         Deactivate a volume group (vgchange -an)
+#   --mapper <name>     Close a LUKS mapper (cryptsetup close)
+#   --md <device>       Stop an mdadm array (mdadm --stop)
+#
+# ENVIRONMENT CONTRACT:
+#   CONFIRM_TEARDOWN_STORAGE=yes   Required for execution.
+#
+# This script is NON-INTERACTIVE. All confirmation from environment.
+
+set -euo pipefail
+
+# --- Signal Handling ---
+cleanup_and_exit() {
+    local sig="$1"
+    echo "TEARDOWN_STORAGE: Received $sig, aborting..." >&2
+    [[ "$sig" == "SIGTERM" ]] && exit 143 || exit 130
+}
+trap 'cleanup_and_exit SIGTERM' SIGTERM
+trap 'cleanup_and_exit SIGINT' SIGINT
+
+# Source common utilities via bootstrap
+SCRIPT_DIR="$(dirname "${BASH_SOURCE[0]}")"
+# shellcheck source=../bootstrap.sh
+source "$SCRIPT_DIR/../bootstrap.sh" || { echo "FATAL: Cannot source bootstrap.sh" >&2; exit 1; }
+source_or_die "$SCRIPT_DIR/../utils.sh"
+
+require_root
+
+# --- Argument Parsing ---
+STEP_KINDS=()
+STEP_TARGETS=()
+
+while [[ $# -gt 0 ]]; do
+    case "$1" in
+        --umount|--swapoff|--vg|--mapper|--md)
+            [[ $# -ge 2 ]] || error_exit "Missing value for $1"
+            STEP_KINDS+=("${1#--}")
+            STEP_TARGETS+=("$2")
+            shift 2
+            ;;
+        *) error_exit "Unknown argument: $1" ;;
+    esac
+done
+
+# --- Validation ---
+if [[ ${#STEP_KINDS[@]} -eq 0 ]]; then
+    error_exit "Nothing to tear down (pass --umount, --swapoff, --vg, --mapper or --md)"
+fi
+
+# Environment contract enforcement
+if [[ "${CONFIRM_TEARDOWN_STORAGE:-}" != "yes" ]]; then
+    error_exit "CONFIRM_TEARDOWN_STORAGE=yes is required. Refusing to tear down storage without confirmation."
+fi
+
+for i in "${!STEP_KINDS[@]}"; do
+    target="${STEP_TARGETS[$i]}"
+    case "${STEP_KINDS[$i]}" in
+        umount)
+            [[ "$target" == /* ]] || error_exit "Mountpoint must be absolute: $target"
+            ;;
+        swapoff|md)
+            validate_device_path "$target" || error_exit "Invalid device path: $target"
+            ;;
+        vg|mapper)
+            validate_safe_string "$target" || error_exit "Invalid name: $target"
+            ;;
+    esac
+done
+
+# --- Teardown ---
+log_phase "Storage Teardown (${#STEP_KINDS[@]} steps)"
+FAILED=0
+
+for i in "${!STEP_KINDS[@]}"; do
+    target="${STEP_TARGETS[$i]}"
+    case "${STEP_KINDS[$i]}" in
+        umount)
+            if ! findmnt -M "$target" >/dev/null 2>&1; then
+                log_info "Not mounted, skipping: $target"
+                continue
+            fi
+            log_cmd "umount -R $target"
+            umount -R "$target" || { log_error "Failed to unmount $target"; FAILED=1; }
+            ;;
+        swapoff)
+            if ! grep -q "^$(readlink -f "$target") " /proc/swaps 2>/dev/null; then
+                log_info "Swap not active, skipping: $target"
+                continue
+            fi
+            log_cmd "swapoff $target"
+            swapoff "$target" || { log_error "Failed to deactivate swap $target"; FAILED=1; }
+            ;;
+        vg)
+            if ! vgs "$target" >/dev/null 2>&1; then
+                log_info "Volume group not present, skipping: $target"
+                continue
+            fi
+            log_cmd "vgchange -an $target"
+            vgchange -an "$target" || { log_error "Failed to deactivate volume group $target"; FAILED=1; }
+            ;;
+        mapper)
+            if [[ ! -e "/dev/mapper/$target" ]]; then
+                log_info "Mapper not open, skipping: $target"
+                continue
+            fi
+            log_cmd "cryptsetup close $target"
+            cryptsetup close "$target" || { log_error "Failed to close mapper $target"; FAILED=1; }
+            ;;
+        md)
+            if [[ ! -e "$target" ]]; then
+                log_info "Array not running, skipping: $target"
+                continue
+            fi
+            log_cmd "mdadm --stop $target"
+            mdadm --stop "$target" || { log_error "Failed to stop array $target"; FAILED=1; }
+            ;;
+    esac
+done
+
+if [[ "$FAILED" -ne 0 ]]; then
+    error_exit "Teardown incomplete — see errors above"
+fi
+
+log_success "Storage teardown complete"
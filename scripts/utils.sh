@@ -142,6 +142,112 @@ log_cmd() {
     echo "  > $message" >> "${LOG_FILE:-/dev/null}" 2>/dev/null || true
 }
 
+# --- Stage Events ---
+# Tagged lines parsed by the TUI (install_state::StageEvent) to drive its
+# install state machine. Stages must be emitted in order; see InstallStage::id.
+
+# Stages in pipeline order (excluding not_started/completed)
+INSTALL_STAGES=(
+    validating_config
+    preparing_system
+    installing_dependencies
+    partitioning_disk
+    installing_base_system
+    generating_fstab
+    configuring_chroot
+    finalizing
+)
+
+# Records the stage in CURRENT_STAGE so exit traps know how far we got
+emit_stage() {
+    CURRENT_STAGE="$1"
+    echo "@@ARCHTUI stage $1"
+}
+
+# Usage: emit_step <index> <total> <description>
+emit_step() {
+    echo "@@ARCHTUI step $1/$2 $3"
+}
+
+# Usage: stage_index <stage_id> — prints its position in INSTALL_STAGES
+stage_index() {
+    local i
+    for i in "${!INSTALL_STAGES[@]}"; do
+        if [[ "${INSTALL_STAGES[$i]}" == "$1" ]]; then
+            echo "$i"
+            return 0
+        fi
+    done
+    return 1
+}
+
+# True for stages that run after the disk was partitioned and mounted
+stage_after_partitioning() {
+    local index
+    index=$(stage_index "$1") || return 1
+    (( index > $(stage_index partitioning_disk) ))
+}
+
+# --- Structured Events ---
+# JSON lines written to the fd named by ARCHTUI_EVENT_FD, which the Rust
+# runner opens for every tool (script_events::ScriptEvent). All emitters are
+# no-ops when the script runs standalone, and never fail the script —
+# except emit_finding with a severity ScriptEvent cannot parse, which is a
+# bug in the calling script and returns 1 instead of emitting a dropped line.
+
+# Usage: json_escape <string> — prints the string escaped for a JSON literal
+# (backslash, quote, and every control character below 0x20 as \u00XX)
+json_escape() {
+    local s="$1"
+    s="${s//\\/\\\\}"
+    s="${s//\"/\\\"}"
+    if [[ "$s" == *[[:cntrl:]]* ]]; then
+        local code oct ch esc
+        for code in {1..31}; do
+            printf -v oct '%03o' "$code"
+            printf -v ch "\\$oct"
+            printf -v esc '\\u%04x' "$code"
+            s="${s//"$ch"/$esc}"
+        done
+    fi
+    printf '%s' "$s"
+}
+
+# Usage: emit_event <json object>
+emit_event() {
+    [[ -n "${ARCHTUI_EVENT_FD:-}" ]] || return 0
+    printf '%s\n' "$1" 2>/dev/null >&"$ARCHTUI_EVENT_FD" || true
+}
+
+# Usage: emit_result <key> <value> — string result
+emit_result() {
+    emit_event "{\"type\":\"result\",\"key\":\"$(json_escape "$1")\",\"value\":\"$(json_escape "$2")\"}"
+}
+
+# Usage: emit_result_int <key> <value> — integer result (null if not an integer)
+emit_result_int() {
+    local value="null"
+    [[ "$2" =~ ^-?[0-9]+$ ]] && value="$2"
+    emit_event "{\"type\":\"result\",\"key\":\"$(json_escape "$1")\",\"value\":$value}"
+}
+
+# Usage: emit_finding <ok|info|warning|critical> <check_id> <message>
+emit_finding() {
+    case "$1" in
+        ok|info|warning|critical) ;;
+        *)
+            log_error "emit_finding: invalid severity '$1' for check '$2'"
+            return 1
+            ;;
+    esac
+    emit_event "{\"type\":\"finding\",\"severity\":\"$1\",\"check\":\"$(json_escape "$2")\",\"message\":\"$(json_escape "$3")\"}"
+}
+
+# Usage: emit_progress <current> <total> <message>
+emit_progress() {
+    emit_event "{\"type\":\"progress\",\"current\":$1,\"total\":$2,\"message\":\"$(json_escape "$3")\"}"
+}
+
 # --- Helper Functions ---
 
 error_exit() {
@@ -190,6 +296,17 @@ check_package_available() {
     pacman -Si "$1" >/dev/null 2>&1
 }
 
+# Returns 0 when installing from a local package source (OFFLINE_INSTALL=Yes).
+# Set by archtui after it verified every package against the local repository.
+is_offline_install() {
+    [[ "${OFFLINE_INSTALL:-No}" == "Yes" ]]
+}
+
+# Log a step left out because it needs the network
+log_offline_skip() {
+    log_info "Offline install: skipping $1"
+}
+
 # format_filesystem is defined in disk_utils.sh with full error handling
 
 # --- Initialization Functions ---
@@ -244,10 +361,11 @@ dump_config() {
         local var val
         for var in \
             INSTALL_DISK PARTITIONING_STRATEGY BOOT_MODE ENCRYPTION \
-            ROOT_FILESYSTEM HOME_FILESYSTEM SEPARATE_HOME SWAP SWAP_SIZE \
+            ROOT_FILESYSTEM HOME_FILESYSTEM SEPARATE_HOME SWAP SWAP_SIZE HIBERNATION \
             ROOT_SIZE HOME_SIZE RAID_LEVEL \
             LOCALE KEYMAP TIMEZONE_REGION TIMEZONE TIME_SYNC \
             MIRROR_COUNTRY KERNEL MULTILIB ADDITIONAL_PACKAGES GPU_DRIVERS \
+            PACSTRAP_PACKAGES GPU_PACKAGES \
             SYSTEM_HOSTNAME MAIN_USERNAME \
             AUR_HELPER ADDITIONAL_AUR_PACKAGES FLATPAK \
             BOOTLOADER OS_PROBER GRUB_THEME GRUB_THEME_SELECTION SECURE_BOOT \
@@ -255,6 +373,7 @@ dump_config() {
             PLYMOUTH PLYMOUTH_THEME NUMLOCK_ON_BOOT \
             GIT_REPOSITORY GIT_REPOSITORY_URL \
             BTRFS_SNAPSHOTS BTRFS_FREQUENCY BTRFS_KEEP_COUNT SNAPSHOT_TOOL \
+            OFFLINE_INSTALL OFFLINE_REPO_DIR OFFLINE_PKG_DIR \
             LOG_LEVEL \
             MAIN_USER_PASSWORD ROOT_PASSWORD ENCRYPTION_PASSWORD
         do
@@ -302,6 +421,13 @@ perform_preflight_checks() {
         log_warn "Not running on Arch Linux ISO — proceeding with caution"
     fi
 
+    # Offline installs read every package from the local repository
+    if is_offline_install; then
+        log_offline_skip "network connectivity check (packages from ${OFFLINE_PKG_DIR:-local repository})"
+        log_success "Pre-flight checks passed"
+        return 0
+    fi
+
     # Check that we have basic network connectivity (relaxed for older NICs)
     # Try multiple methods: curl (HTTPS) > ping (ICMP) > DNS resolve
     local net_ok=false
@@ -336,8 +462,11 @@ check_and_install_dependencies() {
         "arch-chroot"
         "genfstab"
         "pacstrap"
-        "reflector"
     )
+    # reflector only ranks mirrors, which offline installs never use
+    if ! is_offline_install; then
+        required_cmds+=("reflector")
+    fi
 
     for cmd in "${required_cmds[@]}"; do
         if ! command -v "$cmd" >/dev/null 2>&1; then
@@ -351,6 +480,10 @@ check_and_install_dependencies() {
     fi
 
     log_warn "Missing commands: ${missing_deps[*]}"
+    if is_offline_install; then
+        log_error "Cannot install missing commands during an offline install — use an ISO that provides them"
+        return 1
+    fi
     log_info "Attempting to install missing dependencies..."
 
     # Map commands to packages
@@ -14,6 +14,7 @@ pub use state::{
     ToolParam, ToolParameter,
 };
 
+use crate::capability::CapabilityScheduler;
 use crate::components::confirm_dialog::{
     ConfirmDialogState, ConfirmSeverity, start_install_confirm,
 };
@@ -24,9 +25,14 @@ use crate::config::Configuration;
 use crate::error;
 use crate::hardware::HardwareInfo;
 use crate::input::{InputHandler, InputType};
-use crate::installer::Installer;
+use crate::installer::{Installer, PackageCheck};
+use crate::logic::availability::check_install_packages;
+use crate::logic::dry_run::DryRunReport;
+use crate::logic::offline::{self, prepare_offline_install};
+use crate::logic::resolver::package_env_vars;
 use crate::process_guard::{ChildRegistry, CommandProcessGroup, ProcessGuard};
 use crate::script_manifest::ManifestRegistry;
+use crate::script_runner::{OutputStream, ScriptInvocation, run_invocation_streaming};
 use crate::script_traits::{ScriptArgs, is_dry_run, shell_safe};
 use crate::scripts::config::{GenFstabArgs, UserAddArgs};
 use crate::scripts::disk::{
@@ -43,11 +49,10 @@ use crate::scripts::profiles::{EnableServicesArgs, InstallDotfilesArgs};
 use crate::scripts::system::{BootloaderArgs, ChrootArgs, ServicesArgs, SystemInfoArgs};
 use crate::scripts::user::{GroupsArgs, ResetPasswordArgs, SecurityAuditArgs, SshArgs};
 use crate::scripts::user_ops::{InstallAurHelperArgs, UserRunArgs};
-use crate::types::{AurHelper, DesktopEnvironment};
+use crate::types::{AurHelper, DesktopEnvironment, SwapMode};
 use crate::ui::UiRenderer;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -95,6 +100,10 @@ pub struct App {
     hardware_info: HardwareInfo,
     /// Script manifest registry for validating tool executions
     manifest_registry: ManifestRegistry,
+    /// Capabilities provided this session; gates tools on their manifest `requires`
+    capabilities: CapabilityScheduler,
+    /// Script name and CLI args of the running tool, recorded on success
+    running_tool: Option<(String, Vec<String>)>,
     /// Active LUKS SecretFile kept alive during encryption operations
     _active_secret_file: Option<SecretFile>,
 }
@@ -154,17 +163,11 @@ impl App {
         debug!("ProcessGuard initialized for child process tracking");
 
         // Load script manifests for runtime validation
-        let mut manifest_registry = ManifestRegistry::with_core_manifests();
-        let manifest_dir = crate::script_runner::scripts_base_dir().join("manifests");
-        if let Err(e) = manifest_registry.load_from_directory(&manifest_dir) {
-            tracing::warn!(
-                "Failed to load manifests from {}: {}",
-                manifest_dir.display(),
-                e
-            );
-        } else {
-            info!("Script manifests loaded successfully");
-        }
+        let manifest_registry = ManifestRegistry::load_default();
+        info!(
+            count = manifest_registry.all().count(),
+            "Script manifests loaded"
+        );
 
         Self {
             state: Arc::new(Mutex::new(AppState::default())),
@@ -179,6 +182,8 @@ impl App {
             _process_guard: process_guard,
             hardware_info,
             manifest_registry,
+            capabilities: CapabilityScheduler::load_session(),
+            running_tool: None,
             _active_secret_file: None,
         }
     }
@@ -219,7 +224,7 @@ impl App {
         // Load and validate the config file
         match InstallationConfig::load_from_file(path) {
             Ok(config) => {
-                match config.validate() {
+                match config.validate_for_hardware(&self.hardware_info.inventory) {
                     Ok(_) => {
                         // Config is valid — show summary and confirm dialog
                         info!(path = %path.display(), "Configuration loaded and validated");
@@ -444,6 +449,8 @@ impl App {
     fn poll_tool_messages(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Track whether tool finished so we can clean up SecretFile after releasing state lock
         let mut should_clear_secret = false;
+        // Outcome of the running tool, applied to the capability set after the loop
+        let mut tool_succeeded = None;
 
         // Process all pending messages without blocking
         while let Ok(msg) = self.tool_rx.try_recv() {
@@ -468,6 +475,7 @@ impl App {
                     }
                 }
                 ToolMessage::Complete { success, exit_code } => {
+                    tool_succeeded = Some(success);
                     // Update status message first (before borrowing floating_output)
                     let status_msg = if success {
                         "Tool completed successfully".to_string()
@@ -502,6 +510,7 @@ impl App {
                     }
                 }
                 ToolMessage::Error(err) => {
+                    tool_succeeded = Some(false);
                     state.status_message = format!("Tool error: {}", err);
                     state.current_tool = None;
                     should_clear_secret = true;
@@ -521,6 +530,14 @@ impl App {
             self._active_secret_file = None;
         }
 
+        if let Some(success) = tool_succeeded
+            && let Some((script_name, cli_args)) = self.running_tool.take()
+            && success
+        {
+            self.capabilities
+                .record_success(&self.manifest_registry, &script_name, &cli_args);
+        }
+
         Ok(())
     }
 
@@ -1016,6 +1033,14 @@ impl App {
             return Ok(false);
         }
 
+        // E in the dry-run summary exports the report
+        if current_mode == AppMode::DryRunSummary
+            && matches!(key_event.code, KeyCode::Char('e') | KeyCode::Char('E'))
+        {
+            self.export_dry_run_report()?;
+            return Ok(false);
+        }
+
         // Handle Left/Right/Tab for button toggle in GuidedInstaller mode
         if current_mode == AppMode::GuidedInstaller
             && matches!(
@@ -1145,6 +1170,9 @@ impl App {
                 AppMode::DryRunSummary => {
                     state.dry_run_scroll_offset = state.dry_run_scroll_offset.saturating_sub(1);
                 }
+                AppMode::HardwareSummary => {
+                    state.hardware_scroll_offset = state.hardware_scroll_offset.saturating_sub(1);
+                }
                 _ => {}
             }
         }
@@ -1156,35 +1184,20 @@ impl App {
             let mut state = self.lock_state();
             match state.mode {
                 AppMode::MainMenu
-                    if state.main_menu_selection < 3 => {
-                        // 4 items total (0-3)
-                        state.main_menu_selection += 1;
-                    }
+                    if state.main_menu_selection + 1 < crate::ui::menus::MAIN_MENU_ITEMS.len() =>
+                {
+                    state.main_menu_selection += 1;
+                }
                 AppMode::ToolsMenu
-                    if state.tools_menu_selection < 4 => {
-                        // 5 items total (0-4)
-                        state.tools_menu_selection += 1;
-                    }
-                AppMode::DiskTools
-                    if state.tools_menu_selection < 6 => {
-                        // 7 items total (0-6)
-                        state.tools_menu_selection += 1;
-                    }
-                AppMode::SystemTools
-                    if state.tools_menu_selection < 9 => {
-                        // 10 items total (0-9)
-                        state.tools_menu_selection += 1;
-                    }
-                AppMode::UserTools
-                    if state.tools_menu_selection < 7 => {
-                        // 8 items total (0-7)
-                        state.tools_menu_selection += 1;
-                    }
-                AppMode::NetworkTools
-                    if state.tools_menu_selection < 5 => {
-                        // 6 items total (0-5)
-                        state.tools_menu_selection += 1;
-                    }
+                | AppMode::DiskTools
+                | AppMode::SystemTools
+                | AppMode::UserTools
+                | AppMode::NetworkTools
+                    if state.tools_menu_selection + 1
+                        < crate::ui::menus::menu_items(state.mode).len() =>
+                {
+                    state.tools_menu_selection += 1;
+                }
                 AppMode::ToolDialog => {
                     if let Some(ref mut dialog) = state.tool_dialog
                         && dialog.current_param < dialog.parameters.len().saturating_sub(1)
@@ -1215,6 +1228,14 @@ impl App {
                         }
                     }
                 }
+                AppMode::HardwareSummary => {
+                    if let Some(ref summary) = state.hardware_summary {
+                        let max = summary.len().saturating_sub(1);
+                        if state.hardware_scroll_offset < max {
+                            state.hardware_scroll_offset += 1;
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -1365,6 +1386,13 @@ impl App {
                 let mut state = self.lock_state();
                 state.set_mode(AppMode::GuidedInstaller);
                 state.dry_run_summary = None;
+                state.dry_run_report = None;
+            }
+            AppMode::HardwareSummary => {
+                // Dismiss hardware summary and return to main menu
+                let mut state = self.lock_state();
+                state.set_mode(AppMode::MainMenu);
+                state.hardware_summary = None;
             }
         }
 
@@ -1400,6 +1428,7 @@ impl App {
                             disk: PathBuf::from(parts[1]),
                             mode: String::new(),
                             efi_path: None,
+                            confirm: true,
                         };
                         self.execute_via_script_args(
                             sa.script_name(),
@@ -1489,7 +1518,12 @@ impl App {
                                 state.set_mode(AppMode::FloatingOutput);
                                 state.current_tool = Some(display_name);
                             }
-                            self.spawn_tool_script_with_env(&script_path, cli_args, env_vars)?;
+                            self.spawn_tool_script_with_env(
+                                &script_name,
+                                cli_args,
+                                env_vars,
+                                true,
+                            )?;
                         }
                         Err(e) => {
                             let mut state = self.lock_state();
@@ -1559,6 +1593,13 @@ impl App {
                     "Arch Linux Tools - System repair and administration".to_string();
             }
             3 => {
+                // Hardware Summary
+                state.hardware_summary = Some(self.hardware_info.summary_lines());
+                state.hardware_scroll_offset = 0;
+                state.set_mode(AppMode::HardwareSummary);
+                state.status_message = format!("Hardware: {}", self.hardware_info);
+            }
+            4 => {
                 // Quit
                 return Ok(true);
             }
@@ -1619,13 +1660,10 @@ impl App {
         };
 
         // Check if user selected "Back" option (last item in each menu)
-        let is_back_option = match current_mode {
-            AppMode::DiskTools => selection == 6, // 7 items (0-6), back is at index 6
-            AppMode::SystemTools => selection == 9, // 10 items (0-9), back is at index 9
-            AppMode::UserTools => selection == 7, // 8 items (0-7), back is at index 7
-            AppMode::NetworkTools => selection == 5, // 6 items (0-5), back is at index 5
-            _ => false,
-        };
+        let is_back_option = matches!(
+            current_mode,
+            AppMode::DiskTools | AppMode::SystemTools | AppMode::UserTools | AppMode::NetworkTools
+        ) && selection + 1 == crate::ui::menus::menu_items(current_mode).len();
 
         if is_back_option {
             // Go back to tools menu
@@ -1692,6 +1730,10 @@ impl App {
                         // LUKS Encryption - Create dialog
                         self.create_tool_dialog("encrypt_device")?;
                     }
+                    6 => {
+                        // Clean Up Previous Attempt - teardown of the recorded applied ops
+                        self.cleanup_previous_attempt()?;
+                    }
                     _ => {}
                 }
             }
@@ -2019,8 +2061,14 @@ impl App {
                 // Return to guided installer from dry-run summary
                 state.set_mode(AppMode::GuidedInstaller);
                 state.dry_run_summary = None;
+                state.dry_run_report = None;
                 state.status_message = "Dry-run complete - review your configuration".to_string();
             }
+            AppMode::HardwareSummary => {
+                state.set_mode(AppMode::MainMenu);
+                state.hardware_summary = None;
+                state.status_message = "Welcome to Arch Linux Toolkit".to_string();
+            }
         }
         Ok(())
     }
@@ -2037,10 +2085,24 @@ impl App {
             return false;
         }
 
+        // Check the layout fits the selected disk(s)
+        if self.disk_capacity_error(config).is_some() {
+            return false;
+        }
+
         // Then check secure boot requirements
         self.validate_secure_boot_requirements(config)
     }
 
+    /// Check the configured sizes against the selected disk(s), returning the
+    /// reason the layout cannot be installed.
+    fn disk_capacity_error(&self, config: &Configuration) -> Option<String> {
+        let install_config = crate::config_file::InstallationConfig::from(config);
+        crate::engine::capacity::check_disk_capacity(&install_config)
+            .err()
+            .map(|e| e.to_string())
+    }
+
     /// Check if the selected DE requires an AUR helper but none is configured
     fn de_requires_aur_helper(&self, config: &Configuration) -> bool {
         let de_value = config
@@ -2141,6 +2203,11 @@ impl App {
             ));
         }
 
+        // Add disk capacity validation errors
+        if let Some(err) = self.disk_capacity_error(config) {
+            errors.push(err);
+        }
+
         // Add secure boot validation errors
         if let Some(secure_boot_option) =
             config.options.iter().find(|opt| opt.name == "Secure Boot")
@@ -2221,7 +2288,8 @@ impl App {
     }
 
     /// Generate a dry-run summary from the current configuration and transition to DryRunSummary mode.
-    /// No scripts are executed — this is purely in-memory config inspection.
+    /// No scripts are executed — this is purely in-memory config inspection. The
+    /// summary ends with the full `DryRunReport` transcript, exportable with E.
     fn generate_test_config_summary(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let (config, is_valid, errors) = {
             let state = self.lock_state();
@@ -2324,14 +2392,92 @@ impl App {
             }
         }
 
+        // Everything a full install would execute, in order
+        let report = DryRunReport::build(
+            &crate::config_file::InstallationConfig::from(&config),
+            &self.hardware_info.inventory,
+        );
+        summary.push(String::new());
+        summary.extend(report.to_lines());
+
+        self.show_dry_run_summary(summary, report);
+        let mut state = self.lock_state();
+        state.status_message =
+            "Test Config - review your settings (B to go back, E to export)".to_string();
+        Ok(())
+    }
+
+    /// Enter DryRunSummary mode with `summary`, keeping `report` for export.
+    fn show_dry_run_summary(&mut self, summary: Vec<String>, report: DryRunReport) {
         let mut state = self.lock_state();
         state.dry_run_summary = Some(summary);
+        state.dry_run_report = Some(report);
         state.dry_run_scroll_offset = 0;
         state.set_mode(AppMode::DryRunSummary);
-        state.status_message = "Test Config - review your settings (B to go back)".to_string();
+    }
+
+    /// Save the dry-run report as text and JSON in the log directory.
+    fn export_dry_run_report(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(report) = self.lock_state().dry_run_report.clone() else {
+            return Ok(());
+        };
+        let stem = format!("dry-run-{}", crate::installer::now_hms().replace(':', ""));
+
+        match report.write_files(&crate::script_runner::log_dir(), &stem) {
+            Ok((text_path, json_path)) => {
+                info!(
+                    text = %text_path.display(),
+                    json = %json_path.display(),
+                    "Exported dry-run report"
+                );
+                let mut state = self.lock_state();
+                state.status_message = format!(
+                    "Dry-run report saved to {} and {}",
+                    text_path.display(),
+                    json_path.display()
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to export dry-run report: {}", e);
+                let mut state = self.lock_state();
+                state.status_message = format!("Export failed: {}", e);
+            }
+        }
         Ok(())
     }
 
+    /// Release mounts, VGs, LUKS mappers and RAID arrays left active by a
+    /// failed install: the teardown of the storage plan ops `apply-storage`
+    /// recorded as applied.
+    fn cleanup_previous_attempt(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let record =
+            crate::install_checkpoint::AppliedStorage::load(&crate::script_runner::log_dir());
+        let ops = match record {
+            Ok(Some(record)) => record.teardown(),
+            Ok(None) => Vec::new(),
+            Err(e) => {
+                let mut state = self.lock_state();
+                state.status_message = format!("Cannot read the applied storage ops: {}", e);
+                return Ok(());
+            }
+        };
+        if ops.is_empty() {
+            let mut state = self.lock_state();
+            state.status_message = "No applied storage plan ops recorded to clean up".to_string();
+            return Ok(());
+        }
+
+        let args = crate::scripts::storage::TeardownStorageArgs { ops, confirm: true };
+        self.execute_via_script_args(
+            args.script_name(),
+            args.to_cli_args(),
+            args.get_env_vars(),
+            "clean up previous attempt",
+            args.is_destructive(),
+            false,
+        )
+    }
+
     /// Export current configuration to a JSON file in the working directory
     fn export_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let file_config = {
@@ -2418,6 +2564,25 @@ impl App {
             }
         }
 
+        // Dry-run never spawns the installer: show what it would execute
+        if is_dry_run() {
+            return self.generate_test_config_summary();
+        }
+
+        // The guided installer downloads every package; offline installs are
+        // config-file only (`offline_source`), so re-check before refusing in
+        // case the network came up after startup
+        if !self.hardware_info.network.is_online() {
+            self.hardware_info.network = crate::hardware::detect_internet();
+            if !self.hardware_info.network.is_online() {
+                let mut state = self.lock_state();
+                state.status_message = "No network connection: for an offline install, load a \
+                    config file with offline_source or run `archtui install --offline DIR`"
+                    .to_string();
+                return Ok(());
+            }
+        }
+
         // Check if we need to save the config before starting
         if let Some(save_path) = &self.save_config_path {
             info!("Saving configuration to: {:?}", save_path);
@@ -2431,6 +2596,11 @@ impl App {
             state.status_message = format!("Config saved to {}", save_path.display());
         }
 
+        let file_config = {
+            let state = self.lock_state();
+            crate::config_file::InstallationConfig::from(&state.config)
+        };
+
         // Update state to installation mode
         {
             let mut state = self.lock_state();
@@ -2444,7 +2614,13 @@ impl App {
             state.config.clone()
         };
 
-        self.installer = Some(Installer::new(config, Arc::clone(&self.state)));
+        // pacstrap, GPU and microcode lists come from the resolver the pre-flight checked
+        let package_env = package_env_vars(&file_config, &self.hardware_info.inventory);
+        self.installer = Some(
+            Installer::new(config, Arc::clone(&self.state))
+                .with_env_vars(package_env)
+                .with_package_check(self.package_check(file_config)),
+        );
 
         // Start installation in background
         if let Some(ref mut installer) = self.installer {
@@ -2475,6 +2651,45 @@ impl App {
         info!(disk = %file_config.install_disk, hostname = %file_config.hostname,
               "Starting installation from file config");
 
+        // Dry-run never spawns the installer: show what it would execute
+        if is_dry_run() {
+            let report = DryRunReport::build(&file_config, &self.hardware_info.inventory);
+            self.show_dry_run_summary(report.to_lines(), report);
+            let mut state = self.lock_state();
+            state.status_message =
+                "[DRY RUN] Installer not started (B to go back, E to export)".to_string();
+            return Ok(());
+        }
+
+        // Backstop: recheck the fit against sysfs/lsblk right before the install
+        if let Err(e) = crate::engine::capacity::check_disk_capacity(&file_config) {
+            let mut state = self.lock_state();
+            state.status_message = format!("Layout does not fit the target disk: {}", e);
+            return Ok(());
+        }
+
+        // Offline installs must have every package locally before the disk is touched
+        let offline = !file_config.offline_source.trim().is_empty();
+        let offline_env = if !offline {
+            Vec::new()
+        } else {
+            let dir = std::path::Path::new(file_config.offline_source.trim());
+            match prepare_offline_install(dir, &file_config, &self.hardware_info.inventory) {
+                Ok(repo) => {
+                    for step in offline::skipped_steps(&file_config) {
+                        info!(step = %step, "Offline install: skipping");
+                    }
+                    repo.env_vars()
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Offline package source check failed");
+                    let mut state = self.lock_state();
+                    state.status_message = format!("Offline install not possible: {:#}", e);
+                    return Ok(());
+                }
+            }
+        };
+
         // Update state to installation mode
         {
             let mut state = self.lock_state();
@@ -2483,10 +2698,14 @@ impl App {
         }
 
         // Create installer from file config and start
-        self.installer = Some(Installer::from_file_config(
-            &file_config,
-            Arc::clone(&self.state),
-        ));
+        let package_env = package_env_vars(&file_config, &self.hardware_info.inventory);
+        let mut installer = Installer::from_file_config(&file_config, Arc::clone(&self.state))
+            .with_env_vars(package_env)
+            .with_env_vars(offline_env);
+        if !offline {
+            installer = installer.with_package_check(self.package_check(file_config));
+        }
+        self.installer = Some(installer);
 
         if let Some(ref mut installer) = self.installer {
             installer.start()?;
@@ -2495,6 +2714,14 @@ impl App {
         Ok(())
     }
 
+    /// Package availability pre-flight for `config`, run by the installer in
+    /// `InstallStage::ValidatingConfig` off the TUI thread. Typos and stale
+    /// package names fail there, not halfway through pacstrap.
+    fn package_check(&self, config: crate::config_file::InstallationConfig) -> PackageCheck {
+        let hardware = self.hardware_info.inventory.clone();
+        Box::new(move || check_install_packages(&config, &hardware))
+    }
+
     // =========================================================================
     // SECTION: Inline Config Editor (Redesigned TUI)
     // =========================================================================
@@ -2562,25 +2789,27 @@ impl App {
                     state.status_message = "Encryption is auto-set based on partitioning strategy. Use manual partitioning to control encryption.".to_string();
                 }
             }
-            "Swap Size" => {
-                let swap_enabled = {
+            "Swap Size" | "Hibernation" => {
+                let disk_swap = {
                     let state = self.lock_state();
                     state
                         .config
                         .options
                         .iter()
                         .find(|opt| opt.name == "Swap")
-                        .map(|opt| opt.get_value().to_lowercase() == "yes")
-                        .unwrap_or(false)
+                        .and_then(|opt| opt.get_value().parse::<SwapMode>().ok())
+                        .is_some_and(|mode| mode.has_disk_swap())
                 };
 
-                if swap_enabled {
+                if disk_swap {
                     let options = InputHandler::get_predefined_options(&option.name);
                     self.set_inline_selection(options, option.get_value());
                 } else {
                     let mut state = self.lock_state();
-                    state.status_message =
-                        "Swap size can only be configured when swap is enabled.".to_string();
+                    state.status_message = format!(
+                        "{} can only be configured with swap on disk (partition or swapfile).",
+                        option.name
+                    );
                 }
             }
             "Root Size" => {
@@ -3327,7 +3556,7 @@ impl App {
             self.set_inline_text_input(String::new());
             let mut state = self.lock_state();
             state.status_message = format!(
-                "Enter custom {} (e.g. 2048MB, 120GB, 2TB)",
+                "Enter custom {} (e.g. 2048MB, 120GB, 2TB, 40%)",
                 option_name.to_lowercase()
             );
             return Ok(());
@@ -3339,38 +3568,16 @@ impl App {
                 "30GB", "50GB", "80GB", "100GB", "150GB", "200GB", "500GB", "1TB",
             ];
             if !predefined.contains(&value.as_str()) {
-                let trimmed = value.trim();
-                // UTF-8 safe: find byte offset of first non-digit via char_indices
-                let digit_end = trimmed
-                    .char_indices()
-                    .find(|(_, c)| !c.is_ascii_digit())
-                    .map(|(i, _)| i)
-                    .unwrap_or(trimmed.len());
-                let digits = &trimmed[..digit_end];
-                let unit = trimmed[digit_end..].trim().to_uppercase();
-
-                if digits.is_empty() || digits.parse::<u64>().unwrap_or(0) == 0 {
-                    let mut state = self.lock_state();
-                    state.status_message =
-                        "Invalid size — enter a number with MB, GB, or TB (e.g. 2048MB, 120GB, 2TB)"
-                            .to_string();
-                    return Ok(());
-                }
-
-                let normalized_unit = match unit.as_str() {
-                    "M" | "MB" | "MIB" => "MB",
-                    "G" | "GB" | "" => "GB",
-                    "T" | "TB" => "TB",
+                let normalized = match value.parse::<crate::types::DiskSize>() {
+                    Ok(size) if !value.trim().is_empty() => size.to_string(),
                     _ => {
                         let mut state = self.lock_state();
                         state.status_message =
-                            "Invalid unit — use MB, GB, or TB (e.g. 2048MB, 120GB, 2TB)"
+                            "Invalid size — enter a number with MB, GB, or TB, or a percentage (e.g. 2048MB, 120GB, 2TB, 40%)"
                                 .to_string();
                         return Ok(());
                     }
                 };
-
-                let normalized = format!("{}{}", digits, normalized_unit);
                 // Apply normalized value directly instead of recursing
                 let value = normalized;
                 // Fall through to the config assignment below
@@ -3666,7 +3873,11 @@ impl App {
             .unwrap_or_default();
 
         let same = detected.same_disk_os();
-        if !same.is_empty() && strategy != "manual" && strategy != "pre_mounted" {
+        if !same.is_empty()
+            && strategy != "manual"
+            && strategy != "pre_mounted"
+            && strategy != "auto_alongside"
+        {
             let names: Vec<&str> = same.iter().map(|o| o.name.as_str()).collect();
             state.status_message = format!(
                 "WARNING: {} on target disk — auto strategy will DESTROY it! OS Prober auto-enabled.",
@@ -3843,26 +4054,27 @@ impl App {
             let mut state = self.lock_state();
             match option_name {
                 "Swap" => {
-                    if value.to_lowercase() == "no" {
-                        // Disable swap size when swap is disabled
-                        if let Some(swap_size_option) = state
-                            .config
-                            .options
-                            .iter_mut()
-                            .find(|opt| opt.name == "Swap Size")
-                        {
-                            swap_size_option.value = "N/A".to_string();
-                        }
-                    } else if value.to_lowercase() == "yes" {
-                        // Reset swap size to default when swap is enabled
-                        if let Some(swap_size_option) = state
-                            .config
-                            .options
-                            .iter_mut()
-                            .find(|opt| opt.name == "Swap Size")
-                        {
-                            swap_size_option.value = "2GB".to_string();
+                    let disk_swap = value
+                        .parse::<SwapMode>()
+                        .is_ok_and(|mode| mode.has_disk_swap());
+                    if !disk_swap {
+                        // Size and hibernation only apply to swap on disk
+                        for (name, off) in [("Swap Size", "N/A"), ("Hibernation", "No")] {
+                            if let Some(opt) =
+                                state.config.options.iter_mut().find(|o| o.name == name)
+                            {
+                                opt.value = off.to_string();
+                            }
                         }
+                    } else if let Some(swap_size_option) = state
+                        .config
+                        .options
+                        .iter_mut()
+                        .find(|opt| opt.name == "Swap Size")
+                        && swap_size_option.value == "N/A"
+                    {
+                        // Size disk swap from RAM unless the user picked a size
+                        swap_size_option.value = "Auto".to_string();
                     }
                 }
                 "Separate Home Partition" => {
@@ -3991,6 +4203,16 @@ impl App {
                                 opt.value = "N/A".to_string();
                             }
                         }
+                        // No swap partition slot on the members either
+                        if let Some(swap_opt) =
+                            state.config.options.iter_mut().find(|o| o.name == "Swap")
+                            && swap_opt.value == SwapMode::Partition.to_string()
+                        {
+                            swap_opt.value = SwapMode::Swapfile.to_string();
+                            state.status_message =
+                                "Swap partition not available for RAID without LVM — using a swapfile."
+                                    .to_string();
+                        }
                     }
                     // Set RAID Level based on whether strategy is RAID
                     if value.contains("raid") {
@@ -4039,6 +4261,7 @@ impl App {
                     // Warn when switching TO auto strategy with same-disk OS detected
                     if value != "manual"
                         && value != "pre_mounted"
+                        && value != "auto_alongside"
                         && let Some(ref detected) = state.detected_os
                     {
                         let same = detected.same_disk_os();
@@ -5283,108 +5506,47 @@ impl App {
         )
     }
 
-    /// Spawn a tool script in a background thread with real-time output streaming
-    /// and optional environment variables.
+    /// Run a tool script in a background thread with real-time output streaming.
     ///
-    /// # Process Lifecycle Management
+    /// Goes through `run_invocation_streaming`, the same path as CLI tools, so
+    /// manifest validation, timeouts and process lifecycle management apply:
     /// - Child runs in its own process group (allows clean termination of entire tree)
     /// - Child PID is registered with global ChildRegistry
     /// - On App drop or signal, all registered children receive SIGTERM
     fn spawn_tool_script_with_env(
-        &self,
-        script_path: &str,
+        &mut self,
+        script_name: &str,
         args: Vec<String>,
         env_vars: Vec<(String, String)>,
+        is_destructive: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let tx = self.tool_tx.clone();
-        let script_path = script_path.to_string();
+        let invocation = ScriptInvocation::new(script_name, args, env_vars, is_destructive)?;
 
-        thread::spawn(move || {
-            let mut cmd = Command::new("bash");
-            cmd.arg(&script_path)
-                .args(&args)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .stdin(Stdio::null()); // Non-interactive per lint rules
+        // Remember the invocation so a successful run can provide its capabilities
+        self.running_tool = Some((invocation.script_name.clone(), invocation.cli_args.clone()));
 
-            // Pass environment variables
-            for (key, val) in &env_vars {
-                cmd.env(key, val);
-            }
-
-            // Spawn the child process in its own process group
-            let child_result = cmd.in_new_process_group().spawn();
-
-            let mut child = match child_result {
-                Ok(c) => c,
-                Err(e) => {
-                    let _ = tx.send(ToolMessage::Error(format!("Failed to start script: {}", e)));
-                    return;
-                }
-            };
-
-            // Register child PID for lifecycle management
-            let child_pid = child.id();
-            if let Ok(mut registry) = ChildRegistry::global().lock() {
-                registry.register(child_pid);
-            }
-
-            // Stream stdout in a separate thread
-            let stdout_tx = tx.clone();
-            let stdout_handle = child.stdout.take().map(|stdout| {
-                thread::spawn(move || {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines().map_while(Result::ok) {
-                        if stdout_tx.send(ToolMessage::Stdout(line)).is_err() {
-                            break; // Receiver dropped
-                        }
-                    }
-                })
-            });
+        thread::spawn(move || {
+            let line_tx = tx.clone();
+            let result = run_invocation_streaming(
+                &invocation,
+                crate::script_runner::manifest_registry(),
+                &crate::script_runner::scripts_base_dir(),
+                |line| {
+                    let _ = line_tx.send(match line.stream {
+                        OutputStream::Stdout => ToolMessage::Stdout(line.line),
+                        OutputStream::Stderr => ToolMessage::Stderr(line.line),
+                    });
+                },
+            );
 
-            // Stream stderr in a separate thread
-            let stderr_tx = tx.clone();
-            let stderr_handle = child.stderr.take().map(|stderr| {
-                thread::spawn(move || {
-                    let reader = BufReader::new(stderr);
-                    for line in reader.lines().map_while(Result::ok) {
-                        if stderr_tx.send(ToolMessage::Stderr(line)).is_err() {
-                            break; // Receiver dropped
-                        }
-                    }
-                })
+            let _ = tx.send(match result {
+                Ok(output) => ToolMessage::Complete {
+                    success: output.success,
+                    exit_code: output.exit_code,
+                },
+                Err(e) => ToolMessage::Error(e.to_string()),
             });
-
-            // Wait for stdout/stderr threads to finish
-            if let Some(h) = stdout_handle {
-                let _ = h.join();
-            }
-            if let Some(h) = stderr_handle {
-                let _ = h.join();
-            }
-
-            // Wait for child process to complete
-            let result = child.wait();
-
-            // Unregister child PID (process has exited)
-            if let Ok(mut registry) = ChildRegistry::global().lock() {
-                registry.unregister(child_pid);
-            }
-
-            match result {
-                Ok(status) => {
-                    let _ = tx.send(ToolMessage::Complete {
-                        success: status.success(),
-                        exit_code: status.code(),
-                    });
-                }
-                Err(e) => {
-                    let _ = tx.send(ToolMessage::Error(format!(
-                        "Failed to wait for script: {}",
-                        e
-                    )));
-                }
-            }
         });
 
         Ok(())
@@ -5661,6 +5823,17 @@ impl App {
             return Ok(());
         }
 
+        // Capability check: explain which tool has to run first
+        if let Err(unavailable) =
+            self.capabilities
+                .check(&self.manifest_registry, script_name, &cli_args)
+        {
+            tracing::warn!(script = %script_name, "{}", unavailable);
+            let mut state = self.lock_state();
+            state.status_message = format!("Unavailable: {}", unavailable);
+            return Ok(());
+        }
+
         // If destructive and not already confirmed, show confirmation dialog
         if is_destructive && !skip_confirm {
             let severity = match script_name {
@@ -5752,7 +5925,7 @@ impl App {
         }
 
         // Spawn the tool in a background thread
-        self.spawn_tool_script_with_env(&script_path, cli_args, env_vars)?;
+        self.spawn_tool_script_with_env(script_name, cli_args, env_vars, is_destructive)?;
 
         Ok(())
     }
@@ -5955,6 +6128,7 @@ impl App {
                     filesystem: fs,
                     label: params.get(2).filter(|s| !s.is_empty()).cloned(),
                     force: false,
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6218,6 +6392,7 @@ impl App {
                         .filter(|s| !s.is_empty())
                         .cloned()
                         .unwrap_or_default(),
+                    confirm: true,
                 };
                 let mut cli_args = sa.to_cli_args();
                 if params.len() >= 5 && params[4] == "true" {
@@ -6235,6 +6410,7 @@ impl App {
             "generate_fstab" => {
                 let sa = GenFstabArgs {
                     root: PathBuf::from(params.first().cloned().unwrap_or_default()),
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6264,6 +6440,7 @@ impl App {
                     home_dir: None,
                     create_home: true,
                     sudo: params.len() >= 6 && params[5] == "true",
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6291,7 +6468,11 @@ impl App {
                         return Ok(());
                     }
                 };
-                let sa = ResetPasswordArgs { username, password };
+                let sa = ResetPasswordArgs {
+                    username,
+                    password,
+                    confirm: true,
+                };
                 self.execute_via_script_args(
                     sa.script_name(),
                     sa.to_cli_args(),
@@ -6373,6 +6554,7 @@ impl App {
                     action,
                     user: params.get(1).filter(|s| !s.is_empty()).cloned(),
                     group: params.get(2).filter(|s| !s.is_empty()).cloned(),
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6389,6 +6571,7 @@ impl App {
                     port: None,
                     enable_root_login: None,
                     enable_password_auth: None,
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6407,6 +6590,7 @@ impl App {
                     protocol: "tcp".to_string(),
                     allow: false,
                     deny: false,
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
@@ -6654,6 +6838,7 @@ impl App {
                     target_dir: None,
                     branch: params.get(2).filter(|s| !s.is_empty()).cloned(),
                     backup: params.get(3).map(|s| s == "true").unwrap_or(true),
+                    confirm: true,
                 };
                 self.execute_via_script_args(
                     sa.script_name(),
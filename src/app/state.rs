@@ -11,6 +11,8 @@ use crate::components::floating_window::FloatingOutputState;
 use crate::components::pty_terminal::PtyTerminalState;
 use crate::config::Configuration;
 use crate::config_file::InstallationConfig;
+use crate::install_state::InstallerContext;
+use crate::package_events::PackageProgress;
 use crate::scrolling::ScrollState;
 
 /// Tool parameter types for input dialogs
@@ -125,8 +127,10 @@ pub struct AppState {
     pub status_message: String,
     /// Installer output lines
     pub installer_output: Vec<String>,
-    /// Installation progress percentage
-    pub installation_progress: u8,
+    /// Installation stage state machine, driven by the installer's stage events
+    pub install_context: InstallerContext,
+    /// ALPM transaction progress, while `PackageManager` installs packages
+    pub package_progress: Option<PackageProgress>,
     /// Scroll offset for installer output (lines from top)
     pub installer_scroll_offset: usize,
     /// Whether installer output auto-scrolls to bottom
@@ -157,8 +161,14 @@ pub struct AppState {
     pub pre_dialog_mode: Option<AppMode>,
     /// Dry-run summary output
     pub dry_run_summary: Option<Vec<String>>,
+    /// Report behind the dry-run summary (for export)
+    pub dry_run_report: Option<crate::logic::dry_run::DryRunReport>,
     /// Scroll offset for dry-run summary display
     pub dry_run_scroll_offset: usize,
+    /// Hardware summary lines (filled when the screen is opened)
+    pub hardware_summary: Option<Vec<String>>,
+    /// Scroll offset for hardware summary display
+    pub hardware_scroll_offset: usize,
     /// Button selection in guided installer (0 = Test Config, 1 = Export Config, 2 = Start Install)
     pub installer_button_selection: usize,
     /// PID of the running installer process (for cancellation)
@@ -215,6 +225,8 @@ pub enum AppMode {
     ConfirmDialog,
     /// Dry-run summary display
     DryRunSummary,
+    /// Detected hardware display
+    HardwareSummary,
 }
 
 impl AppState {
@@ -237,7 +249,8 @@ impl Default for AppState {
             config,
             status_message: "Welcome to Arch Linux Toolkit".to_string(),
             installer_output: Vec::new(),
-            installation_progress: 0,
+            install_context: InstallerContext::new(),
+            package_progress: None,
             installer_scroll_offset: 0,
             installer_auto_scroll: true,
             installer_visible_height: 30,
@@ -253,7 +266,10 @@ impl Default for AppState {
             confirm_dialog: None,
             pre_dialog_mode: None,
             dry_run_summary: None,
+            dry_run_report: None,
             dry_run_scroll_offset: 0,
+            hardware_summary: None,
+            hardware_scroll_offset: 0,
             installer_button_selection: 2, // Default to "Start Install"
             installer_pid: None,
             log_level: std::env::var("ARCHTUI_LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string()),
@@ -297,6 +313,6 @@ mod tests {
         assert_eq!(state.mode, AppMode::MainMenu);
         assert!(!state.config.options.is_empty());
         assert!(state.installer_output.is_empty());
-        assert_eq!(state.installation_progress, 0);
+        assert_eq!(state.install_context.progress_percent(), 0);
     }
 }
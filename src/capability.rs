@@ -0,0 +1,604 @@
+//! Tool Script Capabilities
+//!
+//! Manifests declare which capabilities a tool script `requires` before it may
+//! run, which it `provides` once it exits successfully, and which a successful
+//! run `revokes` (e.g. unmounting the target). `CapabilityScheduler` tracks the
+//! capabilities provided so far and refuses tools whose requirements are not
+//! met, explaining which script has to run first.
+//!
+//! # Manifest Syntax
+//!
+//! ```json
+//! {
+//!   "script": "scripts/tools/mount_partitions.sh",
+//!   "provides": [{ "capability": "RootMounted", "actions": ["mount"] }],
+//!   "revokes": [{ "capability": "RootMounted", "actions": ["umount"] }]
+//! }
+//! ```
+//!
+//! A rule is either a bare capability name (applies to every invocation) or
+//! an object scoped to the values of the script's `--action` flag.
+//!
+//! # Sessions
+//!
+//! Provided capabilities are persisted to `<runtime dir>/capabilities.json`,
+//! so the TUI tools menu and separate CLI `tools` invocations on the same live
+//! system share one view. The runtime dir is tmpfs-backed and therefore
+//! cleared on reboot.
+//!
+//! Capabilities that can be observed directly are probed instead of trusted:
+//! `RootMounted` follows the live mount table, so a root mounted by hand (or
+//! by a previous install) counts, and a recorded entry whose mount is gone is
+//! dropped from the session file.
+
+// Library API - some helpers are only used by tests
+#![allow(dead_code)]
+
+use crate::script_manifest::ManifestRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name of the session capability set inside the runtime dir.
+pub const SESSION_FILE_NAME: &str = "capabilities.json";
+
+/// Where the target root is mounted; `RootMounted` is probed here.
+pub const TARGET_ROOT: &str = "/mnt";
+
+/// Mount table of the current process, used for probing.
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// Capabilities whose live state can be probed.
+const PROBED: [Capability; 1] = [Capability::RootMounted];
+
+/// A fact about the system that tool scripts establish for each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Capability {
+    /// The target disk's signatures or contents were wiped
+    DiskWiped,
+    /// The target disk's partition table was written
+    DiskPartitioned,
+    /// A partition was formatted with a filesystem
+    PartitionFormatted,
+    /// The target root filesystem is mounted (at `/mnt` by default)
+    RootMounted,
+    /// `/etc/fstab` was generated for the target
+    FstabGenerated,
+    /// A bootloader was installed into the target
+    BootloaderInstalled,
+}
+
+impl Capability {
+    /// Name as written in manifests.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DiskWiped => "DiskWiped",
+            Self::DiskPartitioned => "DiskPartitioned",
+            Self::PartitionFormatted => "PartitionFormatted",
+            Self::RootMounted => "RootMounted",
+            Self::FstabGenerated => "FstabGenerated",
+            Self::BootloaderInstalled => "BootloaderInstalled",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `requires`/`provides`/`revokes` entry in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RawCapabilityRule")]
+pub struct CapabilityRule {
+    /// The capability the rule is about
+    pub capability: Capability,
+    /// `--action` values the rule is limited to (empty = every invocation)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+}
+
+/// Manifest spelling of a rule: a bare name or a scoped object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCapabilityRule {
+    Always(Capability),
+    Scoped {
+        capability: Capability,
+        #[serde(default)]
+        actions: Vec<String>,
+    },
+}
+
+impl From<RawCapabilityRule> for CapabilityRule {
+    fn from(raw: RawCapabilityRule) -> Self {
+        match raw {
+            RawCapabilityRule::Always(capability) => capability.into(),
+            RawCapabilityRule::Scoped {
+                capability,
+                actions,
+            } => Self {
+                capability,
+                actions,
+            },
+        }
+    }
+}
+
+impl From<Capability> for CapabilityRule {
+    fn from(capability: Capability) -> Self {
+        Self {
+            capability,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl CapabilityRule {
+    /// Rule limited to invocations with `--action <action>`.
+    pub fn on_action(capability: Capability, action: impl Into<String>) -> Self {
+        Self {
+            capability,
+            actions: vec![action.into()],
+        }
+    }
+
+    /// Whether the rule applies to an invocation with the given `--action`.
+    pub fn applies_to(&self, action: Option<&str>) -> bool {
+        self.actions.is_empty() || action.is_some_and(|a| self.actions.iter().any(|x| x == a))
+    }
+}
+
+/// The value of `--action` in a tool invocation, if any.
+pub fn invocation_action(cli_args: &[String]) -> Option<&str> {
+    cli_args
+        .iter()
+        .position(|a| a == "--action")
+        .and_then(|i| cli_args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// A requirement that is not met yet, with the scripts that can provide it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingCapability {
+    /// The capability that has not been provided this session
+    pub capability: Capability,
+    /// Tool scripts whose manifests provide it
+    pub providers: Vec<String>,
+}
+
+impl fmt::Display for MissingCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.providers.is_empty() {
+            write!(f, "{}", self.capability)
+        } else {
+            write!(
+                f,
+                "{} (run {} first)",
+                self.capability,
+                self.providers.join(" or ")
+            )
+        }
+    }
+}
+
+/// Why a tool cannot run yet.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{script} is unavailable: requires {}", join_missing(.missing))]
+pub struct ToolUnavailable {
+    /// Script file name (e.g. `generate_fstab.sh`)
+    pub script: String,
+    /// Every unmet requirement
+    pub missing: Vec<MissingCapability>,
+}
+
+fn join_missing(missing: &[MissingCapability]) -> String {
+    missing
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// On-disk form of a session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionFile {
+    provided: BTreeSet<Capability>,
+}
+
+/// Resolve the runtime directory holding session state.
+/// Priority: ARCHTUI_RUNTIME_DIR env > /run/archtui (if writable) > $XDG_RUNTIME_DIR/archtui > temp dir
+pub fn runtime_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("ARCHTUI_RUNTIME_DIR") {
+        return PathBuf::from(dir);
+    }
+    let run = PathBuf::from("/run/archtui");
+    if fs::create_dir_all(&run).is_ok() {
+        let test_path = run.join(".write_test");
+        if fs::write(&test_path, b"").is_ok() {
+            let _ = fs::remove_file(&test_path);
+            return run;
+        }
+    }
+    if let Ok(xdg) = std::env::var("XDG_RUNTIME_DIR") {
+        return PathBuf::from(xdg).join("archtui");
+    }
+    // PID-suffixed for the same reason as script_runner::log_dir()
+    std::env::temp_dir().join(format!("archtui-run.{}", std::process::id()))
+}
+
+/// Whether `mountpoint` appears in a `/proc/<pid>/mountinfo` table.
+fn mountinfo_has_mountpoint(mountinfo: &str, mountpoint: &str) -> bool {
+    mountinfo
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(4))
+        .any(|mp| mp == mountpoint)
+}
+
+/// Tracks the capabilities provided this session and gates tool scripts on them.
+#[derive(Debug, Default)]
+pub struct CapabilityScheduler {
+    provided: BTreeSet<Capability>,
+    session_file: Option<PathBuf>,
+    /// Mount table probed for `RootMounted` (`None` = trust the record)
+    mountinfo: Option<PathBuf>,
+}
+
+impl CapabilityScheduler {
+    /// Scheduler that only lives in memory (nothing is persisted).
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Scheduler for the current session (`<runtime dir>/capabilities.json`),
+    /// probing the live mount table.
+    pub fn load_session() -> Self {
+        Self::with_session_file(runtime_dir().join(SESSION_FILE_NAME))
+            .with_mountinfo(MOUNTINFO_PATH)
+    }
+
+    /// Probe `RootMounted` in the mount table at `path`, dropping a stale
+    /// record of it from the session.
+    pub fn with_mountinfo(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mountinfo = Some(path.into());
+        let before = self.provided.clone();
+        self.sync_probed();
+        if self.provided != before {
+            tracing::info!(provided = ?self.provided, "Capabilities refreshed from live state");
+            self.save();
+        }
+        self
+    }
+
+    /// Scheduler backed by `path`; a missing or unreadable file starts empty.
+    pub fn with_session_file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let provided = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<SessionFile>(&content) {
+                Ok(session) => session.provided,
+                Err(e) => {
+                    tracing::warn!(error = %e, path = %path.display(), "Ignoring corrupt capability session");
+                    BTreeSet::new()
+                }
+            },
+            Err(_) => BTreeSet::new(),
+        };
+        Self {
+            provided,
+            session_file: Some(path),
+            mountinfo: None,
+        }
+    }
+
+    /// Whether `capability` holds: the live state where it can be probed,
+    /// otherwise whether it has been provided this session.
+    pub fn has(&self, capability: Capability) -> bool {
+        self.probe(capability)
+            .unwrap_or_else(|| self.provided.contains(&capability))
+    }
+
+    /// Observe `capability` on the live system, if it is observable.
+    fn probe(&self, capability: Capability) -> Option<bool> {
+        match capability {
+            Capability::RootMounted => {
+                let path = self.mountinfo.as_ref()?;
+                match fs::read_to_string(path) {
+                    Ok(table) => Some(mountinfo_has_mountpoint(&table, TARGET_ROOT)),
+                    Err(e) => {
+                        tracing::warn!(error = %e, path = %path.display(), "Cannot read mount table");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Overwrite the recorded state of every probed capability with the live one.
+    fn sync_probed(&mut self) {
+        for capability in PROBED {
+            match self.probe(capability) {
+                Some(true) => {
+                    self.provided.insert(capability);
+                }
+                Some(false) => {
+                    self.provided.remove(&capability);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Capabilities recorded so far, in declaration order.
+    pub fn provided(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.provided.iter().copied()
+    }
+
+    /// Check that every requirement of `script_name` (for this `--action`) is met.
+    ///
+    /// Scripts without a manifest pass; `run_script_safe` refuses them anyway.
+    pub fn check(
+        &self,
+        manifests: &ManifestRegistry,
+        script_name: &str,
+        cli_args: &[String],
+    ) -> Result<(), ToolUnavailable> {
+        let Some(manifest) = manifests.get(&manifest_key(script_name)) else {
+            return Ok(());
+        };
+        let action = invocation_action(cli_args);
+        let missing: Vec<MissingCapability> = manifest
+            .requires
+            .iter()
+            .filter(|rule| rule.applies_to(action) && !self.has(rule.capability))
+            .map(|rule| MissingCapability {
+                capability: rule.capability,
+                providers: providers_of(manifests, rule.capability),
+            })
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolUnavailable {
+                script: script_name.to_string(),
+                missing,
+            })
+        }
+    }
+
+    /// Apply the `provides`/`revokes` rules of a successful run and persist them.
+    pub fn record_success(
+        &mut self,
+        manifests: &ManifestRegistry,
+        script_name: &str,
+        cli_args: &[String],
+    ) {
+        let Some(manifest) = manifests.get(&manifest_key(script_name)) else {
+            return;
+        };
+        let action = invocation_action(cli_args);
+        let before = self.provided.clone();
+        for rule in manifest.revokes.iter().filter(|r| r.applies_to(action)) {
+            self.provided.remove(&rule.capability);
+        }
+        for rule in manifest.provides.iter().filter(|r| r.applies_to(action)) {
+            self.provided.insert(rule.capability);
+        }
+        // The live state wins over what the manifest says a run provides
+        self.sync_probed();
+        if self.provided != before {
+            tracing::info!(script = %script_name, provided = ?self.provided, "Capabilities updated");
+            self.save();
+        }
+    }
+
+    /// Write the session file; failures only cost persistence, so they are logged.
+    fn save(&self) {
+        let Some(ref path) = self.session_file else {
+            return;
+        };
+        if let Err(e) = write_session(path, &self.provided) {
+            tracing::warn!(error = %e, path = %path.display(), "Failed to save capability session");
+        }
+    }
+}
+
+fn write_session(path: &Path, provided: &BTreeSet<Capability>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&SessionFile {
+        provided: provided.clone(),
+    })
+    .map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)
+}
+
+fn manifest_key(script_name: &str) -> String {
+    format!("scripts/tools/{}", script_name)
+}
+
+/// File names of the tool scripts whose manifests provide `capability`.
+fn providers_of(manifests: &ManifestRegistry, capability: Capability) -> Vec<String> {
+    let mut providers: Vec<String> = manifests
+        .all()
+        .filter(|m| m.provides.iter().any(|r| r.capability == capability))
+        .filter_map(|m| m.script.strip_prefix("scripts/tools/"))
+        .map(str::to_string)
+        .collect();
+    providers.sort();
+    providers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script_manifest::ScriptManifest;
+    use tempfile::TempDir;
+
+    fn registry() -> ManifestRegistry {
+        let mut registry = ManifestRegistry::new();
+        registry.register(
+            ScriptManifest::builder("scripts/tools/mount_partitions.sh", "Mount")
+                .provides(CapabilityRule::on_action(Capability::RootMounted, "mount"))
+                .revokes(CapabilityRule::on_action(Capability::RootMounted, "umount"))
+                .build()
+                .unwrap(),
+        );
+        registry.register(
+            ScriptManifest::builder("scripts/tools/generate_fstab.sh", "Fstab")
+                .requires(Capability::RootMounted)
+                .provides(Capability::FstabGenerated)
+                .build()
+                .unwrap(),
+        );
+        registry
+    }
+
+    fn mount_action(
+        scheduler: &mut CapabilityScheduler,
+        manifests: &ManifestRegistry,
+        action: &str,
+    ) {
+        let cli_args = vec!["--action".to_string(), action.to_string()];
+        scheduler.record_success(manifests, "mount_partitions.sh", &cli_args);
+    }
+
+    #[test]
+    fn test_rule_parses_bare_and_scoped_forms() {
+        let rules: Vec<CapabilityRule> = serde_json::from_str(
+            r#"["RootMounted", {"capability": "DiskWiped", "actions": ["quick"]}]"#,
+        )
+        .unwrap();
+        assert_eq!(rules[0], CapabilityRule::from(Capability::RootMounted));
+        assert_eq!(
+            rules[1],
+            CapabilityRule::on_action(Capability::DiskWiped, "quick")
+        );
+        assert!(serde_json::from_str::<CapabilityRule>(r#""RootMountd""#).is_err());
+    }
+
+    #[test]
+    fn test_fstab_blocked_until_root_mounted() {
+        let manifests = registry();
+        let mut scheduler = CapabilityScheduler::in_memory();
+
+        let err = scheduler
+            .check(&manifests, "generate_fstab.sh", &[])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "generate_fstab.sh is unavailable: requires RootMounted (run mount_partitions.sh first)"
+        );
+
+        // Listing mounts does not provide anything
+        mount_action(&mut scheduler, &manifests, "list");
+        assert!(!scheduler.has(Capability::RootMounted));
+
+        mount_action(&mut scheduler, &manifests, "mount");
+        assert!(
+            scheduler
+                .check(&manifests, "generate_fstab.sh", &[])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_unmount_revokes_root_mounted() {
+        let manifests = registry();
+        let mut scheduler = CapabilityScheduler::in_memory();
+        mount_action(&mut scheduler, &manifests, "mount");
+        mount_action(&mut scheduler, &manifests, "umount");
+        assert!(!scheduler.has(Capability::RootMounted));
+        assert!(
+            scheduler
+                .check(&manifests, "generate_fstab.sh", &[])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_session_file_is_shared() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SESSION_FILE_NAME);
+        let manifests = registry();
+
+        let mut first = CapabilityScheduler::with_session_file(&path);
+        mount_action(&mut first, &manifests, "mount");
+
+        let second = CapabilityScheduler::with_session_file(&path);
+        assert!(second.has(Capability::RootMounted));
+    }
+
+    #[test]
+    fn test_corrupt_session_starts_empty() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SESSION_FILE_NAME);
+        fs::write(&path, "not json").unwrap();
+        let scheduler = CapabilityScheduler::with_session_file(&path);
+        assert_eq!(scheduler.provided().count(), 0);
+    }
+
+    #[test]
+    fn test_root_mounted_follows_mount_table() {
+        let dir = TempDir::new().unwrap();
+        let session = dir.path().join(SESSION_FILE_NAME);
+        let mountinfo = dir.path().join("mountinfo");
+        let manifests = registry();
+        let root_line = "22 1 8:2 / /mnt rw,relatime shared:1 - ext4 /dev/sda2 rw\n";
+        let live_line = "21 1 0:19 / / rw,relatime shared:2 - overlay airootfs rw\n";
+
+        // Mounted by hand: fstab may run without mount_partitions.sh
+        fs::write(&mountinfo, format!("{}{}", live_line, root_line)).unwrap();
+        let scheduler = CapabilityScheduler::with_session_file(&session).with_mountinfo(&mountinfo);
+        assert!(
+            scheduler
+                .check(&manifests, "generate_fstab.sh", &[])
+                .is_ok()
+        );
+
+        // Unmounted since: the stale record is dropped from the session file
+        fs::write(&mountinfo, live_line).unwrap();
+        let scheduler = CapabilityScheduler::with_session_file(&session).with_mountinfo(&mountinfo);
+        assert!(!scheduler.has(Capability::RootMounted));
+        assert!(
+            scheduler
+                .check(&manifests, "generate_fstab.sh", &[])
+                .is_err()
+        );
+        let reloaded = CapabilityScheduler::with_session_file(&session);
+        assert_eq!(reloaded.provided().count(), 0);
+    }
+
+    #[test]
+    fn test_mountinfo_matches_exact_mountpoint() {
+        let table = "30 1 8:3 / /mnt/boot rw - vfat /dev/sda1 rw\n";
+        assert!(!mountinfo_has_mountpoint(table, TARGET_ROOT));
+        assert!(mountinfo_has_mountpoint(table, "/mnt/boot"));
+    }
+
+    // test: every capability a shipped manifest requires has a shipped provider
+    #[test]
+    fn test_shipped_requirements_have_providers() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts/manifests");
+        let mut manifests = ManifestRegistry::with_core_manifests();
+        manifests.load_from_directory(root).unwrap();
+
+        for manifest in manifests.all() {
+            for rule in &manifest.requires {
+                assert!(
+                    !providers_of(&manifests, rule.capability).is_empty(),
+                    "{} requires {} but nothing provides it",
+                    manifest.script,
+                    rule.capability
+                );
+            }
+        }
+    }
+}
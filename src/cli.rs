@@ -11,7 +11,9 @@ pub struct Cli {
     ///
     /// In this mode, destructive operations (wipe, format, install) are
     /// skipped and logged. Non-destructive operations (lsblk, system_info)
-    /// still execute so the preview is realistic.
+    /// still execute so the preview is realistic. `install --config` prints
+    /// the full transcript (storage plan, packages, services, bootloader)
+    /// instead of starting the installer.
     #[arg(long, global = true)]
     pub dry_run: bool,
 
@@ -24,6 +26,15 @@ pub struct Cli {
     #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
 
+    /// Print tool results as a single JSON document on stdout.
+    ///
+    /// Applies to `tools` commands. Script output is echoed to stderr, and
+    /// stdout carries `{"version", "tool", "success", "exit_code", "dry_run",
+    /// "report", "events"}` built from the script's structured events. With
+    /// `install --config ... --dry-run`, prints the dry-run report as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -39,11 +50,82 @@ pub enum Commands {
         /// Save current configuration to file and exit (after TUI configuration)
         #[arg(long)]
         save_config: Option<PathBuf>,
+
+        /// Resume a failed installation from its last checkpoint.
+        ///
+        /// Re-enters the pipeline at the failed stage without repeating
+        /// partitioning. The target must still be mounted at /mnt and the
+        /// configuration must match the one the checkpoint was written for.
+        #[arg(long, requires = "config")]
+        resume: bool,
+
+        /// Install from a local pacman repository or package cache instead
+        /// of the mirrors (overrides `offline_source` in the config).
+        ///
+        /// Every package is checked against the local databases before the
+        /// disk is touched. Mirror ranking, AUR packages and dotfiles are skipped.
+        #[arg(long, requires = "config", value_name = "DIR")]
+        offline: Option<PathBuf>,
     },
     /// Validate a configuration file
     Validate {
         /// Path to configuration file to validate
         config: PathBuf,
+
+        /// Print the effective config after merging `extends` layers, with
+        /// the file each value came from (JSON with --json)
+        #[arg(long)]
+        show_merged: bool,
+
+        /// Reject fields the config does not know (typos, removed options)
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Run the storage pipeline against a loop-backed image file (requires root)
+    ///
+    /// Partitions, formats and mounts a sparse image exactly as the installer
+    /// would a real disk, generates fstab, prints the result and tears it all
+    /// down again. install_disk in the config is ignored.
+    Sandbox {
+        /// Path to configuration file to test
+        #[arg(short, long)]
+        config: PathBuf,
+
+        /// Image file to create (must not exist)
+        #[arg(long, default_value = "/var/tmp/archtui-sandbox.img")]
+        image: PathBuf,
+
+        /// Image size in MiB (sparse)
+        #[arg(long, default_value_t = 16384)]
+        size: u64,
+
+        /// Install the base system from this local package cache (skipped if omitted)
+        #[arg(long)]
+        package_cache: Option<PathBuf>,
+
+        /// Keep the image file after the run
+        #[arg(long)]
+        keep: bool,
+    },
+    /// Execute a storage plan written by the installer (used by install.sh)
+    ///
+    /// Runs every op of the plan through the storage executor and writes the
+    /// resulting device roles as a sourceable bash file. The LUKS passphrase
+    /// is read from ENCRYPTION_PASSWORD.
+    #[command(hide = true)]
+    ApplyStorage {
+        /// Storage plan JSON (`storage-plan.json` in the log dir)
+        #[arg(long)]
+        plan: PathBuf,
+
+        /// Bash file to write the device roles to
+        #[arg(long)]
+        state: PathBuf,
+    },
+    /// Configuration file utilities
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
     },
     /// Arch Linux Tools - System administration and repair
     Tools {
@@ -52,6 +134,40 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Convert a config file between JSON, TOML and YAML (chosen by extension)
+    ///
+    /// Writes the effective config: `extends` layers are flattened, defaults
+    /// are filled in and passwords are left out.
+    Convert {
+        /// Config file to read
+        input: PathBuf,
+
+        /// File to write (.json, .toml, .yaml or .yml)
+        output: PathBuf,
+
+        /// Overwrite the output file if it exists
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Upgrade a config file to the current config_version
+    ///
+    /// Only the given file is upgraded, not the bases it extends. Prints what
+    /// would change; --write rewrites the file in its own format (comments in
+    /// TOML/YAML files are not preserved).
+    Migrate {
+        /// Config file to upgrade
+        config: PathBuf,
+
+        /// Rewrite the file instead of only reporting
+        #[arg(long)]
+        write: bool,
+    },
+    /// Print the JSON Schema for config files
+    Schema,
+}
+
 #[derive(Subcommand)]
 pub enum ToolCommands {
     /// Disk and filesystem tools
@@ -415,6 +531,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_install_resume_requires_config() {
+        let result = Cli::try_parse_from([
+            "archtui",
+            "install",
+            "--config",
+            "/path/to/config.json",
+            "--resume",
+        ]);
+        // SAFETY: asserted by unwrap in test
+        match result.unwrap().command {
+            Some(Commands::Install { resume, .. }) => assert!(resume),
+            _ => panic!("Expected Install command"),
+        }
+
+        let result = Cli::try_parse_from(["archtui", "install", "--resume"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_install_offline_requires_config() {
+        let result = Cli::try_parse_from([
+            "archtui",
+            "install",
+            "--config",
+            "/path/to/config.json",
+            "--offline",
+            "/var/cache/pacman/pkg",
+        ]);
+        // SAFETY: asserted by unwrap in test
+        match result.unwrap().command {
+            Some(Commands::Install { offline, .. }) => {
+                assert_eq!(offline, Some(PathBuf::from("/var/cache/pacman/pkg")));
+            }
+            _ => panic!("Expected Install command"),
+        }
+
+        let result = Cli::try_parse_from(["archtui", "install", "--offline", "/srv/repo"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_cli_validate_command() {
         let result = Cli::try_parse_from(["archtui", "validate", "/path/to/config.json"]);
@@ -422,11 +579,78 @@ mod tests {
         // SAFETY: asserted Ok above
         let cli = result.unwrap();
         match cli.command {
-            Some(Commands::Validate { config }) => {
+            Some(Commands::Validate {
+                config,
+                show_merged,
+                strict,
+            }) => {
                 assert_eq!(config.to_str().unwrap(), "/path/to/config.json");
+                assert!(!show_merged);
+                assert!(!strict);
             }
             _ => panic!("Expected Validate command"),
         }
+
+        let cli = Cli::try_parse_from(["archtui", "validate", "host.json", "--show-merged"])
+            .expect("valid args"); // test: known-good input
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Validate {
+                show_merged: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_config_convert_command() {
+        let cli = Cli::try_parse_from(["archtui", "config", "convert", "host.json", "host.toml"])
+            .expect("valid args"); // test: known-good input
+        match cli.command {
+            Some(Commands::Config {
+                action:
+                    ConfigCommands::Convert {
+                        input,
+                        output,
+                        force,
+                    },
+            }) => {
+                assert_eq!(input, PathBuf::from("host.json"));
+                assert_eq!(output, PathBuf::from("host.toml"));
+                assert!(!force);
+            }
+            _ => panic!("Expected Config convert command"),
+        }
+
+        let result = Cli::try_parse_from(["archtui", "config", "convert", "host.json"]);
+        assert!(result.is_err(), "output path is required");
+    }
+
+    #[test]
+    fn test_cli_config_migrate_and_schema_commands() {
+        let cli = Cli::try_parse_from(["archtui", "config", "migrate", "old.json", "--write"])
+            .expect("valid args"); // test: known-good input
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Config {
+                action: ConfigCommands::Migrate { write: true, .. }
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["archtui", "config", "schema"]).expect("valid args"); // test: known-good input
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Config {
+                action: ConfigCommands::Schema
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["archtui", "validate", "host.json", "--strict"])
+            .expect("valid args"); // test: known-good input
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Validate { strict: true, .. })
+        ));
     }
 
     #[test]
@@ -472,4 +696,15 @@ mod tests {
             Cli::try_parse_from(["archtui", "tools", "network", "test", "--action", "ping"]);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_cli_json_flag_is_global() {
+        let cli = Cli::try_parse_from(["archtui", "tools", "system", "info", "--json"])
+            .expect("--json after the subcommand"); // test: known-good input
+        assert!(cli.json);
+
+        let cli = Cli::try_parse_from(["archtui", "--json", "tools", "system", "info"])
+            .expect("--json before the subcommand"); // test: known-good input
+        assert!(cli.json);
+    }
 }
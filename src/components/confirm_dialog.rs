@@ -257,6 +257,7 @@ pub fn bootloader_confirm(bootloader: &str, disk: &str) -> ConfirmDialogState {
 ///
 /// When OS detection results are available, adds context-aware details:
 /// - Same-disk OS with auto strategy → Danger severity, "WILL DESTROY" warnings
+/// - Same-disk OS with auto_alongside → informational "Preserved" notes
 /// - Other-disk OS → informational "Preserved" notes
 pub fn start_install_confirm(
     detected_os: Option<&crate::hardware::OsDetectionResults>,
@@ -274,7 +275,14 @@ pub fn start_install_confirm(
 
     if let Some(detected) = detected_os {
         let same = detected.same_disk_os();
-        if !same.is_empty() && strategy != "manual" {
+        if strategy == "auto_alongside" {
+            for os in &same {
+                dialog = dialog.with_detail(&format!(
+                    "Preserved (same disk): {} on {}",
+                    os.name, os.device
+                ));
+            }
+        } else if !same.is_empty() && strategy != "manual" {
             dialog.severity = ConfirmSeverity::Danger;
             for os in &same {
                 dialog = dialog.with_detail(&format!(
@@ -29,6 +29,7 @@ pub enum KeyAction {
     ScrollDown,
     Dismiss,
     ExitTerminal,
+    Export,
 }
 
 /// A keybinding definition
@@ -247,6 +248,29 @@ impl KeybindingContext {
             ],
         );
 
+        // Dry Run Summary
+        self.mode_bindings.insert(
+            AppMode::DryRunSummary,
+            vec![
+                Keybinding::new(KeyCode::Up, KeyAction::ScrollUp, "Up", "Scroll up"),
+                Keybinding::new(KeyCode::Down, KeyAction::ScrollDown, "Down", "Scroll down"),
+                Keybinding::new(KeyCode::Char('e'), KeyAction::Export, "E", "Export report"),
+                Keybinding::new(KeyCode::Char('b'), KeyAction::Back, "B", "Back"),
+                Keybinding::new(KeyCode::Enter, KeyAction::Dismiss, "Enter", "Dismiss"),
+            ],
+        );
+
+        // Hardware Summary
+        self.mode_bindings.insert(
+            AppMode::HardwareSummary,
+            vec![
+                Keybinding::new(KeyCode::Up, KeyAction::ScrollUp, "Up", "Scroll up"),
+                Keybinding::new(KeyCode::Down, KeyAction::ScrollDown, "Down", "Scroll down"),
+                Keybinding::new(KeyCode::Char('b'), KeyAction::Back, "B", "Back"),
+                Keybinding::new(KeyCode::Enter, KeyAction::Dismiss, "Enter", "Dismiss"),
+            ],
+        );
+
         // Confirm Dialog
         self.mode_bindings.insert(
             AppMode::ConfirmDialog,
@@ -343,6 +367,13 @@ impl KeybindingContext {
                 vec![KeyAction::Toggle, KeyAction::Confirm, KeyAction::Cancel]
             }
             AppMode::DryRunSummary => vec![
+                KeyAction::ScrollUp,
+                KeyAction::ScrollDown,
+                KeyAction::Export,
+                KeyAction::Back,
+                KeyAction::Dismiss,
+            ],
+            AppMode::HardwareSummary => vec![
                 KeyAction::ScrollUp,
                 KeyAction::ScrollDown,
                 KeyAction::Back,
@@ -437,6 +468,7 @@ impl KeybindingContext {
                         | KeyAction::Toggle
                         | KeyAction::Dismiss
                         | KeyAction::ExitTerminal
+                        | KeyAction::Export
                 )
             })
             .collect();
@@ -192,8 +192,19 @@ impl Default for Configuration {
                     "No",
                 ),
                 ConfigOption::new("Home Filesystem", false, "Home partition filesystem", "N/A"),
-                ConfigOption::new("Swap", false, "Enable swap partition", "No"),
-                ConfigOption::new("Swap Size", false, "Swap partition size", "N/A"),
+                ConfigOption::new(
+                    "Swap",
+                    false,
+                    "Swap mode (none/partition/swapfile/zram/zram+swapfile)",
+                    "none",
+                ),
+                ConfigOption::new("Swap Size", false, "Disk swap size", "N/A"),
+                ConfigOption::new(
+                    "Hibernation",
+                    false,
+                    "Resume from disk swap (suspend-to-disk)",
+                    "No",
+                ),
                 ConfigOption::new("Root Size", false, "Root partition size", "N/A"),
                 ConfigOption::new("Home Size", false, "Home partition size", "N/A"),
                 ConfigOption::new("Btrfs Snapshots", false, "Enable Btrfs snapshots", "No"),
@@ -349,6 +360,7 @@ impl Configuration {
                 "Home Filesystem" => "HOME_FILESYSTEM",
                 "Swap" => "SWAP",
                 "Swap Size" => "SWAP_SIZE",
+                "Hibernation" => "HIBERNATION",
                 "Root Size" => "ROOT_SIZE",
                 "Home Size" => "HOME_SIZE",
                 "Btrfs Snapshots" => "BTRFS_SNAPSHOTS",
@@ -2,22 +2,35 @@
 //!
 //! This module uses type-safe enums instead of strings for configuration values,
 //! providing compile-time validation and preventing typos.
+//!
+//! Config files may be JSON, TOML or YAML; the format is chosen by extension
+//! (`.toml`, `.yaml`/`.yml`, anything else is JSON). All three go through the
+//! same serde derive, so `#[serde(default)]` behaves identically. Renamed
+//! fields are handled by `config_migrations`, not serde aliases.
 
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+use crate::config_migrations::CURRENT_VERSION;
+use crate::scripts::disk::PartitionType;
 use crate::types::{
     AurHelper, AutoToggle, BootMode, Bootloader, DeVariant, DesktopEnvironment, DisplayManager,
     Editor, EncryptionKeyType, Filesystem, GpuDriver, GrubTheme, Kernel, NetworkManager,
-    PartitionScheme, PlymouthTheme, SnapshotFrequency, SnapshotTool, Toggle,
+    PartitionScheme, PlymouthTheme, SnapshotFrequency, SnapshotTool, SwapMode, Toggle,
 };
 
 /// Installation configuration that can be saved/loaded
 /// NOTE: Debug impl redacts password fields
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InstallationConfig {
+    // Schema version; older files are upgraded by `config_migrations` on load
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
+
     // Boot & System
     pub boot_mode: BootMode,
     pub secure_boot: Toggle,
@@ -32,18 +45,29 @@ pub struct InstallationConfig {
     pub separate_home: Toggle,
     pub encryption: AutoToggle,
     pub encryption_password: String, // LUKS passphrase (if encryption enabled)
-    pub swap: Toggle,
-    pub swap_size: String, // Size like "2GB" - flexible format
+    pub swap: SwapMode,
+    pub swap_size: String, // Size like "2GB", or "Auto" to size from RAM
+    #[serde(default)]
+    pub hibernation: Toggle, // Configure resume from disk swap
     #[serde(default = "default_root_size")]
     pub root_size: String, // Size like "50GB" or "Remaining"
     #[serde(default = "default_home_size")]
     pub home_size: String, // Size like "100GB" or "Remaining"
+    #[serde(default)]
+    pub partition_layout: Vec<LayoutPartition>, // Declarative layout (manual strategy only)
+    #[serde(default)]
+    pub alongside_esp: String, // Existing ESP to reuse (auto_alongside only)
+    #[serde(default)]
+    pub shrink_partition: String, // Partition to shrink for free space (auto_alongside only)
+    #[serde(default)]
+    pub shrink_size: String, // New size of shrink_partition, e.g. "200GB"
 
     // Btrfs options
     pub btrfs_snapshots: Toggle,
     pub btrfs_frequency: SnapshotFrequency,
     pub btrfs_keep_count: u8,
-    #[serde(alias = "btrfs_assistant")]
+    #[serde(default = "default_btrfs_subvolumes")]
+    pub btrfs_subvolumes: Vec<BtrfsSubvolume>, // Created and mounted when root is Btrfs
     pub snapshot_tool: SnapshotTool,
 
     // Locale & Time
@@ -56,6 +80,8 @@ pub struct InstallationConfig {
     // Network & Mirrors
     pub mirror_country: String, // Too many options for enum
     pub hostname: String,       // User-defined
+    #[serde(default)]
+    pub offline_source: String, // Local pacman repo or package cache; empty = use mirrors
 
     // User accounts
     pub username: String,      // User-defined
@@ -74,7 +100,6 @@ pub struct InstallationConfig {
     // Boot configuration
     pub bootloader: Bootloader,
     pub os_prober: Toggle,
-    #[serde(alias = "grub_themes")]
     pub grub_theme: Toggle,
     pub grub_theme_selection: GrubTheme,
 
@@ -120,6 +145,7 @@ pub struct InstallationConfig {
 impl std::fmt::Debug for InstallationConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InstallationConfig")
+            .field("config_version", &self.config_version)
             .field("boot_mode", &self.boot_mode)
             .field("secure_boot", &self.secure_boot)
             .field("install_disk", &self.install_disk)
@@ -132,11 +158,17 @@ impl std::fmt::Debug for InstallationConfig {
             .field("encryption_password", &"********")
             .field("swap", &self.swap)
             .field("swap_size", &self.swap_size)
+            .field("hibernation", &self.hibernation)
             .field("root_size", &self.root_size)
             .field("home_size", &self.home_size)
+            .field("partition_layout", &self.partition_layout)
+            .field("alongside_esp", &self.alongside_esp)
+            .field("shrink_partition", &self.shrink_partition)
+            .field("shrink_size", &self.shrink_size)
             .field("btrfs_snapshots", &self.btrfs_snapshots)
             .field("btrfs_frequency", &self.btrfs_frequency)
             .field("btrfs_keep_count", &self.btrfs_keep_count)
+            .field("btrfs_subvolumes", &self.btrfs_subvolumes)
             .field("snapshot_tool", &self.snapshot_tool)
             .field("timezone_region", &self.timezone_region)
             .field("timezone", &self.timezone)
@@ -145,6 +177,7 @@ impl std::fmt::Debug for InstallationConfig {
             .field("time_sync", &self.time_sync)
             .field("mirror_country", &self.mirror_country)
             .field("hostname", &self.hostname)
+            .field("offline_source", &self.offline_source)
             .field("username", &self.username)
             .field("user_password", &"********")
             .field("root_password", &"********")
@@ -172,6 +205,42 @@ impl std::fmt::Debug for InstallationConfig {
     }
 }
 
+/// One entry of `partition_layout`, in on-disk order.
+///
+/// With `partitioning_strategy: manual`, a non-empty layout replaces
+/// interactive partitioning: the engine plans it (`plan_custom_layout`) and
+/// the install executes that plan, as for the automatic strategies. It is
+/// also exported as `PARTITION_LAYOUT`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutPartition {
+    pub size: String, // Size like "512M", "40GB", or "Remaining" (last entry only)
+    #[serde(rename = "type")]
+    pub partition_type: PartitionType, // sgdisk code or alias ("EF00", "Swap", "LUKS")
+    #[serde(default)]
+    pub filesystem: Option<Filesystem>, // None leaves the partition unformatted
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub mountpoint: Option<String>, // Target path like "/" or "/boot" (relative to /mnt)
+    #[serde(default)]
+    pub mount_options: Option<String>,
+    #[serde(default)]
+    pub encrypt: bool, // LUKS-format and open before creating the filesystem
+}
+
+/// One entry of `btrfs_subvolumes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BtrfsSubvolume {
+    pub name: String,       // Subvolume name like "@home"
+    pub mountpoint: String, // Target path like "/home" (relative to /mnt)
+    #[serde(default)]
+    pub mount_options: Option<String>, // None uses "compress=zstd,noatime"; subvol= is always added
+}
+
+fn default_config_version() -> u32 {
+    CURRENT_VERSION
+}
+
 fn default_raid_level() -> String {
     "raid1".to_string()
 }
@@ -184,6 +253,85 @@ fn default_home_size() -> String {
     "Remaining".to_string()
 }
 
+/// Snapper-friendly layout: snapshots of `@` exclude home, logs and the
+/// package cache, and `@snapshots` lives outside the snapshotted tree.
+fn default_btrfs_subvolumes() -> Vec<BtrfsSubvolume> {
+    [
+        ("@", "/"),
+        ("@home", "/home"),
+        ("@log", "/var/log"),
+        ("@pkg", "/var/cache/pacman/pkg"),
+        ("@snapshots", "/.snapshots"),
+    ]
+    .into_iter()
+    .map(|(name, mountpoint)| BtrfsSubvolume {
+        name: name.to_string(),
+        mountpoint: mountpoint.to_string(),
+        mount_options: None,
+    })
+    .collect()
+}
+
+/// On-disk format of a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Format for `path` by extension; unknown or missing extensions are JSON.
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match ext.as_deref() {
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    /// Parse `content` in this format.
+    pub fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        match self {
+            Self::Json => {
+                serde_json::from_str(content).context("Failed to parse configuration JSON")
+            }
+            Self::Toml => toml::from_str(content).context("Failed to parse configuration TOML"),
+            Self::Yaml => {
+                serde_yaml::from_str(content).context("Failed to parse configuration YAML")
+            }
+        }
+    }
+
+    /// Render `value` in this format.
+    pub fn render<T: Serialize>(self, value: &T) -> Result<String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value)
+                .context("Failed to serialize configuration to JSON"),
+            Self::Toml => {
+                toml::to_string_pretty(value).context("Failed to serialize configuration to TOML")
+            }
+            Self::Yaml => {
+                serde_yaml::to_string(value).context("Failed to serialize configuration to YAML")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "JSON"),
+            Self::Toml => write!(f, "TOML"),
+            Self::Yaml => write!(f, "YAML"),
+        }
+    }
+}
+
 impl InstallationConfig {
     /// Create a new empty configuration with sensible defaults
     #[allow(dead_code)] // API: Constructor for external consumers
@@ -191,7 +339,39 @@ impl InstallationConfig {
         Self::default()
     }
 
-    /// Save configuration to a JSON file
+    /// JSON Schema for config files, published as `docs/config.schema.json`
+    /// Generated from this struct, plus the layering keys `extends`/`$schema`
+    pub fn json_schema() -> serde_json::Value {
+        let mut schema = schemars::schema_for!(InstallationConfig);
+        schema.insert(
+            "$id".to_string(),
+            serde_json::json!(
+                "https://github.com/live4thamuzik/ArchTUI/raw/main/docs/config.schema.json"
+            ),
+        );
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            properties.insert(
+                "extends".to_string(),
+                serde_json::json!({
+                    "description": "Base config(s) to merge first, relative to this file",
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ]
+                }),
+            );
+            properties.insert(
+                "$schema".to_string(),
+                serde_json::json!({ "type": "string" }),
+            );
+        }
+        schema.to_value()
+    }
+
+    /// Save configuration to a JSON, TOML or YAML file (by extension)
     /// Passwords are redacted — they are NEVER written to disk
     #[allow(dead_code)] // API: Used by --save-config CLI option
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
@@ -201,10 +381,9 @@ impl InstallationConfig {
         redacted.root_password = String::new();
         redacted.encryption_password = String::new();
 
-        let json = serde_json::to_string_pretty(&redacted)
-            .context("Failed to serialize configuration to JSON")?;
+        let content = ConfigFormat::from_path(path.as_ref()).render(&redacted)?;
 
-        fs::write(&path, json)
+        fs::write(&path, content)
             .with_context(|| format!("Failed to write configuration to {:?}", path.as_ref()))?;
 
         tracing::warn!(
@@ -215,14 +394,12 @@ impl InstallationConfig {
         Ok(())
     }
 
-    /// Load configuration from a JSON file
+    /// Load configuration from a JSON, TOML or YAML file (by extension)
+    ///
+    /// Base configs named in `extends` are merged first (see `config_layers`).
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         tracing::info!(path = %path.as_ref().display(), "Loading configuration from file");
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read configuration from {:?}", path.as_ref()))?;
-
-        let config: Self =
-            serde_json::from_str(&content).context("Failed to parse configuration JSON")?;
+        let config = crate::config_layers::LayeredConfig::load(path.as_ref())?.config;
 
         tracing::info!(
             strategy = %config.partitioning_strategy,
@@ -347,8 +524,9 @@ impl InstallationConfig {
         }
 
         // Validate encryption password if encryption is enabled or strategy requires it
-        let needs_encryption =
-            self.encryption == AutoToggle::Yes || self.partitioning_strategy.uses_encryption();
+        let needs_encryption = self.encryption == AutoToggle::Yes
+            || self.partitioning_strategy.uses_encryption()
+            || self.partition_layout.iter().any(|p| p.encrypt);
         if needs_encryption && self.encryption_password.trim().is_empty() {
             tracing::error!(
                 field = "encryption_password",
@@ -378,6 +556,19 @@ impl InstallationConfig {
             }
         }
 
+        // Validate offline package source (existence is checked when the install starts)
+        let offline_source = self.offline_source.trim();
+        if !offline_source.is_empty() && !offline_source.starts_with('/') {
+            tracing::error!(
+                field = "offline_source",
+                "Offline package source must be an absolute path"
+            );
+            anyhow::bail!(
+                "Offline package source must be an absolute path, got '{}'",
+                offline_source
+            );
+        }
+
         // Validate AUR helper is selected when DE requires AUR packages
         if self.desktop_environment.requires_aur() && self.aur_helper == AurHelper::None {
             tracing::error!(de = %self.desktop_environment, "DE requires AUR helper but none selected");
@@ -402,7 +593,7 @@ impl InstallationConfig {
                     disk_count
                 );
             }
-            let valid_levels = ["raid0", "raid1", "raid5", "raid6", "raid10"];
+            let valid_levels = crate::engine::storage::RAID_LEVELS;
             if !valid_levels.contains(&self.raid_level.as_str()) {
                 tracing::error!(raid_level = %self.raid_level, "Invalid RAID level");
                 anyhow::bail!(
@@ -411,6 +602,157 @@ impl InstallationConfig {
                     valid_levels.join(", ")
                 );
             }
+            // Plain RAID puts root straight on the data array: no slot for home
+            if !self.partitioning_strategy.uses_lvm() && self.separate_home == Toggle::Yes {
+                tracing::error!(strategy = %self.partitioning_strategy, "Separate home on plain RAID");
+                anyhow::bail!(
+                    "{} has no slot for a separate home — disable separate_home, or use an LVM RAID strategy",
+                    self.partitioning_strategy
+                );
+            }
+        }
+
+        // Validate Btrfs subvolume layout
+        if self.root_filesystem == Filesystem::Btrfs {
+            let mut names: Vec<&str> = Vec::new();
+            let mut mountpoints: Vec<&str> = Vec::new();
+            for subvol in &self.btrfs_subvolumes {
+                let name = subvol.name.as_str();
+                if name.is_empty()
+                    || name == "."
+                    || name == ".."
+                    || !name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "@._-".contains(c))
+                {
+                    tracing::error!(subvolume = %name, "Invalid Btrfs subvolume name");
+                    anyhow::bail!(
+                        "Invalid Btrfs subvolume name '{}' (letters, digits, '@', '.', '_', '-')",
+                        name
+                    );
+                }
+                let mountpoint = subvol.mountpoint.as_str();
+                if !mountpoint.starts_with('/') || mountpoint.split('/').any(|c| c == "..") {
+                    tracing::error!(subvolume = %name, mountpoint = %mountpoint, "Invalid subvolume mountpoint");
+                    anyhow::bail!(
+                        "Btrfs subvolume '{}' mountpoint '{}' must be an absolute path without '..'",
+                        name,
+                        mountpoint
+                    );
+                }
+                // '|' and ';' delimit BTRFS_SUBVOLUMES for scripts/disk_utils.sh
+                if [Some(mountpoint), subvol.mount_options.as_deref()]
+                    .iter()
+                    .flatten()
+                    .any(|f| f.contains(['|', ';']))
+                {
+                    tracing::error!(subvolume = %name, "Reserved character in subvolume entry");
+                    anyhow::bail!(
+                        "Btrfs subvolume '{}': mountpoint and mount_options cannot contain '|' or ';'",
+                        name
+                    );
+                }
+                if names.contains(&name) || mountpoints.contains(&mountpoint) {
+                    tracing::error!(subvolume = %name, "Duplicate Btrfs subvolume");
+                    anyhow::bail!(
+                        "Btrfs subvolume '{}' ({}) duplicates another entry's name or mountpoint",
+                        name,
+                        mountpoint
+                    );
+                }
+                names.push(name);
+                mountpoints.push(mountpoint);
+            }
+            if !mountpoints.contains(&"/") {
+                tracing::error!(field = "btrfs_subvolumes", "No subvolume mounted at /");
+                anyhow::bail!("btrfs_subvolumes must include a subvolume mounted at '/'");
+            }
+            if self.btrfs_snapshots == Toggle::Yes && !mountpoints.contains(&"/.snapshots") {
+                tracing::error!(field = "btrfs_subvolumes", "Snapshots enabled without /.snapshots");
+                anyhow::bail!(
+                    "Btrfs snapshots need a subvolume mounted at '/.snapshots' in btrfs_subvolumes"
+                );
+            }
+        }
+
+        // Validate declarative partition layout (planned by the storage engine)
+        if !self.partition_layout.is_empty() {
+            if self.partitioning_strategy != PartitionScheme::Manual {
+                tracing::error!(
+                    strategy = %self.partitioning_strategy,
+                    "partition_layout requires the manual strategy"
+                );
+                anyhow::bail!(
+                    "partition_layout requires partitioning_strategy 'manual' (found '{}')",
+                    self.partitioning_strategy
+                );
+            }
+            if let Err(e) = crate::engine::storage::calculate_storage_plan(self) {
+                tracing::error!(field = "partition_layout", error = %e, "Invalid partition layout");
+                anyhow::bail!("Invalid partition_layout: {}", e);
+            }
+            // '|' and ';' delimit the PARTITION_LAYOUT entries and fields
+            for (i, part) in self.partition_layout.iter().enumerate() {
+                let fields = [&part.label, &part.mountpoint, &part.mount_options];
+                if fields
+                    .iter()
+                    .filter_map(|f| f.as_deref())
+                    .any(|f| f.contains(['|', ';']))
+                {
+                    tracing::error!(
+                        field = "partition_layout",
+                        entry = i + 1,
+                        "Reserved character"
+                    );
+                    anyhow::bail!(
+                        "partition_layout entry {}: label, mountpoint and mount_options cannot contain '|' or ';'",
+                        i + 1
+                    );
+                }
+            }
+        }
+
+        // Validate install-alongside settings (alongside_esp may be left for detection)
+        if self.partitioning_strategy == PartitionScheme::AutoAlongside {
+            if self.boot_mode == BootMode::Bios {
+                tracing::error!(strategy = %self.partitioning_strategy, "Alongside install requires UEFI");
+                anyhow::bail!("auto_alongside requires UEFI boot — it reuses the existing ESP");
+            }
+            if self.encryption == AutoToggle::Yes || self.separate_home == Toggle::Yes {
+                tracing::error!(
+                    strategy = %self.partitioning_strategy,
+                    "Alongside install supports neither encryption nor a separate /home"
+                );
+                anyhow::bail!("auto_alongside supports neither encryption nor a separate /home");
+            }
+            if self.shrink_partition.trim().is_empty() != self.shrink_size.trim().is_empty() {
+                tracing::error!(field = "shrink_partition", "shrink_partition without shrink_size");
+                anyhow::bail!("shrink_partition and shrink_size must be set together");
+            }
+            if let Err(e) = crate::engine::storage::layout_size_spec(&self.shrink_size)
+                && !self.shrink_size.trim().is_empty()
+            {
+                tracing::error!(field = "shrink_size", error = %e, "Invalid shrink size");
+                anyhow::bail!("Invalid shrink_size: {}", e);
+            }
+            if !self.alongside_esp.trim().is_empty()
+                && let Err(e) = crate::engine::storage::calculate_storage_plan(self)
+            {
+                tracing::error!(strategy = %self.partitioning_strategy, error = %e, "Invalid alongside settings");
+                anyhow::bail!("Invalid auto_alongside settings: {}", e);
+            }
+        }
+
+        // Validate swap mode, size and hibernation
+        if let Err(e) = crate::engine::swap::validate_swap(self) {
+            tracing::error!(swap = %self.swap, error = %e, "Invalid swap settings");
+            anyhow::bail!("Invalid swap settings: {}", e);
+        }
+
+        // Validate partition size syntax (the fit needs the disks: validate_for_hardware)
+        if let Err(e) = crate::engine::capacity::check_size_fields(self) {
+            tracing::error!(error = %e, "Invalid partition size");
+            anyhow::bail!("Invalid partition sizes: {}", e);
         }
 
         // Validate UEFI-only bootloaders are not selected with BIOS boot mode
@@ -447,6 +789,63 @@ impl InstallationConfig {
         Ok(())
     }
 
+    /// [`Self::validate`], plus the check that the layout fits the target
+    /// disk(s) whenever `hardware` lists them.
+    pub fn validate_for_hardware(
+        &self,
+        hardware: &crate::hardware::HardwareInventory,
+    ) -> Result<()> {
+        self.validate()?;
+        crate::engine::capacity::check_layout_fits_hardware(self, hardware)
+            .context("Layout does not fit the target disk")
+    }
+
+    /// `partition_layout` as exported to `install.sh`, whose `partition_disk`
+    /// runs the storage plan when it is set.
+    ///
+    /// Entries are separated by `;`, fields by `|`:
+    /// `size|type|filesystem|mountpoint|label|mount_options|encrypt`, where
+    /// `size` is a sgdisk size (`512M`) or `remaining`, `type` the sgdisk
+    /// code, and `encrypt` is `yes`/`no`. Unset fields are empty.
+    fn partition_layout_env(&self) -> String {
+        self.partition_layout
+            .iter()
+            .map(|p| {
+                let size = crate::engine::storage::layout_size_spec(&p.size)
+                    .unwrap_or_else(|_| p.size.trim().to_string());
+                [
+                    size,
+                    p.partition_type.as_sgdisk_code().to_string(),
+                    p.filesystem.map(|f| f.to_string()).unwrap_or_default(),
+                    p.mountpoint.clone().unwrap_or_default(),
+                    p.label.clone().unwrap_or_default(),
+                    p.mount_options.clone().unwrap_or_default(),
+                    if p.encrypt { "yes" } else { "no" }.to_string(),
+                ]
+                .join("|")
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// `btrfs_subvolumes` (plus `@swap` for swapfiles) as read by
+    /// `setup_btrfs_subvolumes` in `scripts/disk_utils.sh`:
+    /// `name|mountpoint|mount_options` entries separated by `;`, with unset
+    /// options resolved to the engine default.
+    fn btrfs_subvolumes_env(&self) -> String {
+        crate::engine::storage::btrfs_subvolumes_for(self)
+            .iter()
+            .map(|s| {
+                let options = s
+                    .mount_options
+                    .as_deref()
+                    .unwrap_or(crate::engine::storage::BTRFS_DEFAULT_MOUNT_OPTIONS);
+                format!("{}|{}|{}", s.name, s.mountpoint, options)
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
     /// Convert to environment variables for Bash scripts.
     /// N/A sentinel values are converted to empty strings.
     #[allow(dead_code)] // API: Used when passing config to install scripts
@@ -482,8 +881,14 @@ impl InstallationConfig {
             ),
             ("SWAP".to_string(), self.swap.to_string()),
             ("SWAP_SIZE".to_string(), sanitize(self.swap_size.clone())),
+            ("HIBERNATION".to_string(), self.hibernation.to_string()),
             ("ROOT_SIZE".to_string(), sanitize(self.root_size.clone())),
+            ("PARTITION_LAYOUT".to_string(), self.partition_layout_env()),
+            ("BTRFS_SUBVOLUMES".to_string(), self.btrfs_subvolumes_env()),
             ("HOME_SIZE".to_string(), sanitize(self.home_size.clone())),
+            ("ALONGSIDE_ESP".to_string(), self.alongside_esp.clone()),
+            ("SHRINK_PARTITION".to_string(), self.shrink_partition.clone()),
+            ("SHRINK_SIZE".to_string(), self.shrink_size.clone()),
             (
                 "BTRFS_SNAPSHOTS".to_string(),
                 self.btrfs_snapshots.to_string(),
@@ -573,6 +978,7 @@ impl InstallationConfig {
 impl Default for InstallationConfig {
     fn default() -> Self {
         Self {
+            config_version: CURRENT_VERSION,
             boot_mode: BootMode::Auto,
             secure_boot: Toggle::No,
             install_disk: String::new(),
@@ -583,13 +989,19 @@ impl Default for InstallationConfig {
             separate_home: Toggle::No,
             encryption: AutoToggle::No,
             encryption_password: String::new(),
-            swap: Toggle::No,
+            swap: SwapMode::None,
             swap_size: "N/A".to_string(),
+            hibernation: Toggle::No,
             root_size: "50GB".to_string(),
             home_size: "Remaining".to_string(),
+            partition_layout: Vec::new(),
+            alongside_esp: String::new(),
+            shrink_partition: String::new(),
+            shrink_size: String::new(),
             btrfs_snapshots: Toggle::No,
             btrfs_frequency: SnapshotFrequency::Weekly,
             btrfs_keep_count: 3,
+            btrfs_subvolumes: default_btrfs_subvolumes(),
             snapshot_tool: SnapshotTool::None,
             timezone_region: "America".to_string(),
             timezone: "New_York".to_string(),
@@ -598,6 +1010,7 @@ impl Default for InstallationConfig {
             time_sync: Toggle::No,
             mirror_country: "United States".to_string(),
             hostname: String::new(),
+            offline_source: String::new(),
             username: String::new(),
             user_password: String::new(),
             root_password: String::new(),
@@ -652,6 +1065,7 @@ impl From<&crate::config::Configuration> for InstallationConfig {
         }
 
         Self {
+            config_version: CURRENT_VERSION,
             boot_mode: parse_or_default(&get_value("Boot Mode")),
             secure_boot: parse_or_default(&get_value("Secure Boot")),
             install_disk: get_value("Disk"),
@@ -674,11 +1088,17 @@ impl From<&crate::config::Configuration> for InstallationConfig {
             },
             swap: parse_or_default(&get_value("Swap")),
             swap_size: get_value("Swap Size"),
+            hibernation: parse_or_default(&get_value("Hibernation")),
             root_size: get_value("Root Size"),
             home_size: get_value("Home Size"),
+            partition_layout: Vec::new(), // Not exposed in the TUI
+            alongside_esp: String::new(),    // Detected by the alongside strategy
+            shrink_partition: String::new(), // Not exposed in the TUI
+            shrink_size: String::new(),      // Not exposed in the TUI
             btrfs_snapshots: parse_or_default(&get_value("Btrfs Snapshots")),
             btrfs_frequency: parse_or_default(&get_value("Snapshot Frequency")),
             btrfs_keep_count: get_value("Snapshot Keep Count").parse().unwrap_or(3),
+            btrfs_subvolumes: default_btrfs_subvolumes(), // Not exposed in the TUI
             snapshot_tool: parse_or_default(&get_value("Snapshot Tool")),
             timezone_region: get_value("Timezone Region"),
             timezone: get_value("Timezone"),
@@ -687,6 +1107,7 @@ impl From<&crate::config::Configuration> for InstallationConfig {
             time_sync: parse_or_default(&get_value("Time Sync (NTP)")),
             mirror_country: get_value("Mirror Country"),
             hostname: get_value("Hostname"),
+            offline_source: String::new(), // Offline installs are config-file/CLI only
             username: get_value("Username"),
             user_password: get_value("User Password"),
             root_password: get_value("Root Password"),
@@ -855,6 +1276,38 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validation_partition_sizes() {
+        let mut config = create_test_config();
+        config.root_size = "50 gigs".to_string();
+        let err = config.validate().expect_err("unparseable root_size");
+        assert!(err.to_string().contains("root_size"));
+
+        config.root_size = "25%".to_string();
+        config.separate_home = Toggle::Yes;
+        config.home_size = "0GB".to_string();
+        assert!(config.validate().is_err());
+        config.home_size = "Remaining".to_string();
+        assert!(config.validate().is_ok());
+
+        // validate() never consults a host disk; validate_for_hardware does
+        config.root_size = "900TB".to_string();
+        assert!(config.validate().is_ok());
+        let mut hardware = crate::hardware::HardwareInventory::default();
+        assert!(config.validate_for_hardware(&hardware).is_ok());
+        hardware.disks.push(crate::hardware::BlockDevice {
+            name: "sda".to_string(),
+            size_mib: 256 * 1024,
+            rotational: false,
+            removable: false,
+            model: None,
+        });
+        let err = config
+            .validate_for_hardware(&hardware)
+            .expect_err("900TB root on a 256GB disk");
+        assert!(format!("{:#}", err).contains("does not fit"));
+    }
+
     #[test]
     fn test_validation_invalid_hostname() {
         let mut config = create_test_config();
@@ -968,6 +1421,119 @@ mod tests {
         assert!(result.is_err(), "Should fail on wrong JSON structure");
     }
 
+    // =========================================================================
+    // TOML / YAML Formats
+    // =========================================================================
+
+    #[test]
+    fn test_config_format_from_extension() {
+        for (name, format) in [
+            ("a.toml", ConfigFormat::Toml),
+            ("a.YAML", ConfigFormat::Yaml),
+            ("a.yml", ConfigFormat::Yaml),
+            ("a.json", ConfigFormat::Json),
+            ("config", ConfigFormat::Json),
+        ] {
+            assert_eq!(ConfigFormat::from_path(Path::new(name)), format, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_toml_and_yaml() {
+        let mut original = create_test_config();
+        original.partition_layout = vec![LayoutPartition {
+            size: "Remaining".to_string(),
+            partition_type: PartitionType::Linux,
+            filesystem: Some(Filesystem::Ext4),
+            label: None,
+            mountpoint: Some("/".to_string()),
+            mount_options: None,
+            encrypt: false,
+        }];
+        // Passwords are redacted on save in every format
+        let mut expected = original.clone();
+        expected.user_password.clear();
+        expected.root_password.clear();
+        expected.encryption_password.clear();
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in ["config.toml", "config.yaml", "config.yml"] {
+            let path = dir.path().join(name);
+            original.save_to_file(&path).unwrap();
+
+            // The file really is in the target format, not JSON
+            let content = std::fs::read_to_string(&path).unwrap();
+            assert!(serde_json::from_str::<serde_json::Value>(&content).is_err());
+
+            let loaded = InstallationConfig::load_from_file(&path).unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&expected).unwrap(),
+                "{} should round-trip",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_toml_and_yaml_keep_legacy_names_and_defaults() {
+        // An unversioned file with legacy key names and optional fields left out
+        let mut value = serde_json::to_value(create_test_config()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        fields.remove("config_version");
+        let snapshot_tool = fields.remove("snapshot_tool").unwrap();
+        fields.insert("btrfs_assistant".to_string(), snapshot_tool);
+        let grub_theme = fields.remove("grub_theme").unwrap();
+        fields.insert("grub_themes".to_string(), grub_theme);
+        fields.remove("btrfs_subvolumes");
+        fields.remove("raid_level");
+
+        let dir = tempfile::tempdir().unwrap();
+        for (name, format) in [
+            ("host.toml", ConfigFormat::Toml),
+            ("host.yaml", ConfigFormat::Yaml),
+        ] {
+            let path = dir.path().join(name);
+            let content = format!(
+                "# Hand-written host config\n{}",
+                format.render(&value).unwrap()
+            );
+            std::fs::write(&path, content).unwrap();
+
+            let config = InstallationConfig::load_from_file(&path).unwrap();
+            let expected = create_test_config();
+            assert_eq!(config.snapshot_tool, expected.snapshot_tool);
+            assert_eq!(config.grub_theme, expected.grub_theme);
+            assert_eq!(config.btrfs_subvolumes.len(), 5, "{}", name);
+            assert_eq!(config.raid_level, default_raid_level());
+        }
+    }
+
+    #[test]
+    fn test_published_schema_is_current() {
+        let published: serde_json::Value =
+            serde_json::from_str(include_str!("../docs/config.schema.json")).unwrap();
+        assert_eq!(
+            published,
+            InstallationConfig::json_schema(),
+            "regenerate with: archtui config schema > docs/config.schema.json"
+        );
+    }
+
+    #[test]
+    fn test_schema_describes_config_fields() {
+        let schema = InstallationConfig::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        let config = serde_json::to_value(InstallationConfig::default()).unwrap();
+        for field in config.as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "{}", field);
+        }
+        assert!(properties.contains_key("extends"));
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&serde_json::json!("boot_mode")));
+        assert!(!required.contains(&serde_json::json!("config_version")));
+    }
+
     // =========================================================================
     // Validation Edge Cases
     // =========================================================================
@@ -1109,6 +1675,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validation_offline_source_must_be_absolute() {
+        let mut config = create_test_config();
+        config.offline_source = "/run/archiso/repo".to_string();
+        assert!(config.validate().is_ok());
+
+        config.offline_source = "repo".to_string();
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("absolute"));
+    }
+
     #[test]
     fn test_validation_raid_level() {
         let mut config = create_test_config();
@@ -1136,6 +1714,230 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validation_plain_raid_rejects_separate_home() {
+        let mut config = create_test_config();
+        config.partitioning_strategy = PartitionScheme::AutoRaid;
+        config.install_disk = "/dev/sda,/dev/sdb".to_string();
+        config.raid_level = "raid1".to_string();
+        config.separate_home = Toggle::Yes;
+        let err = config.validate().expect_err("plain RAID has no home slot");
+        assert!(err.to_string().contains("no slot for a separate home"));
+
+        config.partitioning_strategy = PartitionScheme::AutoRaidLvm;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_partition_layout_from_json() {
+        let mut json = serde_json::to_value(create_test_config()).unwrap();
+        json["partitioning_strategy"] = serde_json::json!("Manual");
+        json["partition_layout"] = serde_json::json!([
+            { "size": "512M", "type": "EF00", "filesystem": "Fat32", "mountpoint": "/boot" },
+            { "size": "8G", "type": "swap", "filesystem": "Swap" },
+            { "size": "Remaining", "type": "LUKS", "filesystem": "Btrfs", "label": "root",
+              "mountpoint": "/", "mount_options": "compress=zstd", "encrypt": true }
+        ]);
+        json["encryption_password"] = serde_json::json!("secret");
+
+        let config: InstallationConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.partition_layout.len(), 3);
+        assert_eq!(config.partition_layout[1].partition_type, PartitionType::Swap);
+        assert_eq!(config.partition_layout[1].mountpoint, None);
+        assert!(!config.partition_layout[1].encrypt);
+        assert_eq!(config.partition_layout[2].partition_type, PartitionType::Luks);
+        assert_eq!(config.partition_layout[2].filesystem, Some(Filesystem::Btrfs));
+        assert!(config.partition_layout[2].encrypt);
+        assert!(config.validate().is_ok());
+
+        // Saved configs round-trip the layout with sgdisk type codes
+        let saved = serde_json::to_value(&config).unwrap();
+        assert_eq!(saved["partition_layout"][1]["type"], "8200");
+    }
+
+    #[test]
+    fn test_validation_partition_layout() {
+        let mut config = create_test_config();
+        config.partition_layout = vec![LayoutPartition {
+            size: "Remaining".to_string(),
+            partition_type: PartitionType::Linux,
+            filesystem: Some(Filesystem::Ext4),
+            label: None,
+            mountpoint: Some("/".to_string()),
+            mount_options: None,
+            encrypt: false,
+        }];
+        config.boot_mode = BootMode::Bios;
+
+        // Layouts are only honored by the manual strategy
+        assert!(config.validate().is_err());
+        config.partitioning_strategy = PartitionScheme::Manual;
+        assert!(config.validate().is_ok());
+
+        // Layout errors surface through validate()
+        config.boot_mode = BootMode::Uefi;
+        assert!(config.validate().is_err(), "UEFI layout needs an ESP");
+        config.boot_mode = BootMode::Bios;
+
+        // Encrypted entries need a passphrase
+        config.partition_layout[0].encrypt = true;
+        assert!(config.validate().is_err());
+        config.encryption_password = "secret".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_partition_layout_env() {
+        let mut config = create_test_config();
+        config.partitioning_strategy = PartitionScheme::Manual;
+        config.boot_mode = BootMode::Uefi;
+        config.partition_layout = vec![
+            LayoutPartition {
+                size: "512MiB".to_string(),
+                partition_type: PartitionType::Efi,
+                filesystem: Some(Filesystem::Fat32),
+                label: Some("EFI".to_string()),
+                mountpoint: Some("/boot".to_string()),
+                mount_options: None,
+                encrypt: false,
+            },
+            LayoutPartition {
+                size: "Remaining".to_string(),
+                partition_type: PartitionType::Luks,
+                filesystem: Some(Filesystem::Btrfs),
+                label: None,
+                mountpoint: Some("/".to_string()),
+                mount_options: Some("compress=zstd,noatime".to_string()),
+                encrypt: true,
+            },
+        ];
+        config.encryption_password = "secret".to_string();
+        assert!(config.validate().is_ok());
+
+        let env: std::collections::HashMap<_, _> = config.to_env_vars().into_iter().collect();
+        assert_eq!(
+            env["PARTITION_LAYOUT"],
+            "512M|EF00|fat32|/boot|EFI||no;remaining|8309|btrfs|/||compress=zstd,noatime|yes"
+        );
+
+        // Delimiters cannot appear inside a field
+        config.partition_layout[1].mount_options = Some("noatime;ro".to_string());
+        assert!(config.validate().is_err());
+
+        config.partition_layout.clear();
+        let env: std::collections::HashMap<_, _> = config.to_env_vars().into_iter().collect();
+        assert_eq!(env["PARTITION_LAYOUT"], "");
+    }
+
+    #[test]
+    fn test_btrfs_subvolumes_default_when_missing_from_json() {
+        let mut json = serde_json::to_value(create_test_config()).unwrap();
+        json.as_object_mut().unwrap().remove("btrfs_subvolumes");
+
+        let config: InstallationConfig = serde_json::from_value(json).unwrap();
+        let names: Vec<&str> = config.btrfs_subvolumes.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["@", "@home", "@log", "@pkg", "@snapshots"]);
+    }
+
+    #[test]
+    fn test_btrfs_subvolumes_env() {
+        let mut config = create_test_config();
+        config.root_filesystem = Filesystem::Btrfs;
+        config.btrfs_subvolumes[2].mount_options = Some("noatime".to_string());
+        assert!(config.validate().is_ok());
+
+        let env: std::collections::HashMap<_, _> = config.to_env_vars().into_iter().collect();
+        assert_eq!(
+            env["BTRFS_SUBVOLUMES"],
+            "@|/|compress=zstd,noatime;@home|/home|compress=zstd,noatime;@log|/var/log|noatime;\
+             @pkg|/var/cache/pacman/pkg|compress=zstd,noatime;@snapshots|/.snapshots|compress=zstd,noatime"
+        );
+
+        // Swapfiles get the same @swap subvolume the storage plan creates
+        config.swap = SwapMode::Swapfile;
+        config.swap_size = "4GB".to_string();
+        let env: std::collections::HashMap<_, _> = config.to_env_vars().into_iter().collect();
+        assert!(env["BTRFS_SUBVOLUMES"].ends_with(";@swap|/swap|noatime"));
+
+        config.btrfs_subvolumes[2].mount_options = Some("noatime;ro".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_bash_default_btrfs_subvolumes_match_config_default() {
+        let mut config = create_test_config();
+        config.root_filesystem = Filesystem::Btrfs;
+        let env: std::collections::HashMap<_, _> = config.to_env_vars().into_iter().collect();
+        let disk_utils = include_str!("../scripts/disk_utils.sh");
+        assert!(disk_utils.contains(&format!(
+            "DEFAULT_BTRFS_SUBVOLUMES=\"{}\"",
+            env["BTRFS_SUBVOLUMES"]
+        )));
+    }
+
+    #[test]
+    fn test_validation_btrfs_subvolumes() {
+        let mut config = create_test_config();
+        config.root_filesystem = Filesystem::Btrfs;
+        config.btrfs_snapshots = Toggle::Yes;
+        assert!(config.validate().is_ok());
+
+        // Snapshots depend on a /.snapshots subvolume
+        config.btrfs_subvolumes.retain(|s| s.mountpoint != "/.snapshots");
+        assert!(config.validate().is_err());
+        config.btrfs_snapshots = Toggle::No;
+        assert!(config.validate().is_ok());
+
+        let valid = config.btrfs_subvolumes.clone();
+        let mut bad_name = valid.clone();
+        bad_name[1].name = "@home/nested".to_string();
+        let mut relative = valid.clone();
+        relative[1].mountpoint = "home".to_string();
+        let mut duplicate = valid.clone();
+        duplicate[2].mountpoint = "/home".to_string();
+        let mut no_root = valid.clone();
+        no_root.remove(0);
+        for subvolumes in [bad_name, relative, duplicate, no_root] {
+            config.btrfs_subvolumes = subvolumes;
+            assert!(config.validate().is_err(), "{:?}", config.btrfs_subvolumes);
+        }
+
+        // Only checked when root is Btrfs
+        config.root_filesystem = Filesystem::Ext4;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_alongside() {
+        let mut config = create_test_config();
+        config.partitioning_strategy = PartitionScheme::AutoAlongside;
+        // The ESP may be left empty for the install script to detect
+        assert!(config.validate().is_ok());
+
+        config.alongside_esp = "/dev/sda1".to_string();
+        config.shrink_partition = "/dev/sda3".to_string();
+        config.shrink_size = "200GB".to_string();
+        assert!(config.validate().is_ok());
+
+        config.shrink_partition = "/dev/sdb3".to_string();
+        assert!(config.validate().is_err(), "shrink target on another disk");
+        config.shrink_partition = "/dev/sda3".to_string();
+
+        config.shrink_size = "a lot".to_string();
+        assert!(config.validate().is_err(), "unparseable shrink size");
+        config.shrink_size.clear();
+        assert!(config.validate().is_err(), "shrink_partition without size");
+        config.shrink_partition.clear();
+        assert!(config.validate().is_ok());
+
+        config.separate_home = Toggle::Yes;
+        assert!(config.validate().is_err(), "separate /home");
+        config.separate_home = Toggle::No;
+
+        config.boot_mode = BootMode::Bios;
+        assert!(config.validate().is_err(), "BIOS boot");
+    }
+
     #[test]
     fn test_validation_uefi_only_bootloaders_on_bios() {
         let mut config = create_test_config();
@@ -0,0 +1,533 @@
+//! Layered Configuration Files
+//!
+//! A config file may name base configs in `extends` — one path or a list of
+//! paths, relative to the file itself. Bases are merged first, in list order,
+//! then the file's own fields override them; bases may extend further bases.
+//! `InstallationConfig` is deserialized from the merged document, so every
+//! loader (TUI, `install --config`, `validate`) sees the effective config.
+//! Layers may mix formats (`ConfigFormat`): a TOML host file can extend a
+//! JSON base.
+//!
+//! ```json
+//! {
+//!   "extends": ["../base.json", "../desktop-kde.json"],
+//!   "hostname": "build-42",
+//!   "install_disk": "/dev/nvme0n1"
+//! }
+//! ```
+//!
+//! # Merge Rules
+//!
+//! - Merging is per top-level field: a field set in a later layer replaces
+//!   the earlier value entirely (lists such as `partition_layout` are not
+//!   concatenated)
+//! - A base listed twice (diamond includes) is merged at each mention;
+//!   a file that extends itself, directly or through another base, is an error
+//! - Fields no layer sets fall back to `InstallationConfig` defaults
+//! - Each layer is upgraded to the current `config_version` on its own
+//!   (`config_migrations`) before it is merged
+//! - `$schema` (editor hint pointing at the published JSON Schema) is ignored
+//!
+//! `LayeredConfig::provenance` records which file supplied each field; it
+//! backs `archtui validate --show-merged`. Fields the config does not know are
+//! collected in `LayeredConfig::unknown_fields`; `archtui validate --strict`
+//! rejects them.
+
+// Library API - some accessors are only used by tests
+#![allow(dead_code)]
+
+use crate::config_file::{ConfigFormat, InstallationConfig};
+use crate::config_migrations::{self, MigrationReport};
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Key naming the base configs of a layer.
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Key editors use to find the JSON Schema of a document.
+pub const SCHEMA_KEY: &str = "$schema";
+
+/// Deepest `extends` chain accepted (guards against runaway generated configs).
+const MAX_DEPTH: usize = 16;
+
+/// Fields shown as `********` when printing a merged config.
+const SECRET_FIELDS: &[&str] = &["user_password", "root_password", "encryption_password"];
+
+/// Provenance label for fields no layer sets.
+const DEFAULT_SOURCE: &str = "(default)";
+
+/// A config merged from a file and everything it extends.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    /// The effective configuration.
+    pub config: InstallationConfig,
+    /// Every file merged, in merge order (the loaded file last).
+    pub layers: Vec<PathBuf>,
+    /// Top-level field → the file that set it last.
+    pub provenance: BTreeMap<String, PathBuf>,
+    /// Layers that needed upgrading, with what changed.
+    pub migrations: Vec<(PathBuf, MigrationReport)>,
+    /// Fields no `InstallationConfig` field matches, as dotted paths
+    /// (`partition_layout.0.mountpont`).
+    pub unknown_fields: Vec<String>,
+}
+
+impl LayeredConfig {
+    /// Load `path`, resolving `extends` recursively.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any layer cannot be read or is not an object,
+    /// if `extends` is malformed or cyclic, if a layer cannot be upgraded, or
+    /// if the merged document is not a valid `InstallationConfig`. Unknown
+    /// fields are not an error here.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut merger = Merger::default();
+        merger.merge_file(path, &mut Vec::new())?;
+
+        let mut unknown_fields = Vec::new();
+        let config: InstallationConfig =
+            serde_ignored::deserialize(Value::Object(merger.fields), |field| {
+                unknown_fields.push(field.to_string())
+            })
+            .context("Failed to parse merged configuration")?;
+        for field in &unknown_fields {
+            tracing::warn!(field = %field, "Ignoring unknown configuration field");
+        }
+        if merger.layers.len() > 1 {
+            tracing::info!(layers = merger.layers.len(), "Merged layered configuration");
+        }
+        Ok(Self {
+            config,
+            layers: merger.layers,
+            provenance: merger.provenance,
+            migrations: merger.migrations,
+            unknown_fields,
+        })
+    }
+
+    /// Whether the config extends at least one base.
+    pub fn is_layered(&self) -> bool {
+        self.layers.len() > 1
+    }
+
+    /// The file that supplied `field`, or `None` if it is a default.
+    pub fn source_of(&self, field: &str) -> Option<&Path> {
+        self.provenance.get(field).map(PathBuf::as_path)
+    }
+
+    /// Fields no layer sets, which therefore hold their defaults.
+    pub fn defaulted_fields(&self) -> Result<Vec<String>> {
+        Ok(self
+            .redacted_fields()?
+            .keys()
+            .filter(|field| !self.provenance.contains_key(*field))
+            .cloned()
+            .collect())
+    }
+
+    /// Unknown fields with the layer that set them, for strict validation.
+    pub fn unknown_field_errors(&self) -> Vec<String> {
+        self.unknown_fields
+            .iter()
+            .map(|field| {
+                let top = field.split('.').next().unwrap_or(field);
+                match self.source_of(top) {
+                    Some(path) => format!("unknown field {} in {}", field, path.display()),
+                    None => format!("unknown field {}", field),
+                }
+            })
+            .collect()
+    }
+
+    /// The effective config as a JSON object with secrets masked.
+    fn redacted_fields(&self) -> Result<Map<String, Value>> {
+        let Value::Object(mut fields) =
+            serde_json::to_value(&self.config).context("Failed to serialize configuration")?
+        else {
+            bail!("Configuration did not serialize to a JSON object");
+        };
+        for key in SECRET_FIELDS {
+            if let Some(Value::String(s)) = fields.get_mut(*key)
+                && !s.is_empty()
+            {
+                *s = "********".to_string();
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Human-readable listing of every field with its value and source.
+    pub fn to_lines(&self) -> Result<Vec<String>> {
+        let chain: Vec<String> = self
+            .layers
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        let mut lines = vec![
+            "=== Effective Configuration ===".to_string(),
+            format!("Layers: {}", chain.join(" -> ")),
+            String::new(),
+        ];
+        for (field, value) in self.redacted_fields()? {
+            let source = self
+                .source_of(&field)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+            lines.push(format!(
+                "{:<26} = {:<28} [{}]",
+                field,
+                value.to_string(),
+                source
+            ));
+        }
+        Ok(lines)
+    }
+
+    /// JSON document with `layers`, the redacted `config`, `provenance`
+    /// (field → file path, or "(default)"), `migrations` and `unknown_fields`.
+    pub fn to_json(&self) -> Result<String> {
+        let fields = self.redacted_fields()?;
+        let provenance: Map<String, Value> = fields
+            .keys()
+            .map(|field| {
+                let source = self
+                    .source_of(field)
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+                (field.clone(), Value::String(source))
+            })
+            .collect();
+        let migrations: Vec<Value> = self
+            .migrations
+            .iter()
+            .map(|(path, report)| {
+                serde_json::json!({
+                    "file": path,
+                    "from_version": report.from_version,
+                    "changes": report.changes,
+                })
+            })
+            .collect();
+        let document = serde_json::json!({
+            "layers": self.layers,
+            "config": fields,
+            "provenance": provenance,
+            "migrations": migrations,
+            "unknown_fields": self.unknown_fields,
+        });
+        serde_json::to_string_pretty(&document).context("Failed to serialize merged configuration")
+    }
+}
+
+/// Accumulates fields and provenance across layers.
+#[derive(Default)]
+struct Merger {
+    fields: Map<String, Value>,
+    provenance: BTreeMap<String, PathBuf>,
+    layers: Vec<PathBuf>,
+    migrations: Vec<(PathBuf, MigrationReport)>,
+}
+
+impl Merger {
+    /// Merge the bases of `path`, then `path` itself. `stack` holds the
+    /// canonical paths of the files currently being extended.
+    fn merge_file(&mut self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<()> {
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("Failed to read configuration from {:?}", path))?;
+        if stack.contains(&canonical) {
+            let chain: Vec<String> = stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            bail!("Configuration extends itself: {}", chain.join(" -> "));
+        }
+        if stack.len() >= MAX_DEPTH {
+            bail!(
+                "Configuration extends chain is deeper than {} files at {:?}",
+                MAX_DEPTH,
+                path
+            );
+        }
+
+        let mut fields = read_fields(path)?;
+        fields.remove(SCHEMA_KEY);
+        let report = config_migrations::migrate(&mut fields)
+            .with_context(|| format!("Failed to upgrade configuration {:?}", path))?;
+        if !report.is_empty() {
+            for change in &report.changes {
+                tracing::warn!(path = %path.display(), "Config migration: {}", change);
+            }
+            self.migrations.push((path.to_path_buf(), report));
+        }
+
+        let bases = take_extends(&mut fields, path)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        stack.push(canonical);
+        for base in bases {
+            self.merge_file(&dir.join(base), stack)?;
+        }
+        stack.pop();
+
+        for (field, value) in fields {
+            self.provenance.insert(field.clone(), path.to_path_buf());
+            self.fields.insert(field, value);
+        }
+        self.layers.push(path.to_path_buf());
+        Ok(())
+    }
+}
+
+/// Read one config file (any `ConfigFormat`) as a JSON object, without
+/// resolving `extends` or upgrading it.
+pub fn read_fields(path: &Path) -> Result<Map<String, Value>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read configuration from {:?}", path))?;
+    let Value::Object(fields) = ConfigFormat::from_path(path)
+        .parse(&content)
+        .with_context(|| format!("Invalid configuration file {:?}", path))?
+    else {
+        bail!("Configuration {:?} is not a table/object", path);
+    };
+    Ok(fields)
+}
+
+/// Remove and parse the `extends` key: absent, one path, or a list of paths.
+fn take_extends(fields: &mut Map<String, Value>, path: &Path) -> Result<Vec<String>> {
+    match fields.remove(EXTENDS_KEY) {
+        None => Ok(Vec::new()),
+        Some(Value::String(base)) => Ok(vec![base]),
+        Some(Value::Array(bases)) => bases
+            .into_iter()
+            .map(|base| match base {
+                Value::String(s) => Ok(s),
+                other => bail!("{:?}: extends entries must be paths, got {}", path, other),
+            })
+            .collect(),
+        Some(other) => bail!(
+            "{:?}: extends must be a path or a list of paths, got {}",
+            path,
+            other
+        ),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DesktopEnvironment, Toggle};
+
+    fn write(dir: &Path, name: &str, json: serde_json::Value) -> PathBuf {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create dir"); // test: known-good input
+        }
+        fs::write(&path, json.to_string()).expect("write layer"); // test: known-good input
+        path
+    }
+
+    /// Helper: a complete base config as JSON
+    fn base_json() -> serde_json::Value {
+        serde_json::to_value(InstallationConfig::new()).expect("serialize") // test: known-good input
+    }
+
+    #[test]
+    fn test_plain_file_has_single_layer() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let path = write(dir.path(), "host.json", base_json());
+
+        let layered = LayeredConfig::load(&path).expect("load"); // test: known-good input
+        assert!(!layered.is_layered());
+        assert_eq!(layered.layers, vec![path.clone()]);
+        assert_eq!(layered.source_of("hostname"), Some(path.as_path()));
+    }
+
+    #[test]
+    fn test_overrides_win_field_by_field() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let mut base = base_json();
+        base["hostname"] = "base".into();
+        base["multilib"] = "Yes".into();
+        let base_path = write(dir.path(), "fleet/base.json", base);
+        let desktop_path = write(
+            dir.path(),
+            "fleet/kde.json",
+            serde_json::json!({ "extends": "base.json", "desktop_environment": "Kde" }),
+        );
+        let host_path = write(
+            dir.path(),
+            "hosts/h1.json",
+            serde_json::json!({
+                "extends": ["../fleet/kde.json"],
+                "hostname": "h1",
+                "install_disk": "/dev/nvme0n1"
+            }),
+        );
+
+        let layered = LayeredConfig::load(&host_path).expect("load"); // test: known-good input
+        assert!(layered.is_layered());
+        assert_eq!(layered.layers.len(), 3);
+        assert_eq!(layered.config.hostname, "h1");
+        assert_eq!(layered.config.install_disk, "/dev/nvme0n1");
+        assert_eq!(layered.config.desktop_environment, DesktopEnvironment::Kde);
+        assert_eq!(layered.config.multilib, Toggle::Yes);
+
+        assert_eq!(layered.source_of("hostname"), Some(host_path.as_path()));
+        let desktop_source = layered.source_of("desktop_environment").expect("sourced"); // test: known-good input
+        assert_eq!(
+            fs::canonicalize(desktop_source).ok(),
+            fs::canonicalize(&desktop_path).ok()
+        );
+        let multilib_source = layered.source_of("multilib").expect("sourced"); // test: known-good input
+        assert_eq!(
+            fs::canonicalize(multilib_source).ok(),
+            fs::canonicalize(&base_path).ok()
+        );
+        assert_eq!(layered.source_of(EXTENDS_KEY), None);
+    }
+
+    #[test]
+    fn test_later_bases_override_earlier_ones() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        write(dir.path(), "a.json", base_json());
+        write(
+            dir.path(),
+            "b.json",
+            serde_json::json!({ "hostname": "from-b" }),
+        );
+        let host = write(
+            dir.path(),
+            "host.json",
+            serde_json::json!({ "extends": ["a.json", "b.json"] }),
+        );
+
+        let layered = LayeredConfig::load(&host).expect("load"); // test: known-good input
+        assert_eq!(layered.config.hostname, "from-b");
+    }
+
+    #[test]
+    fn test_cycle_is_rejected() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        write(
+            dir.path(),
+            "a.json",
+            serde_json::json!({ "extends": "b.json" }),
+        );
+        let b = write(
+            dir.path(),
+            "b.json",
+            serde_json::json!({ "extends": "a.json" }),
+        );
+
+        let err = LayeredConfig::load(&b).expect_err("cycle must fail");
+        assert!(err.to_string().contains("extends itself"), "{}", err);
+    }
+
+    #[test]
+    fn test_toml_layer_extends_json_base() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        write(dir.path(), "base.json", base_json());
+        let host = dir.path().join("h1.toml");
+        fs::write(
+            &host,
+            "# Per-host overrides\nextends = \"base.json\"\nhostname = \"h1\"\n",
+        )
+        .expect("write layer"); // test: known-good input
+
+        let layered = LayeredConfig::load(&host).expect("load"); // test: known-good input
+        assert_eq!(layered.layers.len(), 2);
+        assert_eq!(layered.config.hostname, "h1");
+        assert_eq!(layered.source_of("hostname"), Some(host.as_path()));
+    }
+
+    #[test]
+    fn test_layers_are_upgraded_and_unknown_fields_collected() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let mut legacy = base_json();
+        let fields = legacy.as_object_mut().expect("object"); // test: known-good input
+        fields.remove(config_migrations::VERSION_KEY);
+        let grub_theme = fields.remove("grub_theme").expect("grub_theme"); // test: known-good input
+        fields.insert("grub_themes".to_string(), grub_theme);
+        let base = write(dir.path(), "base.json", legacy);
+        let host = write(
+            dir.path(),
+            "host.json",
+            serde_json::json!({
+                "$schema": "../docs/config.schema.json",
+                "config_version": config_migrations::CURRENT_VERSION,
+                "extends": "base.json",
+                "hostnme": "typo",
+                "btrfs_subvolumes": [{ "name": "@", "mountpoint": "/", "compres": "zstd" }]
+            }),
+        );
+
+        let layered = LayeredConfig::load(&host).expect("load"); // test: known-good input
+        // Only the base needed upgrading
+        assert_eq!(layered.migrations.len(), 1);
+        assert_eq!(
+            fs::canonicalize(&layered.migrations[0].0).ok(),
+            fs::canonicalize(&base).ok()
+        );
+        assert_eq!(
+            layered.migrations[0].1.changes,
+            vec!["renamed grub_themes -> grub_theme"]
+        );
+        assert_eq!(
+            layered.unknown_fields,
+            vec!["btrfs_subvolumes.0.compres", "hostnme"]
+        );
+        let errors = layered.unknown_field_errors();
+        assert!(errors.iter().all(|e| e.ends_with("host.json")));
+        assert_eq!(layered.source_of(SCHEMA_KEY), None);
+    }
+
+    #[test]
+    fn test_malformed_extends_is_rejected() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let bad = write(dir.path(), "bad.json", serde_json::json!({ "extends": 5 }));
+        assert!(LayeredConfig::load(&bad).is_err());
+
+        let missing = write(
+            dir.path(),
+            "missing.json",
+            serde_json::json!({ "extends": "nope.json" }),
+        );
+        let err = LayeredConfig::load(&missing).expect_err("missing base must fail");
+        assert!(format!("{:#}", err).contains("nope.json"));
+    }
+
+    #[test]
+    fn test_show_merged_masks_secrets_and_marks_defaults() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let mut base = base_json();
+        base["root_password"] = "hunter2".into();
+        base.as_object_mut()
+            .expect("object") // test: known-good input
+            .remove("raid_level");
+        let path = write(dir.path(), "host.json", base);
+
+        let layered = LayeredConfig::load(&path).expect("load"); // test: known-good input
+        let text = layered.to_lines().expect("lines").join("\n"); // test: known-good input
+        assert!(!text.contains("hunter2"));
+        assert!(text.contains("********"));
+        let raid_line = text
+            .lines()
+            .find(|l| l.starts_with("raid_level"))
+            .expect("raid_level listed"); // test: known-good input
+        assert!(raid_line.ends_with("[(default)]"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&layered.to_json().expect("json")).expect("valid json"); // test: known-good input
+        assert_eq!(json["config"]["root_password"], "********");
+        assert_eq!(json["provenance"]["raid_level"], "(default)");
+        assert_eq!(json["layers"].as_array().map(Vec::len), Some(1));
+    }
+}
@@ -0,0 +1,201 @@
+//! Config Schema Versions and Migrations
+//!
+//! Config files carry `config_version`; files written before the field
+//! existed are version 0. Every layer is upgraded to `CURRENT_VERSION` one
+//! step at a time before layers are merged (`config_layers`), and each
+//! rewrite is recorded in a `MigrationReport` so an old config never changes
+//! meaning silently.
+//!
+//! | Version | Changes |
+//! |---------|---------|
+//! | 0 → 1   | `btrfs_assistant` → `snapshot_tool`, `grub_themes` → `grub_theme`, `swap: Yes/No` → `Partition/None` |
+//!
+//! # Adding a Migration
+//!
+//! 1. Bump `CURRENT_VERSION`
+//! 2. Append a `Migration` to `MIGRATIONS` whose `to` is the new version
+//! 3. Leave documents that already have the new shape untouched: partial
+//!    layers are usually unversioned and run every step
+
+// Library API - some accessors are only used by tests
+#![allow(dead_code)]
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Version written by this build; files declaring a newer one are rejected.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Key holding the schema version of a config document.
+pub const VERSION_KEY: &str = "config_version";
+
+/// What upgrading one config document changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Version the document declared (0 if it had no `config_version`).
+    pub from_version: u32,
+    /// One line per rewrite, in the order applied.
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether the document already had the current shape.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// One upgrade step, from version `to - 1` to `to`.
+struct Migration {
+    to: u32,
+    apply: fn(&mut Map<String, Value>, &mut Vec<String>),
+}
+
+/// Every upgrade step, oldest first.
+const MIGRATIONS: &[Migration] = &[Migration {
+    to: 1,
+    apply: migrate_v1,
+}];
+
+/// Upgrade a config document in place to `CURRENT_VERSION`.
+///
+/// # Errors
+///
+/// Returns an error if `config_version` is not a non-negative integer or is
+/// newer than this build understands.
+pub fn migrate(fields: &mut Map<String, Value>) -> Result<MigrationReport> {
+    let from_version = match fields.get(VERSION_KEY) {
+        None => 0,
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| {
+                format!(
+                    "{} must be a non-negative integer, got {}",
+                    VERSION_KEY, value
+                )
+            })?,
+    };
+    if from_version > CURRENT_VERSION {
+        bail!(
+            "Configuration version {} is newer than this archtui supports (up to {})",
+            from_version,
+            CURRENT_VERSION
+        );
+    }
+
+    let mut report = MigrationReport {
+        from_version,
+        changes: Vec::new(),
+    };
+    for migration in MIGRATIONS.iter().filter(|m| m.to > from_version) {
+        (migration.apply)(fields, &mut report.changes);
+    }
+    fields.insert(VERSION_KEY.to_string(), Value::from(CURRENT_VERSION));
+    Ok(report)
+}
+
+/// 0 → 1: replace the legacy names that used to be serde aliases.
+fn migrate_v1(fields: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    rename_field(fields, "btrfs_assistant", "snapshot_tool", changes);
+    rename_field(fields, "grub_themes", "grub_theme", changes);
+
+    // swap was a toggle before swap modes existed
+    if let Some(Value::String(swap)) = fields.get_mut("swap") {
+        let mode = match swap.as_str() {
+            "Yes" => "Partition",
+            "No" => "None",
+            _ => return,
+        };
+        changes.push(format!("swap: {} -> {}", swap, mode));
+        *swap = mode.to_string();
+    }
+}
+
+/// Move `old` to `new`; if both are set, `new` wins and `old` is dropped.
+fn rename_field(fields: &mut Map<String, Value>, old: &str, new: &str, changes: &mut Vec<String>) {
+    let Some(value) = fields.remove(old) else {
+        return;
+    };
+    if fields.contains_key(new) {
+        changes.push(format!("dropped {} (superseded by {})", old, new));
+    } else {
+        changes.push(format!("renamed {} -> {}", old, new));
+        fields.insert(new.to_string(), value);
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(fields) => fields,
+            other => panic!("expected an object, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_unversioned_config_is_upgraded() {
+        let mut fields = object(json!({
+            "btrfs_assistant": "Snapper",
+            "grub_themes": "Yes",
+            "swap": "Yes",
+            "hostname": "h1"
+        }));
+
+        let report = migrate(&mut fields).expect("migrate"); // test: known-good input
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.changes.len(), 3);
+        assert_eq!(fields["snapshot_tool"], "Snapper");
+        assert_eq!(fields["grub_theme"], "Yes");
+        assert_eq!(fields["swap"], "Partition");
+        assert_eq!(fields["hostname"], "h1");
+        assert_eq!(fields[VERSION_KEY], CURRENT_VERSION);
+        assert!(!fields.contains_key("btrfs_assistant"));
+        assert!(!fields.contains_key("grub_themes"));
+    }
+
+    #[test]
+    fn test_current_config_is_untouched() {
+        let original = object(json!({
+            "config_version": CURRENT_VERSION,
+            "snapshot_tool": "None",
+            "swap": "Zram"
+        }));
+        let mut fields = original.clone();
+
+        let report = migrate(&mut fields).expect("migrate"); // test: known-good input
+        assert!(report.is_empty());
+        assert_eq!(report.from_version, CURRENT_VERSION);
+        assert_eq!(fields, original);
+    }
+
+    #[test]
+    fn test_new_name_wins_over_legacy_name() {
+        let mut fields = object(json!({ "grub_themes": "Yes", "grub_theme": "No" }));
+
+        let report = migrate(&mut fields).expect("migrate"); // test: known-good input
+        assert_eq!(fields["grub_theme"], "No");
+        assert!(report.changes[0].starts_with("dropped grub_themes"));
+    }
+
+    #[test]
+    fn test_bad_versions_are_rejected() {
+        let mut newer = object(json!({ "config_version": CURRENT_VERSION + 1 }));
+        let err = migrate(&mut newer).expect_err("future version must fail");
+        assert!(err.to_string().contains("newer"));
+
+        let mut garbage = object(json!({ "config_version": "one" }));
+        assert!(migrate(&mut garbage).is_err());
+        let mut negative = object(json!({ "config_version": -1 }));
+        assert!(migrate(&mut negative).is_err());
+    }
+}
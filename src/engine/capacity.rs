@@ -0,0 +1,514 @@
+//! Disk Capacity Checks
+//!
+//! Rejects layouts that cannot fit on the target disk(s) before
+//! `InstallStage::PartitioningDisk` wipes anything. Sizes are parsed into
+//! `DiskSize`; capacities come from sysfs/lsblk through
+//! `hardware::detect_disk_size_mib`.
+//!
+//! `InstallationConfig::validate` only checks the size syntax
+//! (`check_size_fields`). `InstallationConfig::validate_for_hardware` adds
+//! the fit (`check_layout_fits_hardware`) whenever the probed inventory knows
+//! the target disks, which is what the `validate` command, dry-runs and the
+//! TUI use; a config validated on another machine only gets the syntax
+//! check. `check_disk_capacity` repeats the fit against sysfs/lsblk when an
+//! install or sandbox run starts, as a backstop for disk paths the inventory
+//! does not list (e.g. `/dev/disk/by-id/...`).
+//!
+//! The accounting mirrors the partitions `engine::storage` plans:
+//!
+//! | Item            | Space |
+//! |-----------------|-------|
+//! | Boot partitions | ESP 512 MiB (UEFI) or BIOS boot 1 MiB (BIOS) |
+//! | RAID boot       | ESP 512 MiB + boot array 1 GiB (UEFI, GRUB/rEFInd/Limine), ESP 1 GiB (systemd-boot/EFISTUB), BIOS boot 1 MiB + boot array 1 GiB (BIOS) |
+//! | Swap            | `swap_size` when swap lives on disk (partition, LV or swapfile) |
+//! | Root            | `root_size`; 50 GiB if "Remaining" while home is separate |
+//! | Home            | `home_size` when `separate_home` is enabled |
+//!
+//! Whatever takes the remaining space must still get `MIN_ROOT_SIZE_MIB`
+//! (root) or `MIN_HOME_SIZE_MIB` (home). RAID strategies partition every
+//! member alike, so the smallest member bounds the layout and the data area
+//! is scaled by what the RAID level leaves usable.
+//!
+//! `auto_alongside` (free space is checked by its own planner), `manual`
+//! without a `partition_layout` and `pre_mounted` are not checked.
+
+// Library API - consumed by config validation and the TUI
+#![allow(dead_code)]
+
+use crate::config_file::{InstallationConfig, LayoutPartition};
+use crate::engine::swap;
+use crate::hardware::HardwareInventory;
+use crate::types::{BootMode, DiskSize, PartitionScheme, Toggle};
+use anyhow::{Result, bail};
+use std::path::Path;
+
+/// EFI System Partition (matches `DEFAULT_ESP_SIZE_MIB`).
+pub const ESP_SIZE_MIB: u64 = 512;
+/// BIOS boot partition for GRUB on GPT (matches `BIOS_BOOT_PART_SIZE_MIB`).
+pub const BIOS_BOOT_SIZE_MIB: u64 = 1;
+/// `/boot` partition (matches `BOOT_PART_SIZE_MIB`).
+pub const BOOT_PART_SIZE_MIB: u64 = 1024;
+/// ESP that doubles as `/boot` on RAID with systemd-boot/EFISTUB (matches
+/// `RAID_ESP_BOOT_SIZE_MIB`).
+pub const RAID_ESP_BOOT_SIZE_MIB: u64 = 1024;
+/// Root size when root is "Remaining" but home is separate (matches
+/// `DEFAULT_ROOT_SIZE_MIB`).
+pub const DEFAULT_ROOT_SIZE_MIB: u64 = 51200;
+/// Smallest root worth installing to — base system, kernels and a desktop.
+pub const MIN_ROOT_SIZE_MIB: u64 = 8192;
+/// Smallest home partition left over for a "Remaining" home.
+pub const MIN_HOME_SIZE_MIB: u64 = 1024;
+/// Slack for GPT headers, alignment and LUKS/LVM/mdadm metadata.
+const METADATA_RESERVE_MIB: u64 = 16;
+
+/// Size specification for `mib` MiB, as sgdisk and lvcreate take it ("512M").
+pub fn size_spec(mib: u64) -> String {
+    format!("{}M", mib)
+}
+
+/// Parse `root_size`/`home_size`. "N/A" (a TUI placeholder) counts as
+/// "Remaining".
+pub fn parse_size_field(field: &str, value: &str) -> Result<DiskSize> {
+    if value.trim().eq_ignore_ascii_case("N/A") {
+        return Ok(DiskSize::Remaining);
+    }
+    match value.parse::<DiskSize>() {
+        Ok(size) => Ok(size),
+        Err(e) => bail!("{}: {}", field, e),
+    }
+}
+
+/// Check that `root_size` (and `home_size`, when home is separate) parse.
+pub fn check_size_fields(config: &InstallationConfig) -> Result<()> {
+    parse_size_field("root_size", &config.root_size)?;
+    if config.separate_home == Toggle::Yes {
+        parse_size_field("home_size", &config.home_size)?;
+    }
+    Ok(())
+}
+
+/// Check the configured sizes and that they fit the target disk(s) of this
+/// machine.
+///
+/// Size syntax is always checked. The fit itself is skipped with a warning
+/// when a disk's capacity cannot be read.
+///
+/// # Errors
+///
+/// Returns an error for unparseable sizes and for layouts larger than the
+/// target disk.
+pub fn check_disk_capacity(config: &InstallationConfig) -> Result<()> {
+    check_size_fields(config)?;
+    if !is_checked(config) {
+        return Ok(());
+    }
+
+    let mut capacities = Vec::new();
+    for disk in target_disks(config) {
+        match crate::hardware::detect_disk_size_mib(Path::new(disk)) {
+            Some(mib) => capacities.push(mib),
+            None => {
+                tracing::warn!(disk = %disk, "Disk capacity unknown — skipping size check");
+                return Ok(());
+            }
+        }
+    }
+    if capacities.is_empty() {
+        return Ok(());
+    }
+
+    let ram_mib = if config.swap.has_disk_swap() && swap::is_ram_relative(&config.swap_size) {
+        crate::hardware::detect_ram_mib()
+    } else {
+        None
+    };
+    check_layout_fits(config, &capacities, ram_mib)
+}
+
+/// `check_layout_fits` against the disks and RAM of a probed inventory.
+///
+/// Skipped when a target disk is not in the inventory, so configs for
+/// another machine still validate.
+pub fn check_layout_fits_hardware(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Result<()> {
+    let mut capacities = Vec::new();
+    for disk in target_disks(config) {
+        match hardware.disks.iter().find(|d| d.path() == Path::new(disk)) {
+            Some(device) => capacities.push(device.size_mib),
+            None => {
+                tracing::debug!(disk = %disk, "Target disk not in inventory — skipping size check");
+                return Ok(());
+            }
+        }
+    }
+    check_layout_fits(config, &capacities, hardware.ram_mib)
+}
+
+/// `check_disk_capacity` against capacities (MiB, one per target disk) and
+/// RAM supplied by the caller.
+pub fn check_layout_fits(
+    config: &InstallationConfig,
+    disk_capacities_mib: &[u64],
+    ram_mib: Option<u64>,
+) -> Result<()> {
+    let Some(&disk_mib) = disk_capacities_mib.iter().min() else {
+        return Ok(());
+    };
+    if !is_checked(config) {
+        return Ok(());
+    }
+    if config.partitioning_strategy == PartitionScheme::Manual {
+        return check_custom_layout_fits(&config.partition_layout, disk_mib);
+    }
+
+    let overhead_mib = boot_partitions_mib(config) + METADATA_RESERVE_MIB;
+    if disk_mib <= overhead_mib + MIN_ROOT_SIZE_MIB {
+        bail!(
+            "Target disk is too small: {} (need at least {})",
+            format_mib(disk_mib),
+            format_mib(overhead_mib + MIN_ROOT_SIZE_MIB)
+        );
+    }
+    let data_mib = raid_usable_mib(config, disk_mib - overhead_mib, disk_capacities_mib.len());
+
+    let swap_mib = if config.swap.has_disk_swap() {
+        let spec = swap::swap_size_spec(
+            &config.swap_size,
+            ram_mib,
+            config.hibernation == Toggle::Yes,
+        )?;
+        spec.parse::<DiskSize>()
+            .ok()
+            .and_then(|size| size.resolve_mib(disk_mib))
+            .unwrap_or(swap::DEFAULT_SWAP_SIZE_MIB)
+    } else {
+        0
+    };
+
+    let separate_home = config.separate_home == Toggle::Yes;
+    let mut root = parse_size_field("root_size", &config.root_size)?;
+    if separate_home && root == DiskSize::Remaining {
+        root = DiskSize::Mib(DEFAULT_ROOT_SIZE_MIB);
+    }
+    let home = if separate_home {
+        Some(parse_size_field("home_size", &config.home_size)?)
+    } else {
+        None
+    };
+
+    let root_mib = root.resolve_mib(disk_mib);
+    let home_mib = home.and_then(|h| h.resolve_mib(disk_mib));
+    if let Some(root_mib) = root_mib
+        && root_mib < MIN_ROOT_SIZE_MIB
+    {
+        bail!(
+            "root_size {} is below the {} minimum",
+            format_mib(root_mib),
+            format_mib(MIN_ROOT_SIZE_MIB)
+        );
+    }
+
+    let fixed_mib = swap_mib + root_mib.unwrap_or(0) + home_mib.unwrap_or(0);
+    let (remaining_min, remaining_for) = match (root_mib, home, home_mib) {
+        (None, _, _) => (MIN_ROOT_SIZE_MIB, Some("root")),
+        (Some(_), Some(_), None) => (MIN_HOME_SIZE_MIB, Some("home")),
+        _ => (0, None),
+    };
+    if fixed_mib + remaining_min > data_mib {
+        let mut parts = vec![format!("root {}", describe(root_mib, MIN_ROOT_SIZE_MIB))];
+        if home.is_some() {
+            parts.push(format!("home {}", describe(home_mib, MIN_HOME_SIZE_MIB)));
+        }
+        if swap_mib > 0 {
+            parts.push(format!("swap {}", format_mib(swap_mib)));
+        }
+        bail!(
+            "Layout needs {} ({}) but only {} is usable on {}{}",
+            format_mib(fixed_mib + remaining_min),
+            parts.join(", "),
+            format_mib(data_mib),
+            config.install_disk,
+            remaining_for
+                .map(|p| format!(" — {} takes the remaining space", p))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Check that a declarative `partition_layout` fits a disk of `disk_mib`.
+fn check_custom_layout_fits(layout: &[LayoutPartition], disk_mib: u64) -> Result<()> {
+    let mut fixed_mib = 0;
+    let mut has_remaining = false;
+    for partition in layout {
+        match parse_size_field("partition_layout size", &partition.size)?.resolve_mib(disk_mib) {
+            Some(mib) => fixed_mib += mib,
+            None => has_remaining = true,
+        }
+    }
+    let needed_mib = fixed_mib + METADATA_RESERVE_MIB + u64::from(has_remaining);
+    if needed_mib > disk_mib {
+        bail!(
+            "partition_layout needs {} but the target disk holds {}",
+            format_mib(needed_mib),
+            format_mib(disk_mib)
+        );
+    }
+    Ok(())
+}
+
+/// Whether the strategy lays out whole disks this module can account for.
+/// Space the planned ESP/BIOS boot and boot-array partitions take per disk.
+fn boot_partitions_mib(config: &InstallationConfig) -> u64 {
+    let is_uefi = matches!(config.boot_mode, BootMode::Uefi | BootMode::Auto);
+    if !config.partitioning_strategy.requires_raid() {
+        return if is_uefi {
+            ESP_SIZE_MIB
+        } else {
+            BIOS_BOOT_SIZE_MIB
+        };
+    }
+    // RAID members carry a boot array unless the ESP holds the kernels
+    match (is_uefi, config.bootloader.requires_fat32_boot()) {
+        (false, _) => BIOS_BOOT_SIZE_MIB + BOOT_PART_SIZE_MIB,
+        (true, true) => RAID_ESP_BOOT_SIZE_MIB,
+        (true, false) => ESP_SIZE_MIB + BOOT_PART_SIZE_MIB,
+    }
+}
+
+fn is_checked(config: &InstallationConfig) -> bool {
+    match config.partitioning_strategy {
+        PartitionScheme::Manual => !config.partition_layout.is_empty(),
+        PartitionScheme::AutoAlongside | PartitionScheme::PreMounted => false,
+        _ => true,
+    }
+}
+
+/// Disks that get partitioned (`install_disk` is comma-separated for RAID).
+fn target_disks(config: &InstallationConfig) -> Vec<&str> {
+    config
+        .install_disk
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// Usable space of `members` RAID members with `member_mib` of data each;
+/// non-RAID strategies get `member_mib` back.
+fn raid_usable_mib(config: &InstallationConfig, member_mib: u64, members: usize) -> u64 {
+    if !config.partitioning_strategy.requires_raid() {
+        return member_mib;
+    }
+    let n = members as u64;
+    let data_members = match config.raid_level.as_str() {
+        "raid0" => n,
+        "raid5" => n.saturating_sub(1),
+        "raid6" => n.saturating_sub(2),
+        "raid10" => n / 2,
+        _ => 1,
+    };
+    member_mib * data_members.max(1)
+}
+
+/// A fixed size, or "remaining (min X)" for the partition taking the rest.
+fn describe(mib: Option<u64>, min_mib: u64) -> String {
+    match mib {
+        Some(mib) => format_mib(mib),
+        None => format!("remaining, min {}", format_mib(min_mib)),
+    }
+}
+
+/// Human-readable size ("512 MiB", "237.5 GiB").
+pub(crate) fn format_mib(mib: u64) -> String {
+    if mib < 1024 {
+        format!("{} MiB", mib)
+    } else {
+        format!("{:.1} GiB", mib as f64 / 1024.0)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripts::disk::PartitionType;
+    use crate::types::SwapMode;
+
+    const DISK_256G: u64 = 256 * 1024;
+
+    fn test_config(strategy: PartitionScheme) -> InstallationConfig {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.partitioning_strategy = strategy;
+        config.boot_mode = BootMode::Uefi;
+        config.swap = SwapMode::Partition;
+        config.swap_size = "8GB".to_string();
+        config.root_size = "50GB".to_string();
+        config
+    }
+
+    #[test]
+    fn test_default_layout_fits() {
+        let config = test_config(PartitionScheme::AutoLvm);
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_ok());
+    }
+
+    #[test]
+    fn test_boot_partitions_follow_the_planned_layout() {
+        let mut config = test_config(PartitionScheme::AutoSimple);
+        assert_eq!(boot_partitions_mib(&config), ESP_SIZE_MIB);
+        config.boot_mode = BootMode::Bios;
+        assert_eq!(boot_partitions_mib(&config), BIOS_BOOT_SIZE_MIB);
+
+        config.partitioning_strategy = PartitionScheme::AutoRaid;
+        assert_eq!(
+            boot_partitions_mib(&config),
+            BIOS_BOOT_SIZE_MIB + BOOT_PART_SIZE_MIB
+        );
+        config.boot_mode = BootMode::Uefi;
+        config.bootloader = crate::types::Bootloader::Grub;
+        assert_eq!(
+            boot_partitions_mib(&config),
+            ESP_SIZE_MIB + BOOT_PART_SIZE_MIB
+        );
+        config.bootloader = crate::types::Bootloader::SystemdBoot;
+        assert_eq!(boot_partitions_mib(&config), RAID_ESP_BOOT_SIZE_MIB);
+    }
+
+    #[test]
+    fn test_oversized_root_is_rejected() {
+        let mut config = test_config(PartitionScheme::AutoSimple);
+        config.root_size = "900GB".to_string();
+        let err = check_layout_fits(&config, &[DISK_256G], None).expect_err("900GB on 256GB");
+        assert!(err.to_string().contains("root 900.0 GiB"), "{err}");
+    }
+
+    #[test]
+    fn test_home_and_swap_count_against_the_disk() {
+        let mut config = test_config(PartitionScheme::AutoLuksLvm);
+        config.separate_home = Toggle::Yes;
+        config.root_size = "100GB".to_string();
+        config.home_size = "150GB".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_err());
+
+        config.swap = SwapMode::None;
+        config.home_size = "140GB".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_ok());
+    }
+
+    #[test]
+    fn test_remaining_partition_needs_minimum_space() {
+        let mut config = test_config(PartitionScheme::AutoSimple);
+        config.root_size = "Remaining".to_string();
+        // 16 GiB disk: boot + 8 GiB swap leave less than MIN_ROOT_SIZE_MIB
+        assert!(check_layout_fits(&config, &[16 * 1024], None).is_err());
+        assert!(check_layout_fits(&config, &[32 * 1024], None).is_ok());
+
+        // test: Remaining root with separate home falls back to the 50 GiB default
+        config.separate_home = Toggle::Yes;
+        config.home_size = "Remaining".to_string();
+        assert!(check_layout_fits(&config, &[48 * 1024], None).is_err());
+        assert!(check_layout_fits(&config, &[64 * 1024], None).is_ok());
+    }
+
+    #[test]
+    fn test_percent_sizes_resolve_against_the_disk() {
+        let mut config = test_config(PartitionScheme::AutoLvm);
+        config.separate_home = Toggle::Yes;
+        config.root_size = "40%".to_string();
+        config.home_size = "50%".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_ok());
+
+        config.home_size = "60%".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_err());
+    }
+
+    #[test]
+    fn test_ram_relative_swap_uses_detected_ram() {
+        let mut config = test_config(PartitionScheme::AutoSimple);
+        config.swap_size = "Equal to RAM".to_string();
+        config.root_size = "60GB".to_string();
+        assert!(check_layout_fits(&config, &[64 * 1024], Some(2048)).is_ok());
+        assert!(check_layout_fits(&config, &[64 * 1024], Some(16384)).is_err());
+    }
+
+    #[test]
+    fn test_raid_uses_smallest_member_and_level() {
+        let mut config = test_config(PartitionScheme::AutoRaid);
+        config.install_disk = "/dev/sda,/dev/sdb".to_string();
+        config.root_size = "200GB".to_string();
+        config.raid_level = "raid1".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G, DISK_256G], None).is_ok());
+        assert!(check_layout_fits(&config, &[DISK_256G, 128 * 1024], None).is_err());
+
+        // test: raid0 stripes both members, doubling the usable space
+        config.root_size = "400GB".to_string();
+        config.raid_level = "raid0".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G, DISK_256G], None).is_ok());
+    }
+
+    #[test]
+    fn test_custom_layout_sums_partition_sizes() {
+        let mut config = test_config(PartitionScheme::Manual);
+        let part = |size: &str, partition_type| LayoutPartition {
+            size: size.to_string(),
+            partition_type,
+            filesystem: None,
+            label: None,
+            mountpoint: None,
+            mount_options: None,
+            encrypt: false,
+        };
+        config.partition_layout = vec![
+            part("512M", PartitionType::Efi),
+            part("300G", PartitionType::Linux),
+        ];
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_err());
+        assert!(check_layout_fits(&config, &[512 * 1024], None).is_ok());
+    }
+
+    #[test]
+    fn test_fit_against_inventory_skips_unknown_disks() {
+        use crate::hardware::BlockDevice;
+
+        let mut config = test_config(PartitionScheme::AutoSimple);
+        config.root_size = "900GB".to_string();
+        let disk = |name: &str| BlockDevice {
+            name: name.to_string(),
+            size_mib: DISK_256G,
+            rotational: false,
+            removable: false,
+            model: None,
+        };
+        let hardware = HardwareInventory {
+            disks: vec![disk("sda")],
+            ..Default::default()
+        };
+        assert!(check_layout_fits_hardware(&config, &hardware).is_err());
+
+        // test: a disk this machine does not have is left to install start
+        config.install_disk = "/dev/nvme0n1".to_string();
+        assert!(check_layout_fits_hardware(&config, &hardware).is_ok());
+        config.root_size = "50GB".to_string();
+        config.install_disk = "/dev/sda".to_string();
+        assert!(check_layout_fits_hardware(&config, &hardware).is_ok());
+    }
+
+    #[test]
+    fn test_unchecked_strategies_and_bad_sizes() {
+        let mut config = test_config(PartitionScheme::AutoAlongside);
+        config.root_size = "900GB".to_string();
+        assert!(check_layout_fits(&config, &[DISK_256G], None).is_ok());
+
+        config.root_size = "lots".to_string();
+        assert!(check_size_fields(&config).is_err());
+        config.root_size = "N/A".to_string();
+        assert!(check_size_fields(&config).is_ok());
+    }
+}
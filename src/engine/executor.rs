@@ -0,0 +1,1229 @@
+//! Storage Plan Executor
+//!
+//! Walks a `StoragePlan` in order and runs each `StorageOp` through the typed
+//! `ScriptArgs` structs and `run_script_safe`. It drives the sandbox
+//! (`archtui sandbox`), the dry-run preview and real installs: `install.sh`
+//! partitions every automatic strategy and declared `partition_layout` by
+//! running `archtui apply-storage` (`scripts/strategies/storage_plan.sh`) on
+//! the plan archtui computed.
+//!
+//! # Op → Script Mapping
+//!
+//! | StorageOp             | Script(s)                                        |
+//! |-----------------------|--------------------------------------------------|
+//! | `WipeDisk`            | `wipe_disk.sh`                                   |
+//! | `Partition`           | `manual_partition.sh` create-table + add-partition |
+//! | `PartitionRaidMember` | `manual_partition.sh` create-table + add-partition |
+//! | `PartitionLayout`     | `manual_partition.sh` create-table + add-partition |
+//! | `ShrinkPartition`     | `free_space.sh --action shrink`                  |
+//! | `CreatePartitionInFreeSpace` | `free_space.sh --action create`           |
+//! | `CreateRaidArray`     | `manage_raid.sh --action create`                 |
+//! | `WaitRaidSync`        | `manage_raid.sh --action wait`                   |
+//! | `WriteMdadmConf`      | `manage_raid.sh --action write-conf`             |
+//! | `LuksFormat`          | `encrypt_device.sh --action format`              |
+//! | `LuksOpen`            | `encrypt_device.sh --action open`                |
+//! | `CreateLvmPv/Vg/Lv`   | `manage_lvm.sh`                                  |
+//! | `FormatFs`            | `format_partition.sh`                            |
+//! | `CreateBtrfsSubvolumes` | `manage_btrfs.sh --action create-subvolumes`   |
+//! | `Mount`               | `mount_partitions.sh --action mount`             |
+//! | `MountBtrfsSubvolume` | `mount_partitions.sh --action mount` (`subvol=`) |
+//! | `CreateSwapfile`      | `manage_swap.sh --action create-swapfile`        |
+//!
+//! # Failure Policy
+//!
+//! Execution stops at the first failing op. The failing op is reported as
+//! `Failed`, every later op as `Skipped`, and an error naming the op is
+//! returned. Unless disabled, the live state left by the ops that did succeed
+//! (mounts, active VGs, open mappers, running arrays) is then released via
+//! `StoragePlan::teardown` so a retry starts clean. On-disk changes stay.
+//! `apply-storage` keeps the default, so a failed install leaves no live
+//! storage state for `install.sh`'s `cleanup_on_exit` trap to trip over.
+//!
+//! # Dry-Run
+//!
+//! Destructive steps go through `run_script_safe`, which logs them without
+//! executing. Non-destructive steps (mount, LUKS open, RAID wait, subvolume
+//! creation) would act on devices that were never created, so the executor
+//! skips them instead.
+
+// Library API - consumed by installer orchestration
+#![allow(dead_code)]
+
+use crate::engine::capacity::{
+    BIOS_BOOT_SIZE_MIB, BOOT_PART_SIZE_MIB, ESP_SIZE_MIB, RAID_ESP_BOOT_SIZE_MIB, size_spec,
+};
+use crate::engine::storage::{LayoutEntry, StorageOp, StoragePlan, partition_path};
+use crate::script_runner::{ScriptOutput, run_script_safe};
+use crate::script_traits::{ScriptArgs, is_dry_run};
+use crate::scripts::disk::{
+    AddPartitionArgs, CreateTableArgs, FormatPartitionArgs, FreeSpacePartitionArgs,
+    MountPartitionArgs, PartitionType, ShrinkPartitionArgs, TableType, WipeDiskArgs, WipeMethod,
+};
+use crate::scripts::encryption::{LuksCipher, LuksFormatArgs, LuksOpenArgs, SecretFile};
+use crate::scripts::storage::{
+    BtrfsSubvolumeCreateArgs, LvmLvCreateArgs, LvmPvCreateArgs, LvmVgCreateArgs, RaidCreateArgs,
+    RaidWaitArgs, RaidWriteConfArgs, SwapfileCreateArgs, TeardownStorageArgs,
+};
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Options & Status
+// ============================================================================
+
+/// Runtime inputs the plan itself does not carry.
+#[derive(Clone)]
+pub struct ExecutorOptions {
+    /// Wipe method for `WipeDisk` ops.
+    pub wipe_method: WipeMethod,
+    /// LUKS passphrase (required when the plan is encrypted).
+    pub encryption_password: Option<String>,
+    /// LUKS cipher for `LuksFormat` ops.
+    pub luks_cipher: LuksCipher,
+    /// Tear down the ops that succeeded when a later op fails.
+    pub teardown_on_failure: bool,
+}
+
+// Custom Debug impl redacts password field
+impl fmt::Debug for ExecutorOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutorOptions")
+            .field("wipe_method", &self.wipe_method)
+            .field(
+                "encryption_password",
+                &self.encryption_password.as_ref().map(|_| "********"),
+            )
+            .field("luks_cipher", &self.luks_cipher)
+            .field("teardown_on_failure", &self.teardown_on_failure)
+            .finish()
+    }
+}
+
+impl Default for ExecutorOptions {
+    fn default() -> Self {
+        Self {
+            wipe_method: WipeMethod::Quick,
+            encryption_password: None,
+            luks_cipher: LuksCipher::default(),
+            teardown_on_failure: true,
+        }
+    }
+}
+
+/// Status of a single op during execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpStatus {
+    /// The op is about to run.
+    Started,
+    /// Every script for the op completed successfully.
+    Succeeded,
+    /// The op was not run (dry-run, or an earlier op failed).
+    Skipped,
+    /// A script for the op failed; carries the error message.
+    Failed(String),
+}
+
+impl fmt::Display for OpStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Started => write!(f, "started"),
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// A status update for one op, passed to the caller's status callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpReport {
+    /// Zero-based position of the op in `StoragePlan::ops`.
+    pub index: usize,
+    /// Total number of ops in the plan.
+    pub total: usize,
+    /// The op this report refers to.
+    pub op: StorageOp,
+    /// Current status of the op.
+    pub status: OpStatus,
+}
+
+impl fmt::Display for OpReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}/{}] {}: {}",
+            self.index + 1,
+            self.total,
+            self.op,
+            self.status
+        )
+    }
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+/// Execute a storage plan, op by op, through the typed script layer.
+///
+/// `on_status` is called with `Started` before each op and with its final
+/// status afterwards; ops that never run are reported once as `Skipped`.
+///
+/// # Returns
+///
+/// - `Ok(reports)` - Final status of every op (all `Succeeded` or `Skipped`)
+/// - `Err` - Missing inputs (e.g. no LUKS password), or the first op that failed
+///
+/// # Example
+///
+/// ```ignore
+/// let plan = calculate_storage_plan(&config)?;
+/// execute_storage_plan(&plan, &ExecutorOptions::default(), |report| {
+///     tracing::info!("{}", report);
+/// })?;
+/// ```
+pub fn execute_storage_plan(
+    plan: &StoragePlan,
+    options: &ExecutorOptions,
+    on_status: impl FnMut(&OpReport),
+) -> Result<Vec<OpReport>> {
+    execute_with_runner(plan, options, is_dry_run(), on_status, |args| {
+        run_script_safe(args)
+    })
+}
+
+/// Execution core with an injectable script runner (for tests).
+fn execute_with_runner(
+    plan: &StoragePlan,
+    options: &ExecutorOptions,
+    dry_run: bool,
+    mut on_status: impl FnMut(&OpReport),
+    mut run: impl FnMut(&dyn ScriptArgs) -> Result<ScriptOutput>,
+) -> Result<Vec<OpReport>> {
+    // SECURITY: One keyfile for the whole run; SecretFile wipes it on drop.
+    // Created up front so a missing password fails before anything is wiped.
+    let keyfile = if plan.encrypted {
+        let password = match options.encryption_password.as_deref() {
+            Some(p) if !p.is_empty() => p,
+            _ => bail!("Storage plan is encrypted but no encryption password was provided"),
+        };
+        Some(SecretFile::new(password).context("Failed to create temporary keyfile")?)
+    } else {
+        None
+    };
+    let key_file = keyfile.as_ref().map(|k| k.path());
+
+    let total = plan.ops.len();
+    tracing::info!(strategy = ?plan.strategy, ops = total, dry_run, "Executing storage plan");
+
+    let mut reports = Vec::with_capacity(total);
+    let mut failure: Option<(usize, anyhow::Error)> = None;
+
+    for (index, op) in plan.ops.iter().enumerate() {
+        let report = |status: OpStatus| OpReport {
+            index,
+            total,
+            op: op.clone(),
+            status,
+        };
+
+        if failure.is_some() {
+            let skipped = report(OpStatus::Skipped);
+            on_status(&skipped);
+            reports.push(skipped);
+            continue;
+        }
+
+        on_status(&report(OpStatus::Started));
+        tracing::info!(step = index + 1, total, op = %op, "Storage op started");
+
+        let outcome = script_steps(op, plan, options, key_file).and_then(|steps| {
+            let mut ran_any = false;
+            for step in &steps {
+                if dry_run && !step.is_destructive() {
+                    tracing::info!(
+                        script = step.script_name(),
+                        "[DRY RUN] Skipping non-destructive step"
+                    );
+                    continue;
+                }
+                let output = run(step.as_ref())
+                    .with_context(|| format!("Failed to execute {}", step.script_name()))?;
+                output.ensure_success(step.script_name())?;
+                ran_any = true;
+            }
+            Ok(ran_any)
+        });
+
+        let final_report = match outcome {
+            Ok(true) => report(OpStatus::Succeeded),
+            Ok(false) => report(OpStatus::Skipped),
+            Err(e) => {
+                tracing::error!(step = index + 1, op = %op, error = %e, "Storage op failed");
+                let failed = report(OpStatus::Failed(format!("{:#}", e)));
+                let e = e.context(format!("Storage op {}/{} failed: {}", index + 1, total, op));
+                failure = Some((index, e));
+                failed
+            }
+        };
+        on_status(&final_report);
+        reports.push(final_report);
+    }
+
+    // Keyfile must outlive every LUKS step above
+    drop(keyfile);
+
+    match failure {
+        Some((failed_index, e)) => {
+            if options.teardown_on_failure {
+                teardown_applied(plan, failed_index, &mut run);
+            }
+            Err(e)
+        }
+        None => {
+            tracing::info!(ops = total, "Storage plan executed successfully");
+            Ok(reports)
+        }
+    }
+}
+
+/// Best-effort release of the live state left by the first `applied` ops.
+///
+/// Teardown failures are logged, never returned: the caller is already
+/// handling the original error, which is the one worth reporting.
+fn teardown_applied(
+    plan: &StoragePlan,
+    applied: usize,
+    run: &mut impl FnMut(&dyn ScriptArgs) -> Result<ScriptOutput>,
+) {
+    let ops = plan.teardown(applied);
+    if ops.is_empty() {
+        return;
+    }
+    tracing::warn!(
+        steps = ops.len(),
+        "Tearing down partially-applied storage plan"
+    );
+    let args = TeardownStorageArgs { ops, confirm: true };
+    match run(&args).and_then(|output| output.ensure_success("storage teardown")) {
+        Ok(()) => tracing::info!("Storage teardown complete"),
+        Err(e) => {
+            tracing::warn!(error = %e, "Storage teardown incomplete — run Disk Tools cleanup")
+        }
+    }
+}
+
+// ============================================================================
+// Op → ScriptArgs Mapping
+// ============================================================================
+
+/// Translate one op into the ordered script invocations that implement it.
+fn script_steps(
+    op: &StorageOp,
+    plan: &StoragePlan,
+    options: &ExecutorOptions,
+    key_file: Option<&Path>,
+) -> Result<Vec<Box<dyn ScriptArgs>>> {
+    let steps: Vec<Box<dyn ScriptArgs>> = match op {
+        StorageOp::WipeDisk { disk } => vec![Box::new(WipeDiskArgs {
+            device: disk.clone(),
+            method: options.wipe_method,
+            confirm: true,
+        })],
+        StorageOp::Partition {
+            disk,
+            create_efi,
+            swap_size,
+            root_size,
+            home_size,
+        } => partition_steps(
+            plan,
+            disk,
+            *create_efi,
+            swap_size.as_deref(),
+            root_size.as_deref(),
+            home_size.as_deref(),
+        ),
+        StorageOp::PartitionRaidMember {
+            disk,
+            create_efi,
+            create_boot,
+        } => raid_member_steps(disk, *create_efi, *create_boot),
+        StorageOp::PartitionLayout {
+            disk,
+            gpt,
+            partitions,
+        } => layout_steps(disk, *gpt, partitions)?,
+        StorageOp::ShrinkPartition { device, size } => vec![Box::new(ShrinkPartitionArgs {
+            partition: device.clone(),
+            size: size.clone(),
+            confirm: true,
+        })],
+        StorageOp::CreatePartitionInFreeSpace {
+            disk,
+            size,
+            partition_type,
+            label,
+        } => vec![Box::new(FreeSpacePartitionArgs {
+            device: disk.clone(),
+            size: size.clone(),
+            partition_type: *partition_type,
+            label: label.clone(),
+            confirm: true,
+        })],
+        StorageOp::CreateRaidArray {
+            device,
+            level,
+            members,
+        } => vec![Box::new(RaidCreateArgs {
+            device: device.clone(),
+            level: level.clone(),
+            members: members.clone(),
+            confirm: true,
+        })],
+        StorageOp::WaitRaidSync { device } => vec![Box::new(RaidWaitArgs {
+            device: device.clone(),
+        })],
+        StorageOp::WriteMdadmConf { path } => {
+            vec![Box::new(RaidWriteConfArgs {
+                path: path.clone(),
+                confirm: true,
+            })]
+        }
+        StorageOp::LuksFormat { device, label } => vec![Box::new(LuksFormatArgs {
+            device: device.clone(),
+            cipher: options.luks_cipher,
+            key_file: require_key_file(key_file)?,
+            label: Some(label.clone()),
+            confirm: true,
+        })],
+        StorageOp::LuksOpen {
+            device,
+            mapper_name,
+        } => vec![Box::new(LuksOpenArgs {
+            device: device.clone(),
+            mapper_name: mapper_name.clone(),
+            key_file: require_key_file(key_file)?,
+        })],
+        StorageOp::CreateLvmPv { device } => vec![Box::new(LvmPvCreateArgs {
+            device: device.clone(),
+            confirm: true,
+        })],
+        StorageOp::CreateLvmVg { vg_name, pv_device } => vec![Box::new(LvmVgCreateArgs {
+            vg_name: vg_name.clone(),
+            device: pv_device.clone(),
+            confirm: true,
+        })],
+        StorageOp::CreateLvmLv {
+            vg_name,
+            lv_name,
+            size,
+        } => vec![Box::new(LvmLvCreateArgs {
+            vg_name: vg_name.clone(),
+            lv_name: lv_name.clone(),
+            size: size.clone(),
+            confirm: true,
+        })],
+        StorageOp::FormatFs {
+            device,
+            filesystem,
+            label,
+        } => vec![Box::new(FormatPartitionArgs {
+            device: device.clone(),
+            filesystem: *filesystem,
+            label: label.clone(),
+            force: false,
+            confirm: true,
+        })],
+        StorageOp::CreateBtrfsSubvolumes { device, subvolumes } => {
+            vec![Box::new(BtrfsSubvolumeCreateArgs {
+                device: device.clone(),
+                subvolumes: subvolumes.clone(),
+            })]
+        }
+        StorageOp::Mount {
+            device,
+            mountpoint,
+            options,
+        } => vec![Box::new(MountPartitionArgs {
+            device: device.clone(),
+            mountpoint: mountpoint.clone(),
+            options: options.clone(),
+        })],
+        StorageOp::MountBtrfsSubvolume {
+            device,
+            subvolume,
+            mountpoint,
+            options,
+        } => vec![Box::new(MountPartitionArgs {
+            device: device.clone(),
+            mountpoint: mountpoint.clone(),
+            options: Some(if options.is_empty() {
+                format!("subvol={}", subvolume)
+            } else {
+                format!("subvol={},{}", subvolume, options)
+            }),
+        })],
+        StorageOp::CreateSwapfile { path, size } => vec![Box::new(SwapfileCreateArgs {
+            path: path.clone(),
+            size: size.clone(),
+            confirm: true,
+        })],
+    };
+    Ok(steps)
+}
+
+fn require_key_file(key_file: Option<&Path>) -> Result<PathBuf> {
+    key_file
+        .map(Path::to_path_buf)
+        .context("LUKS op in a plan not marked as encrypted — no keyfile available")
+}
+
+// ============================================================================
+// Planning (dry-run transcript)
+// ============================================================================
+
+/// Keyfile path shown in place of the `SecretFile` a real run creates.
+pub const PLACEHOLDER_KEY_FILE: &str = "<keyfile>";
+
+/// One op and the script invocations that implement it.
+pub struct PlannedOp {
+    /// The op, as it appears in `StoragePlan::ops`.
+    pub op: StorageOp,
+    /// Script invocations in execution order.
+    pub steps: Vec<Box<dyn ScriptArgs>>,
+}
+
+/// Every script invocation `execute_storage_plan` would make, without
+/// running anything.
+///
+/// Unlike execution, no passphrase is required: LUKS steps reference
+/// [`PLACEHOLDER_KEY_FILE`] instead of a real keyfile.
+pub fn plan_steps(plan: &StoragePlan, options: &ExecutorOptions) -> Result<Vec<PlannedOp>> {
+    let key_file = plan.encrypted.then(|| Path::new(PLACEHOLDER_KEY_FILE));
+    plan.ops
+        .iter()
+        .map(|op| {
+            let steps = script_steps(op, plan, options, key_file)
+                .with_context(|| format!("Failed to plan storage op: {}", op))?;
+            Ok(PlannedOp {
+                op: op.clone(),
+                steps,
+            })
+        })
+        .collect()
+}
+
+/// Single-disk layout: ESP or BIOS boot + [swap] + root + [home].
+///
+/// Always GPT: BIOS installs get an EF02 partition for GRUB's core image.
+/// Root takes the remaining space unless a home partition follows it. The
+/// root and home partition types follow whatever the plan puts on them
+/// (LUKS, LVM, or plain).
+fn partition_steps(
+    plan: &StoragePlan,
+    disk: &Path,
+    create_efi: bool,
+    swap_size: Option<&str>,
+    root_size: Option<&str>,
+    home_size: Option<&str>,
+) -> Vec<Box<dyn ScriptArgs>> {
+    let mut steps: Vec<Box<dyn ScriptArgs>> = vec![Box::new(CreateTableArgs {
+        device: disk.to_path_buf(),
+        table_type: TableType::Gpt,
+        confirm: true,
+    })];
+
+    if create_efi {
+        steps.push(add_partition(
+            disk,
+            1,
+            &size_spec(ESP_SIZE_MIB),
+            PartitionType::Efi,
+            "EFI",
+        ));
+    } else {
+        steps.push(add_partition(
+            disk,
+            1,
+            &size_spec(BIOS_BOOT_SIZE_MIB),
+            PartitionType::BiosBoot,
+            "BIOSBOOT",
+        ));
+    }
+    let mut number = 2;
+    if let Some(size) = swap_size {
+        steps.push(add_partition(
+            disk,
+            number,
+            size,
+            PartitionType::Swap,
+            "SWAP",
+        ));
+        number += 1;
+    }
+    let root_type = data_partition_type(plan, &partition_path(disk, u32::from(number)));
+    steps.push(add_partition(
+        disk,
+        number,
+        root_size.unwrap_or("remaining"),
+        root_type,
+        "ROOT",
+    ));
+    if let Some(size) = home_size {
+        number += 1;
+        let home_type = data_partition_type(plan, &partition_path(disk, u32::from(number)));
+        steps.push(add_partition(disk, number, size, home_type, "HOME"));
+    }
+    steps
+}
+
+/// RAID member layout: ESP or BIOS boot + [boot member] + data member.
+///
+/// Always GPT: BIOS installs need an EF02 partition for GRUB on every member.
+fn raid_member_steps(disk: &Path, create_efi: bool, create_boot: bool) -> Vec<Box<dyn ScriptArgs>> {
+    let mut steps: Vec<Box<dyn ScriptArgs>> = vec![Box::new(CreateTableArgs {
+        device: disk.to_path_buf(),
+        table_type: TableType::Gpt,
+        confirm: true,
+    })];
+
+    if create_efi {
+        // Without a boot array the ESP holds the kernels too
+        let esp_mib = if create_boot {
+            ESP_SIZE_MIB
+        } else {
+            RAID_ESP_BOOT_SIZE_MIB
+        };
+        steps.push(add_partition(
+            disk,
+            1,
+            &size_spec(esp_mib),
+            PartitionType::Efi,
+            "EFI",
+        ));
+    } else {
+        steps.push(add_partition(
+            disk,
+            1,
+            &size_spec(BIOS_BOOT_SIZE_MIB),
+            PartitionType::BiosBoot,
+            "BIOSBOOT",
+        ));
+    }
+
+    let mut number = 2;
+    if create_boot {
+        steps.push(add_partition(
+            disk,
+            number,
+            &size_spec(BOOT_PART_SIZE_MIB),
+            PartitionType::Raid,
+            "BOOT",
+        ));
+        number += 1;
+    }
+    steps.push(add_partition(
+        disk,
+        number,
+        "remaining",
+        PartitionType::Raid,
+        "DATA",
+    ));
+    steps
+}
+
+/// Declared layout: one add-partition per entry, numbered in order.
+fn layout_steps(
+    disk: &Path,
+    gpt: bool,
+    partitions: &[LayoutEntry],
+) -> Result<Vec<Box<dyn ScriptArgs>>> {
+    let table_type = if gpt { TableType::Gpt } else { TableType::Mbr };
+    let mut steps: Vec<Box<dyn ScriptArgs>> = vec![Box::new(CreateTableArgs {
+        device: disk.to_path_buf(),
+        table_type,
+        confirm: true,
+    })];
+    for (i, entry) in partitions.iter().enumerate() {
+        let number = u8::try_from(i + 1).context("Too many partitions in layout")?;
+        steps.push(Box::new(AddPartitionArgs {
+            device: disk.to_path_buf(),
+            number,
+            size: entry.size.clone(),
+            partition_type: entry.partition_type,
+            label: entry.label.clone(),
+            confirm: true,
+        }));
+    }
+    Ok(steps)
+}
+
+fn add_partition(
+    disk: &Path,
+    number: u8,
+    size: &str,
+    partition_type: PartitionType,
+    label: &str,
+) -> Box<dyn ScriptArgs> {
+    Box::new(AddPartitionArgs {
+        device: disk.to_path_buf(),
+        number,
+        size: size.to_string(),
+        partition_type,
+        label: Some(label.to_string()),
+        confirm: true,
+    })
+}
+
+/// Pick the partition type code for the partition that holds root or home.
+fn data_partition_type(plan: &StoragePlan, device: &Path) -> PartitionType {
+    let used_by = |pred: fn(&StorageOp, &Path) -> bool| plan.ops.iter().any(|op| pred(op, device));
+    if used_by(|op, dev| matches!(op, StorageOp::LuksFormat { device, .. } if device == dev)) {
+        PartitionType::Luks
+    } else if used_by(|op, dev| matches!(op, StorageOp::CreateLvmPv { device } if device == dev)) {
+        PartitionType::Lvm
+    } else {
+        PartitionType::Linux
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::InstallationConfig;
+    use crate::engine::storage::calculate_storage_plan;
+    use crate::types::{BootMode, Filesystem, PartitionScheme, SwapMode, Toggle};
+
+    fn plan_for(strategy: PartitionScheme, disk: &str) -> StoragePlan {
+        let mut config = InstallationConfig::new();
+        config.install_disk = disk.to_string();
+        config.partitioning_strategy = strategy;
+        config.root_filesystem = Filesystem::Ext4;
+        config.boot_mode = BootMode::Uefi;
+        config.swap = SwapMode::Partition;
+        config.swap_size = "4".to_string();
+        calculate_storage_plan(&config).expect("plan generation failed") // test: known-good input
+    }
+
+    fn encrypted_options() -> ExecutorOptions {
+        ExecutorOptions {
+            encryption_password: Some("correct horse".to_string()),
+            ..ExecutorOptions::default()
+        }
+    }
+
+    fn ok_output() -> Result<ScriptOutput> {
+        Ok(ScriptOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: Some(0),
+            success: true,
+            dry_run: false,
+            events: Vec::new(),
+        })
+    }
+
+    fn failed_output() -> Result<ScriptOutput> {
+        Ok(ScriptOutput {
+            stdout: String::new(),
+            stderr: "boom".to_string(),
+            exit_code: Some(1),
+            success: false,
+            dry_run: false,
+            events: Vec::new(),
+        })
+    }
+
+    fn cli(step: &dyn ScriptArgs) -> String {
+        format!("{} {}", step.script_name(), step.to_cli_args().join(" "))
+    }
+
+    #[test]
+    fn test_simple_partition_steps_put_swap_before_root() {
+        let plan = plan_for(PartitionScheme::AutoSimple, "/dev/sda");
+        let steps = script_steps(&plan.ops[1], &plan, &ExecutorOptions::default(), None)
+            .expect("mapping failed"); // test: known-good input
+        let lines: Vec<String> = steps.iter().map(|s| cli(s.as_ref())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "manual_partition.sh --device /dev/sda --action create-table --table-type gpt",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 1 --size 512M --type EF00 --label EFI",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 2 --size 4G --type 8200 --label SWAP",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 3 --size remaining --type 8300 --label ROOT",
+            ]
+        );
+        assert!(steps.iter().all(|s| {
+            s.get_env_vars()
+                .contains(&("CONFIRM_MANUAL_PARTITION".to_string(), "yes".to_string()))
+        }));
+    }
+
+    #[test]
+    fn test_partition_steps_add_home_after_sized_root() {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.boot_mode = BootMode::Uefi;
+        config.swap = SwapMode::None;
+        config.separate_home = Toggle::Yes;
+        config.root_size = "40GB".to_string();
+        config.home_size = "Remaining".to_string();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let steps = script_steps(&plan.ops[1], &plan, &ExecutorOptions::default(), None)
+            .expect("mapping failed"); // test: known-good input
+        let lines: Vec<String> = steps.iter().map(|s| cli(s.as_ref())).collect();
+        assert_eq!(
+            lines[2..],
+            [
+                "manual_partition.sh --device /dev/sda --action add-partition --number 2 --size 40960M --type 8300 --label ROOT",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 3 --size remaining --type 8300 --label HOME",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bios_partition_steps_use_gpt_with_bios_boot() {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.boot_mode = BootMode::Bios;
+        config.swap = SwapMode::Partition;
+        config.swap_size = "4".to_string();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let steps = script_steps(&plan.ops[1], &plan, &ExecutorOptions::default(), None)
+            .expect("mapping failed"); // test: known-good input
+        let lines: Vec<String> = steps.iter().map(|s| cli(s.as_ref())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "manual_partition.sh --device /dev/sda --action create-table --table-type gpt",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 1 --size 1M --type EF02 --label BIOSBOOT",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 2 --size 4G --type 8200 --label SWAP",
+                "manual_partition.sh --device /dev/sda --action add-partition --number 3 --size remaining --type 8300 --label ROOT",
+            ]
+        );
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Mount { device, mountpoint, .. }
+                if device == Path::new("/dev/sda3") && mountpoint == Path::new("/mnt")
+        )));
+    }
+
+    #[test]
+    fn test_data_partition_type_follows_plan() {
+        let luks = plan_for(PartitionScheme::AutoSimpleLuks, "/dev/sda");
+        assert_eq!(
+            data_partition_type(&luks, Path::new("/dev/sda2")),
+            PartitionType::Luks
+        );
+        let lvm = plan_for(PartitionScheme::AutoLvm, "/dev/sda");
+        assert_eq!(
+            data_partition_type(&lvm, Path::new("/dev/sda2")),
+            PartitionType::Lvm
+        );
+    }
+
+    #[test]
+    fn test_raid_member_steps_use_raid_type_codes() {
+        let lines: Vec<String> = raid_member_steps(Path::new("/dev/sdb"), false, true)
+            .iter()
+            .map(|s| cli(s.as_ref()))
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("--table-type gpt"));
+        assert!(lines[1].contains("--number 1 --size 1M --type EF02"));
+        assert!(lines[2].contains("--number 2 --size 1024M --type FD00"));
+        assert!(lines[3].contains("--number 3 --size remaining --type FD00"));
+    }
+
+    #[test]
+    fn test_raid_member_esp_doubles_as_boot_without_boot_array() {
+        let lines: Vec<String> = raid_member_steps(Path::new("/dev/sda"), true, false)
+            .iter()
+            .map(|s| cli(s.as_ref()))
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains("--size 1024M --type EF00"));
+        assert!(lines[2].contains("--number 2 --size remaining --type FD00"));
+    }
+
+    #[test]
+    fn test_btrfs_subvolume_steps() {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.root_filesystem = Filesystem::Btrfs;
+        config.boot_mode = BootMode::Bios;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        let lines: Vec<String> = plan
+            .ops
+            .iter()
+            .filter(|op| {
+                matches!(
+                    op,
+                    StorageOp::CreateBtrfsSubvolumes { .. } | StorageOp::MountBtrfsSubvolume { .. }
+                )
+            })
+            .flat_map(|op| {
+                script_steps(op, &plan, &ExecutorOptions::default(), None).expect("mapping failed")
+            }) // test: known-good input
+            .map(|step| cli(step.as_ref()))
+            .collect();
+        assert_eq!(
+            lines[0],
+            "manage_btrfs.sh --action create-subvolumes --device /dev/sda2 --subvolume @ --subvolume @home --subvolume @log --subvolume @pkg --subvolume @snapshots"
+        );
+        assert_eq!(
+            lines[1],
+            "mount_partitions.sh --action mount --device /dev/sda2 --mountpoint /mnt --options subvol=@,compress=zstd,noatime"
+        );
+        assert_eq!(lines.len(), 6);
+    }
+
+    #[test]
+    fn test_layout_steps_follow_declared_entries() {
+        let partitions = vec![
+            LayoutEntry {
+                size: "1M".to_string(),
+                partition_type: PartitionType::BiosBoot,
+                label: None,
+            },
+            LayoutEntry {
+                size: "remaining".to_string(),
+                partition_type: PartitionType::Linux,
+                label: Some("root".to_string()),
+            },
+        ];
+        let lines: Vec<String> = layout_steps(Path::new("/dev/vda"), true, &partitions)
+            .expect("mapping failed") // test: known-good input
+            .iter()
+            .map(|s| cli(s.as_ref()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "manual_partition.sh --device /dev/vda --action create-table --table-type gpt",
+                "manual_partition.sh --device /dev/vda --action add-partition --number 1 --size 1M --type EF02",
+                "manual_partition.sh --device /dev/vda --action add-partition --number 2 --size remaining --type 8300 --label root",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alongside_steps_shrink_and_create_in_free_space() {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/nvme0n1".to_string();
+        config.partitioning_strategy = PartitionScheme::AutoAlongside;
+        config.boot_mode = BootMode::Uefi;
+        config.alongside_esp = "/dev/nvme0n1p1".to_string();
+        config.shrink_partition = "/dev/nvme0n1p3".to_string();
+        config.shrink_size = "200G".to_string();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        let lines: Vec<String> = plan
+            .ops
+            .iter()
+            .flat_map(|op| {
+                script_steps(op, &plan, &ExecutorOptions::default(), None).expect("mapping failed") // test: known-good input
+            })
+            .map(|s| cli(s.as_ref()))
+            .collect();
+        assert_eq!(
+            lines[..3],
+            [
+                "free_space.sh --action shrink --partition /dev/nvme0n1p3 --size 200G",
+                "free_space.sh --action create --device /dev/nvme0n1 --size 1024M --type 8300 --label archtui-boot",
+                "free_space.sh --action create --device /dev/nvme0n1 --size remaining --type 8300 --label archtui-root",
+            ]
+        );
+        assert!(
+            !lines
+                .iter()
+                .any(|l| l.starts_with("wipe_disk.sh") || l.starts_with("manual_partition.sh"))
+        );
+        assert!(
+            lines.contains(
+                &"mount_partitions.sh --action mount --device /dev/nvme0n1p1 --mountpoint /mnt/efi"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_every_op_maps_to_a_script() {
+        for strategy in [
+            PartitionScheme::AutoSimple,
+            PartitionScheme::AutoSimpleLuks,
+            PartitionScheme::AutoLvm,
+            PartitionScheme::AutoLuksLvm,
+        ] {
+            let plan = plan_for(strategy, "/dev/sda");
+            for op in &plan.ops {
+                let steps =
+                    script_steps(op, &plan, &encrypted_options(), Some(Path::new("/tmp/key")))
+                        .expect("mapping failed"); // test: known-good input
+                assert!(!steps.is_empty(), "{} produced no steps", op);
+                for step in &steps {
+                    assert!(
+                        step.validate().is_ok(),
+                        "{} failed validation",
+                        cli(step.as_ref())
+                    );
+                }
+            }
+        }
+        let raid = plan_for(PartitionScheme::AutoRaidLvmLuks, "/dev/sda,/dev/sdb");
+        for op in &raid.ops {
+            let steps = script_steps(op, &raid, &encrypted_options(), Some(Path::new("/tmp/key")))
+                .expect("mapping failed"); // test: known-good input
+            assert!(!steps.is_empty(), "{} produced no steps", op);
+        }
+    }
+
+    #[test]
+    fn test_luks_steps_pass_keyfile_not_password() {
+        let op = StorageOp::LuksFormat {
+            device: PathBuf::from("/dev/sda2"),
+            label: "cryptroot".to_string(),
+        };
+        let plan = plan_for(PartitionScheme::AutoSimpleLuks, "/dev/sda");
+        let steps = script_steps(
+            &op,
+            &plan,
+            &encrypted_options(),
+            Some(Path::new("/tmp/key")),
+        )
+        .expect("mapping failed"); // test: known-good input
+        let args = steps[0].to_cli_args();
+        assert!(args.contains(&"/tmp/key".to_string()));
+        assert!(!args.iter().any(|a| a.contains("correct horse")));
+    }
+
+    #[test]
+    fn test_encrypted_plan_without_password_fails_before_running() {
+        let plan = plan_for(PartitionScheme::AutoSimpleLuks, "/dev/sda");
+        let mut calls = 0;
+        let result = execute_with_runner(
+            &plan,
+            &ExecutorOptions::default(),
+            false,
+            |_| {},
+            |_| {
+                calls += 1;
+                ok_output()
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 0, "nothing may run without a password");
+    }
+
+    #[test]
+    fn test_successful_run_reports_started_and_succeeded() {
+        let plan = plan_for(PartitionScheme::AutoLvm, "/dev/sda");
+        let mut events = Vec::new();
+        let reports = execute_with_runner(
+            &plan,
+            &ExecutorOptions::default(),
+            false,
+            |r| events.push(r.status.clone()),
+            |_| ok_output(),
+        )
+        .expect("execution failed"); // test: known-good input
+
+        assert_eq!(reports.len(), plan.ops.len());
+        assert!(reports.iter().all(|r| r.status == OpStatus::Succeeded));
+        assert_eq!(events.len(), plan.ops.len() * 2);
+        assert_eq!(events[0], OpStatus::Started);
+        assert_eq!(events[1], OpStatus::Succeeded);
+    }
+
+    #[test]
+    fn test_stops_on_first_failure_and_skips_the_rest() {
+        let plan = plan_for(PartitionScheme::AutoSimple, "/dev/sda");
+        let mut events: Vec<OpReport> = Vec::new();
+        let mut scripts = Vec::new();
+        let result = execute_with_runner(
+            &plan,
+            &ExecutorOptions::default(),
+            false,
+            |r| events.push(r.clone()),
+            |args| {
+                scripts.push(args.script_name());
+                if args.script_name() == "format_partition.sh" {
+                    failed_output()
+                } else {
+                    ok_output()
+                }
+            },
+        );
+
+        let err = result.expect_err("format failure must abort"); // test: injected failure
+        assert!(format!("{:#}", err).contains("Storage op 3/"));
+
+        // Wipe, partition, then the first format — nothing after it
+        assert_eq!(
+            scripts
+                .iter()
+                .filter(|s| **s == "format_partition.sh")
+                .count(),
+            1
+        );
+        assert!(!scripts.contains(&"mount_partitions.sh"));
+
+        let finals: Vec<&OpReport> = events
+            .iter()
+            .filter(|r| r.status != OpStatus::Started)
+            .collect();
+        assert_eq!(finals.len(), plan.ops.len());
+        assert!(matches!(finals[2].status, OpStatus::Failed(_)));
+        assert!(finals[3..].iter().all(|r| r.status == OpStatus::Skipped));
+    }
+
+    #[test]
+    fn test_failure_tears_down_applied_ops() {
+        let plan = plan_for(PartitionScheme::AutoLuksLvm, "/dev/sda");
+        let mut calls: Vec<(String, Vec<String>)> = Vec::new();
+        let result = execute_with_runner(
+            &plan,
+            &encrypted_options(),
+            false,
+            |_| {},
+            |args| {
+                calls.push((args.script_name().to_string(), args.to_cli_args()));
+                // Fail the first mount, after LUKS, LVM and formatting succeeded
+                if args.script_name() == "mount_partitions.sh" {
+                    failed_output()
+                } else {
+                    ok_output()
+                }
+            },
+        );
+        assert!(result.is_err());
+
+        let (script, args) = calls.last().expect("teardown ran"); // test: known-good input
+        assert_eq!(script, "teardown_storage.sh");
+        assert_eq!(
+            args,
+            &vec![
+                "--swapoff",
+                "/dev/archvg/swap",
+                "--vg",
+                "archvg",
+                "--mapper",
+                "cryptlvm"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_teardown_failure_keeps_original_error() {
+        let plan = plan_for(PartitionScheme::AutoSimpleLuks, "/dev/sda");
+        let err = execute_with_runner(
+            &plan,
+            &encrypted_options(),
+            false,
+            |_| {},
+            |args| match args.script_name() {
+                "format_partition.sh" | "teardown_storage.sh" => failed_output(),
+                _ => ok_output(),
+            },
+        )
+        .expect_err("format failure must abort"); // test: injected failure
+        assert!(format!("{:#}", err).contains("FormatFs"));
+    }
+
+    #[test]
+    fn test_teardown_can_be_disabled() {
+        let plan = plan_for(PartitionScheme::AutoLuksLvm, "/dev/sda");
+        let options = ExecutorOptions {
+            teardown_on_failure: false,
+            ..encrypted_options()
+        };
+        let mut scripts = Vec::new();
+        let result = execute_with_runner(
+            &plan,
+            &options,
+            false,
+            |_| {},
+            |args| {
+                scripts.push(args.script_name());
+                if args.script_name() == "mount_partitions.sh" {
+                    failed_output()
+                } else {
+                    ok_output()
+                }
+            },
+        );
+        assert!(result.is_err());
+        assert!(!scripts.contains(&"teardown_storage.sh"));
+    }
+
+    #[test]
+    fn test_dry_run_skips_non_destructive_steps() {
+        let plan = plan_for(PartitionScheme::AutoSimple, "/dev/sda");
+        let mut scripts = Vec::new();
+        let reports = execute_with_runner(
+            &plan,
+            &ExecutorOptions::default(),
+            true,
+            |_| {},
+            |args| {
+                scripts.push(args.script_name());
+                ok_output()
+            },
+        )
+        .expect("execution failed"); // test: known-good input
+
+        assert!(!scripts.contains(&"mount_partitions.sh"));
+        for report in &reports {
+            let expected = if matches!(report.op, StorageOp::Mount { .. }) {
+                OpStatus::Skipped
+            } else {
+                OpStatus::Succeeded
+            };
+            assert_eq!(report.status, expected, "{}", report);
+        }
+    }
+
+    #[test]
+    fn test_plan_steps_needs_no_password() {
+        let plan = plan_for(PartitionScheme::AutoSimpleLuks, "/dev/sda");
+        let planned = plan_steps(&plan, &ExecutorOptions::default()).expect("planning failed"); // test: known-good input
+
+        assert_eq!(planned.len(), plan.ops.len());
+        let luks = planned
+            .iter()
+            .find(|p| matches!(p.op, StorageOp::LuksFormat { .. }))
+            .expect("no LuksFormat op"); // test: known-good input
+        let args = luks.steps[0].to_cli_args();
+        assert!(args.contains(&PLACEHOLDER_KEY_FILE.to_string()));
+    }
+
+    #[test]
+    fn test_op_report_display() {
+        let report = OpReport {
+            index: 0,
+            total: 5,
+            op: StorageOp::WipeDisk {
+                disk: PathBuf::from("/dev/sda"),
+            },
+            status: OpStatus::Failed("exit 1".to_string()),
+        };
+        assert_eq!(
+            report.to_string(),
+            "[1/5] WipeDisk(/dev/sda): failed: exit 1"
+        );
+    }
+
+    #[test]
+    fn test_options_debug_redacts_password() {
+        let debug = format!("{:?}", encrypted_options());
+        assert!(!debug.contains("correct horse"));
+        assert!(debug.contains("********"));
+    }
+}
@@ -1,6 +1,11 @@
 //! Engine modules — the "brain" that translates user config into script sequences.
 //!
 //! The engine layer sits between configuration (what the user wants) and execution
-//! (which scripts to run). It generates ordered, validated operation plans.
+//! (which scripts to run). It generates ordered, validated operation plans and
+//! executes them through the typed script layer.
 
+pub mod capacity;
+pub mod executor;
+pub mod sandbox;
 pub mod storage;
+pub mod swap;
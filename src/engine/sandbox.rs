@@ -0,0 +1,545 @@
+//! Loop-Device Sandbox
+//!
+//! Runs the real storage pipeline — `calculate_storage_plan`, the executor
+//! and the wipe/partition/format/mount scripts — against a sparse image file
+//! attached as a loop device, so the whole path can be exercised without a
+//! spare disk or a VM.
+//!
+//! # Pipeline
+//!
+//! 1. Create a sparse image and attach it with `losetup --find --partscan`
+//! 2. Point the config at the loop device and check the layout fits
+//! 3. Plan storage, rebased from `/mnt` to `SANDBOX_ROOT`, and execute it
+//! 4. Optionally install the base system from a local package cache (ALPM)
+//! 5. Generate `etc/fstab` via `generate_fstab.sh`
+//! 6. Capture the partition table (`sfdisk --json`) and the fstab
+//! 7. Tear down mounts/mappers/VGs, detach the loop device, remove the image
+//!
+//! Only single-disk strategies are supported: RAID needs several member
+//! disks and `auto_alongside` needs an existing OS to shrink.
+//!
+//! # Safety
+//!
+//! The sandbox refuses to run without root, and never touches a device it
+//! did not attach itself. Dry-run is rejected because the executor would
+//! skip every step the sandbox exists to exercise.
+
+// Library API - consumed by the `sandbox` CLI command and integration tests
+#![allow(dead_code)]
+
+use crate::config_file::InstallationConfig;
+use crate::engine::executor::{ExecutorOptions, OpReport, execute_storage_plan};
+use crate::engine::storage::{StoragePlan, calculate_storage_plan};
+use crate::process_guard::CommandProcessGroup;
+use crate::script_runner::run_script_safe;
+use crate::script_traits::is_dry_run;
+use crate::scripts::config::GenFstabArgs;
+use crate::scripts::storage::TeardownStorageArgs;
+use crate::types::PartitionScheme;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where the sandbox mounts the target (must stay below `/mnt` for swapfiles).
+pub const SANDBOX_ROOT: &str = "/mnt/archtui-sandbox";
+/// Default image location (`/var/tmp` survives reboots but not the ISO).
+pub const DEFAULT_IMAGE_PATH: &str = "/var/tmp/archtui-sandbox.img";
+/// Default image size — sparse, so only written blocks use space.
+pub const DEFAULT_IMAGE_SIZE_MIB: u64 = 16384;
+/// Smallest image accepted; the capacity check still applies on top.
+pub const MIN_IMAGE_SIZE_MIB: u64 = 1024;
+
+// ============================================================================
+// Options & Report
+// ============================================================================
+
+/// Inputs for a sandbox run.
+#[derive(Debug, Clone)]
+pub struct SandboxOptions {
+    /// Image file to create (must not exist yet).
+    pub image: PathBuf,
+    /// Image size in MiB.
+    pub size_mib: u64,
+    /// Mount root replacing `/mnt` in the plan.
+    pub target_root: PathBuf,
+    /// Install the base system from this pacman package cache. Skipped when
+    /// `None`; requires the `alpm` feature otherwise.
+    pub package_cache: Option<PathBuf>,
+    /// Leave the image file in place after the run for inspection.
+    pub keep_image: bool,
+}
+
+impl Default for SandboxOptions {
+    fn default() -> Self {
+        Self {
+            image: PathBuf::from(DEFAULT_IMAGE_PATH),
+            size_mib: DEFAULT_IMAGE_SIZE_MIB,
+            target_root: PathBuf::from(SANDBOX_ROOT),
+            package_cache: None,
+            keep_image: false,
+        }
+    }
+}
+
+/// What a sandbox run produced, captured before teardown.
+#[derive(Debug, Clone)]
+pub struct SandboxReport {
+    /// Loop device the image was attached to (detached by now).
+    pub loop_device: PathBuf,
+    /// The executed plan, rebased onto the sandbox root.
+    pub plan: StoragePlan,
+    /// Final status of every op.
+    pub ops: Vec<OpReport>,
+    /// Partition table as written to the image.
+    pub partition_table: PartitionTable,
+    /// Entries of the generated `etc/fstab`.
+    pub fstab: Vec<FstabEntry>,
+    /// Whether the base system was installed from the package cache.
+    pub base_system_installed: bool,
+}
+
+impl SandboxReport {
+    /// The fstab entry mounted at `mountpoint`, if any.
+    pub fn fstab_entry(&self, mountpoint: &str) -> Option<&FstabEntry> {
+        self.fstab.iter().find(|e| e.mountpoint == mountpoint)
+    }
+}
+
+// ============================================================================
+// Partition Table & Fstab
+// ============================================================================
+
+/// A partition table as reported by `sfdisk --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PartitionTable {
+    /// "gpt" or "dos"
+    pub label: String,
+    #[serde(default)]
+    pub partitions: Vec<PartitionEntry>,
+}
+
+/// One partition of a `PartitionTable` (start/size in sectors).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PartitionEntry {
+    pub node: PathBuf,
+    pub start: u64,
+    pub size: u64,
+    /// GPT type GUID or MBR type code
+    #[serde(rename = "type")]
+    pub type_id: String,
+    /// GPT partition name
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SfdiskDump {
+    partitiontable: PartitionTable,
+}
+
+impl PartitionTable {
+    /// Read the partition table of `device` with `sfdisk --json`.
+    pub fn read(device: &Path) -> Result<Self> {
+        let output = Command::new("sfdisk")
+            .arg("--json")
+            .arg(device)
+            .in_new_process_group()
+            .output()
+            .context("Failed to run sfdisk")?;
+        if !output.status.success() {
+            bail!(
+                "sfdisk --json {} failed: {}",
+                device.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parse `sfdisk --json` output.
+    pub fn parse(json: &str) -> Result<Self> {
+        let dump: SfdiskDump =
+            serde_json::from_str(json).context("Failed to parse sfdisk JSON output")?;
+        Ok(dump.partitiontable)
+    }
+
+    /// The partition with the given type (case-insensitive), if any.
+    pub fn find_type(&self, type_id: &str) -> Option<&PartitionEntry> {
+        self.partitions
+            .iter()
+            .find(|p| p.type_id.eq_ignore_ascii_case(type_id))
+    }
+}
+
+/// One non-comment line of an fstab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FstabEntry {
+    /// Device spec (e.g., "UUID=...")
+    pub source: String,
+    /// Mountpoint on the installed system ("none" for swap)
+    pub mountpoint: String,
+    pub fstype: String,
+    pub options: String,
+}
+
+/// Parse fstab contents, skipping blank lines and comments.
+pub fn parse_fstab(contents: &str) -> Vec<FstabEntry> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            Some(FstabEntry {
+                source: fields[0].to_string(),
+                mountpoint: fields[1].to_string(),
+                fstype: fields[2].to_string(),
+                options: fields[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+// ============================================================================
+// Image & Loop Device
+// ============================================================================
+
+/// A sparse image file, removed on drop unless kept.
+struct SandboxImage {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl SandboxImage {
+    fn create(path: &Path, size_mib: u64, keep: bool) -> Result<Self> {
+        // create_new: never truncate a file that might be someone's disk image
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .with_context(|| format!("Failed to create sandbox image {}", path.display()))?;
+        file.set_len(size_mib * 1024 * 1024)
+            .with_context(|| format!("Failed to size sandbox image {}", path.display()))?;
+        tracing::info!(image = %path.display(), size_mib, "Created sparse sandbox image");
+        Ok(Self {
+            path: path.to_path_buf(),
+            keep,
+        })
+    }
+}
+
+impl Drop for SandboxImage {
+    fn drop(&mut self) {
+        if self.keep {
+            tracing::info!(image = %self.path.display(), "Keeping sandbox image");
+            return;
+        }
+        if let Err(e) = fs::remove_file(&self.path) {
+            tracing::warn!(error = %e, image = %self.path.display(), "Failed to remove sandbox image");
+        }
+    }
+}
+
+/// A loop device attached to an image file, detached on drop.
+pub struct LoopDevice {
+    path: PathBuf,
+}
+
+impl LoopDevice {
+    /// Attach `image` to the first free loop device with partition scanning.
+    pub fn attach(image: &Path) -> Result<Self> {
+        let output = Command::new("losetup")
+            .args(["--find", "--show", "--partscan"])
+            .arg(image)
+            .in_new_process_group()
+            .output()
+            .context("Failed to run losetup")?;
+        if !output.status.success() {
+            bail!(
+                "losetup failed for {}: {}",
+                image.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        if !path.starts_with("/dev/loop") {
+            bail!("losetup returned unexpected device '{}'", path.display());
+        }
+        tracing::info!(device = %path.display(), image = %image.display(), "Attached loop device");
+        Ok(Self { path })
+    }
+
+    /// The loop device path (e.g., `/dev/loop0`).
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for LoopDevice {
+    fn drop(&mut self) {
+        let detached = Command::new("losetup")
+            .arg("--detach")
+            .arg(&self.path)
+            .in_new_process_group()
+            .status();
+        match detached {
+            Ok(status) if status.success() => {
+                tracing::info!(device = %self.path.display(), "Detached loop device")
+            }
+            Ok(status) => {
+                tracing::warn!(device = %self.path.display(), %status, "Failed to detach loop device")
+            }
+            Err(e) => {
+                tracing::warn!(device = %self.path.display(), error = %e, "Failed to run losetup --detach")
+            }
+        }
+    }
+}
+
+/// Whether the process runs as root (loop devices and mounts need it).
+pub fn is_root() -> bool {
+    nix::unistd::geteuid().is_root()
+}
+
+// ============================================================================
+// Sandbox Run
+// ============================================================================
+
+/// Run the storage pipeline for `config` against a loop-backed image.
+///
+/// `config.install_disk` is ignored — the loop device replaces it. Status
+/// updates from the executor are forwarded to `on_status`. Everything the run
+/// set up is torn down before returning, whether it succeeded or not.
+///
+/// # Errors
+///
+/// Returns an error if not running as root, in dry-run mode, for RAID or
+/// alongside strategies, if the image already exists or is too small for the
+/// layout, or if any pipeline step fails.
+pub fn run_sandbox(
+    config: &InstallationConfig,
+    options: &SandboxOptions,
+    on_status: impl FnMut(&OpReport),
+) -> Result<SandboxReport> {
+    if !is_root() {
+        bail!("The sandbox needs root to attach loop devices and mount filesystems");
+    }
+    if is_dry_run() {
+        bail!("The sandbox runs the real pipeline — drop --dry-run");
+    }
+    check_strategy(config.partitioning_strategy)?;
+    if options.size_mib < MIN_IMAGE_SIZE_MIB {
+        bail!(
+            "Sandbox image must be at least {} MiB (got {})",
+            MIN_IMAGE_SIZE_MIB,
+            options.size_mib
+        );
+    }
+    if options.package_cache.is_some() && !cfg!(feature = "alpm") {
+        bail!("Installing the base system in the sandbox requires the `alpm` feature");
+    }
+
+    // Declared before the loop device so it is dropped (removed) after detach
+    let image = SandboxImage::create(&options.image, options.size_mib, options.keep_image)?;
+    let loop_device = LoopDevice::attach(&image.path)?;
+
+    let mut config = config.clone();
+    config.install_disk = loop_device.path().display().to_string();
+    crate::engine::capacity::check_disk_capacity(&config)?;
+
+    let plan = calculate_storage_plan(&config)?.rebased(&options.target_root);
+    tracing::info!("Sandbox {}", plan.summary());
+
+    let executor_options = ExecutorOptions {
+        encryption_password: Some(config.encryption_password.clone()).filter(|p| !p.is_empty()),
+        ..ExecutorOptions::default()
+    };
+    // On failure the executor already tore down whatever it applied
+    let ops = execute_storage_plan(&plan, &executor_options, on_status)?;
+
+    let result = inspect_target(&loop_device, &options.target_root, options);
+
+    let teardown = TeardownStorageArgs {
+        ops: plan.teardown(plan.ops.len()),
+        confirm: true,
+    };
+    if let Err(e) = run_script_safe(&teardown).and_then(|o| o.ensure_success("sandbox teardown")) {
+        tracing::warn!(error = %e, "Sandbox teardown incomplete — loop device may stay busy");
+    }
+
+    let (partition_table, fstab, base_system_installed) = result?;
+    Ok(SandboxReport {
+        loop_device: loop_device.path().to_path_buf(),
+        plan,
+        ops,
+        partition_table,
+        fstab,
+        base_system_installed,
+    })
+}
+
+/// Reject strategies that need more than one fresh disk.
+fn check_strategy(strategy: PartitionScheme) -> Result<()> {
+    match strategy {
+        PartitionScheme::AutoRaid
+        | PartitionScheme::AutoRaidLuks
+        | PartitionScheme::AutoRaidLvm
+        | PartitionScheme::AutoRaidLvmLuks => {
+            bail!("The sandbox supports single-disk strategies only — RAID needs member disks")
+        }
+        PartitionScheme::AutoAlongside => {
+            bail!("The sandbox cannot run auto_alongside — there is no existing OS to shrink")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Post-storage steps on the mounted target: base system, fstab, inspection.
+fn inspect_target(
+    loop_device: &LoopDevice,
+    root: &Path,
+    options: &SandboxOptions,
+) -> Result<(PartitionTable, Vec<FstabEntry>, bool)> {
+    let base_system_installed = match &options.package_cache {
+        Some(cache) => {
+            install_base_system(root, cache)?;
+            true
+        }
+        None => {
+            tracing::info!("No package cache given — skipping base system install");
+            false
+        }
+    };
+
+    // genfstab appends to etc/fstab, which only exists after pacstrap
+    let etc = root.join("etc");
+    fs::create_dir_all(&etc).with_context(|| format!("Failed to create {}", etc.display()))?;
+    run_script_safe(&GenFstabArgs {
+        root: root.to_path_buf(),
+        confirm: true,
+    })?
+    .ensure_success("generate_fstab.sh")?;
+
+    let fstab_path = etc.join("fstab");
+    let fstab = fs::read_to_string(&fstab_path)
+        .with_context(|| format!("Failed to read {}", fstab_path.display()))?;
+    let partition_table = PartitionTable::read(loop_device.path())?;
+
+    Ok((partition_table, parse_fstab(&fstab), base_system_installed))
+}
+
+/// Install from the cache, logging each phase and package as it starts.
+#[cfg(feature = "alpm")]
+fn install_base_system(root: &Path, cache: &Path) -> Result<()> {
+    use crate::package_events::{PackageProgress, QuestionPolicy};
+
+    // The cache is the caller's (often the live ISO's): never delete from it
+    let policy = QuestionPolicy {
+        remove_corrupted: false,
+        ..QuestionPolicy::default()
+    };
+    let (events, receiver) = std::sync::mpsc::channel();
+    let logger = std::thread::spawn(move || {
+        let mut progress = PackageProgress::default();
+        for event in receiver {
+            let before = (progress.phase, progress.current_package.clone());
+            progress.apply(&event);
+            if before != (progress.phase, progress.current_package.clone()) {
+                tracing::info!("Sandbox base system: {}", progress.status_line());
+            }
+        }
+    });
+    let result = crate::installer::install_base_system_from_cache(root, cache, events, policy);
+    let _ = logger.join();
+    result
+}
+
+#[cfg(not(feature = "alpm"))]
+fn install_base_system(_root: &Path, _cache: &Path) -> Result<()> {
+    bail!("Installing the base system in the sandbox requires the `alpm` feature")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SFDISK_JSON: &str = r#"{
+   "partitiontable": {
+      "label": "gpt",
+      "id": "6F1C1A33-6C7E-4A4B-8C7D-0E1F2A3B4C5D",
+      "device": "/dev/loop0",
+      "unit": "sectors",
+      "firstlba": 2048,
+      "lastlba": 33554398,
+      "sectorsize": 512,
+      "partitions": [
+         {
+            "node": "/dev/loop0p1",
+            "start": 2048,
+            "size": 1048576,
+            "type": "C12A7328-F81F-11D2-BA4B-00A0C93EC93B",
+            "uuid": "0B7F4E2C-1D1E-4F5A-9B8C-7D6E5F4A3B2C",
+            "name": "EFI"
+         },{
+            "node": "/dev/loop0p2",
+            "start": 1050624,
+            "size": 32501760,
+            "type": "4F68BCE3-E8CD-4DB1-96E7-FBCAF984B709",
+            "uuid": "1C8E5F3D-2E2F-4A6B-8C9D-8E7F6A5B4C3D"
+         }
+      ]
+   }
+}"#;
+
+    #[test]
+    fn test_parse_sfdisk_json() {
+        let table = PartitionTable::parse(SFDISK_JSON).expect("valid sfdisk output"); // test: known-good input
+        assert_eq!(table.label, "gpt");
+        assert_eq!(table.partitions.len(), 2);
+        assert_eq!(table.partitions[0].node, PathBuf::from("/dev/loop0p1"));
+        assert_eq!(table.partitions[0].name.as_deref(), Some("EFI"));
+        assert_eq!(table.partitions[1].name, None);
+        let esp = table
+            .find_type("c12a7328-f81f-11d2-ba4b-00a0c93ec93b")
+            .expect("ESP present"); // test: known-good input
+        assert_eq!(esp.size, 1048576);
+        assert!(PartitionTable::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_fstab_skips_comments() {
+        let fstab = "\
+# Static information about the filesystems.
+# /dev/loop0p2
+UUID=1111\t/\text4\trw,relatime\t0 1
+
+UUID=2222  /boot  vfat  rw,relatime,fmask=0022  0 2
+/swap/swapfile none swap defaults 0 0
+";
+        let entries = parse_fstab(fstab);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].source, "UUID=1111");
+        assert_eq!(entries[0].mountpoint, "/");
+        assert_eq!(entries[0].fstype, "ext4");
+        assert_eq!(entries[1].mountpoint, "/boot");
+        assert_eq!(entries[1].fstype, "vfat");
+        assert_eq!(entries[2].fstype, "swap");
+    }
+
+    #[test]
+    fn test_multi_disk_strategies_are_rejected() {
+        assert!(check_strategy(PartitionScheme::AutoSimple).is_ok());
+        assert!(check_strategy(PartitionScheme::AutoLuksLvm).is_ok());
+        assert!(check_strategy(PartitionScheme::AutoRaidLvm).is_err());
+        assert!(check_strategy(PartitionScheme::AutoAlongside).is_err());
+    }
+}
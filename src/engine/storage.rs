@@ -1,8 +1,11 @@
 //! Storage Stack Engine
 //!
 //! Translates a high-level `PartitionScheme` + `Filesystem` configuration into
-//! an ordered sequence of atomic `StorageOp` operations that the script runner
-//! can execute.
+//! an ordered sequence of atomic `StorageOp` operations. `engine::executor`
+//! runs the plan for the sandbox and, through `archtui apply-storage`
+//! (`scripts/strategies/storage_plan.sh`), for real installs of every
+//! automatic strategy and of a declared `partition_layout`, so the plan is
+//! the layout that gets written.
 //!
 //! # Supported Strategies
 //!
@@ -10,14 +13,44 @@
 //! |-----------------|---------------------|
 //! | AutoSimple      | Wipe → Partition → Format → Mount |
 //! | AutoSimpleLuks  | Wipe → Partition → LuksFormat → LuksOpen → Format → Mount |
-//! | AutoLvm         | Wipe → Partition → PV → VG → LV(root,swap) → Format → Mount |
+//! | AutoLvm         | Wipe → Partition → PV → VG → LV(swap,root,home) → Format → Mount |
 //! | AutoLuksLvm     | Wipe → Partition → LuksFormat → LuksOpen → PV → VG → LV → Format → Mount |
+//! | AutoRaid        | Wipe(each) → Partition(each) → RaidCreate → RaidWait → Format → Mount → mdadm.conf |
+//! | AutoRaidLuks    | … → RaidCreate → RaidWait → LuksFormat → LuksOpen → Format → Mount → mdadm.conf |
+//! | AutoRaidLvm     | … → RaidCreate → RaidWait → PV → VG → LV → Format → Mount → mdadm.conf |
+//! | AutoRaidLvmLuks | … → RaidCreate → RaidWait → LuksFormat → LuksOpen → PV → VG → LV → Format → Mount → mdadm.conf |
+//! | Manual + layout | Wipe → PartitionLayout → (LuksFormat → LuksOpen per encrypted entry) → Format → Mount |
+//! | AutoAlongside   | [Shrink] → CreateInFreeSpace(boot, swap, root) → Format(new only) → Mount (+ existing ESP) |
 //!
-//! RAID strategies are defined but return an error (deferred to a later sprint).
+//! RAID strategies take a comma-separated member list in `install_disk`
+//! (e.g. `/dev/sda,/dev/sdb`) and the array level from `raid_level`.
+//!
+//! `separate_home` adds a home partition (AutoSimple, its own LUKS container
+//! on AutoSimpleLuks) or a home LV (LVM strategies) sized by `home_size`;
+//! root then gets `root_size`. Plain RAID has no slot for one and rejects it.
+//!
+//! AutoAlongside never wipes or repartitions: it only adds partitions in
+//! unallocated space (optionally freed by shrinking `shrink_partition`) and
+//! mounts the existing ESP from `alongside_esp` without formatting it.
+//!
+//! The Manual strategy is interactive unless the config declares a
+//! `partition_layout`, in which case that layout is planned verbatim.
+//!
+//! With a Btrfs root, the root "Mount" step expands to `CreateBtrfsSubvolumes`
+//! followed by one `MountBtrfsSubvolume` per entry of `btrfs_subvolumes`.
+//!
+//! Swapfiles (`swap` = swapfile / zram+swapfile, or a swap partition on
+//! AutoSimpleLuks, which has no slot for one) are a final `CreateSwapfile` op once
+//! everything is mounted; on Btrfs they get their own `@swap` subvolume.
+//! Sizing and hibernation rules live in `engine::swap`.
 //!
 //! # Design
 //!
-//! - **Pure logic**: No I/O, no side effects — only generates the plan
+//! - **Pure logic**: No side effects — only generates the plan. The reads are
+//!   total RAM for RAM-relative swap sizes and the disk size for percentage
+//!   sizes (`calculate_storage_plan_for` takes both as input instead)
+//! - **Reversible**: `StoragePlan::teardown` derives the inverse sequence for
+//!   the ops already applied (unmount, swapoff, vgchange -an, close, stop)
 //! - **Typed output**: Each `StorageOp` maps directly to a `ScriptArgs` struct
 //! - **Validated**: The plan is checked for internal consistency before returning
 //! - **Testable**: All operations are unit-tested with config → plan assertions
@@ -25,11 +58,14 @@
 // Library API - consumed by installer orchestration
 #![allow(dead_code)]
 
-use crate::config_file::InstallationConfig;
-use crate::types::{Filesystem, PartitionScheme, Toggle};
+use crate::config_file::{BtrfsSubvolume, InstallationConfig, LayoutPartition};
+use crate::engine::{capacity, swap};
+use crate::scripts::disk::PartitionType;
+use crate::types::{BootMode, DiskSize, Filesystem, PartitionScheme, SwapMode, Toggle};
 use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 // ============================================================================
 // Storage Operation Types
@@ -39,21 +75,87 @@ use std::path::{Path, PathBuf};
 ///
 /// Operations are ordered: the plan generator ensures correct sequencing
 /// (e.g., PV before VG, VG before LV, LuksFormat before LuksOpen).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StorageOp {
     /// Wipe the entire disk (sgdisk --zap-all)
     WipeDisk { disk: PathBuf },
 
-    /// Create partitions on the disk (sgdisk)
-    /// Generates EFI + root (+ swap partition if applicable)
+    /// Create a GPT partition table on the disk (sgdisk)
+    /// Generates ESP or BIOS boot + (swap partition if applicable) + root
     Partition {
         disk: PathBuf,
-        /// Whether to create an EFI System Partition
+        /// Whether partition 1 is an EFI System Partition (vs. BIOS boot)
+        create_efi: bool,
+        /// Size of a separate swap partition placed before root (non-LVM only)
+        swap_size: Option<String>,
+        /// Size of the root partition (None = the remaining space)
+        root_size: Option<String>,
+        /// Size of a separate home partition placed after root ("remaining"
+        /// for the rest of the disk)
+        home_size: Option<String>,
+    },
+
+    /// Create the partition table on a RAID member disk (sgdisk)
+    ///
+    /// Layout: partition 1 is the ESP (UEFI) or BIOS boot partition,
+    /// partition 2 is the boot array member (if `create_boot`), and the last
+    /// partition is the data array member.
+    PartitionRaidMember {
+        disk: PathBuf,
+        /// Whether partition 1 is an EFI System Partition (vs. BIOS boot)
         create_efi: bool,
-        /// Whether to create a separate swap partition (non-LVM only)
-        create_swap: bool,
+        /// Whether to create a separate boot array member partition
+        create_boot: bool,
+    },
+
+    /// Create a user-declared partition table (sgdisk/sfdisk)
+    ///
+    /// Entries are created in order, numbered from 1.
+    PartitionLayout {
+        disk: PathBuf,
+        /// Whether to create a GPT (vs. MBR) partition table
+        gpt: bool,
+        partitions: Vec<LayoutEntry>,
+    },
+
+    /// Shrink an existing NTFS/ext4 partition (filesystem first, then the
+    /// partition entry) to free up space after it
+    ShrinkPartition {
+        device: PathBuf,
+        /// New size of the partition (e.g., "200G")
+        size: String,
+    },
+
+    /// Create one partition in the largest unallocated block of a GPT disk,
+    /// leaving existing partitions untouched
+    ///
+    /// The new partition is referenced by its GPT name via
+    /// `/dev/disk/by-partlabel/<label>`, since its number is only known once
+    /// sgdisk picks the first free slot.
+    CreatePartitionInFreeSpace {
+        disk: PathBuf,
+        /// Size specification (e.g., "1024M", "remaining")
+        size: String,
+        partition_type: PartitionType,
+        /// GPT partition name (must not already exist on the disk)
+        label: String,
+    },
+
+    /// Create an mdadm software RAID array from member partitions
+    CreateRaidArray {
+        /// Array device (e.g., "/dev/md/DATA")
+        device: PathBuf,
+        /// RAID level as understood by mdadm (e.g., "raid1")
+        level: String,
+        members: Vec<PathBuf>,
     },
 
+    /// Wait for an mdadm array to finish initial assembly/resync
+    WaitRaidSync { device: PathBuf },
+
+    /// Persist the array definitions (mdadm --detail --scan) for boot
+    WriteMdadmConf { path: PathBuf },
+
     /// Format a partition with LUKS2 encryption
     LuksFormat { device: PathBuf, label: String },
 
@@ -84,13 +186,36 @@ pub enum StorageOp {
         label: Option<String>,
     },
 
+    /// Create subvolumes at the top level of a freshly formatted Btrfs device
+    CreateBtrfsSubvolumes {
+        device: PathBuf,
+        /// Subvolume names (e.g., "@", "@home")
+        subvolumes: Vec<String>,
+    },
+
     /// Mount a device at a mountpoint
     Mount {
         device: PathBuf,
         mountpoint: PathBuf,
-        /// Optional mount options (e.g., "subvol=@,compress=zstd")
+        /// Optional mount options (e.g., "compress=zstd,noatime")
         options: Option<String>,
     },
+
+    /// Mount one Btrfs subvolume (`subvol=<name>` is prepended to `options`)
+    MountBtrfsSubvolume {
+        device: PathBuf,
+        subvolume: String,
+        mountpoint: PathBuf,
+        options: String,
+    },
+
+    /// Create, format and activate a swapfile on a mounted filesystem
+    CreateSwapfile {
+        /// Swapfile path under `/mnt` (e.g., "/mnt/swapfile")
+        path: PathBuf,
+        /// Size specification (e.g., "8192M", "4G")
+        size: String,
+    },
 }
 
 impl fmt::Display for StorageOp {
@@ -100,16 +225,85 @@ impl fmt::Display for StorageOp {
             Self::Partition {
                 disk,
                 create_efi,
-                create_swap,
+                swap_size,
+                root_size,
+                home_size,
+            } => {
+                write!(
+                    f,
+                    "Partition({}, efi={}, swap={}, root={}, home={})",
+                    disk.display(),
+                    create_efi,
+                    swap_size.as_deref().unwrap_or("none"),
+                    root_size.as_deref().unwrap_or("remaining"),
+                    home_size.as_deref().unwrap_or("none")
+                )
+            }
+            Self::PartitionRaidMember {
+                disk,
+                create_efi,
+                create_boot,
             } => {
                 write!(
                     f,
-                    "Partition({}, efi={}, swap={})",
+                    "PartitionRaidMember({}, efi={}, boot={})",
                     disk.display(),
                     create_efi,
-                    create_swap
+                    create_boot
+                )
+            }
+            Self::PartitionLayout {
+                disk,
+                gpt,
+                partitions,
+            } => {
+                let entries: Vec<String> = partitions
+                    .iter()
+                    .map(|p| format!("{}:{}", p.partition_type, p.size))
+                    .collect();
+                write!(
+                    f,
+                    "PartitionLayout({}, {}, [{}])",
+                    disk.display(),
+                    if *gpt { "gpt" } else { "mbr" },
+                    entries.join(", ")
+                )
+            }
+            Self::ShrinkPartition { device, size } => {
+                write!(f, "ShrinkPartition({}, size={})", device.display(), size)
+            }
+            Self::CreatePartitionInFreeSpace {
+                disk,
+                size,
+                partition_type,
+                label,
+            } => {
+                write!(
+                    f,
+                    "CreatePartitionInFreeSpace({}, {}:{}, label={})",
+                    disk.display(),
+                    partition_type,
+                    size,
+                    label
+                )
+            }
+            Self::CreateRaidArray {
+                device,
+                level,
+                members,
+            } => {
+                let members: Vec<String> =
+                    members.iter().map(|m| m.display().to_string()).collect();
+                write!(
+                    f,
+                    "CreateRaidArray({}, level={}, members=[{}])",
+                    device.display(),
+                    level,
+                    members.join(", ")
                 )
             }
+            Self::WaitRaidSync { device } => write!(f, "WaitRaidSync({})", device.display()),
+            Self::WriteMdadmConf { path } => write!(f, "WriteMdadmConf({})", path.display()),
             Self::LuksFormat { device, label } => {
                 write!(f, "LuksFormat({}, label={})", device.display(), label)
             }
@@ -148,6 +342,14 @@ impl fmt::Display for StorageOp {
                     label
                 )
             }
+            Self::CreateBtrfsSubvolumes { device, subvolumes } => {
+                write!(
+                    f,
+                    "CreateBtrfsSubvolumes({}, [{}])",
+                    device.display(),
+                    subvolumes.join(", ")
+                )
+            }
             Self::Mount {
                 device,
                 mountpoint,
@@ -161,23 +363,125 @@ impl fmt::Display for StorageOp {
                     options
                 )
             }
+            Self::MountBtrfsSubvolume {
+                device,
+                subvolume,
+                mountpoint,
+                options,
+            } => {
+                write!(
+                    f,
+                    "MountBtrfsSubvolume({}[{}] -> {}, opts={})",
+                    device.display(),
+                    subvolume,
+                    mountpoint.display(),
+                    options
+                )
+            }
+            Self::CreateSwapfile { path, size } => {
+                write!(f, "CreateSwapfile({}, size={})", path.display(), size)
+            }
+        }
+    }
+}
+
+/// One partition of a `StorageOp::PartitionLayout`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutEntry {
+    /// Size specification (e.g., "512M", "40G", "remaining")
+    pub size: String,
+    pub partition_type: PartitionType,
+    /// GPT partition name
+    pub label: Option<String>,
+}
+
+/// A single step that undoes the live-environment state left by a `StorageOp`.
+///
+/// Only ops that leave something active (mounts, swap, open mappers, active
+/// VGs, running arrays) have an inverse; on-disk changes are not reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TeardownOp {
+    /// Unmount a mountpoint (recursively)
+    Unmount { mountpoint: PathBuf },
+
+    /// Deactivate a swap device or swapfile
+    SwapOff { device: PathBuf },
+
+    /// Deactivate all logical volumes in a volume group (vgchange -an)
+    DeactivateVg { vg_name: String },
+
+    /// Close (lock) an opened LUKS mapper
+    LuksClose { mapper_name: String },
+
+    /// Stop an mdadm array (mdadm --stop)
+    StopRaidArray { device: PathBuf },
+}
+
+impl fmt::Display for TeardownOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unmount { mountpoint } => write!(f, "Unmount({})", mountpoint.display()),
+            Self::SwapOff { device } => write!(f, "SwapOff({})", device.display()),
+            Self::DeactivateVg { vg_name } => write!(f, "DeactivateVG({})", vg_name),
+            Self::LuksClose { mapper_name } => write!(f, "LuksClose({})", mapper_name),
+            Self::StopRaidArray { device } => write!(f, "StopRaidArray({})", device.display()),
+        }
+    }
+}
+
+impl StorageOp {
+    /// The teardown step that releases what this op leaves active, if any.
+    pub fn inverse(&self) -> Option<TeardownOp> {
+        match self {
+            Self::Mount { mountpoint, .. } | Self::MountBtrfsSubvolume { mountpoint, .. } => {
+                Some(TeardownOp::Unmount {
+                    mountpoint: mountpoint.clone(),
+                })
+            }
+            Self::FormatFs {
+                device,
+                filesystem: Filesystem::Swap,
+                ..
+            } => Some(TeardownOp::SwapOff {
+                device: device.clone(),
+            }),
+            Self::CreateSwapfile { path, .. } => Some(TeardownOp::SwapOff {
+                device: path.clone(),
+            }),
+            Self::CreateLvmVg { vg_name, .. } => Some(TeardownOp::DeactivateVg {
+                vg_name: vg_name.clone(),
+            }),
+            Self::LuksOpen { mapper_name, .. } => Some(TeardownOp::LuksClose {
+                mapper_name: mapper_name.clone(),
+            }),
+            Self::CreateRaidArray { device, .. } => Some(TeardownOp::StopRaidArray {
+                device: device.clone(),
+            }),
+            _ => None,
         }
     }
 }
 
 /// A complete storage plan: an ordered list of operations.
-#[derive(Debug, Clone)]
+///
+/// Serialized to JSON to hand the plan to `archtui apply-storage`, which
+/// `install.sh` runs in place of a partitioning script.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoragePlan {
     /// Ordered sequence of storage operations
     pub ops: Vec<StorageOp>,
     /// The strategy that generated this plan
     pub strategy: PartitionScheme,
-    /// Target disk
+    /// Target disk (first member disk for RAID strategies)
     pub disk: PathBuf,
+    /// All target disks (a single entry unless the strategy uses RAID)
+    pub disks: Vec<PathBuf>,
     /// Whether encryption is involved
     pub encrypted: bool,
     /// Whether LVM is involved
     pub lvm: bool,
+    /// RAID level of the data array, if the strategy uses RAID
+    pub raid_level: Option<String>,
 }
 
 impl StoragePlan {
@@ -188,6 +492,11 @@ impl StoragePlan {
                 op,
                 StorageOp::WipeDisk { .. }
                     | StorageOp::Partition { .. }
+                    | StorageOp::PartitionRaidMember { .. }
+                    | StorageOp::PartitionLayout { .. }
+                    | StorageOp::ShrinkPartition { .. }
+                    | StorageOp::CreatePartitionInFreeSpace { .. }
+                    | StorageOp::CreateRaidArray { .. }
                     | StorageOp::LuksFormat { .. }
                     | StorageOp::FormatFs { .. }
             )
@@ -196,24 +505,218 @@ impl StoragePlan {
 
     /// Returns a summary of the plan for logging/display.
     pub fn summary(&self) -> String {
+        let targets: Vec<String> = self.disks.iter().map(|d| d.display().to_string()).collect();
         let mut lines = vec![
             format!("Storage Plan: {:?}", self.strategy),
-            format!("  Target: {}", self.disk.display()),
+            format!("  Target: {}", targets.join(", ")),
             format!("  Encrypted: {}", self.encrypted),
             format!("  LVM: {}", self.lvm),
-            format!("  Operations ({}):", self.ops.len()),
         ];
+        if let Some(level) = &self.raid_level {
+            lines.push(format!("  RAID: {}", level));
+            if self.raid_esp_count() > 1 {
+                lines.push(format!(
+                    "  ESP: one per member, only {} is mounted and gets the bootloader \
+                     — copy it to the other members to boot from them",
+                    partition_path(&self.disks[0], 1).display()
+                ));
+            }
+        }
+        lines.push(format!("  Operations ({}):", self.ops.len()));
         for (i, op) in self.ops.iter().enumerate() {
             lines.push(format!("    {}. {}", i + 1, op));
         }
         lines.join("\n")
     }
+
+    /// Number of FAT32 ESPs a RAID plan formats, one per member under UEFI.
+    fn raid_esp_count(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| {
+                matches!(
+                    op,
+                    StorageOp::FormatFs {
+                        filesystem: Filesystem::Fat32,
+                        ..
+                    }
+                )
+            })
+            .count()
+    }
+
+    /// Derive the teardown sequence for the first `applied` ops of this plan.
+    ///
+    /// Inverses are returned in reverse order of application, so mounts are
+    /// released before VGs are deactivated, VGs before LUKS mappers are
+    /// closed, and mappers before their RAID arrays are stopped. `applied` is
+    /// clamped to the plan length; pass `self.ops.len()` to tear down a plan
+    /// whose progress is unknown.
+    pub fn teardown(&self, applied: usize) -> Vec<TeardownOp> {
+        let applied = applied.min(self.ops.len());
+        self.ops[..applied]
+            .iter()
+            .rev()
+            .filter_map(StorageOp::inverse)
+            .collect()
+    }
+
+    /// Relocate every path the plan places under `/mnt` to `root` instead.
+    ///
+    /// Used by the loop-device sandbox so a test run never mounts over the
+    /// live system's `/mnt`. Keep `root` below `/mnt` when the plan creates a
+    /// swapfile: `manage_swap.sh` refuses swapfiles anywhere else.
+    pub fn rebased(mut self, root: &Path) -> Self {
+        for op in &mut self.ops {
+            let path = match op {
+                StorageOp::Mount { mountpoint, .. }
+                | StorageOp::MountBtrfsSubvolume { mountpoint, .. } => mountpoint,
+                StorageOp::CreateSwapfile { path, .. } | StorageOp::WriteMdadmConf { path } => path,
+                _ => continue,
+            };
+            if let Ok(rest) = path.strip_prefix("/mnt") {
+                *path = root.components().chain(rest.components()).collect();
+            }
+        }
+        self
+    }
+
+    /// The device the plan gives each role on the installed system, as the
+    /// bootloader, fstab and crypttab steps of `install.sh` need them.
+    pub fn devices(&self) -> PlanDevices {
+        let mounted_at = |target: &str| {
+            self.ops.iter().find_map(|op| match op {
+                StorageOp::Mount {
+                    device, mountpoint, ..
+                }
+                | StorageOp::MountBtrfsSubvolume {
+                    device, mountpoint, ..
+                } if mountpoint == Path::new(target) => Some(device.clone()),
+                _ => None,
+            })
+        };
+        let is_esp = |device: &PathBuf| {
+            self.ops.iter().any(|op| {
+                matches!(op, StorageOp::FormatFs {
+                    device: formatted,
+                    filesystem: Filesystem::Fat32,
+                    ..
+                } if formatted == device)
+            })
+        };
+        let luks_mappers: Vec<(PathBuf, String)> = self
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::LuksOpen {
+                    device,
+                    mapper_name,
+                } => Some((device.clone(), mapper_name.clone())),
+                _ => None,
+            })
+            .collect();
+
+        let boot = mounted_at("/mnt/boot");
+        PlanDevices {
+            root: mounted_at("/mnt"),
+            efi: mounted_at("/mnt/efi")
+                .or_else(|| mounted_at("/mnt/boot/efi"))
+                .or_else(|| boot.clone().filter(is_esp)),
+            boot,
+            home: mounted_at("/mnt/home"),
+            swap: self.ops.iter().find_map(|op| match op {
+                StorageOp::FormatFs {
+                    device,
+                    filesystem: Filesystem::Swap,
+                    ..
+                } => Some(device.clone()),
+                _ => None,
+            }),
+            luks: luks_mappers
+                .iter()
+                .find(|(_, mapper)| mapper == "cryptroot" || mapper == "cryptlvm")
+                .map(|(device, _)| device.clone()),
+            luks_mappers,
+        }
+    }
+}
+
+/// Devices by role, derived from a plan by `StoragePlan::devices`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanDevices {
+    /// Device mounted at `/mnt` (the mapper or LV, not the partition under it)
+    pub root: Option<PathBuf>,
+    /// Device mounted at `/mnt/boot`
+    pub boot: Option<PathBuf>,
+    /// ESP: mounted at `/mnt/efi` or `/mnt/boot/efi`, or at `/mnt/boot` when
+    /// formatted FAT32
+    pub efi: Option<PathBuf>,
+    /// Device mounted at `/mnt/home`
+    pub home: Option<PathBuf>,
+    /// Swap partition or LV
+    pub swap: Option<PathBuf>,
+    /// LUKS container that holds root (opened as `cryptroot` or `cryptlvm`)
+    pub luks: Option<PathBuf>,
+    /// Every LUKS container the plan opens, with its mapper name
+    pub luks_mappers: Vec<(PathBuf, String)>,
+}
+
+impl PlanDevices {
+    /// Bash assignments for `install.sh` to source after the plan ran.
+    ///
+    /// Exports `ROOT_DEVICE`, `BOOT_DEVICE`, `EFI_DEVICE`, `HOME_DEVICE`,
+    /// `SWAP_DEVICE` and `LUKS_DEVICE` (empty when the plan has no such
+    /// device) and `LUKS_MAPPERS` as `device|mapper` entries separated by
+    /// `;`.
+    pub fn to_shell(&self) -> String {
+        let roles = [
+            ("ROOT_DEVICE", &self.root),
+            ("BOOT_DEVICE", &self.boot),
+            ("EFI_DEVICE", &self.efi),
+            ("HOME_DEVICE", &self.home),
+            ("SWAP_DEVICE", &self.swap),
+            ("LUKS_DEVICE", &self.luks),
+        ];
+        let mut script = String::new();
+        for (name, device) in roles {
+            let value = device
+                .as_ref()
+                .map(|d| d.display().to_string())
+                .unwrap_or_default();
+            script.push_str(&format!("export {}={}\n", name, shell_quote(&value)));
+        }
+        let mappers: Vec<String> = self
+            .luks_mappers
+            .iter()
+            .map(|(device, mapper)| format!("{}|{}", device.display(), mapper))
+            .collect();
+        script.push_str(&format!(
+            "export LUKS_MAPPERS={}\n",
+            shell_quote(&mappers.join(";"))
+        ));
+        script
+    }
+}
+
+/// Quote `value` as a single bash word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 // ============================================================================
 // Plan Calculation
 // ============================================================================
 
+/// Machine facts a plan can depend on. `calculate_storage_plan` reads each
+/// one only when the config refers to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostSizes {
+    /// Total RAM (MiB), for RAM-relative swap sizes
+    pub ram_mib: Option<u64>,
+    /// Capacity (MiB) of the first target disk, for percentage root/home sizes
+    pub disk_mib: Option<u64>,
+}
+
 /// Calculate the storage operation plan from an installation config.
 ///
 /// Translates the high-level strategy (AutoSimple, AutoLvm, etc.) into
@@ -222,16 +725,77 @@ impl StoragePlan {
 /// # Errors
 ///
 /// Returns an error if:
-/// - The strategy requires RAID (not yet implemented)
-/// - The strategy is Manual (handled separately by the TUI)
+/// - The strategy is Manual without a `partition_layout` (handled by the TUI)
+/// - A declared `partition_layout` is inconsistent (see `plan_custom_layout`)
 /// - The disk path is empty or invalid
+/// - A RAID strategy has fewer than 2 member disks or an unknown RAID level
+/// - An alongside install has no existing ESP or an invalid shrink target
+/// - `swap_size`, `root_size` or `home_size` is not a valid size
 ///
 /// # What This Explicitly Refuses To Do
 ///
-/// - RAID strategies: Not implemented. Returns error with explanation.
-/// - Manual partitioning: This is interactive and handled by the TUI directly.
-/// - Executing anything: This is pure plan generation. No I/O.
+/// - Interactive manual partitioning: handled by the TUI directly.
+/// - Executing anything: This is pure plan generation. The only I/O is
+///   reading total RAM when `swap_size` is RAM-relative and the disk size
+///   when `root_size`/`home_size` is a percentage.
 pub fn calculate_storage_plan(config: &InstallationConfig) -> Result<StoragePlan> {
+    let ram_mib = if config.swap.has_disk_swap() && swap::is_ram_relative(&config.swap_size) {
+        crate::hardware::detect_ram_mib()
+    } else {
+        None
+    };
+    let disk_mib = if uses_percent_sizes(config) {
+        config
+            .install_disk
+            .split(',')
+            .next()
+            .and_then(|disk| crate::hardware::detect_disk_size_mib(Path::new(disk.trim())))
+    } else {
+        None
+    };
+    calculate_storage_plan_for(config, HostSizes { ram_mib, disk_mib })
+}
+
+/// `calculate_storage_plan` for an install on this machine: `boot_mode`
+/// Auto follows the firmware and an empty `alongside_esp` becomes the ESP
+/// found on the install disk, as `install.sh` resolves them.
+///
+/// This is the plan `archtui apply-storage` executes for a real install.
+pub fn calculate_install_plan(config: &InstallationConfig) -> Result<StoragePlan> {
+    let mut config = config.clone();
+    if config.boot_mode == BootMode::Auto {
+        config.boot_mode = if crate::hardware::detect_firmware_mode().is_uefi() {
+            BootMode::Uefi
+        } else {
+            BootMode::Bios
+        };
+    }
+    if config.partitioning_strategy == PartitionScheme::AutoAlongside
+        && config.alongside_esp.trim().is_empty()
+        && let Some(esp) = crate::hardware::detect_esp_partition(Path::new(&config.install_disk))
+    {
+        config.alongside_esp = esp.display().to_string();
+    }
+    calculate_storage_plan(&config)
+}
+
+/// Whether `install.sh` partitions for `config` by executing its storage
+/// plan (`archtui apply-storage`). Interactive manual partitioning (no
+/// `partition_layout`) and pre-mounted targets have no plan.
+pub fn is_plan_executed(config: &InstallationConfig) -> bool {
+    match config.partitioning_strategy {
+        PartitionScheme::Manual => !config.partition_layout.is_empty(),
+        PartitionScheme::PreMounted => false,
+        _ => true,
+    }
+}
+
+/// `calculate_storage_plan` with the machine's RAM and disk size supplied by
+/// the caller instead of read from `/proc` and sysfs.
+pub fn calculate_storage_plan_for(
+    config: &InstallationConfig,
+    host: HostSizes,
+) -> Result<StoragePlan> {
     let disk = PathBuf::from(&config.install_disk);
 
     if config.install_disk.is_empty() {
@@ -247,29 +811,53 @@ pub fn calculate_storage_plan(config: &InstallationConfig) -> Result<StoragePlan
 
     let strategy = config.partitioning_strategy;
     let root_fs = config.root_filesystem;
-    let wants_swap = config.swap == Toggle::Yes;
-    let swap_size = config.swap_size.clone();
-    let is_uefi = config.boot_mode == crate::types::BootMode::Uefi
-        || config.boot_mode == crate::types::BootMode::Auto;
+    let is_uefi = config.boot_mode == BootMode::Uefi || config.boot_mode == BootMode::Auto;
+    let sizes = data_sizes(config, host.disk_mib)?;
+
+    // Swap on disk: a partition/LV where the strategy has a slot for one,
+    // otherwise a swapfile inside the (possibly encrypted) root
+    let swap_size = if config.swap.has_disk_swap() {
+        Some(swap::swap_size_spec(
+            &config.swap_size,
+            host.ram_mib,
+            config.hibernation == Toggle::Yes,
+        )?)
+    } else {
+        None
+    };
+    let (swap_partition, swapfile_size) = if uses_swapfile(config) {
+        (None, swap_size)
+    } else {
+        (swap_size, None)
+    };
+    let subvolumes = btrfs_subvolumes_for(config);
+    let swap_partition = swap_partition.as_deref();
 
-    match strategy {
-        PartitionScheme::AutoSimple => plan_simple(&disk, root_fs, is_uefi, wants_swap, &swap_size),
+    let mut plan = match strategy {
+        PartitionScheme::AutoSimple => {
+            plan_simple(&disk, root_fs, is_uefi, swap_partition, &sizes, &subvolumes)
+        }
         PartitionScheme::AutoSimpleLuks => {
-            plan_simple_luks(&disk, root_fs, is_uefi, wants_swap, &swap_size)
+            plan_simple_luks(&disk, root_fs, is_uefi, &sizes, &subvolumes)
+        }
+        PartitionScheme::AutoLvm => {
+            plan_lvm(&disk, root_fs, is_uefi, swap_partition, &sizes, &subvolumes)
         }
-        PartitionScheme::AutoLvm => plan_lvm(&disk, root_fs, is_uefi, wants_swap, &swap_size),
         PartitionScheme::AutoLuksLvm => {
-            plan_luks_lvm(&disk, root_fs, is_uefi, wants_swap, &swap_size)
+            plan_luks_lvm(&disk, root_fs, is_uefi, swap_partition, &sizes, &subvolumes)
         }
         PartitionScheme::AutoRaid
         | PartitionScheme::AutoRaidLuks
         | PartitionScheme::AutoRaidLvm
         | PartitionScheme::AutoRaidLvmLuks => {
-            bail!(
-                "RAID strategy {:?} is not yet implemented. \
-                 Prerequisite: multi-disk selection and mdadm integration.",
-                strategy
-            )
+            let disks = parse_raid_disks(&config.install_disk)?;
+            plan_raid(config, &disks, swap_partition, &sizes, &subvolumes)
+        }
+        PartitionScheme::AutoAlongside => {
+            plan_alongside(config, &disk, is_uefi, swap_partition, &subvolumes)
+        }
+        PartitionScheme::Manual if !config.partition_layout.is_empty() => {
+            plan_custom_layout(&disk, &config.partition_layout, is_uefi, &subvolumes)
         }
         PartitionScheme::Manual => {
             bail!(
@@ -283,27 +871,50 @@ pub fn calculate_storage_plan(config: &InstallationConfig) -> Result<StoragePlan
                  it detects existing mounts at /mnt."
             )
         }
+    }?;
+
+    // Swapfile once every filesystem (and the @swap subvolume) is mounted
+    if let Some(size) = swapfile_size {
+        let path = swapfile_path(&plan.ops);
+        let after_mounts = plan
+            .ops
+            .iter()
+            .rposition(|op| {
+                matches!(
+                    op,
+                    StorageOp::Mount { .. } | StorageOp::MountBtrfsSubvolume { .. }
+                )
+            })
+            .map_or(plan.ops.len(), |i| i + 1);
+        plan.ops
+            .insert(after_mounts, StorageOp::CreateSwapfile { path, size });
     }
+    Ok(plan)
 }
 
 // ============================================================================
 // Strategy Implementations
 // ============================================================================
 
-/// Plan: Simple partitioning (EFI + root, optional swap partition)
+/// Plan: Simple partitioning (EFI + root, optional swap and home partitions)
 ///
 /// Disk layout:
 /// ```text
-/// /dev/sdX1 - EFI System Partition (512M, FAT32) [if UEFI]
-/// /dev/sdX2 - Root partition (remaining space)
-/// /dev/sdX3 - Swap partition [if enabled]
+/// /dev/sdX1 - EFI System Partition (512M, FAT32) [UEFI] or BIOS boot (1M) [BIOS]
+/// /dev/sdX2 - Swap partition [if enabled]
+/// /dev/sdX3 - Root partition (remaining space, or `root_size` with a home)
+/// /dev/sdX4 - Home partition (`home_size`) [if separate]
 /// ```
+///
+/// Swap sits before root so root (or home) can always take the remaining
+/// space.
 fn plan_simple(
     disk: &Path,
     root_fs: Filesystem,
     is_uefi: bool,
-    wants_swap: bool,
-    swap_size: &str,
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
 ) -> Result<StoragePlan> {
     let mut ops = Vec::new();
 
@@ -313,22 +924,22 @@ fn plan_simple(
     });
 
     // Step 2: Create partitions
-    ops.push(StorageOp::Partition {
-        disk: disk.to_path_buf(),
-        create_efi: is_uefi,
-        create_swap: wants_swap,
-    });
+    ops.push(partition_op(disk, is_uefi, swap_size, sizes));
 
     // Step 3: Format EFI partition
-    let root_part_num = if is_uefi {
+    // Partition 1 is the ESP (UEFI) or the BIOS boot partition (BIOS)
+    if is_uefi {
         ops.push(StorageOp::FormatFs {
             device: partition_path(disk, 1),
             filesystem: Filesystem::Fat32,
             label: Some("EFI".to_string()),
         });
-        2
+    }
+    let first_data_part = 2;
+    let root_part_num = if swap_size.is_some() {
+        first_data_part + 1
     } else {
-        1
+        first_data_part
     };
 
     // Step 4: Format root partition
@@ -339,13 +950,11 @@ fn plan_simple(
         label: Some("archroot".to_string()),
     });
 
-    // Step 5: Mount root
-    let mount_opts = btrfs_mount_options(root_fs);
-    ops.push(StorageOp::Mount {
-        device: root_device,
-        mountpoint: PathBuf::from("/mnt"),
-        options: mount_opts,
-    });
+    // Step 5: Mount root, then the home partition (if separate)
+    ops.extend(root_mount_ops(root_device, root_fs, subvolumes)?);
+    if let Some(home) = &sizes.home {
+        ops.extend(home_ops(partition_path(disk, root_part_num + 1), home));
+    }
 
     // Step 6: Mount EFI
     if is_uefi {
@@ -357,12 +966,11 @@ fn plan_simple(
     }
 
     // Step 7: Swap partition (if requested, non-LVM)
-    if wants_swap {
-        let swap_part = partition_path(disk, root_part_num + 1);
+    if swap_size.is_some() {
         ops.push(StorageOp::FormatFs {
-            device: swap_part,
+            device: partition_path(disk, first_data_part),
             filesystem: Filesystem::Swap,
-            label: Some(format!("swap:{}", swap_size)),
+            label: Some("swap".to_string()),
         });
     }
 
@@ -370,24 +978,29 @@ fn plan_simple(
         ops,
         strategy: PartitionScheme::AutoSimple,
         disk: disk.to_path_buf(),
+        disks: vec![disk.to_path_buf()],
         encrypted: false,
         lvm: false,
+        raid_level: None,
     })
 }
 
-/// Plan: Simple + LUKS encryption on root
+/// Plan: Simple + LUKS encryption on root (and on home, if separate)
 ///
 /// Disk layout:
 /// ```text
-/// /dev/sdX1 - EFI System Partition (512M, FAT32) [if UEFI]
+/// /dev/sdX1 - EFI System Partition (512M, FAT32) [UEFI] or BIOS boot (1M) [BIOS]
 /// /dev/sdX2 - LUKS-encrypted root → /dev/mapper/cryptroot
+/// /dev/sdX3 - LUKS-encrypted home → /dev/mapper/crypthome [if separate]
 /// ```
+///
+/// There is no swap partition: swap is a swapfile inside the encrypted root.
 fn plan_simple_luks(
     disk: &Path,
     root_fs: Filesystem,
     is_uefi: bool,
-    wants_swap: bool,
-    swap_size: &str,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
 ) -> Result<StoragePlan> {
     let mut ops = Vec::new();
 
@@ -395,38 +1008,23 @@ fn plan_simple_luks(
         disk: disk.to_path_buf(),
     });
 
-    ops.push(StorageOp::Partition {
-        disk: disk.to_path_buf(),
-        create_efi: is_uefi,
-        create_swap: false, // Swap goes inside LUKS or as swapfile
-    });
+    // Swap is a swapfile inside LUKS
+    ops.push(partition_op(disk, is_uefi, None, sizes));
 
-    let root_part_num = if is_uefi {
+    // Partition 1 is the ESP (UEFI) or the BIOS boot partition (BIOS)
+    if is_uefi {
         ops.push(StorageOp::FormatFs {
             device: partition_path(disk, 1),
             filesystem: Filesystem::Fat32,
             label: Some("EFI".to_string()),
         });
-        2
-    } else {
-        1
-    };
-
-    let root_part = partition_path(disk, root_part_num);
+    }
+    let root_part_num = 2;
 
     // LUKS format + open
-    ops.push(StorageOp::LuksFormat {
-        device: root_part.clone(),
-        label: "cryptroot".to_string(),
-    });
-    ops.push(StorageOp::LuksOpen {
-        device: root_part,
-        mapper_name: "cryptroot".to_string(),
-    });
+    let mapper_device = luks_ops(&mut ops, partition_path(disk, root_part_num), "cryptroot");
 
     // Format the decrypted device
-    let mapper_device = PathBuf::from("/dev/mapper/cryptroot");
-    let mount_opts = btrfs_mount_options(root_fs);
     ops.push(StorageOp::FormatFs {
         device: mapper_device.clone(),
         filesystem: root_fs,
@@ -434,11 +1032,16 @@ fn plan_simple_luks(
     });
 
     // Mount
-    ops.push(StorageOp::Mount {
-        device: mapper_device,
-        mountpoint: PathBuf::from("/mnt"),
-        options: mount_opts,
-    });
+    ops.extend(root_mount_ops(mapper_device, root_fs, subvolumes)?);
+
+    if let Some(home) = &sizes.home {
+        let home_mapper = luks_ops(
+            &mut ops,
+            partition_path(disk, root_part_num + 1),
+            "crypthome",
+        );
+        ops.extend(home_ops(home_mapper, home));
+    }
 
     if is_uefi {
         ops.push(StorageOp::Mount {
@@ -448,16 +1051,14 @@ fn plan_simple_luks(
         });
     }
 
-    // Swap note: with LUKS, swap is typically a swapfile inside the encrypted root
-    // or an encrypted swap partition. For simplicity, we skip swap partition here.
-    let _ = (wants_swap, swap_size); // Acknowledge but defer
-
     Ok(StoragePlan {
         ops,
         strategy: PartitionScheme::AutoSimpleLuks,
         disk: disk.to_path_buf(),
+        disks: vec![disk.to_path_buf()],
         encrypted: true,
         lvm: false,
+        raid_level: None,
     })
 }
 
@@ -465,92 +1066,45 @@ fn plan_simple_luks(
 ///
 /// Disk layout:
 /// ```text
-/// /dev/sdX1 - EFI System Partition (512M, FAT32) [if UEFI]
-/// /dev/sdX2 - LVM PV → VG "archvg" → LV "root" + LV "swap"
+/// /dev/sdX1 - EFI System Partition (512M, FAT32) [UEFI] or BIOS boot (1M) [BIOS]
+/// /dev/sdX2 - LVM PV → VG "archvg" → LV "swap" + LV "root" [+ LV "home"]
 /// ```
 fn plan_lvm(
     disk: &Path,
     root_fs: Filesystem,
     is_uefi: bool,
-    wants_swap: bool,
-    swap_size: &str,
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
 ) -> Result<StoragePlan> {
     let mut ops = Vec::new();
-    let vg_name = "archvg";
 
     ops.push(StorageOp::WipeDisk {
         disk: disk.to_path_buf(),
     });
 
-    ops.push(StorageOp::Partition {
-        disk: disk.to_path_buf(),
-        create_efi: is_uefi,
-        create_swap: false, // LVM manages swap
-    });
+    // LVM manages swap
+    ops.push(partition_op(disk, is_uefi, None, &DataSizes::default()));
 
-    let lvm_part_num = if is_uefi {
+    // Partition 1 is the ESP (UEFI) or the BIOS boot partition (BIOS)
+    if is_uefi {
         ops.push(StorageOp::FormatFs {
             device: partition_path(disk, 1),
             filesystem: Filesystem::Fat32,
             label: Some("EFI".to_string()),
         });
-        2
-    } else {
-        1
-    };
-
-    let lvm_part = partition_path(disk, lvm_part_num);
-
-    // LVM: PV → VG → LVs
-    ops.push(StorageOp::CreateLvmPv {
-        device: lvm_part.clone(),
-    });
-    ops.push(StorageOp::CreateLvmVg {
-        vg_name: vg_name.to_string(),
-        pv_device: lvm_part,
-    });
-
-    // Swap LV (if requested) — create first so root gets remaining space
-    if wants_swap {
-        ops.push(StorageOp::CreateLvmLv {
-            vg_name: vg_name.to_string(),
-            lv_name: "swap".to_string(),
-            size: format!("{}G", swap_size.trim_end_matches('G').trim_end_matches('g')),
-        });
-    }
-
-    // Root LV — takes all remaining space
-    ops.push(StorageOp::CreateLvmLv {
-        vg_name: vg_name.to_string(),
-        lv_name: "root".to_string(),
-        size: "100%FREE".to_string(),
-    });
-
-    // Format root LV
-    let root_lv = PathBuf::from(format!("/dev/{}/root", vg_name));
-    let mount_opts = btrfs_mount_options(root_fs);
-    ops.push(StorageOp::FormatFs {
-        device: root_lv.clone(),
-        filesystem: root_fs,
-        label: Some("archroot".to_string()),
-    });
-
-    // Format swap LV
-    if wants_swap {
-        let swap_lv = PathBuf::from(format!("/dev/{}/swap", vg_name));
-        ops.push(StorageOp::FormatFs {
-            device: swap_lv,
-            filesystem: Filesystem::Swap,
-            label: Some("swap".to_string()),
-        });
     }
-
-    // Mount root
-    ops.push(StorageOp::Mount {
-        device: root_lv,
-        mountpoint: PathBuf::from("/mnt"),
-        options: mount_opts,
-    });
+    let lvm_part_num = 2;
+
+    // LVM: PV → VG → LVs, then format and mount them
+    lvm_ops(
+        &mut ops,
+        partition_path(disk, lvm_part_num),
+        root_fs,
+        swap_size,
+        sizes,
+        subvolumes,
+    )?;
 
     // Mount EFI
     if is_uefi {
@@ -565,8 +1119,10 @@ fn plan_lvm(
         ops,
         strategy: PartitionScheme::AutoLvm,
         disk: disk.to_path_buf(),
+        disks: vec![disk.to_path_buf()],
         encrypted: false,
         lvm: true,
+        raid_level: None,
     })
 }
 
@@ -574,114 +1130,612 @@ fn plan_lvm(
 ///
 /// Disk layout:
 /// ```text
-/// /dev/sdX1 - EFI System Partition (512M, FAT32) [if UEFI]
-/// /dev/sdX2 - LUKS → /dev/mapper/cryptlvm → PV → VG "archvg" → LV "root" + LV "swap"
+/// /dev/sdX1 - EFI System Partition (512M, FAT32) [UEFI] or BIOS boot (1M) [BIOS]
+/// /dev/sdX2 - LUKS → /dev/mapper/cryptlvm → PV → VG "archvg" → LV "swap" + LV "root" [+ LV "home"]
 /// ```
 fn plan_luks_lvm(
     disk: &Path,
     root_fs: Filesystem,
     is_uefi: bool,
-    wants_swap: bool,
-    swap_size: &str,
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
 ) -> Result<StoragePlan> {
     let mut ops = Vec::new();
-    let vg_name = "archvg";
 
     ops.push(StorageOp::WipeDisk {
         disk: disk.to_path_buf(),
     });
 
-    ops.push(StorageOp::Partition {
-        disk: disk.to_path_buf(),
-        create_efi: is_uefi,
-        create_swap: false,
-    });
+    ops.push(partition_op(disk, is_uefi, None, &DataSizes::default()));
 
-    let luks_part_num = if is_uefi {
+    // Partition 1 is the ESP (UEFI) or the BIOS boot partition (BIOS)
+    if is_uefi {
         ops.push(StorageOp::FormatFs {
             device: partition_path(disk, 1),
             filesystem: Filesystem::Fat32,
             label: Some("EFI".to_string()),
         });
-        2
-    } else {
-        1
-    };
+    }
+    let luks_part_num = 2;
+
+    // LUKS, then LVM on top of it
+    let mapper_device = luks_ops(&mut ops, partition_path(disk, luks_part_num), "cryptlvm");
+    lvm_ops(
+        &mut ops,
+        mapper_device,
+        root_fs,
+        swap_size,
+        sizes,
+        subvolumes,
+    )?;
 
-    let luks_part = partition_path(disk, luks_part_num);
+    if is_uefi {
+        ops.push(StorageOp::Mount {
+            device: partition_path(disk, 1),
+            mountpoint: PathBuf::from("/mnt/boot"),
+            options: None,
+        });
+    }
 
-    // LUKS
-    ops.push(StorageOp::LuksFormat {
-        device: luks_part.clone(),
-        label: "cryptlvm".to_string(),
-    });
-    ops.push(StorageOp::LuksOpen {
-        device: luks_part,
-        mapper_name: "cryptlvm".to_string(),
-    });
+    Ok(StoragePlan {
+        ops,
+        strategy: PartitionScheme::AutoLuksLvm,
+        disk: disk.to_path_buf(),
+        disks: vec![disk.to_path_buf()],
+        encrypted: true,
+        lvm: true,
+        raid_level: None,
+    })
+}
 
-    let mapper_device = PathBuf::from("/dev/mapper/cryptlvm");
+/// Plan: Software RAID (mdadm) across all member disks, optionally with
+/// LUKS and/or LVM layered on the data array.
+///
+/// Every member disk is partitioned identically. The boot array is always
+/// RAID1 for redundancy; the data array uses `raid_level`. The ESP cannot live
+/// on an md array, so every member's ESP is formatted but only the first one
+/// is mounted (and gets the bootloader).
+///
+/// Disk layout (per member disk):
+/// ```text
+/// UEFI (GRUB/rEFInd/Limine):
+///   /dev/sdX1 - EFI System Partition (512M, FAT32) → /mnt/efi
+///   /dev/sdX2 - XBOOTLDR member → /dev/md/XBOOTLDR (RAID1, ext4) → /mnt/boot
+///   /dev/sdX3 - data member → /dev/md/DATA
+/// UEFI (systemd-boot/EFISTUB — kernels must be on FAT32):
+///   /dev/sdX1 - EFI System Partition (1G, FAT32) → /mnt/boot
+///   /dev/sdX2 - data member → /dev/md/DATA
+/// BIOS:
+///   /dev/sdX1 - BIOS boot partition (1M, unformatted)
+///   /dev/sdX2 - boot member → /dev/md/BOOT (RAID1, ext4) → /mnt/boot
+///   /dev/sdX3 - data member → /dev/md/DATA
+/// ```
+///
+/// Swap and home: with LVM they are LVs next to root; without LVM the array
+/// is a single device with no slot for either, so both are rejected
+/// (swapfiles are added by the caller as `CreateSwapfile`).
+fn plan_raid(
+    config: &InstallationConfig,
+    disks: &[PathBuf],
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
+) -> Result<StoragePlan> {
+    let strategy = config.partitioning_strategy;
+    let level = config.raid_level.trim();
+    if !RAID_LEVELS.contains(&level) {
+        bail!(
+            "Invalid RAID level '{}' — must be one of: {}",
+            level,
+            RAID_LEVELS.join(", ")
+        );
+    }
+    if level == "raid5" && disks.len() < 3 {
+        bail!("raid5 requires at least 3 disks (found {})", disks.len());
+    }
+    // raid10 uses mdadm's near layout, which works from two members
+    if level == "raid6" && disks.len() < 4 {
+        bail!("raid6 requires at least 4 disks (found {})", disks.len());
+    }
 
-    // LVM on top of LUKS
-    ops.push(StorageOp::CreateLvmPv {
-        device: mapper_device.clone(),
-    });
-    ops.push(StorageOp::CreateLvmVg {
-        vg_name: vg_name.to_string(),
-        pv_device: mapper_device,
-    });
+    let root_fs = config.root_filesystem;
+    let is_uefi = config.boot_mode == BootMode::Uefi || config.boot_mode == BootMode::Auto;
+    // systemd-boot/EFISTUB read kernels from the ESP, so no separate boot array
+    let boot_array = if !is_uefi {
+        Some(PathBuf::from("/dev/md/BOOT"))
+    } else if config.bootloader.requires_fat32_boot() {
+        None
+    } else {
+        Some(PathBuf::from("/dev/md/XBOOTLDR"))
+    };
+    let data_part_num = if boot_array.is_some() { 3 } else { 2 };
+    let encrypted = strategy.uses_encryption();
+    let lvm = strategy.uses_lvm();
+    // Members carry no swap partition; only the LVM variants have a slot
+    if swap_size.is_some() && !lvm {
+        bail!(
+            "{:?} has no slot for a swap partition — use swap 'swapfile' or 'zram', \
+             or an LVM RAID strategy",
+            strategy
+        );
+    }
+    if sizes.home.is_some() && !lvm {
+        bail!(
+            "{:?} has no slot for a separate home — disable separate_home, \
+             or use an LVM RAID strategy",
+            strategy
+        );
+    }
 
-    if wants_swap {
-        ops.push(StorageOp::CreateLvmLv {
-            vg_name: vg_name.to_string(),
-            lv_name: "swap".to_string(),
-            size: format!("{}G", swap_size.trim_end_matches('G').trim_end_matches('g')),
+    let mut ops = Vec::new();
+
+    // Step 1: Wipe and partition every member identically
+    for disk in disks {
+        ops.push(StorageOp::WipeDisk { disk: disk.clone() });
+    }
+    for disk in disks {
+        ops.push(StorageOp::PartitionRaidMember {
+            disk: disk.clone(),
+            create_efi: is_uefi,
+            create_boot: boot_array.is_some(),
         });
     }
 
-    ops.push(StorageOp::CreateLvmLv {
-        vg_name: vg_name.to_string(),
-        lv_name: "root".to_string(),
-        size: "100%FREE".to_string(),
+    // Step 2: Create arrays (boot is always RAID1), then wait for them
+    if let Some(boot_md) = &boot_array {
+        ops.push(StorageOp::CreateRaidArray {
+            device: boot_md.clone(),
+            level: "raid1".to_string(),
+            members: disks.iter().map(|d| partition_path(d, 2)).collect(),
+        });
+    }
+    let data_md = PathBuf::from("/dev/md/DATA");
+    ops.push(StorageOp::CreateRaidArray {
+        device: data_md.clone(),
+        level: level.to_string(),
+        members: disks
+            .iter()
+            .map(|d| partition_path(d, data_part_num))
+            .collect(),
     });
-
-    let root_lv = PathBuf::from(format!("/dev/{}/root", vg_name));
-    let mount_opts = btrfs_mount_options(root_fs);
-    ops.push(StorageOp::FormatFs {
-        device: root_lv.clone(),
-        filesystem: root_fs,
-        label: Some("archroot".to_string()),
+    if let Some(boot_md) = &boot_array {
+        ops.push(StorageOp::WaitRaidSync {
+            device: boot_md.clone(),
+        });
+    }
+    ops.push(StorageOp::WaitRaidSync {
+        device: data_md.clone(),
     });
 
-    if wants_swap {
-        let swap_lv = PathBuf::from(format!("/dev/{}/swap", vg_name));
+    // Step 3: Format boot array and every member's ESP. Firmware cannot read
+    // an md array, so the ESPs stay plain partitions; only the first one is
+    // mounted (see `StoragePlan::summary`)
+    if let Some(boot_md) = &boot_array {
         ops.push(StorageOp::FormatFs {
-            device: swap_lv,
-            filesystem: Filesystem::Swap,
-            label: Some("swap".to_string()),
+            device: boot_md.clone(),
+            filesystem: Filesystem::Ext4,
+            label: Some("BOOT".to_string()),
         });
     }
+    let esp = partition_path(&disks[0], 1);
+    if is_uefi {
+        for disk in disks {
+            ops.push(StorageOp::FormatFs {
+                device: partition_path(disk, 1),
+                filesystem: Filesystem::Fat32,
+                label: Some("EFI".to_string()),
+            });
+        }
+    }
 
-    ops.push(StorageOp::Mount {
-        device: root_lv,
-        mountpoint: PathBuf::from("/mnt"),
-        options: mount_opts,
-    });
+    // Step 4: Optional LUKS on the data array
+    let mut data_device = data_md;
+    if encrypted {
+        let mapper_name = if lvm { "cryptlvm" } else { "cryptroot" };
+        data_device = luks_ops(&mut ops, data_device, mapper_name);
+    }
 
-    if is_uefi {
+    // Step 5: LVM volumes or a plain root on the data array, formatted and mounted
+    if lvm {
+        lvm_ops(&mut ops, data_device, root_fs, swap_size, sizes, subvolumes)?;
+    } else {
+        ops.push(StorageOp::FormatFs {
+            device: data_device.clone(),
+            filesystem: root_fs,
+            label: Some("archroot".to_string()),
+        });
+        ops.extend(root_mount_ops(data_device, root_fs, subvolumes)?);
+    }
+
+    // Step 6: Mount boot/ESP
+    if let Some(boot_md) = &boot_array {
         ops.push(StorageOp::Mount {
-            device: partition_path(disk, 1),
+            device: boot_md.clone(),
             mountpoint: PathBuf::from("/mnt/boot"),
             options: None,
         });
     }
+    if is_uefi {
+        let esp_mountpoint = if boot_array.is_some() {
+            "/mnt/efi"
+        } else {
+            "/mnt/boot"
+        };
+        ops.push(StorageOp::Mount {
+            device: esp,
+            mountpoint: PathBuf::from(esp_mountpoint),
+            options: None,
+        });
+    }
+
+    // Step 7: Persist array definitions so the initramfs can assemble them
+    ops.push(StorageOp::WriteMdadmConf {
+        path: PathBuf::from("/mnt/etc/mdadm.conf"),
+    });
 
     Ok(StoragePlan {
         ops,
-        strategy: PartitionScheme::AutoLuksLvm,
+        strategy,
+        disk: disks[0].clone(),
+        disks: disks.to_vec(),
+        encrypted,
+        lvm,
+        raid_level: Some(level.to_string()),
+    })
+}
+
+/// Plan: Declarative layout from `partition_layout` (Manual strategy)
+///
+/// Partitions are created in the declared order and numbered from 1. Each
+/// entry is optionally LUKS-formatted and opened, then formatted, and
+/// finally mounted shallowest-first so `/` is mounted before `/boot`.
+///
+/// Mapper names are `cryptroot` for the entry mounted at `/` (matching the
+/// other strategies) and `cryptN` (N = partition number) otherwise.
+///
+/// A Btrfs entry at `/` is mounted through `root_mount_ops` with the
+/// configured subvolumes, minus those whose mountpoint another entry takes
+/// (a declared `/home` partition replaces `@home`).
+///
+/// The table is GPT under UEFI or when a BIOS boot partition is declared,
+/// MBR otherwise.
+///
+/// # Errors
+///
+/// Rejects layouts that are not installable as declared: no `/` mountpoint,
+/// duplicate or relative mountpoints, a `remaining` size before the last
+/// entry, mountpoints on swap or unformatted entries, encrypted ESP/BIOS boot
+/// entries, a missing ESP under UEFI, or more than 4 entries on MBR.
+fn plan_custom_layout(
+    disk: &Path,
+    layout: &[LayoutPartition],
+    is_uefi: bool,
+    subvolumes: &[BtrfsSubvolume],
+) -> Result<StoragePlan> {
+    let gpt = is_uefi
+        || layout
+            .iter()
+            .any(|p| p.partition_type == PartitionType::BiosBoot);
+    if !gpt && layout.len() > 4 {
+        bail!(
+            "partition_layout has {} entries but an MBR table holds at most 4",
+            layout.len()
+        );
+    }
+    if is_uefi
+        && !layout
+            .iter()
+            .any(|p| p.partition_type == PartitionType::Efi)
+    {
+        bail!("partition_layout needs an EFI System Partition (type EF00) for UEFI boot");
+    }
+
+    let root_count = layout
+        .iter()
+        .filter(|p| p.mountpoint.as_deref() == Some("/"))
+        .count();
+    if root_count != 1 {
+        bail!(
+            "partition_layout must mount exactly one entry at '/' (found {})",
+            root_count
+        );
+    }
+
+    let mut entries = Vec::with_capacity(layout.len());
+    let mut mountpoints: Vec<&str> = Vec::new();
+    for (i, part) in layout.iter().enumerate() {
+        let number = i + 1;
+        let size = layout_size_spec(&part.size)
+            .map_err(|e| anyhow::anyhow!("partition_layout entry {}: {}", number, e))?;
+        if size == "remaining" && number != layout.len() {
+            bail!(
+                "partition_layout entry {}: only the last entry can use the remaining space",
+                number
+            );
+        }
+        if part.encrypt
+            && matches!(
+                part.partition_type,
+                PartitionType::Efi | PartitionType::BiosBoot
+            )
+        {
+            bail!(
+                "partition_layout entry {}: {} partitions cannot be encrypted",
+                number,
+                part.partition_type.display_name()
+            );
+        }
+        if let Some(mountpoint) = part.mountpoint.as_deref() {
+            if !mountpoint.starts_with('/')
+                || Path::new(mountpoint)
+                    .components()
+                    .any(|c| c == Component::ParentDir)
+            {
+                bail!(
+                    "partition_layout entry {}: mountpoint '{}' must be an absolute path without '..'",
+                    number,
+                    mountpoint
+                );
+            }
+            match part.filesystem {
+                None => bail!(
+                    "partition_layout entry {}: mountpoint '{}' needs a filesystem",
+                    number,
+                    mountpoint
+                ),
+                Some(Filesystem::Swap) => bail!(
+                    "partition_layout entry {}: swap cannot be mounted at '{}'",
+                    number,
+                    mountpoint
+                ),
+                Some(_) => {}
+            }
+            if mountpoints.contains(&mountpoint) {
+                bail!(
+                    "partition_layout mounts more than one entry at '{}'",
+                    mountpoint
+                );
+            }
+            mountpoints.push(mountpoint);
+        }
+        entries.push(LayoutEntry {
+            size,
+            partition_type: part.partition_type,
+            label: part.label.clone(),
+        });
+    }
+
+    let mut ops = vec![
+        StorageOp::WipeDisk {
+            disk: disk.to_path_buf(),
+        },
+        StorageOp::PartitionLayout {
+            disk: disk.to_path_buf(),
+            gpt,
+            partitions: entries,
+        },
+    ];
+
+    // Subvolumes the layout does not mount a partition over
+    let subvolumes: Vec<BtrfsSubvolume> = subvolumes
+        .iter()
+        .filter(|s| s.mountpoint == "/" || !mountpoints.contains(&s.mountpoint.as_str()))
+        .cloned()
+        .collect();
+
+    // Encrypt + format in partition order, remembering where each mount goes
+    let mut mounts: Vec<StorageOp> = Vec::new();
+    for (i, part) in layout.iter().enumerate() {
+        let number = i + 1;
+        let part_device = partition_path(disk, number as u32);
+        let device = if part.encrypt {
+            let mapper_name = if part.mountpoint.as_deref() == Some("/") {
+                "cryptroot".to_string()
+            } else {
+                format!("crypt{}", number)
+            };
+            ops.push(StorageOp::LuksFormat {
+                device: part_device.clone(),
+                label: mapper_name.clone(),
+            });
+            ops.push(StorageOp::LuksOpen {
+                device: part_device,
+                mapper_name: mapper_name.clone(),
+            });
+            PathBuf::from(format!("/dev/mapper/{}", mapper_name))
+        } else {
+            part_device
+        };
+        if let Some(filesystem) = part.filesystem {
+            ops.push(StorageOp::FormatFs {
+                device: device.clone(),
+                filesystem,
+                label: part.label.clone(),
+            });
+        }
+        match part.mountpoint.as_deref() {
+            // A Btrfs root gets the configured subvolumes, like the automatic plans
+            Some("/") if part.filesystem == Some(Filesystem::Btrfs) && !subvolumes.is_empty() => {
+                // The entry's options apply to subvolumes that set none
+                let subvolumes: Vec<BtrfsSubvolume> = subvolumes
+                    .iter()
+                    .map(|s| BtrfsSubvolume {
+                        mount_options: s.mount_options.clone().or(part.mount_options.clone()),
+                        ..s.clone()
+                    })
+                    .collect();
+                let mut root_ops = root_mount_ops(device, Filesystem::Btrfs, &subvolumes)?;
+                let subvolume_mounts = root_ops.split_off(1);
+                ops.append(&mut root_ops);
+                mounts.extend(subvolume_mounts);
+            }
+            Some(mountpoint) => mounts.push(StorageOp::Mount {
+                device,
+                mountpoint: target_path(mountpoint),
+                options: part.mount_options.clone(),
+            }),
+            None => {}
+        }
+    }
+
+    // Mount parents before children (stable, so equal depths keep layout order)
+    mounts.sort_by_key(|op| match op {
+        StorageOp::Mount { mountpoint, .. } | StorageOp::MountBtrfsSubvolume { mountpoint, .. } => {
+            mountpoint.components().count()
+        }
+        _ => 0,
+    });
+    ops.extend(mounts);
+
+    Ok(StoragePlan {
+        ops,
+        strategy: PartitionScheme::Manual,
         disk: disk.to_path_buf(),
-        encrypted: true,
-        lvm: true,
+        disks: vec![disk.to_path_buf()],
+        encrypted: layout.iter().any(|p| p.encrypt),
+        lvm: false,
+        raid_level: None,
+    })
+}
+
+/// GPT names of the partitions an alongside install creates.
+const ALONGSIDE_BOOT_LABEL: &str = "archtui-boot";
+const ALONGSIDE_SWAP_LABEL: &str = "archtui-swap";
+const ALONGSIDE_ROOT_LABEL: &str = "archtui-root";
+
+/// Plan: Install alongside an existing OS (dual-boot, UEFI/GPT only)
+///
+/// Disk layout (existing partitions are kept as-is):
+/// ```text
+/// <existing>         - ESP from `alongside_esp` (mounted at /efi, not formatted)
+/// <shrink_partition> - Shrunk to `shrink_size` [if set]
+/// archtui-boot       - /boot (1G, ext4) in free space
+/// archtui-swap       - Swap partition in free space [if enabled]
+/// archtui-root       - Root partition (rest of the free block)
+/// ```
+///
+/// The ESP goes to /efi with a separate /boot, since an existing ESP is
+/// shared with the other OS.
+fn plan_alongside(
+    config: &InstallationConfig,
+    disk: &Path,
+    is_uefi: bool,
+    swap_size: Option<&str>,
+    subvolumes: &[BtrfsSubvolume],
+) -> Result<StoragePlan> {
+    if !is_uefi {
+        bail!("The alongside strategy requires UEFI boot — it reuses the existing ESP");
+    }
+    let esp = config.alongside_esp.trim();
+    if esp.is_empty() {
+        bail!("The alongside strategy needs alongside_esp — the existing EFI System Partition");
+    }
+    if !esp.starts_with("/dev/") {
+        bail!("Invalid alongside_esp '{}' — must start with /dev/", esp);
+    }
+
+    let mut ops = Vec::new();
+
+    // Step 1: Free up space by shrinking an existing partition (optional)
+    let shrink_partition = config.shrink_partition.trim();
+    let shrink_size = config.shrink_size.trim();
+    match (shrink_partition.is_empty(), shrink_size.is_empty()) {
+        (true, true) => {}
+        (false, false) => {
+            if !is_partition_of(disk, shrink_partition) {
+                bail!(
+                    "shrink_partition '{}' is not a partition of {}",
+                    shrink_partition,
+                    disk.display()
+                );
+            }
+            if shrink_partition == esp {
+                bail!("shrink_partition cannot be the ESP ({})", esp);
+            }
+            let size =
+                layout_size_spec(shrink_size).map_err(|e| anyhow::anyhow!("shrink_size: {}", e))?;
+            if size == "remaining" {
+                bail!("shrink_size must be an explicit size (e.g. 200G)");
+            }
+            ops.push(StorageOp::ShrinkPartition {
+                device: PathBuf::from(shrink_partition),
+                size,
+            });
+        }
+        _ => bail!("shrink_partition and shrink_size must be set together"),
+    }
+
+    // Step 2: Create boot, swap and root in the free space
+    let mut new_partition = |size: String, partition_type: PartitionType, label: &str| {
+        ops.push(StorageOp::CreatePartitionInFreeSpace {
+            disk: disk.to_path_buf(),
+            size,
+            partition_type,
+            label: label.to_string(),
+        });
+        partlabel_path(label)
+    };
+    // Existing ESPs are often too small for kernels: /boot gets its own partition
+    let boot_device = new_partition(
+        capacity::size_spec(capacity::BOOT_PART_SIZE_MIB),
+        PartitionType::Linux,
+        ALONGSIDE_BOOT_LABEL,
+    );
+    let swap_device = swap_size
+        .map(|size| new_partition(size.to_string(), PartitionType::Swap, ALONGSIDE_SWAP_LABEL));
+    let root_device = new_partition(
+        "remaining".to_string(),
+        PartitionType::Linux,
+        ALONGSIDE_ROOT_LABEL,
+    );
+
+    // Step 3: Format only the partitions created above
+    ops.push(StorageOp::FormatFs {
+        device: boot_device.clone(),
+        filesystem: Filesystem::Ext4,
+        label: Some("BOOT".to_string()),
+    });
+    ops.push(StorageOp::FormatFs {
+        device: root_device.clone(),
+        filesystem: config.root_filesystem,
+        label: Some("archroot".to_string()),
+    });
+
+    // Step 4: Mount root, /boot and the existing ESP
+    ops.extend(root_mount_ops(
+        root_device,
+        config.root_filesystem,
+        subvolumes,
+    )?);
+    ops.push(StorageOp::Mount {
+        device: boot_device,
+        mountpoint: PathBuf::from("/mnt/boot"),
+        options: None,
+    });
+    ops.push(StorageOp::Mount {
+        device: PathBuf::from(esp),
+        mountpoint: PathBuf::from("/mnt/efi"),
+        options: None,
+    });
+
+    // Step 5: Swap partition (if requested)
+    if let Some(device) = swap_device {
+        ops.push(StorageOp::FormatFs {
+            device,
+            filesystem: Filesystem::Swap,
+            label: Some("swap".to_string()),
+        });
+    }
+
+    Ok(StoragePlan {
+        ops,
+        strategy: PartitionScheme::AutoAlongside,
+        disk: disk.to_path_buf(),
+        disks: vec![disk.to_path_buf()],
+        encrypted: false,
+        lvm: false,
+        raid_level: None,
     })
 }
 
@@ -689,10 +1743,45 @@ fn plan_luks_lvm(
 // Helpers
 // ============================================================================
 
+/// RAID levels accepted for the data array (mdadm `--level` names).
+///
+/// Shared with `InstallationConfig::validate` and mirrored by the level check
+/// in `scripts/install.sh`.
+pub const RAID_LEVELS: [&str; 5] = ["raid0", "raid1", "raid5", "raid6", "raid10"];
+
+/// Parse the comma-separated RAID member list from `install_disk`.
+///
+/// Rejects fewer than 2 members, non-`/dev/` paths and duplicates.
+fn parse_raid_disks(install_disk: &str) -> Result<Vec<PathBuf>> {
+    let mut disks: Vec<PathBuf> = Vec::new();
+    for entry in install_disk
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        if !entry.starts_with("/dev/") {
+            bail!("Invalid disk path '{}' — must start with /dev/", entry);
+        }
+        let path = PathBuf::from(entry);
+        if disks.contains(&path) {
+            bail!("RAID member '{}' is listed more than once", entry);
+        }
+        disks.push(path);
+    }
+    if disks.len() < 2 {
+        bail!(
+            "RAID strategies require at least 2 disks (found {}). \
+             List members comma-separated, e.g. /dev/sda,/dev/sdb",
+            disks.len()
+        );
+    }
+    Ok(disks)
+}
+
 /// Generate a partition device path from a disk path and partition number.
 ///
 /// Handles both `/dev/sdX` → `/dev/sdX1` and `/dev/nvme0n1` → `/dev/nvme0n1p1` patterns.
-fn partition_path(disk: &Path, partition_num: u32) -> PathBuf {
+pub(crate) fn partition_path(disk: &Path, partition_num: u32) -> PathBuf {
     let disk_str = disk.display().to_string();
 
     // NVMe and loop devices use 'p' separator (e.g., /dev/nvme0n1p1, /dev/loop0p1)
@@ -703,17 +1792,324 @@ fn partition_path(disk: &Path, partition_num: u32) -> PathBuf {
     }
 }
 
-/// Generate Btrfs-specific mount options if the filesystem is Btrfs.
+/// Stable path of a partition by its GPT name.
+fn partlabel_path(label: &str) -> PathBuf {
+    PathBuf::from(format!("/dev/disk/by-partlabel/{}", label))
+}
+
+/// Whether `partition` names a partition of `disk` (`/dev/sda3` of
+/// `/dev/sda`, `/dev/nvme0n1p2` of `/dev/nvme0n1`).
+fn is_partition_of(disk: &Path, partition: &str) -> bool {
+    let disk = disk.display().to_string();
+    let Some(rest) = partition.strip_prefix(&disk) else {
+        return false;
+    };
+    let number = if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        rest.strip_prefix('p').unwrap_or("")
+    } else {
+        rest
+    };
+    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Root and home sizes resolved into size specifications ("51200M",
+/// "remaining").
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DataSizes {
+    /// `root_size`; None when root takes the remaining space (only without
+    /// a separate home)
+    root: Option<String>,
+    /// The separate home, if any
+    home: Option<HomeVolume>,
+}
+
+/// A separate `/home` partition or LV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HomeVolume {
+    /// `home_size` ("remaining" for the rest of the disk or VG)
+    size: String,
+    filesystem: Filesystem,
+}
+
+/// Resolve `root_size` and `home_size` for a plan.
 ///
-/// Returns `Some("subvol=@,compress=zstd,noatime")` for Btrfs, `None` otherwise.
-fn btrfs_mount_options(fs: Filesystem) -> Option<String> {
-    if fs == Filesystem::Btrfs {
-        Some("subvol=@,compress=zstd,noatime".to_string())
+/// A "Remaining" root next to a separate home falls back to
+/// `DEFAULT_ROOT_SIZE_MIB`, since only one of them can take the rest.
+/// Percentages need the disk capacity.
+fn data_sizes(config: &InstallationConfig, disk_mib: Option<u64>) -> Result<DataSizes> {
+    let resolve = |field: &str, value: &str| -> Result<Option<String>> {
+        let size = capacity::parse_size_field(field, value)?;
+        if size == DiskSize::Remaining {
+            return Ok(None);
+        }
+        match disk_mib.and_then(|mib| size.resolve_mib(mib)) {
+            Some(mib) => Ok(Some(capacity::size_spec(mib))),
+            None => match size {
+                DiskSize::Mib(mib) => Ok(Some(capacity::size_spec(mib))),
+                _ => bail!("{} '{}': the disk size is unknown", field, value),
+            },
+        }
+    };
+
+    let mut root = resolve("root_size", &config.root_size)?;
+    let home = if has_separate_home(config) {
+        if root.is_none() {
+            root = Some(capacity::size_spec(capacity::DEFAULT_ROOT_SIZE_MIB));
+        }
+        Some(HomeVolume {
+            size: resolve("home_size", &config.home_size)?
+                .unwrap_or_else(|| "remaining".to_string()),
+            filesystem: config.home_filesystem,
+        })
     } else {
         None
+    };
+    Ok(DataSizes { root, home })
+}
+
+/// Whether `root_size` or `home_size` is a percentage of the disk.
+fn uses_percent_sizes(config: &InstallationConfig) -> bool {
+    let is_percent = |value: &str| matches!(value.parse(), Ok(DiskSize::Percent(_)));
+    is_percent(&config.root_size) || (has_separate_home(config) && is_percent(&config.home_size))
+}
+
+/// Whether `config` asks for a separate home on a strategy that lays one
+/// out (`partition_layout` declares its own, alongside installs reject it).
+fn has_separate_home(config: &InstallationConfig) -> bool {
+    config.separate_home == Toggle::Yes
+        && !matches!(
+            config.partitioning_strategy,
+            PartitionScheme::AutoAlongside | PartitionScheme::Manual | PartitionScheme::PreMounted
+        )
+}
+
+/// The `Partition` op of a single-disk strategy.
+///
+/// Root keeps the remaining space unless a home partition follows it.
+fn partition_op(
+    disk: &Path,
+    create_efi: bool,
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+) -> StorageOp {
+    let home_size = sizes.home.as_ref().map(|home| home.size.clone());
+    StorageOp::Partition {
+        disk: disk.to_path_buf(),
+        create_efi,
+        swap_size: swap_size.map(str::to_string),
+        root_size: home_size.as_ref().and(sizes.root.clone()),
+        home_size,
+    }
+}
+
+/// Push the ops that LUKS-format and open `device`; returns the mapper.
+fn luks_ops(ops: &mut Vec<StorageOp>, device: PathBuf, mapper_name: &str) -> PathBuf {
+    ops.push(StorageOp::LuksFormat {
+        device: device.clone(),
+        label: mapper_name.to_string(),
+    });
+    ops.push(StorageOp::LuksOpen {
+        device,
+        mapper_name: mapper_name.to_string(),
+    });
+    PathBuf::from(format!("/dev/mapper/{}", mapper_name))
+}
+
+/// Push the ops that put VG "archvg" on `pv_device` and format and mount
+/// its LVs: swap first, then root, then home — whichever comes last gets
+/// the free space left (`root_size` is honored even without a home).
+fn lvm_ops(
+    ops: &mut Vec<StorageOp>,
+    pv_device: PathBuf,
+    root_fs: Filesystem,
+    swap_size: Option<&str>,
+    sizes: &DataSizes,
+    subvolumes: &[BtrfsSubvolume],
+) -> Result<()> {
+    let vg_name = "archvg";
+    ops.push(StorageOp::CreateLvmPv {
+        device: pv_device.clone(),
+    });
+    ops.push(StorageOp::CreateLvmVg {
+        vg_name: vg_name.to_string(),
+        pv_device,
+    });
+
+    let mut lv = |name: &str, size: Option<&str>| {
+        ops.push(StorageOp::CreateLvmLv {
+            vg_name: vg_name.to_string(),
+            lv_name: name.to_string(),
+            size: match size {
+                Some(size) if size != "remaining" => size.to_string(),
+                _ => "100%FREE".to_string(),
+            },
+        });
+        PathBuf::from(format!("/dev/{}/{}", vg_name, name))
+    };
+    let swap_lv = swap_size.map(|size| lv("swap", Some(size)));
+    let root_lv = lv("root", sizes.root.as_deref());
+    let home_lv = sizes
+        .home
+        .as_ref()
+        .map(|home| (lv("home", Some(&home.size)), home));
+
+    ops.push(StorageOp::FormatFs {
+        device: root_lv.clone(),
+        filesystem: root_fs,
+        label: Some("archroot".to_string()),
+    });
+    if let Some(device) = swap_lv {
+        ops.push(StorageOp::FormatFs {
+            device,
+            filesystem: Filesystem::Swap,
+            label: Some("swap".to_string()),
+        });
+    }
+    ops.extend(root_mount_ops(root_lv, root_fs, subvolumes)?);
+    if let Some((device, home)) = home_lv {
+        ops.extend(home_ops(device, home));
+    }
+    Ok(())
+}
+
+/// Format a separate home device and mount it at `/mnt/home` (after root).
+fn home_ops(device: PathBuf, home: &HomeVolume) -> [StorageOp; 2] {
+    [
+        StorageOp::FormatFs {
+            device: device.clone(),
+            filesystem: home.filesystem,
+            label: Some("archhome".to_string()),
+        },
+        StorageOp::Mount {
+            device,
+            mountpoint: PathBuf::from("/mnt/home"),
+            options: None,
+        },
+    ]
+}
+
+/// Whether `config` keeps its swap in a swapfile: the swapfile modes, or a
+/// swap partition on AutoSimpleLuks, which has no slot for one (as
+/// `setup_swap_target` in `scripts/disk_utils.sh` decides).
+///
+/// The non-LVM RAID strategies have no slot either, but `plan_raid` rejects
+/// the request rather than converting it.
+fn uses_swapfile(config: &InstallationConfig) -> bool {
+    match config.swap {
+        SwapMode::Partition => config.partitioning_strategy == PartitionScheme::AutoSimpleLuks,
+        mode => mode.has_disk_swap(),
+    }
+}
+
+/// The Btrfs subvolumes a Btrfs root gets: `btrfs_subvolumes`, plus the
+/// `@swap` subvolume when swap is a swapfile, minus the `/home` one when
+/// home is a separate partition or LV.
+///
+/// Exported to the scripts as `BTRFS_SUBVOLUMES`, so `setup_swap_target`
+/// knows the swapfile goes into the mounted `@swap`.
+pub fn btrfs_subvolumes_for(config: &InstallationConfig) -> Vec<BtrfsSubvolume> {
+    let mut subvolumes = if uses_swapfile(config) {
+        swap::with_swap_subvolume(&config.btrfs_subvolumes)
+    } else {
+        config.btrfs_subvolumes.clone()
+    };
+    if has_separate_home(config) {
+        subvolumes.retain(|s| s.mountpoint != "/home");
+    }
+    subvolumes
+}
+
+/// Where `CreateSwapfile` puts the swapfile: in the `@swap` subvolume when
+/// the plan mounts one, otherwise at the root of the installed system.
+fn swapfile_path(ops: &[StorageOp]) -> PathBuf {
+    let swap_dir = target_path(swap::SWAP_SUBVOLUME_MOUNTPOINT);
+    let has_swap_mount = ops.iter().any(|op| {
+        matches!(op, StorageOp::MountBtrfsSubvolume { mountpoint, .. } if *mountpoint == swap_dir)
+    });
+    if has_swap_mount {
+        swap_dir.join("swapfile")
+    } else {
+        PathBuf::from("/mnt/swapfile")
     }
 }
 
+/// Normalize a user-declared partition size into an sgdisk/sfdisk size
+/// specification.
+///
+/// Accepts "Remaining" (any case) and a positive integer with a K/M/G/T unit,
+/// optionally suffixed with "B" or "iB" ("512MiB" → "512M", "40GB" → "40G").
+pub(crate) fn layout_size_spec(size: &str) -> Result<String> {
+    let size = size.trim();
+    if size.eq_ignore_ascii_case("remaining") {
+        return Ok("remaining".to_string());
+    }
+    let upper = size.to_ascii_uppercase();
+    let spec = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper);
+    let (amount, unit) = spec.split_at(spec.len().saturating_sub(1));
+    match (amount.parse::<u64>(), unit) {
+        (Ok(n), "K" | "M" | "G" | "T") if n > 0 => Ok(format!("{}{}", n, unit)),
+        _ => bail!(
+            "invalid size '{}' — use e.g. 512M, 40G, 1T or Remaining",
+            size
+        ),
+    }
+}
+
+/// Mount options for subvolumes that do not declare their own.
+pub(crate) const BTRFS_DEFAULT_MOUNT_OPTIONS: &str = "compress=zstd,noatime";
+
+/// Ops that mount the formatted root device at `/mnt`.
+///
+/// On Btrfs the configured subvolumes are created first and each is mounted
+/// shallowest-first (so `/` precedes `/var/log`); other filesystems get a
+/// single plain mount.
+fn root_mount_ops(
+    device: PathBuf,
+    root_fs: Filesystem,
+    subvolumes: &[BtrfsSubvolume],
+) -> Result<Vec<StorageOp>> {
+    if root_fs != Filesystem::Btrfs {
+        return Ok(vec![StorageOp::Mount {
+            device,
+            mountpoint: PathBuf::from("/mnt"),
+            options: None,
+        }]);
+    }
+    if !subvolumes.iter().any(|s| s.mountpoint == "/") {
+        bail!("btrfs_subvolumes must include a subvolume mounted at '/'");
+    }
+
+    let mut ops = vec![StorageOp::CreateBtrfsSubvolumes {
+        device: device.clone(),
+        subvolumes: subvolumes.iter().map(|s| s.name.clone()).collect(),
+    }];
+    let mut ordered: Vec<&BtrfsSubvolume> = subvolumes.iter().collect();
+    ordered.sort_by_key(|s| Path::new(&s.mountpoint).components().count());
+    for subvol in ordered {
+        ops.push(StorageOp::MountBtrfsSubvolume {
+            device: device.clone(),
+            subvolume: subvol.name.clone(),
+            mountpoint: target_path(&subvol.mountpoint),
+            options: subvol
+                .mount_options
+                .clone()
+                .unwrap_or_else(|| BTRFS_DEFAULT_MOUNT_OPTIONS.to_string()),
+        });
+    }
+    Ok(ops)
+}
+
+/// Map a path on the installed system to its location under `/mnt`.
+fn target_path(mountpoint: &str) -> PathBuf {
+    Path::new("/mnt")
+        .components()
+        .chain(Path::new(mountpoint).components().skip(1))
+        .collect()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -731,7 +2127,7 @@ mod tests {
         config.partitioning_strategy = strategy;
         config.root_filesystem = fs;
         config.boot_mode = BootMode::Uefi;
-        config.swap = Toggle::Yes;
+        config.swap = SwapMode::Partition;
         config.swap_size = "2".to_string();
         config
     }
@@ -840,11 +2236,11 @@ mod tests {
             StorageOp::CreateLvmVg { vg_name, .. } if vg_name == "archvg"
         )));
 
-        // Must have root LV
+        // Must have root LV, sized by root_size ("50GB")
         assert!(plan.ops.iter().any(|op| matches!(
             op,
             StorageOp::CreateLvmLv { lv_name, size, .. }
-                if lv_name == "root" && size == "100%FREE"
+                if lv_name == "root" && size == "51200M"
         )));
 
         // Must have swap LV (swap=Yes)
@@ -894,37 +2290,423 @@ mod tests {
         // PV must be on /dev/mapper/cryptlvm (not raw partition)
         assert!(plan.ops.iter().any(|op| matches!(
             op,
-            StorageOp::CreateLvmPv { device }
-                if device == &PathBuf::from("/dev/mapper/cryptlvm")
+            StorageOp::CreateLvmPv { device }
+                if device == &PathBuf::from("/dev/mapper/cryptlvm")
+        )));
+
+        assert!(plan.encrypted);
+        assert!(plan.lvm);
+    }
+
+    #[test]
+    fn test_btrfs_plan_creates_and_mounts_subvolumes() {
+        let config = test_config(PartitionScheme::AutoSimple, Filesystem::Btrfs);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        let create_idx = plan
+            .ops
+            .iter()
+            .position(|op| {
+                *op == StorageOp::CreateBtrfsSubvolumes {
+                    device: PathBuf::from("/dev/sda3"),
+                    subvolumes: vec![
+                        "@".to_string(),
+                        "@home".to_string(),
+                        "@log".to_string(),
+                        "@pkg".to_string(),
+                        "@snapshots".to_string(),
+                    ],
+                }
+            })
+            .expect("default subvolumes are created"); // test: known-good input
+        let format_idx = plan
+            .ops
+            .iter()
+            .position(|op| {
+                matches!(
+                    op,
+                    StorageOp::FormatFs {
+                        filesystem: Filesystem::Btrfs,
+                        ..
+                    }
+                )
+            })
+            .expect("root is formatted"); // test: known-good input
+        assert!(format_idx < create_idx);
+
+        // Each subvolume is mounted shallowest-first with the default options
+        let mounts: Vec<(String, String)> = plan
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::MountBtrfsSubvolume {
+                    subvolume,
+                    mountpoint,
+                    options,
+                    ..
+                } => {
+                    assert_eq!(options, BTRFS_DEFAULT_MOUNT_OPTIONS);
+                    Some((subvolume.clone(), mountpoint.display().to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            mounts,
+            vec![
+                ("@".to_string(), "/mnt".to_string()),
+                ("@home".to_string(), "/mnt/home".to_string()),
+                ("@snapshots".to_string(), "/mnt/.snapshots".to_string()),
+                ("@log".to_string(), "/mnt/var/log".to_string()),
+                ("@pkg".to_string(), "/mnt/var/cache/pacman/pkg".to_string()),
+            ]
+        );
+
+        // No plain root mount remains, and the summary names the subvolumes
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Mount { mountpoint, .. } if mountpoint == &PathBuf::from("/mnt")
+        )));
+        assert!(
+            plan.summary()
+                .contains("[@, @home, @log, @pkg, @snapshots]")
+        );
+    }
+
+    #[test]
+    fn test_btrfs_custom_subvolumes() {
+        let mut config = test_config(PartitionScheme::AutoLuksLvm, Filesystem::Btrfs);
+        config.btrfs_subvolumes = vec![
+            BtrfsSubvolume {
+                name: "@".to_string(),
+                mountpoint: "/".to_string(),
+                mount_options: Some("compress=zstd:3,noatime".to_string()),
+            },
+            BtrfsSubvolume {
+                name: "@vm".to_string(),
+                mountpoint: "/var/lib/libvirt/images".to_string(),
+                mount_options: Some("nodatacow".to_string()),
+            },
+        ];
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.contains(&StorageOp::MountBtrfsSubvolume {
+            device: PathBuf::from("/dev/archvg/root"),
+            subvolume: "@vm".to_string(),
+            mountpoint: PathBuf::from("/mnt/var/lib/libvirt/images"),
+            options: "nodatacow".to_string(),
+        }));
+        assert_eq!(
+            plan.teardown(plan.ops.len())[0],
+            TeardownOp::Unmount {
+                mountpoint: PathBuf::from("/mnt/boot"),
+            }
+        );
+
+        // A list without a root subvolume cannot be planned
+        config.btrfs_subvolumes.remove(0);
+        assert!(calculate_storage_plan(&config).is_err());
+
+        // Non-Btrfs roots ignore the list entirely
+        config.root_filesystem = Filesystem::Ext4;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(
+            !plan
+                .ops
+                .iter()
+                .any(|op| matches!(op, StorageOp::CreateBtrfsSubvolumes { .. }))
+        );
+    }
+
+    /// Helper: create a RAID config across two disks
+    fn raid_config(strategy: PartitionScheme) -> InstallationConfig {
+        let mut config = test_config(strategy, Filesystem::Ext4);
+        config.install_disk = "/dev/sda,/dev/sdb".to_string();
+        config.raid_level = "raid1".to_string();
+        // Non-LVM members have no swap slot; tests that want swap set it
+        config.swap = SwapMode::Zram;
+        config
+    }
+
+    #[test]
+    fn test_raid_plan_requires_two_disks() {
+        let config = test_config(PartitionScheme::AutoRaid, Filesystem::Ext4);
+        let result = calculate_storage_plan(&config);
+        assert!(result.is_err());
+
+        let err_msg = result.expect_err("should fail").to_string(); // test: expected error
+        assert!(err_msg.contains("RAID"));
+    }
+
+    #[test]
+    fn test_raid_plan_rejects_invalid_level() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.raid_level = "raid2".to_string();
+        assert!(calculate_storage_plan(&config).is_err());
+
+        // raid5 needs three members
+        config.raid_level = "raid5".to_string();
+        assert!(calculate_storage_plan(&config).is_err());
+        config.install_disk = "/dev/sda,/dev/sdb,/dev/sdc".to_string();
+        assert!(calculate_storage_plan(&config).is_ok());
+
+        // raid10 (near layout) works on two members, like raid.sh
+        config.raid_level = "raid10".to_string();
+        config.install_disk = "/dev/sda,/dev/sdb".to_string();
+        assert!(calculate_storage_plan(&config).is_ok());
+    }
+
+    #[test]
+    fn test_raid_plan_rejects_duplicate_members() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.install_disk = "/dev/sda,/dev/sda".to_string();
+        assert!(calculate_storage_plan(&config).is_err());
+    }
+
+    #[test]
+    fn test_raid_plan_wipes_and_partitions_every_member() {
+        let config = raid_config(PartitionScheme::AutoRaid);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert_eq!(
+            plan.disks,
+            vec![PathBuf::from("/dev/sda"), PathBuf::from("/dev/sdb")]
+        );
+        assert_eq!(plan.disk, PathBuf::from("/dev/sda"));
+        assert_eq!(plan.raid_level.as_deref(), Some("raid1"));
+
+        for disk in &plan.disks {
+            assert!(plan.ops.iter().any(|op| matches!(
+                op,
+                StorageOp::WipeDisk { disk: d } if d == disk
+            )));
+            assert!(plan.ops.iter().any(|op| matches!(
+                op,
+                StorageOp::PartitionRaidMember { disk: d, .. } if d == disk
+            )));
+        }
+        assert!(plan.is_destructive());
+    }
+
+    #[test]
+    fn test_raid_plan_creates_data_array_with_level() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.raid_level = "raid0".to_string();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        // UEFI + GRUB: data members are partition 3 (ESP, XBOOTLDR, data)
+        assert!(plan.ops.contains(&StorageOp::CreateRaidArray {
+            device: PathBuf::from("/dev/md/DATA"),
+            level: "raid0".to_string(),
+            members: vec![PathBuf::from("/dev/sda3"), PathBuf::from("/dev/sdb3")],
+        }));
+
+        // Boot array is always RAID1 regardless of data level
+        assert!(plan.ops.contains(&StorageOp::CreateRaidArray {
+            device: PathBuf::from("/dev/md/XBOOTLDR"),
+            level: "raid1".to_string(),
+            members: vec![PathBuf::from("/dev/sda2"), PathBuf::from("/dev/sdb2")],
+        }));
+
+        // Root is formatted directly on the array
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::FormatFs { device, filesystem: Filesystem::Ext4, .. }
+                if device == &PathBuf::from("/dev/md/DATA")
+        )));
+    }
+
+    #[test]
+    fn test_raid_plan_waits_before_use_and_writes_mdadm_conf_last() {
+        let config = raid_config(PartitionScheme::AutoRaid);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        let create_idx = plan
+            .ops
+            .iter()
+            .position(|op| matches!(op, StorageOp::CreateRaidArray { device, .. } if device == &PathBuf::from("/dev/md/DATA")));
+        let wait_idx = plan
+            .ops
+            .iter()
+            .position(|op| matches!(op, StorageOp::WaitRaidSync { device } if device == &PathBuf::from("/dev/md/DATA")));
+        let format_idx = plan
+            .ops
+            .iter()
+            .position(|op| matches!(op, StorageOp::FormatFs { device, .. } if device == &PathBuf::from("/dev/md/DATA")));
+
+        assert!(create_idx.expect("create op") < wait_idx.expect("wait op")); // test: asserted present
+        assert!(wait_idx.expect("wait op") < format_idx.expect("format op")); // test: asserted present
+
+        assert_eq!(
+            plan.ops.last(),
+            Some(&StorageOp::WriteMdadmConf {
+                path: PathBuf::from("/mnt/etc/mdadm.conf")
+            })
+        );
+    }
+
+    #[test]
+    fn test_raid_plan_uefi_mounts_esp_at_efi() {
+        let config = raid_config(PartitionScheme::AutoRaid);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.contains(&StorageOp::Mount {
+            device: PathBuf::from("/dev/md/XBOOTLDR"),
+            mountpoint: PathBuf::from("/mnt/boot"),
+            options: None,
+        }));
+        assert!(plan.ops.contains(&StorageOp::Mount {
+            device: PathBuf::from("/dev/sda1"),
+            mountpoint: PathBuf::from("/mnt/efi"),
+            options: None,
+        }));
+    }
+
+    #[test]
+    fn test_raid_plan_formats_esp_on_every_member() {
+        let config = raid_config(PartitionScheme::AutoRaid);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        for esp in ["/dev/sda1", "/dev/sdb1"] {
+            assert!(plan.ops.contains(&StorageOp::FormatFs {
+                device: PathBuf::from(esp),
+                filesystem: Filesystem::Fat32,
+                label: Some("EFI".to_string()),
+            }));
+        }
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Mount { device, .. } if device == &PathBuf::from("/dev/sdb1")
+        )));
+        assert!(plan.summary().contains("only /dev/sda1 is mounted"));
+    }
+
+    #[test]
+    fn test_raid_plan_rejects_swap_partition_without_lvm() {
+        for strategy in [PartitionScheme::AutoRaid, PartitionScheme::AutoRaidLuks] {
+            let mut config = raid_config(strategy);
+            config.swap = SwapMode::Partition;
+            let err = calculate_storage_plan(&config).expect_err("no swap slot"); // test: known-good input
+            assert!(err.to_string().contains("no slot for a swap partition"));
+        }
+
+        // The LVM variants carve swap from the volume group
+        let mut config = raid_config(PartitionScheme::AutoRaidLvm);
+        config.swap = SwapMode::Partition;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(plan.ops.contains(&StorageOp::FormatFs {
+            device: PathBuf::from("/dev/archvg/swap"),
+            filesystem: Filesystem::Swap,
+            label: Some("swap".to_string()),
+        }));
+    }
+
+    #[test]
+    fn test_raid_plan_fat32_bootloader_has_no_boot_array() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.bootloader = Bootloader::SystemdBoot;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        // Only the data array, built from partition 2
+        let arrays: Vec<&StorageOp> = plan
+            .ops
+            .iter()
+            .filter(|op| matches!(op, StorageOp::CreateRaidArray { .. }))
+            .collect();
+        assert_eq!(arrays.len(), 1);
+        assert!(plan.ops.contains(&StorageOp::CreateRaidArray {
+            device: PathBuf::from("/dev/md/DATA"),
+            level: "raid1".to_string(),
+            members: vec![PathBuf::from("/dev/sda2"), PathBuf::from("/dev/sdb2")],
+        }));
+
+        // ESP doubles as /boot
+        assert!(plan.ops.contains(&StorageOp::Mount {
+            device: PathBuf::from("/dev/sda1"),
+            mountpoint: PathBuf::from("/mnt/boot"),
+            options: None,
+        }));
+    }
+
+    #[test]
+    fn test_raid_plan_bios_uses_boot_array() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.boot_mode = BootMode::Bios;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::CreateRaidArray { device, .. } if device == &PathBuf::from("/dev/md/BOOT")
+        )));
+        // No ESP on BIOS
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::FormatFs {
+                filesystem: Filesystem::Fat32,
+                ..
+            }
         )));
+    }
+
+    #[test]
+    fn test_raid_luks_encrypts_data_array() {
+        let config = raid_config(PartitionScheme::AutoRaidLuks);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
 
+        assert!(plan.ops.contains(&StorageOp::LuksFormat {
+            device: PathBuf::from("/dev/md/DATA"),
+            label: "cryptroot".to_string(),
+        }));
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Mount { device, mountpoint, .. }
+                if device == &PathBuf::from("/dev/mapper/cryptroot")
+                    && mountpoint == &PathBuf::from("/mnt")
+        )));
         assert!(plan.encrypted);
-        assert!(plan.lvm);
+        assert!(!plan.lvm);
     }
 
     #[test]
-    fn test_btrfs_plan_has_mount_options() {
-        let config = test_config(PartitionScheme::AutoSimple, Filesystem::Btrfs);
+    fn test_raid_lvm_puts_pv_on_array() {
+        let mut config = raid_config(PartitionScheme::AutoRaidLvm);
+        config.swap = SwapMode::Partition;
         let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
 
-        // Must have btrfs mount options on root mount
+        assert!(plan.ops.contains(&StorageOp::CreateLvmPv {
+            device: PathBuf::from("/dev/md/DATA"),
+        }));
         assert!(plan.ops.iter().any(|op| matches!(
             op,
-            StorageOp::Mount { options: Some(opts), mountpoint, .. }
-                if mountpoint == &PathBuf::from("/mnt")
-                    && opts.contains("subvol=@")
-                    && opts.contains("compress=zstd")
+            StorageOp::CreateLvmLv { lv_name, .. } if lv_name == "swap"
         )));
+        assert!(!plan.encrypted);
+        assert!(plan.lvm);
     }
 
     #[test]
-    fn test_raid_plan_returns_error() {
-        let config = test_config(PartitionScheme::AutoRaid, Filesystem::Ext4);
-        let result = calculate_storage_plan(&config);
-        assert!(result.is_err());
+    fn test_raid_lvm_luks_stacks_all_layers() {
+        let config = raid_config(PartitionScheme::AutoRaidLvmLuks);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
 
-        let err_msg = result.expect_err("should fail").to_string(); // test: expected error
-        assert!(err_msg.contains("RAID"));
+        let luks_idx = plan.ops.iter().position(|op| {
+            matches!(op, StorageOp::LuksFormat { device, .. } if device == &PathBuf::from("/dev/md/DATA"))
+        });
+        let pv_idx = plan.ops.iter().position(|op| {
+            matches!(op, StorageOp::CreateLvmPv { device } if device == &PathBuf::from("/dev/mapper/cryptlvm"))
+        });
+        assert!(luks_idx.expect("luks op") < pv_idx.expect("pv op")); // test: asserted present
+        assert!(plan.encrypted);
+        assert!(plan.lvm);
+    }
+
+    #[test]
+    fn test_raid_plan_summary_lists_members() {
+        let config = raid_config(PartitionScheme::AutoRaid);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let summary = plan.summary();
+        assert!(summary.contains("/dev/sda, /dev/sdb"));
+        assert!(summary.contains("RAID: raid1"));
     }
 
     #[test]
@@ -974,10 +2756,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_simple_plan_swap_partition_precedes_root() {
+        let config = test_config(PartitionScheme::AutoSimple, Filesystem::Ext4);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.contains(&StorageOp::Partition {
+            disk: PathBuf::from("/dev/sda"),
+            create_efi: true,
+            swap_size: Some("2G".to_string()),
+            root_size: None,
+            home_size: None,
+        }));
+        assert!(plan.ops.contains(&StorageOp::FormatFs {
+            device: PathBuf::from("/dev/sda2"),
+            filesystem: Filesystem::Swap,
+            label: Some("swap".to_string()),
+        }));
+        assert!(plan.ops.contains(&StorageOp::Mount {
+            device: PathBuf::from("/dev/sda3"),
+            mountpoint: PathBuf::from("/mnt"),
+            options: None,
+        }));
+    }
+
     #[test]
     fn test_lvm_no_swap() {
         let mut config = test_config(PartitionScheme::AutoLvm, Filesystem::Ext4);
-        config.swap = Toggle::No;
+        config.swap = SwapMode::None;
         let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
 
         // Should NOT have swap LV
@@ -987,6 +2793,84 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn test_swapfile_mode_creates_swapfile_after_mounts() {
+        let mut config = test_config(PartitionScheme::AutoSimple, Filesystem::Ext4);
+        config.swap = SwapMode::Swapfile;
+        config.swap_size = "Auto".to_string();
+        let plan = calculate_storage_plan_for(
+            &config,
+            HostSizes {
+                ram_mib: Some(8192),
+                ..Default::default()
+            },
+        )
+        .expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Partition {
+                swap_size: None,
+                ..
+            }
+        )));
+        assert_eq!(
+            plan.ops.last(),
+            Some(&StorageOp::CreateSwapfile {
+                path: PathBuf::from("/mnt/swapfile"),
+                size: "8192M".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_btrfs_swapfile_gets_own_subvolume() {
+        let mut config = test_config(PartitionScheme::AutoSimple, Filesystem::Btrfs);
+        config.swap = SwapMode::ZramSwapfile;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::CreateBtrfsSubvolumes { subvolumes, .. } if subvolumes.iter().any(|s| s == "@swap")
+        )));
+        assert!(matches!(
+            plan.ops.last(),
+            Some(StorageOp::CreateSwapfile { path, .. }) if path == &PathBuf::from("/mnt/swap/swapfile")
+        ));
+    }
+
+    #[test]
+    fn test_swap_partition_without_slot_falls_back_to_swapfile() {
+        let mut config = test_config(PartitionScheme::AutoSimpleLuks, Filesystem::Ext4);
+        config.swap = SwapMode::Partition;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::FormatFs {
+                filesystem: Filesystem::Swap,
+                ..
+            }
+        )));
+        assert!(matches!(
+            plan.ops.last(),
+            Some(StorageOp::CreateSwapfile { size, .. }) if size == "2G"
+        ));
+    }
+
+    #[test]
+    fn test_zram_has_no_storage_ops() {
+        let mut config = test_config(PartitionScheme::AutoLvm, Filesystem::Ext4);
+        config.swap = SwapMode::Zram;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert!(!plan.ops.iter().any(|op| match op {
+            StorageOp::CreateSwapfile { .. } => true,
+            StorageOp::CreateLvmLv { lv_name, .. } => lv_name == "swap",
+            _ => false,
+        }));
+    }
+
     #[test]
     fn test_plan_summary_not_empty() {
         let config = test_config(PartitionScheme::AutoSimple, Filesystem::Ext4);
@@ -1029,4 +2913,705 @@ mod tests {
         // open_idx safe: asserted is_some above
         assert!(format_idx.expect("just checked") < open_idx.expect("just checked"));
     }
+
+    #[test]
+    fn test_teardown_of_nothing_applied_is_empty() {
+        let config = test_config(PartitionScheme::AutoLuksLvm, Filesystem::Ext4);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(plan.teardown(0).is_empty());
+    }
+
+    #[test]
+    fn test_teardown_luks_lvm_reverses_layers() {
+        let config = test_config(PartitionScheme::AutoLuksLvm, Filesystem::Ext4);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let teardown = plan.teardown(plan.ops.len());
+
+        assert_eq!(
+            teardown,
+            vec![
+                TeardownOp::Unmount {
+                    mountpoint: PathBuf::from("/mnt/boot")
+                },
+                TeardownOp::Unmount {
+                    mountpoint: PathBuf::from("/mnt")
+                },
+                TeardownOp::SwapOff {
+                    device: PathBuf::from("/dev/archvg/swap")
+                },
+                TeardownOp::DeactivateVg {
+                    vg_name: "archvg".to_string()
+                },
+                TeardownOp::LuksClose {
+                    mapper_name: "cryptlvm".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_teardown_only_covers_applied_ops() {
+        let config = test_config(PartitionScheme::AutoLuksLvm, Filesystem::Ext4);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let vg_idx = plan
+            .ops
+            .iter()
+            .position(|op| matches!(op, StorageOp::CreateLvmVg { .. }))
+            .expect("plan has a VG"); // test: known-good input
+
+        // Died right after the VG was created: close VG, then mapper
+        let teardown = plan.teardown(vg_idx + 1);
+        assert_eq!(
+            teardown,
+            vec![
+                TeardownOp::DeactivateVg {
+                    vg_name: "archvg".to_string()
+                },
+                TeardownOp::LuksClose {
+                    mapper_name: "cryptlvm".to_string()
+                },
+            ]
+        );
+
+        // Out-of-range counts are clamped
+        assert_eq!(plan.teardown(usize::MAX), plan.teardown(plan.ops.len()));
+    }
+
+    #[test]
+    fn test_teardown_raid_luks_stops_arrays_after_closing_mapper() {
+        let mut config = raid_config(PartitionScheme::AutoRaidLuks);
+        config.swap = SwapMode::Swapfile;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let teardown = plan.teardown(plan.ops.len());
+
+        let close_idx = teardown
+            .iter()
+            .position(|op| matches!(op, TeardownOp::LuksClose { .. }))
+            .expect("mapper is closed"); // test: known-good input
+        let stop_idx = teardown
+            .iter()
+            .position(|op| matches!(op, TeardownOp::StopRaidArray { .. }))
+            .expect("arrays are stopped"); // test: known-good input
+        assert!(close_idx < stop_idx);
+        // The swapfile inside the encrypted root is released before any unmount
+        assert_eq!(
+            teardown[0],
+            TeardownOp::SwapOff {
+                device: PathBuf::from("/mnt/swapfile")
+            }
+        );
+        assert!(matches!(teardown[1], TeardownOp::Unmount { .. }));
+        assert!(matches!(
+            teardown.last(),
+            Some(TeardownOp::StopRaidArray { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rebased_moves_every_target_path() {
+        let mut config = test_config(PartitionScheme::AutoSimple, Filesystem::Btrfs);
+        config.swap = SwapMode::Swapfile;
+        let plan = calculate_storage_plan(&config)
+            .expect("plan generation failed") // test: known-good input
+            .rebased(Path::new("/mnt/sandbox"));
+
+        let paths: Vec<&PathBuf> = plan
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::Mount { mountpoint, .. }
+                | StorageOp::MountBtrfsSubvolume { mountpoint, .. } => Some(mountpoint),
+                StorageOp::CreateSwapfile { path, .. } => Some(path),
+                _ => None,
+            })
+            .collect();
+        assert!(paths.contains(&&PathBuf::from("/mnt/sandbox")));
+        assert!(paths.contains(&&PathBuf::from("/mnt/sandbox/boot")));
+        assert!(paths.contains(&&PathBuf::from("/mnt/sandbox/swap/swapfile")));
+        assert!(paths.iter().all(|p| p.starts_with("/mnt/sandbox")));
+        // Devices are untouched
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::FormatFs { device, .. } if device == &PathBuf::from("/dev/sda1")
+        )));
+    }
+
+    /// Helper: a Manual config with the given declarative layout
+    fn layout_config(layout: Vec<LayoutPartition>) -> InstallationConfig {
+        let mut config = test_config(PartitionScheme::Manual, Filesystem::Ext4);
+        config.partition_layout = layout;
+        config
+    }
+
+    fn layout_part(
+        size: &str,
+        partition_type: PartitionType,
+        filesystem: Option<Filesystem>,
+        mountpoint: Option<&str>,
+    ) -> LayoutPartition {
+        LayoutPartition {
+            size: size.to_string(),
+            partition_type,
+            filesystem,
+            label: None,
+            mountpoint: mountpoint.map(str::to_string),
+            mount_options: None,
+            encrypt: false,
+        }
+    }
+
+    #[test]
+    fn test_custom_layout_plan() {
+        let mut root = layout_part(
+            "Remaining",
+            PartitionType::Luks,
+            Some(Filesystem::Btrfs),
+            Some("/"),
+        );
+        root.encrypt = true;
+        root.mount_options = Some("compress=zstd".to_string());
+        let mut home = layout_part(
+            "200GB",
+            PartitionType::Linux,
+            Some(Filesystem::Xfs),
+            Some("/home"),
+        );
+        home.label = Some("home".to_string());
+        let config = layout_config(vec![
+            layout_part(
+                "1GiB",
+                PartitionType::Efi,
+                Some(Filesystem::Fat32),
+                Some("/boot"),
+            ),
+            layout_part("8G", PartitionType::Swap, Some(Filesystem::Swap), None),
+            home,
+            root,
+        ]);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert_eq!(plan.strategy, PartitionScheme::Manual);
+        assert!(plan.encrypted);
+        assert!(!plan.lvm);
+        assert_eq!(
+            plan.ops[1],
+            StorageOp::PartitionLayout {
+                disk: PathBuf::from("/dev/sda"),
+                gpt: true,
+                partitions: vec![
+                    LayoutEntry {
+                        size: "1G".to_string(),
+                        partition_type: PartitionType::Efi,
+                        label: None,
+                    },
+                    LayoutEntry {
+                        size: "8G".to_string(),
+                        partition_type: PartitionType::Swap,
+                        label: None,
+                    },
+                    LayoutEntry {
+                        size: "200G".to_string(),
+                        partition_type: PartitionType::Linux,
+                        label: Some("home".to_string()),
+                    },
+                    LayoutEntry {
+                        size: "remaining".to_string(),
+                        partition_type: PartitionType::Luks,
+                        label: None,
+                    },
+                ],
+            }
+        );
+        assert!(plan.ops.contains(&StorageOp::LuksOpen {
+            device: PathBuf::from("/dev/sda4"),
+            mapper_name: "cryptroot".to_string(),
+        }));
+        assert!(plan.ops.contains(&StorageOp::FormatFs {
+            device: PathBuf::from("/dev/mapper/cryptroot"),
+            filesystem: Filesystem::Btrfs,
+            label: None,
+        }));
+
+        // The Btrfs root gets the subvolumes the layout does not replace
+        assert!(plan.ops.contains(&StorageOp::CreateBtrfsSubvolumes {
+            device: PathBuf::from("/dev/mapper/cryptroot"),
+            subvolumes: vec![
+                "@".to_string(),
+                "@log".to_string(),
+                "@pkg".to_string(),
+                "@snapshots".to_string(),
+            ],
+        }));
+
+        // Mounts come last, shallowest first, in layout order at equal depth
+        let mounts: Vec<(String, String, String)> = plan
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::Mount {
+                    device,
+                    mountpoint,
+                    options,
+                } => Some((
+                    device.display().to_string(),
+                    mountpoint.display().to_string(),
+                    options.clone().unwrap_or_default(),
+                )),
+                StorageOp::MountBtrfsSubvolume {
+                    subvolume,
+                    mountpoint,
+                    options,
+                    ..
+                } => Some((
+                    subvolume.clone(),
+                    mountpoint.display().to_string(),
+                    options.clone(),
+                )),
+                _ => None,
+            })
+            .collect();
+        let row = |source: &str, mountpoint: &str, options: &str| {
+            (
+                source.to_string(),
+                mountpoint.to_string(),
+                options.to_string(),
+            )
+        };
+        assert_eq!(
+            mounts,
+            vec![
+                row("@", "/mnt", "compress=zstd"),
+                row("/dev/sda1", "/mnt/boot", ""),
+                row("/dev/sda3", "/mnt/home", ""),
+                row("@snapshots", "/mnt/.snapshots", "compress=zstd"),
+                row("@log", "/mnt/var/log", "compress=zstd"),
+                row("@pkg", "/mnt/var/cache/pacman/pkg", "compress=zstd"),
+            ]
+        );
+        assert!(matches!(
+            plan.ops.last(),
+            Some(StorageOp::MountBtrfsSubvolume { .. })
+        ));
+    }
+
+    #[test]
+    fn test_declared_layout_is_executed_from_the_plan() {
+        let mut config = layout_config(Vec::new());
+        assert!(!is_plan_executed(&config));
+        config.partition_layout = vec![layout_part(
+            "Remaining",
+            PartitionType::Linux,
+            Some(Filesystem::Ext4),
+            Some("/"),
+        )];
+        assert!(is_plan_executed(&config));
+        config.partitioning_strategy = PartitionScheme::PreMounted;
+        assert!(!is_plan_executed(&config));
+    }
+
+    #[test]
+    fn test_custom_layout_bios_uses_mbr_unless_bios_boot_declared() {
+        let mut config = layout_config(vec![layout_part(
+            "Remaining",
+            PartitionType::Linux,
+            Some(Filesystem::Ext4),
+            Some("/"),
+        )]);
+        config.boot_mode = BootMode::Bios;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(matches!(
+            plan.ops[1],
+            StorageOp::PartitionLayout { gpt: false, .. }
+        ));
+
+        config
+            .partition_layout
+            .insert(0, layout_part("1M", PartitionType::BiosBoot, None, None));
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(matches!(
+            plan.ops[1],
+            StorageOp::PartitionLayout { gpt: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_custom_layout_rejects_inconsistent_layouts() {
+        let esp = || {
+            layout_part(
+                "512M",
+                PartitionType::Efi,
+                Some(Filesystem::Fat32),
+                Some("/boot"),
+            )
+        };
+        let root = |size: &str| {
+            layout_part(
+                size,
+                PartitionType::Linux,
+                Some(Filesystem::Ext4),
+                Some("/"),
+            )
+        };
+
+        let invalid = vec![
+            // No root mountpoint
+            vec![esp()],
+            // Missing ESP under UEFI
+            vec![root("Remaining")],
+            // "Remaining" before the last entry
+            vec![
+                esp(),
+                root("Remaining"),
+                layout_part("8G", PartitionType::Swap, Some(Filesystem::Swap), None),
+            ],
+            // Unparseable size
+            vec![esp(), root("lots")],
+            // Duplicate mountpoint
+            vec![esp(), esp(), root("Remaining")],
+            // Swap with a mountpoint
+            vec![
+                esp(),
+                layout_part(
+                    "8G",
+                    PartitionType::Swap,
+                    Some(Filesystem::Swap),
+                    Some("/swap"),
+                ),
+                root("Remaining"),
+            ],
+            // Mountpoint without a filesystem
+            vec![
+                esp(),
+                layout_part("8G", PartitionType::Linux, None, Some("/srv")),
+                root("Remaining"),
+            ],
+            // Relative mountpoint
+            vec![
+                esp(),
+                layout_part(
+                    "8G",
+                    PartitionType::Linux,
+                    Some(Filesystem::Ext4),
+                    Some("srv"),
+                ),
+                root("Remaining"),
+            ],
+            // Mountpoint escaping the target root
+            vec![
+                esp(),
+                layout_part(
+                    "8G",
+                    PartitionType::Linux,
+                    Some(Filesystem::Ext4),
+                    Some("/srv/../.."),
+                ),
+                root("Remaining"),
+            ],
+        ];
+        for layout in invalid {
+            let config = layout_config(layout.clone());
+            assert!(
+                calculate_storage_plan(&config).is_err(),
+                "layout should be rejected: {:?}",
+                layout
+            );
+        }
+
+        let mut encrypted_esp = esp();
+        encrypted_esp.encrypt = true;
+        let config = layout_config(vec![encrypted_esp, root("Remaining")]);
+        assert!(calculate_storage_plan(&config).is_err());
+    }
+
+    #[test]
+    fn test_layout_size_spec() {
+        assert_eq!(layout_size_spec("512M").expect("valid"), "512M"); // test: known-good input
+        assert_eq!(layout_size_spec("512MiB").expect("valid"), "512M"); // test: known-good input
+        assert_eq!(layout_size_spec("40gb").expect("valid"), "40G"); // test: known-good input
+        assert_eq!(layout_size_spec(" 1T ").expect("valid"), "1T"); // test: known-good input
+        assert_eq!(layout_size_spec("Remaining").expect("valid"), "remaining"); // test: known-good input
+        assert!(layout_size_spec("512").is_err());
+        assert!(layout_size_spec("0G").is_err());
+        assert!(layout_size_spec("-5G").is_err());
+        assert!(layout_size_spec("").is_err());
+    }
+
+    fn alongside_config() -> InstallationConfig {
+        let mut config = test_config(PartitionScheme::AutoAlongside, Filesystem::Ext4);
+        config.alongside_esp = "/dev/sda1".to_string();
+        config.shrink_partition = "/dev/sda3".to_string();
+        config.shrink_size = "200GB".to_string();
+        config
+    }
+
+    #[test]
+    fn test_alongside_plan_never_wipes_or_formats_existing_partitions() {
+        let config = alongside_config();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert_eq!(plan.strategy, PartitionScheme::AutoAlongside);
+        assert_eq!(
+            plan.ops[0],
+            StorageOp::ShrinkPartition {
+                device: PathBuf::from("/dev/sda3"),
+                size: "200G".to_string(),
+            }
+        );
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::WipeDisk { .. }
+                | StorageOp::Partition { .. }
+                | StorageOp::PartitionRaidMember { .. }
+                | StorageOp::PartitionLayout { .. }
+        )));
+
+        // Every device that gets formatted was created by this plan
+        let created: Vec<PathBuf> = plan
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::CreatePartitionInFreeSpace { label, .. } => Some(partlabel_path(label)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(created.len(), 3, "boot + swap + root");
+        for op in &plan.ops {
+            if let StorageOp::FormatFs { device, .. } | StorageOp::LuksFormat { device, .. } = op {
+                assert!(
+                    created.contains(device),
+                    "formats pre-existing {:?}",
+                    device
+                );
+            }
+        }
+
+        // The existing ESP is only mounted
+        let esp_ops: Vec<&StorageOp> = plan
+            .ops
+            .iter()
+            .filter(|op| op.to_string().contains("/dev/sda1"))
+            .collect();
+        assert_eq!(
+            esp_ops,
+            vec![&StorageOp::Mount {
+                device: PathBuf::from("/dev/sda1"),
+                mountpoint: PathBuf::from("/mnt/efi"),
+                options: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_alongside_plan_uses_free_space_as_is_without_shrink() {
+        let mut config = alongside_config();
+        config.shrink_partition.clear();
+        config.shrink_size.clear();
+        config.swap = SwapMode::None;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+
+        assert_eq!(
+            plan.ops[..2],
+            [
+                StorageOp::CreatePartitionInFreeSpace {
+                    disk: PathBuf::from("/dev/sda"),
+                    size: "1024M".to_string(),
+                    partition_type: PartitionType::Linux,
+                    label: "archtui-boot".to_string(),
+                },
+                StorageOp::CreatePartitionInFreeSpace {
+                    disk: PathBuf::from("/dev/sda"),
+                    size: "remaining".to_string(),
+                    partition_type: PartitionType::Linux,
+                    label: "archtui-root".to_string(),
+                },
+            ]
+        );
+        assert!(plan.ops.contains(&StorageOp::Mount {
+            device: PathBuf::from("/dev/disk/by-partlabel/archtui-root"),
+            mountpoint: PathBuf::from("/mnt"),
+            options: None,
+        }));
+    }
+
+    #[test]
+    fn test_alongside_plan_rejects_invalid_settings() {
+        type Mutate = fn(&mut InstallationConfig);
+        let cases: Vec<(&str, Mutate)> = vec![
+            ("bios", |c| c.boot_mode = BootMode::Bios),
+            ("no esp", |c| c.alongside_esp.clear()),
+            ("size without partition", |c| c.shrink_partition.clear()),
+            ("partition without size", |c| c.shrink_size.clear()),
+            ("other disk", |c| {
+                c.shrink_partition = "/dev/sdb3".to_string()
+            }),
+            ("prefix of disk", |c| {
+                c.shrink_partition = "/dev/sdab3".to_string()
+            }),
+            ("shrink esp", |c| {
+                c.shrink_partition = "/dev/sda1".to_string()
+            }),
+            ("remaining", |c| c.shrink_size = "Remaining".to_string()),
+        ];
+        for (case, mutate) in cases {
+            let mut config = alongside_config();
+            mutate(&mut config);
+            assert!(
+                calculate_storage_plan(&config).is_err(),
+                "{} should be rejected",
+                case
+            );
+        }
+    }
+
+    fn home_config(strategy: PartitionScheme) -> InstallationConfig {
+        let mut config = test_config(strategy, Filesystem::Ext4);
+        config.separate_home = Toggle::Yes;
+        config.root_size = "Remaining".to_string();
+        config.home_size = "100GB".to_string();
+        config.home_filesystem = Filesystem::Xfs;
+        config
+    }
+
+    #[test]
+    fn test_simple_plan_separate_home() {
+        let plan = calculate_storage_plan(&home_config(PartitionScheme::AutoSimple))
+            .expect("plan generation failed"); // test: known-good input
+
+        // Root "Remaining" falls back to the default size to leave room for home
+        assert!(plan.ops.contains(&StorageOp::Partition {
+            disk: PathBuf::from("/dev/sda"),
+            create_efi: true,
+            swap_size: Some("2G".to_string()),
+            root_size: Some("51200M".to_string()),
+            home_size: Some("102400M".to_string()),
+        }));
+        assert!(plan.ops.contains(&StorageOp::FormatFs {
+            device: PathBuf::from("/dev/sda4"),
+            filesystem: Filesystem::Xfs,
+            label: Some("archhome".to_string()),
+        }));
+        assert_eq!(plan.devices().home, Some(PathBuf::from("/dev/sda4")));
+    }
+
+    #[test]
+    fn test_simple_plan_btrfs_home_partition_replaces_subvolume() {
+        let mut config = home_config(PartitionScheme::AutoSimple);
+        config.root_filesystem = Filesystem::Btrfs;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(!plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::MountBtrfsSubvolume { mountpoint, .. } if mountpoint == Path::new("/mnt/home")
+        )));
+        assert_eq!(plan.devices().home, Some(PathBuf::from("/dev/sda4")));
+    }
+
+    #[test]
+    fn test_simple_luks_plan_encrypts_home() {
+        let mut config = home_config(PartitionScheme::AutoSimpleLuks);
+        config.swap = SwapMode::None;
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        assert!(plan.ops.contains(&StorageOp::LuksOpen {
+            device: PathBuf::from("/dev/sda3"),
+            mapper_name: "crypthome".to_string(),
+        }));
+        let devices = plan.devices();
+        assert_eq!(devices.home, Some(PathBuf::from("/dev/mapper/crypthome")));
+        assert_eq!(devices.luks, Some(PathBuf::from("/dev/sda2")));
+        assert_eq!(
+            devices.luks_mappers,
+            vec![
+                (PathBuf::from("/dev/sda2"), "cryptroot".to_string()),
+                (PathBuf::from("/dev/sda3"), "crypthome".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lvm_plan_separate_home_lv() {
+        let mut config = home_config(PartitionScheme::AutoLvm);
+        config.home_size = "Remaining".to_string();
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let lvs: Vec<(&str, &str)> = plan
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                StorageOp::CreateLvmLv { lv_name, size, .. } => {
+                    Some((lv_name.as_str(), size.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            lvs,
+            vec![("swap", "2G"), ("root", "51200M"), ("home", "100%FREE")]
+        );
+        assert_eq!(plan.devices().home, Some(PathBuf::from("/dev/archvg/home")));
+    }
+
+    #[test]
+    fn test_raid_plan_rejects_separate_home_without_lvm() {
+        let mut config = raid_config(PartitionScheme::AutoRaid);
+        config.separate_home = Toggle::Yes;
+        let err = calculate_storage_plan(&config).expect_err("no home slot"); // test: known-good input
+        assert!(err.to_string().contains("no slot for a separate home"));
+    }
+
+    #[test]
+    fn test_percent_sizes_need_the_disk_size() {
+        let mut config = home_config(PartitionScheme::AutoSimple);
+        config.home_size = "50%".to_string();
+        let err = calculate_storage_plan_for(&config, HostSizes::default())
+            .expect_err("disk size unknown"); // test: known-good input
+        assert!(err.to_string().contains("disk size is unknown"));
+
+        let plan = calculate_storage_plan_for(
+            &config,
+            HostSizes {
+                disk_mib: Some(409600),
+                ..Default::default()
+            },
+        )
+        .expect("plan generation failed"); // test: known-good input
+        assert!(plan.ops.iter().any(|op| matches!(
+            op,
+            StorageOp::Partition { home_size: Some(size), .. } if size == "204800M"
+        )));
+    }
+
+    #[test]
+    fn test_plan_devices_to_shell() {
+        let config = test_config(PartitionScheme::AutoLuksLvm, Filesystem::Ext4);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let devices = plan.devices();
+        assert_eq!(devices.root, Some(PathBuf::from("/dev/archvg/root")));
+        assert_eq!(devices.efi, Some(PathBuf::from("/dev/sda1")));
+        assert_eq!(devices.swap, Some(PathBuf::from("/dev/archvg/swap")));
+        assert_eq!(devices.luks, Some(PathBuf::from("/dev/sda2")));
+
+        let shell = devices.to_shell();
+        assert!(shell.contains("export ROOT_DEVICE='/dev/archvg/root'\n"));
+        assert!(shell.contains("export HOME_DEVICE=''\n"));
+        assert!(shell.contains("export LUKS_MAPPERS='/dev/sda2|cryptlvm'\n"));
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_plan_json_round_trip() {
+        let config = raid_config(PartitionScheme::AutoRaidLvmLuks);
+        let plan = calculate_storage_plan(&config).expect("plan generation failed"); // test: known-good input
+        let json = serde_json::to_string(&plan).expect("serialize failed"); // test: known-good input
+        let loaded: StoragePlan = serde_json::from_str(&json).expect("deserialize failed"); // test: known-good input
+        assert_eq!(loaded.ops, plan.ops);
+        assert_eq!(loaded.disks, plan.disks);
+        assert_eq!(loaded.raid_level, plan.raid_level);
+    }
+
+    #[test]
+    fn test_is_partition_of() {
+        assert!(is_partition_of(Path::new("/dev/sda"), "/dev/sda3"));
+        assert!(is_partition_of(Path::new("/dev/nvme0n1"), "/dev/nvme0n1p2"));
+        assert!(!is_partition_of(Path::new("/dev/nvme0n1"), "/dev/nvme0n12"));
+        assert!(!is_partition_of(Path::new("/dev/sda"), "/dev/sda"));
+        assert!(!is_partition_of(Path::new("/dev/sda"), "/dev/sdab1"));
+    }
 }
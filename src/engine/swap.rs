@@ -0,0 +1,257 @@
+//! Swap Planning
+//!
+//! Resolves the typed swap settings (`swap`, `swap_size`, `hibernation`) into
+//! what the storage plan and the installed system need.
+//!
+//! | SwapMode       | Storage plan                                   | Installed system |
+//! |----------------|------------------------------------------------|------------------|
+//! | `None`         | —                                              | — |
+//! | `Partition`    | Swap partition/LV (swapfile on AutoSimpleLuks; rejected on AutoRaid/AutoRaidLuks) | `resume=UUID=<swap>` |
+//! | `Swapfile`     | `CreateSwapfile` after all mounts              | `resume=UUID=<root> resume_offset=<n>` |
+//! | `Zram`         | —                                              | zram-generator |
+//! | `ZramSwapfile` | `CreateSwapfile` after all mounts              | zram (higher priority) + swapfile |
+//!
+//! Resume parameters and the mkinitcpio `resume` hook are only written when
+//! `hibernation` is enabled. A swapfile's `resume_offset` is only known once
+//! the file exists, so `setup_swap_target` in `scripts/disk_utils.sh`
+//! computes it at install time.
+//!
+//! # Sizing
+//!
+//! `swap_size` is either explicit ("8GB", "4096M"; bare numbers above 64 are
+//! MiB, otherwise GiB) or relative to RAM ("Auto", "Equal to RAM",
+//! "Double RAM"). Auto mirrors `get_swap_size_mib` in `disk_utils.sh`: twice
+//! the RAM up to 4 GiB, then equal to RAM, capped at 16 GiB — unless
+//! hibernating, where swap must hold the whole of RAM.
+
+// Library API - consumed by the storage engine and config validation
+#![allow(dead_code)]
+
+use crate::config_file::{BtrfsSubvolume, InstallationConfig};
+use crate::types::{PartitionScheme, SwapMode, Toggle};
+use anyhow::{Result, bail};
+
+/// Swap size when RAM cannot be detected (matches `DEFAULT_SWAP_SIZE_MIB`).
+pub const DEFAULT_SWAP_SIZE_MIB: u64 = 2048;
+/// Largest swap the Auto rule picks without hibernation.
+const AUTO_SWAP_CAP_MIB: u64 = 16384;
+
+/// Btrfs subvolume holding the swapfile, kept out of root snapshots.
+pub const SWAP_SUBVOLUME: &str = "@swap";
+/// Mountpoint of `SWAP_SUBVOLUME` on the installed system.
+pub const SWAP_SUBVOLUME_MOUNTPOINT: &str = "/swap";
+
+/// Whether `swap_size` is sized from RAM rather than given explicitly.
+///
+/// Unset values ("", "N/A") count as "Auto".
+pub fn is_ram_relative(swap_size: &str) -> bool {
+    let size = swap_size.trim();
+    size.is_empty()
+        || ["N/A", "Auto", "Equal to RAM", "Double RAM"]
+            .iter()
+            .any(|s| size.eq_ignore_ascii_case(s))
+}
+
+/// Swap size in MiB picked by "Auto" for a machine with `ram_mib` of RAM.
+pub fn auto_swap_size_mib(ram_mib: u64, hibernation: bool) -> u64 {
+    let size = if ram_mib <= 4096 {
+        ram_mib * 2
+    } else {
+        ram_mib.min(AUTO_SWAP_CAP_MIB)
+    };
+    if hibernation { size.max(ram_mib) } else { size }
+}
+
+/// Normalize `swap_size` into a size specification understood by sgdisk,
+/// lvcreate and `create_swapfile.sh` (e.g. "2G", "512M").
+///
+/// RAM-relative sizes need `ram_mib`; without it they fall back to
+/// `DEFAULT_SWAP_SIZE_MIB`, like the bash installer.
+///
+/// # Errors
+///
+/// Returns an error for sizes that are neither explicit nor RAM-relative.
+pub fn swap_size_spec(swap_size: &str, ram_mib: Option<u64>, hibernation: bool) -> Result<String> {
+    let size = swap_size.trim();
+    if is_ram_relative(size) {
+        let mib = match ram_mib {
+            None => DEFAULT_SWAP_SIZE_MIB,
+            Some(ram) if size.eq_ignore_ascii_case("Equal to RAM") => ram,
+            Some(ram) if size.eq_ignore_ascii_case("Double RAM") => ram * 2,
+            Some(ram) => auto_swap_size_mib(ram, hibernation),
+        };
+        return Ok(format!("{}M", mib));
+    }
+
+    let upper = size.to_ascii_uppercase();
+    let spec = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper);
+    let (amount, unit) = match spec.strip_suffix(['G', 'M']) {
+        Some(amount) => (amount, &spec[amount.len()..]),
+        None => (spec, ""),
+    };
+    match (amount.trim().parse::<u64>(), unit) {
+        (Ok(n), "G" | "M") if n > 0 => Ok(format!("{}{}", n, unit)),
+        (Ok(n), "") if n > 64 => Ok(format!("{}M", n)),
+        (Ok(n), "") if n > 0 => Ok(format!("{}G", n)),
+        _ => bail!(
+            "invalid swap_size '{}' — use e.g. 8GB, 4096M, Auto, Equal to RAM or Double RAM",
+            swap_size
+        ),
+    }
+}
+
+/// `subvolumes` plus the swapfile subvolume, unless one is already declared
+/// at `SWAP_SUBVOLUME_MOUNTPOINT`.
+///
+/// A swapfile cannot live in a subvolume that gets snapshotted, so Btrfs
+/// roots keep it in its own subvolume.
+pub fn with_swap_subvolume(subvolumes: &[BtrfsSubvolume]) -> Vec<BtrfsSubvolume> {
+    let mut subvolumes = subvolumes.to_vec();
+    if !subvolumes
+        .iter()
+        .any(|s| s.mountpoint == SWAP_SUBVOLUME_MOUNTPOINT)
+    {
+        subvolumes.push(BtrfsSubvolume {
+            name: SWAP_SUBVOLUME.to_string(),
+            mountpoint: SWAP_SUBVOLUME_MOUNTPOINT.to_string(),
+            mount_options: Some("noatime".to_string()),
+        });
+    }
+    subvolumes
+}
+
+/// Check that the swap settings can be installed as configured.
+///
+/// # Errors
+///
+/// Rejects hibernation without swap on disk (zram is lost on power-off), a
+/// swap partition on a RAID strategy without LVM (the members have no slot
+/// for one) and swap sizes `swap_size_spec` cannot parse.
+pub fn validate_swap(config: &InstallationConfig) -> Result<()> {
+    if config.swap == SwapMode::Partition
+        && matches!(
+            config.partitioning_strategy,
+            PartitionScheme::AutoRaid | PartitionScheme::AutoRaidLuks
+        )
+    {
+        bail!(
+            "{:?} has no slot for a swap partition — use swap 'swapfile' or 'zram', \
+             or an LVM RAID strategy",
+            config.partitioning_strategy
+        );
+    }
+    if config.hibernation == Toggle::Yes && !config.swap.has_disk_swap() {
+        bail!(
+            "hibernation needs swap on disk — use swap 'partition', 'swapfile' or \
+             'zram+swapfile' (found '{}')",
+            config.swap
+        );
+    }
+    if config.swap.has_disk_swap() {
+        swap_size_spec(&config.swap_size, None, config.hibernation == Toggle::Yes)?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SwapMode;
+
+    #[test]
+    fn test_auto_swap_size_follows_ram() {
+        assert_eq!(auto_swap_size_mib(2048, false), 4096);
+        assert_eq!(auto_swap_size_mib(4096, false), 8192);
+        assert_eq!(auto_swap_size_mib(8192, false), 8192);
+        assert_eq!(auto_swap_size_mib(65536, false), 16384);
+        // Hibernation needs room for all of RAM
+        assert_eq!(auto_swap_size_mib(65536, true), 65536);
+        assert_eq!(auto_swap_size_mib(2048, true), 4096);
+    }
+
+    #[test]
+    fn test_swap_size_spec() {
+        assert_eq!(swap_size_spec("2", None, false).expect("valid"), "2G"); // test: known-good input
+        assert_eq!(swap_size_spec("4G", None, false).expect("valid"), "4G"); // test: known-good input
+        assert_eq!(swap_size_spec("8GB", None, false).expect("valid"), "8G"); // test: known-good input
+        assert_eq!(swap_size_spec(" 16g ", None, false).expect("valid"), "16G"); // test: known-good input
+        assert_eq!(
+            swap_size_spec("4096MiB", None, false).expect("valid"),
+            "4096M"
+        ); // test: known-good input
+        assert_eq!(swap_size_spec("512", None, false).expect("valid"), "512M"); // test: known-good input
+
+        assert!(swap_size_spec("lots", None, false).is_err());
+        assert!(swap_size_spec("0G", None, false).is_err());
+        assert!(swap_size_spec("2T", None, false).is_err());
+    }
+
+    #[test]
+    fn test_ram_relative_swap_size_spec() {
+        let ram = Some(8192);
+        assert_eq!(swap_size_spec("Auto", ram, false).expect("valid"), "8192M"); // test: known-good input
+        assert_eq!(swap_size_spec("N/A", ram, false).expect("valid"), "8192M"); // test: known-good input
+        assert_eq!(
+            swap_size_spec("Equal to RAM", ram, false).expect("valid"),
+            "8192M"
+        ); // test: known-good input
+        assert_eq!(
+            swap_size_spec("Double RAM", ram, false).expect("valid"),
+            "16384M"
+        ); // test: known-good input
+        assert_eq!(
+            swap_size_spec("auto", Some(32768), true).expect("valid"),
+            "32768M"
+        ); // test: known-good input
+        // Unknown RAM falls back to the bash default
+        assert_eq!(swap_size_spec("Auto", None, false).expect("valid"), "2048M"); // test: known-good input
+    }
+
+    #[test]
+    fn test_with_swap_subvolume_adds_once() {
+        let subvolumes = crate::config_file::InstallationConfig::new().btrfs_subvolumes;
+        let with_swap = with_swap_subvolume(&subvolumes);
+        assert_eq!(with_swap.len(), subvolumes.len() + 1);
+        let swap = with_swap.last().expect("swap subvolume appended"); // test: known-good input
+        assert_eq!(swap.name, SWAP_SUBVOLUME);
+        assert_eq!(swap.mountpoint, SWAP_SUBVOLUME_MOUNTPOINT);
+        assert_eq!(with_swap_subvolume(&with_swap).len(), with_swap.len());
+    }
+
+    #[test]
+    fn test_validate_swap() {
+        let mut config = InstallationConfig::new();
+        config.swap = SwapMode::Swapfile;
+        config.swap_size = "Auto".to_string();
+        config.hibernation = Toggle::Yes;
+        assert!(validate_swap(&config).is_ok());
+
+        config.swap = SwapMode::ZramSwapfile;
+        assert!(validate_swap(&config).is_ok());
+
+        for mode in [SwapMode::None, SwapMode::Zram] {
+            config.swap = mode;
+            let err = validate_swap(&config).expect_err("zram cannot hold an image"); // test: known-good input
+            assert!(err.to_string().contains("hibernation"));
+        }
+
+        config.swap = SwapMode::Partition;
+        config.hibernation = Toggle::No;
+        config.swap_size = "plenty".to_string();
+        assert!(validate_swap(&config).is_err());
+
+        config.swap_size = "Auto".to_string();
+        config.partitioning_strategy = PartitionScheme::AutoRaid;
+        let err = validate_swap(&config).expect_err("RAID members have no swap slot"); // test: known-good input
+        assert!(err.to_string().contains("no slot"));
+        config.partitioning_strategy = PartitionScheme::AutoRaidLvm;
+        assert!(validate_swap(&config).is_ok());
+    }
+}
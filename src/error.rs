@@ -46,6 +46,10 @@ pub enum ArchTuiError {
     #[error("Manifest error: {0}")]
     Manifest(String),
 
+    /// Script terminated by the watchdog after exceeding its time limit
+    #[error("Script timed out: {0}")]
+    Timeout(String),
+
     /// JSON serialization/deserialization errors
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
@@ -100,6 +104,11 @@ impl ArchTuiError {
         Self::Manifest(msg.into())
     }
 
+    /// Create a script timeout error
+    pub fn timeout(msg: impl Into<String>) -> Self {
+        Self::Timeout(msg.into())
+    }
+
     /// Create a general error
     pub fn general(msg: impl Into<String>) -> Self {
         Self::General(msg.into())
@@ -1,6 +1,7 @@
 //! Hardware environment detection
 //!
-//! Detects firmware mode (UEFI vs BIOS) and network connectivity using pure Rust.
+//! Detects firmware mode (UEFI vs BIOS), network connectivity and a hardware
+//! inventory (CPU, GPUs, Wi-Fi, RAM, disks, TPM, battery) using pure Rust.
 //! No shelling out — all detection uses std library primitives.
 //!
 //! # Design
@@ -8,6 +9,8 @@
 //! - **Fail Fast**: Ambiguous detection logs a warning and defaults to safe mode (BIOS)
 //! - **Pure Rust**: Network check uses `TcpStream::connect_timeout`, not ping/shell
 //! - **No `unwrap()`**: All fallible paths use `anyhow::Result`
+//! - **Testable**: The inventory reads `/proc` and `/sys` below a root path,
+//!   so tests point it at a fake tree
 //!
 //! # Integration
 //!
@@ -20,7 +23,7 @@
 use anyhow::Result;
 use std::fmt;
 use std::net::{SocketAddr, TcpStream};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::process_guard::CommandProcessGroup;
@@ -95,14 +98,16 @@ pub struct HardwareInfo {
     pub firmware: FirmwareMode,
     /// Network connectivity state
     pub network: NetworkState,
+    /// CPU, GPUs, RAM, disks and other devices
+    pub inventory: HardwareInventory,
 }
 
 impl HardwareInfo {
     /// Detect hardware environment.
     ///
-    /// Checks firmware mode and network connectivity. This function
-    /// never panics — detection failures are handled gracefully with
-    /// safe defaults.
+    /// Checks firmware mode and network connectivity and probes the hardware
+    /// inventory. This function never panics — detection failures are
+    /// handled gracefully with safe defaults.
     ///
     /// # Returns
     ///
@@ -128,13 +133,38 @@ impl HardwareInfo {
             }
         };
 
+        let inventory = HardwareInventory::probe(Path::new("/"));
+
         tracing::info!(
             "Hardware detection: firmware={}, network={}",
             firmware,
             network
         );
+        tracing::info!(
+            cpu = ?inventory.cpu.as_ref().map(|c| c.vendor),
+            gpus = inventory.gpus.len(),
+            disks = inventory.disks.len(),
+            ram_mib = ?inventory.ram_mib,
+            tpm2 = inventory.tpm2,
+            laptop = inventory.laptop,
+            "Hardware inventory"
+        );
 
-        Self { firmware, network }
+        Self {
+            firmware,
+            network,
+            inventory,
+        }
+    }
+
+    /// Lines for the hardware summary screen.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            summary_line("Firmware", self.firmware),
+            summary_line("Network", self.network),
+        ];
+        lines.extend(self.inventory.summary_lines());
+        lines
     }
 
     /// Returns true if the bootloader choice is compatible with the firmware.
@@ -156,6 +186,381 @@ impl fmt::Display for HardwareInfo {
     }
 }
 
+// ============================================================================
+// Hardware Inventory
+// ============================================================================
+
+/// PCI vendor IDs of GPU and Wi-Fi makers.
+pub const PCI_VENDOR_INTEL: u16 = 0x8086;
+pub const PCI_VENDOR_AMD: u16 = 0x1002;
+pub const PCI_VENDOR_NVIDIA: u16 = 0x10de;
+pub const PCI_VENDOR_VMWARE: u16 = 0x15ad;
+pub const PCI_VENDOR_VIRTIO: u16 = 0x1af4;
+pub const PCI_VENDOR_QEMU: u16 = 0x1234;
+pub const PCI_VENDOR_VIRTUALBOX: u16 = 0x80ee;
+
+/// `/sys/class/dmi/id/chassis_type` values of portable machines
+/// (portable, laptop, notebook, sub notebook, tablet, convertible, detachable).
+const LAPTOP_CHASSIS_TYPES: &[u8] = &[8, 9, 10, 14, 30, 31, 32];
+
+/// CPU vendor from the `vendor_id` field of `/proc/cpuinfo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+    /// Anything else, including CPUs without `vendor_id` (ARM)
+    Other,
+}
+
+impl fmt::Display for CpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Intel => write!(f, "Intel"),
+            Self::Amd => write!(f, "AMD"),
+            Self::Other => write!(f, "Other"),
+        }
+    }
+}
+
+/// Processor summary from `/proc/cpuinfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuInfo {
+    pub vendor: CpuVendor,
+    /// `model name`, e.g. "AMD Ryzen 7 5800X 8-Core Processor"
+    pub model: String,
+    /// Logical CPUs (`processor` entries)
+    pub threads: usize,
+}
+
+/// One PCI function from `/sys/bus/pci/devices`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PciDevice {
+    /// Bus address, e.g. "0000:01:00.0"
+    pub address: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// 24-bit class code (class, subclass, prog-if), e.g. 0x030000 for VGA
+    pub class: u32,
+    /// Kernel driver bound to the device, if any
+    pub driver: Option<String>,
+}
+
+impl PciDevice {
+    /// Display controller (VGA, 3D or other display).
+    pub fn is_gpu(&self) -> bool {
+        self.class >> 16 == 0x03
+    }
+
+    /// Network controller of the "other" subclass, which is where wireless
+    /// cards report themselves.
+    pub fn is_wireless(&self) -> bool {
+        self.class >> 8 == 0x0280
+    }
+
+    /// Vendor name for the IDs the installer knows, else "Unknown".
+    pub fn vendor_name(&self) -> &'static str {
+        match self.vendor_id {
+            PCI_VENDOR_INTEL => "Intel",
+            PCI_VENDOR_AMD => "AMD",
+            PCI_VENDOR_NVIDIA => "NVIDIA",
+            PCI_VENDOR_VMWARE => "VMware",
+            PCI_VENDOR_VIRTIO => "virtio",
+            PCI_VENDOR_QEMU => "QEMU",
+            PCI_VENDOR_VIRTUALBOX => "VirtualBox",
+            _ => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for PciDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{:04x}:{:04x}]",
+            self.vendor_name(),
+            self.vendor_id,
+            self.device_id
+        )?;
+        if let Some(ref driver) = self.driver {
+            write!(f, " driver {}", driver)?;
+        }
+        Ok(())
+    }
+}
+
+/// A whole disk from `/sys/class/block` (partitions and virtual devices
+/// such as loop, zram and device-mapper are skipped).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockDevice {
+    /// Kernel name, e.g. "nvme0n1"
+    pub name: String,
+    pub size_mib: u64,
+    /// Spinning disk (`queue/rotational`)
+    pub rotational: bool,
+    /// Removable media such as USB sticks and card readers
+    pub removable: bool,
+    /// `device/model`, if the driver exposes one
+    pub model: Option<String>,
+}
+
+impl BlockDevice {
+    /// Device node, e.g. `/dev/nvme0n1`.
+    pub fn path(&self) -> PathBuf {
+        Path::new("/dev").join(&self.name)
+    }
+
+    pub fn is_nvme(&self) -> bool {
+        self.name.starts_with("nvme")
+    }
+
+    /// Short media label: NVMe, SSD, HDD or Removable.
+    pub fn kind(&self) -> &'static str {
+        if self.removable {
+            "Removable"
+        } else if self.is_nvme() {
+            "NVMe"
+        } else if self.rotational {
+            "HDD"
+        } else {
+            "SSD"
+        }
+    }
+}
+
+/// Devices found under `/proc` and `/sys`.
+///
+/// Every probe is best-effort: an unreadable file leaves its field empty
+/// rather than failing detection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HardwareInventory {
+    pub cpu: Option<CpuInfo>,
+    /// Display controllers, by PCI address
+    pub gpus: Vec<PciDevice>,
+    /// Wireless network controllers, by PCI address
+    pub wireless: Vec<PciDevice>,
+    pub ram_mib: Option<u64>,
+    /// Whole disks, by name
+    pub disks: Vec<BlockDevice>,
+    /// A TPM 2.0 device is present (`/sys/class/tpm`)
+    pub tpm2: bool,
+    /// A battery is present (`/sys/class/power_supply`)
+    pub battery: bool,
+    /// Battery or a portable DMI chassis type
+    pub laptop: bool,
+}
+
+impl HardwareInventory {
+    /// Probe the system whose `/proc` and `/sys` live below `root`
+    /// (`/` for the running system).
+    pub fn probe(root: &Path) -> Self {
+        let cpu = read_optional(&root.join("proc/cpuinfo")).and_then(|s| parse_cpuinfo(&s));
+        let ram_mib =
+            read_optional(&root.join("proc/meminfo")).and_then(|s| parse_meminfo_total_mib(&s));
+        let pci = probe_pci_devices(&root.join("sys/bus/pci/devices"));
+        let battery = probe_battery(&root.join("sys/class/power_supply"));
+        let laptop_chassis = read_optional(&root.join("sys/class/dmi/id/chassis_type"))
+            .and_then(|s| s.trim().parse::<u8>().ok())
+            .is_some_and(|t| LAPTOP_CHASSIS_TYPES.contains(&t));
+
+        Self {
+            cpu,
+            gpus: pci.iter().filter(|d| d.is_gpu()).cloned().collect(),
+            wireless: pci.iter().filter(|d| d.is_wireless()).cloned().collect(),
+            ram_mib,
+            disks: probe_block_devices(&root.join("sys/class/block")),
+            tpm2: probe_tpm2(&root.join("sys/class/tpm")),
+            battery,
+            laptop: battery || laptop_chassis,
+        }
+    }
+
+    /// Whether any GPU is from `vendor_id`.
+    pub fn has_gpu_vendor(&self, vendor_id: u16) -> bool {
+        self.gpus.iter().any(|g| g.vendor_id == vendor_id)
+    }
+
+    /// One line per fact, for the hardware summary screen.
+    pub fn summary_lines(&self) -> Vec<String> {
+        use crate::engine::capacity::format_mib;
+
+        let mut lines = Vec::new();
+        lines.push(match self.cpu {
+            Some(ref cpu) => summary_line(
+                "CPU",
+                format!("{} ({} threads, {})", cpu.model, cpu.threads, cpu.vendor),
+            ),
+            None => summary_line("CPU", "unknown"),
+        });
+        lines.push(summary_line(
+            "RAM",
+            self.ram_mib.map_or("unknown".to_string(), format_mib),
+        ));
+        if self.gpus.is_empty() {
+            lines.push(summary_line("GPU", "none detected"));
+        }
+        for gpu in &self.gpus {
+            lines.push(summary_line("GPU", gpu));
+        }
+        for card in &self.wireless {
+            lines.push(summary_line("Wi-Fi", card));
+        }
+        if self.disks.is_empty() {
+            lines.push(summary_line("Disk", "none detected"));
+        }
+        for disk in &self.disks {
+            let mut value = format!(
+                "{:<14} {:>10}  {}",
+                disk.path().display(),
+                format_mib(disk.size_mib),
+                disk.kind()
+            );
+            if let Some(ref model) = disk.model {
+                value.push_str("  ");
+                value.push_str(model);
+            }
+            lines.push(summary_line("Disk", value));
+        }
+        lines.push(summary_line(
+            "TPM 2.0",
+            if self.tpm2 { "present" } else { "not found" },
+        ));
+        lines.push(summary_line(
+            "Form",
+            match (self.laptop, self.battery) {
+                (true, true) => "laptop (battery present)",
+                (true, false) => "laptop",
+                _ => "desktop / server",
+            },
+        ));
+        lines
+    }
+}
+
+/// "Label:     value", aligned for the summary screen.
+fn summary_line(label: &str, value: impl fmt::Display) -> String {
+    format!("{:<10} {}", format!("{}:", label), value)
+}
+
+/// Read a sysfs/procfs file, `None` if it is missing or unreadable.
+fn read_optional(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+/// Parse a sysfs hex attribute such as "0x10de\n".
+fn parse_sysfs_hex(s: &str) -> Option<u32> {
+    u32::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Parse vendor, model and thread count out of `/proc/cpuinfo`.
+fn parse_cpuinfo(cpuinfo: &str) -> Option<CpuInfo> {
+    let field = |name: &str| {
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let threads = cpuinfo
+        .lines()
+        .filter(|line| line.split(':').next().map(str::trim) == Some("processor"))
+        .count();
+    if threads == 0 {
+        return None;
+    }
+    let vendor = match field("vendor_id").as_deref() {
+        Some("GenuineIntel") => CpuVendor::Intel,
+        Some("AuthenticAMD") => CpuVendor::Amd,
+        _ => CpuVendor::Other,
+    };
+    Some(CpuInfo {
+        vendor,
+        model: field("model name").unwrap_or_else(|| "unknown".to_string()),
+        threads,
+    })
+}
+
+/// Every PCI function under `dir` that reports vendor, device and class.
+fn probe_pci_devices(dir: &Path) -> Vec<PciDevice> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        tracing::debug!(path = %dir.display(), "No PCI devices in sysfs");
+        return Vec::new();
+    };
+    let mut devices: Vec<PciDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let attr =
+                |name: &str| read_optional(&path.join(name)).and_then(|s| parse_sysfs_hex(&s));
+            Some(PciDevice {
+                address: entry.file_name().to_string_lossy().into_owned(),
+                vendor_id: u16::try_from(attr("vendor")?).ok()?,
+                device_id: u16::try_from(attr("device")?).ok()?,
+                class: attr("class")?,
+                driver: std::fs::read_link(path.join("driver"))
+                    .ok()
+                    .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned())),
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.address.cmp(&b.address));
+    devices
+}
+
+/// Whole, non-empty disks backed by a device under `dir`.
+fn probe_block_devices(dir: &Path) -> Vec<BlockDevice> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        tracing::debug!(path = %dir.display(), "No block devices in sysfs");
+        return Vec::new();
+    };
+    let flag = |path: &Path| read_optional(path).is_some_and(|s| s.trim() == "1");
+    let mut disks: Vec<BlockDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            // Partitions have a `partition` attribute; loop, zram and dm
+            // devices have no backing `device`
+            if path.join("partition").exists() || !path.join("device").exists() {
+                return None;
+            }
+            let sectors = read_optional(&path.join("size"))?
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            if sectors == 0 {
+                return None; // Empty card reader or optical drive
+            }
+            Some(BlockDevice {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                size_mib: sectors * 512 / (1024 * 1024),
+                rotational: flag(&path.join("queue/rotational")),
+                removable: flag(&path.join("removable")),
+                model: read_optional(&path.join("device/model"))
+                    .map(|m| m.trim().to_string())
+                    .filter(|m| !m.is_empty()),
+            })
+        })
+        .collect();
+    disks.sort_by(|a, b| a.name.cmp(&b.name));
+    disks
+}
+
+/// Whether any TPM under `dir` reports major version 2.
+fn probe_tpm2(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            read_optional(&entry.path().join("tpm_version_major")).is_some_and(|v| v.trim() == "2")
+        })
+    })
+}
+
+/// Whether any power supply under `dir` is a battery.
+fn probe_battery(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            read_optional(&entry.path().join("type")).is_some_and(|t| t.trim() == "Battery")
+        })
+    })
+}
+
 // ============================================================================
 // OS Detection Types
 // ============================================================================
@@ -612,6 +1017,97 @@ pub fn detect_internet_strict() -> Result<bool> {
     }
 }
 
+/// Detect total RAM in MiB from `/proc/meminfo`.
+///
+/// Used to size swap when `swap_size` is RAM-relative ("Auto"). Returns
+/// `None` if the file is unreadable or has no `MemTotal` line.
+pub fn detect_ram_mib() -> Option<u64> {
+    match std::fs::read_to_string("/proc/meminfo") {
+        Ok(meminfo) => parse_meminfo_total_mib(&meminfo),
+        Err(e) => {
+            tracing::warn!(error = %e, "Cannot read /proc/meminfo — RAM size unknown");
+            None
+        }
+    }
+}
+
+/// Parse the `MemTotal:` line (in kB) of `/proc/meminfo` into MiB.
+fn parse_meminfo_total_mib(meminfo: &str) -> Option<u64> {
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+/// Detect the capacity of a whole block device in MiB.
+///
+/// Reads `/sys/class/block/<name>/size` (always in 512-byte sectors) and
+/// falls back to `lsblk -bdno SIZE` when sysfs is unavailable. Returns
+/// `None` if neither source yields a size, e.g. the disk does not exist.
+pub fn detect_disk_size_mib(disk: &Path) -> Option<u64> {
+    use std::process::Command;
+
+    let name = disk.file_name()?.to_str()?;
+    let sysfs = Path::new("/sys/class/block").join(name).join("size");
+    if let Some(sectors) = std::fs::read_to_string(&sysfs)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+    {
+        return Some(sectors * 512 / (1024 * 1024));
+    }
+
+    let output = match Command::new("lsblk")
+        .args(["-bdno", "SIZE"])
+        .arg(disk)
+        .in_new_process_group()
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!(disk = %disk.display(), error = %e, "lsblk failed — disk size unknown");
+            return None;
+        }
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|bytes| bytes / (1024 * 1024))
+}
+
+/// Find the first EFI System Partition on `disk` by its GPT type GUID.
+///
+/// Returns `None` if lsblk fails or the disk has no ESP. Used for the ESP an
+/// alongside install reuses when `alongside_esp` is left empty.
+pub fn detect_esp_partition(disk: &Path) -> Option<PathBuf> {
+    use std::process::Command;
+
+    let output = match Command::new("lsblk")
+        .args(["-rno", "PATH,PARTTYPE"])
+        .arg(disk)
+        .in_new_process_group()
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!(disk = %disk.display(), error = %e, "lsblk failed — ESP unknown");
+            return None;
+        }
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let (path, parttype) = line.split_once(' ')?;
+            parttype
+                .eq_ignore_ascii_case(ESP_PARTTYPE_GUID)
+                .then(|| PathBuf::from(path))
+        })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -651,6 +1147,7 @@ mod tests {
         let info = HardwareInfo {
             firmware: FirmwareMode::Uefi,
             network: NetworkState::Online,
+            inventory: HardwareInventory::default(),
         };
         assert_eq!(info.to_string(), "Firmware: UEFI, Network: Online");
     }
@@ -660,10 +1157,12 @@ mod tests {
         let uefi_hw = HardwareInfo {
             firmware: FirmwareMode::Uefi,
             network: NetworkState::Offline,
+            inventory: HardwareInventory::default(),
         };
         let bios_hw = HardwareInfo {
             firmware: FirmwareMode::Bios,
             network: NetworkState::Offline,
+            inventory: HardwareInventory::default(),
         };
 
         use crate::types::Bootloader;
@@ -677,6 +1176,162 @@ mod tests {
         assert!(bios_hw.is_bootloader_compatible(&Bootloader::Grub));
     }
 
+    #[test]
+    fn test_parse_meminfo_total_mib() {
+        let meminfo = "MemTotal:       16318412 kB\nMemFree:         8123456 kB\n";
+        assert_eq!(parse_meminfo_total_mib(meminfo), Some(15935));
+        assert_eq!(parse_meminfo_total_mib("MemFree: 1024 kB\n"), None);
+        assert_eq!(parse_meminfo_total_mib("MemTotal: lots kB\n"), None);
+    }
+
+    /// Write `contents` to `root/rel`, creating parent directories.
+    fn write_fake(root: &Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("mkdir"); // test: known-good input
+        std::fs::write(path, contents).expect("write"); // test: known-good input
+    }
+
+    /// A laptop with an Intel CPU, hybrid Intel/NVIDIA graphics, Intel Wi-Fi,
+    /// an NVMe disk, a USB stick, a partition and a loop device.
+    fn fake_laptop_root() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let root = dir.path();
+        write_fake(
+            root,
+            "proc/cpuinfo",
+            "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Core(TM) i7-1165G7\n\n\
+             processor\t: 1\nvendor_id\t: GenuineIntel\nmodel name\t: Intel(R) Core(TM) i7-1165G7\n",
+        );
+        write_fake(root, "proc/meminfo", "MemTotal:       16317952 kB\n");
+
+        let pci = [
+            ("0000:00:02.0", "0x8086", "0x9a49", "0x030000"),
+            ("0000:01:00.0", "0x10de", "0x1f99", "0x030200"),
+            ("0000:00:14.3", "0x8086", "0xa0f0", "0x028000"),
+            ("0000:00:1f.3", "0x8086", "0xa0c8", "0x040380"),
+        ];
+        for (addr, vendor, device, class) in pci {
+            let base = format!("sys/bus/pci/devices/{}", addr);
+            write_fake(root, &format!("{}/vendor", base), vendor);
+            write_fake(root, &format!("{}/device", base), device);
+            write_fake(root, &format!("{}/class", base), class);
+        }
+        let drivers = root.join("sys/bus/pci/drivers/i915");
+        std::fs::create_dir_all(&drivers).expect("mkdir"); // test: known-good input
+        let link = root.join("sys/bus/pci/devices/0000:00:02.0/driver");
+        std::os::unix::fs::symlink(&drivers, link).expect("symlink"); // test: known-good input
+
+        let block = [
+            ("nvme0n1", "1000215216", "0", "0", Some("Samsung SSD 980")),
+            ("sda", "60437492", "0", "1", None),
+        ];
+        for (name, size, rotational, removable, model) in block {
+            let base = format!("sys/class/block/{}", name);
+            write_fake(root, &format!("{}/size", base), size);
+            write_fake(root, &format!("{}/queue/rotational", base), rotational);
+            write_fake(root, &format!("{}/removable", base), removable);
+            write_fake(root, &format!("{}/device/model", base), model.unwrap_or(""));
+        }
+        write_fake(root, "sys/class/block/nvme0n1p1/partition", "1");
+        write_fake(root, "sys/class/block/nvme0n1p1/size", "1048576");
+        write_fake(root, "sys/class/block/loop0/size", "2097152");
+
+        write_fake(root, "sys/class/tpm/tpm0/tpm_version_major", "2\n");
+        write_fake(root, "sys/class/power_supply/AC/type", "Mains\n");
+        write_fake(root, "sys/class/power_supply/BAT0/type", "Battery\n");
+        dir
+    }
+
+    #[test]
+    fn test_inventory_probe_fake_laptop() {
+        let root = fake_laptop_root();
+        let inv = HardwareInventory::probe(root.path());
+
+        let cpu = inv.cpu.as_ref().expect("cpu"); // test: known-good input
+        assert_eq!(cpu.vendor, CpuVendor::Intel);
+        assert_eq!(cpu.threads, 2);
+        assert_eq!(cpu.model, "Intel(R) Core(TM) i7-1165G7");
+        assert_eq!(inv.ram_mib, Some(15935));
+
+        assert_eq!(inv.gpus.len(), 2);
+        assert_eq!(inv.gpus[0].driver.as_deref(), Some("i915"));
+        assert!(inv.has_gpu_vendor(PCI_VENDOR_NVIDIA));
+        assert!(!inv.has_gpu_vendor(PCI_VENDOR_AMD));
+        assert_eq!(inv.gpus[1].to_string(), "NVIDIA [10de:1f99]");
+        assert_eq!(inv.wireless.len(), 1);
+        assert_eq!(inv.wireless[0].device_id, 0xa0f0);
+
+        let names: Vec<&str> = inv.disks.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["nvme0n1", "sda"]);
+        assert!(inv.disks[0].is_nvme());
+        assert_eq!(inv.disks[0].size_mib, 488386);
+        assert_eq!(inv.disks[0].model.as_deref(), Some("Samsung SSD 980"));
+        assert_eq!(inv.disks[1].kind(), "Removable");
+        assert_eq!(inv.disks[1].model, None);
+
+        assert!(inv.tpm2);
+        assert!(inv.battery);
+        assert!(inv.laptop);
+    }
+
+    #[test]
+    fn test_inventory_probe_empty_root() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        assert_eq!(
+            HardwareInventory::probe(dir.path()),
+            HardwareInventory::default()
+        );
+    }
+
+    #[test]
+    fn test_inventory_laptop_from_chassis_type() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        write_fake(dir.path(), "sys/class/dmi/id/chassis_type", "10\n");
+        write_fake(dir.path(), "sys/class/tpm/tpm0/tpm_version_major", "1\n");
+        let inv = HardwareInventory::probe(dir.path());
+        assert!(inv.laptop);
+        assert!(!inv.battery);
+        assert!(!inv.tpm2);
+
+        write_fake(dir.path(), "sys/class/dmi/id/chassis_type", "3\n");
+        assert!(!HardwareInventory::probe(dir.path()).laptop);
+    }
+
+    #[test]
+    fn test_parse_cpuinfo_vendors() {
+        let cpuinfo = "processor : 0\nvendor_id : AuthenticAMD\nmodel name : AMD Ryzen 7\n";
+        let amd = parse_cpuinfo(cpuinfo).expect("cpu"); // test: known-good input
+        assert_eq!(amd.vendor, CpuVendor::Amd);
+        assert_eq!(amd.model, "AMD Ryzen 7");
+
+        let arm = parse_cpuinfo("processor : 0\nBogoMIPS : 48.00\n").expect("cpu"); // test: known-good input
+        assert_eq!(arm.vendor, CpuVendor::Other);
+        assert_eq!(arm.model, "unknown");
+
+        assert_eq!(parse_cpuinfo(""), None);
+    }
+
+    #[test]
+    fn test_inventory_summary_lines() {
+        let root = fake_laptop_root();
+        let info = HardwareInfo {
+            firmware: FirmwareMode::Uefi,
+            network: NetworkState::Online,
+            inventory: HardwareInventory::probe(root.path()),
+        };
+        let lines = info.summary_lines();
+        assert_eq!(lines[0], "Firmware:  UEFI");
+        assert!(lines.contains(&"RAM:       15.6 GiB".to_string()));
+        assert_eq!(lines.iter().filter(|l| l.starts_with("GPU:")).count(), 2);
+        let disk = lines.iter().find(|l| l.starts_with("Disk:"));
+        assert!(disk.is_some_and(|l| l.contains("/dev/nvme0n1") && l.ends_with("Samsung SSD 980")));
+        assert!(lines.iter().any(|l| l == "TPM 2.0:   present"));
+
+        let empty = HardwareInventory::default().summary_lines();
+        assert!(empty.contains(&"GPU:       none detected".to_string()));
+        assert!(empty.contains(&"Form:      desktop / server".to_string()));
+    }
+
     #[test]
     fn test_detect_firmware_mode_runs() {
         // This test runs on any system — just verify it returns a valid variant
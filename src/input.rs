@@ -7,7 +7,7 @@ use crate::process_guard::CommandProcessGroup;
 use crate::types::{
     AurHelper, AutoToggle, BootMode, Bootloader, DeVariant, DesktopEnvironment, DisplayManager,
     Editor, EncryptionKeyType, Filesystem, GpuDriver, GrubTheme, Kernel, NetworkManager,
-    PartitionScheme, PlymouthTheme, SnapshotFrequency, SnapshotTool, Toggle,
+    PartitionScheme, PlymouthTheme, SnapshotFrequency, SnapshotTool, SwapMode, Toggle,
 };
 use ratatui::widgets::ListState;
 use strum::IntoEnumIterator;
@@ -1418,7 +1418,8 @@ impl InputHandler {
                 .map(|v| v.to_string())
                 .collect(),
             "Separate Home Partition" => Toggle::iter().map(|v| v.to_string()).collect(),
-            "Swap" => Toggle::iter().map(|v| v.to_string()).collect(),
+            "Swap" => SwapMode::iter().map(|v| v.to_string()).collect(),
+            "Hibernation" => Toggle::iter().map(|v| v.to_string()).collect(),
             "Btrfs Snapshots" => Toggle::iter().map(|v| v.to_string()).collect(),
             "Snapshot Frequency" => SnapshotFrequency::iter().map(|v| v.to_string()).collect(),
             "Snapshot Tool" => SnapshotTool::iter().map(|v| v.to_string()).collect(),
@@ -1501,6 +1502,7 @@ impl InputHandler {
                 "dvorak".to_string(),
             ],
             "Swap Size" => vec![
+                "Auto".to_string(),
                 "1GB".to_string(),
                 "2GB".to_string(),
                 "4GB".to_string(),
@@ -0,0 +1,813 @@
+//! Install Checkpoints
+//!
+//! Persists `InstallerContext` progress so an install that fails after the
+//! disk was partitioned (e.g. pacstrap on a flaky mirror) can be resumed with
+//! `archtui install --config <file> --resume` instead of re-wiping the disk.
+//!
+//! # Files
+//!
+//! - `<log dir>/install-checkpoint.json` — rewritten on every stage transition
+//! - `/mnt/var/lib/archtui/install-checkpoint.json` — copy on the target, once
+//!   partitioning has finished and the target is mounted
+//! - `<log dir>/storage-plan.json` — the storage plan `install.sh` hands to
+//!   `archtui apply-storage` to partition the disk
+//! - `<log dir>/storage-applied.json` — the plan with the number of its ops
+//!   `apply-storage` has applied, rewritten after every op
+//! - `<log dir>/storage-state.sh` — written by `install.sh` after partitioning
+//!   (device UUIDs and other values later stages need)
+//!
+//! A checkpoint records the stage history, a hash of the configuration
+//! (passwords excluded), the storage plan ops and the mounts under `/mnt` as
+//! they were right after partitioning.
+//!
+//! # Resume Rules
+//!
+//! - Only stages after `PartitioningDisk` can be resumed; nothing destructive
+//!   is ever repeated
+//! - The configuration must hash to the checkpointed value
+//! - Every recorded mount must still be mounted from the same source
+//!   (`install.sh` leaves the target mounted when a resumable stage fails)
+//!
+//! An install that fails before that is torn down from the applied-storage
+//! record: exactly the ops that ran are released, in reverse order.
+
+// Library API - some helpers are only used by tests and the headless installer
+#![allow(dead_code)]
+
+use crate::config_file::InstallationConfig;
+use crate::engine::storage::{self, StoragePlan, TeardownOp};
+use crate::install_state::InstallStage;
+use crate::script_runner::ScriptOutput;
+use crate::scripts::storage::TeardownStorageArgs;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the checkpoint in the log dir and on the target.
+pub const CHECKPOINT_FILE_NAME: &str = "install-checkpoint.json";
+
+/// File name of the storage plan handed to `archtui apply-storage`.
+pub const STORAGE_PLAN_FILE_NAME: &str = "storage-plan.json";
+
+/// File name of the record of the storage plan ops applied so far.
+pub const APPLIED_STORAGE_FILE_NAME: &str = "storage-applied.json";
+
+/// Root of the target system while installing.
+pub const TARGET_ROOT: &str = "/mnt";
+
+/// Checkpoint directory on the target, relative to `TARGET_ROOT`.
+pub const TARGET_CHECKPOINT_SUBDIR: &str = "var/lib/archtui";
+
+/// Checkpoint file format version.
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// One entry of the mount table under the target root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountRecord {
+    /// Mounted device (e.g. `/dev/mapper/cryptroot`)
+    pub source: String,
+    /// Mountpoint (e.g. `/mnt/boot`)
+    pub target: PathBuf,
+    /// Filesystem type as reported by the kernel
+    pub fstype: String,
+}
+
+/// Saved progress of an installation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallCheckpoint {
+    /// Checkpoint format version
+    pub version: u32,
+    /// `config_hash` of the configuration being installed
+    pub config_hash: String,
+    /// Stage transitions with unix timestamps, as in `InstallerContext`
+    pub stage_history: Vec<(InstallStage, u64)>,
+    /// Stage the installation failed in, if it failed
+    pub failed_at: Option<InstallStage>,
+    /// Storage plan ops for the configuration (empty if it has no plan)
+    pub storage_ops: Vec<String>,
+    /// Mounts under the target root once partitioning finished
+    pub mounts: Vec<MountRecord>,
+}
+
+impl InstallCheckpoint {
+    /// Load a checkpoint file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        let checkpoint: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse checkpoint {}", path.display()))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            bail!(
+                "Unsupported checkpoint version {} in {}",
+                checkpoint.version,
+                path.display()
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    /// Find the most recent checkpoint in the log dir or on the target.
+    pub fn find() -> Result<(Self, PathBuf)> {
+        let candidates = [
+            crate::script_runner::log_dir().join(CHECKPOINT_FILE_NAME),
+            Path::new(TARGET_ROOT)
+                .join(TARGET_CHECKPOINT_SUBDIR)
+                .join(CHECKPOINT_FILE_NAME),
+        ];
+        candidates
+            .iter()
+            .filter(|path| path.exists())
+            .filter_map(|path| match Self::load(path) {
+                Ok(checkpoint) => Some((checkpoint, path.clone())),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Ignoring unreadable checkpoint");
+                    None
+                }
+            })
+            .max_by_key(|(checkpoint, _)| checkpoint.updated_at())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No install checkpoint found (looked for {})",
+                    candidates
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+
+    /// Write the checkpoint into `dir`, replacing any previous one atomically.
+    pub fn save_to(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create checkpoint dir {}", dir.display()))?;
+        let path = dir.join(CHECKPOINT_FILE_NAME);
+        let tmp = dir.join(format!("{}.tmp", CHECKPOINT_FILE_NAME));
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize checkpoint")?;
+        fs::write(&tmp, json).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Timestamp of the latest recorded transition (0 if none).
+    pub fn updated_at(&self) -> u64 {
+        self.stage_history.last().map_or(0, |&(_, ts)| ts)
+    }
+
+    /// Stage a resumed install re-enters the pipeline at.
+    ///
+    /// That is the failed stage, or the last stage entered if the installer
+    /// died without recording a failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the install completed, never started, or stopped
+    /// at or before `PartitioningDisk` (resuming would repeat destructive work).
+    pub fn resume_stage(&self) -> Result<InstallStage> {
+        let last_entered = self
+            .stage_history
+            .iter()
+            .rev()
+            .map(|&(stage, _)| stage)
+            .find(|&stage| stage != InstallStage::Failed);
+        let stage = match (self.failed_at, last_entered) {
+            (Some(stage), _) => stage,
+            (None, Some(InstallStage::Completed)) => {
+                bail!("The checkpointed installation already completed")
+            }
+            (None, Some(stage)) => stage,
+            (None, None) => bail!("The checkpoint records no installation progress"),
+        };
+        if !is_resumable(stage) {
+            bail!(
+                "Installation stopped during '{}', before partitioning finished. \
+                 Resuming would repeat destructive stages; start a fresh install instead",
+                stage.description().to_lowercase()
+            );
+        }
+        Ok(stage)
+    }
+
+    /// Check that `config` is the configuration this checkpoint was written for.
+    pub fn verify_config(&self, config: &InstallationConfig) -> Result<()> {
+        let hash = config_hash(config);
+        if hash != self.config_hash {
+            bail!(
+                "Configuration does not match the checkpoint (hash {} != {}). \
+                 Resume with the same configuration file",
+                hash,
+                self.config_hash
+            );
+        }
+        Ok(())
+    }
+
+    /// Check that the target is still mounted as it was after partitioning.
+    pub fn verify_mounts(&self) -> Result<()> {
+        let table =
+            fs::read_to_string("/proc/self/mounts").context("Failed to read /proc/self/mounts")?;
+        self.verify_mounts_with(&table, Path::new(TARGET_ROOT))
+    }
+
+    /// `verify_mounts` against the given mount table and target root.
+    pub fn verify_mounts_with(&self, mount_table: &str, target_root: &Path) -> Result<()> {
+        let current = parse_mounts(mount_table, target_root);
+        if self.mounts.is_empty() {
+            // Nothing recorded (checkpoint from before partitioning finished)
+            if !current.iter().any(|m| m.target == target_root) {
+                bail!("Target root {} is not mounted", target_root.display());
+            }
+            return Ok(());
+        }
+
+        let missing: Vec<String> = self
+            .mounts
+            .iter()
+            .filter(|m| {
+                !current
+                    .iter()
+                    .any(|c| c.target == m.target && c.source == m.source)
+            })
+            .map(|m| format!("{} on {}", m.source, m.target.display()))
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Target is no longer mounted as it was after partitioning (missing: {}). \
+                 Re-open and re-mount it, then retry --resume",
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if an install that stopped in `stage` can be resumed.
+pub fn is_resumable(stage: InstallStage) -> bool {
+    !stage.is_terminal() && stage.order() > InstallStage::PartitioningDisk.order()
+}
+
+/// Stable hash of a configuration, ignoring passwords.
+///
+/// FNV-1a over the serialized config, so it does not change between builds.
+pub fn config_hash(config: &InstallationConfig) -> String {
+    let mut redacted = config.clone();
+    redacted.user_password.clear();
+    redacted.root_password.clear();
+    redacted.encryption_password.clear();
+    let json = serde_json::to_vec(&redacted).unwrap_or_default();
+    let hash = json.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Parse a `/proc/self/mounts` table, keeping mounts at or below `root`.
+pub fn parse_mounts(mount_table: &str, root: &Path) -> Vec<MountRecord> {
+    mount_table
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let source = unescape_mount_field(fields.next()?);
+            let target = PathBuf::from(unescape_mount_field(fields.next()?));
+            let fstype = fields.next()?.to_string();
+            target.starts_with(root).then_some(MountRecord {
+                source,
+                target,
+                fstype,
+            })
+        })
+        .collect()
+}
+
+/// Load a storage plan written by `CheckpointWriter::storage_plan_env`.
+pub fn load_storage_plan(path: &Path) -> Result<StoragePlan> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read storage plan {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse storage plan {}", path.display()))
+}
+
+/// Storage plan ops `archtui apply-storage` has applied to the disks.
+///
+/// Saved next to the plan after every op, so whatever is left active (mounts,
+/// VGs, LUKS mappers, RAID arrays) can be released op by op instead of
+/// guessed from the current configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedStorage {
+    /// Plan being applied
+    pub plan: StoragePlan,
+    /// Number of leading `plan.ops` that completed
+    pub applied: usize,
+}
+
+impl AppliedStorage {
+    /// Load the record from `dir`, if one was written.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(APPLIED_STORAGE_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write the record to `dir`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(APPLIED_STORAGE_FILE_NAME);
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize applied storage")?;
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Teardown of the applied ops, last applied first.
+    pub fn teardown(&self) -> Vec<TeardownOp> {
+        self.plan.teardown(self.applied)
+    }
+}
+
+/// Release the storage recorded in `dir` after an install failed in
+/// `failed_at`, running the teardown through `run`.
+///
+/// Nothing is released when the stage is resumable: the target stays
+/// mounted for `--resume`. After a complete teardown the record is reset to
+/// no applied ops. Returns the number of teardown steps run.
+///
+/// # Errors
+///
+/// Returns an error if the record cannot be read or the teardown fails.
+pub fn teardown_applied_storage(
+    dir: &Path,
+    failed_at: InstallStage,
+    run: impl FnOnce(&TeardownStorageArgs) -> Result<ScriptOutput>,
+) -> Result<usize> {
+    if is_resumable(failed_at) {
+        return Ok(0);
+    }
+    let Some(mut record) = AppliedStorage::load(dir)? else {
+        return Ok(0);
+    };
+    let ops = record.teardown();
+    if ops.is_empty() {
+        return Ok(0);
+    }
+    let steps = ops.len();
+    let args = TeardownStorageArgs { ops, confirm: true };
+    run(&args)?.ensure_success("storage teardown")?;
+    record.applied = 0;
+    record.save(dir)?;
+    Ok(steps)
+}
+
+/// Decode the octal escapes (`\040` for space, ...) used in the mount table.
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i + 1..i + 4) {
+            Some(octal) if bytes[i] == b'\\' => std::str::from_utf8(octal)
+                .ok()
+                .and_then(|o| u8::from_str_radix(o, 8).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Writes checkpoints for an `InstallerContext` (see `enable_checkpoints`).
+///
+/// Writing is best-effort: a failed write is logged and the install goes on.
+#[derive(Debug, Clone)]
+pub struct CheckpointWriter {
+    config_hash: String,
+    storage_ops: Vec<String>,
+    /// Plan `install.sh` executes, or why a plan-executed config has none
+    storage_plan: Result<Option<StoragePlan>, String>,
+    mounts: Vec<MountRecord>,
+    log_dir: PathBuf,
+    target_root: PathBuf,
+}
+
+impl CheckpointWriter {
+    /// Writer for `config`, using the log dir and `/mnt`.
+    pub fn new(config: &InstallationConfig) -> Self {
+        let storage_plan = match storage::calculate_install_plan(config) {
+            Ok(plan) => Ok(Some(plan)),
+            Err(e) if storage::is_plan_executed(config) => {
+                tracing::warn!(error = %e, "No storage plan for the install");
+                Err(format!("{:#}", e))
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "No storage plan to checkpoint");
+                Ok(None)
+            }
+        };
+        let storage_ops = match &storage_plan {
+            Ok(Some(plan)) => plan.ops.iter().map(ToString::to_string).collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            storage_plan,
+            ..Self::with_paths(
+                config_hash(config),
+                storage_ops,
+                crate::script_runner::log_dir(),
+                PathBuf::from(TARGET_ROOT),
+            )
+        }
+    }
+
+    /// Writer with explicit locations (the target copy goes under
+    /// `target_root/var/lib/archtui`).
+    pub fn with_paths(
+        config_hash: String,
+        storage_ops: Vec<String>,
+        log_dir: PathBuf,
+        target_root: PathBuf,
+    ) -> Self {
+        Self {
+            config_hash,
+            storage_ops,
+            storage_plan: Ok(None),
+            mounts: Vec::new(),
+            log_dir,
+            target_root,
+        }
+    }
+
+    /// Carry over the mounts recorded by the checkpoint being resumed.
+    pub fn resuming(mut self, checkpoint: &InstallCheckpoint) -> Self {
+        self.mounts = checkpoint.mounts.clone();
+        self
+    }
+
+    /// Directory the checkpoint (and `install.sh` storage state) is written to.
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+
+    /// Write the storage plan to the log dir and return the environment that
+    /// hands it to `install.sh`: `ARCHTUI_STORAGE_PLAN`, and `ARCHTUI_BIN`
+    /// to run `apply-storage` with.
+    ///
+    /// Empty when the configuration is not partitioned from a plan
+    /// (interactive manual, pre-mounted).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration needs a plan but none could be
+    /// calculated, or the plan cannot be written.
+    pub fn storage_plan_env(&self) -> Result<Vec<(String, String)>> {
+        let plan = match &self.storage_plan {
+            Ok(Some(plan)) => plan,
+            Ok(None) => return Ok(Vec::new()),
+            Err(e) => bail!("Cannot plan the storage layout: {}", e),
+        };
+        fs::create_dir_all(&self.log_dir)
+            .with_context(|| format!("Failed to create {}", self.log_dir.display()))?;
+        let path = self.log_dir.join(STORAGE_PLAN_FILE_NAME);
+        let json =
+            serde_json::to_string_pretty(plan).context("Failed to serialize storage plan")?;
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+        let exe = std::env::current_exe().context("Cannot locate the archtui binary")?;
+        Ok(vec![
+            (
+                "ARCHTUI_STORAGE_PLAN".to_string(),
+                path.display().to_string(),
+            ),
+            ("ARCHTUI_BIN".to_string(), exe.display().to_string()),
+        ])
+    }
+
+    /// Write a checkpoint for the given stage history.
+    ///
+    /// Mounts are captured the first time the history shows partitioning
+    /// finished; from then on a copy is also written to the target.
+    pub(crate) fn write(
+        &mut self,
+        stage_history: &[(InstallStage, u64)],
+        failed_at: Option<InstallStage>,
+    ) {
+        let partitioned = stage_history
+            .iter()
+            .any(|&(stage, _)| is_resumable(stage) || stage == InstallStage::Completed);
+        if partitioned && self.mounts.is_empty() {
+            match fs::read_to_string("/proc/self/mounts") {
+                Ok(table) => self.mounts = parse_mounts(&table, &self.target_root),
+                Err(e) => tracing::warn!(error = %e, "Cannot record target mounts"),
+            }
+        }
+
+        let checkpoint = InstallCheckpoint {
+            version: CHECKPOINT_VERSION,
+            config_hash: self.config_hash.clone(),
+            stage_history: stage_history.to_vec(),
+            failed_at,
+            storage_ops: self.storage_ops.clone(),
+            mounts: self.mounts.clone(),
+        };
+
+        let mut dirs = vec![self.log_dir.clone()];
+        if partitioned {
+            dirs.push(self.target_root.join(TARGET_CHECKPOINT_SUBDIR));
+        }
+        for dir in dirs {
+            if let Err(e) = checkpoint.save_to(&dir) {
+                tracing::warn!(error = %e, "Failed to write install checkpoint");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install_state::InstallerContext;
+    use tempfile::TempDir;
+
+    fn failed_checkpoint(failed_at: InstallStage) -> InstallCheckpoint {
+        let mut history: Vec<(InstallStage, u64)> = InstallStage::all_stages()
+            .iter()
+            .copied()
+            .skip(1)
+            .take_while(|s| s.order() <= failed_at.order())
+            .zip(100..)
+            .collect();
+        history.push((InstallStage::Failed, 200));
+        InstallCheckpoint {
+            version: CHECKPOINT_VERSION,
+            config_hash: "0123456789abcdef".to_string(),
+            stage_history: history,
+            failed_at: Some(failed_at),
+            storage_ops: Vec::new(),
+            mounts: vec![
+                MountRecord {
+                    source: "/dev/sda3".to_string(),
+                    target: PathBuf::from("/mnt"),
+                    fstype: "ext4".to_string(),
+                },
+                MountRecord {
+                    source: "/dev/sda1".to_string(),
+                    target: PathBuf::from("/mnt/boot"),
+                    fstype: "vfat".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_resume_stage_after_partitioning() {
+        let checkpoint = failed_checkpoint(InstallStage::InstallingBaseSystem);
+        assert_eq!(
+            checkpoint.resume_stage().unwrap(),
+            InstallStage::InstallingBaseSystem
+        );
+
+        // test: failure in or before partitioning is not resumable
+        assert!(
+            failed_checkpoint(InstallStage::PartitioningDisk)
+                .resume_stage()
+                .is_err()
+        );
+        assert!(
+            failed_checkpoint(InstallStage::PreparingSystem)
+                .resume_stage()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_resume_stage_interrupted_and_completed() {
+        // test: killed without recording a failure resumes at the last stage entered
+        let mut checkpoint = failed_checkpoint(InstallStage::GeneratingFstab);
+        checkpoint.stage_history.pop();
+        checkpoint.failed_at = None;
+        assert_eq!(
+            checkpoint.resume_stage().unwrap(),
+            InstallStage::GeneratingFstab
+        );
+
+        checkpoint.stage_history = InstallStage::all_stages()[1..]
+            .iter()
+            .map(|&s| (s, 1))
+            .collect();
+        assert!(checkpoint.resume_stage().is_err());
+    }
+
+    #[test]
+    fn test_resume_context_reenters_failed_stage() {
+        let checkpoint = failed_checkpoint(InstallStage::InstallingBaseSystem);
+        let stage = checkpoint.resume_stage().unwrap();
+        let mut ctx = InstallerContext::resume_at(&checkpoint.stage_history, stage).unwrap();
+
+        assert_eq!(ctx.current_stage(), InstallStage::PartitioningDisk);
+        assert!(ctx.is_destructive_confirmed());
+        assert_eq!(ctx.stage_history().len(), 4);
+        assert!(
+            ctx.transition_to(InstallStage::InstallingBaseSystem)
+                .is_ok()
+        );
+
+        // test: a history with a gap is rejected
+        let gapped = vec![
+            (InstallStage::ValidatingConfig, 1),
+            (InstallStage::InstallingDependencies, 2),
+        ];
+        assert!(InstallerContext::resume_at(&gapped, InstallStage::PartitioningDisk).is_err());
+    }
+
+    #[test]
+    fn test_verify_mounts() {
+        let checkpoint = failed_checkpoint(InstallStage::InstallingBaseSystem);
+        let table = "proc /proc proc rw 0 0\n\
+                     /dev/sda3 /mnt ext4 rw,relatime 0 0\n\
+                     /dev/sda1 /mnt/boot vfat rw 0 0\n";
+        assert!(
+            checkpoint
+                .verify_mounts_with(table, Path::new("/mnt"))
+                .is_ok()
+        );
+
+        // test: boot no longer mounted
+        let table = "/dev/sda3 /mnt ext4 rw,relatime 0 0\n";
+        let err = checkpoint
+            .verify_mounts_with(table, Path::new("/mnt"))
+            .unwrap_err();
+        assert!(err.to_string().contains("/dev/sda1 on /mnt/boot"));
+
+        // test: same mountpoint, different device
+        let table = "/dev/sdb3 /mnt ext4 rw 0 0\n/dev/sda1 /mnt/boot vfat rw 0 0\n";
+        assert!(
+            checkpoint
+                .verify_mounts_with(table, Path::new("/mnt"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_mounts_unescapes_and_filters() {
+        let table = "/dev/sda3 /mnt ext4 rw 0 0\n\
+                     /dev/sdb1 /mnt/data\\040disk ext4 rw 0 0\n\
+                     /dev/sdc1 /mntx ext4 rw 0 0\n";
+        let mounts = parse_mounts(table, Path::new("/mnt"));
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[1].target, PathBuf::from("/mnt/data disk"));
+    }
+
+    #[test]
+    fn test_config_hash_ignores_passwords() {
+        let mut config = InstallationConfig::new();
+        let hash = config_hash(&config);
+        config.root_password = "secret".to_string();
+        assert_eq!(config_hash(&config), hash);
+
+        config.hostname = "other-host".to_string();
+        assert_ne!(config_hash(&config), hash);
+        assert_eq!(hash.len(), 16);
+    }
+
+    #[test]
+    fn test_writer_persists_transitions() {
+        let dir = TempDir::new().unwrap();
+        let log_dir = dir.path().join("log");
+        let target = dir.path().join("target");
+        let writer = CheckpointWriter::with_paths(
+            "0123456789abcdef".to_string(),
+            vec!["WipeDisk(/dev/sda)".to_string()],
+            log_dir.clone(),
+            target.clone(),
+        );
+
+        let mut ctx = InstallerContext::new();
+        ctx.confirm_destructive_operations();
+        ctx.enable_checkpoints(writer);
+        for _ in 0..4 {
+            ctx.advance().unwrap();
+        }
+        let saved = InstallCheckpoint::load(&log_dir.join(CHECKPOINT_FILE_NAME)).unwrap();
+        assert_eq!(saved.stage_history.len(), 4);
+        assert_eq!(saved.storage_ops, vec!["WipeDisk(/dev/sda)".to_string()]);
+        // test: no target copy until partitioning finished
+        assert!(!target.join(TARGET_CHECKPOINT_SUBDIR).exists());
+
+        ctx.advance().unwrap();
+        ctx.fail().unwrap();
+        let saved = InstallCheckpoint::load(&log_dir.join(CHECKPOINT_FILE_NAME)).unwrap();
+        assert_eq!(saved.failed_at, Some(InstallStage::InstallingBaseSystem));
+        assert_eq!(
+            saved.resume_stage().unwrap(),
+            InstallStage::InstallingBaseSystem
+        );
+        let on_target = target
+            .join(TARGET_CHECKPOINT_SUBDIR)
+            .join(CHECKPOINT_FILE_NAME);
+        assert_eq!(InstallCheckpoint::load(&on_target).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_storage_plan_env_writes_the_plan() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = CheckpointWriter::with_paths(
+            "0123456789abcdef".to_string(),
+            Vec::new(),
+            dir.path().to_path_buf(),
+            dir.path().join("target"),
+        );
+        // test: nothing to hand over without a plan
+        assert!(writer.storage_plan_env().unwrap().is_empty());
+
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.boot_mode = crate::types::BootMode::Uefi;
+        let plan = storage::calculate_storage_plan(&config).unwrap();
+        writer.storage_plan = Ok(Some(plan.clone()));
+        let env = writer.storage_plan_env().unwrap();
+        let path = &env
+            .iter()
+            .find(|(key, _)| key == "ARCHTUI_STORAGE_PLAN")
+            .unwrap()
+            .1;
+        assert_eq!(load_storage_plan(Path::new(path)).unwrap().ops, plan.ops);
+        assert!(env.iter().any(|(key, _)| key == "ARCHTUI_BIN"));
+
+        writer.storage_plan = Err("no disk".to_string());
+        assert!(writer.storage_plan_env().is_err());
+    }
+
+    fn script_output(success: bool) -> ScriptOutput {
+        ScriptOutput {
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: Some(i32::from(!success)),
+            success,
+            dry_run: false,
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_teardown_applied_storage_releases_recorded_ops() {
+        let dir = TempDir::new().unwrap();
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.boot_mode = crate::types::BootMode::Uefi;
+        config.partitioning_strategy = crate::types::PartitionScheme::AutoSimpleLuks;
+        let plan = storage::calculate_storage_plan(&config).unwrap();
+        let applied = plan.ops.len() - 1;
+        let record = AppliedStorage {
+            plan: plan.clone(),
+            applied,
+        };
+        record.save(dir.path()).unwrap();
+        let loaded = AppliedStorage::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.applied, applied);
+        assert_eq!(loaded.teardown(), plan.teardown(applied));
+
+        // test: a resumable failure keeps the target for --resume
+        let steps =
+            teardown_applied_storage(dir.path(), InstallStage::InstallingBaseSystem, |_| {
+                panic!("no teardown for a resumable stage")
+            })
+            .unwrap();
+        assert_eq!(steps, 0);
+
+        // test: a failed teardown keeps the record for Disk Tools cleanup
+        assert!(
+            teardown_applied_storage(dir.path(), InstallStage::PartitioningDisk, |_| {
+                Ok(script_output(false))
+            })
+            .is_err()
+        );
+        assert_eq!(
+            AppliedStorage::load(dir.path()).unwrap().unwrap().applied,
+            applied
+        );
+
+        let mut ran = Vec::new();
+        let steps = teardown_applied_storage(dir.path(), InstallStage::PartitioningDisk, |args| {
+            ran = args.ops.clone();
+            Ok(script_output(true))
+        })
+        .unwrap();
+        assert_eq!(ran, plan.teardown(applied));
+        assert_eq!(steps, ran.len());
+        assert_eq!(
+            AppliedStorage::load(dir.path()).unwrap().unwrap().applied,
+            0
+        );
+    }
+}
@@ -35,11 +35,35 @@
 //!
 //! (Any stage can transition to Failed)
 //! ```
+//!
+//! # Stage Events
+//!
+//! `install.sh` reports progress with tagged lines on stdout, emitted by
+//! `emit_stage`/`emit_step` in `scripts/utils.sh`:
+//!
+//! ```text
+//! @@ARCHTUI stage partitioning_disk
+//! @@ARCHTUI step 2/5 Configuring bootloader
+//! ```
+//!
+//! `StageEvent::parse` turns these into events and `InstallerContext::apply`
+//! feeds them through the validated transitions, so progress, the failed
+//! stage and stage timing all come from the state machine.
+//!
+//! # Checkpoints
+//!
+//! With `enable_checkpoints`, every transition is persisted through a
+//! `CheckpointWriter` (see `install_checkpoint`). `resume_at` rebuilds a
+//! context from a saved history so a failed install re-enters the pipeline
+//! at the failed stage.
 
-// Library API - these types are exported for external use but not yet consumed by the binary
+// Library API - some helpers (reset, refresh_network) are not yet consumed by the binary
 #![allow(dead_code)]
 
 use crate::hardware::{FirmwareMode, HardwareInfo, NetworkState};
+use crate::install_checkpoint::CheckpointWriter;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use thiserror::Error;
 
@@ -138,6 +162,28 @@ impl InstallStage {
         }
     }
 
+    /// Returns the stable identifier used in `@@ARCHTUI stage <id>` events
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::NotStarted => "not_started",
+            Self::ValidatingConfig => "validating_config",
+            Self::PreparingSystem => "preparing_system",
+            Self::InstallingDependencies => "installing_dependencies",
+            Self::PartitioningDisk => "partitioning_disk",
+            Self::InstallingBaseSystem => "installing_base_system",
+            Self::GeneratingFstab => "generating_fstab",
+            Self::ConfiguringChroot => "configuring_chroot",
+            Self::Finalizing => "finalizing",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Looks up a stage by its `id()` (Failed cannot be entered by id)
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all_stages().iter().copied().find(|s| s.id() == id)
+    }
+
     /// Returns a human-readable description of this stage
     pub const fn description(self) -> &'static str {
         match self {
@@ -195,6 +241,24 @@ impl fmt::Display for InstallStage {
     }
 }
 
+// Stages are stored by `id()` in checkpoint files
+impl Serialize for InstallStage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for InstallStage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        if id == Self::Failed.id() {
+            return Ok(Self::Failed);
+        }
+        Self::from_id(&id)
+            .ok_or_else(|| D::Error::custom(format!("unknown install stage '{}'", id)))
+    }
+}
+
 /// Errors that can occur during state transitions
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum InstallTransitionError {
@@ -225,6 +289,56 @@ pub enum InstallTransitionError {
     AlreadyAtStage { stage: InstallStage },
 }
 
+/// Prefix of the tagged stage-event lines written by the installer script.
+pub const STAGE_EVENT_PREFIX: &str = "@@ARCHTUI ";
+
+/// Progress within a stage, reported as step `index` of `total`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageStep {
+    /// 1-based position of this step within the stage
+    pub index: u8,
+    /// Number of steps the stage is split into
+    pub total: u8,
+    /// What the step is doing, shown as the status line
+    pub description: String,
+}
+
+/// A structured progress event emitted by `install.sh`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageEvent {
+    /// `@@ARCHTUI stage <id>` — the script entered the next stage
+    Stage(InstallStage),
+    /// `@@ARCHTUI step <index>/<total> <description>` — progress within the current stage
+    Step(StageStep),
+}
+
+impl StageEvent {
+    /// Parse a line of installer output.
+    ///
+    /// Returns `None` for ordinary output and for malformed tags.
+    pub fn parse(line: &str) -> Option<Self> {
+        let event = line.trim().strip_prefix(STAGE_EVENT_PREFIX)?;
+        let (kind, rest) = event.split_once(' ').unwrap_or((event, ""));
+        match kind {
+            "stage" => InstallStage::from_id(rest.trim()).map(Self::Stage),
+            "step" => {
+                let (counter, description) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (index, total) = counter.split_once('/')?;
+                let (index, total) = (index.parse::<u8>().ok()?, total.parse::<u8>().ok()?);
+                if index == 0 || index > total {
+                    return None;
+                }
+                Some(Self::Step(StageStep {
+                    index,
+                    total,
+                    description: description.trim().to_string(),
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Context for tracking installation state.
 ///
 /// This struct owns the current installation stage and provides validated
@@ -261,11 +375,17 @@ pub struct InstallerContext {
     /// Whether destructive operations have been confirmed
     destructive_confirmed: bool,
 
+    /// Latest step reported within the current stage (cleared on transition)
+    current_step: Option<StageStep>,
+
     /// Detected firmware mode (UEFI or BIOS) — set once at startup
     firmware_mode: FirmwareMode,
 
     /// Detected network connectivity — set at startup, can be refreshed
     network_state: NetworkState,
+
+    /// Persists a checkpoint after each transition (see `enable_checkpoints`)
+    checkpoint: Option<CheckpointWriter>,
 }
 
 impl Default for InstallerContext {
@@ -285,8 +405,10 @@ impl InstallerContext {
             failed_at: None,
             stage_history: Vec::with_capacity(InstallStage::all_stages().len()),
             destructive_confirmed: false,
+            current_step: None,
             firmware_mode: FirmwareMode::Bios,
             network_state: NetworkState::Offline,
+            checkpoint: None,
         }
     }
 
@@ -301,11 +423,56 @@ impl InstallerContext {
             failed_at: None,
             stage_history: Vec::with_capacity(InstallStage::all_stages().len()),
             destructive_confirmed: false,
+            current_step: None,
             firmware_mode: hw.firmware,
             network_state: hw.network,
+            checkpoint: None,
         }
     }
 
+    /// Rebuild a context from a checkpointed stage history, positioned just
+    /// before `stage` so the resumed script's `stage` event is a valid transition.
+    ///
+    /// History entries from `stage` onwards (including `Failed`) are dropped.
+    /// Destructive operations count as confirmed once partitioning has finished.
+    ///
+    /// # Errors
+    ///
+    /// - `SkippedStage` if the history is not a contiguous chain leading up to `stage`
+    pub fn resume_at(
+        history: &[(InstallStage, u64)],
+        stage: InstallStage,
+    ) -> Result<Self, InstallTransitionError> {
+        let mut ctx = Self::new();
+        let completed = history
+            .iter()
+            .take_while(|(s, _)| s.order() < stage.order());
+        for &(recorded, timestamp) in completed {
+            if ctx.current.next() != Some(recorded) {
+                return Err(InstallTransitionError::SkippedStage {
+                    from: ctx.current,
+                    to: recorded,
+                });
+            }
+            ctx.stage_history.push((recorded, timestamp));
+            ctx.current = recorded;
+        }
+        if ctx.current.next() != Some(stage) {
+            return Err(InstallTransitionError::SkippedStage {
+                from: ctx.current,
+                to: stage,
+            });
+        }
+        ctx.destructive_confirmed = ctx.current.order() >= InstallStage::PartitioningDisk.order();
+        Ok(ctx)
+    }
+
+    /// Persist a checkpoint now and after every later transition.
+    pub fn enable_checkpoints(&mut self, writer: CheckpointWriter) {
+        self.checkpoint = Some(writer);
+        self.save_checkpoint();
+    }
+
     /// Returns the detected firmware mode (UEFI or BIOS).
     #[inline]
     pub fn firmware_mode(&self) -> FirmwareMode {
@@ -355,9 +522,32 @@ impl InstallerContext {
     }
 
     /// Returns the current progress percentage (0-100)
-    #[inline]
+    ///
+    /// Steps within a stage move the percentage towards the next stage's.
     pub fn progress_percent(&self) -> u8 {
-        self.current.progress_percent()
+        let base = self.current.progress_percent();
+        match (&self.current_step, self.current.next()) {
+            (Some(step), Some(next)) => {
+                let span = u16::from(next.progress_percent() - base);
+                let total = u16::from(step.total.max(1));
+                let done = u16::from(step.index.saturating_sub(1)).min(total);
+                base + (span * done / total) as u8
+            }
+            _ => base,
+        }
+    }
+
+    /// Returns the latest step reported within the current stage
+    #[inline]
+    pub fn current_step(&self) -> Option<&StageStep> {
+        self.current_step.as_ref()
+    }
+
+    /// Returns a one-line status: the current step, or the stage description
+    pub fn status_text(&self) -> &str {
+        self.current_step
+            .as_ref()
+            .map_or(self.current.description(), |step| step.description.as_str())
     }
 
     /// Returns the stage history as a slice of (stage, timestamp) pairs
@@ -365,6 +555,17 @@ impl InstallerContext {
         &self.stage_history
     }
 
+    /// Returns how long (seconds) each finished stage took, in order.
+    ///
+    /// A stage is finished once the next transition (including `Failed`)
+    /// was recorded, so the failed stage is included with its time until failure.
+    pub fn stage_durations(&self) -> Vec<(InstallStage, u64)> {
+        self.stage_history
+            .windows(2)
+            .map(|pair| (pair[0].0, pair[1].1.saturating_sub(pair[0].1)))
+            .collect()
+    }
+
     /// Confirm that destructive operations are authorized.
     ///
     /// This must be called before transitioning to `PartitioningDisk`.
@@ -406,6 +607,7 @@ impl InstallerContext {
         // Record transition
         self.record_stage_transition(next_stage);
         self.current = next_stage;
+        self.save_checkpoint();
 
         Ok(next_stage)
     }
@@ -469,10 +671,34 @@ impl InstallerContext {
         // Valid transition
         self.record_stage_transition(target);
         self.current = target;
+        self.save_checkpoint();
 
         Ok(target)
     }
 
+    /// Apply a stage event reported by the installer script.
+    ///
+    /// Re-announcing the current stage is a no-op: install.sh always opens with
+    /// `validating_config`, which the pre-flight may already have entered.
+    ///
+    /// # Errors
+    ///
+    /// - Any `transition_to` error for `StageEvent::Stage`
+    /// - `FromTerminalState` for a step reported after Completed or Failed
+    pub fn apply(&mut self, event: StageEvent) -> Result<(), InstallTransitionError> {
+        match event {
+            StageEvent::Stage(stage) if stage == self.current && !stage.is_terminal() => Ok(()),
+            StageEvent::Stage(stage) => self.transition_to(stage).map(|_| ()),
+            StageEvent::Step(_) if self.current.is_terminal() => {
+                Err(InstallTransitionError::FromTerminalState { from: self.current })
+            }
+            StageEvent::Step(step) => {
+                self.current_step = Some(step);
+                Ok(())
+            }
+        }
+    }
+
     /// Mark the installation as failed.
     ///
     /// This can be called from any non-terminal state and records which stage
@@ -489,10 +715,18 @@ impl InstallerContext {
         self.failed_at = Some(self.current);
         self.record_stage_transition(InstallStage::Failed);
         self.current = InstallStage::Failed;
+        self.save_checkpoint();
 
         Ok(())
     }
 
+    /// Write the current history through the checkpoint writer, if enabled
+    fn save_checkpoint(&mut self) {
+        if let Some(writer) = self.checkpoint.as_mut() {
+            writer.write(&self.stage_history, self.failed_at);
+        }
+    }
+
     /// Record a stage transition in the history
     fn record_stage_transition(&mut self, stage: InstallStage) {
         // Use monotonic-ish timestamp (seconds since UNIX_EPOCH)
@@ -503,6 +737,7 @@ impl InstallerContext {
             .unwrap_or(0); // Fallback to 0 if system time is before epoch (shouldn't happen)
 
         self.stage_history.push((stage, timestamp));
+        self.current_step = None;
     }
 
     /// Reset the context to NotStarted state.
@@ -513,6 +748,7 @@ impl InstallerContext {
         self.failed_at = None;
         self.stage_history.clear();
         self.destructive_confirmed = false;
+        self.current_step = None;
     }
 }
 
@@ -865,6 +1101,119 @@ mod tests {
         assert!(matches!(err, InstallTransitionError::SkippedStage { .. }));
     }
 
+    // =========================================================================
+    // Stage Event Tests
+    // =========================================================================
+
+    #[test]
+    fn test_stage_ids_round_trip() {
+        for stage in InstallStage::all_stages() {
+            assert_eq!(InstallStage::from_id(stage.id()), Some(*stage));
+        }
+        assert_eq!(InstallStage::from_id("failed"), None);
+        assert_eq!(InstallStage::from_id("Partitioning disk"), None);
+    }
+
+    #[test]
+    fn test_parse_stage_events() {
+        assert_eq!(
+            StageEvent::parse("@@ARCHTUI stage partitioning_disk"),
+            Some(StageEvent::Stage(InstallStage::PartitioningDisk))
+        );
+        assert_eq!(
+            StageEvent::parse("  @@ARCHTUI step 2/5 Configuring bootloader"),
+            Some(StageEvent::Step(StageStep {
+                index: 2,
+                total: 5,
+                description: "Configuring bootloader".to_string(),
+            }))
+        );
+
+        // test: ordinary output and malformed tags are not events
+        for line in [
+            "[INFO] Phase 4: Partitioning disk...",
+            "PROGRESS: Configuring bootloader",
+            "@@ARCHTUI stage bogus",
+            "@@ARCHTUI step 6/5 Too far",
+            "@@ARCHTUI step 0/5 Zero",
+            "@@ARCHTUI step two/five Words",
+            "@@ARCHTUI finish",
+        ] {
+            assert_eq!(StageEvent::parse(line), None, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_apply_drives_progress_and_status() {
+        let mut ctx = InstallerContext::new();
+        ctx.confirm_destructive_operations();
+        for stage in &InstallStage::all_stages()[1..=7] {
+            ctx.apply(StageEvent::Stage(*stage)).expect("next stage"); // test: known-good input
+        }
+        assert_eq!(ctx.progress_percent(), 75);
+        assert_eq!(ctx.status_text(), "Configuring system");
+
+        let step = |index, description: &str| {
+            StageEvent::Step(StageStep {
+                index,
+                total: 5,
+                description: description.to_string(),
+            })
+        };
+        ctx.apply(step(3, "Installing desktop environment"))
+            .expect("step within stage"); // test: known-good input
+        assert_eq!(ctx.progress_percent(), 83);
+        assert_eq!(ctx.status_text(), "Installing desktop environment");
+
+        // Entering the next stage clears the step
+        ctx.apply(StageEvent::Stage(InstallStage::Finalizing))
+            .expect("next stage"); // test: known-good input
+        assert_eq!(ctx.progress_percent(), 95);
+        assert!(ctx.current_step().is_none());
+
+        // test: out-of-order stage events are rejected, not applied
+        assert!(
+            ctx.apply(StageEvent::Stage(InstallStage::PartitioningDisk))
+                .is_err()
+        );
+        assert_eq!(ctx.current_stage(), InstallStage::Finalizing);
+    }
+
+    #[test]
+    fn test_apply_ignores_reannounced_stage() {
+        let mut ctx = InstallerContext::new();
+        ctx.transition_to(InstallStage::ValidatingConfig)
+            .expect("pre-flight stage"); // test: known-good input
+        ctx.apply(StageEvent::Stage(InstallStage::ValidatingConfig))
+            .expect("re-announced stage"); // test: known-good input
+        assert_eq!(ctx.stage_history().len(), 1);
+        ctx.apply(StageEvent::Stage(InstallStage::PreparingSystem))
+            .expect("next stage"); // test: known-good input
+        assert_eq!(ctx.current_stage(), InstallStage::PreparingSystem);
+    }
+
+    #[test]
+    fn test_stage_durations_include_failed_stage() {
+        let mut ctx = InstallerContext::new();
+        ctx.advance().expect("validating"); // test: known-good input
+        ctx.advance().expect("preparing"); // test: known-good input
+        ctx.fail().expect("fail from preparing"); // test: known-good input
+
+        let stages: Vec<InstallStage> = ctx.stage_durations().iter().map(|(s, _)| *s).collect();
+        assert_eq!(
+            stages,
+            vec![
+                InstallStage::ValidatingConfig,
+                InstallStage::PreparingSystem
+            ]
+        );
+        assert_eq!(ctx.failed_at(), Some(InstallStage::PreparingSystem));
+        assert!(
+            ctx.apply(StageEvent::Stage(InstallStage::Finalizing))
+                .is_err()
+        );
+    }
+
     // =========================================================================
     // Error Display Tests
     // =========================================================================
@@ -19,6 +19,12 @@
 
 use crate::app::AppState;
 use crate::config::Configuration;
+use crate::install_checkpoint::{self, CheckpointWriter};
+use crate::install_state::{InstallStage, InstallerContext, StageEvent};
+use crate::logic::availability::AvailabilityReport;
+#[cfg(feature = "alpm")]
+use crate::package_events::QuestionPolicy;
+use crate::package_events::{PackageEvent, PackageProgress, PacmanOutputParser};
 #[cfg(feature = "alpm")]
 use crate::package_manager::PackageManager;
 use crate::process_guard::{ChildRegistry, CommandProcessGroup};
@@ -35,6 +41,7 @@ use crate::scripts::network::{CheckConnectivityArgs, MirrorSortMethod, UpdateMir
 use crate::scripts::profiles::EnableServicesArgs;
 use crate::scripts::profiles::InstallDotfilesArgs;
 use crate::types::Filesystem;
+use crate::watchdog::{ActivityClock, Watchdog, WatchdogLimits};
 use anyhow::{Context, Result};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
@@ -42,6 +49,7 @@ use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
@@ -141,18 +149,84 @@ fn write_master_log(log_file: &Arc<Mutex<Option<File>>>, line: &str) {
     }
 }
 
+/// Feed a stage event from `install.sh` into the state machine, mirroring the
+/// result into the status line and the master log.
+fn apply_stage_event(state: &mut AppState, event: StageEvent, log_file: &Arc<Mutex<Option<File>>>) {
+    match state.install_context.apply(event) {
+        Ok(()) => {
+            let ctx = &state.install_context;
+            state.status_message = ctx.status_text().to_string();
+            write_master_log(
+                log_file,
+                &format!(
+                    "[RUST] Progress: {}% - {}",
+                    ctx.progress_percent(),
+                    ctx.status_text()
+                ),
+            );
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Ignoring out-of-order stage event");
+            write_master_log(log_file, &format!("[RUST] Ignoring stage event: {}", e));
+        }
+    }
+}
+
+/// Mirror package transaction events into `AppState::package_progress` for
+/// the installation screen. Answered questions also go to the output pane.
+///
+/// Fed from install.sh's pacman output by `stream_stdout`; an ALPM install
+/// hands the sender to `PackageManager::with_events`. The thread ends once
+/// the last sender is dropped.
+pub fn forward_package_events(
+    app_state: Arc<Mutex<AppState>>,
+) -> (Sender<PackageEvent>, thread::JoinHandle<()>) {
+    let (events, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        for event in receiver {
+            // SAFETY: poison recovery via into_inner — never panic on mutex
+            let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+            if let PackageEvent::Question { question, answer } = &event {
+                let answer = match answer {
+                    Some(true) => "yes",
+                    Some(false) => "no",
+                    None => "default",
+                };
+                state
+                    .installer_output
+                    .push(format!("[ALPM] {} -> {}", question, answer));
+            }
+            state
+                .package_progress
+                .get_or_insert_with(PackageProgress::default)
+                .apply(&event);
+        }
+    });
+    (events, handle)
+}
+
+/// Package availability pre-flight run by `Installer::start`, usually
+/// `logic::availability::check_install_packages` for the install's config.
+pub type PackageCheck = Box<dyn FnOnce() -> Result<AvailabilityReport> + Send>;
+
 /// Installer instance
 pub struct Installer {
     env_vars: std::collections::HashMap<String, String>,
     app_state: Arc<Mutex<AppState>>,
+    checkpoint: CheckpointWriter,
+    package_check: Option<PackageCheck>,
 }
 
 impl Installer {
     /// Create a new installer from TUI configuration
     pub fn new(config: Configuration, app_state: Arc<Mutex<AppState>>) -> Self {
+        let checkpoint =
+            CheckpointWriter::new(&crate::config_file::InstallationConfig::from(&config));
         Self {
             env_vars: config.to_env_vars(),
             app_state,
+            checkpoint,
+            package_check: None,
         }
     }
 
@@ -166,9 +240,25 @@ impl Installer {
         Self {
             env_vars,
             app_state,
+            checkpoint: CheckpointWriter::new(config),
+            package_check: None,
         }
     }
 
+    /// Add environment variables on top of the configuration's
+    /// (e.g. the offline repository from `logic::offline`)
+    pub fn with_env_vars(mut self, vars: Vec<(String, String)>) -> Self {
+        self.env_vars.extend(vars);
+        self
+    }
+
+    /// Run `check` in `InstallStage::ValidatingConfig` before install.sh is
+    /// spawned; blocking issues fail the install before the disk is touched
+    pub fn with_package_check(mut self, check: PackageCheck) -> Self {
+        self.package_check = Some(check);
+        self
+    }
+
     /// Start the installation process
     pub fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Update app state to installation mode
@@ -177,7 +267,13 @@ impl Installer {
             let mut state = self.app_state.lock().unwrap_or_else(|e| e.into_inner());
             state.mode = crate::app::AppMode::Installation;
             state.status_message = "Starting installation...".to_string();
-            state.installation_progress = 10;
+            state.install_context = InstallerContext::new();
+            state.package_progress = None;
+            // The wipe was confirmed in the TUI before the installer was started
+            state.install_context.confirm_destructive_operations();
+            state
+                .install_context
+                .enable_checkpoints(self.checkpoint.clone());
 
             // Add initial debug output
             state
@@ -197,6 +293,10 @@ impl Installer {
         // Pass LOG_LEVEL to child process (ARCHTUI_LOG_LEVEL env var → LOG_LEVEL in bash)
         let log_level = std::env::var("ARCHTUI_LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string());
         env_vars.insert("LOG_LEVEL".to_string(), log_level.clone());
+        env_vars.insert(
+            "ARCHTUI_CHECKPOINT_DIR".to_string(),
+            self.checkpoint.log_dir().to_string_lossy().to_string(),
+        );
 
         // Inject manual partition assignments (if set)
         {
@@ -245,299 +345,406 @@ impl Installer {
             }
         }
 
-        // --- Master Log File ---
-        // Create a persistent log that captures everything the TUI sees (and more),
-        // surviving the 500-line ringbuffer cap. ANSI-stripped, timestamped.
-        let master_log: Arc<Mutex<Option<File>>> = {
-            let log_dir = crate::script_runner::log_dir();
-            let log_dir = log_dir.to_string_lossy().to_string();
-            let _ = fs::create_dir_all(&log_dir);
-            let timestamp = now_hms().replace(':', "");
-            let log_path = format!("{}/install-{}-master.log", log_dir, timestamp);
-            match OpenOptions::new().create(true).append(true).open(&log_path) {
-                Ok(mut f) => {
-                    // Write header block
-                    let _ = writeln!(f, "=== ArchTUI Master Installation Log ===");
-                    let _ = writeln!(f, "[{}] Log level: {}", now_hms(), log_level);
-                    let _ = writeln!(f, "[{}] === Environment Variables ===", now_hms());
-                    for (k, v) in &env_vars {
-                        let display_val = if k.contains("PASSWORD") {
-                            "********"
-                        } else {
-                            v.as_str()
-                        };
-                        let _ = writeln!(f, "[{}]   {}={}", now_hms(), k, display_val);
-                    }
-                    let _ = writeln!(f, "[{}] === End Environment Variables ===", now_hms());
-                    let _ = writeln!(f);
-                    Arc::new(Mutex::new(Some(f)))
-                }
-                Err(_) => Arc::new(Mutex::new(None)),
-            }
+        // partition_disk executes the storage plan through `archtui apply-storage`
+        let checkpoint = self.checkpoint.clone();
+        let launch = move |mut env_vars: std::collections::HashMap<String, String>,
+                           app_state: Arc<Mutex<AppState>>|
+              -> Result<()> {
+            env_vars.extend(checkpoint.storage_plan_env()?);
+            spawn_install_script(env_vars, &log_level, app_state)
+        };
+
+        let app_state = Arc::clone(&self.app_state);
+        let Some(check) = self.package_check.take() else {
+            return Ok(launch(env_vars, app_state)?);
         };
 
-        // Determine script path - use wrapper for TUI-friendly output
-        let script_path = crate::script_runner::scripts_base_dir()
-            .join("install_wrapper.sh")
-            .to_string_lossy()
-            .to_string();
-
-        // Launch the installation script
-        // stdin is null - scripts are non-interactive per lint rules
-        let mut child = Command::new("/bin/bash")
-            .arg(&script_path)
-            .envs(&env_vars)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .stdin(Stdio::null())
-            .in_new_process_group()
-            .spawn()
-            .context("Failed to spawn install wrapper script")?;
-
-        // Register child PID for Death Pact compliance and cancellation
-        let child_pid = child.id();
+        // The pre-flight fetches the sync databases: keep it off the TUI thread
+        thread::spawn(move || {
+            if !run_package_check(&app_state, check) {
+                return;
+            }
+            if let Err(e) = launch(env_vars, Arc::clone(&app_state)) {
+                tracing::error!(error = %e, "Failed to start the installer script");
+                // SAFETY: poison recovery via into_inner — never panic on mutex
+                let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+                let _ = state.install_context.fail();
+                state.installer_output.push(format!("ERROR: {:#}", e));
+                state.status_message = format!("Installation error: {:#}", e);
+                state.mode = crate::app::AppMode::Complete;
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Run the package availability pre-flight as `InstallStage::ValidatingConfig`,
+/// mirroring the report into the output pane. Returns whether the install
+/// may go ahead.
+///
+/// A check that cannot run (no network, no `alpm` feature) is logged and
+/// does not block; install.sh runs its own network pre-flight.
+fn run_package_check(app_state: &Arc<Mutex<AppState>>, check: PackageCheck) -> bool {
+    {
+        // SAFETY: poison recovery via into_inner — never panic on mutex
+        let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = state
+            .install_context
+            .transition_to(InstallStage::ValidatingConfig)
         {
+            tracing::warn!(error = %e, "Package check outside ValidatingConfig");
+        }
+        state.status_message = "Checking package availability...".to_string();
+        state
+            .installer_output
+            .push("Checking package availability against the sync databases...".to_string());
+    }
+
+    // The lock is not held while the databases are fetched
+    let result = check();
+
+    // SAFETY: poison recovery via into_inner — never panic on mutex
+    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::warn!(error = %e, "Package availability not checked");
+            state
+                .installer_output
+                .push(format!("Package availability not checked: {:#}", e));
+            return true;
+        }
+    };
+    for line in report.to_lines() {
+        tracing::info!("Package availability: {}", line);
+        state.installer_output.push(line);
+    }
+    match report.ensure_ok("the sync databases") {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::error!(error = %e, "Package availability check failed");
+            let _ = state.install_context.fail();
+            state.status_message = format!("Install not possible: {:#}", e);
+            state.installer_output.push(format!("ERROR: {:#}", e));
+            state.mode = crate::app::AppMode::Complete;
+            false
+        }
+    }
+}
+
+/// Stream install.sh's stdout into `app_state`: stage events drive the state
+/// machine, pacstrap and pacman output drives `package_progress` through
+/// `forward_package_events`, and every other line goes to the output pane.
+fn stream_stdout(
+    reader: impl BufRead,
+    app_state: &Arc<Mutex<AppState>>,
+    log_file: &Arc<Mutex<Option<File>>>,
+    clock: &ActivityClock,
+) {
+    let (events, forwarder) = forward_package_events(Arc::clone(app_state));
+    let mut pacman = PacmanOutputParser::default();
+
+    for line in reader.lines().map_while(Result::ok) {
+        clock.touch();
+        // Strip ANSI color codes and carriage returns before storing.
+        // Bash scripts emit these for terminal display, but ratatui renders
+        // them as visible garbage causing screen artifacts.
+        let clean_line = strip_ansi_and_cr(&line);
+
+        // Stage events drive progress; they are protocol, not output
+        if let Some(event) = StageEvent::parse(&clean_line) {
+            // A new stage means pacman's last transaction is over
+            if let Some(done) = pacman.finish() {
+                let _ = events.send(done);
+            }
             // SAFETY: poison recovery via into_inner — never panic on mutex
-            let mut state = self.app_state.lock().unwrap_or_else(|e| e.into_inner());
-            state.installer_pid = Some(child_pid);
+            let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+            apply_stage_event(&mut state, event, log_file);
+            continue;
         }
-        if let Ok(mut registry) = ChildRegistry::global().lock() {
-            registry.register(child_pid);
+        if let Some(event) = pacman.parse_line(&clean_line) {
+            let _ = events.send(event);
         }
 
-        // Handle stdout in separate thread
-        if let Some(stdout) = child.stdout.take() {
-            let app_state = Arc::clone(&self.app_state);
-            let log_file = Arc::clone(&master_log);
-
-            thread::spawn(move || {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines().map_while(Result::ok) {
-                    // Strip ANSI color codes and carriage returns before storing.
-                    // Bash scripts emit these for terminal display, but ratatui renders
-                    // them as visible garbage causing screen artifacts.
-                    let clean_line = strip_ansi_and_cr(&line);
-
-                    // Write to master log BEFORE the ringbuffer cap discards old lines
-                    write_master_log(&log_file, &clean_line);
-
-                    // SAFETY: poison recovery via into_inner — never panic on mutex
-                    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
-                    state.installer_output.push(clean_line);
-
-                    // Keep only last 500 lines
-                    if state.installer_output.len() > 500 {
-                        state.installer_output.remove(0);
-                    }
+        // SAFETY: poison recovery via into_inner — never panic on mutex
+        let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Write to master log BEFORE the ringbuffer cap discards old lines
+        write_master_log(log_file, &clean_line);
+        state.installer_output.push(clean_line);
+
+        // Keep only last 500 lines
+        if state.installer_output.len() > 500 {
+            state.installer_output.remove(0);
+        }
+    }
+
+    if let Some(done) = pacman.finish() {
+        let _ = events.send(done);
+    }
+    drop(events);
+    let _ = forwarder.join();
+}
 
-                    // Update progress based on output content (match on raw line —
-                    // ANSI codes don't interfere with contains() substring matching)
-                    //
-                    // Phases 1-4 (fast) = 2%-18%, pacstrap = 22%-40%,
-                    // Phase 7 chroot (longest) = 48%-88% with 5 PROGRESS: sub-milestones,
-                    // Phase 8 + completion = 92%-100%
-                    let progress_msg = if line.contains("Starting Arch Linux installation") {
-                        state.installation_progress = 2;
-                        Some("Installation started")
-                    } else if line.contains("Phase 1:") {
-                        state.installation_progress = 5;
-                        Some("Validating configuration")
-                    } else if line.contains("Phase 2:") {
-                        state.installation_progress = 8;
-                        Some("Preparing system")
-                    } else if line.contains("Mirrors ranked") {
-                        state.installation_progress = 10;
-                        Some("Mirrors configured")
-                    } else if line.contains("Phase 3:") {
-                        state.installation_progress = 12;
-                        Some("Installing dependencies")
-                    } else if line.contains("Phase 4:") {
-                        state.installation_progress = 14;
-                        Some("Partitioning disk")
-                    } else if line.contains("Starting disk partitioning") {
-                        state.installation_progress = 16;
-                        Some("Partitioning disk")
-                    } else if line.contains("Disk partitioning complete") {
-                        state.installation_progress = 18;
-                        Some("Partitioning complete")
-                    } else if line.contains("Phase 5:") {
-                        state.installation_progress = 20;
-                        Some("Installing base system")
-                    } else if line.contains("Starting pacstrap") {
-                        state.installation_progress = 22;
-                        Some("Running pacstrap (this takes several minutes)")
-                    } else if line.contains("Base system installed") {
-                        state.installation_progress = 40;
-                        Some("Base system installed")
-                    } else if line.contains("Phase 6:") {
-                        state.installation_progress = 45;
-                        Some("Generating fstab")
-                    } else if line.contains("Phase 7:") {
-                        state.installation_progress = 48;
-                        Some("Configuring system in chroot")
-                    } else if line.contains("PROGRESS: Configuring base system") {
-                        state.installation_progress = 52;
-                        Some("Configuring base system")
-                    } else if line.contains("PROGRESS: Configuring bootloader") {
-                        state.installation_progress = 58;
-                        Some("Configuring bootloader")
-                    } else if line.contains("PROGRESS: Installing desktop environment") {
-                        state.installation_progress = 65;
-                        Some("Installing desktop environment")
-                    } else if line.contains("PROGRESS: Installing additional software") {
-                        state.installation_progress = 78;
-                        Some("Installing additional software")
-                    } else if line.contains("PROGRESS: Running final configuration") {
-                        state.installation_progress = 88;
-                        Some("Running final configuration")
-                    } else if line.contains("Phase 8:") {
-                        state.installation_progress = 92;
-                        Some("Finalizing installation")
-                    } else if line.contains("Installation complete") {
-                        state.installation_progress = 100;
-                        Some("Installation completed successfully!")
+/// Spawn `install_wrapper.sh` with `env_vars` and stream its output, stage
+/// events and exit status into `app_state`.
+fn spawn_install_script(
+    env_vars: std::collections::HashMap<String, String>,
+    log_level: &str,
+    app_state: Arc<Mutex<AppState>>,
+) -> Result<()> {
+    // --- Master Log File ---
+    // Create a persistent log that captures everything the TUI sees (and more),
+    // surviving the 500-line ringbuffer cap. ANSI-stripped, timestamped.
+    let master_log: Arc<Mutex<Option<File>>> = {
+        let log_dir = crate::script_runner::log_dir();
+        let log_dir = log_dir.to_string_lossy().to_string();
+        let _ = fs::create_dir_all(&log_dir);
+        let timestamp = now_hms().replace(':', "");
+        let log_path = format!("{}/install-{}-master.log", log_dir, timestamp);
+        match OpenOptions::new().create(true).append(true).open(&log_path) {
+            Ok(mut f) => {
+                // Write header block
+                let _ = writeln!(f, "=== ArchTUI Master Installation Log ===");
+                let _ = writeln!(f, "[{}] Log level: {}", now_hms(), log_level);
+                let _ = writeln!(f, "[{}] === Environment Variables ===", now_hms());
+                for (k, v) in &env_vars {
+                    let display_val = if k.contains("PASSWORD") {
+                        "********"
                     } else {
-                        None
+                        v.as_str()
                     };
-
-                    if let Some(msg) = progress_msg {
-                        state.status_message = msg.to_string();
-                        // Log Rust-side state events to master log
-                        write_master_log(
-                            &log_file,
-                            &format!(
-                                "[RUST] Progress: {}% - {}",
-                                state.installation_progress, msg
-                            ),
-                        );
-                    }
+                    let _ = writeln!(f, "[{}]   {}={}", now_hms(), k, display_val);
                 }
-            });
+                let _ = writeln!(f, "[{}] === End Environment Variables ===", now_hms());
+                let _ = writeln!(f);
+                Arc::new(Mutex::new(Some(f)))
+            }
+            Err(_) => Arc::new(Mutex::new(None)),
         }
+    };
 
-        // Handle stderr in separate thread
-        if let Some(stderr) = child.stderr.take() {
-            let app_state = Arc::clone(&self.app_state);
-            let log_file = Arc::clone(&master_log);
+    // Determine script path - use wrapper for TUI-friendly output
+    let script_path = crate::script_runner::scripts_base_dir()
+        .join("install_wrapper.sh")
+        .to_string_lossy()
+        .to_string();
+
+    // Launch the installation script
+    // stdin is null - scripts are non-interactive per lint rules
+    let mut child = Command::new("/bin/bash")
+        .arg(&script_path)
+        .envs(&env_vars)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null())
+        .in_new_process_group()
+        .spawn()
+        .context("Failed to spawn install wrapper script")?;
+
+    // Register child PID for Death Pact compliance and cancellation
+    let child_pid = child.id();
+    {
+        // SAFETY: poison recovery via into_inner — never panic on mutex
+        let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+        state.installer_pid = Some(child_pid);
+    }
+    if let Ok(mut registry) = ChildRegistry::global().lock() {
+        registry.register(child_pid);
+    }
 
-            thread::spawn(move || {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines().map_while(Result::ok) {
-                    let clean_line = strip_ansi_and_cr(&line);
+    // Hang detection: install.sh's manifest sets the idle-output limit
+    let clock = ActivityClock::new();
+    let limits = crate::script_runner::manifest_registry()
+        .get("scripts/install.sh")
+        .map(WatchdogLimits::from_manifest)
+        .unwrap_or_default();
+    let watchdog = Watchdog::spawn("install.sh", child_pid, limits, clock.clone());
+
+    // Handle stdout in separate thread
+    if let Some(stdout) = child.stdout.take() {
+        let app_state = Arc::clone(&app_state);
+        let log_file = Arc::clone(&master_log);
+        let clock = clock.clone();
+
+        thread::spawn(move || stream_stdout(BufReader::new(stdout), &app_state, &log_file, &clock));
+    }
 
-                    // Write stderr to master log with ERROR prefix
-                    write_master_log(&log_file, &format!("STDERR: {}", clean_line));
+    // Handle stderr in separate thread
+    if let Some(stderr) = child.stderr.take() {
+        let app_state = Arc::clone(&app_state);
+        let log_file = Arc::clone(&master_log);
 
-                    // SAFETY: poison recovery via into_inner — never panic on mutex
-                    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
-                    state
-                        .installer_output
-                        .push(format!("ERROR: {}", clean_line));
+        thread::spawn(move || {
+            let reader = BufReader::new(stderr);
+            for line in reader.lines().map_while(Result::ok) {
+                clock.touch();
+                let clean_line = strip_ansi_and_cr(&line);
+
+                // SAFETY: poison recovery via into_inner — never panic on mutex
+                let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+
+                // Stage events on stderr too (in case wrapper doesn't merge)
+                if let Some(event) = StageEvent::parse(&clean_line) {
+                    apply_stage_event(&mut state, event, &log_file);
+                    continue;
+                }
 
-                    // Keep only last 500 lines
-                    if state.installer_output.len() > 500 {
-                        state.installer_output.remove(0);
-                    }
+                // Write stderr to master log with ERROR prefix
+                write_master_log(&log_file, &format!("STDERR: {}", clean_line));
+                state
+                    .installer_output
+                    .push(format!("ERROR: {}", clean_line));
 
-                    // Check progress triggers on stderr too (in case wrapper doesn't merge)
-                    if line.contains("Phase 1:") {
-                        state.installation_progress = 5;
-                        state.status_message = "Validating configuration".to_string();
-                    } else if line.contains("Phase 2:") {
-                        state.installation_progress = 8;
-                        state.status_message = "Preparing system".to_string();
-                    } else if line.contains("Phase 3:") {
-                        state.installation_progress = 12;
-                        state.status_message = "Installing dependencies".to_string();
-                    } else if line.contains("Phase 4:") {
-                        state.installation_progress = 14;
-                        state.status_message = "Partitioning disk".to_string();
-                    } else if line.contains("Phase 5:") {
-                        state.installation_progress = 20;
-                        state.status_message = "Installing base system".to_string();
-                    } else if line.contains("Phase 6:") {
-                        state.installation_progress = 45;
-                        state.status_message = "Generating fstab".to_string();
-                    } else if line.contains("Phase 7:") {
-                        state.installation_progress = 48;
-                        state.status_message = "Configuring system in chroot".to_string();
-                    } else if line.contains("Phase 8:") {
-                        state.installation_progress = 92;
-                        state.status_message = "Finalizing installation".to_string();
-                    } else if line.contains("ERROR") || line.contains("FATAL") {
-                        state.status_message = format!("Error: {}", line);
-                    }
+                // Keep only last 500 lines
+                if state.installer_output.len() > 500 {
+                    state.installer_output.remove(0);
                 }
-            });
-        }
 
-        // Wait for installation completion in separate thread
-        let app_state = Arc::clone(&self.app_state);
-        let wait_log = Arc::clone(&master_log);
+                if line.contains("ERROR") || line.contains("FATAL") {
+                    state.status_message = format!("Error: {}", line);
+                }
+            }
+        });
+    }
 
-        thread::spawn(move || {
-            let result = child.wait();
+    // Wait for installation completion in separate thread
+    let app_state = Arc::clone(&app_state);
+    let wait_log = Arc::clone(&master_log);
 
-            // Unregister child PID (process has exited)
-            if let Ok(mut registry) = ChildRegistry::global().lock() {
-                registry.unregister(child_pid);
-            }
+    thread::spawn(move || {
+        let result = child.wait();
+        let timed_out = watchdog.finish();
 
-            match result {
-                Ok(status) => {
-                    // SAFETY: poison recovery via into_inner — never panic on mutex
-                    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
-                    state.installer_pid = None;
-
-                    if status.success() {
-                        state.installation_progress = 100;
-                        state.mode = crate::app::AppMode::Complete;
-                        state.status_message = "Installation completed successfully!".to_string();
-                        state
-                            .installer_output
-                            .push("Installation completed successfully!".to_string());
+        // Unregister child PID (process has exited)
+        if let Ok(mut registry) = ChildRegistry::global().lock() {
+            registry.unregister(child_pid);
+        }
+
+        let mut failed_at = None;
+        match result {
+            Ok(status) => {
+                // SAFETY: poison recovery via into_inner — never panic on mutex
+                let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.installer_pid = None;
+
+                if status.success() {
+                    // Close out stages the script never reported
+                    if !state.install_context.is_complete() {
                         write_master_log(
                             &wait_log,
-                            "[RUST] Installation completed successfully (exit code 0)",
+                            &format!(
+                                "[RUST] Installer exited 0 during '{}' without reporting completion",
+                                state.install_context.current_stage()
+                            ),
                         );
+                        let ctx = &mut state.install_context;
+                        while !ctx.is_complete() && ctx.advance().is_ok() {}
+                    }
+                    state.mode = crate::app::AppMode::Complete;
+                    state.status_message = "Installation completed successfully!".to_string();
+                    state
+                        .installer_output
+                        .push("Installation completed successfully!".to_string());
+                    write_master_log(
+                        &wait_log,
+                        "[RUST] Installation completed successfully (exit code 0)",
+                    );
+                } else {
+                    let exit_code = status.code().unwrap_or(-1);
+                    let failed_stage = state.install_context.current_stage();
+                    let _ = state.install_context.fail();
+                    failed_at = Some(failed_stage);
+                    if let Some(ref timeout) = timed_out {
+                        state.status_message = format!(
+                            "Installation failed during {}: {}",
+                            failed_stage.description().to_lowercase(),
+                            timeout
+                        );
+                        state.installer_output.push(format!("ERROR: {}", timeout));
+                        write_master_log(&wait_log, &format!("[RUST] Watchdog: {}", timeout));
                     } else {
-                        let exit_code = status.code().unwrap_or(-1);
-                        state.status_message =
-                            format!("Installation failed with exit code: {}", exit_code);
-                        state
-                            .installer_output
-                            .push(format!("Installation failed with exit code: {}", exit_code));
-                        state.installer_output.push(format!(
-                            "Check {}/ for full details (master log + verbose trace)",
-                            crate::script_runner::log_dir().display()
-                        ));
-                        state.mode = crate::app::AppMode::Complete;
-                        write_master_log(
-                            &wait_log,
-                            &format!("[RUST] Installation FAILED (exit code {})", exit_code),
+                        state.status_message = format!(
+                            "Installation failed during {} (exit code {})",
+                            failed_stage.description().to_lowercase(),
+                            exit_code
                         );
                     }
-                }
-                Err(e) => {
-                    // SAFETY: poison recovery via into_inner — never panic on mutex
-                    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
-                    state.installer_pid = None;
-
                     state
                         .installer_output
-                        .push(format!("ERROR: Failed to wait for installer: {}", e));
-                    state.status_message = format!("Installation error: {}", e);
+                        .push(format!("Installation failed with exit code: {}", exit_code));
+                    state.installer_output.push(format!(
+                        "Check {}/ for full details (master log + verbose trace)",
+                        crate::script_runner::log_dir().display()
+                    ));
+                    if install_checkpoint::is_resumable(failed_stage) {
+                        state.installer_output.push(
+                            "The target was left mounted; save the config and run `archtui install --config <file> --resume` to continue"
+                                .to_string(),
+                        );
+                    }
                     state.mode = crate::app::AppMode::Complete;
                     write_master_log(
                         &wait_log,
-                        &format!("[RUST] Installation ERROR: Failed to wait: {}", e),
+                        &format!("[RUST] Installation FAILED (exit code {})", exit_code),
                     );
                 }
             }
-        });
+            Err(e) => {
+                // SAFETY: poison recovery via into_inner — never panic on mutex
+                let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+                state.installer_pid = None;
+
+                let _ = state.install_context.fail();
+                state
+                    .installer_output
+                    .push(format!("ERROR: Failed to wait for installer: {}", e));
+                state.status_message = format!("Installation error: {}", e);
+                state.mode = crate::app::AppMode::Complete;
+                write_master_log(
+                    &wait_log,
+                    &format!("[RUST] Installation ERROR: Failed to wait: {}", e),
+                );
+            }
+        }
 
-        Ok(())
-    }
+        // The teardown runs scripts: not while holding the state lock
+        if let Some(stage) = failed_at {
+            release_applied_storage(&app_state, stage, &wait_log);
+        }
+    });
+
+    Ok(())
+}
+
+/// Tear down the storage plan ops `apply-storage` recorded as applied, after
+/// the install failed in `failed_at` (a no-op for resumable stages).
+fn release_applied_storage(
+    app_state: &Arc<Mutex<AppState>>,
+    failed_at: InstallStage,
+    master_log: &Arc<Mutex<Option<File>>>,
+) {
+    let result = install_checkpoint::teardown_applied_storage(
+        &crate::script_runner::log_dir(),
+        failed_at,
+        run_script_safe,
+    );
+    let line = match result {
+        Ok(0) => return,
+        Ok(steps) => format!("Released the applied storage plan ops ({} steps)", steps),
+        Err(e) => {
+            tracing::error!(error = %e, "Storage teardown incomplete");
+            format!(
+                "Storage teardown incomplete: {:#} — run Disk Tools > Clean Up Previous Attempt",
+                e
+            )
+        }
+    };
+    write_master_log(master_log, &format!("[RUST] {}", line));
+    // SAFETY: poison recovery via into_inner — never panic on mutex
+    let mut state = app_state.lock().unwrap_or_else(|e| e.into_inner());
+    state.installer_output.push(line);
 }
 
 // ============================================================================
@@ -645,6 +852,7 @@ pub fn prepare_disks(layout: &DiskLayout, wipe: bool, confirm_wipe: bool) -> Res
         filesystem: Filesystem::Fat32,
         label: Some("EFI".to_string()),
         force: false,
+        confirm: true,
     };
     let output =
         run_script_safe(&format_boot).context("Failed to execute boot partition format")?;
@@ -658,6 +866,7 @@ pub fn prepare_disks(layout: &DiskLayout, wipe: bool, confirm_wipe: bool) -> Res
         filesystem: layout.root_filesystem,
         label: Some("archroot".to_string()),
         force: false,
+        confirm: true,
     };
     let output =
         run_script_safe(&format_root).context("Failed to execute root partition format")?;
@@ -852,6 +1061,50 @@ pub fn install_base_system_with_extras(target_root: &Path, extra_packages: &[&st
     Ok(())
 }
 
+#[cfg(feature = "alpm")]
+/// Install the base system, preferring packages from a local cache.
+///
+/// Used by the loop-device sandbox: with a warm cache (e.g. the live ISO's
+/// `/var/cache/pacman/pkg`) no package has to be downloaded. Sync databases
+/// still come from the live system's `pacman.conf`.
+///
+/// # Arguments
+///
+/// * `target_root` - The mount point of the root partition
+/// * `cache_dir` - Directory of `*.pkg.tar.zst` files to install from
+/// * `events` - Receives the transaction's download, progress and question events
+/// * `policy` - How the transaction's questions are answered
+#[allow(dead_code)] // Library API - used by engine::sandbox
+pub fn install_base_system_from_cache(
+    target_root: &Path,
+    cache_dir: &Path,
+    events: Sender<PackageEvent>,
+    policy: QuestionPolicy,
+) -> Result<()> {
+    tracing::info!(cache = ?cache_dir, "Installing base system from local cache");
+
+    if !cache_dir.is_dir() {
+        anyhow::bail!("Package cache does not exist: {:?}", cache_dir);
+    }
+
+    let db_path = target_root.join("var/lib/pacman");
+    std::fs::create_dir_all(&db_path)
+        .with_context(|| format!("Failed to create pacman db path: {:?}", db_path))?;
+
+    let live_pacman_conf = Path::new("/etc/pacman.conf");
+    let mut pm = PackageManager::from_pacman_conf(target_root, live_pacman_conf)
+        .context("Failed to load pacman.conf")?
+        .with_events(events)
+        .with_question_policy(policy);
+    pm.add_cache_dir(cache_dir)?;
+
+    pm.install_packages(BASE_PACKAGES)
+        .context("Failed to install base packages from cache")?;
+
+    tracing::info!("Base system installation from cache complete");
+    Ok(())
+}
+
 // ============================================================================
 // System Configuration
 // ============================================================================
@@ -974,6 +1227,7 @@ pub fn configure_system(config: &SystemConfig) -> Result<()> {
     tracing::info!("Generating /etc/fstab");
     let fstab_args = GenFstabArgs {
         root: config.target_root.clone(),
+        confirm: true,
     };
     let output = run_script_safe(&fstab_args).context("Failed to execute fstab generation")?;
     output.ensure_success("Fstab generation")?;
@@ -995,6 +1249,7 @@ pub fn configure_system(config: &SystemConfig) -> Result<()> {
         locale: config.locale.clone(),
         timezone: config.timezone.clone(),
         keymap: config.keymap.clone(),
+        confirm: true,
     };
     let output = run_script_safe(&locale_args).context("Failed to execute locale configuration")?;
     output.ensure_success("Locale configuration")?;
@@ -1020,6 +1275,7 @@ pub fn configure_system(config: &SystemConfig) -> Result<()> {
         home_dir: None,
         create_home: true,
         sudo: config.user_sudo,
+        confirm: true,
     };
 
     // SELF-AUDIT: Verify password is NOT in CLI args
@@ -1045,6 +1301,7 @@ pub fn configure_system(config: &SystemConfig) -> Result<()> {
             home_dir: None,
             create_home: false, // Root home already exists
             sudo: false,
+            confirm: true,
         };
 
         // SELF-AUDIT: Verify password is NOT in CLI args
@@ -1309,6 +1566,7 @@ pub fn install_dotfiles(config: &DotfilesConfig) -> Result<()> {
         target_dir: config.target_dir.as_ref().map(PathBuf::from),
         branch: config.branch.clone(),
         backup: true, // Always backup existing files
+        confirm: true,
     };
 
     let output = run_script_safe(&args).context("Failed to install dotfiles")?;
@@ -1490,4 +1748,85 @@ mod tests {
         // Verify result is valid UTF-8 (would panic on construction if not)
         assert!(result.is_char_boundary(result.len()));
     }
+
+    #[test]
+    fn test_forward_package_events_updates_progress() {
+        use super::{AppState, forward_package_events};
+        use crate::package_events::{PackageEvent, PackageQuestion, TransactionPhase};
+        use std::sync::{Arc, Mutex};
+
+        let state = Arc::new(Mutex::new(AppState::default()));
+        let (events, handle) = forward_package_events(Arc::clone(&state));
+        for event in [
+            PackageEvent::Phase(TransactionPhase::Resolving),
+            PackageEvent::Question {
+                question: PackageQuestion::Conflict {
+                    package: "pipewire-jack".to_string(),
+                    conflicting: "jack2".to_string(),
+                },
+                answer: Some(false),
+            },
+        ] {
+            events.send(event).expect("receiver alive"); // test: known-good input
+        }
+        drop(events);
+        handle.join().expect("forwarder thread"); // test: known-good input
+
+        let state = state.lock().expect("state lock"); // test: known-good input
+        let progress = state.package_progress.as_ref().expect("progress set"); // test: known-good input
+        assert_eq!(progress.phase, Some(TransactionPhase::Resolving));
+        assert_eq!(
+            state.installer_output.last().map(String::as_str),
+            Some("[ALPM] pipewire-jack and jack2 are in conflict. Remove jack2? -> no")
+        );
+    }
+
+    #[test]
+    fn test_stream_stdout_drives_package_progress() {
+        use super::{AppState, stream_stdout};
+        use crate::install_state::InstallStage;
+        use crate::package_events::TransactionPhase;
+        use crate::watchdog::ActivityClock;
+        use std::sync::{Arc, Mutex};
+
+        let output = "\
+@@ARCHTUI stage validating_config
+@@ARCHTUI stage preparing_system
+@@ARCHTUI stage installing_dependencies
+@@ARCHTUI stage partitioning_disk
+@@ARCHTUI stage installing_base_system
+  [pacstrap] resolving dependencies...
+  [pacstrap] Packages (3) acl-2.3.2-1  base-3-2  linux-6.11-1
+  [pacstrap] :: Retrieving packages...
+  [pacstrap]  linux-6.11-1-x86_64 downloading...
+  [pacstrap] :: Processing package changes...
+  [pacstrap] installing acl...
+  [pacstrap] installing base...
+";
+        let state = Arc::new(Mutex::new(AppState::default()));
+        {
+            let mut state = state.lock().expect("state lock"); // test: known-good input
+            state.install_context.confirm_destructive_operations();
+        }
+        let log_file = Arc::new(Mutex::new(None));
+        stream_stdout(output.as_bytes(), &state, &log_file, &ActivityClock::new());
+
+        let state = state.lock().expect("state lock"); // test: known-good input
+        assert_eq!(
+            state.install_context.current_stage(),
+            InstallStage::InstallingBaseSystem
+        );
+        let progress = state.package_progress.as_ref().expect("progress set"); // test: known-good input
+        assert_eq!(progress.current_package.as_deref(), Some("base"));
+        assert_eq!(progress.install_position, (2, 3));
+        // The end of the output closes the transaction
+        assert_eq!(progress.phase, Some(TransactionPhase::Done));
+        // pacman's lines still reach the output pane
+        assert!(
+            state
+                .installer_output
+                .iter()
+                .any(|line| line == "  [pacstrap] installing acl...")
+        );
+    }
 }
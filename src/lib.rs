@@ -2,23 +2,29 @@
 //!
 //! This library provides the core functionality for the Arch Linux TUI installer.
 pub mod app;
+pub mod capability;
 pub mod cli;
 pub mod components;
 pub mod config;
 pub mod config_file;
+pub mod config_layers;
+pub mod config_migrations;
 pub mod engine;
 pub mod error;
 pub mod hardware;
 pub mod input;
+pub mod install_checkpoint;
 pub mod install_state;
 pub mod installer;
 pub mod logic;
 pub mod option_help;
+pub mod package_events;
 #[cfg(feature = "alpm")]
 pub mod package_manager;
 pub mod package_utils;
 pub mod process_guard;
 pub mod profiles;
+pub mod script_events;
 pub mod script_manifest;
 pub mod script_runner;
 pub mod script_traits;
@@ -27,18 +33,25 @@ pub mod scrolling;
 pub mod theme;
 pub mod types;
 pub mod ui;
+pub mod watchdog;
 
 // Re-export UI wizard types
 pub use ui::{WizardData, WizardState};
 
 // Re-export main types for convenience
+pub use capability::{Capability, CapabilityScheduler, ToolUnavailable};
 pub use config::{ConfigOption, Configuration, Package};
 pub use config_file::InstallationConfig;
 pub use error::ArchTuiError;
-pub use install_state::{InstallStage, InstallTransitionError, InstallerContext};
+pub use install_checkpoint::{CheckpointWriter, InstallCheckpoint};
+pub use install_state::{
+    InstallStage, InstallTransitionError, InstallerContext, StageEvent, StageStep,
+};
 pub use installer::{DiskLayout, SystemConfig, configure_system, prepare_disks};
 #[cfg(feature = "alpm")]
-pub use installer::{install_base_system, install_base_system_with_extras};
+pub use installer::{
+    install_base_system, install_base_system_from_cache, install_base_system_with_extras,
+};
 #[cfg(feature = "alpm")]
 pub use package_manager::PackageManager;
 pub use process_guard::{ChildRegistry, CommandProcessGroup, ProcessGuard};
@@ -67,14 +80,19 @@ pub use types::{
 pub use hardware::{FirmwareMode, HardwareInfo, NetworkState};
 
 // Storage engine
+pub use engine::executor::{ExecutorOptions, OpReport, OpStatus, execute_storage_plan};
+pub use engine::sandbox::{SandboxOptions, SandboxReport, run_sandbox};
 pub use engine::storage::{StorageOp, StoragePlan, calculate_storage_plan};
 
 // Package/service resolver
-pub use logic::resolver::{resolve_packages, resolve_services};
+pub use logic::resolver::{resolve_packages, resolve_packages_for_hardware, resolve_services};
 
 // Pre-install orchestration
 pub use logic::preinstall::{MirrorRankResult, PreinstallConfig, SkipReason, rank_mirrors};
 
+// Dry-run transcript
+pub use logic::dry_run::{DryRunReport, PlannedCommand, TranscriptStep};
+
 // Post-install orchestration
 pub use logic::postinstall::{
     PostInstallConfig, PostInstallResult, clone_dotfiles_safe, install_aur_helper_safe,
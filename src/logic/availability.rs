@@ -0,0 +1,271 @@
+//! Package availability pre-flight
+//!
+//! Resolves every package an install will request against the sync
+//! databases while the config is validated (`InstallStage::ValidatingConfig`),
+//! so a typo in `additional_packages` or a stale profile package list fails
+//! before the disk is touched instead of halfway through pacstrap.
+//!
+//! # What Gets Checked
+//!
+//! The targets are `resolve_packages_for_hardware` plus the members of any
+//! group among them. `PackageManager::check_availability` walks each
+//! target's dependency closure and records:
+//!
+//! | Issue      | Meaning | Blocks the install |
+//! |------------|---------|--------------------|
+//! | `Missing`  | No package, provider or group by that name | yes |
+//! | `Renamed`  | Gone, but another package `replaces` it | yes (pacman does not follow renames on `-S`) |
+//! | `Replaced` | Still exists, but another package `replaces` it | no |
+//! | `NotCached`| Offline installs only: the package file is not in the source | yes |
+//!
+//! The report also carries the download and installed size of the whole
+//! closure, so the user sees what the install will pull before it starts.
+//!
+//! The TUI hands the check to `Installer::with_package_check`, which runs it
+//! on the installer thread so the UI keeps drawing while the databases are
+//! fetched; the headless installer runs it inline.
+//!
+//! # Feature Flag
+//!
+//! Resolving needs libalpm. Without the `alpm` feature `check_install_packages`
+//! returns an error and callers log it and carry on, as they do when the sync
+//! databases cannot be fetched.
+
+// Library API - consumed by the headless installer and the TUI
+#![allow(dead_code)]
+
+use crate::config_file::InstallationConfig;
+use crate::engine::capacity::format_mib;
+use crate::hardware::HardwareInventory;
+use anyhow::Result;
+use std::fmt;
+
+/// A problem with one package in the install's dependency closure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageIssue {
+    /// No package, provider or group satisfies `name`.
+    Missing {
+        name: String,
+        /// Package whose dependency this is; `None` for a requested package.
+        required_by: Option<String>,
+    },
+    /// `name` no longer exists; `new_name` lists it in `replaces`.
+    Renamed { name: String, new_name: String },
+    /// `name` still exists, but `by` lists it in `replaces`.
+    Replaced { name: String, by: String },
+    /// The package resolves, but its file is missing from `dir`.
+    NotCached {
+        name: String,
+        file: String,
+        dir: String,
+    },
+}
+
+impl PackageIssue {
+    /// Whether the install would fail on this issue.
+    pub fn is_blocking(&self) -> bool {
+        !matches!(self, Self::Replaced { .. })
+    }
+}
+
+impl fmt::Display for PackageIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing {
+                name,
+                required_by: Some(parent),
+            } => write!(f, "{} (required by {})", name, parent),
+            Self::Missing {
+                name,
+                required_by: None,
+            } => write!(f, "{} (not in any sync database)", name),
+            Self::Renamed { name, new_name } => {
+                write!(f, "{} (renamed to {})", name, new_name)
+            }
+            Self::Replaced { name, by } => write!(f, "{} (replaced by {})", name, by),
+            Self::NotCached { name, file, dir } => {
+                write!(f, "{} ({} missing from {})", name, file, dir)
+            }
+        }
+    }
+}
+
+/// Outcome of resolving an install's packages against the sync databases.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AvailabilityReport {
+    /// Packages requested by the install.
+    pub targets: usize,
+    /// Packages in the resolved closure (targets, group members, dependencies).
+    pub resolved: usize,
+    /// Bytes to download, less whatever is already in the package cache.
+    pub download_bytes: i64,
+    /// Bytes the closure takes once installed.
+    pub installed_bytes: i64,
+    /// Problems found, sorted by package name.
+    pub issues: Vec<PackageIssue>,
+}
+
+impl AvailabilityReport {
+    /// Issues that would make the install fail.
+    pub fn blocking(&self) -> Vec<&PackageIssue> {
+        self.issues.iter().filter(|i| i.is_blocking()).collect()
+    }
+
+    /// Whether the install can go ahead.
+    pub fn is_ok(&self) -> bool {
+        self.issues.iter().all(|i| !i.is_blocking())
+    }
+
+    /// One-line summary, e.g. "412 packages, 498 MiB download, 1.9 GiB installed".
+    pub fn summary(&self) -> String {
+        format!(
+            "{} packages, {} download, {} installed",
+            self.resolved,
+            format_bytes(self.download_bytes),
+            format_bytes(self.installed_bytes)
+        )
+    }
+
+    /// Summary followed by one line per issue, errors first.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.summary()];
+        let (errors, warnings): (Vec<_>, Vec<_>) =
+            self.issues.iter().partition(|i| i.is_blocking());
+        lines.extend(errors.iter().map(|i| format!("[ERROR] {}", i)));
+        lines.extend(warnings.iter().map(|i| format!("[WARN] {}", i)));
+        lines
+    }
+
+    /// Fail with every blocking issue listed.
+    pub fn ensure_ok(&self, source: &str) -> Result<()> {
+        let blocking = self.blocking();
+        if !blocking.is_empty() {
+            let lines: Vec<String> = blocking.iter().map(|i| i.to_string()).collect();
+            anyhow::bail!(
+                "{} package(s) are not available from {}:\n  {}",
+                lines.len(),
+                source,
+                lines.join("\n  ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Sizes are rounded up to whole MiB; a few hundred packages make that moot.
+pub(crate) fn format_bytes(bytes: i64) -> String {
+    format_mib((bytes.max(0) as u64).div_ceil(1024 * 1024))
+}
+
+/// Resolve the packages `config` installs against the live system's sync
+/// databases (`/etc/pacman.conf`).
+///
+/// The databases are fetched into a scratch root under the runtime
+/// directory, so the live system's own databases are left alone.
+#[cfg(feature = "alpm")]
+pub fn check_install_packages(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Result<AvailabilityReport> {
+    use crate::logic::resolver::resolve_packages_for_hardware;
+    use crate::package_manager::PackageManager;
+    use anyhow::Context;
+    use std::path::Path;
+
+    let alpm_root = crate::capability::runtime_dir().join("availability");
+    let db_path = alpm_root.join("var/lib/pacman");
+    std::fs::create_dir_all(&db_path)
+        .with_context(|| format!("Failed to create {}", db_path.display()))?;
+
+    let mut pm =
+        PackageManager::from_pacman_conf(alpm_root.as_path(), Path::new("/etc/pacman.conf"))
+            .context("Failed to load /etc/pacman.conf")?;
+    pm.check_availability(&resolve_packages_for_hardware(config, hardware), None)
+}
+
+#[cfg(not(feature = "alpm"))]
+pub fn check_install_packages(
+    _config: &InstallationConfig,
+    _hardware: &HardwareInventory,
+) -> Result<AvailabilityReport> {
+    anyhow::bail!("Package availability check requires the `alpm` feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(issues: Vec<PackageIssue>) -> AvailabilityReport {
+        AvailabilityReport {
+            targets: 3,
+            resolved: 412,
+            download_bytes: 498 * 1024 * 1024 + 1,
+            installed_bytes: 1946 * 1024 * 1024,
+            issues,
+        }
+    }
+
+    #[test]
+    fn test_issue_display_and_blocking() {
+        let missing = PackageIssue::Missing {
+            name: "firefx".to_string(),
+            required_by: None,
+        };
+        let dep = PackageIssue::Missing {
+            name: "libfoo.so".to_string(),
+            required_by: Some("foo".to_string()),
+        };
+        let renamed = PackageIssue::Renamed {
+            name: "pipewire-media-session".to_string(),
+            new_name: "wireplumber".to_string(),
+        };
+        let replaced = PackageIssue::Replaced {
+            name: "jack2".to_string(),
+            by: "pipewire-jack".to_string(),
+        };
+        assert_eq!(missing.to_string(), "firefx (not in any sync database)");
+        assert_eq!(dep.to_string(), "libfoo.so (required by foo)");
+        assert_eq!(
+            renamed.to_string(),
+            "pipewire-media-session (renamed to wireplumber)"
+        );
+        assert_eq!(replaced.to_string(), "jack2 (replaced by pipewire-jack)");
+        assert!(missing.is_blocking() && dep.is_blocking() && renamed.is_blocking());
+        assert!(!replaced.is_blocking());
+    }
+
+    #[test]
+    fn test_report_lines_and_ensure_ok() {
+        let replaced = PackageIssue::Replaced {
+            name: "jack2".to_string(),
+            by: "pipewire-jack".to_string(),
+        };
+        let ok = report(vec![replaced.clone()]);
+        assert!(ok.is_ok());
+        assert!(ok.ensure_ok("the sync databases").is_ok());
+        assert_eq!(
+            ok.to_lines(),
+            vec![
+                "412 packages, 499 MiB download, 1.9 GiB installed".to_string(),
+                "[WARN] jack2 (replaced by pipewire-jack)".to_string(),
+            ]
+        );
+
+        let missing = PackageIssue::Missing {
+            name: "firefx".to_string(),
+            required_by: None,
+        };
+        let bad = report(vec![replaced, missing]);
+        assert!(!bad.is_ok());
+        assert_eq!(
+            bad.to_lines()[1],
+            "[ERROR] firefx (not in any sync database)"
+        );
+        let err = bad
+            .ensure_ok("the sync databases")
+            .expect_err("missing package must fail");
+        assert!(err.to_string().contains("1 package(s)"));
+        assert!(err.to_string().contains("firefx"));
+        assert!(!err.to_string().contains("jack2"));
+    }
+}
@@ -0,0 +1,594 @@
+//! Dry-Run Report
+//!
+//! Describes everything a full install would do for a configuration, in
+//! execution order, without running or spawning anything:
+//!
+//! 1. Mirror ranking (`update_mirrors.sh`), unless installing offline
+//! 2. Partitioning: the `scripts/strategies/` file `install.sh` sources for
+//!    the strategy, and the storage part of its environment
+//! 3. The `install.sh` invocation and the environment it receives
+//! 4. The AUR helper build, if one is selected and not installing offline
+//!
+//! The storage engine's plan for the same config (`engine::executor::plan_steps`)
+//! is listed separately as `storage_plan`: for the automatic strategies it is
+//! what `storage_plan.sh` hands to `archtui apply-storage`, op for op.
+//!
+//! alongside the resolved packages (`resolve_packages_for_hardware`), services
+//! (`resolve_services`) and bootloader settings. Secret env values are
+//! redacted with `redact_env_vars` before they enter the report, so the
+//! text and JSON forms are safe to save or share.
+//!
+//! # What This Explicitly Refuses To Do
+//!
+//! - Probe disks: a plan that cannot be computed is reported as
+//!   `storage_error` instead of failing the whole report
+//! - Predict script-internal commands: `install.sh` is listed as one step
+
+// Library API - consumed by the TUI dry-run summary and the headless CLI
+#![allow(dead_code)]
+
+use crate::config_file::InstallationConfig;
+use crate::engine::executor::{ExecutorOptions, plan_steps};
+use crate::engine::storage::calculate_storage_plan;
+use crate::hardware::HardwareInventory;
+use crate::logic::offline::skipped_steps;
+use crate::logic::preinstall::{mirror_args, preinstall_config_from};
+use crate::logic::resolver::{
+    resolve_bootloader_packages, resolve_packages_for_hardware, resolve_services,
+};
+use crate::script_runner::redact_env_vars;
+use crate::script_traits::ScriptArgs;
+use crate::scripts::user_ops::InstallAurHelperArgs;
+use crate::types::{AurHelper, Bootloader, PartitionScheme, Toggle};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Version of the JSON report layout. Bump on incompatible changes.
+pub const DRY_RUN_REPORT_VERSION: u32 = 2;
+
+/// Variables of `InstallationConfig::to_env_vars` the partitioning strategies read.
+const STORAGE_ENV_KEYS: &[&str] = &[
+    "INSTALL_DISK",
+    "PARTITIONING_STRATEGY",
+    "BOOT_MODE",
+    "RAID_LEVEL",
+    "ROOT_FILESYSTEM",
+    "HOME_FILESYSTEM",
+    "SEPARATE_HOME",
+    "ROOT_SIZE",
+    "HOME_SIZE",
+    "ENCRYPTION",
+    "ENCRYPTION_PASSWORD",
+    "ENCRYPTION_KEY_TYPE",
+    "SWAP",
+    "SWAP_SIZE",
+    "HIBERNATION",
+    "PARTITION_LAYOUT",
+    "BTRFS_SUBVOLUMES",
+    "ALONGSIDE_ESP",
+    "SHRINK_PARTITION",
+    "SHRINK_SIZE",
+];
+
+/// The strategy file `install.sh` (`partition_disk`) sources for `config`.
+pub fn strategy_script(config: &InstallationConfig) -> &'static str {
+    match config.partitioning_strategy {
+        PartitionScheme::AutoSimple
+        | PartitionScheme::AutoSimpleLuks
+        | PartitionScheme::AutoLvm
+        | PartitionScheme::AutoLuksLvm
+        | PartitionScheme::AutoRaid
+        | PartitionScheme::AutoRaidLuks
+        | PartitionScheme::AutoRaidLvm
+        | PartitionScheme::AutoRaidLvmLuks
+        | PartitionScheme::AutoAlongside => "scripts/strategies/storage_plan.sh",
+        PartitionScheme::Manual if !config.partition_layout.is_empty() => {
+            "scripts/strategies/storage_plan.sh"
+        }
+        PartitionScheme::Manual => "scripts/strategies/manual.sh",
+        PartitionScheme::PreMounted => "scripts/strategies/pre_mounted.sh",
+    }
+}
+
+/// Install phase a transcript step belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Preinstall,
+    Storage,
+    Install,
+    Postinstall,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Preinstall => write!(f, "pre-install"),
+            Self::Storage => write!(f, "storage"),
+            Self::Install => write!(f, "install"),
+            Self::Postinstall => write!(f, "post-install"),
+        }
+    }
+}
+
+/// One script invocation, as it would be spawned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedCommand {
+    /// Script path relative to the scripts directory's parent
+    /// (e.g. `scripts/tools/wipe_disk.sh`), matching the manifest key
+    pub script: String,
+    /// CLI arguments
+    pub args: Vec<String>,
+    /// `KEY=VALUE` environment entries, secrets already redacted
+    pub env: Vec<String>,
+    /// Whether `run_script_safe` would skip it under `--dry-run`
+    pub destructive: bool,
+    /// Script that sources this one instead of spawning it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sourced_by: Option<String>,
+}
+
+impl PlannedCommand {
+    /// Describe a typed tool script invocation.
+    pub fn from_args(args: &dyn ScriptArgs) -> Self {
+        Self {
+            script: format!("scripts/tools/{}", args.script_name()),
+            args: args.to_cli_args(),
+            env: redact_env_vars(&args.get_env_vars()),
+            destructive: args.is_destructive(),
+            sourced_by: None,
+        }
+    }
+
+    /// Command line without the environment (`bash <script> <args>`, or
+    /// `source <script> (in <parent>)` for a sourced script).
+    pub fn command_line(&self) -> String {
+        if let Some(ref parent) = self.sourced_by {
+            return format!("source {} (in {})", self.script, parent);
+        }
+        let mut parts = vec!["bash".to_string(), self.script.clone()];
+        parts.extend(self.args.iter().cloned());
+        parts.join(" ")
+    }
+}
+
+/// One entry of the ordered transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptStep {
+    pub phase: Phase,
+    /// What the step does (e.g. the storage op it implements)
+    pub description: String,
+    /// Script invocations in execution order
+    pub commands: Vec<PlannedCommand>,
+}
+
+/// Bootloader settings the install would apply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootloaderPlan {
+    pub bootloader: String,
+    pub boot_mode: String,
+    pub secure_boot: bool,
+    pub os_prober: bool,
+    /// GRUB theme, when GRUB theming is enabled
+    pub grub_theme: Option<String>,
+    /// Packages the bootloader pulls in
+    pub packages: Vec<String>,
+}
+
+impl BootloaderPlan {
+    fn from_config(config: &InstallationConfig) -> Self {
+        let packages = resolve_bootloader_packages(config);
+        let grub_theme = (config.bootloader == Bootloader::Grub
+            && config.grub_theme == Toggle::Yes)
+            .then(|| config.grub_theme_selection.to_string());
+        Self {
+            bootloader: config.bootloader.to_string(),
+            boot_mode: config.boot_mode.to_string(),
+            secure_boot: config.secure_boot == Toggle::Yes,
+            os_prober: config.os_prober == Toggle::Yes,
+            grub_theme,
+            packages: packages.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+/// Everything a full install would do for one configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// Layout version ([`DRY_RUN_REPORT_VERSION`])
+    pub version: u32,
+    /// Validation error, if the configuration would be rejected
+    pub validation_error: Option<String>,
+    /// Partitioning strategy
+    pub strategy: String,
+    /// Why the storage plan could not be computed (`storage_plan` is then empty)
+    pub storage_error: Option<String>,
+    /// Every script invocation, in execution order
+    pub transcript: Vec<TranscriptStep>,
+    /// The storage engine's ops for this config, as `archtui apply-storage`
+    /// runs them for the automatic strategies
+    #[serde(default)]
+    pub storage_plan: Vec<String>,
+    /// Packages installed into the target (`resolve_packages_for_hardware`)
+    pub packages: Vec<String>,
+    /// AUR packages built after the install
+    pub aur_packages: Vec<String>,
+    /// Services enabled in the target (`resolve_services`)
+    pub services: Vec<String>,
+    pub bootloader: BootloaderPlan,
+    /// Network-only steps an offline install leaves out (`offline::skipped_steps`)
+    #[serde(default)]
+    pub skipped: Vec<String>,
+}
+
+impl DryRunReport {
+    /// Build the report for `config` on `hardware` (which decides `Auto`
+    /// GPU drivers and microcode). Never runs or spawns anything.
+    pub fn build(config: &InstallationConfig, hardware: &HardwareInventory) -> Self {
+        let mut transcript = Vec::new();
+        let offline = !config.offline_source.trim().is_empty();
+
+        if !offline {
+            transcript.push(TranscriptStep {
+                phase: Phase::Preinstall,
+                description: "Rank pacman mirrors (skipped when offline)".to_string(),
+                commands: vec![PlannedCommand::from_args(&mirror_args(
+                    &preinstall_config_from(config),
+                ))],
+            });
+        }
+
+        let env = config.to_env_vars();
+        let storage_env: Vec<(String, String)> = env
+            .iter()
+            .filter(|(key, _)| STORAGE_ENV_KEYS.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        transcript.push(TranscriptStep {
+            phase: Phase::Storage,
+            description:
+                "Partition, encrypt, format and mount the target (install.sh partition_disk)"
+                    .to_string(),
+            commands: vec![PlannedCommand {
+                script: strategy_script(config).to_string(),
+                args: Vec::new(),
+                env: redact_env_vars(&storage_env),
+                destructive: config.partitioning_strategy != PartitionScheme::PreMounted,
+                sourced_by: Some("scripts/install.sh".to_string()),
+            }],
+        });
+
+        let storage = calculate_storage_plan(config)
+            .and_then(|plan| plan_steps(&plan, &ExecutorOptions::default()));
+        let (storage_plan, storage_error) = match storage {
+            Ok(planned) => (planned.iter().map(|p| p.op.to_string()).collect(), None),
+            Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+        };
+
+        let install_description = if offline {
+            format!(
+                "Base system, packages, services, bootloader and users from {} (after partitioning)",
+                config.offline_source.trim()
+            )
+        } else {
+            "Base system, packages, services, bootloader and users (after partitioning)".to_string()
+        };
+        transcript.push(TranscriptStep {
+            phase: Phase::Install,
+            description: install_description,
+            commands: vec![PlannedCommand {
+                script: "scripts/install.sh".to_string(),
+                args: Vec::new(),
+                env: redact_env_vars(&env),
+                destructive: true,
+                sourced_by: None,
+            }],
+        });
+
+        if config.aur_helper != AurHelper::None && !offline {
+            let args = InstallAurHelperArgs {
+                helper: config.aur_helper,
+                target_user: config.username.clone(),
+                chroot_path: PathBuf::from("/mnt"),
+            };
+            transcript.push(TranscriptStep {
+                phase: Phase::Postinstall,
+                description: format!("Build AUR helper {}", config.aur_helper),
+                commands: vec![PlannedCommand::from_args(&args)],
+            });
+        }
+
+        Self {
+            version: DRY_RUN_REPORT_VERSION,
+            validation_error: config
+                .validate_for_hardware(hardware)
+                .err()
+                .map(|e| format!("{:#}", e)),
+            strategy: config.partitioning_strategy.to_string(),
+            storage_error,
+            transcript,
+            storage_plan,
+            packages: resolve_packages_for_hardware(config, hardware),
+            aur_packages: config
+                .additional_aur_packages
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+            services: resolve_services(config),
+            bootloader: BootloaderPlan::from_config(config),
+            skipped: skipped_steps(config),
+        }
+    }
+
+    /// Human-readable report, one line per entry.
+    ///
+    /// Destructive commands start with `[DESTRUCTIVE]`; other commands and
+    /// each command's environment start with `  ->`, which the TUI summary
+    /// styles.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec!["=== Dry-Run Transcript ===".to_string()];
+        if let Some(ref err) = self.validation_error {
+            lines.push(format!("[FAIL] Configuration would be rejected: {}", err));
+        }
+        lines.push(format!("Strategy: {}", self.strategy));
+        if let Some(ref err) = self.storage_error {
+            lines.push(format!("[SKIP] Storage plan unavailable: {}", err));
+        }
+        lines.push(String::new());
+
+        for (i, step) in self.transcript.iter().enumerate() {
+            lines.push(format!("{}. {}: {}", i + 1, step.phase, step.description));
+            for command in &step.commands {
+                if command.destructive {
+                    lines.push(format!("[DESTRUCTIVE] {}", command.command_line()));
+                } else {
+                    lines.push(format!("  -> {}", command.command_line()));
+                }
+                for var in &command.env {
+                    lines.push(format!("  ->   {}", var));
+                }
+            }
+        }
+        lines.push(String::new());
+
+        if !self.storage_plan.is_empty() {
+            lines.push(format!(
+                "=== Storage Plan ({} ops, run by archtui apply-storage) ===",
+                self.storage_plan.len()
+            ));
+            for (i, op) in self.storage_plan.iter().enumerate() {
+                lines.push(format!("  {}. {}", i + 1, op));
+            }
+        }
+
+        lines.push(format!("=== Packages ({}) ===", self.packages.len()));
+        push_wrapped(&mut lines, &self.packages);
+        if !self.aur_packages.is_empty() {
+            lines.push(format!(
+                "=== AUR Packages ({}) ===",
+                self.aur_packages.len()
+            ));
+            push_wrapped(&mut lines, &self.aur_packages);
+        }
+        lines.push(format!("=== Services ({}) ===", self.services.len()));
+        push_wrapped(&mut lines, &self.services);
+
+        let boot = &self.bootloader;
+        lines.push("=== Bootloader ===".to_string());
+        lines.push(format!("  {} ({})", boot.bootloader, boot.boot_mode));
+        lines.push(format!(
+            "  Secure Boot: {}, os-prober: {}",
+            yes_no(boot.secure_boot),
+            yes_no(boot.os_prober)
+        ));
+        if let Some(ref theme) = boot.grub_theme {
+            lines.push(format!("  GRUB theme: {}", theme));
+        }
+        if !boot.packages.is_empty() {
+            lines.push(format!("  Packages: {}", boot.packages.join(" ")));
+        }
+        if !self.skipped.is_empty() {
+            lines.push(format!("=== Skipped Offline ({}) ===", self.skipped.len()));
+            for step in &self.skipped {
+                lines.push(format!("[SKIP] {}", step));
+            }
+        }
+        lines
+    }
+
+    /// The text report as a single string.
+    pub fn to_text(&self) -> String {
+        let mut text = self.to_lines().join("\n");
+        text.push('\n');
+        text
+    }
+
+    /// The JSON report (pretty-printed).
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Write `<stem>.txt` and `<stem>.json` into `dir` (created if missing).
+    ///
+    /// Returns the two paths written.
+    pub fn write_files(&self, dir: &Path, stem: &str) -> io::Result<(PathBuf, PathBuf)> {
+        fs::create_dir_all(dir)?;
+        let text_path = dir.join(format!("{}.txt", stem));
+        let json_path = dir.join(format!("{}.json", stem));
+        fs::write(&text_path, self.to_text())?;
+        fs::write(&json_path, self.to_json()?)?;
+        Ok((text_path, json_path))
+    }
+}
+
+/// Append `items` as indented lines of at most eight entries.
+fn push_wrapped(lines: &mut Vec<String>, items: &[String]) {
+    for chunk in items.chunks(8) {
+        lines.push(format!("  {}", chunk.join(" ")));
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AutoToggle, BootMode, Filesystem, PartitionScheme};
+
+    fn luks_config() -> InstallationConfig {
+        let mut config = InstallationConfig::new();
+        config.install_disk = "/dev/sda".to_string();
+        config.partitioning_strategy = PartitionScheme::AutoSimpleLuks;
+        config.encryption = AutoToggle::Yes;
+        config.encryption_password = "correct horse".to_string();
+        config.root_password = "hunter2".to_string();
+        config.root_filesystem = Filesystem::Ext4;
+        config.boot_mode = BootMode::Uefi;
+        config
+    }
+
+    #[test]
+    fn test_report_orders_phases() {
+        let report = DryRunReport::build(&luks_config(), &HardwareInventory::default());
+        assert!(report.storage_error.is_none(), "{:?}", report.storage_error);
+
+        let phases: Vec<Phase> = report.transcript.iter().map(|s| s.phase).collect();
+        assert_eq!(phases.first(), Some(&Phase::Preinstall));
+        let install = phases
+            .iter()
+            .position(|p| *p == Phase::Install)
+            .expect("no install step"); // test: known-good input
+        assert!(phases[1..install].iter().all(|p| *p == Phase::Storage));
+        assert!(install > 1, "storage steps missing");
+        let storage = &report.transcript[1].commands[0];
+        assert_eq!(storage.script, "scripts/strategies/storage_plan.sh");
+        assert_eq!(storage.sourced_by.as_deref(), Some("scripts/install.sh"));
+        assert!(
+            storage
+                .env
+                .contains(&"PARTITIONING_STRATEGY=auto_simple_luks".to_string())
+        );
+        assert!(report.packages.contains(&"base".to_string()));
+    }
+
+    #[test]
+    fn test_report_never_contains_secrets() {
+        let report = DryRunReport::build(&luks_config(), &HardwareInventory::default());
+        let text = report.to_text();
+        let json = report.to_json().expect("serialize failed"); // test: known-good input
+        for output in [&text, &json] {
+            assert!(!output.contains("correct horse"));
+            assert!(!output.contains("hunter2"));
+        }
+        assert!(text.contains("ROOT_PASSWORD=<REDACTED>"));
+        assert!(text.contains("ENCRYPTION_PASSWORD=<REDACTED>"));
+        assert!(text.contains(
+            "[DESTRUCTIVE] source scripts/strategies/storage_plan.sh (in scripts/install.sh)"
+        ));
+    }
+
+    // test: the transcript only lists scripts a real install runs
+    #[test]
+    fn test_transcript_lists_no_executor_tool_scripts() {
+        let report = DryRunReport::build(&luks_config(), &HardwareInventory::default());
+        let tools: Vec<&str> = report
+            .transcript
+            .iter()
+            .filter(|s| s.phase == Phase::Storage)
+            .flat_map(|s| &s.commands)
+            .map(|c| c.script.as_str())
+            .filter(|script| script.starts_with("scripts/tools/"))
+            .collect();
+        assert!(tools.is_empty(), "{:?}", tools);
+
+        // The engine's plan is listed as the ops apply-storage runs
+        assert!(report.storage_plan.iter().any(|op| op.contains("/dev/sda")));
+        assert!(report.to_text().contains("=== Storage Plan ("));
+    }
+
+    #[test]
+    fn test_strategy_script_follows_partition_layout() {
+        let mut config = luks_config();
+        config.partitioning_strategy = PartitionScheme::Manual;
+        assert_eq!(strategy_script(&config), "scripts/strategies/manual.sh");
+        config.partition_layout = vec![crate::config_file::LayoutPartition {
+            size: "Remaining".to_string(),
+            partition_type: crate::scripts::disk::PartitionType::Linux,
+            filesystem: Some(Filesystem::Ext4),
+            label: None,
+            mountpoint: Some("/".to_string()),
+            mount_options: None,
+            encrypt: false,
+        }];
+        assert_eq!(
+            strategy_script(&config),
+            "scripts/strategies/storage_plan.sh"
+        );
+
+        // Every strategy resolves to a shipped file
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for scheme in <PartitionScheme as strum::IntoEnumIterator>::iter() {
+            config.partitioning_strategy = scheme;
+            assert!(root.join(strategy_script(&config)).exists(), "{:?}", scheme);
+        }
+    }
+
+    #[test]
+    fn test_report_survives_unplannable_storage() {
+        let mut config = luks_config();
+        config.install_disk = String::new();
+        let report = DryRunReport::build(&config, &HardwareInventory::default());
+        assert!(report.storage_error.is_some());
+        assert!(report.validation_error.is_some());
+        assert!(
+            report
+                .transcript
+                .iter()
+                .flat_map(|s| &s.commands)
+                .any(|c| c.script == "scripts/install.sh")
+        );
+    }
+
+    #[test]
+    fn test_offline_report_skips_network_steps() {
+        let mut config = luks_config();
+        config.aur_helper = AurHelper::Paru;
+        config.offline_source = "/var/cache/pacman/pkg".to_string();
+        let report = DryRunReport::build(&config, &HardwareInventory::default());
+
+        let phases: Vec<Phase> = report.transcript.iter().map(|s| s.phase).collect();
+        assert_eq!(phases.first(), Some(&Phase::Storage));
+        assert!(!phases.contains(&Phase::Postinstall));
+        assert!(report.skipped.contains(&"AUR helper paru".to_string()));
+        let text = report.to_text();
+        assert!(text.contains("[SKIP] Mirror ranking (reflector)"));
+    }
+
+    #[test]
+    fn test_write_files() {
+        let dir = tempfile::tempdir().expect("tempdir failed"); // test: known-good input
+        let report = DryRunReport::build(&luks_config(), &HardwareInventory::default());
+        let (text_path, json_path) = report
+            .write_files(&dir.path().join("reports"), "dry-run")
+            .expect("write failed"); // test: known-good input
+        assert_eq!(
+            std::fs::read_to_string(text_path).expect("read failed"), // test: known-good input
+            report.to_text()
+        );
+        assert!(json_path.ends_with("reports/dry-run.json"));
+    }
+
+    #[test]
+    fn test_report_json_round_trip() {
+        let report = DryRunReport::build(&luks_config(), &HardwareInventory::default());
+        let json = report.to_json().expect("serialize failed"); // test: known-good input
+        let parsed: DryRunReport = serde_json::from_str(&json).expect("parse failed"); // test: known-good input
+        assert_eq!(parsed, report);
+        assert_eq!(parsed.version, DRY_RUN_REPORT_VERSION);
+    }
+}
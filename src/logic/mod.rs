@@ -6,9 +6,15 @@
 //! # Modules
 //!
 //! - `resolver` — Package and service name resolution
+//! - `availability` — Pre-flight check of resolved packages against the sync databases
+//! - `dry_run` — Ordered transcript of what a full install would execute
+//! - `offline` — Installing from a local package repository or cache
 //! - `preinstall` — Pre-install orchestration: mirror ranking
 //! - `postinstall` — Post-install orchestration: AUR, dotfiles
 
+pub mod availability;
+pub mod dry_run;
+pub mod offline;
 pub mod postinstall;
 pub mod preinstall;
 pub mod resolver;
@@ -0,0 +1,454 @@
+//! Offline Installs
+//!
+//! Installs from a local package source instead of the mirrors. The source
+//! is either a pacman repository (one or more `<name>.db` files next to their
+//! packages, as written by `repo-add`) or a plain package cache such as
+//! `/var/cache/pacman/pkg`, which is indexed with `repo-add` first.
+//!
+//! # Flow
+//!
+//! 1. `OfflineSource::detect` classifies the directory
+//! 2. `OfflineRepo::prepare` writes the database (cache only) and two
+//!    pacman.conf files into a work directory: one for pacstrap on the live
+//!    system, one for pacman inside the chroot, where the directories are
+//!    bind-mounted at `CHROOT_REPO_DIR` and `CHROOT_PKG_DIR`
+//! 3. `OfflineRepo::check_packages` resolves every package and dependency
+//!    through ALPM against the local databases, before the disk is touched
+//! 4. `install.sh` receives `OfflineRepo::env_vars` and uses the generated
+//!    pacman.conf files instead of the mirrors
+//!
+//! # Where It Is Available
+//!
+//! Offline mode is driven by a config file: `offline_source` in a loaded
+//! config (TUI or `archtui install --config`) or `archtui install --offline`.
+//! The guided TUI installer has no package source option and refuses to
+//! start while `HardwareInfo::network` is `Offline`.
+//!
+//! # What Gets Skipped
+//!
+//! Steps that need the network are skipped with a log line instead of
+//! failing mid-install (`skipped_steps`): mirror ranking, the AUR helper and
+//! AUR packages, dotfiles, theme downloads and the Flathub remote.
+
+// Library API - consumed by the headless installer and the TUI
+#![allow(dead_code)]
+
+use crate::config_file::InstallationConfig;
+use crate::hardware::HardwareInventory;
+use crate::logic::resolver::resolve_packages_for_hardware;
+use crate::process_guard::CommandProcessGroup;
+use crate::types::{AurHelper, Bootloader, Toggle};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Repository name given to an indexed package cache.
+pub const CACHE_REPO_NAME: &str = "archtui-offline";
+/// Where the database directory is bind-mounted inside the target.
+pub const CHROOT_REPO_DIR: &str = "/var/cache/archtui-offline/repo";
+/// Where the package directory is bind-mounted inside the target.
+pub const CHROOT_PKG_DIR: &str = "/var/cache/archtui-offline/pkg";
+
+/// Signature policy for the local repositories: packages and databases
+/// built with `repo-add` are usually unsigned, but signatures that are
+/// present are still checked.
+const REPO_SIG_LEVEL: &str = "Optional TrustAll";
+
+// ============================================================================
+// Package Source
+// ============================================================================
+
+/// What kind of directory an offline source is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceKind {
+    /// A pacman repository; carries the repository names, sorted.
+    Repo(Vec<String>),
+    /// Package files without a database.
+    Cache,
+}
+
+/// A local directory to install packages from.
+#[derive(Debug, Clone)]
+pub struct OfflineSource {
+    pub dir: PathBuf,
+    pub kind: SourceKind,
+    /// Package files found in `dir`, sorted by file name.
+    pub packages: Vec<PathBuf>,
+}
+
+impl OfflineSource {
+    /// Classify `dir` as a repository (it has `*.db` files) or a cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` is not an absolute path to a directory or
+    /// holds neither a database nor any package files.
+    pub fn detect(dir: &Path) -> Result<Self> {
+        if !dir.is_absolute() {
+            bail!(
+                "Offline package source must be an absolute path: {}",
+                dir.display()
+            );
+        }
+        if !dir.is_dir() {
+            bail!("Offline package source does not exist: {}", dir.display());
+        }
+
+        let mut repos = Vec::new();
+        let mut packages = Vec::new();
+        let entries =
+            fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(repo) = name.strip_suffix(".db") {
+                repos.push(repo.to_string());
+            } else if is_package_file(&name) {
+                packages.push(entry.path());
+            }
+        }
+        repos.sort();
+        packages.sort();
+
+        let kind = if !repos.is_empty() {
+            SourceKind::Repo(repos)
+        } else if !packages.is_empty() {
+            SourceKind::Cache
+        } else {
+            bail!(
+                "{} is neither a pacman repository (no *.db) nor a package cache (no *.pkg.tar.*)",
+                dir.display()
+            );
+        };
+        tracing::info!(dir = %dir.display(), ?kind, packages = packages.len(), "Detected offline package source");
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            kind,
+            packages,
+        })
+    }
+}
+
+/// Whether `name` is a package archive (not its detached signature).
+fn is_package_file(name: &str) -> bool {
+    name.contains(".pkg.tar") && !name.ends_with(".sig")
+}
+
+// ============================================================================
+// Prepared Repository
+// ============================================================================
+
+/// An offline source ready for pacstrap: databases and pacman.conf files.
+#[derive(Debug, Clone)]
+pub struct OfflineRepo {
+    pub source: OfflineSource,
+    /// Repositories listed in the generated pacman.conf files.
+    pub repos: Vec<String>,
+    /// Directory holding the `<repo>.db` files.
+    pub db_dir: PathBuf,
+    /// Directory holding the package files.
+    pub pkg_dir: PathBuf,
+    /// pacman.conf for pacstrap on the live system.
+    pub pacman_conf: PathBuf,
+    /// pacman.conf used inside the chroot.
+    pub chroot_pacman_conf: PathBuf,
+    work_dir: PathBuf,
+}
+
+impl OfflineRepo {
+    /// Index the source if needed and write the pacman.conf files into
+    /// `work_dir` (created if missing).
+    ///
+    /// A cache is indexed into `work_dir/repo` as `CACHE_REPO_NAME`; when it
+    /// holds several versions of a package, the one sorting last wins.
+    pub fn prepare(source: OfflineSource, work_dir: &Path) -> Result<Self> {
+        fs::create_dir_all(work_dir)
+            .with_context(|| format!("Failed to create {}", work_dir.display()))?;
+
+        let (repos, db_dir) = match &source.kind {
+            SourceKind::Repo(repos) => (repos.clone(), source.dir.clone()),
+            SourceKind::Cache => {
+                let db_dir = work_dir.join("repo");
+                index_cache(&source.packages, &db_dir)?;
+                (vec![CACHE_REPO_NAME.to_string()], db_dir)
+            }
+        };
+        let pkg_dir = source.dir.clone();
+
+        let pacman_conf = work_dir.join("pacman.conf");
+        let chroot_pacman_conf = work_dir.join("pacman.chroot.conf");
+        write_file(&pacman_conf, &render_pacman_conf(&repos, &db_dir, &pkg_dir))?;
+        write_file(
+            &chroot_pacman_conf,
+            &render_pacman_conf(
+                &repos,
+                Path::new(CHROOT_REPO_DIR),
+                Path::new(CHROOT_PKG_DIR),
+            ),
+        )?;
+
+        tracing::info!(repos = ?repos, db_dir = %db_dir.display(), "Offline repository prepared");
+        Ok(Self {
+            source,
+            repos,
+            db_dir,
+            pkg_dir,
+            pacman_conf,
+            chroot_pacman_conf,
+            work_dir: work_dir.to_path_buf(),
+        })
+    }
+
+    /// Verify every package in `packages`, and every dependency, can be
+    /// installed from the local repositories.
+    ///
+    /// # Errors
+    ///
+    /// Lists each package that is missing from the databases or whose file
+    /// is missing from the package directory.
+    #[cfg(feature = "alpm")]
+    pub fn check_packages(&self, packages: &[String]) -> Result<()> {
+        use crate::package_manager::PackageManager;
+
+        // ALPM needs a root and db path of its own; the target is not mounted yet
+        let alpm_root = self.work_dir.join("alpm");
+        let db_path = alpm_root.join("var/lib/pacman");
+        fs::create_dir_all(&db_path)
+            .with_context(|| format!("Failed to create {}", db_path.display()))?;
+
+        let mut pm = PackageManager::from_pacman_conf(alpm_root.as_path(), &self.pacman_conf)
+            .context("Failed to load the offline pacman.conf")?;
+        pm.check_availability(packages, Some(&self.pkg_dir))?
+            .ensure_ok(&self.source.dir.display().to_string())?;
+        tracing::info!(
+            packages = packages.len(),
+            "All packages available from the offline source"
+        );
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alpm"))]
+    pub fn check_packages(&self, _packages: &[String]) -> Result<()> {
+        bail!("Offline installs require the `alpm` feature to verify the local packages")
+    }
+
+    /// Environment for `install.sh`.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let path = |p: &Path| p.display().to_string();
+        vec![
+            ("OFFLINE_INSTALL".to_string(), "Yes".to_string()),
+            ("OFFLINE_PACMAN_CONF".to_string(), path(&self.pacman_conf)),
+            (
+                "OFFLINE_CHROOT_PACMAN_CONF".to_string(),
+                path(&self.chroot_pacman_conf),
+            ),
+            ("OFFLINE_REPO_DIR".to_string(), path(&self.db_dir)),
+            ("OFFLINE_PKG_DIR".to_string(), path(&self.pkg_dir)),
+            (
+                "OFFLINE_CHROOT_REPO_DIR".to_string(),
+                CHROOT_REPO_DIR.to_string(),
+            ),
+            (
+                "OFFLINE_CHROOT_PKG_DIR".to_string(),
+                CHROOT_PKG_DIR.to_string(),
+            ),
+        ]
+    }
+}
+
+/// Detect, index and verify `dir` as the package source for `config`.
+///
+/// Every package `resolve_packages_for_hardware` selects must be available,
+/// so a missing package fails here rather than after partitioning.
+pub fn prepare_offline_install(
+    dir: &Path,
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Result<OfflineRepo> {
+    let source = OfflineSource::detect(dir)?;
+    let repo = OfflineRepo::prepare(source, &crate::capability::runtime_dir().join("offline"))?;
+    repo.check_packages(&resolve_packages_for_hardware(config, hardware))?;
+    Ok(repo)
+}
+
+/// Render a pacman.conf serving `repos` from `db_dir` with packages cached
+/// in `pkg_dir`.
+pub fn render_pacman_conf(repos: &[String], db_dir: &Path, pkg_dir: &Path) -> String {
+    let mut conf = format!(
+        "# Generated by archtui for an offline install\n\
+         [options]\n\
+         Architecture = auto\n\
+         CacheDir = {}/\n",
+        pkg_dir.display()
+    );
+    for repo in repos {
+        conf.push_str(&format!(
+            "\n[{}]\nSigLevel = {}\nServer = file://{}\n",
+            repo,
+            REPO_SIG_LEVEL,
+            db_dir.display()
+        ));
+    }
+    conf
+}
+
+/// Build `CACHE_REPO_NAME`'s database in `db_dir` from `packages`.
+fn index_cache(packages: &[PathBuf], db_dir: &Path) -> Result<()> {
+    // Always rebuild: the cache may have changed since the last run
+    if db_dir.exists() {
+        fs::remove_dir_all(db_dir)
+            .with_context(|| format!("Failed to remove {}", db_dir.display()))?;
+    }
+    fs::create_dir_all(db_dir).with_context(|| format!("Failed to create {}", db_dir.display()))?;
+
+    let db_file = db_dir.join(format!("{}.db.tar.gz", CACHE_REPO_NAME));
+    tracing::info!(packages = packages.len(), db = %db_file.display(), "Indexing package cache");
+    let output = Command::new("repo-add")
+        .arg("--quiet")
+        .arg(&db_file)
+        .args(packages)
+        .in_new_process_group()
+        .output()
+        .context("Failed to run repo-add")?;
+    if !output.status.success() {
+        bail!(
+            "repo-add failed for {}: {}",
+            db_file.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+// ============================================================================
+// Skipped Steps
+// ============================================================================
+
+/// Network-only steps an offline install of `config` leaves out, in install
+/// order. Empty when `config` has no offline source.
+pub fn skipped_steps(config: &InstallationConfig) -> Vec<String> {
+    if config.offline_source.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut steps = vec!["Mirror ranking (reflector)".to_string()];
+    if config.plymouth == Toggle::Yes {
+        steps.push("Plymouth theme download (built-in themes still apply)".to_string());
+    }
+    if config.aur_helper != AurHelper::None {
+        steps.push(format!("AUR helper {}", config.aur_helper));
+    }
+    if !config.additional_aur_packages.trim().is_empty() {
+        steps.push(format!(
+            "Additional AUR packages: {}",
+            config.additional_aur_packages.trim()
+        ));
+    }
+    if config.flatpak == Toggle::Yes {
+        steps.push("Flathub remote (flatpak itself is installed)".to_string());
+    }
+    if config.bootloader == Bootloader::Grub && config.grub_theme == Toggle::Yes {
+        steps.push(format!(
+            "GRUB theme download for {} (bundled copies still apply)",
+            config.grub_theme_selection
+        ));
+    }
+    if config.git_repository == Toggle::Yes {
+        steps.push(format!("Dotfiles from {}", config.git_repository_url));
+    }
+    steps
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_repo_and_cache() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let err = OfflineSource::detect(dir.path()).expect_err("empty dir is no source");
+        assert!(err.to_string().contains("neither"));
+
+        fs::write(dir.path().join("zsh-5.9-5-x86_64.pkg.tar.zst"), b"").expect("write"); // test: known-good input
+        fs::write(dir.path().join("zsh-5.9-5-x86_64.pkg.tar.zst.sig"), b"").expect("write"); // test: known-good input
+        let cache = OfflineSource::detect(dir.path()).expect("cache"); // test: known-good input
+        assert_eq!(cache.kind, SourceKind::Cache);
+        assert_eq!(cache.packages.len(), 1);
+
+        fs::write(dir.path().join("custom.db"), b"").expect("write"); // test: known-good input
+        fs::write(dir.path().join("custom.files"), b"").expect("write"); // test: known-good input
+        let repo = OfflineSource::detect(dir.path()).expect("repo"); // test: known-good input
+        assert_eq!(repo.kind, SourceKind::Repo(vec!["custom".to_string()]));
+    }
+
+    #[test]
+    fn test_detect_rejects_bad_paths() {
+        assert!(OfflineSource::detect(Path::new("relative/repo")).is_err());
+        assert!(OfflineSource::detect(Path::new("/nonexistent/archtui-repo")).is_err());
+    }
+
+    #[test]
+    fn test_prepare_repo_writes_both_confs() {
+        let dir = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        let work = tempfile::tempdir().expect("tempdir"); // test: known-good input
+        fs::write(dir.path().join("custom.db"), b"").expect("write"); // test: known-good input
+        let source = OfflineSource::detect(dir.path()).expect("repo"); // test: known-good input
+
+        let repo = OfflineRepo::prepare(source, work.path()).expect("prepare"); // test: known-good input
+        assert_eq!(repo.db_dir, dir.path());
+        assert_eq!(repo.pkg_dir, dir.path());
+
+        let host = fs::read_to_string(&repo.pacman_conf).expect("read"); // test: known-good input
+        assert!(host.contains("[custom]"));
+        assert!(host.contains(&format!("Server = file://{}", dir.path().display())));
+        let chroot = fs::read_to_string(&repo.chroot_pacman_conf).expect("read"); // test: known-good input
+        assert!(chroot.contains(&format!("Server = file://{}", CHROOT_REPO_DIR)));
+        assert!(chroot.contains(&format!("CacheDir = {}/", CHROOT_PKG_DIR)));
+
+        let env = repo.env_vars();
+        assert!(env.contains(&("OFFLINE_INSTALL".to_string(), "Yes".to_string())));
+        assert!(env.iter().any(|(k, _)| k == "OFFLINE_CHROOT_PACMAN_CONF"));
+    }
+
+    #[test]
+    fn test_render_pacman_conf() {
+        let conf = render_pacman_conf(
+            &["core".to_string(), "extra".to_string()],
+            Path::new("/srv/repo"),
+            Path::new("/srv/pkg"),
+        );
+        assert!(conf.contains("CacheDir = /srv/pkg/\n"));
+        assert!(conf.contains("[core]\nSigLevel = Optional TrustAll\nServer = file:///srv/repo\n"));
+        assert!(conf.contains("[extra]"));
+        assert!(!conf.contains("Include"));
+    }
+
+    #[test]
+    fn test_skipped_steps() {
+        let mut config = InstallationConfig {
+            aur_helper: AurHelper::Paru,
+            git_repository: Toggle::Yes,
+            git_repository_url: "https://example.com/dots.git".to_string(),
+            ..InstallationConfig::default()
+        };
+        assert!(skipped_steps(&config).is_empty());
+
+        config.offline_source = "/var/cache/pacman/pkg".to_string();
+        let steps = skipped_steps(&config);
+        assert_eq!(steps[0], "Mirror ranking (reflector)");
+        assert!(steps.contains(&"AUR helper paru".to_string()));
+        assert!(steps.iter().any(|s| s.starts_with("Dotfiles")));
+
+        config.additional_aur_packages = " yay-bin ".to_string();
+        let steps = skipped_steps(&config);
+        assert!(steps.contains(&"Additional AUR packages: yay-bin".to_string()));
+    }
+}
@@ -181,6 +181,7 @@ pub fn clone_dotfiles_safe(repo_url: &str, user: &str, branch: Option<&str>) ->
         target_user: user.to_string(),
         target_dir: None, // Default: user's home directory
         branch: branch.map(String::from),
+        confirm: true,
     };
 
     match run_script_safe(&args) {
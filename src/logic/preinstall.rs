@@ -141,15 +141,7 @@ pub fn rank_mirrors(config: &PreinstallConfig) -> MirrorRankResult {
     );
 
     // 3. Run reflector via update_mirrors.sh
-    let args = UpdateMirrorsArgs {
-        country: config.mirror_country.clone(),
-        limit: config.mirror_limit,
-        sort: config.mirror_sort,
-        protocol: Some("https".to_string()),
-        save: true,
-    };
-
-    match run_script_safe(&args) {
+    match run_script_safe(&mirror_args(config)) {
         Ok(output) => {
             if output.success {
                 tracing::info!("Mirror ranking completed successfully");
@@ -176,12 +168,24 @@ pub fn rank_mirrors(config: &PreinstallConfig) -> MirrorRankResult {
     }
 }
 
+/// The `update_mirrors.sh` invocation `rank_mirrors` makes.
+pub fn mirror_args(config: &PreinstallConfig) -> UpdateMirrorsArgs {
+    UpdateMirrorsArgs {
+        country: config.mirror_country.clone(),
+        limit: config.mirror_limit,
+        sort: config.mirror_sort,
+        protocol: Some("https".to_string()),
+        save: true,
+    }
+}
+
 /// Build a `PreinstallConfig` from an `InstallationConfig`.
 ///
 /// Maps the high-level installation config to pre-install parameters.
 pub fn preinstall_config_from(config: &crate::config_file::InstallationConfig) -> PreinstallConfig {
     PreinstallConfig {
-        skip_mirrors: false, // TUI sets this if user opts out
+        // Offline installs never use the mirrors; the TUI sets this if the user opts out
+        skip_mirrors: !config.offline_source.trim().is_empty(),
         mirror_country: if config.mirror_country.is_empty() {
             None
         } else {
@@ -7,7 +7,8 @@
 //!
 //! - **No hardcoded strings**: All package lists come from `profiles/mod.rs` constants
 //! - **Deduplication**: Output is deduplicated and sorted for deterministic results
-//! - **Pure logic**: No I/O, no side effects — only resolves names
+//! - **Pure logic**: No I/O, no side effects — only resolves names; detected
+//!   hardware is passed in (`hardware::HardwareInventory`)
 //!
 //! # Resolution Rules
 //!
@@ -15,6 +16,8 @@
 //! |--------------------|-------------|
 //! | `kernel`           | Kernel + headers packages |
 //! | `gpu_drivers`      | Driver-specific packages |
+//! | `gpu_drivers: Auto`| Packages of each detected GPU's family (`GPU_FAMILIES`) |
+//! | CPU vendor         | `intel-ucode` / `amd-ucode` (detected hardware only) |
 //! | `desktop_env`      | Profile packages (from Profile enum) |
 //! | `bootloader`       | Bootloader packages |
 //! | `multilib`         | Enables lib32-* packages |
@@ -26,8 +29,15 @@
 #![allow(dead_code)]
 
 use crate::config_file::InstallationConfig;
-use crate::profiles::{BASE_PACKAGES, Profile, bootloader_packages, gpu_packages, kernel_packages};
+use crate::hardware::{
+    CpuVendor, HardwareInventory, PCI_VENDOR_AMD, PCI_VENDOR_INTEL, PCI_VENDOR_NVIDIA,
+    PCI_VENDOR_QEMU, PCI_VENDOR_VIRTIO, PCI_VENDOR_VIRTUALBOX, PCI_VENDOR_VMWARE, PciDevice,
+};
+use crate::profiles::{
+    BASE_PACKAGES, Profile, bootloader_packages, gpu_packages, kernel_packages, microcode_packages,
+};
 use crate::types::*;
+use std::ops::RangeInclusive;
 
 // ============================================================================
 // Package Resolution
@@ -35,14 +45,24 @@ use crate::types::*;
 
 /// Resolve all packages to install based on the installation configuration.
 ///
+/// No hardware is known, so `GpuDriver::Auto` falls back to
+/// `gpu_packages::AUTO` and no microcode is selected. Use
+/// `resolve_packages_for_hardware` when the target machine has been probed.
+pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
+    resolve_packages_for_hardware(config, &HardwareInventory::default())
+}
+
+/// Resolve all packages to install for a configuration on detected hardware.
+///
 /// Collects packages from:
-/// 1. Base system packages (always installed)
-/// 2. Kernel packages (based on selected kernel)
-/// 3. GPU driver packages (based on selected GPU driver)
-/// 4. Bootloader packages
-/// 5. Desktop/WM profile packages
-/// 6. Additional user-specified packages
-/// 7. Flatpak (if enabled)
+/// 1. The pacstrap set (`resolve_pacstrap_packages`): base system, kernel,
+///    CPU microcode, filesystem/storage tools, bootloader, snapshot, swap and
+///    Secure Boot tools and the opt-in groups
+/// 2. GPU driver packages (based on selected GPU driver, or the detected
+///    GPUs for `Auto`)
+/// 3. Desktop/WM profile packages
+/// 4. Flatpak (if enabled)
+/// 5. Additional user-specified packages
 ///
 /// # Returns
 ///
@@ -53,7 +73,10 @@ use crate::types::*;
 /// - Install AUR packages: Those require a separate AUR helper flow
 /// - Validate package existence: That's ALPM's job at install time
 /// - Handle package conflicts: pacman/ALPM resolves dependencies
-pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
+pub fn resolve_packages_for_hardware(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Vec<String> {
     tracing::info!(
         kernel = %config.kernel,
         gpu = %config.gpu_drivers,
@@ -64,18 +87,79 @@ pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
     );
     let mut packages: Vec<&str> = Vec::new();
 
-    // 1. Base system — always installed
-    packages.extend_from_slice(BASE_PACKAGES);
+    // 1. GPU drivers
+    packages.extend(resolve_gpu_packages(config, hardware));
 
-    // 1b. Network manager (user choice)
-    packages.extend_from_slice(config.network_manager.packages());
+    // 2. Desktop/WM profile
+    let profile = desktop_to_profile(config.desktop_environment);
+    let profile_pkgs = profile.get_packages();
+    packages.extend_from_slice(profile_pkgs);
+    tracing::debug!(de = %config.desktop_environment, count = profile_pkgs.len(), "Resolved profile packages");
 
-    // 1c. Default editor (user choice; may be None)
-    if let Some(editor_pkg) = config.editor.package() {
-        packages.push(editor_pkg);
+    // 2a. Full-variant extras (only meta-group DEs have meaningful extras)
+    if profile.has_full_variant() && config.de_variant == crate::types::DeVariant::Full {
+        let extras = profile.get_full_extras();
+        packages.extend_from_slice(extras);
+        tracing::debug!(de = %config.desktop_environment, count = extras.len(), "Added Full-variant extras");
+    }
+
+    // 3. Flatpak
+    if config.flatpak == Toggle::Yes {
+        packages.push("flatpak");
+    }
+
+    // 4. FIDO2 hardware key support
+    if config.partitioning_strategy.uses_encryption()
+        && config.encryption_key_type != EncryptionKeyType::Password
+    {
+        packages.push("libfido2");
+    }
+
+    // 5. NTFS tools (alongside installs usually share the disk with Windows)
+    if config.partitioning_strategy == PartitionScheme::AutoAlongside {
+        packages.push("ntfs-3g");
+    }
+
+    // 6. Plymouth (if enabled)
+    if config.plymouth == Toggle::Yes {
+        packages.push("plymouth");
     }
 
-    // 2. Kernel
+    // 7. Additional user-specified packages
+    let additional = parse_package_list(&config.additional_packages);
+
+    // Deduplicate and sort
+    let mut result = resolve_pacstrap_packages(config, hardware);
+    result.extend(packages.iter().map(|s| s.to_string()));
+    result.extend(additional);
+    result.sort();
+    result.dedup();
+
+    tracing::info!(count = result.len(), "Package resolution complete");
+    tracing::debug!(packages = ?result, "Resolved package list");
+    result
+}
+
+/// Resolve the packages `install.sh` pacstraps into the target.
+///
+/// Exported to bash as `PACSTRAP_PACKAGES` (`package_env_vars`) and part of
+/// `resolve_packages_for_hardware`, so the list the pre-flight and offline
+/// checks verify is the list pacstrap installs. `build_pacstrap_package_list`
+/// in install.sh mirrors it for runs without archtui.
+///
+/// `BootMode::Auto` counts as UEFI: `efibootmgr` and `dosfstools` are only
+/// left out for an explicit BIOS install.
+pub fn resolve_pacstrap_packages(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Vec<String> {
+    let uefi = config.boot_mode != BootMode::Bios;
+    let mut packages: Vec<&str> = Vec::new();
+
+    // 1. Base system — always installed
+    packages.extend_from_slice(BASE_PACKAGES);
+
+    // 1b. Kernel
     let kernel_pkgs = match config.kernel {
         Kernel::Linux => kernel_packages::LINUX,
         Kernel::LinuxLts => kernel_packages::LINUX_LTS,
@@ -85,48 +169,15 @@ pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
     packages.extend_from_slice(kernel_pkgs);
     tracing::debug!(kernel = %config.kernel, count = kernel_pkgs.len(), "Resolved kernel packages");
 
-    // 3. GPU drivers
-    let gpu_pkgs = match config.gpu_drivers {
-        GpuDriver::Nvidia => gpu_packages::NVIDIA,
-        GpuDriver::NvidiaOpen => gpu_packages::NVIDIA_OPEN,
-        GpuDriver::Amd => gpu_packages::AMD,
-        GpuDriver::Intel => gpu_packages::INTEL,
-        GpuDriver::Nouveau => gpu_packages::NOUVEAU,
-        GpuDriver::None => gpu_packages::NONE,
-        GpuDriver::Auto => gpu_packages::AUTO,
-    };
-    // Only include lib32-* packages if multilib is enabled
-    if config.multilib == Toggle::Yes {
-        packages.extend_from_slice(gpu_pkgs);
-    } else {
-        packages.extend(gpu_pkgs.iter().filter(|p| !p.starts_with("lib32-")));
-    }
-
-    // 4. Bootloader
-    let boot_pkgs = match config.bootloader {
-        Bootloader::Grub => bootloader_packages::GRUB,
-        Bootloader::SystemdBoot => bootloader_packages::SYSTEMD_BOOT,
-        Bootloader::Refind => bootloader_packages::REFIND,
-        Bootloader::Limine => bootloader_packages::LIMINE,
-        Bootloader::Efistub => bootloader_packages::EFISTUB,
-    };
-    packages.extend_from_slice(boot_pkgs);
-    tracing::debug!(bootloader = %config.bootloader, count = boot_pkgs.len(), "Resolved bootloader packages");
-
-    // 5. Desktop/WM profile
-    let profile = desktop_to_profile(config.desktop_environment);
-    let profile_pkgs = profile.get_packages();
-    packages.extend_from_slice(profile_pkgs);
-    tracing::debug!(de = %config.desktop_environment, count = profile_pkgs.len(), "Resolved profile packages");
+    // 2. Network manager (user choice)
+    packages.extend_from_slice(config.network_manager.packages());
 
-    // 5a. Full-variant extras (only meta-group DEs have meaningful extras)
-    if profile.has_full_variant() && config.de_variant == crate::types::DeVariant::Full {
-        let extras = profile.get_full_extras();
-        packages.extend_from_slice(extras);
-        tracing::debug!(de = %config.desktop_environment, count = extras.len(), "Added Full-variant extras");
+    // 2b. Default editor (user choice; may be None)
+    if let Some(editor_pkg) = config.editor.package() {
+        packages.push(editor_pkg);
     }
 
-    // 5b. DE-tier plumbing: bluetooth + mDNS for any DE (TTY installs skip this).
+    // 2c. DE-tier plumbing: bluetooth + mDNS for any DE (TTY installs skip this).
     // Wiki: https://wiki.archlinux.org/title/Bluetooth and https://wiki.archlinux.org/title/Avahi
     if config.desktop_environment != DesktopEnvironment::None {
         packages.extend_from_slice(&["bluez", "bluez-utils", "avahi", "nss-mdns"]);
@@ -136,49 +187,45 @@ pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
         }
     }
 
-    // 6. Flatpak
-    if config.flatpak == Toggle::Yes {
-        packages.push("flatpak");
-    }
-
-    // 7. Encryption tools (if encryption is enabled)
-    if config.partitioning_strategy.uses_encryption() {
-        packages.push("cryptsetup");
-        // FIDO2 hardware key support
-        if config.encryption_key_type != EncryptionKeyType::Password {
-            packages.push("libfido2");
+    // 3. Filesystem tools for the root and home filesystems
+    for fs in [config.root_filesystem, config.home_filesystem] {
+        match fs {
+            Filesystem::Btrfs => packages.push("btrfs-progs"),
+            Filesystem::Xfs => packages.push("xfsprogs"),
+            Filesystem::Ext4 => packages.push("e2fsprogs"),
+            Filesystem::F2fs => packages.push("f2fs-tools"),
+            _ => {}
         }
     }
 
-    // 8. LVM tools (if LVM is enabled)
-    if config.partitioning_strategy.uses_lvm() {
-        packages.push("lvm2");
+    // 3b. FAT tools for the EFI system partition
+    if uefi {
+        packages.push("dosfstools");
     }
 
-    // 9. Btrfs tools (if Btrfs filesystem selected for root or home)
-    if config.root_filesystem == Filesystem::Btrfs || config.home_filesystem == Filesystem::Btrfs {
-        packages.push("btrfs-progs");
-    }
-
-    // 10. XFS tools (if XFS filesystem selected)
-    if config.root_filesystem == Filesystem::Xfs || config.home_filesystem == Filesystem::Xfs {
-        packages.push("xfsprogs");
+    // 4. Storage stack tools. A declared layout can encrypt any partition.
+    if config.encryption == AutoToggle::Yes
+        || config.partitioning_strategy.uses_encryption()
+        || config.partition_layout.iter().any(|p| p.encrypt)
+    {
+        packages.push("cryptsetup");
     }
-
-    // 10b. F2FS tools (if F2FS filesystem selected)
-    if config.root_filesystem == Filesystem::F2fs || config.home_filesystem == Filesystem::F2fs {
-        packages.push("f2fs-tools");
+    if config.partitioning_strategy.uses_lvm() {
+        packages.push("lvm2");
     }
-
-    // 11. FAT tools (always needed for EFI partition)
-    packages.push("dosfstools");
-
-    // 12. RAID tools (if RAID strategy selected)
     if config.partitioning_strategy.requires_raid() {
         packages.push("mdadm");
     }
 
-    // 13. Snapshot tool (if btrfs snapshots enabled)
+    // 5. Bootloader
+    let boot_pkgs = resolve_bootloader_packages(config);
+    tracing::debug!(bootloader = %config.bootloader, count = boot_pkgs.len(), "Resolved bootloader packages");
+    packages.extend(boot_pkgs);
+
+    // 6. CPU microcode (loaded early by the bootloader)
+    packages.extend_from_slice(resolve_microcode(hardware));
+
+    // 7. Snapshot tool (if btrfs snapshots enabled)
     if config.root_filesystem == Filesystem::Btrfs && config.btrfs_snapshots == Toggle::Yes {
         match config.snapshot_tool {
             crate::types::SnapshotTool::Snapper => {
@@ -195,41 +242,319 @@ pub fn resolve_packages(config: &InstallationConfig) -> Vec<String> {
         }
     }
 
-    // 14. OS prober (if enabled)
-    if config.os_prober == Toggle::Yes {
-        packages.push("os-prober");
+    // 8. zram-generator for compressed swap in RAM
+    if matches!(config.swap, SwapMode::Zram | SwapMode::ZramSwapfile) {
+        packages.push("zram-generator");
     }
 
-    // 15. Plymouth (if enabled)
-    if config.plymouth == Toggle::Yes {
-        packages.push("plymouth");
+    // 9. sbctl for Secure Boot
+    if config.secure_boot == Toggle::Yes {
+        packages.push("sbctl");
     }
 
-    // 16. Opt-in package groups (Network Tools / System Utilities / Dev Tools)
-    let opt_ins: Vec<String> = [
-        config.network_tools.as_str(),
-        config.system_utilities.as_str(),
-        config.dev_tools.as_str(),
-    ]
-    .iter()
-    .flat_map(|s| parse_package_list(s))
-    .collect();
-
-    // 17. Additional user-specified packages
-    let additional = parse_package_list(&config.additional_packages);
-
-    // Deduplicate and sort
+    // 10. Opt-in package groups (Network Tools / System Utilities / Dev Tools)
     let mut result: Vec<String> = packages.iter().map(|s| s.to_string()).collect();
-    result.extend(opt_ins);
-    result.extend(additional);
+    for group in [
+        &config.network_tools,
+        &config.system_utilities,
+        &config.dev_tools,
+    ] {
+        result.extend(parse_package_list(group));
+    }
     result.sort();
     result.dedup();
-
-    tracing::info!(count = result.len(), "Package resolution complete");
-    tracing::debug!(packages = ?result, "Resolved package list");
     result
 }
 
+// ============================================================================
+// Hardware-Based Resolution
+// ============================================================================
+
+/// A GPU family: the PCI IDs it covers and what it needs installed.
+#[derive(Debug)]
+pub struct GpuFamily {
+    pub vendor_id: u16,
+    /// PCI device IDs of the family
+    pub device_ids: RangeInclusive<u16>,
+    pub name: &'static str,
+    /// Kernel driver that binds the family by default
+    pub kernel_driver: &'static str,
+    pub packages: &'static [&'static str],
+}
+
+/// Every GPU family `GpuDriver::Auto` knows. Rows are checked in order and
+/// the first match wins, so a narrow range listed above a vendor's broad
+/// range carves out an exception; each vendor ends with a catch-all row.
+pub const GPU_FAMILIES: &[GpuFamily] = &[
+    // NVIDIA: the open kernel modules need GSP firmware (Turing+); the
+    // proprietary driver still covers Maxwell to Volta; older cards only
+    // have nouveau.
+    GpuFamily {
+        vendor_id: PCI_VENDOR_NVIDIA,
+        device_ids: 0x0000..=0x133f,
+        name: "Kepler or older",
+        kernel_driver: "nouveau",
+        packages: gpu_packages::NOUVEAU,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_NVIDIA,
+        device_ids: 0x1340..=0x1dff,
+        name: "Maxwell/Pascal/Volta",
+        kernel_driver: "nvidia",
+        packages: gpu_packages::NVIDIA,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_NVIDIA,
+        device_ids: 0x1e00..=0xffff,
+        name: "Turing or newer",
+        kernel_driver: "nvidia",
+        packages: gpu_packages::NVIDIA_OPEN,
+    },
+    // AMD: TeraScale and GCN 1.0/1.1 default to radeon, GCN 1.2+ to amdgpu.
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x6860..=0x687f,
+        name: "Vega 10",
+        kernel_driver: "amdgpu",
+        packages: gpu_packages::AMD,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x9870..=0x987f,
+        name: "Carrizo",
+        kernel_driver: "amdgpu",
+        packages: gpu_packages::AMD,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x98e0..=0x98ef,
+        name: "Stoney",
+        kernel_driver: "amdgpu",
+        packages: gpu_packages::AMD,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x1304..=0x131f,
+        name: "Kaveri",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x6600..=0x666f,
+        name: "Oland/Bonaire/Hainan",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x6700..=0x67bf,
+        name: "Northern Islands to Hawaii",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x6800..=0x68ff,
+        name: "Evergreen/Pitcairn/Cape Verde",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x7100..=0x72ff,
+        name: "R500",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x9400..=0x9fff,
+        name: "TeraScale/Kabini/Mullins",
+        kernel_driver: "radeon",
+        packages: gpu_packages::AMD_RADEON,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_AMD,
+        device_ids: 0x0000..=0xffff,
+        name: "GCN 1.2 or newer",
+        kernel_driver: "amdgpu",
+        packages: gpu_packages::AMD,
+    },
+    // Intel: Gen8 (Broadwell) and newer get Vulkan and the iHD media driver.
+    GpuFamily {
+        vendor_id: PCI_VENDOR_INTEL,
+        device_ids: 0x22b0..=0x22bf,
+        name: "Braswell/Cherry Trail",
+        kernel_driver: "i915",
+        packages: gpu_packages::INTEL_MODERN,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_INTEL,
+        device_ids: 0x0000..=0x0fff,
+        name: "Gen7.5 or older",
+        kernel_driver: "i915",
+        packages: gpu_packages::INTEL_LEGACY,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_INTEL,
+        device_ids: 0x2000..=0x2fff,
+        name: "GMA",
+        kernel_driver: "i915",
+        packages: gpu_packages::INTEL_LEGACY,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_INTEL,
+        device_ids: 0x0000..=0xffff,
+        name: "Gen8 or newer",
+        kernel_driver: "i915",
+        packages: gpu_packages::INTEL_MODERN,
+    },
+    // Virtual machines
+    GpuFamily {
+        vendor_id: PCI_VENDOR_VMWARE,
+        device_ids: 0x0000..=0xffff,
+        name: "SVGA",
+        kernel_driver: "vmwgfx",
+        packages: gpu_packages::VMWARE,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_VIRTIO,
+        device_ids: 0x0000..=0xffff,
+        name: "virtio-gpu",
+        kernel_driver: "virtio_gpu",
+        packages: gpu_packages::AUTO,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_QEMU,
+        device_ids: 0x0000..=0xffff,
+        name: "standard VGA",
+        kernel_driver: "bochs",
+        packages: gpu_packages::AUTO,
+    },
+    GpuFamily {
+        vendor_id: PCI_VENDOR_VIRTUALBOX,
+        device_ids: 0x0000..=0xffff,
+        name: "VGA",
+        kernel_driver: "vboxvideo",
+        packages: gpu_packages::VIRTUALBOX,
+    },
+];
+
+/// Family of `gpu` in `GPU_FAMILIES`, `None` for unknown vendors.
+pub fn gpu_family(gpu: &PciDevice) -> Option<&'static GpuFamily> {
+    GPU_FAMILIES
+        .iter()
+        .find(|f| f.vendor_id == gpu.vendor_id && f.device_ids.contains(&gpu.device_id))
+}
+
+/// Bootloader packages: the bootloader's own, `efibootmgr` to register it
+/// with the firmware (not on BIOS, not for Limine), and os-prober with
+/// ntfs-3g when GRUB should find other systems.
+pub fn resolve_bootloader_packages(config: &InstallationConfig) -> Vec<&'static str> {
+    let mut packages = match config.bootloader {
+        Bootloader::Grub => bootloader_packages::GRUB,
+        Bootloader::SystemdBoot => bootloader_packages::SYSTEMD_BOOT,
+        Bootloader::Refind => bootloader_packages::REFIND,
+        Bootloader::Limine => bootloader_packages::LIMINE,
+        Bootloader::Efistub => bootloader_packages::EFISTUB,
+    }
+    .to_vec();
+    let uefi = config.boot_mode != BootMode::Bios;
+    if uefi && config.bootloader != Bootloader::Limine && !packages.contains(&"efibootmgr") {
+        packages.push("efibootmgr");
+    }
+    if config.bootloader == Bootloader::Grub && config.os_prober == Toggle::Yes {
+        packages.extend_from_slice(&["os-prober", "ntfs-3g"]);
+    }
+    packages
+}
+
+/// GPU driver packages for the configured driver, or the detected GPUs for
+/// `GpuDriver::Auto`. lib32-* packages are only kept when multilib is enabled.
+pub fn resolve_gpu_packages(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Vec<&'static str> {
+    let gpu_pkgs = match config.gpu_drivers {
+        GpuDriver::Nvidia => gpu_packages::NVIDIA.to_vec(),
+        GpuDriver::NvidiaOpen => gpu_packages::NVIDIA_OPEN.to_vec(),
+        GpuDriver::Amd => gpu_packages::AMD.to_vec(),
+        GpuDriver::Intel => gpu_packages::INTEL.to_vec(),
+        GpuDriver::Nouveau => gpu_packages::NOUVEAU.to_vec(),
+        GpuDriver::None => gpu_packages::NONE.to_vec(),
+        GpuDriver::Auto => resolve_auto_gpu_packages(hardware),
+    };
+    if config.multilib == Toggle::Yes {
+        gpu_pkgs
+    } else {
+        gpu_pkgs
+            .into_iter()
+            .filter(|p| !p.starts_with("lib32-"))
+            .collect()
+    }
+}
+
+/// Environment for `install.sh` carrying the resolved package lists:
+/// `PACSTRAP_PACKAGES` (the whole pacstrap set, CPU microcode included) and
+/// `GPU_PACKAGES` (installed by `install_gpu_drivers` in the chroot).
+///
+/// These are the same lists `resolve_packages_for_hardware` returns, so the
+/// packages checked before the install are the packages bash installs.
+pub fn package_env_vars(
+    config: &InstallationConfig,
+    hardware: &HardwareInventory,
+) -> Vec<(String, String)> {
+    vec![
+        (
+            "PACSTRAP_PACKAGES".to_string(),
+            resolve_pacstrap_packages(config, hardware).join(" "),
+        ),
+        (
+            "GPU_PACKAGES".to_string(),
+            resolve_gpu_packages(config, hardware).join(" "),
+        ),
+    ]
+}
+
+/// Microcode package for the detected CPU vendor.
+fn resolve_microcode(hardware: &HardwareInventory) -> &'static [&'static str] {
+    match hardware.cpu.as_ref().map(|cpu| cpu.vendor) {
+        Some(CpuVendor::Intel) => microcode_packages::INTEL,
+        Some(CpuVendor::Amd) => microcode_packages::AMD,
+        Some(CpuVendor::Other) | None => &[],
+    }
+}
+
+/// Packages for `GpuDriver::Auto`: each detected GPU's family packages, plus
+/// PRIME offloading when an NVIDIA-driven GPU sits next to another GPU.
+fn resolve_auto_gpu_packages(hardware: &HardwareInventory) -> Vec<&'static str> {
+    if hardware.gpus.is_empty() {
+        return gpu_packages::AUTO.to_vec();
+    }
+
+    let mut packages = Vec::new();
+    let mut nvidia_driven = false;
+    for gpu in &hardware.gpus {
+        match gpu_family(gpu) {
+            Some(family) => {
+                tracing::debug!(gpu = %gpu, family = family.name, "Resolved GPU family");
+                nvidia_driven |= family.kernel_driver == "nvidia";
+                packages.extend_from_slice(family.packages);
+            }
+            None => {
+                tracing::warn!(gpu = %gpu, "Unknown GPU vendor, installing generic mesa");
+                packages.extend_from_slice(gpu_packages::AUTO);
+            }
+        }
+    }
+    if nvidia_driven && hardware.gpus.len() > 1 {
+        tracing::debug!("Hybrid graphics detected, adding PRIME offload");
+        packages.extend_from_slice(gpu_packages::NVIDIA_PRIME);
+    }
+    packages
+}
+
 // ============================================================================
 // Service Resolution
 // ============================================================================
@@ -1043,6 +1368,7 @@ mod tests {
                 PartitionScheme::AutoRaidLvmLuks,
                 vec!["cryptsetup", "lvm2", "mdadm"],
             ),
+            (PartitionScheme::AutoAlongside, vec!["ntfs-3g"]),
             (PartitionScheme::Manual, vec![]),
             (PartitionScheme::PreMounted, vec![]),
         ];
@@ -1170,4 +1496,341 @@ mod tests {
         assert!(packages.contains(&"snap-pac".to_string()));
         assert!(!packages.contains(&"grub-btrfs".to_string()));
     }
+
+    /// Helper: a display controller with the given PCI IDs
+    fn gpu(vendor_id: u16, device_id: u16) -> PciDevice {
+        PciDevice {
+            address: format!("0000:00:{:02x}.0", device_id & 0x1f),
+            vendor_id,
+            device_id,
+            class: 0x030000,
+            driver: None,
+        }
+    }
+
+    /// Helper: detected hardware with a CPU vendor and GPUs
+    fn hardware(vendor: CpuVendor, gpus: Vec<PciDevice>) -> HardwareInventory {
+        HardwareInventory {
+            cpu: Some(crate::hardware::CpuInfo {
+                vendor,
+                model: "test".to_string(),
+                threads: 4,
+            }),
+            gpus,
+            ..HardwareInventory::default()
+        }
+    }
+
+    #[test]
+    fn test_gpu_family_per_device_family() {
+        // (vendor, device, package only that family's row provides)
+        let cases = [
+            (PCI_VENDOR_NVIDIA, 0x1180, "xf86-video-nouveau"), // GTX 680 (Kepler)
+            (PCI_VENDOR_NVIDIA, 0x1b80, "nvidia-dkms"),        // GTX 1080 (Pascal)
+            (PCI_VENDOR_NVIDIA, 0x1f08, "nvidia-open-dkms"),   // RTX 2060 (Turing)
+            (PCI_VENDOR_NVIDIA, 0x2684, "nvidia-open-dkms"),   // RTX 4090 (Ada)
+            (PCI_VENDOR_AMD, 0x9830, "xf86-video-ati"),        // Kabini
+            (PCI_VENDOR_AMD, 0x6898, "xf86-video-ati"),        // HD 5870 (Evergreen)
+            (PCI_VENDOR_AMD, 0x6798, "xf86-video-ati"),        // HD 7970 (GCN 1.0)
+            (PCI_VENDOR_AMD, 0x687f, "vulkan-radeon"),         // Vega 64
+            (PCI_VENDOR_AMD, 0x98e4, "vulkan-radeon"),         // Stoney
+            (PCI_VENDOR_AMD, 0x67df, "vulkan-radeon"),         // RX 480 (Polaris)
+            (PCI_VENDOR_AMD, 0x73bf, "vulkan-radeon"),         // RX 6800 (RDNA 2)
+            (PCI_VENDOR_INTEL, 0x0412, "libva-intel-driver"),  // HD 4600 (Haswell)
+            (PCI_VENDOR_INTEL, 0x2e22, "libva-intel-driver"),  // GMA X4500
+            (PCI_VENDOR_INTEL, 0x22b0, "intel-media-driver"),  // Braswell
+            (PCI_VENDOR_INTEL, 0x5917, "intel-media-driver"),  // UHD 620
+            (PCI_VENDOR_INTEL, 0x56a0, "vulkan-intel"),        // Arc A770
+            (PCI_VENDOR_VMWARE, 0x0405, "xf86-video-vmware"),
+            (PCI_VENDOR_VIRTIO, 0x1050, "mesa"),
+            (PCI_VENDOR_QEMU, 0x1111, "mesa"),
+            (PCI_VENDOR_VIRTUALBOX, 0xbeef, "virtualbox-guest-utils"),
+        ];
+        for (vendor, device, pkg) in cases {
+            let family = gpu_family(&gpu(vendor, device))
+                .unwrap_or_else(|| panic!("{:04x}:{:04x} must have a family", vendor, device));
+            assert!(
+                family.packages.contains(&pkg),
+                "{:04x}:{:04x} ({}) must include {}",
+                vendor,
+                device,
+                family.name,
+                pkg
+            );
+        }
+        assert_eq!(
+            gpu_family(&gpu(PCI_VENDOR_NVIDIA, 0x1b80)).map(|f| f.name),
+            Some("Maxwell/Pascal/Volta")
+        );
+
+        // ASPEED BMC graphics: unknown vendor
+        assert!(gpu_family(&gpu(0x1a03, 0x2000)).is_none());
+    }
+
+    #[test]
+    fn test_gpu_families_end_with_catch_all() {
+        for vendor in [PCI_VENDOR_NVIDIA, PCI_VENDOR_AMD, PCI_VENDOR_INTEL] {
+            let last = GPU_FAMILIES
+                .iter()
+                .rfind(|f| f.vendor_id == vendor)
+                .expect("vendor has families"); // test: known-good input
+            assert_eq!(*last.device_ids.end(), 0xffff, "{:04x}", vendor);
+        }
+    }
+
+    #[test]
+    fn test_resolve_auto_gpu_from_detected_hardware() {
+        let mut config = test_config();
+        config.gpu_drivers = GpuDriver::Auto;
+        config.multilib = Toggle::No;
+
+        let amd = hardware(CpuVendor::Amd, vec![gpu(PCI_VENDOR_AMD, 0x73bf)]);
+        let packages = resolve_packages_for_hardware(&config, &amd);
+        assert!(packages.contains(&"vulkan-radeon".to_string()));
+        assert!(packages.contains(&"xf86-video-amdgpu".to_string()));
+        assert!(!packages.iter().any(|p| p.starts_with("lib32-")));
+        assert!(!packages.iter().any(|p| p.starts_with("nvidia")));
+
+        // Nothing detected: generic mesa, as before
+        let packages = resolve_packages_for_hardware(&config, &HardwareInventory::default());
+        assert!(packages.contains(&"mesa".to_string()));
+        assert!(!packages.contains(&"vulkan-radeon".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_auto_gpu_hybrid_graphics() {
+        let mut config = test_config();
+        config.gpu_drivers = GpuDriver::Auto;
+        config.multilib = Toggle::Yes;
+
+        // Intel iGPU + RTX 3060 laptop
+        let hybrid = hardware(
+            CpuVendor::Intel,
+            vec![
+                gpu(PCI_VENDOR_INTEL, 0x9a49),
+                gpu(PCI_VENDOR_NVIDIA, 0x2520),
+            ],
+        );
+        let packages = resolve_packages_for_hardware(&config, &hybrid);
+        assert!(packages.contains(&"vulkan-intel".to_string()));
+        assert!(packages.contains(&"nvidia-open-dkms".to_string()));
+        assert!(packages.contains(&"lib32-nvidia-utils".to_string()));
+        assert!(packages.contains(&"nvidia-prime".to_string()));
+
+        // Intel iGPU + Kepler dGPU: nouveau needs no PRIME helper
+        let nouveau = hardware(
+            CpuVendor::Intel,
+            vec![
+                gpu(PCI_VENDOR_INTEL, 0x0416),
+                gpu(PCI_VENDOR_NVIDIA, 0x1292),
+            ],
+        );
+        let packages = resolve_packages_for_hardware(&config, &nouveau);
+        assert!(packages.contains(&"xf86-video-nouveau".to_string()));
+        assert!(!packages.contains(&"nvidia-prime".to_string()));
+
+        // A lone NVIDIA card is not hybrid
+        let desktop = hardware(CpuVendor::Amd, vec![gpu(PCI_VENDOR_NVIDIA, 0x2684)]);
+        let packages = resolve_packages_for_hardware(&config, &desktop);
+        assert!(!packages.contains(&"nvidia-prime".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_explicit_gpu_driver_ignores_detected_gpus() {
+        let mut config = test_config();
+        config.gpu_drivers = GpuDriver::Nouveau;
+        let hw = hardware(CpuVendor::Intel, vec![gpu(PCI_VENDOR_NVIDIA, 0x2684)]);
+        let packages = resolve_packages_for_hardware(&config, &hw);
+
+        assert!(packages.contains(&"xf86-video-nouveau".to_string()));
+        assert!(!packages.contains(&"nvidia-open-dkms".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_microcode_by_cpu_vendor() {
+        let config = test_config();
+        let cases = [
+            (CpuVendor::Intel, Some("intel-ucode")),
+            (CpuVendor::Amd, Some("amd-ucode")),
+            (CpuVendor::Other, None),
+        ];
+        for (vendor, expected) in cases {
+            let packages = resolve_packages_for_hardware(&config, &hardware(vendor, Vec::new()));
+            let ucode: Vec<&String> = packages.iter().filter(|p| p.ends_with("-ucode")).collect();
+            assert_eq!(ucode.first().map(|p| p.as_str()), expected, "{:?}", vendor);
+            assert!(ucode.len() <= 1);
+        }
+
+        // No hardware probed: no microcode
+        let packages = resolve_packages(&config);
+        assert!(!packages.iter().any(|p| p.ends_with("-ucode")));
+    }
+
+    #[test]
+    fn test_package_env_vars_match_resolved_packages() {
+        let mut config = test_config();
+        config.gpu_drivers = GpuDriver::Auto;
+        config.multilib = Toggle::No;
+        let hybrid = hardware(
+            CpuVendor::Amd,
+            vec![gpu(PCI_VENDOR_AMD, 0x1638), gpu(PCI_VENDOR_NVIDIA, 0x2520)],
+        );
+
+        let env: std::collections::HashMap<_, _> =
+            package_env_vars(&config, &hybrid).into_iter().collect();
+        let gpu_env: Vec<&str> = env["GPU_PACKAGES"].split_whitespace().collect();
+        assert!(gpu_env.contains(&"nvidia-open-dkms"));
+        assert!(gpu_env.contains(&"nvidia-prime"));
+        assert!(!gpu_env.iter().any(|p| p.starts_with("lib32-")));
+        let pacstrap_env: Vec<&str> = env["PACSTRAP_PACKAGES"].split_whitespace().collect();
+        assert!(pacstrap_env.contains(&"amd-ucode"));
+        assert!(pacstrap_env.contains(&"sof-firmware"));
+
+        // Everything exported is part of the resolved (pre-flight checked) list
+        let packages = resolve_packages_for_hardware(&config, &hybrid);
+        for pkg in gpu_env.iter().chain(&pacstrap_env) {
+            assert!(packages.contains(&pkg.to_string()), "{}", pkg);
+        }
+
+        // No GPU probed: generic mesa
+        let env: std::collections::HashMap<_, _> =
+            package_env_vars(&config, &HardwareInventory::default())
+                .into_iter()
+                .collect();
+        assert_eq!(env["GPU_PACKAGES"], "mesa");
+    }
+
+    /// Configs covering every branch of the pacstrap list.
+    fn pacstrap_matrix() -> Vec<InstallationConfig> {
+        let mut configs = vec![test_config()];
+
+        let mut bios = test_config();
+        bios.boot_mode = BootMode::Bios;
+        bios.bootloader = Bootloader::Grub;
+        bios.os_prober = Toggle::Yes;
+        bios.network_manager = crate::types::NetworkManager::Iwd;
+        bios.editor = Editor::None;
+        configs.push(bios);
+
+        let mut storage = test_config();
+        storage.boot_mode = BootMode::Uefi;
+        storage.partitioning_strategy = PartitionScheme::AutoRaidLvmLuks;
+        storage.root_filesystem = Filesystem::Xfs;
+        storage.home_filesystem = Filesystem::F2fs;
+        storage.bootloader = Bootloader::Refind;
+        storage.kernel = Kernel::LinuxLts;
+        configs.push(storage);
+
+        let mut desktop = test_config();
+        desktop.boot_mode = BootMode::Uefi;
+        desktop.root_filesystem = Filesystem::Btrfs;
+        desktop.btrfs_snapshots = Toggle::Yes;
+        desktop.snapshot_tool = crate::types::SnapshotTool::Snapper;
+        desktop.bootloader = Bootloader::Grub;
+        desktop.swap = SwapMode::ZramSwapfile;
+        desktop.secure_boot = Toggle::Yes;
+        desktop.desktop_environment = DesktopEnvironment::Kde;
+        desktop.editor = Editor::Neovim;
+        desktop.dev_tools = "base-devel  cmake".to_string();
+        configs.push(desktop);
+
+        let mut limine = test_config();
+        limine.boot_mode = BootMode::Uefi;
+        limine.bootloader = Bootloader::Limine;
+        limine.encryption = AutoToggle::Yes;
+        limine.root_filesystem = Filesystem::Btrfs;
+        limine.btrfs_snapshots = Toggle::Yes;
+        limine.snapshot_tool = crate::types::SnapshotTool::Timeshift;
+        limine.desktop_environment = DesktopEnvironment::Sway;
+        limine.network_manager = crate::types::NetworkManager::Dhcpcd;
+        configs.push(limine);
+
+        let mut efistub = test_config();
+        efistub.boot_mode = BootMode::Uefi;
+        efistub.bootloader = Bootloader::Efistub;
+        efistub.kernel = Kernel::LinuxZen;
+        efistub.swap = SwapMode::Zram;
+        configs.push(efistub);
+
+        configs
+    }
+
+    #[test]
+    fn test_pacstrap_packages_are_resolved_packages() {
+        let hw = hardware(CpuVendor::Intel, vec![gpu(PCI_VENDOR_INTEL, 0x9a49)]);
+        for config in pacstrap_matrix() {
+            let resolved = resolve_packages_for_hardware(&config, &hw);
+            for pkg in resolve_pacstrap_packages(&config, &hw) {
+                assert!(resolved.contains(&pkg), "{} not resolved", pkg);
+            }
+        }
+
+        // BIOS: no ESP tools, no firmware boot entry
+        let mut bios = test_config();
+        bios.boot_mode = BootMode::Bios;
+        let packages = resolve_pacstrap_packages(&bios, &hw);
+        assert!(!packages.contains(&"efibootmgr".to_string()));
+        assert!(!packages.contains(&"dosfstools".to_string()));
+
+        // A declared layout with an encrypted partition needs cryptsetup
+        let mut layout = test_config();
+        layout.partitioning_strategy = PartitionScheme::Manual;
+        layout.partition_layout = vec![crate::config_file::LayoutPartition {
+            size: "Remaining".to_string(),
+            partition_type: crate::scripts::disk::PartitionType::Linux,
+            filesystem: Some(Filesystem::Ext4),
+            label: None,
+            mountpoint: Some("/".to_string()),
+            mount_options: None,
+            encrypt: true,
+        }];
+        assert!(resolve_pacstrap_packages(&layout, &hw).contains(&"cryptsetup".to_string()));
+    }
+
+    /// install.sh's own list (used without archtui) and the resolver's list
+    /// that archtui passes as `PACSTRAP_PACKAGES` must not drift apart.
+    #[test]
+    fn test_bash_pacstrap_list_matches_resolver() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        // Both sides read the host's /proc/cpuinfo for microcode
+        let hw = HardwareInventory::probe(std::path::Path::new("/"));
+        let script = r#"
+            set -euo pipefail
+            source scripts/config_loader.sh
+            eval "$(sed -n '/^build_pacstrap_package_list() {/,/^}/p' scripts/install.sh)"
+            SWAP_MODE="$(normalize_swap_mode "$SWAP")"
+            build_pacstrap_package_list 2>/dev/null | sed '/^$/d' | sort -u
+        "#;
+        for config in pacstrap_matrix() {
+            // install.sh resolves Auto to the firmware before pacstrap
+            let boot_mode = if config.boot_mode == BootMode::Bios {
+                "BIOS"
+            } else {
+                "UEFI"
+            };
+            let output = std::process::Command::new("/bin/bash")
+                .arg("-c")
+                .arg(script)
+                .current_dir(root)
+                .envs(config.to_env_vars())
+                .env("BOOT_MODE", boot_mode)
+                .output()
+                .expect("bash runs"); // test: known-good input
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let bash: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect();
+            assert_eq!(
+                bash,
+                resolve_pacstrap_packages(&config, &hw),
+                "{:?}",
+                config
+            );
+        }
+    }
 }
@@ -2,23 +2,29 @@
 //!
 //! A clean, modular TUI for Arch Linux installation with proper separation of concerns.
 mod app;
+mod capability;
 mod cli;
 mod components;
 mod config;
 mod config_file;
+mod config_layers;
+mod config_migrations;
 mod engine;
 mod error;
 mod hardware;
 mod input;
+mod install_checkpoint;
 mod install_state;
 mod installer;
 mod logic;
 mod option_help;
+mod package_events;
 #[cfg(feature = "alpm")]
 mod package_manager;
 mod package_utils;
 mod process_guard;
 mod profiles;
+mod script_events;
 mod script_manifest;
 mod script_runner;
 mod script_traits;
@@ -27,18 +33,22 @@ mod scrolling;
 mod theme;
 mod types;
 mod ui;
+mod watchdog;
 
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io::stdout;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
 use anyhow::Context;
 use tracing::{debug, error, info};
 
+use crate::capability::CapabilityScheduler;
 use crate::cli::Cli;
 use crate::config_file::InstallationConfig;
 use crate::process_guard::{ChildRegistry, CommandProcessGroup};
-use crate::script_runner::run_script_safe;
+use crate::script_events::ToolJsonOutput;
+use crate::script_runner::{OutputStream, run_script_streaming};
 use crate::script_traits::ScriptArgs;
 use crate::scripts::config::{GenFstabArgs, UserAddArgs};
 use crate::scripts::disk::{
@@ -58,6 +68,9 @@ use crate::scripts::user::{GroupsArgs, ResetPasswordArgs, SecurityAuditArgs, Ssh
 use crate::scripts::user_ops::{InstallAurHelperArgs, UserRunArgs};
 use crate::types::AurHelper;
 
+/// Set from `--json` at startup; read by `execute_tool`.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
 /// Initialize the tracing subscriber for CLI mode (writes to stderr)
 fn init_logger_cli() {
     use tracing_subscriber::{EnvFilter, fmt};
@@ -69,6 +82,8 @@ fn init_logger_cli() {
         .with_file(true)
         .with_line_number(true)
         .with_target(false)
+        // stdout is reserved for command output (`--json`, dry-run reports)
+        .with_writer(std::io::stderr)
         .init();
 }
 
@@ -103,6 +118,9 @@ fn main() -> anyhow::Result<()> {
     // Determine if we're entering TUI mode (logs go to file instead of stderr)
     let is_tui_mode = match &cli.command {
         Some(crate::cli::Commands::Validate { .. }) => false,
+        Some(crate::cli::Commands::Sandbox { .. }) => false,
+        Some(crate::cli::Commands::ApplyStorage { .. }) => false,
+        Some(crate::cli::Commands::Config { .. }) => false,
         Some(crate::cli::Commands::Tools { .. }) => false,
         Some(crate::cli::Commands::Install { config, .. }) => config.is_none(),
         None => true,
@@ -139,11 +157,20 @@ fn main() -> anyhow::Result<()> {
     }
     debug!("Scripts directory verified: {:?}", scripts_dir);
 
+    // JSON mode keeps stdout for the result document
+    if cli.json {
+        JSON_OUTPUT.store(true, Relaxed);
+    }
+
     // Enable dry-run mode if requested
     if cli.dry_run {
         script_traits::enable_dry_run();
         info!("Dry-run mode enabled - destructive operations will be skipped");
-        println!("Running in DRY-RUN mode: destructive operations will be skipped");
+        if cli.json {
+            eprintln!("Running in DRY-RUN mode: destructive operations will be skipped");
+        } else {
+            println!("Running in DRY-RUN mode: destructive operations will be skipped");
+        }
     }
 
     // Enable verbose logging for bash scripts (RUST_LOG already set above before logger init)
@@ -154,23 +181,38 @@ fn main() -> anyhow::Result<()> {
     }
 
     match cli.command {
-        Some(crate::cli::Commands::Validate { config }) => {
+        Some(crate::cli::Commands::Validate {
+            config,
+            show_merged,
+            strict,
+        }) => {
             info!("Validating configuration file: {:?}", config);
-            match InstallationConfig::load_from_file(&config) {
-                Ok(config) => match config.validate() {
+            match config_layers::LayeredConfig::load(&config) {
+                Ok(layered) => match validate_layered(&layered, strict) {
                     Ok(_) => {
                         info!("Configuration validation successful");
-                        println!("✓ Configuration file is valid (all fields validated)");
+                        if show_merged {
+                            print_merged_config(&layered)?;
+                        }
+                        // stdout carries the merged document in JSON mode
+                        if JSON_OUTPUT.load(Relaxed) {
+                            eprintln!("✓ Configuration file is valid (all fields validated)");
+                        } else {
+                            println!("✓ Configuration file is valid (all fields validated)");
+                        }
                     }
                     Err(e) => {
-                        error!("Configuration validation failed: {}", e);
-                        eprintln!("✗ Configuration validation failed: {}", e);
+                        error!("Configuration validation failed: {:#}", e);
+                        if show_merged {
+                            print_merged_config(&layered)?;
+                        }
+                        eprintln!("✗ Configuration validation failed: {:#}", e);
                         std::process::exit(1);
                     }
                 },
                 Err(e) => {
-                    error!("Failed to load configuration file: {}", e);
-                    eprintln!("✗ Failed to load configuration file: {}", e);
+                    error!("Failed to load configuration file: {:#}", e);
+                    eprintln!("✗ Failed to load configuration file: {:#}", e);
                     std::process::exit(1);
                 }
             }
@@ -178,13 +220,15 @@ fn main() -> anyhow::Result<()> {
         Some(crate::cli::Commands::Install {
             config,
             save_config,
+            resume,
+            offline,
         }) => {
             if let Some(config_path) = config {
                 info!(
                     "Running headless installation with config: {:?}",
                     config_path
                 );
-                run_installer_with_config(&config_path)?;
+                run_installer_with_config(&config_path, resume, offline.as_deref())?;
             } else if let Some(save_path) = save_config {
                 info!(
                     "Running TUI installer with config save path: {:?}",
@@ -196,6 +240,47 @@ fn main() -> anyhow::Result<()> {
                 run_tui_installer()?;
             }
         }
+        Some(crate::cli::Commands::Sandbox {
+            config,
+            image,
+            size,
+            package_cache,
+            keep,
+        }) => {
+            info!("Running sandbox install with config: {:?}", config);
+            let options = engine::sandbox::SandboxOptions {
+                image,
+                size_mib: size,
+                package_cache,
+                keep_image: keep,
+                ..Default::default()
+            };
+            run_sandbox_with_config(&config, &options)?;
+        }
+        Some(crate::cli::Commands::ApplyStorage { plan, state }) => {
+            info!("Applying storage plan: {:?}", plan);
+            run_apply_storage(&plan, &state)?;
+        }
+        Some(crate::cli::Commands::Config { action }) => match action {
+            crate::cli::ConfigCommands::Convert {
+                input,
+                output,
+                force,
+            } => {
+                info!("Converting configuration {:?} -> {:?}", input, output);
+                convert_config(&input, &output, force)?;
+            }
+            crate::cli::ConfigCommands::Migrate { config, write } => {
+                info!("Upgrading configuration {:?}", config);
+                migrate_config_file(&config, write)?;
+            }
+            crate::cli::ConfigCommands::Schema => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&InstallationConfig::json_schema())?
+                );
+            }
+        },
         Some(crate::cli::Commands::Tools { tool }) => {
             debug!("Running tool command");
             run_tool_command(&tool)?;
@@ -209,6 +294,86 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Report upgrades, defaults and unknown fields, then validate the config.
+/// With `strict`, unknown fields fail validation instead of being ignored.
+fn validate_layered(layered: &config_layers::LayeredConfig, strict: bool) -> anyhow::Result<()> {
+    for (path, report) in &layered.migrations {
+        eprintln!(
+            "⚠ {}: upgraded from config_version {} to {}",
+            path.display(),
+            report.from_version,
+            config_migrations::CURRENT_VERSION
+        );
+        for change in &report.changes {
+            eprintln!("    {}", change);
+        }
+    }
+    let defaulted = layered.defaulted_fields()?;
+    if !defaulted.is_empty() {
+        eprintln!("ℹ Not set, using defaults: {}", defaulted.join(", "));
+    }
+
+    let unknown = layered.unknown_field_errors();
+    if strict && !unknown.is_empty() {
+        anyhow::bail!("{} (strict mode)", unknown.join("; "));
+    }
+    for message in &unknown {
+        eprintln!("⚠ Ignoring {} (use --strict to reject)", message);
+    }
+    // Layouts that cannot fit this machine's disks fail here, not at install start
+    let hardware = hardware::HardwareInventory::probe(std::path::Path::new("/"));
+    layered.config.validate_for_hardware(&hardware)
+}
+
+/// Upgrade one config file to the current `config_version` (`config migrate`)
+fn migrate_config_file(path: &std::path::Path, write: bool) -> anyhow::Result<()> {
+    use crate::config_migrations::CURRENT_VERSION;
+
+    let mut fields = config_layers::read_fields(path)?;
+    let report = config_migrations::migrate(&mut fields)?;
+    if report.from_version == CURRENT_VERSION {
+        println!(
+            "✓ {} is already at config_version {}",
+            path.display(),
+            CURRENT_VERSION
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}: config_version {} -> {}",
+        path.display(),
+        report.from_version,
+        CURRENT_VERSION
+    );
+    for change in &report.changes {
+        println!("  {}", change);
+    }
+    if !write {
+        println!("Run again with --write to update the file");
+        return Ok(());
+    }
+    let content =
+        config_file::ConfigFormat::from_path(path).render(&serde_json::Value::Object(fields))?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write configuration to {:?}", path))?;
+    println!("✓ Updated {}", path.display());
+    Ok(())
+}
+
+/// Print the effective config of `validate --show-merged` (JSON with `--json`)
+fn print_merged_config(layered: &config_layers::LayeredConfig) -> anyhow::Result<()> {
+    if JSON_OUTPUT.load(Relaxed) {
+        println!("{}", layered.to_json()?);
+    } else {
+        for line in layered.to_lines()? {
+            println!("{}", line);
+        }
+        println!();
+    }
+    Ok(())
+}
+
 /// Run the TUI installer
 fn run_tui_installer() -> anyhow::Result<()> {
     debug!("Initializing terminal for TUI mode");
@@ -240,22 +405,90 @@ fn run_tui_installer() -> anyhow::Result<()> {
 }
 
 /// Run installer with configuration file (headless mode)
+///
+/// `offline` overrides the config's `offline_source`.
 fn run_installer_with_config(
     config_path: &std::path::Path,
+    resume: bool,
+    offline: Option<&std::path::Path>,
 ) -> anyhow::Result<()> {
     use std::fs::{self, OpenOptions};
     use std::io::{BufRead, BufReader, Write};
     use std::process::{Command, Stdio};
+    use watchdog::{ActivityClock, Watchdog, WatchdogLimits};
 
     info!("Loading configuration from: {:?}", config_path);
 
     // Load and validate configuration
-    let config = InstallationConfig::load_from_file(config_path)?;
-    config.validate()?;
+    let layered = config_layers::LayeredConfig::load(config_path)?;
+    let mut config = layered.config.clone();
+    if let Some(dir) = offline {
+        config.offline_source = dir.display().to_string();
+    }
+    let hardware = hardware::HardwareInventory::probe(std::path::Path::new("/"));
+    config.validate_for_hardware(&hardware)?;
+    // Backstop for disk paths the inventory does not list (e.g. /dev/disk/by-id)
+    engine::capacity::check_disk_capacity(&config)
+        .context("Layout does not fit the target disk")?;
+
+    // Dry-run never spawns install.sh: print what it would do instead
+    if script_traits::is_dry_run() {
+        let report = logic::dry_run::DryRunReport::build(&config, &hardware);
+        if JSON_OUTPUT.load(Relaxed) {
+            println!("{}", report.to_json()?);
+        } else {
+            print!("{}", report.to_text());
+        }
+        info!(
+            steps = report.transcript.len(),
+            "Dry-run report printed; installer not started"
+        );
+        return Ok(());
+    }
 
     info!("Configuration validated successfully");
     println!("✓ Configuration loaded and validated");
-    println!("Starting installation with configuration file...");
+
+    // Stage events from install.sh drive the same state machine as the TUI
+    let mut install_context = install_state::InstallerContext::new();
+    install_context.confirm_destructive_operations();
+    let mut checkpoint = install_checkpoint::CheckpointWriter::new(&config);
+    let mut resume_stage = None;
+
+    if resume {
+        let (saved, path) = install_checkpoint::InstallCheckpoint::find()?;
+        info!("Resuming from checkpoint: {:?}", path);
+        saved.verify_config(&config)?;
+        let stage = saved.resume_stage()?;
+        saved.verify_mounts()?;
+        install_context = install_state::InstallerContext::resume_at(&saved.stage_history, stage)?;
+        checkpoint = checkpoint.resuming(&saved);
+        resume_stage = Some(stage);
+        println!("✓ Checkpoint verified: {}", path.display());
+        println!("Resuming installation at: {}", stage);
+    } else {
+        println!("Starting installation with configuration file...");
+    }
+    let checkpoint_dir = checkpoint.log_dir().to_path_buf();
+    let storage_env = checkpoint.storage_plan_env()?;
+    install_context.enable_checkpoints(checkpoint);
+
+    // The package pre-flight is the ValidatingConfig stage install.sh re-announces
+    if resume_stage.is_none() {
+        install_context.transition_to(install_state::InstallStage::ValidatingConfig)?;
+        println!(
+            "==> [{:>3}%] {}",
+            install_context.progress_percent(),
+            install_context.status_text()
+        );
+    }
+    let offline_repo = match check_install_packages(&config, &hardware) {
+        Ok(repo) => repo,
+        Err(e) => {
+            let _ = install_context.fail();
+            return Err(e);
+        }
+    };
 
     // Pass LOG_LEVEL to child process
     let log_level = std::env::var("ARCHTUI_LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string());
@@ -266,11 +499,30 @@ fn run_installer_with_config(
         .to_string();
     info!("Spawning installer script: {}", script_path);
 
-    let mut child = Command::new("/bin/bash")
+    // Every config reaches install.sh through the environment contract the
+    // TUI uses, whatever its format, layering or version: the file on disk is
+    // never re-read by bash, so what was validated is what gets installed
+    info!(
+        layers = layered.layers.len(),
+        format = %config_file::ConfigFormat::from_path(config_path),
+        "Passing configuration via environment"
+    );
+    let mut command = Command::new("/bin/bash");
+    command
         .arg(script_path)
-        .arg("--config")
-        .arg(config_path)
+        .envs(config.to_env_vars())
+        .envs(logic::resolver::package_env_vars(&config, &hardware));
+    if let Some(ref repo) = offline_repo {
+        command.envs(repo.env_vars());
+    }
+    command
         .env("LOG_LEVEL", &log_level)
+        .env("ARCHTUI_CHECKPOINT_DIR", &checkpoint_dir)
+        .envs(storage_env);
+    if let Some(stage) = resume_stage {
+        command.env("ARCHTUI_RESUME_STAGE", stage.id());
+    }
+    let mut child = command
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .in_new_process_group()
@@ -286,6 +538,28 @@ fn run_installer_with_config(
         registry.register(child_pid);
     }
 
+    // Hang detection: install.sh's manifest sets the idle-output limit
+    let clock = ActivityClock::new();
+    let limits = script_runner::manifest_registry()
+        .get("scripts/install.sh")
+        .map(WatchdogLimits::from_manifest)
+        .unwrap_or_default();
+    let watchdog = Watchdog::spawn("install.sh", child_pid, limits, clock.clone());
+
+    // Drain stderr concurrently so it counts as activity and cannot fill its pipe
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        let clock = clock.clone();
+        std::thread::spawn(move || {
+            let mut collected = String::new();
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                clock.touch();
+                collected.push_str(&line);
+                collected.push('\n');
+            }
+            collected
+        })
+    });
+
     // Create master log for headless mode (best-effort)
     let log_dir = crate::script_runner::log_dir();
     let log_dir = log_dir.to_string_lossy();
@@ -314,15 +588,28 @@ fn run_installer_with_config(
         for line in reader.lines() {
             match line {
                 Ok(line_content) => {
+                    clock.touch();
+                    let clean = installer::strip_ansi_and_cr(&line_content);
+                    if let Some(event) = install_state::StageEvent::parse(&clean) {
+                        match install_context.apply(event) {
+                            Ok(()) => println!(
+                                "==> [{:>3}%] {}",
+                                install_context.progress_percent(),
+                                install_context.status_text()
+                            ),
+                            Err(e) => debug!(error = %e, "Ignoring out-of-order stage event"),
+                        }
+                        continue;
+                    }
                     println!("{}", line_content);
                     if let Some(ref mut f) = master_log {
-                        let clean = installer::strip_ansi_and_cr(&line_content);
                         let _ = writeln!(f, "[{}] {}", installer::now_hms(), clean);
                     }
                 }
                 Err(e) => {
                     // If there's an error reading stdout, still wait for the child
                     let _ = child.wait();
+                    let _ = watchdog.finish();
                     return Err(e.into());
                 }
             }
@@ -330,16 +617,20 @@ fn run_installer_with_config(
     }
 
     // Always wait for the child process to finish
-    let output = child
-        .wait_with_output()
+    let status = child
+        .wait()
         .context("Failed to wait for installer subprocess")?;
+    let timed_out = watchdog.finish();
 
     // Death Pact: unregister child now that it has exited
     if let Ok(mut registry) = ChildRegistry::global().lock() {
         registry.unregister(child_pid);
     }
+    let stderr = stderr_reader
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
 
-    if output.status.success() {
+    if status.success() {
         info!("Installation completed successfully");
         println!("\n✓ Installation completed successfully!");
         if let Some(ref mut f) = master_log {
@@ -350,22 +641,50 @@ fn run_installer_with_config(
             );
         }
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Installation failed. Exit code: {:?}", output.status.code());
+        error!("Installation failed. Exit code: {:?}", status.code());
         if !stderr.is_empty() {
             error!("Stderr: {}", stderr);
         }
-        eprintln!("\n Installation failed");
+        let failed_stage = install_context.current_stage();
+        let _ = install_context.fail();
+        eprintln!("\n Installation failed during: {}", failed_stage);
+        if let Some(ref timeout) = timed_out {
+            eprintln!("{}", timeout);
+            if let Some(ref mut f) = master_log {
+                let _ = writeln!(f, "[{}] [RUST] Watchdog: {}", installer::now_hms(), timeout);
+            }
+        }
         if !stderr.is_empty() {
             eprintln!("--- Errors ---");
             eprintln!("{}", stderr);
         }
+        if install_checkpoint::is_resumable(failed_stage) {
+            eprintln!(
+                "The target was left mounted. Fix the problem, then resume with:\n  archtui install --config {} --resume",
+                config_path.display()
+            );
+        }
+        match install_checkpoint::teardown_applied_storage(
+            &checkpoint_dir,
+            failed_stage,
+            script_runner::run_script_safe,
+        ) {
+            Ok(0) => {}
+            Ok(steps) => println!("Released the applied storage plan ops ({} steps)", steps),
+            Err(e) => {
+                error!(error = %e, "Storage teardown incomplete");
+                eprintln!(
+                    "Storage teardown incomplete: {:#}\nRun Disk Tools > Clean Up Previous Attempt before retrying",
+                    e
+                );
+            }
+        }
         if let Some(ref mut f) = master_log {
             let _ = writeln!(
                 f,
                 "[{}] [RUST] Installation FAILED (exit code {:?})",
                 installer::now_hms(),
-                output.status.code()
+                status.code()
             );
         }
         std::process::exit(1);
@@ -374,6 +693,174 @@ fn run_installer_with_config(
     Ok(())
 }
 
+/// Package pre-flight for a config-file install: resolve every package against
+/// the sync databases, or prepare the offline repository when one is set.
+/// Returns the offline repository install.sh should use, if any.
+fn check_install_packages(
+    config: &InstallationConfig,
+    hardware: &hardware::HardwareInventory,
+) -> anyhow::Result<Option<logic::offline::OfflineRepo>> {
+    // Offline installs must have every package locally before the disk is touched
+    if config.offline_source.trim().is_empty() {
+        // Typos and stale package names fail here, not halfway through pacstrap
+        match logic::availability::check_install_packages(config, hardware) {
+            Ok(report) => {
+                report.ensure_ok("the sync databases")?;
+                println!("✓ Packages available: {}", report.summary());
+                for issue in &report.issues {
+                    println!("  Warning: {}", issue);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Package availability not checked");
+                println!("⚠ Package availability not checked: {:#}", e);
+            }
+        }
+        Ok(None)
+    } else {
+        let dir = std::path::Path::new(config.offline_source.trim());
+        let repo = logic::offline::prepare_offline_install(dir, config, hardware)?;
+        println!("✓ All packages available from {}", dir.display());
+        for step in logic::offline::skipped_steps(config) {
+            println!("  Offline install: skipping {}", step);
+        }
+        Ok(Some(repo))
+    }
+}
+
+/// Run the storage pipeline against a loop-backed image (sandbox mode)
+fn run_sandbox_with_config(
+    config_path: &std::path::Path,
+    options: &engine::sandbox::SandboxOptions,
+) -> anyhow::Result<()> {
+    let config = InstallationConfig::load_from_file(config_path)?;
+    println!(
+        "Sandbox: {} MiB image at {}, target mounted at {}",
+        options.size_mib,
+        options.image.display(),
+        options.target_root.display()
+    );
+
+    let report = engine::sandbox::run_sandbox(&config, options, |report| {
+        println!("  {}", report);
+    })?;
+
+    println!(
+        "\nPartition table ({}, {}):",
+        report.partition_table.label,
+        report.loop_device.display()
+    );
+    for part in &report.partition_table.partitions {
+        println!(
+            "  {:<16} start={:<10} size={:<10} type={} {}",
+            part.node.display(),
+            part.start,
+            part.size,
+            part.type_id,
+            part.name.as_deref().unwrap_or("")
+        );
+    }
+    println!("\nfstab:");
+    for entry in &report.fstab {
+        println!(
+            "  {:<12} {:<8} {:<44} {}",
+            entry.mountpoint, entry.fstype, entry.source, entry.options
+        );
+    }
+    if !report.base_system_installed {
+        println!("\nBase system install skipped (no --package-cache)");
+    }
+    println!("\n✓ Sandbox run completed; storage torn down");
+    Ok(())
+}
+
+/// Execute the storage plan `install.sh` hands over and write the device
+/// roles it produced to `state_path` (the `apply-storage` command)
+fn run_apply_storage(
+    plan_path: &std::path::Path,
+    state_path: &std::path::Path,
+) -> anyhow::Result<()> {
+    let plan = install_checkpoint::load_storage_plan(plan_path)?;
+    println!("{}", plan.summary());
+
+    // storage_plan.sh runs inside install.sh, which has the passphrase as
+    // ENCRYPTION_PASSWORD. archtui tears the applied ops down from the
+    // record if the install fails, so the executor leaves them in place.
+    let options = engine::executor::ExecutorOptions {
+        encryption_password: std::env::var("ENCRYPTION_PASSWORD")
+            .ok()
+            .filter(|p| !p.is_empty()),
+        teardown_on_failure: false,
+        ..Default::default()
+    };
+    let record_dir = plan_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let mut record = install_checkpoint::AppliedStorage {
+        plan: plan.clone(),
+        applied: 0,
+    };
+    record.save(record_dir)?;
+    engine::executor::execute_storage_plan(&plan, &options, |report| {
+        println!("  {}", report);
+        if report.status == engine::executor::OpStatus::Succeeded {
+            record.applied = report.index + 1;
+            if let Err(e) = record.save(record_dir) {
+                tracing::warn!(error = %e, "Failed to record applied storage op");
+            }
+        }
+    })?;
+
+    std::fs::write(state_path, plan.devices().to_shell())
+        .with_context(|| format!("Failed to write {}", state_path.display()))?;
+    println!("✓ Storage plan applied");
+    Ok(())
+}
+
+/// Convert a config file to the format implied by `output`'s extension
+fn convert_config(
+    input: &std::path::Path,
+    output: &std::path::Path,
+    force: bool,
+) -> anyhow::Result<()> {
+    use crate::config_file::ConfigFormat;
+
+    if output.exists() && !force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            output.display()
+        );
+    }
+    let layered = config_layers::LayeredConfig::load(input)?;
+    let config = &layered.config;
+    config.save_to_file(output)?;
+
+    println!(
+        "✓ Converted {} ({}) -> {} ({})",
+        input.display(),
+        ConfigFormat::from_path(input),
+        output.display(),
+        ConfigFormat::from_path(output)
+    );
+    if layered.is_layered() {
+        println!(
+            "  Flattened {} extends layers into one file",
+            layered.layers.len()
+        );
+    }
+    if [
+        &config.user_password,
+        &config.root_password,
+        &config.encryption_password,
+    ]
+    .iter()
+    .any(|p| !p.is_empty())
+    {
+        eprintln!("⚠ Passwords are never written to disk; set them again in the new file");
+    }
+    Ok(())
+}
+
 /// Run TUI installer and save configuration when done
 fn run_tui_installer_with_save(
     save_path: &std::path::Path,
@@ -452,6 +939,7 @@ fn dispatch_disk_tool(disk_tool: &crate::cli::DiskToolCommands) -> anyhow::Resul
                     filesystem: fs,
                     label: label.clone(),
                     force: false,
+                    confirm: true,
                 };
                 execute_tool(&format_args)?;
             }
@@ -587,12 +1075,14 @@ fn dispatch_system_tool(system_tool: &crate::cli::SystemToolCommands) -> anyhow:
                     disk: PathBuf::from(disk),
                     mode: mode.clone(),
                     efi_path: efi_path.as_ref().map(PathBuf::from),
+                    confirm: true,
                 };
                 execute_tool(&bootloader_args)?;
             }
             crate::cli::SystemToolCommands::Fstab { root } => {
                 let fstab_args = GenFstabArgs {
                     root: PathBuf::from(root),
+                    confirm: true,
                 };
                 execute_tool(&fstab_args)?;
             }
@@ -664,6 +1154,7 @@ fn dispatch_user_tool(user_tool: &crate::cli::UserToolCommands) -> anyhow::Resul
                     home_dir: None,
                     create_home: true,
                     sudo: false,
+                    confirm: true,
                 };
                 execute_tool(&add_user_args)?;
             }
@@ -677,6 +1168,7 @@ fn dispatch_user_tool(user_tool: &crate::cli::UserToolCommands) -> anyhow::Resul
                 let reset_args = ResetPasswordArgs {
                     username: username.clone(),
                     password,
+                    confirm: true,
                 };
                 execute_tool(&reset_args)?;
             }
@@ -689,6 +1181,7 @@ fn dispatch_user_tool(user_tool: &crate::cli::UserToolCommands) -> anyhow::Resul
                     action: action.clone(),
                     user: user.clone(),
                     group: group.clone(),
+                    confirm: true,
                 };
                 execute_tool(&groups_args)?;
             }
@@ -703,6 +1196,7 @@ fn dispatch_user_tool(user_tool: &crate::cli::UserToolCommands) -> anyhow::Resul
                     port: *port,
                     enable_root_login: *root_login,
                     enable_password_auth: *password_auth,
+                    confirm: true,
                 };
                 execute_tool(&ssh_args)?;
             }
@@ -724,6 +1218,7 @@ fn dispatch_user_tool(user_tool: &crate::cli::UserToolCommands) -> anyhow::Resul
                     target_dir: None,
                     branch: branch.clone(),
                     backup: *backup,
+                    confirm: true,
                 };
                 execute_tool(&dotfiles_args)?;
             }
@@ -752,6 +1247,7 @@ fn dispatch_network_tool(network_tool: &crate::cli::NetworkToolCommands) -> anyh
                     interface: interface.clone(),
                     ip: ip.clone(),
                     gateway: gateway.clone(),
+                    confirm: true,
                 };
                 execute_tool(&network_args)?;
             }
@@ -782,6 +1278,7 @@ fn dispatch_network_tool(network_tool: &crate::cli::NetworkToolCommands) -> anyh
                     protocol: protocol.clone(),
                     allow: *allow,
                     deny: *deny,
+                    confirm: true,
                 };
                 execute_tool(&firewall_args)?;
             }
@@ -824,41 +1321,64 @@ fn dispatch_network_tool(network_tool: &crate::cli::NetworkToolCommands) -> anyh
 
 /// Execute a tool script with typed arguments and print output (CLI helper).
 ///
-/// This wraps the shared `run_script_safe` from `script_runner` module
-/// to provide CLI-friendly output and process exit on failure.
+/// This wraps the shared `run_script_streaming` from `script_runner` module
+/// to print output as it arrives and exit the process on failure. Tools whose
+/// manifest `requires` capabilities not yet provided this session are refused
+/// with an explanation. With `--json`, a `ToolJsonOutput` document is printed
+/// on stdout instead of the human-readable summary.
 fn execute_tool<T: ScriptArgs>(args: &T) -> anyhow::Result<()> {
     let script_name = args.script_name();
     let cli_args = args.to_cli_args();
     let env_vars = args.get_env_vars();
+    let json = JSON_OUTPUT.load(Relaxed);
+
+    let manifests = script_runner::manifest_registry();
+    let mut capabilities = CapabilityScheduler::load_session();
+    capabilities.check(manifests, script_name, &cli_args)?;
 
     // Print what we're executing
-    println!(
-        "🔧 Executing: scripts/tools/{} {}",
-        script_name,
-        cli_args.join(" ")
-    );
-    if !env_vars.is_empty() {
+    if !json {
         println!(
-            "   ENV: {}",
-            env_vars
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect::<Vec<_>>()
-                .join(" ")
+            "🔧 Executing: scripts/tools/{} {}",
+            script_name,
+            cli_args.join(" ")
         );
+        if !env_vars.is_empty() {
+            println!(
+                "   ENV: {}",
+                env_vars
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
     }
 
-    // Execute via shared runner
-    let output = run_script_safe(args)?;
+    // Execute via shared runner, echoing output as it is produced
+    // (all of it to stderr in JSON mode)
+    let output = run_script_streaming(args, |line| match line.stream {
+        OutputStream::Stdout if !json => println!("{}", line.line),
+        _ => eprintln!("{}", line.line),
+    })?;
 
-    // Print stdout
-    if !output.stdout.is_empty() {
-        print!("{}", output.stdout);
+    if output.success && !output.dry_run {
+        capabilities.record_success(manifests, script_name, &cli_args);
     }
 
-    // Print stderr
-    if !output.stderr.is_empty() {
-        eprint!("{}", output.stderr);
+    if json {
+        let document = ToolJsonOutput::new(
+            script_name,
+            output.success,
+            output.exit_code,
+            output.dry_run,
+            output.events,
+        );
+        println!("{}", serde_json::to_string_pretty(&document)?);
+        if !output.success {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
 
     if output.success {
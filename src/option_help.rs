@@ -113,6 +113,7 @@ fn partitioning(v: &str) -> Option<&'static str> {
         "auto_raid_luks" => "RAID with LUKS encryption on top",
         "auto_raid_lvm" => "RAID with LVM on top — redundancy + flexibility",
         "auto_raid_lvm_luks" => "RAID + LVM + LUKS — most complex, most flexible",
+        "auto_alongside" => "Dual-boot — install into free space, keep existing OS and ESP",
         "manual" => "Pre-partition with cfdisk/fdisk first; assign mount points here",
         "pre_mounted" => "Skip partitioning — root and /boot already mounted at /mnt",
         _ => return None,
@@ -0,0 +1,673 @@
+//! Package Transaction Events
+//!
+//! `PackageManager::install_packages` turns libalpm's download, progress,
+//! event and question callbacks into [`PackageEvent`]s sent over an
+//! `mpsc` channel (`PackageManager::with_events`). The types here do not
+//! depend on libalpm, so the TUI renders them with or without the `alpm`
+//! feature:
+//!
+//! - [`PackageEvent`] — one typed event per callback worth showing
+//! - [`PackageProgress`] — folds events into what the installation screen
+//!   shows: current package, bytes downloaded and overall percent
+//! - [`QuestionPolicy`] — how replace/conflict/key questions are answered,
+//!   since nobody is at the keyboard to answer them
+//! - [`PacmanOutputParser`] — the same events from the text pacman prints
+//!   when its output is piped, for the pacstrap and `pacman -S` runs in
+//!   install.sh
+//!
+//! # Overall Percent
+//!
+//! Downloading and installing each count for half of the bar when there is
+//! something to download; with a warm cache installing is the whole bar.
+
+// Library API - produced by package_manager, rendered by the installation screen
+#![allow(dead_code)]
+
+use crate::logic::availability::format_bytes;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Stage of an ALPM sync transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionPhase {
+    /// Resolving dependencies
+    Resolving,
+    /// Checking inter-package and file conflicts
+    CheckingConflicts,
+    /// Downloading package files
+    Downloading,
+    /// Verifying signatures and checksums, loading package files
+    CheckingIntegrity,
+    /// Checking available disk space
+    CheckingDiskSpace,
+    /// Extracting packages into the target root
+    Installing,
+    /// Running post-transaction hooks
+    RunningHooks,
+    /// Transaction committed
+    Done,
+}
+
+impl fmt::Display for TransactionPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Resolving => "Resolving dependencies",
+            Self::CheckingConflicts => "Checking for conflicts",
+            Self::Downloading => "Downloading packages",
+            Self::CheckingIntegrity => "Checking package integrity",
+            Self::CheckingDiskSpace => "Checking available disk space",
+            Self::Installing => "Installing packages",
+            Self::RunningHooks => "Running post-transaction hooks",
+            Self::Done => "Transaction complete",
+        })
+    }
+}
+
+/// What a progress callback is reporting on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStep {
+    Install,
+    Upgrade,
+    Reinstall,
+    Downgrade,
+    Remove,
+    Conflicts,
+    DiskSpace,
+    Integrity,
+    Load,
+    Keyring,
+}
+
+impl ProgressStep {
+    /// Whether the step changes a package in the target root.
+    pub fn is_package_operation(self) -> bool {
+        matches!(
+            self,
+            Self::Install | Self::Upgrade | Self::Reinstall | Self::Downgrade | Self::Remove
+        )
+    }
+}
+
+impl fmt::Display for ProgressStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Install => "Installing",
+            Self::Upgrade => "Upgrading",
+            Self::Reinstall => "Reinstalling",
+            Self::Downgrade => "Downgrading",
+            Self::Remove => "Removing",
+            Self::Conflicts => "Checking conflicts",
+            Self::DiskSpace => "Checking disk space",
+            Self::Integrity => "Checking integrity",
+            Self::Load => "Loading packages",
+            Self::Keyring => "Checking keys",
+        })
+    }
+}
+
+/// A question libalpm asks during a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageQuestion {
+    /// Replace installed `old` with `new` from `repo`?
+    Replace {
+        old: String,
+        new: String,
+        repo: String,
+    },
+    /// `package` conflicts with `conflicting`; remove `conflicting`?
+    Conflict {
+        package: String,
+        conflicting: String,
+    },
+    /// Delete a corrupted package file from the cache?
+    Corrupted { file: String },
+    /// Import an unknown PGP key?
+    ImportKey { uid: String, fingerprint: String },
+    /// Anything else (ignored packages, provider selection, ...); libalpm's
+    /// default answer stands
+    Other(String),
+}
+
+impl fmt::Display for PackageQuestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Replace { old, new, repo } => {
+                write!(f, "Replace {} with {}/{}?", old, repo, new)
+            }
+            Self::Conflict {
+                package,
+                conflicting,
+            } => write!(
+                f,
+                "{} and {} are in conflict. Remove {}?",
+                package, conflicting, conflicting
+            ),
+            Self::Corrupted { file } => write!(f, "File {} is corrupted. Delete it?", file),
+            Self::ImportKey { uid, fingerprint } => {
+                write!(f, "Import PGP key {} ({})?", fingerprint, uid)
+            }
+            Self::Other(kind) => write!(f, "{} (default answer)", kind),
+        }
+    }
+}
+
+/// How questions are answered during an unattended transaction.
+///
+/// The default matches `pacman --noconfirm`: replacements, corrupted file
+/// removal and key imports are accepted, conflicting packages are not
+/// removed (the transaction fails instead).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuestionPolicy {
+    /// Accept package replacements
+    pub replace: bool,
+    /// Remove a package that conflicts with one being installed
+    pub remove_conflicts: bool,
+    /// Delete corrupted package files so they are downloaded again
+    pub remove_corrupted: bool,
+    /// Import PGP keys needed to verify packages
+    pub import_keys: bool,
+}
+
+impl Default for QuestionPolicy {
+    fn default() -> Self {
+        Self {
+            replace: true,
+            remove_conflicts: false,
+            remove_corrupted: true,
+            import_keys: true,
+        }
+    }
+}
+
+impl QuestionPolicy {
+    /// The answer to give, or `None` to keep libalpm's default.
+    pub fn answer(&self, question: &PackageQuestion) -> Option<bool> {
+        match question {
+            PackageQuestion::Replace { .. } => Some(self.replace),
+            PackageQuestion::Conflict { .. } => Some(self.remove_conflicts),
+            PackageQuestion::Corrupted { .. } => Some(self.remove_corrupted),
+            PackageQuestion::ImportKey { .. } => Some(self.import_keys),
+            PackageQuestion::Other(_) => None,
+        }
+    }
+}
+
+/// One event from an ALPM transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageEvent {
+    /// The transaction moved to a new phase
+    Phase(TransactionPhase),
+    /// Package downloads begin: `count` files, `total_bytes` in all
+    DownloadsStarted { count: usize, total_bytes: u64 },
+    /// Bytes received for one file
+    Download {
+        file: String,
+        downloaded: u64,
+        total: u64,
+    },
+    /// One file finished downloading (`ok` is false if it failed)
+    DownloadDone { file: String, ok: bool },
+    /// Progress of a step on one package (`current` of `total`)
+    Progress {
+        step: ProgressStep,
+        package: String,
+        percent: u8,
+        current: usize,
+        total: usize,
+    },
+    /// A question was answered (`None`: libalpm's default was kept)
+    Question {
+        question: PackageQuestion,
+        answer: Option<bool>,
+    },
+}
+
+/// Running totals of a transaction, built from [`PackageEvent`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageProgress {
+    /// Current transaction phase
+    pub phase: Option<TransactionPhase>,
+    /// Package being downloaded or installed
+    pub current_package: Option<String>,
+    /// Bytes received across all package files
+    pub downloaded_bytes: u64,
+    /// Bytes to download; 0 when everything is cached
+    pub download_total: u64,
+    /// Package operation position and total (e.g. 12 of 412)
+    pub install_position: (usize, usize),
+    /// Percent done of the current package operation
+    pub install_percent: u8,
+    /// Files that failed to download
+    pub failed_downloads: Vec<String>,
+    /// Bytes received per file, so repeated progress events do not add up
+    file_bytes: HashMap<String, u64>,
+}
+
+impl PackageProgress {
+    /// Fold one event into the totals.
+    pub fn apply(&mut self, event: &PackageEvent) {
+        match event {
+            // Each transaction starts from zero; install.sh runs several
+            PackageEvent::Phase(TransactionPhase::Resolving) => {
+                *self = Self {
+                    phase: Some(TransactionPhase::Resolving),
+                    ..Self::default()
+                };
+            }
+            PackageEvent::Phase(phase) => self.phase = Some(*phase),
+            PackageEvent::DownloadsStarted { total_bytes, .. } => {
+                self.download_total = *total_bytes;
+                self.downloaded_bytes = 0;
+                self.file_bytes.clear();
+            }
+            // Database refreshes also download; only package files count
+            PackageEvent::Download {
+                file, downloaded, ..
+            } if self.phase == Some(TransactionPhase::Downloading) => {
+                self.file_bytes.insert(file.clone(), *downloaded);
+                self.downloaded_bytes = self.file_bytes.values().sum();
+                self.current_package = Some(package_from_file(file).to_string());
+            }
+            PackageEvent::Download { .. } => {}
+            PackageEvent::DownloadDone { file, ok } => {
+                if !ok {
+                    self.failed_downloads.push(file.clone());
+                }
+            }
+            PackageEvent::Progress {
+                step,
+                package,
+                percent,
+                current,
+                total,
+            } if step.is_package_operation() => {
+                // File conflict and disk space checks can fire after TransactionStart
+                self.phase = Some(TransactionPhase::Installing);
+                self.current_package = Some(package.clone());
+                self.install_position = (*current, *total);
+                self.install_percent = (*percent).min(100);
+            }
+            PackageEvent::Progress { .. } | PackageEvent::Question { .. } => {}
+        }
+    }
+
+    /// Percent of the whole transaction done (0-100).
+    pub fn overall_percent(&self) -> u8 {
+        if self.phase == Some(TransactionPhase::Done) {
+            return 100;
+        }
+        let (current, total) = self.install_position;
+        let installed = if total == 0 {
+            0.0
+        } else {
+            (current.saturating_sub(1) as f64 + f64::from(self.install_percent) / 100.0)
+                / total as f64
+        };
+        let overall = if self.download_total == 0 {
+            installed
+        } else {
+            let downloaded = (self.downloaded_bytes as f64 / self.download_total as f64).min(1.0);
+            (downloaded + installed) / 2.0
+        };
+        (overall.clamp(0.0, 1.0) * 100.0) as u8
+    }
+
+    /// One-line description for the installation screen.
+    pub fn status_line(&self) -> String {
+        let package = self.current_package.as_deref().unwrap_or("");
+        let detail = match self.phase {
+            // pacman's piped output names the files but not their sizes
+            Some(TransactionPhase::Downloading) if self.download_total == 0 => {
+                format!("Downloading {}", package)
+            }
+            Some(TransactionPhase::Downloading) => format!(
+                "Downloading {} ({} / {})",
+                package,
+                format_bytes(self.downloaded_bytes as i64),
+                format_bytes(self.download_total as i64)
+            ),
+            Some(TransactionPhase::Installing) if self.install_position.1 > 0 => format!(
+                "Installing {} ({}/{})",
+                package, self.install_position.0, self.install_position.1
+            ),
+            Some(phase) => phase.to_string(),
+            None => "Preparing transaction".to_string(),
+        };
+        format!("{} - {}%", detail, self.overall_percent())
+    }
+}
+
+/// Turns pacman's piped output into [`PackageEvent`]s.
+///
+/// With stdout redirected pacman draws no progress bars and prints one line
+/// per step instead (`installing acl...`), which install.sh passes through
+/// with a `[pacstrap]` or `[pacman]` prefix. The lines carry no byte counts,
+/// so downloads only name the current file; installs are numbered against
+/// the `Packages (N)` summary. Questions are reported with the default
+/// answer, which is what `--noconfirm` gives them.
+#[derive(Debug, Default)]
+pub struct PacmanOutputParser {
+    /// A transaction was started and not yet reported done
+    active: bool,
+    /// Package operations reported so far in this transaction
+    current: usize,
+    /// Package count from the `Packages (N)` summary
+    total: usize,
+}
+
+impl PacmanOutputParser {
+    /// The event `line` reports, if any.
+    pub fn parse_line(&mut self, line: &str) -> Option<PackageEvent> {
+        let line = line.trim();
+        let line = ["[pacstrap]", "[pacman]"]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .unwrap_or(line)
+            .trim();
+
+        let phase = match line {
+            "resolving dependencies..." => {
+                *self = Self {
+                    active: true,
+                    ..Self::default()
+                };
+                Some(TransactionPhase::Resolving)
+            }
+            "looking for conflicting packages..." | "checking for file conflicts..." => {
+                Some(TransactionPhase::CheckingConflicts)
+            }
+            ":: Retrieving packages..." => Some(TransactionPhase::Downloading),
+            "checking keyring..."
+            | "checking package integrity..."
+            | "loading package files..." => Some(TransactionPhase::CheckingIntegrity),
+            "checking available disk space..." => Some(TransactionPhase::CheckingDiskSpace),
+            ":: Processing package changes..." => Some(TransactionPhase::Installing),
+            ":: Running post-transaction hooks..." => Some(TransactionPhase::RunningHooks),
+            // --needed with everything installed: no transaction follows
+            "there is nothing to do" => return self.finish(),
+            _ => None,
+        };
+        if let Some(phase) = phase {
+            return Some(PackageEvent::Phase(phase));
+        }
+
+        if let Some(count) = line
+            .strip_prefix("Packages (")
+            .and_then(|rest| rest.split(')').next())
+        {
+            self.total = count.parse().unwrap_or(0);
+            return None;
+        }
+        if let Some(file) = line.strip_suffix(" downloading...") {
+            return Some(PackageEvent::Download {
+                file: file.to_string(),
+                downloaded: 0,
+                total: 0,
+            });
+        }
+        for (verb, step) in [
+            ("installing ", ProgressStep::Install),
+            ("upgrading ", ProgressStep::Upgrade),
+            ("reinstalling ", ProgressStep::Reinstall),
+            ("downgrading ", ProgressStep::Downgrade),
+        ] {
+            if let Some(package) = line
+                .strip_prefix(verb)
+                .and_then(|rest| rest.strip_suffix("..."))
+            {
+                self.current += 1;
+                return Some(PackageEvent::Progress {
+                    step,
+                    package: package.to_string(),
+                    percent: 0,
+                    current: self.current,
+                    total: self.total.max(self.current),
+                });
+            }
+        }
+        parse_question(line).map(|question| PackageEvent::Question {
+            question,
+            answer: None,
+        })
+    }
+
+    /// Report the open transaction as done: pacman prints nothing once its
+    /// hooks have run, so the caller decides when the output moved on.
+    pub fn finish(&mut self) -> Option<PackageEvent> {
+        std::mem::take(&mut self.active).then_some(PackageEvent::Phase(TransactionPhase::Done))
+    }
+}
+
+/// ":: Replace a with core/b? [Y/n]" and ":: a and b are in conflict. Remove b? [y/N]".
+fn parse_question(line: &str) -> Option<PackageQuestion> {
+    let question = line.strip_prefix(":: ")?;
+    if let Some(rest) = question.strip_prefix("Replace ") {
+        let (old, target) = rest.split_once(" with ")?;
+        let (repo, new) = target.split_once('/')?;
+        let new = new.split('?').next()?;
+        return Some(PackageQuestion::Replace {
+            old: old.to_string(),
+            new: new.to_string(),
+            repo: repo.to_string(),
+        });
+    }
+    let (package, rest) = question.split_once(" and ")?;
+    let (conflicting, _) = rest.split_once(" are in conflict")?;
+    Some(PackageQuestion::Conflict {
+        package: package.to_string(),
+        conflicting: conflicting.to_string(),
+    })
+}
+
+/// "firefox-130.0-1-x86_64.pkg.tar.zst" -> "firefox-130.0-1-x86_64".
+fn package_from_file(file: &str) -> &str {
+    file.split(".pkg.tar").next().unwrap_or(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(package: &str, percent: u8, current: usize, total: usize) -> PackageEvent {
+        PackageEvent::Progress {
+            step: ProgressStep::Install,
+            package: package.to_string(),
+            percent,
+            current,
+            total,
+        }
+    }
+
+    #[test]
+    fn test_progress_through_download_and_install() {
+        let mut p = PackageProgress::default();
+        assert_eq!(p.status_line(), "Preparing transaction - 0%");
+
+        // Database refresh downloads do not count towards the package total
+        p.apply(&PackageEvent::Download {
+            file: "core.db".to_string(),
+            downloaded: 5_000,
+            total: 5_000,
+        });
+        assert_eq!(p.downloaded_bytes, 0);
+
+        p.apply(&PackageEvent::Phase(TransactionPhase::Downloading));
+        p.apply(&PackageEvent::DownloadsStarted {
+            count: 2,
+            total_bytes: 4 * 1024 * 1024,
+        });
+        for downloaded in [512 * 1024, 1024 * 1024] {
+            p.apply(&PackageEvent::Download {
+                file: "firefox-130.0-1-x86_64.pkg.tar.zst".to_string(),
+                downloaded,
+                total: 2 * 1024 * 1024,
+            });
+        }
+        p.apply(&PackageEvent::Download {
+            file: "vim-9.1-1-x86_64.pkg.tar.zst".to_string(),
+            downloaded: 1024 * 1024,
+            total: 2 * 1024 * 1024,
+        });
+        assert_eq!(p.downloaded_bytes, 2 * 1024 * 1024);
+        assert_eq!(p.overall_percent(), 25);
+        assert_eq!(
+            p.status_line(),
+            "Downloading vim-9.1-1-x86_64 (2 MiB / 4 MiB) - 25%"
+        );
+
+        p.apply(&PackageEvent::Download {
+            file: "firefox-130.0-1-x86_64.pkg.tar.zst".to_string(),
+            downloaded: 2 * 1024 * 1024,
+            total: 2 * 1024 * 1024,
+        });
+        p.apply(&PackageEvent::Download {
+            file: "vim-9.1-1-x86_64.pkg.tar.zst".to_string(),
+            downloaded: 2 * 1024 * 1024,
+            total: 2 * 1024 * 1024,
+        });
+        p.apply(&PackageEvent::Phase(TransactionPhase::Installing));
+        p.apply(&progress("vim", 50, 2, 2));
+        assert_eq!(p.current_package.as_deref(), Some("vim"));
+        assert_eq!(p.overall_percent(), 87);
+        assert_eq!(p.status_line(), "Installing vim (2/2) - 87%");
+
+        p.apply(&PackageEvent::Phase(TransactionPhase::Done));
+        assert_eq!(p.overall_percent(), 100);
+    }
+
+    #[test]
+    fn test_progress_without_downloads_and_failures() {
+        let mut p = PackageProgress::default();
+        p.apply(&PackageEvent::Phase(TransactionPhase::Installing));
+        p.apply(&progress("base", 0, 1, 4));
+        assert_eq!(p.overall_percent(), 0);
+        p.apply(&progress("linux", 100, 2, 4));
+        assert_eq!(p.overall_percent(), 50);
+
+        // Integrity and conflict checks report progress too but install nothing
+        p.apply(&PackageEvent::Progress {
+            step: ProgressStep::Integrity,
+            package: String::new(),
+            percent: 100,
+            current: 4,
+            total: 4,
+        });
+        assert_eq!(p.install_position, (2, 4));
+
+        p.apply(&PackageEvent::DownloadDone {
+            file: "linux.pkg.tar.zst".to_string(),
+            ok: false,
+        });
+        assert_eq!(p.failed_downloads, vec!["linux.pkg.tar.zst".to_string()]);
+    }
+
+    #[test]
+    fn test_question_policy() {
+        let replace = PackageQuestion::Replace {
+            old: "pipewire-media-session".to_string(),
+            new: "wireplumber".to_string(),
+            repo: "extra".to_string(),
+        };
+        let conflict = PackageQuestion::Conflict {
+            package: "pipewire-jack".to_string(),
+            conflicting: "jack2".to_string(),
+        };
+        let other = PackageQuestion::Other("SelectProvider".to_string());
+
+        let policy = QuestionPolicy::default();
+        assert_eq!(policy.answer(&replace), Some(true));
+        assert_eq!(policy.answer(&conflict), Some(false));
+        assert_eq!(policy.answer(&other), None);
+
+        let policy = QuestionPolicy {
+            remove_conflicts: true,
+            ..QuestionPolicy::default()
+        };
+        assert_eq!(policy.answer(&conflict), Some(true));
+
+        assert_eq!(
+            replace.to_string(),
+            "Replace pipewire-media-session with extra/wireplumber?"
+        );
+        assert_eq!(
+            conflict.to_string(),
+            "pipewire-jack and jack2 are in conflict. Remove jack2?"
+        );
+    }
+
+    #[test]
+    fn test_pacman_output_parser() {
+        let mut parser = PacmanOutputParser::default();
+        let mut parse = |line: &str| parser.parse_line(line);
+
+        assert_eq!(parse("==> Creating install root at /mnt"), None);
+        assert_eq!(
+            parse("  [pacstrap] resolving dependencies..."),
+            Some(PackageEvent::Phase(TransactionPhase::Resolving))
+        );
+        assert_eq!(
+            parse("  [pacstrap] Packages (2) acl-2.3.2-1  base-3-2"),
+            None
+        );
+        assert_eq!(
+            parse("  [pacstrap]  acl-2.3.2-1-x86_64 downloading..."),
+            Some(PackageEvent::Download {
+                file: "acl-2.3.2-1-x86_64".to_string(),
+                downloaded: 0,
+                total: 0,
+            })
+        );
+        assert_eq!(
+            parse("  [pacman] :: Replace jack2 with extra/pipewire-jack? [Y/n]"),
+            Some(PackageEvent::Question {
+                question: PackageQuestion::Replace {
+                    old: "jack2".to_string(),
+                    new: "pipewire-jack".to_string(),
+                    repo: "extra".to_string(),
+                },
+                answer: None,
+            })
+        );
+        assert_eq!(
+            parse("  [pacstrap] installing acl..."),
+            Some(progress("acl", 0, 1, 2))
+        );
+        assert_eq!(
+            parse("  [pacstrap] installing base..."),
+            Some(progress("base", 0, 2, 2))
+        );
+        assert_eq!(
+            parse("  [pacstrap] :: Running post-transaction hooks..."),
+            Some(PackageEvent::Phase(TransactionPhase::RunningHooks))
+        );
+        assert_eq!(
+            parser.finish(),
+            Some(PackageEvent::Phase(TransactionPhase::Done))
+        );
+        assert_eq!(parser.finish(), None);
+
+        // --needed with everything installed ends the transaction at once
+        parser.parse_line("  [pacman] resolving dependencies...");
+        assert_eq!(
+            parser.parse_line("  [pacman]  there is nothing to do"),
+            Some(PackageEvent::Phase(TransactionPhase::Done))
+        );
+    }
+
+    #[test]
+    fn test_new_transaction_resets_progress() {
+        let mut p = PackageProgress::default();
+        p.apply(&PackageEvent::Phase(TransactionPhase::Downloading));
+        p.apply(&PackageEvent::Download {
+            file: "acl-2.3.2-1-x86_64".to_string(),
+            downloaded: 0,
+            total: 0,
+        });
+        assert_eq!(p.status_line(), "Downloading acl-2.3.2-1-x86_64 - 0%");
+        p.apply(&progress("acl", 0, 3, 4));
+
+        p.apply(&PackageEvent::Phase(TransactionPhase::Resolving));
+        assert_eq!(p.install_position, (0, 0));
+        assert!(p.current_package.is_none());
+    }
+}
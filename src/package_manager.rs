@@ -13,7 +13,10 @@
 //!
 //! - `PackageManager`: Main struct that owns the ALPM handle
 //! - `log_cb`: Routes ALPM log messages to `tracing::*` macros
+//! - Download, progress, event and question callbacks: turned into
+//!   `package_events::PackageEvent`s for the installation screen
 //! - `install_packages`: Runs a sync transaction on target packages
+//! - `check_availability`: Resolves targets without a transaction (pre-flight)
 //!
 //! # Feature Flag
 //!
@@ -23,9 +26,18 @@
 // Library API - used for base system installation via ALPM
 #![allow(dead_code)]
 
-use alpm::{Alpm, LogLevel, SigLevel, TransFlag};
+use crate::logic::availability::{AvailabilityReport, PackageIssue};
+use crate::package_events::{
+    PackageEvent, PackageQuestion, ProgressStep, QuestionPolicy, TransactionPhase,
+};
+use alpm::{
+    Alpm, AnyDownloadEvent, AnyEvent, AnyQuestion, DownloadEvent, DownloadResult, Event, HookWhen,
+    LogLevel, Package, Progress, Question, SigLevel, TransFlag,
+};
 use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::mpsc::Sender;
 
 /// Package manager wrapping libalpm with full logging transparency.
 ///
@@ -33,6 +45,10 @@ use std::path::Path;
 /// not the live system, ensuring packages are installed to the correct location.
 pub struct PackageManager {
     handle: Alpm,
+    /// Receives transaction events from `install_packages`
+    events: Option<Sender<PackageEvent>>,
+    /// Answers libalpm's questions during `install_packages`
+    policy: QuestionPolicy,
 }
 
 impl PackageManager {
@@ -72,7 +88,26 @@ impl PackageManager {
             db_path.display()
         );
 
-        Ok(Self { handle })
+        Ok(Self {
+            handle,
+            events: None,
+            policy: QuestionPolicy::default(),
+        })
+    }
+
+    /// Send typed transaction events to `events` during `install_packages`.
+    ///
+    /// The receiver going away does not affect the transaction.
+    pub fn with_events(mut self, events: Sender<PackageEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Answer replace/conflict/key questions with `policy` instead of the
+    /// `pacman --noconfirm` defaults.
+    pub fn with_question_policy(mut self, policy: QuestionPolicy) -> Self {
+        self.policy = policy;
+        self
     }
 
     /// Create a PackageManager from pacman.conf on the target system.
@@ -128,6 +163,7 @@ impl PackageManager {
         }
 
         tracing::info!("Starting package installation: {:?}", targets);
+        self.set_transaction_callbacks();
 
         // Refresh databases
         self.handle
@@ -231,6 +267,252 @@ impl PackageManager {
         Ok(())
     }
 
+    /// Wire libalpm's download, progress, event and question callbacks to
+    /// the event channel and the question policy.
+    fn set_transaction_callbacks(&self) {
+        let emit = self.emitter();
+        self.handle.set_dl_cb(
+            (),
+            move |file: &str, event: AnyDownloadEvent, _: &mut ()| match event.event() {
+                DownloadEvent::Progress(progress) => emit(PackageEvent::Download {
+                    file: file.to_string(),
+                    downloaded: progress.downloaded.max(0) as u64,
+                    total: progress.total.max(0) as u64,
+                }),
+                DownloadEvent::Completed(done) => emit(PackageEvent::DownloadDone {
+                    file: file.to_string(),
+                    ok: done.result != DownloadResult::Failed,
+                }),
+                DownloadEvent::Init(_) | DownloadEvent::Retry(_) => {}
+            },
+        );
+
+        let emit = self.emitter();
+        self.handle.set_progress_cb(
+            (),
+            move |progress: Progress,
+                  package: &str,
+                  percent: i32,
+                  total: usize,
+                  current: usize,
+                  _: &mut ()| {
+                emit(PackageEvent::Progress {
+                    step: progress_step(progress),
+                    package: package.to_string(),
+                    percent: percent.clamp(0, 100) as u8,
+                    current,
+                    total,
+                });
+            },
+        );
+
+        let emit = self.emitter();
+        self.handle
+            .set_event_cb((), move |event: AnyEvent, _: &mut ()| {
+                let phase = match event.event() {
+                    Event::ResolveDepsStart => TransactionPhase::Resolving,
+                    Event::InterConflictsStart | Event::FileConflictsStart => {
+                        TransactionPhase::CheckingConflicts
+                    }
+                    Event::PkgRetrieveStart(retrieve) => {
+                        emit(PackageEvent::Phase(TransactionPhase::Downloading));
+                        emit(PackageEvent::DownloadsStarted {
+                            count: retrieve.num(),
+                            total_bytes: retrieve.total_size().max(0) as u64,
+                        });
+                        return;
+                    }
+                    Event::IntegrityStart | Event::KeyringStart | Event::LoadStart => {
+                        TransactionPhase::CheckingIntegrity
+                    }
+                    Event::DiskSpaceStart => TransactionPhase::CheckingDiskSpace,
+                    Event::TransactionStart => TransactionPhase::Installing,
+                    Event::HookStart(hook) if matches!(hook.when(), HookWhen::PostTransaction) => {
+                        TransactionPhase::RunningHooks
+                    }
+                    Event::TransactionDone => TransactionPhase::Done,
+                    Event::ScriptletInfo(info) => {
+                        tracing::info!("[ALPM] {}", info.line().trim_end());
+                        return;
+                    }
+                    _ => return,
+                };
+                tracing::debug!(%phase, "Package transaction phase");
+                emit(PackageEvent::Phase(phase));
+            });
+
+        let emit = self.emitter();
+        let policy = self.policy;
+        self.handle
+            .set_question_cb((), move |mut question: AnyQuestion, _: &mut ()| {
+                let asked = match question.question() {
+                    Question::Replace(q) => PackageQuestion::Replace {
+                        old: q.oldpkg().name().to_string(),
+                        new: q.newpkg().name().to_string(),
+                        repo: q.newdb().name().to_string(),
+                    },
+                    Question::Conflict(q) => PackageQuestion::Conflict {
+                        package: q.conflict().package1().name().to_string(),
+                        conflicting: q.conflict().package2().name().to_string(),
+                    },
+                    Question::Corrupted(q) => PackageQuestion::Corrupted {
+                        file: q.filepath().to_string(),
+                    },
+                    Question::ImportKey(q) => PackageQuestion::ImportKey {
+                        uid: q.uid().to_string(),
+                        fingerprint: q.fingerprint().to_string(),
+                    },
+                    _ => PackageQuestion::Other(format!("{:?}", question.question_type())),
+                };
+                let answer = policy.answer(&asked);
+                if let Some(answer) = answer {
+                    question.set_answer(answer);
+                }
+                tracing::info!(?answer, "[ALPM] {}", asked);
+                emit(PackageEvent::Question {
+                    question: asked,
+                    answer,
+                });
+            });
+    }
+
+    /// A callback-owned handle on the event channel.
+    fn emitter(&self) -> impl Fn(PackageEvent) + 'static {
+        let events = self.events.clone();
+        move |event| {
+            if let Some(events) = &events {
+                let _ = events.send(event);
+            }
+        }
+    }
+
+    /// Resolve `targets` against the sync databases without installing.
+    ///
+    /// Refreshes the databases, then resolves each target (a package, a
+    /// provider or a group) and walks its dependency closure, summing the
+    /// download and installed size. Targets that are gone but named in
+    /// another package's `replaces` are reported as renamed; targets that
+    /// still exist but are replaced are reported as warnings. With
+    /// `pkg_dir`, packages whose file is not in that directory are reported
+    /// too, so an offline install fails here instead of halfway through
+    /// pacstrap.
+    pub fn check_availability(
+        &mut self,
+        targets: &[String],
+        pkg_dir: Option<&Path>,
+    ) -> Result<AvailabilityReport> {
+        self.handle
+            .syncdbs_mut()
+            .update(false)
+            .context("Failed to update sync databases")?;
+
+        let dbs = self.handle.syncdbs();
+
+        // old name -> package that replaces it
+        let mut replaced_by: HashMap<String, String> = HashMap::new();
+        for db in dbs {
+            for pkg in db.pkgs() {
+                for old in pkg.replaces() {
+                    replaced_by.insert(old.name().to_string(), pkg.name().to_string());
+                }
+            }
+        }
+
+        let mut report = AvailabilityReport {
+            targets: targets.len(),
+            ..AvailabilityReport::default()
+        };
+        let mut seen_deps = HashSet::new();
+        let mut seen_pkgs = HashSet::new();
+        let mut queue: Vec<(String, Option<String>)> =
+            targets.iter().map(|t| (t.clone(), None)).collect();
+
+        while let Some((dep, required_by)) = queue.pop() {
+            if !seen_deps.insert(dep.clone()) {
+                continue;
+            }
+
+            let pkgs: Vec<&Package> = match dbs.find_satisfier(dep.as_str()) {
+                Some(pkg) => vec![pkg],
+                // Groups (e.g. "gnome") can only be named by targets
+                None if required_by.is_none() => dbs
+                    .iter()
+                    .filter_map(|db| db.group(dep.as_str()).ok())
+                    .flat_map(|group| group.packages())
+                    .collect(),
+                None => Vec::new(),
+            };
+            if pkgs.is_empty() {
+                let issue = match (required_by, replaced_by.get(&dep)) {
+                    (None, Some(new_name)) => PackageIssue::Renamed {
+                        name: dep,
+                        new_name: new_name.clone(),
+                    },
+                    (required_by, _) => PackageIssue::Missing {
+                        name: dep,
+                        required_by,
+                    },
+                };
+                report.issues.push(issue);
+                continue;
+            }
+            if required_by.is_none()
+                && let Some(by) = replaced_by.get(&dep)
+                && *by != dep
+            {
+                report.issues.push(PackageIssue::Replaced {
+                    name: dep.clone(),
+                    by: by.clone(),
+                });
+            }
+
+            for pkg in pkgs {
+                if !seen_pkgs.insert(pkg.name().to_string()) {
+                    continue;
+                }
+                report.download_bytes += pkg.download_size();
+                report.installed_bytes += pkg.isize();
+                if let (Some(dir), Some(file)) = (pkg_dir, pkg.filename())
+                    && !dir.join(file).is_file()
+                {
+                    report.issues.push(PackageIssue::NotCached {
+                        name: pkg.name().to_string(),
+                        file: file.to_string(),
+                        dir: dir.display().to_string(),
+                    });
+                }
+                for depend in pkg.depends() {
+                    queue.push((depend.to_string(), Some(pkg.name().to_string())));
+                }
+            }
+        }
+
+        report.resolved = seen_pkgs.len();
+        report.issues.sort_by_key(|issue| issue.to_string());
+        tracing::info!(
+            targets = report.targets,
+            resolved = report.resolved,
+            download = %humanize_size(report.download_bytes),
+            installed = %humanize_size(report.installed_bytes),
+            issues = report.issues.len(),
+            "Checked package availability"
+        );
+        Ok(report)
+    }
+
+    /// Add a package cache directory searched before downloading.
+    ///
+    /// Packages already present (and valid) in the cache are installed
+    /// without touching the network.
+    pub fn add_cache_dir(&mut self, dir: &Path) -> Result<()> {
+        let dir_str = dir.to_str().context("Cache path contains invalid UTF-8")?;
+        self.handle
+            .add_cachedir(dir_str)
+            .with_context(|| format!("Failed to add cache dir: {}", dir.display()))?;
+        tracing::info!("Package cache added: {}", dir.display());
+        Ok(())
+    }
+
     /// Get a reference to the underlying ALPM handle.
     ///
     /// Use this for advanced operations not covered by this wrapper.
@@ -268,6 +550,22 @@ fn log_cb(level: LogLevel, msg: &str, _: &mut ()) {
     }
 }
 
+/// Map libalpm's progress kinds onto `ProgressStep`.
+fn progress_step(progress: Progress) -> ProgressStep {
+    match progress {
+        Progress::AddStart => ProgressStep::Install,
+        Progress::UpgradeStart => ProgressStep::Upgrade,
+        Progress::DowngradeStart => ProgressStep::Downgrade,
+        Progress::ReinstallStart => ProgressStep::Reinstall,
+        Progress::RemoveStart => ProgressStep::Remove,
+        Progress::ConflictsStart => ProgressStep::Conflicts,
+        Progress::DiskspaceStart => ProgressStep::DiskSpace,
+        Progress::IntegrityStart => ProgressStep::Integrity,
+        Progress::LoadStart => ProgressStep::Load,
+        Progress::KeyringStart => ProgressStep::Keyring,
+    }
+}
+
 /// Convert bytes to human-readable size string.
 fn humanize_size(bytes: i64) -> String {
     const KB: i64 = 1024;
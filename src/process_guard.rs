@@ -52,6 +52,11 @@ impl ChildRegistry {
         tracing::debug!(pid, "Unregistered child process");
     }
 
+    /// Whether `pid` is tracked (its owner unregisters it once reaped)
+    pub fn contains(&self, pid: u32) -> bool {
+        self.pids.contains(&pid)
+    }
+
     /// Get count of tracked children
     ///
     /// Useful for debugging and tests to verify process registration.
@@ -73,7 +78,11 @@ impl ChildRegistry {
         );
 
         let pids_to_kill: Vec<u32> = self.pids.iter().copied().collect();
-        for &pid in &pids_to_kill {
+        self.terminate_pids(&pids_to_kill, grace_period);
+    }
+
+    fn terminate_pids(&mut self, pids_to_kill: &[u32], grace_period: Duration) {
+        for &pid in pids_to_kill {
             if let Err(e) = send_signal_to_group(pid, Signal::SIGTERM) {
                 tracing::warn!("Failed to send SIGTERM to process group {}: {}", pid, e);
                 let _ = send_signal(pid, Signal::SIGTERM);
@@ -88,21 +97,20 @@ impl ChildRegistry {
                 .copied()
                 .collect();
             if still_alive.is_empty() {
-                self.pids.clear();
-                return;
+                break;
             }
             std::thread::sleep(Duration::from_millis(100));
         }
 
-        for &pid in &pids_to_kill {
+        for &pid in pids_to_kill {
             if is_process_alive(pid) {
                 tracing::warn!("Process {} did not terminate, sending SIGKILL", pid);
                 let _ = send_signal_to_group(pid, Signal::SIGKILL);
                 let _ = send_signal(pid, Signal::SIGKILL);
             }
         }
-        for &pid in &pids_to_kill {
-            self.pids.remove(&pid);
+        for pid in pids_to_kill {
+            self.pids.remove(pid);
         }
     }
 
@@ -175,6 +183,48 @@ impl ChildRegistry {
     }
 }
 
+/// Terminate a single registered child process group (SIGTERM, grace period,
+/// SIGKILL) without holding the registry lock while it waits.
+///
+/// Used by the script watchdog when a script exceeds its time limit; other
+/// tracked children are left alone. The child counts as gone once it has
+/// exited (dead, or a zombie its owner has yet to reap) or its owner
+/// unregistered it, so the wait ends as soon as it exits and a reaped pid
+/// is never signalled.
+pub fn terminate_child(pid: u32, grace_period: Duration) {
+    let registered = || {
+        ChildRegistry::global()
+            .lock()
+            // SAFETY: poison recovery via into_inner — never panic on mutex
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(pid)
+    };
+    let running = || registered() && is_process_alive(pid);
+    if !running() {
+        return;
+    }
+
+    tracing::info!(pid, "Terminating timed-out process group");
+    if let Err(e) = send_signal_to_group(pid, Signal::SIGTERM) {
+        tracing::warn!("Failed to send SIGTERM to process group {}: {}", pid, e);
+        let _ = send_signal(pid, Signal::SIGTERM);
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < grace_period {
+        if !running() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    if running() {
+        tracing::warn!("Process {} did not terminate, sending SIGKILL", pid);
+        let _ = send_signal_to_group(pid, Signal::SIGKILL);
+        let _ = send_signal(pid, Signal::SIGKILL);
+    }
+}
+
 /// Send a signal to a process
 fn send_signal(pid: u32, signal: Signal) -> Result<(), nix::Error> {
     let pid_i32 = i32::try_from(pid).map_err(|_| nix::errno::Errno::EINVAL)?;
@@ -569,6 +619,59 @@ mod tests {
         assert!(died, "Process should exit from SIGTERM trap");
     }
 
+    #[test]
+    fn test_terminate_child_returns_once_child_exits() {
+        use std::process::Command;
+
+        // Exits from SIGTERM and stays an unreaped zombie until wait() below
+        let mut child = Command::new("bash")
+            .args(["-c", "trap 'exit 0' TERM; sleep 60 & wait"])
+            .in_new_process_group()
+            .spawn()
+            .expect("Failed to spawn bash with trap");
+        let pid = child.id();
+        ChildRegistry::global()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .register(pid);
+        std::thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        terminate_child(pid, Duration::from_secs(5));
+        assert!(
+            start.elapsed() < Duration::from_secs(3),
+            "a zombie must not hold terminate_child for the full grace period"
+        );
+
+        let status = child.wait().expect("child should be reapable");
+        assert_eq!(status.code(), Some(0), "exited from SIGTERM, not SIGKILL");
+        ChildRegistry::global()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unregister(pid);
+    }
+
+    #[test]
+    fn test_terminate_child_ignores_unregistered_pid() {
+        use std::process::Command;
+
+        let mut child = Command::new("sleep")
+            .arg("60")
+            .in_new_process_group()
+            .spawn()
+            .expect("Failed to spawn sleep");
+        let pid = child.id();
+
+        terminate_child(pid, Duration::from_millis(100));
+        assert!(
+            is_process_alive(pid),
+            "unregistered pid must not be signalled"
+        );
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
     #[test]
     fn test_send_signal_to_nonexistent_pid() {
         // Sending signal to a PID that doesn't exist should return error
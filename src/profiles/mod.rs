@@ -961,8 +961,49 @@ pub mod gpu_packages {
     /// No GPU driver packages.
     pub const NONE: &[&str] = &[];
 
-    /// Fallback packages when GPU is auto-detected at runtime.
+    /// Fallback packages for `Auto` when no known GPU was detected.
     pub const AUTO: &[&str] = &["mesa"];
+
+    /// GPUs driven by the legacy `radeon` kernel driver (TeraScale, GCN 1.0/1.1):
+    /// no Vulkan, no amdgpu DDX.
+    pub const AMD_RADEON: &[&str] = &["mesa", "xf86-video-ati", "lib32-mesa"];
+
+    /// Intel Gen8+ (Broadwell and newer, Arc): modesetting DDX, Vulkan and
+    /// iHD VA-API.
+    pub const INTEL_MODERN: &[&str] = &[
+        "mesa",
+        "vulkan-intel",
+        "intel-media-driver",
+        "lib32-mesa",
+        "lib32-vulkan-intel",
+    ];
+
+    /// Intel Gen7.5 and older (Haswell, Ivy Bridge, Atom, GMA): intel DDX and
+    /// i965 VA-API.
+    pub const INTEL_LEGACY: &[&str] = &[
+        "mesa",
+        "xf86-video-intel",
+        "libva-intel-driver",
+        "lib32-mesa",
+    ];
+
+    /// VMware SVGA virtual GPU.
+    pub const VMWARE: &[&str] = &["mesa", "xf86-video-vmware"];
+
+    /// VirtualBox virtual GPU (guest additions provide the video driver).
+    pub const VIRTUALBOX: &[&str] = &["mesa", "virtualbox-guest-utils"];
+
+    /// Hybrid graphics extras: `prime-run` to offload onto an NVIDIA dGPU.
+    pub const NVIDIA_PRIME: &[&str] = &["nvidia-prime"];
+}
+
+/// CPU microcode packages indexed by CPU vendor.
+pub mod microcode_packages {
+    /// Intel CPU microcode.
+    pub const INTEL: &[&str] = &["intel-ucode"];
+
+    /// AMD CPU microcode.
+    pub const AMD: &[&str] = &["amd-ucode"];
 }
 
 /// Kernel packages indexed by kernel variant.
@@ -989,6 +1030,7 @@ pub mod kernel_packages {
 pub const BASE_PACKAGES: &[&str] = &[
     "base",
     "linux-firmware",
+    "sof-firmware", // Sound Open Firmware (modern onboard audio)
     "sudo",
     "git",        // Culturally unavoidable on Arch (AUR clones, dotfiles, every wiki tutorial)
     "man-db",     // Wiki philosophy: offline man pages > googling
@@ -999,8 +1041,8 @@ pub const BASE_PACKAGES: &[&str] = &[
 
 /// Bootloader packages.
 pub mod bootloader_packages {
-    /// GRUB bootloader packages (os-prober added conditionally by resolver).
-    pub const GRUB: &[&str] = &["grub", "efibootmgr"];
+    /// GRUB bootloader packages (efibootmgr and os-prober added conditionally by resolver).
+    pub const GRUB: &[&str] = &["grub"];
 
     /// systemd-boot (included in systemd, no extra packages needed).
     pub const SYSTEMD_BOOT: &[&str] = &[];
@@ -0,0 +1,395 @@
+//! Structured Script Events
+//!
+//! Tool scripts normally talk to us in free text. For results that automation
+//! needs to consume, a script can additionally write JSON lines to a
+//! dedicated file descriptor:
+//!
+//! - `script_runner` opens a pipe as fd [`EVENT_FD`] in the child and sets
+//!   [`EVENT_FD_ENV`] (`ARCHTUI_EVENT_FD`)
+//! - `scripts/utils.sh` provides `emit_result`, `emit_result_int`,
+//!   `emit_finding` and `emit_progress`, which write one JSON object per line
+//!   and are no-ops when the script runs standalone
+//! - The runner collects the parsed events in `ScriptOutput::events`
+//!
+//! ```text
+//! {"type":"result","key":"kernel","value":"6.9.7-arch1-1"}
+//! {"type":"finding","severity":"warning","check":"ssh_root_login","message":"SSH root login is enabled"}
+//! {"type":"progress","current":2,"total":4,"message":"Bad blocks scan"}
+//! ```
+//!
+//! Typed reports (`SystemInfoReport`, `DiskHealthReport`,
+//! `SecurityAuditReport`) are built from those events for the read-only
+//! tools, and [`ToolJsonOutput`] is the stable document printed by
+//! `archtui tools ... --json`.
+
+// Library API - reports are consumed by the CLI and external automation
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::{BufRead, BufReader, PipeReader, PipeWriter};
+use std::os::fd::AsRawFd;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+
+/// File descriptor the event pipe is opened on in the child.
+pub const EVENT_FD: i32 = 3;
+
+/// Environment variable telling scripts which fd to write events to.
+pub const EVENT_FD_ENV: &str = "ARCHTUI_EVENT_FD";
+
+/// Version of the `--json` document layout. Bump on incompatible changes.
+pub const JSON_OUTPUT_VERSION: u32 = 1;
+
+/// One structured event written by a tool script.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptEvent {
+    /// A named result value (`emit_result` / `emit_result_int`)
+    Result { key: String, value: Value },
+    /// Outcome of a single check (`emit_finding`)
+    Finding(Finding),
+    /// Progress through a multi-step run (`emit_progress`)
+    Progress {
+        current: u32,
+        total: u32,
+        #[serde(default)]
+        message: String,
+    },
+}
+
+impl ScriptEvent {
+    /// Parse one line from the event fd.
+    ///
+    /// Returns `None` (and logs) for blank or malformed lines so a buggy
+    /// script cannot fail an otherwise successful run.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                tracing::warn!(error = %e, line, "Ignoring malformed script event");
+                None
+            }
+        }
+    }
+}
+
+/// Make `writer` the child's fd [`EVENT_FD`] and advertise it via
+/// [`EVENT_FD_ENV`].
+///
+/// `writer` must stay open until the command is spawned and be dropped right
+/// after, so the reader sees EOF once the script and its children exit.
+pub fn attach_event_pipe(cmd: &mut Command, writer: &PipeWriter) {
+    use std::os::unix::process::CommandExt;
+    let fd = writer.as_raw_fd();
+    cmd.env(EVENT_FD_ENV, EVENT_FD.to_string());
+    // SAFETY: pre_exec runs in the child after fork() and before exec().
+    // dup2 and fcntl are async-signal-safe; the closure only captures an fd number.
+    unsafe {
+        cmd.pre_exec(move || {
+            if fd == EVENT_FD {
+                // Already in place; dup2 would be a no-op and keep FD_CLOEXEC set
+                if nix::libc::fcntl(fd, nix::libc::F_SETFD, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            } else if nix::libc::dup2(fd, EVENT_FD) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Read and parse events from the pipe on a background thread until EOF.
+pub fn collect_events(reader: PipeReader) -> JoinHandle<Vec<ScriptEvent>> {
+    thread::spawn(move || {
+        BufReader::new(reader)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| ScriptEvent::parse(&line))
+            .collect()
+    })
+}
+
+/// Severity of a check outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Check passed
+    Ok,
+    /// Informational, nothing to fix
+    Info,
+    /// Something worth fixing
+    Warning,
+    /// A problem that needs attention now
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Ok => "ok",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    /// How serious the outcome is
+    pub severity: Severity,
+    /// Stable check identifier (e.g. `ssh_root_login`)
+    pub check: String,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// All `Result` events as a JSON object (later keys win).
+pub fn results(events: &[ScriptEvent]) -> Map<String, Value> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            ScriptEvent::Result { key, value } => Some((key.clone(), value.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// All `Finding` events in emission order.
+pub fn findings(events: &[ScriptEvent]) -> Vec<Finding> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            ScriptEvent::Finding(finding) => Some(finding.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Deserialize the `Result` events into a typed struct.
+fn results_as<T: DeserializeOwned>(events: &[ScriptEvent]) -> Result<T, serde_json::Error> {
+    serde_json::from_value(Value::Object(results(events)))
+}
+
+/// `system_info.sh` results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemInfoReport {
+    pub hostname: Option<String>,
+    pub distribution: Option<String>,
+    pub kernel: Option<String>,
+    pub architecture: Option<String>,
+    pub boot_mode: Option<String>,
+    pub cpu_model: Option<String>,
+    pub cpu_cores: Option<u64>,
+    pub memory_total: Option<String>,
+    pub memory_used: Option<String>,
+    pub memory_available: Option<String>,
+    pub swap_usage: Option<String>,
+    pub network_interfaces: Vec<String>,
+    pub mounted_filesystems: Option<u64>,
+    pub timezone: Option<String>,
+}
+
+impl SystemInfoReport {
+    /// Build the report from `system_info.sh` events.
+    pub fn from_events(events: &[ScriptEvent]) -> Result<Self, serde_json::Error> {
+        let mut results = results(events);
+        // Interfaces travel as one space-separated result
+        if let Some(Value::String(list)) = results.remove("network_interfaces") {
+            let interfaces = list.split_whitespace().map(Value::from).collect();
+            results.insert("network_interfaces".to_string(), Value::Array(interfaces));
+        }
+        serde_json::from_value(Value::Object(results))
+    }
+}
+
+/// `check_disk_health.sh` results and per-test findings
+/// (`filesystem`, `bad_blocks`, `performance`, `smart`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskHealthReport {
+    pub device: Option<String>,
+    pub size: Option<String>,
+    pub read_speed: Option<String>,
+    pub write_speed: Option<String>,
+    /// Overall verdict: `excellent`, `good` or `issues`
+    pub verdict: Option<String>,
+    pub findings: Vec<Finding>,
+}
+
+impl DiskHealthReport {
+    /// Build the report from `check_disk_health.sh` events.
+    pub fn from_events(events: &[ScriptEvent]) -> Result<Self, serde_json::Error> {
+        let mut report: Self = results_as(events)?;
+        report.findings = findings(events);
+        Ok(report)
+    }
+}
+
+/// `security_audit.sh` findings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SecurityAuditReport {
+    pub findings: Vec<Finding>,
+}
+
+impl SecurityAuditReport {
+    /// Build the report from `security_audit.sh` events.
+    pub fn from_events(events: &[ScriptEvent]) -> Self {
+        Self {
+            findings: findings(events),
+        }
+    }
+
+    /// Highest severity among the findings, if any.
+    pub fn worst(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+}
+
+/// Typed report for a tool that has one, serialized for JSON output.
+pub fn tool_report(script_name: &str, events: &[ScriptEvent]) -> Option<Value> {
+    let report = match script_name {
+        "system_info.sh" => serde_json::to_value(SystemInfoReport::from_events(events).ok()?),
+        "check_disk_health.sh" => serde_json::to_value(DiskHealthReport::from_events(events).ok()?),
+        "security_audit.sh" => serde_json::to_value(SecurityAuditReport::from_events(events)),
+        _ => return None,
+    };
+    report.ok()
+}
+
+/// The document `archtui tools ... --json` prints on stdout.
+///
+/// Field names and meanings are stable within a [`JSON_OUTPUT_VERSION`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolJsonOutput {
+    /// Layout version ([`JSON_OUTPUT_VERSION`])
+    pub version: u32,
+    /// Script file name (e.g. `system_info.sh`)
+    pub tool: String,
+    /// Whether the exit code was one of the manifest's valid codes
+    pub success: bool,
+    /// Exit code, `null` if the script was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Whether the run was skipped by dry-run mode
+    pub dry_run: bool,
+    /// Typed report for tools that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<Value>,
+    /// Every event the script emitted
+    pub events: Vec<ScriptEvent>,
+}
+
+impl ToolJsonOutput {
+    /// Assemble the document for a finished run.
+    pub fn new(
+        tool: &str,
+        success: bool,
+        exit_code: Option<i32>,
+        dry_run: bool,
+        events: Vec<ScriptEvent>,
+    ) -> Self {
+        Self {
+            version: JSON_OUTPUT_VERSION,
+            tool: tool.to_string(),
+            success,
+            exit_code,
+            dry_run,
+            report: tool_report(tool, &events),
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(lines: &[&str]) -> Vec<ScriptEvent> {
+        lines.iter().filter_map(|l| ScriptEvent::parse(l)).collect()
+    }
+
+    #[test]
+    fn test_parse_event_lines() {
+        let events = parse_all(&[
+            r#"{"type":"result","key":"kernel","value":"6.9.7-arch1-1"}"#,
+            r#"{"type":"finding","severity":"warning","check":"ssh","message":"SSH is running"}"#,
+            r#"{"type":"progress","current":2,"total":4}"#,
+            "",
+            "not json",
+            r#"{"type":"unknown"}"#,
+        ]);
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1],
+            ScriptEvent::Finding(Finding {
+                severity: Severity::Warning,
+                check: "ssh".to_string(),
+                message: "SSH is running".to_string(),
+            })
+        );
+        assert!(matches!(
+            events[2],
+            ScriptEvent::Progress {
+                current: 2,
+                total: 4,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_system_info_report() {
+        let events = parse_all(&[
+            r#"{"type":"result","key":"hostname","value":"archiso"}"#,
+            r#"{"type":"result","key":"cpu_cores","value":8}"#,
+            r#"{"type":"result","key":"network_interfaces","value":"lo enp1s0 "}"#,
+            r#"{"type":"result","key":"future_field","value":"ignored"}"#,
+        ]);
+        let report = SystemInfoReport::from_events(&events).unwrap();
+        assert_eq!(report.hostname.as_deref(), Some("archiso"));
+        assert_eq!(report.cpu_cores, Some(8));
+        assert_eq!(report.network_interfaces, ["lo", "enp1s0"]);
+        assert_eq!(report.kernel, None);
+    }
+
+    #[test]
+    fn test_security_audit_report_worst() {
+        let events = parse_all(&[
+            r#"{"type":"finding","severity":"ok","check":"passwd_perms","message":"OK"}"#,
+            r#"{"type":"finding","severity":"warning","check":"firewall","message":"None"}"#,
+        ]);
+        let report = SecurityAuditReport::from_events(&events);
+        assert_eq!(report.findings.len(), 2);
+        assert_eq!(report.worst(), Some(Severity::Warning));
+    }
+
+    #[test]
+    fn test_json_output_document() {
+        let events = parse_all(&[
+            r#"{"type":"result","key":"device","value":"/dev/sda"}"#,
+            r#"{"type":"result","key":"verdict","value":"good"}"#,
+            r#"{"type":"finding","severity":"ok","check":"smart","message":"SMART Health: GOOD"}"#,
+        ]);
+        let doc = ToolJsonOutput::new("check_disk_health.sh", true, Some(0), false, events);
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["version"], JSON_OUTPUT_VERSION);
+        assert_eq!(json["tool"], "check_disk_health.sh");
+        assert_eq!(json["report"]["device"], "/dev/sda");
+        assert_eq!(json["report"]["findings"][0]["check"], "smart");
+        assert_eq!(json["events"].as_array().unwrap().len(), 3);
+
+        // Tools without a typed report omit the field
+        let doc = ToolJsonOutput::new("manage_swap.sh", true, Some(0), false, vec![]);
+        assert!(serde_json::to_value(&doc).unwrap().get("report").is_none());
+    }
+}
@@ -21,6 +21,11 @@
 //!   "description": "Securely wipe a disk",
 //!   "destructive": true,
 //!   "required_confirmation": "CONFIRM_WIPE_DISK",
+//!   "confirm_actions": [],
+//!   "confirm_flags": [],
+//!   "required_args": [
+//!     { "flag": "--disk", "description": "Disk to wipe", "pattern": "^/dev/" }
+//!   ],
 //!   "required_env": [
 //!     { "name": "INSTALL_DISK", "description": "Target disk device path", "pattern": "^/dev/" }
 //!   ],
@@ -29,7 +34,14 @@
 //!   ]
 //! }
 //! ```
+//!
+//! `confirm_actions` and `confirm_flags` narrow the confirmation of a
+//! destructive script to the listed `--action` values and flags; left empty,
+//! every invocation needs it. `required_args` are checked against the
+//! invocation's command line, so only [`ScriptManifest::validate_invocation`]
+//! enforces them.
 
+use crate::capability::{Capability, CapabilityRule};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -51,6 +63,18 @@ pub enum ManifestError {
     #[error("Required environment variable '{name}' is not set (script: {script})")]
     MissingEnvVar { script: String, name: String },
 
+    /// Required command-line flag missing or without a value
+    #[error("Required argument '{flag}' is not set (script: {script})")]
+    MissingArg { script: String, flag: String },
+
+    /// Command-line flag value doesn't match required pattern
+    #[error("Argument '{flag}' has invalid value '{value}': {reason}")]
+    InvalidArgValue {
+        flag: String,
+        value: String,
+        reason: String,
+    },
+
     /// Environment variable value doesn't match required pattern
     #[error("Environment variable '{name}' has invalid value '{value}': {reason}")]
     InvalidEnvValue {
@@ -147,31 +171,93 @@ impl EnvRequirement {
         }
 
         // Check pattern if specified
-        if let Some(ref pattern) = self.pattern {
-            // Simple pattern matching (prefix/suffix/contains)
-            // Full regex would require the regex crate
-            let matches = if pattern.starts_with('^') && pattern.ends_with('$') {
-                // Exact match (minus anchors)
-                let inner = &pattern[1..pattern.len() - 1];
-                value == inner
-            } else if let Some(prefix) = pattern.strip_prefix('^') {
-                // Prefix match
-                value.starts_with(prefix)
-            } else if pattern.ends_with('$') {
-                // Suffix match
-                value.ends_with(&pattern[..pattern.len() - 1])
-            } else {
-                // Contains match
-                value.contains(pattern)
-            };
-
-            if !matches {
-                return Err(ManifestError::InvalidEnvValue {
-                    name: self.name.clone(),
-                    value: value.to_string(),
-                    reason: format!("value must match pattern: {}", pattern),
-                });
-            }
+        if let Some(ref pattern) = self.pattern
+            && !matches_pattern(pattern, value)
+        {
+            return Err(ManifestError::InvalidEnvValue {
+                name: self.name.clone(),
+                value: value.to_string(),
+                reason: format!("value must match pattern: {}", pattern),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Simple pattern matching (prefix/suffix/contains/exact) for manifest values
+///
+/// Full regex would require the regex crate.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    if pattern.starts_with('^') && pattern.ends_with('$') {
+        // Exact match (minus anchors)
+        value == &pattern[1..pattern.len() - 1]
+    } else if let Some(prefix) = pattern.strip_prefix('^') {
+        // Prefix match
+        value.starts_with(prefix)
+    } else if let Some(suffix) = pattern.strip_suffix('$') {
+        // Suffix match
+        value.ends_with(suffix)
+    } else {
+        // Contains match
+        value.contains(pattern)
+    }
+}
+
+/// Command-line argument requirement: `flag` must be passed with a value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArgRequirement {
+    /// Flag name (e.g., "--device")
+    pub flag: String,
+
+    /// Human-readable description
+    pub description: String,
+
+    /// Optional pattern for the flag's value, same syntax as
+    /// [`EnvRequirement::pattern`]
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+#[allow(dead_code)] // Library API: used in manifest validation
+impl ArgRequirement {
+    /// Create a new required argument
+    pub fn new(flag: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            flag: flag.into(),
+            description: description.into(),
+            pattern: None,
+        }
+    }
+
+    /// Add a validation pattern for the value
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Validate `cli_args` of an invocation of `script` against this requirement
+    pub fn validate(&self, script: &str, cli_args: &[String]) -> Result<(), ManifestError> {
+        let value = cli_args
+            .iter()
+            .position(|arg| *arg == self.flag)
+            .and_then(|i| cli_args.get(i + 1))
+            .filter(|value| !value.is_empty());
+        let Some(value) = value else {
+            return Err(ManifestError::MissingArg {
+                script: script.to_string(),
+                flag: self.flag.clone(),
+            });
+        };
+
+        if let Some(ref pattern) = self.pattern
+            && !matches_pattern(pattern, value)
+        {
+            return Err(ManifestError::InvalidArgValue {
+                flag: self.flag.clone(),
+                value: value.clone(),
+                reason: format!("value must match pattern: {}", pattern),
+            });
         }
 
         Ok(())
@@ -232,6 +318,19 @@ pub struct ScriptManifest {
     #[serde(default)]
     pub required_confirmation: Option<String>,
 
+    /// `--action` values that need the confirmation; when this and
+    /// `confirm_flags` are both empty every invocation needs it
+    #[serde(default)]
+    pub confirm_actions: Vec<String>,
+
+    /// Flags whose presence makes an invocation need the confirmation
+    #[serde(default)]
+    pub confirm_flags: Vec<String>,
+
+    /// Command-line flags every invocation must pass with a value
+    #[serde(default)]
+    pub required_args: Vec<ArgRequirement>,
+
     /// Required environment variables (script will fail without these)
     #[serde(default)]
     pub required_env: Vec<EnvRequirement>,
@@ -240,6 +339,18 @@ pub struct ScriptManifest {
     #[serde(default)]
     pub optional_env: Vec<OptionalEnv>,
 
+    /// Capabilities that must be provided this session before the script may run
+    #[serde(default)]
+    pub requires: Vec<CapabilityRule>,
+
+    /// Capabilities a successful run provides
+    #[serde(default)]
+    pub provides: Vec<CapabilityRule>,
+
+    /// Capabilities a successful run takes away (e.g. unmounting the target)
+    #[serde(default)]
+    pub revokes: Vec<CapabilityRule>,
+
     /// Expected exit codes (default: [0] for success only)
     #[serde(default = "default_exit_codes")]
     pub valid_exit_codes: Vec<i32>,
@@ -248,6 +359,15 @@ pub struct ScriptManifest {
     #[serde(default)]
     pub needs_stdin: bool,
 
+    /// Wall-clock limit in seconds; the script is terminated once exceeded
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Longest allowed gap in seconds between output chunks before the
+    /// script is considered hung and terminated
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+
     /// Script version for compatibility tracking
     #[serde(default = "default_version")]
     pub version: String,
@@ -308,6 +428,28 @@ impl ScriptManifest {
             });
         }
 
+        // Narrowing the confirmation only makes sense for destructive scripts
+        if !self.destructive && (!self.confirm_actions.is_empty() || !self.confirm_flags.is_empty())
+        {
+            return Err(ManifestError::InvalidFormat {
+                reason: format!(
+                    "Script '{}' lists confirm_actions/confirm_flags but is not destructive",
+                    self.script
+                ),
+            });
+        }
+
+        // Required arguments are long flags, each listed once
+        let mut flags = std::collections::HashSet::new();
+        for req in &self.required_args {
+            if !req.flag.starts_with("--") || !flags.insert(&req.flag) {
+                tracing::error!(script = %self.script, flag = %req.flag, "Invalid required_args entry");
+                return Err(ManifestError::InvalidFormat {
+                    reason: format!("Invalid or duplicate required_args flag: {}", req.flag),
+                });
+            }
+        }
+
         // Check for duplicate environment variable names
         let mut seen = std::collections::HashSet::new();
         for req in &self.required_env {
@@ -327,6 +469,13 @@ impl ScriptManifest {
             }
         }
 
+        // A zero limit would terminate the script immediately
+        if self.timeout_secs == Some(0) || self.idle_timeout_secs == Some(0) {
+            return Err(ManifestError::InvalidFormat {
+                reason: format!("Script '{}' has a zero timeout", self.script),
+            });
+        }
+
         Ok(())
     }
 
@@ -344,7 +493,58 @@ impl ScriptManifest {
         env: &HashMap<String, String>,
         scripts_dir: Option<&Path>,
     ) -> Result<ValidatedExecution, ManifestError> {
-        tracing::debug!(script = %self.script, "Validating execution requirements");
+        self.validate_requirements(env, scripts_dir, self.destructive, None)
+    }
+
+    /// Validate a single invocation of the script with arguments `cli_args`
+    ///
+    /// Some scripts bundle read-only actions (e.g. `encrypt_device.sh --action open`)
+    /// with destructive ones, so the confirmation is demanded whenever
+    /// [`Self::requires_confirmation`] says so for `cli_args`. `required_args`
+    /// are checked against `cli_args`; all other requirements exactly as in
+    /// [`Self::validate_execution`].
+    pub fn validate_invocation(
+        &self,
+        env: &HashMap<String, String>,
+        scripts_dir: Option<&Path>,
+        cli_args: &[String],
+    ) -> Result<ValidatedExecution, ManifestError> {
+        self.validate_requirements(
+            env,
+            scripts_dir,
+            self.requires_confirmation(cli_args),
+            Some(cli_args),
+        )
+    }
+
+    /// Whether an invocation with `cli_args` must carry the confirmation
+    ///
+    /// Decided by the manifest alone: a destructive script needs it for
+    /// every invocation unless `confirm_actions`/`confirm_flags` narrow it
+    /// down to the listed `--action` values and flags.
+    pub fn requires_confirmation(&self, cli_args: &[String]) -> bool {
+        if !self.destructive {
+            return false;
+        }
+        if self.confirm_actions.is_empty() && self.confirm_flags.is_empty() {
+            return true;
+        }
+        let action = cli_args
+            .iter()
+            .position(|arg| arg == "--action")
+            .and_then(|i| cli_args.get(i + 1));
+        action.is_some_and(|a| self.confirm_actions.contains(a))
+            || cli_args.iter().any(|arg| self.confirm_flags.contains(arg))
+    }
+
+    fn validate_requirements(
+        &self,
+        env: &HashMap<String, String>,
+        scripts_dir: Option<&Path>,
+        confirm: bool,
+        cli_args: Option<&[String]>,
+    ) -> Result<ValidatedExecution, ManifestError> {
+        tracing::debug!(script = %self.script, confirm, "Validating execution requirements");
 
         // Check script file exists
         if let Some(base_dir) = scripts_dir {
@@ -358,9 +558,7 @@ impl ScriptManifest {
         }
 
         // Check confirmation for destructive scripts
-        if self.destructive
-            && let Some(ref confirmation_var) = self.required_confirmation
-        {
+        if confirm && let Some(ref confirmation_var) = self.required_confirmation {
             let confirmation_value = env.get(confirmation_var).map(|s| s.as_str()).unwrap_or("");
             if confirmation_value != "yes" {
                 tracing::error!(script = %self.script, var = %confirmation_var, "Missing destructive confirmation");
@@ -371,6 +569,15 @@ impl ScriptManifest {
             }
         }
 
+        // Check required arguments (only known for a concrete invocation)
+        if let Some(cli_args) = cli_args {
+            for req in &self.required_args {
+                req.validate(&self.script, cli_args).inspect_err(|_| {
+                    tracing::error!(script = %self.script, flag = %req.flag, "Missing or invalid required argument");
+                })?;
+            }
+        }
+
         // Check required environment variables
         for req in &self.required_env {
             match env.get(&req.name) {
@@ -419,6 +626,22 @@ impl ScriptManifest {
             lines.push(format!("#   {}=yes   Required for execution", conf));
         }
 
+        if !self.required_args.is_empty() {
+            lines.push("#".to_string());
+            lines.push("# REQUIRED ARGUMENTS:".to_string());
+            for req in &self.required_args {
+                let pattern_note = req
+                    .pattern
+                    .as_ref()
+                    .map(|p| format!(" (pattern: {})", p))
+                    .unwrap_or_default();
+                lines.push(format!(
+                    "#   {} - {}{}",
+                    req.flag, req.description, pattern_note
+                ));
+            }
+        }
+
         if !self.required_env.is_empty() {
             lines.push("#".to_string());
             lines.push("# REQUIRED ENVIRONMENT VARIABLES:".to_string());
@@ -485,10 +708,18 @@ pub struct ManifestBuilder {
     description: String,
     destructive: bool,
     required_confirmation: Option<String>,
+    confirm_actions: Vec<String>,
+    confirm_flags: Vec<String>,
+    required_args: Vec<ArgRequirement>,
     required_env: Vec<EnvRequirement>,
     optional_env: Vec<OptionalEnv>,
+    requires: Vec<CapabilityRule>,
+    provides: Vec<CapabilityRule>,
+    revokes: Vec<CapabilityRule>,
     valid_exit_codes: Vec<i32>,
     needs_stdin: bool,
+    timeout_secs: Option<u64>,
+    idle_timeout_secs: Option<u64>,
     version: String,
 }
 
@@ -501,10 +732,18 @@ impl ManifestBuilder {
             description: description.into(),
             destructive: false,
             required_confirmation: None,
+            confirm_actions: Vec::new(),
+            confirm_flags: Vec::new(),
+            required_args: Vec::new(),
             required_env: Vec::new(),
             optional_env: Vec::new(),
+            requires: Vec::new(),
+            provides: Vec::new(),
+            revokes: Vec::new(),
             valid_exit_codes: vec![0],
             needs_stdin: false,
+            timeout_secs: None,
+            idle_timeout_secs: None,
             version: "1.0".to_string(),
         }
     }
@@ -516,6 +755,24 @@ impl ManifestBuilder {
         self
     }
 
+    /// Only demand the confirmation for `--action <action>` (repeatable)
+    pub fn confirm_action(mut self, action: impl Into<String>) -> Self {
+        self.confirm_actions.push(action.into());
+        self
+    }
+
+    /// Demand the confirmation whenever `flag` is passed (repeatable)
+    pub fn confirm_flag(mut self, flag: impl Into<String>) -> Self {
+        self.confirm_flags.push(flag.into());
+        self
+    }
+
+    /// Add a required command-line argument
+    pub fn require_arg(mut self, req: ArgRequirement) -> Self {
+        self.required_args.push(req);
+        self
+    }
+
     /// Add a required environment variable
     pub fn require_env(mut self, req: EnvRequirement) -> Self {
         self.required_env.push(req);
@@ -528,6 +785,24 @@ impl ManifestBuilder {
         self
     }
 
+    /// Add a capability the script requires
+    pub fn requires(mut self, rule: impl Into<CapabilityRule>) -> Self {
+        self.requires.push(rule.into());
+        self
+    }
+
+    /// Add a capability the script provides
+    pub fn provides(mut self, rule: impl Into<CapabilityRule>) -> Self {
+        self.provides.push(rule.into());
+        self
+    }
+
+    /// Add a capability the script revokes
+    pub fn revokes(mut self, rule: impl Into<CapabilityRule>) -> Self {
+        self.revokes.push(rule.into());
+        self
+    }
+
     /// Set valid exit codes
     pub fn valid_exit_codes(mut self, codes: Vec<i32>) -> Self {
         self.valid_exit_codes = codes;
@@ -540,6 +815,18 @@ impl ManifestBuilder {
         self
     }
 
+    /// Set the wall-clock timeout in seconds
+    pub fn timeout_secs(mut self, secs: u64) -> Self {
+        self.timeout_secs = Some(secs);
+        self
+    }
+
+    /// Set the idle-output timeout in seconds
+    pub fn idle_timeout_secs(mut self, secs: u64) -> Self {
+        self.idle_timeout_secs = Some(secs);
+        self
+    }
+
     /// Set version
     pub fn version(mut self, version: impl Into<String>) -> Self {
         self.version = version.into();
@@ -553,10 +840,18 @@ impl ManifestBuilder {
             description: self.description,
             destructive: self.destructive,
             required_confirmation: self.required_confirmation,
+            confirm_actions: self.confirm_actions,
+            confirm_flags: self.confirm_flags,
+            required_args: self.required_args,
             required_env: self.required_env,
             optional_env: self.optional_env,
+            requires: self.requires,
+            provides: self.provides,
+            revokes: self.revokes,
             valid_exit_codes: self.valid_exit_codes,
             needs_stdin: self.needs_stdin,
+            timeout_secs: self.timeout_secs,
+            idle_timeout_secs: self.idle_timeout_secs,
             version: self.version,
         };
         manifest.validate_structure()?;
@@ -637,6 +932,24 @@ impl ManifestRegistry {
         self.manifests.values()
     }
 
+    /// Create a registry with the core manifests plus every manifest shipped
+    /// in `<scripts dir>/manifests`
+    ///
+    /// A missing or unreadable manifest directory is logged and leaves only
+    /// the core manifests registered.
+    pub fn load_default() -> Self {
+        let mut registry = Self::with_core_manifests();
+        let manifest_dir = crate::script_runner::scripts_base_dir().join("manifests");
+        if let Err(e) = registry.load_from_directory(&manifest_dir) {
+            tracing::warn!(
+                "Failed to load manifests from {}: {}",
+                manifest_dir.display(),
+                e
+            );
+        }
+        registry
+    }
+
     /// Create a registry with built-in manifests for core scripts
     pub fn with_core_manifests() -> Self {
         let mut registry = Self::new();
@@ -675,6 +988,8 @@ impl ManifestRegistry {
                     "none",
                 ))
                 .needs_stdin() // For password passing
+                // No overall limit (slow mirrors), but a silent half hour means a hang
+                .idle_timeout_secs(1800)
                 .build()
                 .expect("Core manifest should be valid"), // SAFETY: hardcoded valid manifest — cannot fail
         );
@@ -683,14 +998,13 @@ impl ManifestRegistry {
         registry.register(
             ScriptManifest::builder("scripts/tools/wipe_disk.sh", "Securely wipe a disk")
                 .destructive("CONFIRM_WIPE_DISK")
-                .require_env(
-                    EnvRequirement::new("INSTALL_DISK", "Disk to wipe").with_pattern("^/dev/"),
-                )
+                .require_arg(ArgRequirement::new("--disk", "Disk to wipe").with_pattern("^/dev/"))
                 .optional_env(OptionalEnv::new(
                     "WIPE_METHOD",
                     "Wipe method (quick or zero)",
                     "quick",
                 ))
+                .provides(Capability::DiskWiped)
                 .build()
                 .expect("Core manifest should be valid"), // SAFETY: hardcoded valid manifest — cannot fail
         );
@@ -702,9 +1016,10 @@ impl ManifestRegistry {
                 "Manual disk partitioning with cfdisk",
             )
             .destructive("CONFIRM_MANUAL_PARTITION")
-            .require_env(
-                EnvRequirement::new("INSTALL_DISK", "Disk to partition").with_pattern("^/dev/"),
+            .require_arg(
+                ArgRequirement::new("--device", "Disk to partition").with_pattern("^/dev/"),
             )
+            .provides(Capability::DiskPartitioned)
             .build()
             .expect("Core manifest should be valid"), // SAFETY: hardcoded valid manifest — cannot fail
         );
@@ -825,10 +1140,18 @@ mod tests {
             description: "test".to_string(),
             destructive: true,
             required_confirmation: None, // This is invalid!
+            confirm_actions: vec![],
+            confirm_flags: vec![],
+            required_args: vec![],
             required_env: vec![],
             optional_env: vec![],
+            requires: vec![],
+            provides: vec![],
+            revokes: vec![],
             valid_exit_codes: vec![0],
             needs_stdin: false,
+            timeout_secs: None,
+            idle_timeout_secs: None,
             version: "1.0".to_string(),
         };
 
@@ -859,6 +1182,52 @@ mod tests {
         assert_eq!(manifest.optional_env.len(), 1);
     }
 
+    #[test]
+    fn test_manifest_capabilities_from_json() {
+        use crate::capability::{Capability, CapabilityRule};
+
+        let json = r#"{
+            "script": "scripts/tools/generate_fstab.sh",
+            "description": "Generate fstab",
+            "requires": ["RootMounted"],
+            "provides": [{"capability": "FstabGenerated"}]
+        }"#;
+
+        let manifest = ScriptManifest::from_json(json).unwrap();
+        assert_eq!(
+            manifest.requires,
+            vec![CapabilityRule::from(Capability::RootMounted)]
+        );
+        assert_eq!(
+            manifest.provides,
+            vec![CapabilityRule::from(Capability::FstabGenerated)]
+        );
+        assert!(manifest.revokes.is_empty());
+    }
+
+    #[test]
+    fn test_manifest_timeouts_from_json() {
+        let json = r#"{
+            "script": "scripts/tools/update_mirrors.sh",
+            "description": "Rank mirrors",
+            "timeout_secs": 600
+        }"#;
+
+        let manifest = ScriptManifest::from_json(json).unwrap();
+        assert_eq!(manifest.timeout_secs, Some(600));
+        assert_eq!(manifest.idle_timeout_secs, None);
+
+        let zero = r#"{
+            "script": "test.sh",
+            "description": "test",
+            "idle_timeout_secs": 0
+        }"#;
+        assert!(matches!(
+            ScriptManifest::from_json(zero),
+            Err(ManifestError::InvalidFormat { .. })
+        ));
+    }
+
     #[test]
     fn test_manifest_duplicate_env_vars_rejected() {
         let json = r#"{
@@ -924,6 +1293,52 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_requires_confirmation_follows_manifest() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let every = ScriptManifest::builder("scripts/dangerous.sh", "Dangerous")
+            .destructive("CONFIRM_DANGER")
+            .build()
+            .unwrap();
+        assert!(every.requires_confirmation(&[]));
+        assert!(every.requires_confirmation(&args(&["--action", "open"])));
+
+        let narrowed = ScriptManifest::builder("scripts/luks.sh", "LUKS")
+            .destructive("CONFIRM_LUKS")
+            .confirm_action("format")
+            .confirm_flag("--save")
+            .build()
+            .unwrap();
+        assert!(narrowed.requires_confirmation(&args(&["--action", "format"])));
+        assert!(!narrowed.requires_confirmation(&args(&["--action", "open"])));
+        assert!(narrowed.requires_confirmation(&args(&["--action", "open", "--save"])));
+
+        let env = HashMap::new();
+        assert!(
+            narrowed
+                .validate_invocation(&env, None, &args(&["--action", "open"]))
+                .is_ok()
+        );
+        assert!(matches!(
+            narrowed.validate_invocation(&env, None, &args(&["--action", "format"])),
+            Err(ManifestError::MissingConfirmation { .. })
+        ));
+    }
+
+    #[test]
+    fn test_confirm_actions_require_destructive_manifest() {
+        let json = r#"{
+            "script": "test.sh",
+            "description": "test",
+            "confirm_actions": ["format"]
+        }"#;
+
+        assert!(matches!(
+            ScriptManifest::from_json(json),
+            Err(ManifestError::InvalidFormat { .. })
+        ));
+    }
+
     #[test]
     fn test_validate_execution_success() {
         let manifest = ScriptManifest::builder("scripts/test.sh", "Test")
@@ -982,6 +1397,48 @@ mod tests {
         assert!(matches!(result, Err(ManifestError::InvalidEnvValue { .. })));
     }
 
+    #[test]
+    fn test_validate_invocation_checks_required_args() {
+        let manifest = ScriptManifest::builder("scripts/test.sh", "Test")
+            .require_arg(ArgRequirement::new("--device", "Target disk").with_pattern("^/dev/"))
+            .build()
+            .unwrap();
+        let env = HashMap::new();
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(
+            manifest
+                .validate_invocation(&env, None, &args(&["--device", "/dev/sda"]))
+                .is_ok()
+        );
+        assert!(matches!(
+            manifest.validate_invocation(&env, None, &args(&["--action", "list"])),
+            Err(ManifestError::MissingArg { .. })
+        ));
+        assert!(matches!(
+            manifest.validate_invocation(&env, None, &args(&["--device"])),
+            Err(ManifestError::MissingArg { .. })
+        ));
+        assert!(matches!(
+            manifest.validate_invocation(&env, None, &args(&["--device", "sda"])),
+            Err(ManifestError::InvalidArgValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_required_args_must_be_unique_long_flags() {
+        for flags in [vec!["device"], vec!["--device", "--device"]] {
+            let mut builder = ScriptManifest::builder("scripts/test.sh", "Test");
+            for flag in flags {
+                builder = builder.require_arg(ArgRequirement::new(flag, "Target disk"));
+            }
+            assert!(matches!(
+                builder.build(),
+                Err(ManifestError::InvalidFormat { .. })
+            ));
+        }
+    }
+
     // =========================================================================
     // Registry Tests
     // =========================================================================
@@ -1016,7 +1473,6 @@ mod tests {
 
         let mut env = HashMap::new();
         env.insert("CONFIRM_WIPE_DISK".to_string(), "yes".to_string());
-        env.insert("INSTALL_DISK".to_string(), "/dev/sda".to_string());
 
         let result = registry.validate_execution("scripts/tools/wipe_disk.sh", &env, None);
         assert!(result.is_ok());
@@ -1048,6 +1504,7 @@ mod tests {
         let manifest =
             ScriptManifest::builder("scripts/tools/wipe_disk.sh", "Securely wipe a disk")
                 .destructive("CONFIRM_WIPE_DISK")
+                .require_arg(ArgRequirement::new("--disk", "Target disk").with_pattern("^/dev/"))
                 .require_env(
                     EnvRequirement::new("INSTALL_DISK", "Target disk").with_pattern("^/dev/"),
                 )
@@ -1059,6 +1516,8 @@ mod tests {
 
         assert!(header.contains("ENVIRONMENT CONTRACT:"));
         assert!(header.contains("CONFIRM_WIPE_DISK=yes"));
+        assert!(header.contains("REQUIRED ARGUMENTS:"));
+        assert!(header.contains("--disk - Target disk (pattern: ^/dev/)"));
         assert!(header.contains("REQUIRED ENVIRONMENT VARIABLES:"));
         assert!(header.contains("INSTALL_DISK"));
         assert!(header.contains("OPTIONAL ENVIRONMENT VARIABLES:"));
@@ -7,6 +7,7 @@
 //! - Proper PID registration for cleanup
 //! - Type-safe argument passing via `ScriptArgs` trait
 //! - Dry-run mode support
+//! - Manifest enforcement (`scripts/manifests/*.json`)
 //!
 //! # Architecture Rule
 //!
@@ -22,12 +23,32 @@
 //!
 //! Non-destructive scripts (like `lsblk`) still execute so the dry-run
 //! produces realistic output for validation.
+//!
+//! # Manifests
+//!
+//! Every invocation is checked against the script's `ScriptManifest` before
+//! anything is spawned (dry-run included). Scripts without a manifest are
+//! refused, a destructive invocation must carry the manifest's
+//! `required_confirmation`, and `valid_exit_codes` decides
+//! `ScriptOutput::success`. A script that exceeds the manifest's
+//! `timeout_secs` or `idle_timeout_secs` is terminated by the watchdog and
+//! reported as `ArchTuiError::Timeout`.
 
+use crate::error::ArchTuiError;
 use crate::process_guard::{ChildRegistry, CommandProcessGroup};
-use crate::script_traits::{ScriptArgs, is_dry_run};
+use crate::script_events::{ScriptEvent, attach_event_pipe, collect_events};
+use crate::script_manifest::ManifestRegistry;
+use crate::script_traits::{ScriptArgs, is_dry_run, shell_safe};
+use crate::watchdog::{ActivityClock, Watchdog, WatchdogLimits};
 use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
 use tracing::{info, warn};
 
 /// Resolve the log directory.
@@ -80,6 +101,12 @@ pub fn scripts_base_dir() -> PathBuf {
     PathBuf::from("./scripts")
 }
 
+/// Manifests consulted by `run_script_safe`, loaded once on first use.
+pub fn manifest_registry() -> &'static ManifestRegistry {
+    static REGISTRY: OnceLock<ManifestRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ManifestRegistry::load_default)
+}
+
 /// Execute a tool script with type-safe arguments.
 ///
 /// This is the ONLY way to execute scripts in the `scripts/tools/` directory.
@@ -101,6 +128,12 @@ pub fn scripts_base_dir() -> PathBuf {
 /// Non-destructive scripts (e.g., `lsblk`, `system_info`) still execute
 /// so disk lists and system checks work during dry-run.
 ///
+/// # Manifest Enforcement
+///
+/// The invocation is validated against `scripts/tools/<name>` in the
+/// manifest registry (core manifests plus `<scripts dir>/manifests`). See
+/// [`run_script_with_manifests`] for the rules.
+///
 /// # Arguments
 ///
 /// * `args` - A struct implementing `ScriptArgs` that provides CLI args and env vars
@@ -108,7 +141,8 @@ pub fn scripts_base_dir() -> PathBuf {
 /// # Returns
 ///
 /// - `Ok(output)` - Script executed successfully with stdout/stderr captured
-/// - `Err` - Script not found, execution failed, or non-zero exit
+/// - `Err` - Script or manifest not found, manifest check failed, spawning failed,
+///   or the script timed out
 ///
 /// # Example
 ///
@@ -124,9 +158,152 @@ pub fn scripts_base_dir() -> PathBuf {
 ///
 /// run_script_safe(&args)?;
 /// ```
-pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
-    let script_name = args.script_name();
-    let script_path = scripts_base_dir().join("tools").join(script_name);
+pub fn run_script_safe<T: ScriptArgs + ?Sized>(args: &T) -> Result<ScriptOutput> {
+    run_script_with_manifests(args, manifest_registry(), &scripts_base_dir())
+}
+
+/// Execute a tool script and stream its output line by line.
+///
+/// Same validation, dry-run handling and death-pact registration as
+/// [`run_script_safe`]; `on_line` is called on the calling thread for every
+/// stdout/stderr line as it is produced. The returned `ScriptOutput` still
+/// carries the complete output.
+///
+/// ```ignore
+/// run_script_streaming(&args, |line| println!("{}", line.line))?;
+/// ```
+pub fn run_script_streaming<T: ScriptArgs + ?Sized>(
+    args: &T,
+    on_line: impl FnMut(OutputLine),
+) -> Result<ScriptOutput> {
+    let invocation = ScriptInvocation::from_args(args)?;
+    run_invocation_streaming(
+        &invocation,
+        manifest_registry(),
+        &scripts_base_dir(),
+        on_line,
+    )
+}
+
+/// Execute a tool script from `scripts_dir/tools` after checking it against
+/// `manifests`.
+///
+/// - A script with no manifest is refused before anything is spawned.
+/// - `required_env` is validated against `args.get_env_vars()`.
+/// - The manifest's `required_confirmation` is only demanded when
+///   `args.is_destructive()`, so read-only actions of a destructive script
+///   (e.g. `LuksOpenArgs`) still run.
+/// - `ScriptOutput::success` is true when the exit code is one of the
+///   manifest's `valid_exit_codes`.
+/// - `timeout_secs` / `idle_timeout_secs` are enforced by a [`Watchdog`];
+///   a terminated script returns an `ArchTuiError::Timeout` error instead of
+///   a `ScriptOutput`.
+///
+/// Optional env defaults are not injected; scripts keep applying their own.
+pub fn run_script_with_manifests<T: ScriptArgs + ?Sized>(
+    args: &T,
+    manifests: &ManifestRegistry,
+    scripts_dir: &Path,
+) -> Result<ScriptOutput> {
+    let invocation = ScriptInvocation::from_args(args)?;
+    run_invocation_streaming(&invocation, manifests, scripts_dir, |_| {})
+}
+
+/// Which stream a line of script output came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// One line of live script output.
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    /// Stream the line was written to
+    pub stream: OutputStream,
+    /// Line content without the trailing newline
+    pub line: String,
+    /// When the line was read
+    #[allow(dead_code)] // API: For callers that log or pace output
+    pub timestamp: SystemTime,
+}
+
+impl OutputLine {
+    fn new(stream: OutputStream, line: impl Into<String>) -> Self {
+        Self {
+            stream,
+            line: line.into(),
+            timestamp: SystemTime::now(),
+        }
+    }
+}
+
+/// A tool script invocation detached from its typed `ScriptArgs`.
+///
+/// Lets callers that only keep the rendered arguments (the TUI's confirm
+/// dialog round-trips them through JSON) run through the same path as
+/// typed args.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptInvocation {
+    /// Script filename in `scripts/tools/`
+    pub script_name: String,
+    /// CLI arguments
+    pub cli_args: Vec<String>,
+    /// Environment variables
+    pub env_vars: Vec<(String, String)>,
+    /// Whether the invocation modifies system state
+    pub destructive: bool,
+}
+
+impl ScriptInvocation {
+    /// Render typed args after running their `validate()`.
+    pub fn from_args<T: ScriptArgs + ?Sized>(args: &T) -> Result<Self> {
+        args.validate()
+            .map_err(|e| anyhow::anyhow!("Argument validation failed: {}", e))?;
+        tracing::debug!(script = %args.script_name(), "Argument validation passed");
+        Ok(Self {
+            script_name: args.script_name().to_string(),
+            cli_args: args.to_cli_args(),
+            env_vars: args.get_env_vars(),
+            destructive: args.is_destructive(),
+        })
+    }
+
+    /// Build an invocation from already-rendered arguments, applying the
+    /// default `shell_safe` argument check.
+    pub fn new(
+        script_name: impl Into<String>,
+        cli_args: Vec<String>,
+        env_vars: Vec<(String, String)>,
+        destructive: bool,
+    ) -> Result<Self> {
+        if let Some(arg) = cli_args.iter().find(|arg| !shell_safe(arg)) {
+            bail!(
+                "Argument validation failed: Unsafe characters in argument: {}",
+                arg
+            );
+        }
+        Ok(Self {
+            script_name: script_name.into(),
+            cli_args,
+            env_vars,
+            destructive,
+        })
+    }
+}
+
+/// Execute an invocation against `manifests`, streaming output to `on_line`.
+///
+/// The rules of [`run_script_with_manifests`] apply. In dry-run mode a
+/// destructive invocation produces a single `[DRY RUN] Skipped` stdout line.
+pub fn run_invocation_streaming(
+    invocation: &ScriptInvocation,
+    manifests: &ManifestRegistry,
+    scripts_dir: &Path,
+    mut on_line: impl FnMut(OutputLine),
+) -> Result<ScriptOutput> {
+    let script_name = invocation.script_name.as_str();
+    let script_path = scripts_dir.join("tools").join(script_name);
     let script_path = script_path.to_string_lossy().to_string();
 
     // Validate script exists before attempting execution
@@ -138,22 +315,32 @@ pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
         );
     }
 
-    let cli_args = args.to_cli_args();
-    let env_vars = args.get_env_vars();
+    let cli_args = &invocation.cli_args;
+    let env_vars = &invocation.env_vars;
 
-    args.validate()
-        .map_err(|e| anyhow::anyhow!("Argument validation failed: {}", e))?;
-    tracing::debug!(script = %script_name, "Argument validation passed");
+    let manifest_key = format!("scripts/tools/{}", script_name);
+    let Some(manifest) = manifests.get(&manifest_key) else {
+        warn!(script = %script_name, "Refusing to run script without a manifest");
+        bail!(
+            "Refusing to run {}: no manifest found for {}",
+            script_name,
+            manifest_key
+        );
+    };
+    let env_map: HashMap<String, String> = env_vars.iter().cloned().collect();
+    let validated = manifest
+        .validate_invocation(&env_map, None, cli_args)
+        .map_err(|e| anyhow::anyhow!("Manifest validation failed: {}", e))?;
 
     // Log exact command and environment for transparency
     // redact password-containing env vars
-    let redacted_env = redact_env_vars(&env_vars);
+    let redacted_env = redact_env_vars(env_vars);
     info!(
         script = %script_name,
         path = %script_path,
         args = ?cli_args,
         env = ?redacted_env,
-        destructive = args.is_destructive(),
+        destructive = invocation.destructive,
         "Spawning script"
     );
 
@@ -161,9 +348,9 @@ pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
     // DRY-RUN CHECK
     // Skip destructive operations when dry-run is enabled
     // ========================================================================
-    if is_dry_run() && args.is_destructive() {
+    if is_dry_run() && invocation.destructive {
         // Format the command that WOULD have been executed (passwords redacted)
-        let env_display = redact_env_vars(&env_vars);
+        let env_display = redact_env_vars(env_vars);
         let env_prefix = if env_display.is_empty() {
             String::new()
         } else {
@@ -175,34 +362,47 @@ pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
         info!("[DRY RUN] Would execute: {}", would_execute);
 
         // Return success without executing
+        let skipped = format!("[DRY RUN] Skipped: {}", script_name);
+        on_line(OutputLine::new(OutputStream::Stdout, skipped.clone()));
         return Ok(ScriptOutput {
-            stdout: format!("[DRY RUN] Skipped: {}\n", script_name),
+            stdout: format!("{}\n", skipped),
             stderr: String::new(),
             exit_code: Some(0),
             success: true,
             dry_run: true,
+            events: Vec::new(),
         });
     }
 
     // Build command with process group isolation.
     // Absolute path defeats PATH-injection attacks (a malicious "bash" earlier in PATH).
     let mut cmd = Command::new("/bin/bash");
+    // stdin is closed: a prompt would otherwise read from (or hang on) the
+    // TUI's terminal
     cmd.arg(&script_path)
-        .args(&cli_args)
+        .args(cli_args)
+        .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .in_new_process_group(); // CRITICAL: Enables death pact
 
     // Inject environment variables from typed args
-    for (key, value) in &env_vars {
+    for (key, value) in env_vars {
         cmd.env(key, value);
     }
 
+    // Structured events channel (see script_events)
+    let (event_reader, event_writer) =
+        std::io::pipe().context("Failed to create script event pipe")?;
+    attach_event_pipe(&mut cmd, &event_writer);
+
     // Spawn and register with global registry
-    let child = cmd
-        .spawn()
-        .with_context(|| format!("Failed to spawn script: {}", script_path))?;
+    let spawned = cmd.spawn();
+    // Only the child may hold the write end, or the event reader never sees EOF
+    drop(event_writer);
+    let mut child = spawned.with_context(|| format!("Failed to spawn script: {}", script_path))?;
     let pid = child.id();
+    let event_collector = collect_events(event_reader);
 
     // Register PID for cleanup on parent exit
     {
@@ -214,10 +414,48 @@ pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
         guard.register(pid);
     }
 
+    // Read output on background threads; lines are handed to `on_line` here
+    // so the callback does not have to be Send
+    let clock = ActivityClock::new();
+    let (line_tx, line_rx) = mpsc::channel();
+    let stdout_reader = stream_lines(
+        child.stdout.take(),
+        OutputStream::Stdout,
+        clock.clone(),
+        line_tx.clone(),
+    );
+    let stderr_reader = stream_lines(
+        child.stderr.take(),
+        OutputStream::Stderr,
+        clock.clone(),
+        line_tx,
+    );
+    let watchdog = Watchdog::spawn(
+        script_name,
+        pid,
+        WatchdogLimits::from_manifest(manifest),
+        clock,
+    );
+
+    // Ends once both readers reach EOF (the script and its children exited)
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    for line in line_rx {
+        let buffer = match line.stream {
+            OutputStream::Stdout => &mut stdout,
+            OutputStream::Stderr => &mut stderr,
+        };
+        buffer.push_str(&line.line);
+        buffer.push('\n');
+        on_line(line);
+    }
+    let _ = stdout_reader.join();
+    let _ = stderr_reader.join();
+    let events = event_collector.join().unwrap_or_default();
+
     // Wait for completion
-    let output = child
-        .wait_with_output()
-        .with_context(|| format!("Failed waiting for script: {}", script_name))?;
+    let status = child.wait();
+    let timed_out = watchdog.finish();
 
     // Unregister PID after completion
     {
@@ -229,30 +467,70 @@ pub fn run_script_safe<T: ScriptArgs>(args: &T) -> Result<ScriptOutput> {
         guard.unregister(pid);
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let exit_code = output.status.code();
+    if let Some(timeout) = timed_out {
+        warn!(script = %script_name, "{}", timeout);
+        return Err(ArchTuiError::from(timeout).into());
+    }
+    let status = status.with_context(|| format!("Failed waiting for script: {}", script_name))?;
+    let exit_code = status.code();
 
-    if output.status.success() {
+    // Signal termination (no exit code) is never a valid outcome
+    let success = exit_code.is_some_and(|code| validated.is_valid_exit_code(code));
+    if success {
         info!(script = %script_name, exit_code = ?exit_code, "Script completed successfully");
-        Ok(ScriptOutput {
-            stdout,
-            stderr,
-            exit_code,
-            success: true,
-            dry_run: false,
-        })
     } else {
         let code = exit_code.unwrap_or(-1);
         warn!(script = %script_name, exit_code = code, "Script failed");
-        Ok(ScriptOutput {
-            stdout,
-            stderr,
-            exit_code,
-            success: false,
-            dry_run: false,
-        })
     }
+    Ok(ScriptOutput {
+        stdout,
+        stderr,
+        exit_code,
+        success,
+        dry_run: false,
+        events,
+    })
+}
+
+/// Split a child's output stream into `OutputLine`s on a background thread.
+///
+/// Reads in chunks and touches `clock` for each one, so progress output
+/// that only redraws with `\r` still counts as activity for the watchdog.
+fn stream_lines<R: Read + Send + 'static>(
+    stream: Option<R>,
+    kind: OutputStream,
+    clock: ActivityClock,
+    tx: Sender<OutputLine>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let Some(mut stream) = stream else {
+            return;
+        };
+        let emit = |bytes: &[u8]| {
+            let text = String::from_utf8_lossy(bytes);
+            let _ = tx.send(OutputLine::new(kind, text.trim_end_matches('\r')));
+        };
+        let mut pending = Vec::new();
+        let mut buf = [0u8; 8192];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    clock.touch();
+                    pending.extend_from_slice(&buf[..n]);
+                    while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                        emit(&pending[..pos]);
+                        pending.drain(..=pos);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        if !pending.is_empty() {
+            emit(&pending);
+        }
+    })
 }
 
 /// Names that are unconditionally treated as secret. New secret-bearing
@@ -304,17 +582,20 @@ pub fn redact_env_vars(env_vars: &[(String, String)]) -> Vec<String> {
 #[derive(Debug, Clone)]
 pub struct ScriptOutput {
     /// Standard output from the script.
+    #[allow(dead_code)] // API: Read by library callers; the CLI streams lines instead
     pub stdout: String,
     /// Standard error from the script.
     pub stderr: String,
     /// Exit code (None if terminated by signal).
     pub exit_code: Option<i32>,
-    /// Whether the script exited successfully (exit code 0).
+    /// Whether the exit code is one of the manifest's `valid_exit_codes`.
     pub success: bool,
     /// Whether this was a dry-run (script not actually executed).
     /// Callers can check this to know if the output is real or simulated.
     #[allow(dead_code)] // API: Checked by callers to distinguish real vs simulated output
     pub dry_run: bool,
+    /// Structured events the script wrote to its event fd, in order.
+    pub events: Vec<ScriptEvent>,
 }
 
 impl ScriptOutput {
@@ -430,6 +711,396 @@ mod tests {
         assert!(!redacted.contains("luks_canary"));
     }
 
+    /// Test-only args for `tools/probe.sh`, which exits with its first argument
+    /// and leaves a marker file behind when it runs. Destructive runs pass
+    /// `--destroy`, the flag the probe manifest demands confirmation for.
+    struct ProbeArgs {
+        exit_code: i32,
+        destructive: bool,
+        confirm: bool,
+    }
+
+    impl ScriptArgs for ProbeArgs {
+        fn to_cli_args(&self) -> Vec<String> {
+            let mut args = vec![self.exit_code.to_string()];
+            if self.destructive {
+                args.push("--destroy".to_string());
+            }
+            args
+        }
+
+        fn get_env_vars(&self) -> Vec<(String, String)> {
+            if self.confirm {
+                vec![("CONFIRM_PROBE".to_string(), "yes".to_string())]
+            } else {
+                vec![]
+            }
+        }
+
+        fn script_name(&self) -> &'static str {
+            "probe.sh"
+        }
+
+        fn is_destructive(&self) -> bool {
+            self.destructive
+        }
+    }
+
+    fn probe_scripts_dir() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("tools")).unwrap();
+        std::fs::write(
+            dir.path().join("tools/probe.sh"),
+            "touch \"$(dirname \"$0\")/ran\"\nexit \"$1\"\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn probe_registry() -> ManifestRegistry {
+        let mut registry = ManifestRegistry::new();
+        registry.register(
+            crate::script_manifest::ScriptManifest::builder("scripts/tools/probe.sh", "Probe")
+                .destructive("CONFIRM_PROBE")
+                .confirm_flag("--destroy")
+                .valid_exit_codes(vec![0, 3])
+                .build()
+                .unwrap(),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_script_without_manifest_is_refused() {
+        let dir = probe_scripts_dir();
+        let args = ProbeArgs {
+            exit_code: 0,
+            destructive: false,
+            confirm: false,
+        };
+
+        let err = run_script_with_manifests(&args, &ManifestRegistry::new(), dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no manifest"), "unexpected error: {}", err);
+        assert!(!dir.path().join("tools/ran").exists());
+    }
+
+    #[test]
+    fn test_destructive_invocation_requires_confirmation() {
+        let dir = probe_scripts_dir();
+        let args = ProbeArgs {
+            exit_code: 0,
+            destructive: true,
+            confirm: false,
+        };
+
+        let err = run_script_with_manifests(&args, &probe_registry(), dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("CONFIRM_PROBE"), "unexpected error: {}", err);
+        assert!(!dir.path().join("tools/ran").exists());
+    }
+
+    #[test]
+    fn test_read_only_invocation_skips_confirmation() {
+        let dir = probe_scripts_dir();
+        let args = ProbeArgs {
+            exit_code: 0,
+            destructive: false,
+            confirm: false,
+        };
+
+        let output = run_script_with_manifests(&args, &probe_registry(), dir.path()).unwrap();
+        assert!(output.success);
+        assert!(dir.path().join("tools/ran").exists());
+    }
+
+    #[test]
+    fn test_manifest_not_caller_decides_confirmation() {
+        let dir = probe_scripts_dir();
+        let mut registry = ManifestRegistry::new();
+        registry.register(
+            crate::script_manifest::ScriptManifest::builder("scripts/tools/probe.sh", "Probe")
+                .destructive("CONFIRM_PROBE")
+                .build()
+                .unwrap(),
+        );
+        // Args claiming to be read-only cannot skip a confirmation the
+        // manifest demands for every invocation
+        let args = ProbeArgs {
+            exit_code: 0,
+            destructive: false,
+            confirm: false,
+        };
+
+        let err = run_script_with_manifests(&args, &registry, dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("CONFIRM_PROBE"), "unexpected error: {}", err);
+        assert!(!dir.path().join("tools/ran").exists());
+    }
+
+    #[test]
+    fn test_success_follows_valid_exit_codes() {
+        let dir = probe_scripts_dir();
+        let registry = probe_registry();
+        let run = |exit_code| {
+            let args = ProbeArgs {
+                exit_code,
+                destructive: false,
+                confirm: false,
+            };
+            run_script_with_manifests(&args, &registry, dir.path()).unwrap()
+        };
+
+        let allowed = run(3);
+        assert_eq!(allowed.exit_code, Some(3));
+        assert!(allowed.success, "exit 3 is listed in valid_exit_codes");
+
+        let rejected = run(4);
+        assert_eq!(rejected.exit_code, Some(4));
+        assert!(!rejected.success);
+    }
+
+    /// Scripts dir whose `tools/probe.sh` runs `body` instead of exiting.
+    fn slow_probe_scripts_dir(body: &str) -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("tools")).unwrap();
+        std::fs::write(dir.path().join("tools/probe.sh"), body).unwrap();
+        dir
+    }
+
+    fn limited_registry(
+        timeout_secs: Option<u64>,
+        idle_timeout_secs: Option<u64>,
+    ) -> ManifestRegistry {
+        let mut builder =
+            crate::script_manifest::ScriptManifest::builder("scripts/tools/probe.sh", "Probe");
+        if let Some(secs) = timeout_secs {
+            builder = builder.timeout_secs(secs);
+        }
+        if let Some(secs) = idle_timeout_secs {
+            builder = builder.idle_timeout_secs(secs);
+        }
+        let mut registry = ManifestRegistry::new();
+        registry.register(builder.build().unwrap());
+        registry
+    }
+
+    fn quiet_probe() -> ProbeArgs {
+        ProbeArgs {
+            exit_code: 0,
+            destructive: false,
+            confirm: false,
+        }
+    }
+
+    #[test]
+    fn test_total_timeout_terminates_script() {
+        let dir = slow_probe_scripts_dir("echo started\nsleep 30\n");
+        let started = std::time::Instant::now();
+
+        let err =
+            run_script_with_manifests(&quiet_probe(), &limited_registry(Some(1), None), dir.path())
+                .unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(15));
+        assert!(matches!(
+            err.downcast_ref::<ArchTuiError>(),
+            Some(ArchTuiError::Timeout(_))
+        ));
+        assert!(
+            err.to_string().contains("did not finish within 1s"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_idle_timeout_terminates_silent_script() {
+        let dir = slow_probe_scripts_dir("echo started\nsleep 30\n");
+
+        let err =
+            run_script_with_manifests(&quiet_probe(), &limited_registry(None, Some(1)), dir.path())
+                .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchTuiError>(),
+            Some(ArchTuiError::Timeout(_))
+        ));
+        assert!(err.to_string().contains("no output for 1s"), "{}", err);
+    }
+
+    #[test]
+    fn test_idle_timeout_spares_chatty_script() {
+        let dir = slow_probe_scripts_dir("for i in 1 2 3 4 5 6; do echo $i; sleep 0.3; done\n");
+
+        let output =
+            run_script_with_manifests(&quiet_probe(), &limited_registry(None, Some(1)), dir.path())
+                .unwrap();
+        assert!(output.success);
+        assert_eq!(output.stdout.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_streaming_yields_lines_per_stream() {
+        let dir = slow_probe_scripts_dir("echo one\necho oops >&2\nprintf 'two\\r\\nthree'\n");
+        let invocation = ScriptInvocation::from_args(&quiet_probe()).unwrap();
+
+        let mut lines = Vec::new();
+        let output = run_invocation_streaming(
+            &invocation,
+            &limited_registry(None, None),
+            dir.path(),
+            |line| lines.push((line.stream, line.line)),
+        )
+        .unwrap();
+
+        let stdout: Vec<_> = lines
+            .iter()
+            .filter(|(stream, _)| *stream == OutputStream::Stdout)
+            .map(|(_, line)| line.as_str())
+            .collect();
+        assert_eq!(stdout, ["one", "two", "three"]);
+        assert!(lines.contains(&(OutputStream::Stderr, "oops".to_string())));
+        assert_eq!(output.stdout, "one\ntwo\nthree\n");
+        assert_eq!(output.stderr, "oops\n");
+        assert!(output.success);
+    }
+
+    #[test]
+    fn test_streaming_script_reads_eof_from_stdin() {
+        // read exits 1 on EOF but >128 on timeout, so a blocked stdin fails
+        let dir = slow_probe_scripts_dir("read -r -t 5 line\necho \"status=$?\"\n");
+        let invocation = ScriptInvocation::from_args(&quiet_probe()).unwrap();
+
+        let output = run_invocation_streaming(
+            &invocation,
+            &limited_registry(None, None),
+            dir.path(),
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(output.stdout, "status=1\n");
+    }
+
+    #[test]
+    fn test_events_are_collected_from_event_fd() {
+        let dir = slow_probe_scripts_dir(
+            "echo \"fd=$ARCHTUI_EVENT_FD\"\n\
+             echo '{\"type\":\"result\",\"key\":\"kernel\",\"value\":\"6.9\"}' >&3\n\
+             echo 'garbage' >&3\n",
+        );
+        let invocation = ScriptInvocation::from_args(&quiet_probe()).unwrap();
+
+        let output = run_invocation_streaming(
+            &invocation,
+            &limited_registry(None, None),
+            dir.path(),
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(output.stdout, "fd=3\n");
+        assert_eq!(
+            output.events,
+            [ScriptEvent::Result {
+                key: "kernel".to_string(),
+                value: "6.9".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invocation_rejects_unsafe_args() {
+        let err = ScriptInvocation::new("probe.sh", vec!["$(reboot)".to_string()], vec![], false)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsafe characters"), "{}", err);
+    }
+
+    // test: the refuse-without-manifest rule must never hit a shipped tool
+    #[test]
+    fn test_every_tool_script_has_a_manifest() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts");
+        let mut registry = ManifestRegistry::with_core_manifests();
+        registry
+            .load_from_directory(root.join("manifests"))
+            .unwrap();
+
+        for entry in std::fs::read_dir(root.join("tools")).unwrap() {
+            let name = entry.unwrap().file_name().to_string_lossy().to_string();
+            if name.ends_with(".sh") {
+                let key = format!("scripts/tools/{}", name);
+                assert!(registry.get(&key).is_some(), "no manifest for {}", key);
+            }
+        }
+    }
+
+    // test: read-only actions of destructive tools pass without confirmation,
+    // and the manifests' required_args are satisfied by the typed args
+    #[test]
+    fn test_shipped_manifests_accept_typed_args() {
+        use crate::scripts::disk::{CreateTableArgs, TableType, WipeDiskArgs, WipeMethod};
+        use crate::scripts::encryption::LuksOpenArgs;
+        use crate::scripts::network::{MirrorSortMethod, UpdateMirrorsArgs};
+        use crate::scripts::user::GroupsArgs;
+
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts");
+        let mut registry = ManifestRegistry::new();
+        registry
+            .load_from_directory(root.join("manifests"))
+            .unwrap();
+        let check = |args: &dyn ScriptArgs| {
+            let manifest = registry
+                .get(&format!("scripts/tools/{}", args.script_name()))
+                .unwrap();
+            let env: HashMap<String, String> = args.get_env_vars().into_iter().collect();
+            manifest.validate_invocation(&env, None, &args.to_cli_args())
+        };
+
+        let open = LuksOpenArgs {
+            device: PathBuf::from("/dev/sda2"),
+            mapper_name: "cryptroot".to_string(),
+            key_file: PathBuf::from("/tmp/key"),
+        };
+        assert!(check(&open).is_ok());
+
+        let mirrors = UpdateMirrorsArgs {
+            country: None,
+            limit: 20,
+            sort: MirrorSortMethod::Rate,
+            protocol: None,
+            save: false,
+        };
+        assert!(check(&mirrors).is_ok());
+
+        let mut groups = GroupsArgs {
+            action: "add".to_string(),
+            user: Some("archuser".to_string()),
+            group: Some("wheel".to_string()),
+            confirm: false,
+        };
+        assert!(check(&groups).is_ok());
+        groups.action = "delete".to_string();
+        assert!(check(&groups).is_err(), "delete needs CONFIRM_GROUP_DELETE");
+
+        let wipe = WipeDiskArgs {
+            device: PathBuf::from("/dev/sda"),
+            method: WipeMethod::Quick,
+            confirm: true,
+        };
+        assert!(check(&wipe).is_ok());
+
+        let mut table = CreateTableArgs {
+            device: PathBuf::from("/dev/sda"),
+            table_type: TableType::Gpt,
+            confirm: true,
+        };
+        assert!(check(&table).is_ok());
+        table.device = PathBuf::from("sda");
+        assert!(check(&table).is_err(), "--device must be a /dev path");
+    }
+
     #[test]
     fn test_scripts_base_dir_returns_path() {
         let dir = scripts_base_dir();
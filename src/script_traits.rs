@@ -83,6 +83,9 @@ pub fn shell_safe(s: &str) -> bool {
 ///
 /// - The returned CLI args MUST match the bash script's argument parser.
 /// - Environment variables MUST match the script's environment contract.
+///   `run_script_safe` checks them against the script's manifest, and a
+///   destructive invocation is refused without the manifest's
+///   `required_confirmation` set to `yes`.
 /// - Scripts are identified by name only (path is resolved at execution time).
 /// - Destructive scripts MUST return `true` from `is_destructive()`.
 ///
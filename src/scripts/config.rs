@@ -41,6 +41,7 @@ use crate::script_traits::ScriptArgs;
 ///
 /// let args = GenFstabArgs {
 ///     root: PathBuf::from("/mnt"),
+///     confirm: true,
 /// };
 ///
 /// assert_eq!(args.to_cli_args(), vec!["--root", "/mnt"]);
@@ -49,6 +50,8 @@ use crate::script_traits::ScriptArgs;
 pub struct GenFstabArgs {
     /// Root mount path (e.g., `/mnt`) - where the target system is mounted.
     pub root: PathBuf,
+    /// Whether to set `CONFIRM_GENERATE_FSTAB=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for GenFstabArgs {
@@ -57,7 +60,11 @@ impl ScriptArgs for GenFstabArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_GENERATE_FSTAB".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -92,6 +99,7 @@ impl ScriptArgs for GenFstabArgs {
 /// | `full_name`| `--full-name`  | -              | Optional |
 /// | `home_dir` | `--home-dir`   | -              | Optional |
 /// | `sudo`     | `--groups wheel` | -            | Added to groups |
+/// | `confirm`  | -              | `CONFIRM_ADD_USER` | Set to `yes` when true |
 ///
 /// # Example
 ///
@@ -108,6 +116,7 @@ impl ScriptArgs for GenFstabArgs {
 ///     home_dir: None,
 ///     create_home: true,
 ///     sudo: true,
+///     confirm: true,
 /// };
 ///
 /// // Password NOT in CLI args
@@ -136,6 +145,8 @@ pub struct UserAddArgs {
     pub create_home: bool,
     /// Whether to add to wheel group for sudo access.
     pub sudo: bool,
+    /// Whether to set `CONFIRM_ADD_USER=yes`.
+    pub confirm: bool,
 }
 
 // Custom Debug impl redacts password field
@@ -198,13 +209,16 @@ impl ScriptArgs for UserAddArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if self.confirm {
+            env.push(("CONFIRM_ADD_USER".to_string(), "yes".to_string()));
+        }
         // SECURITY: Password passed via environment, not CLI
         // CLI args are visible in /proc/<pid>/cmdline
         if let Some(ref pwd) = self.password {
-            vec![("USER_PASSWORD".to_string(), pwd.clone())]
-        } else {
-            vec![]
+            env.push(("USER_PASSWORD".to_string(), pwd.clone()));
         }
+        env
     }
 
     fn script_name(&self) -> &'static str {
@@ -244,6 +258,8 @@ pub struct LocaleArgs {
     pub timezone: String,
     /// Keymap for console (e.g., "us").
     pub keymap: Option<String>,
+    /// Whether to set `CONFIRM_CONFIGURE_LOCALE=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for LocaleArgs {
@@ -268,7 +284,11 @@ impl ScriptArgs for LocaleArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_CONFIGURE_LOCALE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -290,6 +310,7 @@ mod tests {
     fn test_gen_fstab_args() {
         let args = GenFstabArgs {
             root: PathBuf::from("/mnt"),
+            confirm: true,
         };
         assert_eq!(args.to_cli_args(), vec!["--root", "/mnt"]);
         assert_eq!(args.script_name(), "generate_fstab.sh");
@@ -306,6 +327,7 @@ mod tests {
             home_dir: None,
             create_home: true,
             sudo: false,
+            confirm: true,
         };
 
         let cli = args.to_cli_args();
@@ -332,14 +354,16 @@ mod tests {
             home_dir: None,
             create_home: true,
             sudo: false,
+            confirm: true,
         };
 
         let env = args.get_env_vars();
 
-        // Password must be in env vars
-        assert_eq!(env.len(), 1);
-        assert_eq!(env[0].0, "USER_PASSWORD");
-        assert_eq!(env[0].1, "supersecret");
+        // Password must be in env vars, next to the add_user.sh confirmation
+        assert_eq!(env.len(), 2);
+        assert_eq!(env[0], ("CONFIRM_ADD_USER".to_string(), "yes".to_string()));
+        assert_eq!(env[1].0, "USER_PASSWORD");
+        assert_eq!(env[1].1, "supersecret");
     }
 
     #[test]
@@ -353,6 +377,7 @@ mod tests {
             home_dir: None,
             create_home: true,
             sudo: true,
+            confirm: true,
         };
 
         let cli = args.to_cli_args();
@@ -368,7 +393,7 @@ mod tests {
     }
 
     #[test]
-    fn test_user_add_no_password_empty_env() {
+    fn test_user_add_no_password_only_confirmation() {
         let args = UserAddArgs {
             username: "nopwd".to_string(),
             password: None,
@@ -378,10 +403,34 @@ mod tests {
             home_dir: None,
             create_home: true,
             sudo: false,
+            confirm: true,
         };
 
         let env = args.get_env_vars();
-        assert!(env.is_empty(), "No password means no env vars");
+        assert_eq!(
+            env,
+            vec![("CONFIRM_ADD_USER".to_string(), "yes".to_string())],
+            "No password means only the confirmation"
+        );
+    }
+
+    #[test]
+    fn test_user_add_no_confirm_omits_confirmation() {
+        let args = UserAddArgs {
+            username: "pending".to_string(),
+            password: Some("pw".to_string()),
+            groups: None,
+            shell: None,
+            full_name: None,
+            home_dir: None,
+            create_home: true,
+            sudo: false,
+            confirm: false,
+        };
+
+        let env = args.get_env_vars();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env[0].0, "USER_PASSWORD");
     }
 
     #[test]
@@ -392,6 +441,7 @@ mod tests {
             locale: "en_US.UTF-8".to_string(),
             timezone: "America/New_York".to_string(),
             keymap: Some("us".to_string()),
+            confirm: true,
         };
 
         let cli = args.to_cli_args();
@@ -7,6 +7,7 @@
 //! - `MountPartitionsArgs` for advanced mount/umount operations
 //! - `CheckDiskHealthArgs` for `check_disk_health.sh`
 //! - `ManualPartitionArgs` for `manual_partition.sh`
+//! - `ShrinkPartitionArgs` / `FreeSpacePartitionArgs` for `free_space.sh`
 //!
 //! # Why This Exists
 //!
@@ -50,6 +51,7 @@ use crate::types::Filesystem;
 /// | `filesystem` | `--filesystem` | Yes      |
 /// | `label`      | `--label`      | No       |
 /// | `force`      | `--force`      | No       |
+/// | `confirm`    | N/A (env)      | Sets `CONFIRM_FORMAT_PARTITION=yes` |
 #[derive(Debug, Clone)]
 pub struct FormatPartitionArgs {
     /// Partition device path (e.g., `/dev/sda1`).
@@ -60,6 +62,8 @@ pub struct FormatPartitionArgs {
     pub label: Option<String>,
     /// Force formatting even if mounted.
     pub force: bool,
+    /// Whether to set `CONFIRM_FORMAT_PARTITION=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for FormatPartitionArgs {
@@ -82,7 +86,11 @@ impl ScriptArgs for FormatPartitionArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_FORMAT_PARTITION".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -330,6 +338,8 @@ pub enum PartitionType {
     Lvm,
     /// Linux LUKS (sgdisk 8309, MBR 83)
     Luks,
+    /// Linux RAID member (sgdisk FD00, MBR fd)
+    Raid,
 }
 
 #[allow(dead_code)] // Used by bash script mapping + tests
@@ -343,6 +353,7 @@ impl PartitionType {
             PartitionType::Swap => "8200",
             PartitionType::Lvm => "8E00",
             PartitionType::Luks => "8309",
+            PartitionType::Raid => "FD00",
         }
     }
 
@@ -355,6 +366,7 @@ impl PartitionType {
             PartitionType::Swap => "82",
             PartitionType::Lvm => "8e",
             PartitionType::Luks => "83",
+            PartitionType::Raid => "fd",
         }
     }
 
@@ -366,6 +378,7 @@ impl PartitionType {
             PartitionType::Swap => "Linux Swap",
             PartitionType::Lvm => "Linux LVM",
             PartitionType::Luks => "Linux LUKS",
+            PartitionType::Raid => "Linux RAID",
         }
     }
 }
@@ -387,14 +400,44 @@ impl std::str::FromStr for PartitionType {
             "8200" | "LINUX SWAP" | "SWAP" => Ok(PartitionType::Swap),
             "8E00" | "LINUX LVM" | "LVM" => Ok(PartitionType::Lvm),
             "8309" | "LINUX LUKS" | "LUKS" => Ok(PartitionType::Luks),
+            "FD00" | "LINUX RAID" | "RAID" => Ok(PartitionType::Raid),
             _ => Err(format!(
-                "Invalid partition type '{}'. Valid: EF00/EFI, EF02/BIOS Boot, 8300/Linux, 8200/Swap, 8E00/LVM, 8309/LUKS",
+                "Invalid partition type '{}'. Valid: EF00/EFI, EF02/BIOS Boot, 8300/Linux, 8200/Swap, 8E00/LVM, 8309/LUKS, FD00/RAID",
                 s
             )),
         }
     }
 }
 
+/// Serialized as the sgdisk code; deserialized through `FromStr`, so config
+/// files may use either the code (`"EF00"`) or an alias (`"EFI"`).
+impl serde::Serialize for PartitionType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_sgdisk_code())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PartitionType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl schemars::JsonSchema for PartitionType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "PartitionType".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "sgdisk type code or alias (case-insensitive)",
+            "type": "string",
+            "examples": ["EF00", "EF02", "8300", "8200", "8E00", "8309", "FD00", "EFI", "Swap", "LUKS"]
+        })
+    }
+}
+
 // ============================================================================
 // Manual Partition (cfdisk mode)
 // ============================================================================
@@ -612,6 +655,128 @@ impl ScriptArgs for DeletePartitionArgs {
     }
 }
 
+// ============================================================================
+// Shrink Partition
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/free_space.sh --action shrink`.
+///
+/// Shrinks an NTFS or ext4 filesystem and then its GPT partition entry,
+/// keeping the partition's start, type and GUID.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field  | CLI Flag      | Required |
+/// |-------------|---------------|----------|
+/// | `partition` | `--partition` | Yes      |
+/// | `size`      | `--size`      | Yes      |
+/// | `confirm`   | N/A (env)     | Sets `CONFIRM_FREE_SPACE=yes` |
+#[derive(Debug, Clone)]
+pub struct ShrinkPartitionArgs {
+    /// Partition to shrink (e.g., `/dev/sda3`).
+    pub partition: PathBuf,
+    /// New partition size (e.g., "200G").
+    pub size: String,
+    /// Whether to set `CONFIRM_FREE_SPACE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for ShrinkPartitionArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "shrink".to_string(),
+            "--partition".to_string(),
+            self.partition.display().to_string(),
+            "--size".to_string(),
+            self.size.clone(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_FREE_SPACE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "free_space.sh"
+    }
+
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Free-Space Partition
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/free_space.sh --action create`.
+///
+/// Creates a partition at the start of the largest unallocated block of a GPT
+/// disk, using the first free partition number. The script refuses a label
+/// that already names a partition on the disk, so the result can be found at
+/// `/dev/disk/by-partlabel/<label>`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field       | CLI Flag   | Required |
+/// |------------------|------------|----------|
+/// | `device`         | `--device` | Yes      |
+/// | `size`           | `--size`   | Yes      |
+/// | `partition_type` | `--type`   | Yes      |
+/// | `label`          | `--label`  | Yes      |
+/// | `confirm`        | N/A (env)  | Sets `CONFIRM_FREE_SPACE=yes` |
+#[derive(Debug, Clone)]
+pub struct FreeSpacePartitionArgs {
+    /// Disk device path (e.g., `/dev/sda`).
+    pub device: PathBuf,
+    /// Partition size (e.g., "1024M", "remaining").
+    pub size: String,
+    /// Partition type code.
+    pub partition_type: PartitionType,
+    /// GPT partition name.
+    pub label: String,
+    /// Whether to set `CONFIRM_FREE_SPACE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for FreeSpacePartitionArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "create".to_string(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+            "--size".to_string(),
+            self.size.clone(),
+            "--type".to_string(),
+            self.partition_type.as_sgdisk_code().to_string(),
+            "--label".to_string(),
+            self.label.clone(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_FREE_SPACE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "free_space.sh"
+    }
+
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
 // ============================================================================
 // Wipe Disk (existing)
 // ============================================================================
@@ -960,6 +1125,7 @@ mod tests {
             filesystem: Filesystem::Ext4,
             label: None,
             force: false,
+            confirm: true,
         };
 
         let cli_args = args.to_cli_args();
@@ -976,6 +1142,7 @@ mod tests {
             filesystem: Filesystem::Fat32,
             label: Some("EFI".to_string()),
             force: false,
+            confirm: true,
         };
 
         let cli_args = args.to_cli_args();
@@ -994,6 +1161,7 @@ mod tests {
             filesystem: Filesystem::Btrfs,
             label: Some("archroot".to_string()),
             force: true,
+            confirm: true,
         };
 
         let cli_args = args.to_cli_args();
@@ -1009,6 +1177,7 @@ mod tests {
             filesystem: Filesystem::Ext4,
             label: None,
             force: false,
+            confirm: true,
         };
         assert_eq!(args.script_name(), "format_partition.sh");
     }
@@ -1106,6 +1275,7 @@ mod tests {
         assert_eq!(PartitionType::Swap.as_sgdisk_code(), "8200");
         assert_eq!(PartitionType::Lvm.as_sgdisk_code(), "8E00");
         assert_eq!(PartitionType::Luks.as_sgdisk_code(), "8309");
+        assert_eq!(PartitionType::Raid.as_sgdisk_code(), "FD00");
     }
 
     #[test]
@@ -1114,6 +1284,7 @@ mod tests {
         assert_eq!(PartitionType::Linux.as_mbr_id(), "83");
         assert_eq!(PartitionType::Swap.as_mbr_id(), "82");
         assert_eq!(PartitionType::Lvm.as_mbr_id(), "8e");
+        assert_eq!(PartitionType::Raid.as_mbr_id(), "fd");
     }
 
     #[test]
@@ -1145,6 +1316,10 @@ mod tests {
             "BIOS Boot".parse::<PartitionType>().unwrap(),
             PartitionType::BiosBoot
         );
+        assert_eq!(
+            "FD00".parse::<PartitionType>().unwrap(),
+            PartitionType::Raid
+        );
     }
 
     #[test]
@@ -1152,6 +1327,15 @@ mod tests {
         assert!("invalid".parse::<PartitionType>().is_err());
     }
 
+    #[test]
+    fn test_partition_type_serde_uses_sgdisk_code() {
+        let json = serde_json::to_string(&PartitionType::Swap).expect("serialize partition type"); // test: known-good input
+        assert_eq!(json, "\"8200\"");
+        let parsed: PartitionType = serde_json::from_str("\"efi\"").expect("alias should parse"); // test: known-good input
+        assert_eq!(parsed, PartitionType::Efi);
+        assert!(serde_json::from_str::<PartitionType>("\"0700\"").is_err());
+    }
+
     #[test]
     fn test_partition_type_display_name() {
         assert_eq!(PartitionType::Efi.display_name(), "EFI System");
@@ -1160,6 +1344,7 @@ mod tests {
         assert_eq!(PartitionType::Swap.display_name(), "Linux Swap");
         assert_eq!(PartitionType::Lvm.display_name(), "Linux LVM");
         assert_eq!(PartitionType::Luks.display_name(), "Linux LUKS");
+        assert_eq!(PartitionType::Raid.display_name(), "Linux RAID");
     }
 
     // ========================================================================
@@ -1343,6 +1528,65 @@ mod tests {
         assert!(args.is_destructive());
     }
 
+    // ========================================================================
+    // Free-Space Tests
+    // ========================================================================
+
+    #[test]
+    fn test_shrink_partition_args() {
+        let args = ShrinkPartitionArgs {
+            partition: PathBuf::from("/dev/nvme0n1p3"),
+            size: "200G".to_string(),
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "shrink",
+                "--partition",
+                "/dev/nvme0n1p3",
+                "--size",
+                "200G"
+            ]
+        );
+        assert_eq!(
+            args.get_env_vars(),
+            vec![("CONFIRM_FREE_SPACE".to_string(), "yes".to_string())]
+        );
+        assert_eq!(args.script_name(), "free_space.sh");
+        assert!(args.is_destructive());
+    }
+
+    #[test]
+    fn test_free_space_partition_args() {
+        let args = FreeSpacePartitionArgs {
+            device: PathBuf::from("/dev/sda"),
+            size: "remaining".to_string(),
+            partition_type: PartitionType::Linux,
+            label: "archtui-root".to_string(),
+            confirm: false,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "create",
+                "--device",
+                "/dev/sda",
+                "--size",
+                "remaining",
+                "--type",
+                "8300",
+                "--label",
+                "archtui-root"
+            ]
+        );
+        assert!(args.get_env_vars().is_empty());
+        assert_eq!(args.script_name(), "free_space.sh");
+        assert!(args.is_destructive());
+    }
+
     // ========================================================================
     // DetectOsArgs Tests
     // ========================================================================
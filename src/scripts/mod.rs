@@ -11,6 +11,7 @@
 //! - `encryption`: LUKS encryption (format, open, close)
 //! - `network`: Network configuration (configure, test, firewall, mirrors)
 //! - `profiles`: Desktop profiles and dotfiles
+//! - `storage`: LVM, software RAID and teardown (pv/vg/lv, mdadm, cleanup)
 //! - `system`: System configuration (bootloader, chroot, services)
 //! - `user`: User management (password, groups, ssh, security)
 //!
@@ -27,6 +28,7 @@ pub mod disk;
 pub mod encryption;
 pub mod network;
 pub mod profiles;
+pub mod storage;
 pub mod system;
 pub mod user;
 pub mod user_ops;
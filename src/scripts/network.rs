@@ -22,6 +22,8 @@ pub struct ConfigureNetworkArgs {
     pub ip: Option<String>,
     /// Optional gateway address.
     pub gateway: Option<String>,
+    /// Whether to set `CONFIRM_NETWORK_CONFIG=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for ConfigureNetworkArgs {
@@ -39,7 +41,11 @@ impl ScriptArgs for ConfigureNetworkArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_NETWORK_CONFIG".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -112,6 +118,8 @@ pub struct FirewallArgs {
     pub allow: bool,
     /// Deny flag.
     pub deny: bool,
+    /// Whether to set `CONFIRM_FIREWALL_CHANGE=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for FirewallArgs {
@@ -138,7 +146,11 @@ impl ScriptArgs for FirewallArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_FIREWALL_CHANGE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -304,7 +316,11 @@ impl ScriptArgs for UpdateMirrorsArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.save {
+            vec![("CONFIRM_UPDATE_MIRRORS".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
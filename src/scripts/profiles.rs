@@ -33,6 +33,7 @@ use crate::script_traits::ScriptArgs;
 /// | `target_dir`  | `--target`    | Optional target directory |
 /// | `branch`      | `--branch`    | Optional branch name |
 /// | `backup`      | `--backup`    | Backup existing files |
+/// | `confirm`     | N/A (env)     | Sets `CONFIRM_INSTALL_DOTFILES=yes` |
 ///
 /// # Prerequisites
 ///
@@ -49,6 +50,8 @@ pub struct InstallDotfilesArgs {
     pub branch: Option<String>,
     /// Whether to backup existing files before overwriting.
     pub backup: bool,
+    /// Whether to set `CONFIRM_INSTALL_DOTFILES=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for InstallDotfilesArgs {
@@ -78,7 +81,11 @@ impl ScriptArgs for InstallDotfilesArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_INSTALL_DOTFILES".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -153,6 +160,7 @@ mod tests {
             target_dir: Some(PathBuf::from("/home/archuser")),
             branch: Some("main".to_string()),
             backup: true,
+            confirm: true,
         };
 
         let cli_args = args.to_cli_args();
@@ -178,6 +186,7 @@ mod tests {
             target_dir: None,
             branch: None,
             backup: false,
+            confirm: true,
         };
 
         let cli_args = args.to_cli_args();
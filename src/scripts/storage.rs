@@ -0,0 +1,689 @@
+//! Type-safe arguments for LVM, software RAID, Btrfs and storage teardown scripts.
+//!
+//! This module provides typed argument structs for the storage stack layers
+//! that sit between partitions and filesystems:
+//! - `LvmPvCreateArgs` for `manage_lvm.sh --action pv-create`
+//! - `LvmVgCreateArgs` for `manage_lvm.sh --action vg-create`
+//! - `LvmLvCreateArgs` for `manage_lvm.sh --action lv-create`
+//! - `RaidCreateArgs` for `manage_raid.sh --action create`
+//! - `RaidWaitArgs` for `manage_raid.sh --action wait`
+//! - `RaidWriteConfArgs` for `manage_raid.sh --action write-conf`
+//! - `BtrfsSubvolumeCreateArgs` for `manage_btrfs.sh --action create-subvolumes`
+//! - `SwapfileCreateArgs` for `manage_swap.sh --action create-swapfile`
+//! - `TeardownStorageArgs` for `teardown_storage.sh`
+//!
+//! These are the targets the storage plan executor maps `StorageOp`s onto.
+
+use std::path::PathBuf;
+
+use crate::engine::storage::TeardownOp;
+use crate::script_traits::ScriptArgs;
+
+// ============================================================================
+// LVM Physical Volume
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_lvm.sh --action pv-create`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "pv-create" |
+/// | `device`   | `--device` | Partition or mapper device |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_LVM_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct LvmPvCreateArgs {
+    /// Device to initialize as a physical volume (e.g., `/dev/sda2`).
+    pub device: PathBuf,
+    /// Whether to set `CONFIRM_LVM_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for LvmPvCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "pv-create".to_string(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        lvm_confirm_env(self.confirm)
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_lvm.sh"
+    }
+
+    /// pvcreate is DESTRUCTIVE - overwrites the device header.
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// LVM Volume Group
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_lvm.sh --action vg-create`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "vg-create" |
+/// | `vg_name`  | `--vg`     | Volume group name |
+/// | `device`   | `--device` | Physical volume to add |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_LVM_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct LvmVgCreateArgs {
+    /// Volume group name (e.g., `archvg`).
+    pub vg_name: String,
+    /// Physical volume backing the group (e.g., `/dev/sda2`).
+    pub device: PathBuf,
+    /// Whether to set `CONFIRM_LVM_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for LvmVgCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "vg-create".to_string(),
+            "--vg".to_string(),
+            self.vg_name.clone(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        lvm_confirm_env(self.confirm)
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_lvm.sh"
+    }
+
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// LVM Logical Volume
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_lvm.sh --action lv-create`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "lv-create" |
+/// | `vg_name`  | `--vg`     | Volume group name |
+/// | `lv_name`  | `--lv`     | Logical volume name |
+/// | `size`     | `--size`   | Absolute (`4G`) or extents (`100%FREE`) |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_LVM_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct LvmLvCreateArgs {
+    /// Volume group to allocate from (e.g., `archvg`).
+    pub vg_name: String,
+    /// Logical volume name (e.g., `root`).
+    pub lv_name: String,
+    /// Size specification (e.g., `4G`, `100%FREE`).
+    pub size: String,
+    /// Whether to set `CONFIRM_LVM_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for LvmLvCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "lv-create".to_string(),
+            "--vg".to_string(),
+            self.vg_name.clone(),
+            "--lv".to_string(),
+            self.lv_name.clone(),
+            "--size".to_string(),
+            self.size.clone(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        lvm_confirm_env(self.confirm)
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_lvm.sh"
+    }
+
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+fn lvm_confirm_env(confirm: bool) -> Vec<(String, String)> {
+    if confirm {
+        vec![("CONFIRM_LVM_CREATE".to_string(), "yes".to_string())]
+    } else {
+        vec![]
+    }
+}
+
+// ============================================================================
+// RAID Create
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_raid.sh --action create`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "create" |
+/// | `device`   | `--device` | Array device (e.g., `/dev/md/DATA`) |
+/// | `level`    | `--level`  | mdadm level (e.g., `raid1`) |
+/// | `members`  | `--member` | Repeated once per member partition |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_RAID_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct RaidCreateArgs {
+    /// Array device to create (e.g., `/dev/md/DATA`).
+    pub device: PathBuf,
+    /// RAID level as understood by mdadm (e.g., `raid1`).
+    pub level: String,
+    /// Member partitions (e.g., `/dev/sda3`, `/dev/sdb3`).
+    pub members: Vec<PathBuf>,
+    /// Whether to set `CONFIRM_RAID_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for RaidCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--action".to_string(),
+            "create".to_string(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+            "--level".to_string(),
+            self.level.clone(),
+        ];
+        for member in &self.members {
+            args.push("--member".to_string());
+            args.push(member.display().to_string());
+        }
+        args
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_RAID_CREATE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_raid.sh"
+    }
+
+    /// Array creation is DESTRUCTIVE - overwrites member superblocks.
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// RAID Wait
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_raid.sh --action wait`.
+///
+/// Blocks until the array has finished assembling/resyncing.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "wait" |
+/// | `device`   | `--device` | Array device |
+#[derive(Debug, Clone)]
+pub struct RaidWaitArgs {
+    /// Array device to wait on (e.g., `/dev/md/DATA`).
+    pub device: PathBuf,
+}
+
+impl ScriptArgs for RaidWaitArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "wait".to_string(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_raid.sh"
+    }
+
+    /// Waiting is READ-ONLY - not destructive.
+    fn is_destructive(&self) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// RAID Write Config
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_raid.sh --action write-conf`.
+///
+/// Writes `mdadm --detail --scan` output so the target can assemble its
+/// arrays at boot.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "write-conf" |
+/// | `path`     | `--path`   | e.g., `/mnt/etc/mdadm.conf` |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_RAID_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct RaidWriteConfArgs {
+    /// Destination config file (e.g., `/mnt/etc/mdadm.conf`).
+    pub path: PathBuf,
+    /// Whether to set `CONFIRM_RAID_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for RaidWriteConfArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "write-conf".to_string(),
+            "--path".to_string(),
+            self.path.display().to_string(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_RAID_CREATE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_raid.sh"
+    }
+
+    /// Writes into the target root - treated as destructive.
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Btrfs Subvolumes
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_btrfs.sh --action create-subvolumes`.
+///
+/// The script mounts the top-level subvolume on a temporary directory,
+/// creates each subvolume, and unmounts again.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field   | CLI Flag      | Notes |
+/// |--------------|---------------|-------|
+/// | (action)     | `--action`    | Always "create-subvolumes" |
+/// | `device`     | `--device`    | Formatted Btrfs device |
+/// | `subvolumes` | `--subvolume` | Repeated once per subvolume |
+#[derive(Debug, Clone)]
+pub struct BtrfsSubvolumeCreateArgs {
+    /// Btrfs device (e.g., `/dev/sda2`, `/dev/mapper/cryptroot`).
+    pub device: PathBuf,
+    /// Subvolume names (e.g., `@`, `@home`).
+    pub subvolumes: Vec<String>,
+}
+
+impl ScriptArgs for BtrfsSubvolumeCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--action".to_string(),
+            "create-subvolumes".to_string(),
+            "--device".to_string(),
+            self.device.display().to_string(),
+        ];
+        for subvolume in &self.subvolumes {
+            args.push("--subvolume".to_string());
+            args.push(subvolume.clone());
+        }
+        args
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_btrfs.sh"
+    }
+
+    /// Only adds subvolumes to a filesystem formatted earlier in the plan.
+    fn is_destructive(&self) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Swapfile
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/manage_swap.sh --action create-swapfile`.
+///
+/// # Field to Flag Mapping
+///
+/// | Rust Field | CLI Flag   | Notes |
+/// |------------|------------|-------|
+/// | (action)   | `--action` | Always "create-swapfile" |
+/// | `path`     | `--path`   | Must be under `/mnt` |
+/// | `size`     | `--size`   | `<n>G` or `<n>M` |
+/// | `confirm`  | N/A (env)  | Sets `CONFIRM_SWAPFILE_CREATE=yes` |
+#[derive(Debug, Clone)]
+pub struct SwapfileCreateArgs {
+    /// Swapfile path on the mounted target (e.g., `/mnt/swap/swapfile`).
+    pub path: PathBuf,
+    /// Size specification (e.g., `4G`, `8192M`).
+    pub size: String,
+    /// Whether to set `CONFIRM_SWAPFILE_CREATE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for SwapfileCreateArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        vec![
+            "--action".to_string(),
+            "create-swapfile".to_string(),
+            "--path".to_string(),
+            self.path.display().to_string(),
+            "--size".to_string(),
+            self.size.clone(),
+        ]
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_SWAPFILE_CREATE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "manage_swap.sh"
+    }
+
+    /// Allocates space on the target filesystem - treated as destructive.
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Storage Teardown
+// ============================================================================
+
+/// Type-safe arguments for `scripts/tools/teardown_storage.sh`.
+///
+/// Each `TeardownOp` becomes one flag; the script runs them in the order
+/// given, skipping anything that is already released and continuing past
+/// failures so a single stuck step cannot block the rest.
+///
+/// # Op to Flag Mapping
+///
+/// | TeardownOp      | CLI Flag    |
+/// |-----------------|-------------|
+/// | `Unmount`       | `--umount`  |
+/// | `SwapOff`       | `--swapoff` |
+/// | `DeactivateVg`  | `--vg`      |
+/// | `LuksClose`     | `--mapper`  |
+/// | `StopRaidArray` | `--md`      |
+/// | `confirm`       | env: `CONFIRM_TEARDOWN_STORAGE=yes` |
+#[derive(Debug, Clone)]
+pub struct TeardownStorageArgs {
+    /// Ordered teardown steps (see `StoragePlan::teardown`).
+    pub ops: Vec<TeardownOp>,
+    /// Whether to set `CONFIRM_TEARDOWN_STORAGE=yes`.
+    pub confirm: bool,
+}
+
+impl ScriptArgs for TeardownStorageArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        let mut args = Vec::with_capacity(self.ops.len() * 2);
+        for op in &self.ops {
+            let (flag, value) = match op {
+                TeardownOp::Unmount { mountpoint } => {
+                    ("--umount", mountpoint.display().to_string())
+                }
+                TeardownOp::SwapOff { device } => ("--swapoff", device.display().to_string()),
+                TeardownOp::DeactivateVg { vg_name } => ("--vg", vg_name.clone()),
+                TeardownOp::LuksClose { mapper_name } => ("--mapper", mapper_name.clone()),
+                TeardownOp::StopRaidArray { device } => ("--md", device.display().to_string()),
+            };
+            args.push(flag.to_string());
+            args.push(value);
+        }
+        args
+    }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        if self.confirm {
+            vec![("CONFIRM_TEARDOWN_STORAGE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
+    }
+
+    fn script_name(&self) -> &'static str {
+        "teardown_storage.sh"
+    }
+
+    /// Teardown changes live system state (mounts, mappers, arrays).
+    fn is_destructive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lvm_pv_create_args() {
+        let args = LvmPvCreateArgs {
+            device: PathBuf::from("/dev/sda2"),
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["--action", "pv-create", "--device", "/dev/sda2"]
+        );
+        assert_eq!(
+            args.get_env_vars(),
+            vec![("CONFIRM_LVM_CREATE".to_string(), "yes".to_string())]
+        );
+        assert_eq!(args.script_name(), "manage_lvm.sh");
+    }
+
+    #[test]
+    fn test_lvm_vg_create_args() {
+        let args = LvmVgCreateArgs {
+            vg_name: "archvg".to_string(),
+            device: PathBuf::from("/dev/mapper/cryptlvm"),
+            confirm: false,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "vg-create",
+                "--vg",
+                "archvg",
+                "--device",
+                "/dev/mapper/cryptlvm"
+            ]
+        );
+        assert!(args.get_env_vars().is_empty());
+    }
+
+    #[test]
+    fn test_lvm_lv_create_args_accept_percent_sizes() {
+        let args = LvmLvCreateArgs {
+            vg_name: "archvg".to_string(),
+            lv_name: "root".to_string(),
+            size: "100%FREE".to_string(),
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "lv-create",
+                "--vg",
+                "archvg",
+                "--lv",
+                "root",
+                "--size",
+                "100%FREE"
+            ]
+        );
+        assert!(args.validate().is_ok());
+    }
+
+    #[test]
+    fn test_raid_create_args_repeats_member_flag() {
+        let args = RaidCreateArgs {
+            device: PathBuf::from("/dev/md/DATA"),
+            level: "raid1".to_string(),
+            members: vec![PathBuf::from("/dev/sda3"), PathBuf::from("/dev/sdb3")],
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "create",
+                "--device",
+                "/dev/md/DATA",
+                "--level",
+                "raid1",
+                "--member",
+                "/dev/sda3",
+                "--member",
+                "/dev/sdb3"
+            ]
+        );
+        assert_eq!(
+            args.get_env_vars(),
+            vec![("CONFIRM_RAID_CREATE".to_string(), "yes".to_string())]
+        );
+        assert!(args.is_destructive());
+    }
+
+    #[test]
+    fn test_raid_wait_is_not_destructive() {
+        let args = RaidWaitArgs {
+            device: PathBuf::from("/dev/md/DATA"),
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["--action", "wait", "--device", "/dev/md/DATA"]
+        );
+        assert!(!args.is_destructive());
+    }
+
+    #[test]
+    fn test_teardown_args_preserve_order() {
+        let args = TeardownStorageArgs {
+            ops: vec![
+                TeardownOp::Unmount {
+                    mountpoint: PathBuf::from("/mnt"),
+                },
+                TeardownOp::DeactivateVg {
+                    vg_name: "archvg".to_string(),
+                },
+                TeardownOp::LuksClose {
+                    mapper_name: "cryptlvm".to_string(),
+                },
+                TeardownOp::StopRaidArray {
+                    device: PathBuf::from("/dev/md/DATA"),
+                },
+            ],
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--umount",
+                "/mnt",
+                "--vg",
+                "archvg",
+                "--mapper",
+                "cryptlvm",
+                "--md",
+                "/dev/md/DATA"
+            ]
+        );
+        assert_eq!(
+            args.get_env_vars(),
+            vec![("CONFIRM_TEARDOWN_STORAGE".to_string(), "yes".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_raid_write_conf_args() {
+        let args = RaidWriteConfArgs {
+            path: PathBuf::from("/mnt/etc/mdadm.conf"),
+            confirm: true,
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["--action", "write-conf", "--path", "/mnt/etc/mdadm.conf"]
+        );
+        assert_eq!(args.script_name(), "manage_raid.sh");
+    }
+
+    #[test]
+    fn test_btrfs_subvolume_create_args_repeat_subvolume_flag() {
+        let args = BtrfsSubvolumeCreateArgs {
+            device: PathBuf::from("/dev/mapper/cryptroot"),
+            subvolumes: vec!["@".to_string(), "@home".to_string()],
+        };
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "--action",
+                "create-subvolumes",
+                "--device",
+                "/dev/mapper/cryptroot",
+                "--subvolume",
+                "@",
+                "--subvolume",
+                "@home"
+            ]
+        );
+        assert!(args.validate().is_ok());
+        assert!(!args.is_destructive());
+    }
+}
@@ -27,6 +27,8 @@ pub struct BootloaderArgs {
     pub mode: String,
     /// Optional EFI partition path.
     pub efi_path: Option<PathBuf>,
+    /// Whether to set `CONFIRM_INSTALL_BOOTLOADER=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for BootloaderArgs {
@@ -47,7 +49,11 @@ impl ScriptArgs for BootloaderArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_INSTALL_BOOTLOADER".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
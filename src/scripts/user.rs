@@ -21,6 +21,8 @@ pub struct ResetPasswordArgs {
     pub username: String,
     /// New password (passed via env var, never on CLI).
     pub password: String,
+    /// Whether to set `CONFIRM_RESET_PASSWORD=yes`.
+    pub confirm: bool,
 }
 
 // Custom Debug impl redacts password field
@@ -29,6 +31,7 @@ impl std::fmt::Debug for ResetPasswordArgs {
         f.debug_struct("ResetPasswordArgs")
             .field("username", &self.username)
             .field("password", &"********")
+            .field("confirm", &self.confirm)
             .finish()
     }
 }
@@ -39,7 +42,12 @@ impl ScriptArgs for ResetPasswordArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![("USER_PASSWORD".to_string(), self.password.clone())]
+        let mut env = Vec::new();
+        if self.confirm {
+            env.push(("CONFIRM_RESET_PASSWORD".to_string(), "yes".to_string()));
+        }
+        env.push(("USER_PASSWORD".to_string(), self.password.clone()));
+        env
     }
 
     fn script_name(&self) -> &'static str {
@@ -65,6 +73,8 @@ pub struct GroupsArgs {
     pub user: Option<String>,
     /// Optional group to operate on.
     pub group: Option<String>,
+    /// Whether to set `CONFIRM_GROUP_DELETE=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for GroupsArgs {
@@ -82,7 +92,11 @@ impl ScriptArgs for GroupsArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_GROUP_DELETE".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -110,6 +124,8 @@ pub struct SshArgs {
     pub enable_root_login: Option<bool>,
     /// Enable password authentication.
     pub enable_password_auth: Option<bool>,
+    /// Whether to set `CONFIRM_SSH_CONFIG=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for SshArgs {
@@ -137,7 +153,11 @@ impl ScriptArgs for SshArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_SSH_CONFIG".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
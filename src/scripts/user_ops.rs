@@ -167,6 +167,7 @@ impl ScriptArgs for InstallAurHelperArgs {
 /// | `target_user` | `--user`   | User for ownership |
 /// | `target_dir`  | `--target` | Clone target directory |
 /// | `branch`      | `--branch` | Optional branch |
+/// | `confirm`     | N/A (env)  | Sets `CONFIRM_INSTALL_DOTFILES=yes` |
 #[derive(Debug, Clone)]
 pub struct CloneDotfilesArgs {
     /// Git repository URL.
@@ -177,6 +178,8 @@ pub struct CloneDotfilesArgs {
     pub target_dir: Option<PathBuf>,
     /// Branch to clone.
     pub branch: Option<String>,
+    /// Whether to set `CONFIRM_INSTALL_DOTFILES=yes`.
+    pub confirm: bool,
 }
 
 impl ScriptArgs for CloneDotfilesArgs {
@@ -203,7 +206,11 @@ impl ScriptArgs for CloneDotfilesArgs {
     }
 
     fn get_env_vars(&self) -> Vec<(String, String)> {
-        vec![]
+        if self.confirm {
+            vec![("CONFIRM_INSTALL_DOTFILES".to_string(), "yes".to_string())]
+        } else {
+            vec![]
+        }
     }
 
     fn script_name(&self) -> &'static str {
@@ -298,6 +305,7 @@ mod tests {
             target_user: "archuser".to_string(),
             target_dir: Some(PathBuf::from("/home/archuser/.dotfiles")),
             branch: Some("main".to_string()),
+            confirm: true,
         };
 
         let cli = args.to_cli_args();
@@ -322,6 +330,7 @@ mod tests {
             target_user: "archuser".to_string(),
             target_dir: None,
             branch: None,
+            confirm: true,
         };
 
         let cli = args.to_cli_args();
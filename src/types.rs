@@ -3,6 +3,7 @@
 //! This module replaces stringly-typed configuration with proper Rust enums
 //! that provide compile-time validation and exhaustive matching.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, EnumString};
 
@@ -16,6 +17,7 @@ use strum::{Display, EnumIter, EnumString};
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -41,6 +43,7 @@ pub enum BootMode {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -75,6 +78,7 @@ pub enum Filesystem {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -97,6 +101,8 @@ pub enum PartitionScheme {
     AutoRaidLvm,
     #[strum(serialize = "auto_raid_lvm_luks")]
     AutoRaidLvmLuks,
+    #[strum(serialize = "auto_alongside")]
+    AutoAlongside,
     #[strum(serialize = "manual")]
     Manual,
     #[strum(serialize = "pre_mounted")]
@@ -139,6 +145,7 @@ impl PartitionScheme {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -212,6 +219,7 @@ impl DesktopEnvironment {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -247,6 +255,7 @@ pub enum DisplayManager {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -284,6 +293,7 @@ impl Bootloader {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -311,6 +321,7 @@ pub enum AurHelper {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -337,6 +348,7 @@ pub enum Kernel {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -369,6 +381,7 @@ pub enum GpuDriver {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -405,6 +418,7 @@ impl From<bool> for Toggle {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -419,6 +433,125 @@ pub enum AutoToggle {
     No,
 }
 
+/// How swap space is provided on the installed system.
+///
+/// `Yes`/`No` from configs written before swap modes existed are still
+/// accepted and map to `Partition`/`None`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+pub enum SwapMode {
+    #[default]
+    #[serde(alias = "No")]
+    #[strum(to_string = "none", serialize = "No")]
+    None,
+    #[serde(alias = "Yes")]
+    #[strum(to_string = "partition", serialize = "Yes")]
+    Partition,
+    #[strum(serialize = "swapfile")]
+    Swapfile,
+    #[strum(serialize = "zram")]
+    Zram,
+    #[strum(serialize = "zram+swapfile")]
+    ZramSwapfile,
+}
+
+impl SwapMode {
+    /// Whether swap lives on disk (and can therefore hold a hibernation image).
+    pub fn has_disk_swap(&self) -> bool {
+        matches!(self, Self::Partition | Self::Swapfile | Self::ZramSwapfile)
+    }
+}
+
+/// Partition size as written in `root_size`/`home_size` ("50GB", "512MiB",
+/// "2TB", "25%" or "Remaining").
+///
+/// Decimal and binary suffixes are treated alike (1GB = 1GiB = 1024 MiB) and
+/// a bare number is GiB, matching `get_root_size_mib` in `disk_utils.sh`.
+/// Percentages are of the whole target disk. An empty string is `Remaining`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskSize {
+    /// Fixed size in MiB
+    Mib(u64),
+    /// Share of the target disk, 1–100
+    Percent(u8),
+    /// Whatever space is left after the fixed-size partitions
+    Remaining,
+}
+
+impl DiskSize {
+    /// Size in MiB on a disk of `capacity_mib`, or `None` for `Remaining`.
+    pub fn resolve_mib(&self, capacity_mib: u64) -> Option<u64> {
+        match self {
+            Self::Mib(mib) => Some(*mib),
+            Self::Percent(pct) => Some(capacity_mib * u64::from(*pct) / 100),
+            Self::Remaining => None,
+        }
+    }
+}
+
+impl std::str::FromStr for DiskSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid size '{}'. Use e.g. 512MiB, 50GB, 2TB, 25% or Remaining",
+                s
+            )
+        };
+        let size = s.trim();
+        if size.is_empty() || size.eq_ignore_ascii_case("remaining") {
+            return Ok(Self::Remaining);
+        }
+        if let Some(pct) = size.strip_suffix('%') {
+            return match pct.trim().parse::<u8>() {
+                Ok(pct @ 1..=100) => Ok(Self::Percent(pct)),
+                _ => Err(invalid()),
+            };
+        }
+
+        let digit_end = size
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(size.len());
+        let amount = match size[..digit_end].parse::<u64>() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(invalid()),
+        };
+        let mib = match size[digit_end..].trim().to_ascii_uppercase().as_str() {
+            "K" | "KB" | "KIB" => Some(amount.div_ceil(1024)),
+            "M" | "MB" | "MIB" => Some(amount),
+            "" | "G" | "GB" | "GIB" => amount.checked_mul(1024),
+            "T" | "TB" | "TIB" => amount.checked_mul(1024 * 1024),
+            _ => None,
+        };
+        mib.map(Self::Mib).ok_or_else(invalid)
+    }
+}
+
+impl std::fmt::Display for DiskSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mib(mib) if mib % (1024 * 1024) == 0 => write!(f, "{}TB", mib / (1024 * 1024)),
+            Self::Mib(mib) if mib % 1024 == 0 => write!(f, "{}GB", mib / 1024),
+            Self::Mib(mib) => write!(f, "{}MB", mib),
+            Self::Percent(pct) => write!(f, "{}%", pct),
+            Self::Remaining => write!(f, "Remaining"),
+        }
+    }
+}
+
 /// Encryption key type for LUKS
 #[derive(
     Debug,
@@ -429,6 +562,7 @@ pub enum AutoToggle {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -455,6 +589,7 @@ pub enum EncryptionKeyType {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -495,6 +630,7 @@ pub enum PlymouthTheme {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -523,6 +659,7 @@ pub enum GrubTheme {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -548,6 +685,7 @@ pub enum SnapshotTool {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -576,6 +714,7 @@ pub enum SnapshotFrequency {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -619,6 +758,7 @@ impl NetworkManager {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -642,6 +782,7 @@ pub enum DeVariant {
     Default,
     Serialize,
     Deserialize,
+    JsonSchema,
     Display,
     EnumString,
     EnumIter,
@@ -720,6 +861,57 @@ mod tests {
         assert_eq!(Toggle::from(false), Toggle::No);
     }
 
+    #[test]
+    fn test_swap_mode_accepts_legacy_toggle_values() {
+        assert_eq!(SwapMode::from_str("Yes").unwrap(), SwapMode::Partition);
+        assert_eq!(SwapMode::from_str("No").unwrap(), SwapMode::None);
+        assert_eq!(SwapMode::Partition.to_string(), "partition");
+        assert_eq!(SwapMode::ZramSwapfile.to_string(), "zram+swapfile");
+        assert_eq!(
+            SwapMode::from_str("zram+swapfile").unwrap(),
+            SwapMode::ZramSwapfile
+        );
+
+        let parsed: SwapMode = serde_json::from_str("\"Yes\"").unwrap();
+        assert_eq!(parsed, SwapMode::Partition);
+        let parsed: SwapMode = serde_json::from_str("\"No\"").unwrap();
+        assert_eq!(parsed, SwapMode::None);
+    }
+
+    #[test]
+    fn test_disk_size_parsing() {
+        assert_eq!(DiskSize::from_str("50GB").unwrap(), DiskSize::Mib(51200));
+        assert_eq!(DiskSize::from_str("50GiB").unwrap(), DiskSize::Mib(51200));
+        assert_eq!(DiskSize::from_str("50").unwrap(), DiskSize::Mib(51200));
+        assert_eq!(DiskSize::from_str("512MiB").unwrap(), DiskSize::Mib(512));
+        assert_eq!(DiskSize::from_str("2048 mb").unwrap(), DiskSize::Mib(2048));
+        assert_eq!(DiskSize::from_str("1TB").unwrap(), DiskSize::Mib(1048576));
+        assert_eq!(DiskSize::from_str("25%").unwrap(), DiskSize::Percent(25));
+        assert_eq!(
+            DiskSize::from_str("remaining").unwrap(),
+            DiskSize::Remaining
+        );
+        assert_eq!(DiskSize::from_str("").unwrap(), DiskSize::Remaining);
+
+        // test: malformed input is rejected, not silently defaulted
+        for bad in ["0GB", "50.5GB", "-1G", "50PB", "0%", "150%", "GB", "N/A"] {
+            assert!(DiskSize::from_str(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_disk_size_resolve_and_display() {
+        assert_eq!(DiskSize::Percent(25).resolve_mib(100_000), Some(25_000));
+        assert_eq!(DiskSize::Mib(512).resolve_mib(100_000), Some(512));
+        assert_eq!(DiskSize::Remaining.resolve_mib(100_000), None);
+
+        // test: Display round-trips through the bash size helpers' units
+        assert_eq!(DiskSize::Mib(2048).to_string(), "2GB");
+        assert_eq!(DiskSize::Mib(1048576).to_string(), "1TB");
+        assert_eq!(DiskSize::Mib(1500).to_string(), "1500MB");
+        assert_eq!(DiskSize::Percent(40).to_string(), "40%");
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         let original = BootMode::Uefi;
@@ -224,6 +224,7 @@ pub fn get_disk_tool_description(selection: usize) -> Vec<Line<'static>> {
         3 => check_disk_health_description(),
         4 => mount_unmount_description(),
         5 => luks_encryption_description(),
+        6 => cleanup_previous_attempt_description(),
         _ => back_to_menu_description("Tools Menu"),
     }
 }
@@ -1093,6 +1094,49 @@ fn luks_encryption_description() -> Vec<Line<'static>> {
     ]
 }
 
+fn cleanup_previous_attempt_description() -> Vec<Line<'static>> {
+    vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "  Clean Up Previous Attempt",
+            Styles::category(),
+        )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "  Release storage left active by a failed install",
+            Styles::text(),
+        )]),
+        Line::from(vec![Span::styled(
+            "  so the next attempt starts clean.",
+            Styles::text(),
+        )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "  Steps (the ops the last install recorded as applied):",
+            Style::default()
+                .fg(Colors::SUCCESS)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![Span::styled(
+            "  • Unmount /mnt and its submounts",
+            Styles::text_secondary(),
+        )]),
+        Line::from(vec![Span::styled(
+            "  • Swapoff, deactivate LVM volume groups",
+            Styles::text_secondary(),
+        )]),
+        Line::from(vec![Span::styled(
+            "  • Close LUKS mappers, stop RAID arrays",
+            Styles::text_secondary(),
+        )]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  ℹ️  ", Styles::info()),
+            Span::styled("Does not modify partitions or data on disk", Styles::info()),
+        ]),
+    ]
+}
+
 fn enable_services_description() -> Vec<Line<'static>> {
     vec![
         Line::from(""),
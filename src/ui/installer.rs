@@ -6,6 +6,7 @@
 use super::header::{render_installer_output, render_progress_bar};
 use super::menus::render_breadcrumb;
 use crate::app::{AppState, ConfigEditState};
+use crate::install_state::{InstallStage, InstallerContext};
 use crate::theme::Colors;
 use ratatui::{
     Frame,
@@ -910,49 +911,68 @@ pub fn render_automated_install_ui(f: &mut Frame, _state: &AppState, area: Rect)
 // Installation Progress
 // =============================================================================
 
-/// Phase names for step indicators — aligned to actual progress percentages
+/// Phase names for step indicators, mapped from install stages by `install_phase`
 const INSTALL_PHASES: &[&str] = &[
-    "Preflight",     //  0-13%: validate, mirrors, deps
-    "Partitioning",  // 14-19%: disk partition + format
-    "Base System",   // 20-44%: pacstrap
-    "System Config", // 45-57%: fstab, locale, timezone, hostname
-    "Bootloader",    // 58-64%: bootloader + initramfs
-    "Desktop",       // 65-77%: DE/WM install
-    "Packages",      // 78-91%: AUR, extra packages, dotfiles
-    "Finalizing",    // 92-100%: cleanup + unmount
+    "Preflight",     // validate, prepare system, dependencies
+    "Partitioning",  // disk partition + format
+    "Base System",   // pacstrap
+    "System Config", // fstab, chroot step 1: locale, timezone, hostname
+    "Bootloader",    // chroot step 2: bootloader + initramfs
+    "Desktop",       // chroot step 3: DE/WM install
+    "Packages",      // chroot steps 4-5: AUR, extra packages, dotfiles
+    "Finalizing",    // cleanup + unmount
 ];
 
-/// Map progress percentage to phase index via thresholds (not linear)
-fn progress_to_phase(progress: u8) -> usize {
-    match progress {
-        0..=13 => 0,
-        14..=19 => 1,
-        20..=44 => 2,
-        45..=57 => 3,
-        58..=64 => 4,
-        65..=77 => 5,
-        78..=91 => 6,
-        _ => 7,
+/// Map the installer's stage (and chroot step) to a phase indicator index.
+/// A failed install stays on the phase it failed in.
+fn install_phase(ctx: &InstallerContext) -> usize {
+    match ctx.failed_at().unwrap_or(ctx.current_stage()) {
+        InstallStage::NotStarted
+        | InstallStage::ValidatingConfig
+        | InstallStage::PreparingSystem
+        | InstallStage::InstallingDependencies => 0,
+        InstallStage::PartitioningDisk => 1,
+        InstallStage::InstallingBaseSystem => 2,
+        InstallStage::GeneratingFstab => 3,
+        InstallStage::ConfiguringChroot => match ctx.current_step().map(|step| step.index) {
+            None | Some(1) => 3,
+            Some(2) => 4,
+            Some(3) => 5,
+            Some(_) => 6,
+        },
+        InstallStage::Finalizing | InstallStage::Completed | InstallStage::Failed => 7,
+    }
+}
+
+/// Format a stage duration as m:ss (or h:mm:ss).
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
     }
 }
 
 pub fn render_installation_ui(f: &mut Frame, state: &AppState, area: Rect) {
+    let packages_height = u16::from(state.package_progress.is_some());
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // Breadcrumb
-            Constraint::Length(3), // Progress bar
-            Constraint::Length(1), // Phase steps
-            Constraint::Length(1), // Status
-            Constraint::Min(1),    // Output
+            Constraint::Length(1),               // Breadcrumb
+            Constraint::Length(3),               // Progress bar
+            Constraint::Length(1),               // Phase steps
+            Constraint::Length(1),               // Status
+            Constraint::Length(packages_height), // ALPM packages
+            Constraint::Min(1),                  // Output
         ])
         .split(area);
 
     render_breadcrumb(f, layout[0], &["Installation"]);
-    render_progress_bar(f, layout[1], state.installation_progress as u16);
+    let ctx = &state.install_context;
+    render_progress_bar(f, layout[1], ctx.progress_percent() as u16);
 
     // Phase step indicators
-    let current_phase = progress_to_phase(state.installation_progress);
+    let current_phase = install_phase(ctx);
     let phase_spans: Vec<Span> = INSTALL_PHASES
         .iter()
         .enumerate()
@@ -981,7 +1001,7 @@ pub fn render_installation_ui(f: &mut Frame, state: &AppState, area: Rect) {
     f.render_widget(phases, layout[2]);
 
     // Status line
-    let status_style = if state.installation_progress >= 100 {
+    let status_style = if ctx.is_complete() {
         Style::default().fg(Colors::SUCCESS).bg(Colors::BG_PRIMARY)
     } else {
         Style::default()
@@ -998,9 +1018,32 @@ pub fn render_installation_ui(f: &mut Frame, state: &AppState, area: Rect) {
     .style(Style::default().bg(Colors::BG_PRIMARY));
     f.render_widget(status_line, layout[3]);
 
+    // ALPM transaction: current package, bytes and overall percent
+    if let Some(ref progress) = state.package_progress {
+        let mut spans = vec![
+            Span::styled(
+                " Packages: ",
+                Style::default().fg(Colors::FG_MUTED).bg(Colors::BG_PRIMARY),
+            ),
+            Span::styled(
+                progress.status_line(),
+                Style::default().fg(Colors::PRIMARY).bg(Colors::BG_PRIMARY),
+            ),
+        ];
+        if !progress.failed_downloads.is_empty() {
+            spans.push(Span::styled(
+                format!("  ({} downloads failed)", progress.failed_downloads.len()),
+                Style::default().fg(Colors::ERROR).bg(Colors::BG_PRIMARY),
+            ));
+        }
+        let packages_line =
+            Paragraph::new(Line::from(spans)).style(Style::default().bg(Colors::BG_PRIMARY));
+        f.render_widget(packages_line, layout[4]);
+    }
+
     render_installer_output(
         f,
-        layout[4],
+        layout[5],
         &state.installer_output,
         state.installer_scroll_offset,
         state.installer_auto_scroll,
@@ -1012,9 +1055,8 @@ pub fn render_installation_ui(f: &mut Frame, state: &AppState, area: Rect) {
 // =============================================================================
 
 pub fn render_completion_ui(f: &mut Frame, state: &AppState, area: Rect) {
-    let is_success = state.installation_progress >= 100
-        && !state.status_message.to_lowercase().contains("fail")
-        && !state.status_message.to_lowercase().contains("error");
+    let ctx = &state.install_context;
+    let is_success = ctx.is_complete();
 
     let crumb = if is_success { "Complete" } else { "Failed" };
 
@@ -1034,6 +1076,7 @@ pub fn render_completion_ui(f: &mut Frame, state: &AppState, area: Rect) {
             vec![
                 Constraint::Length(1),  // Breadcrumb
                 Constraint::Length(5),  // Status banner
+                Constraint::Length(5),  // Stage timing
                 Constraint::Length(10), // Secure Boot reminder
                 Constraint::Min(1),     // Output log
                 Constraint::Length(1),  // Hint
@@ -1042,6 +1085,7 @@ pub fn render_completion_ui(f: &mut Frame, state: &AppState, area: Rect) {
             vec![
                 Constraint::Length(1), // Breadcrumb
                 Constraint::Length(5), // Status banner
+                Constraint::Length(5), // Stage timing
                 Constraint::Min(1),    // Output log
                 Constraint::Length(1), // Hint
             ]
@@ -1072,6 +1116,30 @@ pub fn render_completion_ui(f: &mut Frame, state: &AppState, area: Rect) {
         .alignment(Alignment::Left);
     f.render_widget(banner, layout[1]);
 
+    // Stage timing from the install state machine
+    let stage_spans: Vec<Span> = ctx
+        .stage_durations()
+        .into_iter()
+        .flat_map(|(stage, secs)| {
+            let (icon, style) = if ctx.failed_at() == Some(stage) {
+                ("\u{2717}", Style::default().fg(Colors::ERROR))
+            } else {
+                ("\u{2713}", Style::default().fg(Colors::SUCCESS))
+            };
+            vec![
+                Span::styled(format!(" {} {} ", icon, stage), style),
+                Span::styled(
+                    format!("{}  ", format_duration(secs)),
+                    Style::default().fg(Colors::FG_MUTED),
+                ),
+            ]
+        })
+        .collect();
+    let stages = Paragraph::new(Line::from(stage_spans))
+        .block(panel_inactive("Stages"))
+        .wrap(Wrap { trim: true });
+    f.render_widget(stages, layout[2]);
+
     // Secure Boot post-install reminder
     let log_idx = if secure_boot_enabled {
         let sb_lines = vec![
@@ -1106,10 +1174,10 @@ pub fn render_completion_ui(f: &mut Frame, state: &AppState, area: Rect) {
         let sb_para = Paragraph::new(sb_lines)
             .block(panel_active("Secure Boot"))
             .alignment(Alignment::Left);
-        f.render_widget(sb_para, layout[2]);
-        3 // output log at index 3
+        f.render_widget(sb_para, layout[3]);
+        4 // output log at index 4
     } else {
-        2 // output log at index 2
+        3 // output log at index 3
     };
     let hint_idx = log_idx + 1;
 
@@ -1212,7 +1280,7 @@ pub fn render_dry_run_summary(f: &mut Frame, state: &AppState, area: Rect) {
 
     let block = panel_active("Actions to be Performed").title_bottom(
         Line::from(vec![Span::styled(
-            " \u{2191}\u{2193} scroll | B=back | Enter=dismiss ",
+            " \u{2191}\u{2193} scroll | E=export | B=back | Enter=dismiss ",
             Style::default().fg(Colors::FG_MUTED),
         )])
         .alignment(Alignment::Right),
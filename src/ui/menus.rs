@@ -5,7 +5,7 @@
 //! full-width highlight bar. Rounded borders everywhere.
 
 use super::descriptions;
-use crate::app::AppState;
+use crate::app::{AppMode, AppState};
 use crate::theme::Colors;
 use ratatui::{
     Frame,
@@ -18,6 +18,89 @@ use ratatui::{
     },
 };
 
+// =============================================================================
+// Menu items
+// =============================================================================
+
+/// Main menu items, in selection order
+pub const MAIN_MENU_ITEMS: &[&str] = &[
+    "Guided Installer",
+    "Automated Install",
+    "Arch Linux Tools",
+    "Hardware Summary",
+    "Quit",
+];
+
+/// Tool categories, "Back" last
+pub const TOOLS_MENU_ITEMS: &[&str] = &[
+    "Disk Tools",
+    "System Tools",
+    "User Tools",
+    "Network Tools",
+    "\u{25c0} Back",
+];
+
+/// Disk tools, "Back" last
+pub const DISK_TOOLS_ITEMS: &[&str] = &[
+    "Partition Disk",
+    "Format Partition",
+    "Wipe Disk",
+    "Check Disk Health",
+    "Mount/Unmount",
+    "LUKS Encryption",
+    "Clean Up Previous Attempt",
+    "\u{25c0} Back",
+];
+
+/// System tools, "Back" last
+pub const SYSTEM_TOOLS_ITEMS: &[&str] = &[
+    "Install Bootloader",
+    "Generate fstab",
+    "Chroot into System",
+    "Manage Services",
+    "System Info",
+    "Enable Services",
+    "Install AUR Helper",
+    "Rebuild Initramfs",
+    "View Install Logs",
+    "\u{25c0} Back",
+];
+
+/// User tools, "Back" last
+pub const USER_TOOLS_ITEMS: &[&str] = &[
+    "Add User",
+    "Reset Password",
+    "Manage Groups",
+    "Configure SSH",
+    "Security Audit",
+    "Install Dotfiles",
+    "Run As User",
+    "\u{25c0} Back",
+];
+
+/// Network tools, "Back" last
+pub const NETWORK_TOOLS_ITEMS: &[&str] = &[
+    "Configure Network",
+    "Test Connectivity",
+    "Firewall Rules",
+    "Network Info",
+    "Update Mirrors",
+    "\u{25c0} Back",
+];
+
+/// Items of the menu shown in `mode` (empty for screens that are not menus)
+pub fn menu_items(mode: AppMode) -> &'static [&'static str] {
+    match mode {
+        AppMode::MainMenu => MAIN_MENU_ITEMS,
+        AppMode::ToolsMenu => TOOLS_MENU_ITEMS,
+        AppMode::DiskTools => DISK_TOOLS_ITEMS,
+        AppMode::SystemTools => SYSTEM_TOOLS_ITEMS,
+        AppMode::UserTools => USER_TOOLS_ITEMS,
+        AppMode::NetworkTools => NETWORK_TOOLS_ITEMS,
+        _ => &[],
+    }
+}
+
 // =============================================================================
 // Shared helpers
 // =============================================================================
@@ -186,19 +269,14 @@ pub fn render_main_menu(f: &mut Frame, state: &AppState, area: Rect) {
 
     let (left, right) = split_pane(layout[1]);
     let sel = state.main_menu_selection;
-    let count = 4;
+    let count = MAIN_MENU_ITEMS.len();
     let pos = format!("{}/{}", sel + 1, count);
 
-    let items: Vec<ListItem> = [
-        "Guided Installer",
-        "Automated Install",
-        "Arch Linux Tools",
-        "Quit",
-    ]
-    .iter()
-    .enumerate()
-    .map(|(i, name)| highlight_item(i, sel, name, left.width))
-    .collect();
+    let items: Vec<ListItem> = MAIN_MENU_ITEMS
+        .iter()
+        .enumerate()
+        .map(|(i, name)| highlight_item(i, sel, name, left.width))
+        .collect();
 
     let menu = List::new(items).block(panel_active("Main Menu", Some(&pos)));
     f.render_widget(menu, left);
@@ -337,6 +415,33 @@ pub fn render_main_menu(f: &mut Frame, state: &AppState, area: Rect) {
                 ),
             ]),
         ],
+        3 => vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  Hardware Summary",
+                Style::default()
+                    .fg(Colors::SECONDARY)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  What the installer detected on this",
+                Style::default().fg(Colors::FG_PRIMARY),
+            )),
+            Line::from(Span::styled(
+                "  machine, before anything is changed.",
+                Style::default().fg(Colors::FG_PRIMARY),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  CPU, GPUs, Wi-Fi, RAM, disks, TPM 2.0",
+                Style::default().fg(Colors::FG_SECONDARY),
+            )),
+            Line::from(Span::styled(
+                "  and laptop/battery.",
+                Style::default().fg(Colors::FG_SECONDARY),
+            )),
+        ],
         _ => vec![
             Line::from(""),
             Line::from(Span::styled(
@@ -359,6 +464,49 @@ pub fn render_main_menu(f: &mut Frame, state: &AppState, area: Rect) {
     f.render_widget(info, right);
 }
 
+// =============================================================================
+// HARDWARE SUMMARY
+// =============================================================================
+
+pub fn render_hardware_summary(f: &mut Frame, state: &AppState, area: Rect) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(area);
+    render_breadcrumb(f, layout[0], &["Hardware"]);
+
+    let block = panel_active("Detected Hardware", None).title_bottom(
+        Line::from(vec![Span::styled(
+            " \u{2191}\u{2193} scroll | B=back ",
+            Style::default().fg(Colors::FG_MUTED),
+        )])
+        .alignment(Alignment::Right),
+    );
+    let inner_area = block.inner(layout[1]);
+    f.render_widget(block, layout[1]);
+
+    let visible_height = inner_area.height as usize;
+    let lines: Vec<ListItem> = match state.hardware_summary {
+        Some(ref summary) => {
+            let max_offset = summary.len().saturating_sub(visible_height);
+            let offset = state.hardware_scroll_offset.min(max_offset);
+            summary
+                .iter()
+                .skip(offset)
+                .take(visible_height)
+                .map(|line| {
+                    ListItem::new(format!("  {}", line))
+                        .style(Style::default().fg(Colors::FG_PRIMARY))
+                })
+                .collect()
+        }
+        None => vec![
+            ListItem::new("  No hardware detected").style(Style::default().fg(Colors::FG_MUTED)),
+        ],
+    };
+    f.render_widget(List::new(lines), inner_area);
+}
+
 // =============================================================================
 // TOOLS MENU
 // =============================================================================
@@ -372,20 +520,14 @@ pub fn render_tools_menu(f: &mut Frame, state: &AppState, area: Rect) {
 
     let (left, right) = split_pane(layout[1]);
     let sel = state.tools_menu_selection;
-    let count = 5;
+    let count = TOOLS_MENU_ITEMS.len();
     let pos = format!("{}/{}", sel + 1, count);
 
-    let items: Vec<ListItem> = [
-        "Disk Tools",
-        "System Tools",
-        "User Tools",
-        "Network Tools",
-        "\u{25c0} Back",
-    ]
-    .iter()
-    .enumerate()
-    .map(|(i, name)| highlight_item(i, sel, name, left.width))
-    .collect();
+    let items: Vec<ListItem> = TOOLS_MENU_ITEMS
+        .iter()
+        .enumerate()
+        .map(|(i, name)| highlight_item(i, sel, name, left.width))
+        .collect();
 
     let menu = List::new(items).block(panel_active("Select Category", Some(&pos)));
     f.render_widget(menu, left);
@@ -456,15 +598,7 @@ pub fn render_disk_tools_menu(f: &mut Frame, state: &AppState, area: Rect) {
         &["Tools", "Disk"],
         "Disk Tools",
         Some(Colors::CAT_DISK),
-        &[
-            "Partition Disk",
-            "Format Partition",
-            "Wipe Disk",
-            "Check Disk Health",
-            "Mount/Unmount",
-            "LUKS Encryption",
-            "\u{25c0} Back",
-        ],
+        DISK_TOOLS_ITEMS,
         descriptions::get_disk_tool_description,
     );
 }
@@ -477,18 +611,7 @@ pub fn render_system_tools_menu(f: &mut Frame, state: &AppState, area: Rect) {
         &["Tools", "System"],
         "System Tools",
         Some(Colors::CAT_SYSTEM),
-        &[
-            "Install Bootloader",
-            "Generate fstab",
-            "Chroot into System",
-            "Manage Services",
-            "System Info",
-            "Enable Services",
-            "Install AUR Helper",
-            "Rebuild Initramfs",
-            "View Install Logs",
-            "\u{25c0} Back",
-        ],
+        SYSTEM_TOOLS_ITEMS,
         descriptions::get_system_tool_description,
     );
 }
@@ -501,16 +624,7 @@ pub fn render_user_tools_menu(f: &mut Frame, state: &AppState, area: Rect) {
         &["Tools", "User"],
         "User Tools",
         Some(Colors::CAT_USER),
-        &[
-            "Add User",
-            "Reset Password",
-            "Manage Groups",
-            "Configure SSH",
-            "Security Audit",
-            "Install Dotfiles",
-            "Run As User",
-            "\u{25c0} Back",
-        ],
+        USER_TOOLS_ITEMS,
         descriptions::get_user_tool_description,
     );
 }
@@ -523,14 +637,32 @@ pub fn render_network_tools_menu(f: &mut Frame, state: &AppState, area: Rect) {
         &["Tools", "Network"],
         "Network Tools",
         Some(Colors::CAT_NETWORK),
-        &[
-            "Configure Network",
-            "Test Connectivity",
-            "Firewall Rules",
-            "Network Info",
-            "Update Mirrors",
-            "\u{25c0} Back",
-        ],
+        NETWORK_TOOLS_ITEMS,
         descriptions::get_network_tool_description,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_menus_end_with_back() {
+        for mode in [
+            AppMode::ToolsMenu,
+            AppMode::DiskTools,
+            AppMode::SystemTools,
+            AppMode::UserTools,
+            AppMode::NetworkTools,
+        ] {
+            assert_eq!(
+                menu_items(mode).last(),
+                Some(&"\u{25c0} Back"),
+                "{:?}",
+                mode
+            );
+        }
+        assert_eq!(menu_items(AppMode::MainMenu).last(), Some(&"Quit"));
+        assert!(menu_items(AppMode::Complete).is_empty());
+    }
+}
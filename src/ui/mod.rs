@@ -322,6 +322,9 @@ impl UiRenderer {
             AppMode::DryRunSummary => {
                 installer::render_dry_run_summary(f, state, content_area);
             }
+            AppMode::HardwareSummary => {
+                menus::render_hardware_summary(f, state, content_area);
+            }
         }
 
         // Render nav bar (redesigned component)
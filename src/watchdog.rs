@@ -0,0 +1,241 @@
+//! Script Watchdog
+//!
+//! Enforces the `timeout_secs` and `idle_timeout_secs` limits a manifest may
+//! declare. A watchdog thread watches one child process group and, once a
+//! limit is exceeded, terminates it through
+//! `process_guard::terminate_child` (SIGTERM, grace period, then SIGKILL).
+//!
+//! - `timeout_secs`: wall-clock limit for the whole run
+//! - `idle_timeout_secs`: longest allowed gap between two chunks of output
+//!   on stdout/stderr, for tools that hang silently (a stuck `reflector`,
+//!   `pacman` waiting on a lock)
+//!
+//! The caller learns whether the watchdog fired from `Watchdog::finish`, and
+//! reports it as `ArchTuiError::Timeout`.
+
+// Library API - not every helper is used by the binary
+#![allow(dead_code)]
+
+use crate::error::ArchTuiError;
+use crate::process_guard;
+use crate::script_manifest::ScriptManifest;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Grace period between SIGTERM and SIGKILL for a timed-out script.
+pub const TIMEOUT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How often the watchdog re-checks its limits.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Time limits for one script run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchdogLimits {
+    /// Wall-clock limit for the whole run
+    pub timeout: Option<Duration>,
+    /// Longest allowed gap between two chunks of output
+    pub idle_timeout: Option<Duration>,
+}
+
+impl WatchdogLimits {
+    /// Limits declared by a manifest.
+    pub fn from_manifest(manifest: &ScriptManifest) -> Self {
+        Self {
+            timeout: manifest.timeout_secs.map(Duration::from_secs),
+            idle_timeout: manifest.idle_timeout_secs.map(Duration::from_secs),
+        }
+    }
+
+    /// Whether no limit is set (no watchdog thread is needed).
+    pub fn is_unlimited(&self) -> bool {
+        self.timeout.is_none() && self.idle_timeout.is_none()
+    }
+}
+
+/// Time of the most recent output, shared by the output readers and the watchdog.
+#[derive(Debug, Clone)]
+pub struct ActivityClock(Arc<Mutex<Instant>>);
+
+impl ActivityClock {
+    /// Clock starting now.
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    /// Record output.
+    pub fn touch(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Time since the last output (or since the clock was created).
+    pub fn idle_for(&self) -> Duration {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).elapsed()
+    }
+}
+
+impl Default for ActivityClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which limit a script exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutKind {
+    /// `timeout_secs` — the run took too long overall
+    Total,
+    /// `idle_timeout_secs` — no output for too long
+    Idle,
+}
+
+/// A script the watchdog terminated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptTimeout {
+    /// Script file name
+    pub script: String,
+    /// Which limit was exceeded
+    pub kind: TimeoutKind,
+    /// The limit that was exceeded
+    pub limit: Duration,
+}
+
+impl fmt::Display for ScriptTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            TimeoutKind::Total => write!(
+                f,
+                "{} did not finish within {}s and was terminated",
+                self.script,
+                self.limit.as_secs()
+            ),
+            TimeoutKind::Idle => write!(
+                f,
+                "{} produced no output for {}s and was terminated",
+                self.script,
+                self.limit.as_secs()
+            ),
+        }
+    }
+}
+
+impl From<ScriptTimeout> for ArchTuiError {
+    fn from(timeout: ScriptTimeout) -> Self {
+        ArchTuiError::Timeout(timeout.to_string())
+    }
+}
+
+/// Watchdog for one running child process group.
+#[derive(Debug)]
+pub struct Watchdog {
+    stop_tx: Option<Sender<()>>,
+    handle: Option<JoinHandle<Option<ScriptTimeout>>>,
+}
+
+impl Watchdog {
+    /// Watch `pid` (a registered process group leader) against `limits`.
+    ///
+    /// No thread is started when `limits` is unlimited.
+    pub fn spawn(script: &str, pid: u32, limits: WatchdogLimits, clock: ActivityClock) -> Self {
+        if limits.is_unlimited() {
+            return Self {
+                stop_tx: None,
+                handle: None,
+            };
+        }
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let script = script.to_string();
+        let handle = thread::spawn(move || {
+            let started = Instant::now();
+            loop {
+                match stop_rx.recv_timeout(POLL_INTERVAL) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // Stopped by `finish` (or the owner went away)
+                    _ => return None,
+                }
+                let exceeded = match (limits.timeout, limits.idle_timeout) {
+                    (Some(limit), _) if started.elapsed() >= limit => {
+                        Some((TimeoutKind::Total, limit))
+                    }
+                    (_, Some(limit)) if clock.idle_for() >= limit => {
+                        Some((TimeoutKind::Idle, limit))
+                    }
+                    _ => None,
+                };
+                if let Some((kind, limit)) = exceeded {
+                    let timeout = ScriptTimeout {
+                        script,
+                        kind,
+                        limit,
+                    };
+                    tracing::warn!(pid, "{}", timeout);
+                    process_guard::terminate_child(pid, TIMEOUT_GRACE_PERIOD);
+                    return Some(timeout);
+                }
+            }
+        });
+        Self {
+            stop_tx: Some(stop_tx),
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop watching (call after the child exited) and report whether the
+    /// watchdog terminated it.
+    pub fn finish(mut self) -> Option<ScriptTimeout> {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(());
+        }
+        self.handle.take().and_then(|h| h.join().ok()).flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_from_manifest() {
+        let manifest = ScriptManifest::builder("scripts/tools/test.sh", "Test")
+            .timeout_secs(30)
+            .build()
+            .unwrap();
+        let limits = WatchdogLimits::from_manifest(&manifest);
+        assert_eq!(limits.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(limits.idle_timeout, None);
+        assert!(!limits.is_unlimited());
+
+        let manifest = ScriptManifest::builder("scripts/tools/test.sh", "Test")
+            .build()
+            .unwrap();
+        assert!(WatchdogLimits::from_manifest(&manifest).is_unlimited());
+    }
+
+    #[test]
+    fn test_timeout_display_and_error_variant() {
+        let timeout = ScriptTimeout {
+            script: "update_mirrors.sh".to_string(),
+            kind: TimeoutKind::Idle,
+            limit: Duration::from_secs(120),
+        };
+        let err = ArchTuiError::from(timeout);
+        assert!(matches!(err, ArchTuiError::Timeout(_)));
+        assert_eq!(
+            err.to_string(),
+            "Script timed out: update_mirrors.sh produced no output for 120s and was terminated"
+        );
+    }
+
+    #[test]
+    fn test_finish_without_firing() {
+        let limits = WatchdogLimits {
+            timeout: Some(Duration::from_secs(60)),
+            idle_timeout: None,
+        };
+        // PID is never signalled because the limit is not reached
+        let watchdog = Watchdog::spawn("test.sh", u32::MAX, limits, ActivityClock::new());
+        assert_eq!(watchdog.finish(), None);
+    }
+}
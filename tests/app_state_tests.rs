@@ -8,6 +8,7 @@
 //! - ToolParameter and ToolDialogState behavior
 //! - State field interactions
 
+use archtui::StageEvent;
 use archtui::app::{AppMode, AppState, ToolDialogState, ToolParam, ToolParameter};
 
 // =============================================================================
@@ -36,7 +37,7 @@ fn test_app_state_default_selections_are_zero() {
 #[test]
 fn test_app_state_default_progress_is_zero() {
     let state = AppState::default();
-    assert_eq!(state.installation_progress, 0);
+    assert_eq!(state.install_context.progress_percent(), 0);
 }
 
 #[test]
@@ -131,6 +132,7 @@ fn test_all_app_modes_are_distinct() {
         AppMode::FileBrowser,
         AppMode::ConfirmDialog,
         AppMode::DryRunSummary,
+        AppMode::HardwareSummary,
     ];
 
     let set: HashSet<_> = modes.iter().collect();
@@ -337,11 +339,20 @@ fn test_app_state_menu_selection_bounds() {
 fn test_app_state_progress_update() {
     let mut state = AppState::default();
 
-    state.installation_progress = 50;
-    assert_eq!(state.installation_progress, 50);
+    state.install_context.confirm_destructive_operations();
+    for event in [
+        "@@ARCHTUI stage validating_config",
+        "@@ARCHTUI stage preparing_system",
+        "@@ARCHTUI stage installing_dependencies",
+        "@@ARCHTUI stage partitioning_disk",
+    ] {
+        let event = StageEvent::parse(event).expect("stage event"); // test: known-good input
+        state.install_context.apply(event).expect("next stage"); // test: known-good input
+    }
+    assert_eq!(state.install_context.progress_percent(), 25);
 
-    state.installation_progress = 100;
-    assert_eq!(state.installation_progress, 100);
+    while state.install_context.advance().is_ok() {}
+    assert_eq!(state.install_context.progress_percent(), 100);
 }
 
 #[test]
@@ -8,6 +8,7 @@
 //! - Progress tracking
 //! - State updates during installation
 
+use archtui::StageEvent;
 use archtui::app::{AppMode, AppState};
 use archtui::config::Configuration;
 use archtui::installer::Installer;
@@ -116,28 +117,31 @@ fn test_installer_with_shared_state() {
 fn test_progress_update_pattern() {
     let app_state = Arc::new(Mutex::new(AppState::default()));
 
-    // Simulate progress updates like installer does
+    // Simulate stage events like the installer applies them
     {
         let mut state = app_state.lock().unwrap();
-        state.installation_progress = 10;
-        state.status_message = "Starting installation...".to_string();
+        state.install_context.confirm_destructive_operations();
+        let event = StageEvent::parse("@@ARCHTUI stage validating_config").unwrap();
+        state.install_context.apply(event).unwrap();
+        state.status_message = state.install_context.status_text().to_string();
     }
 
     {
         let state = app_state.lock().unwrap();
-        assert_eq!(state.installation_progress, 10);
+        assert_eq!(state.install_context.progress_percent(), 5);
+        assert_eq!(state.status_message, "Validating configuration");
     }
 
     {
         let mut state = app_state.lock().unwrap();
-        state.installation_progress = 50;
-        state.status_message = "Installing packages...".to_string();
+        // Skipping stages is rejected and leaves progress untouched
+        let event = StageEvent::parse("@@ARCHTUI stage installing_base_system").unwrap();
+        assert!(state.install_context.apply(event).is_err());
     }
 
     {
         let state = app_state.lock().unwrap();
-        assert_eq!(state.installation_progress, 50);
-        assert!(state.status_message.contains("Installing"));
+        assert_eq!(state.install_context.progress_percent(), 5);
     }
 }
 
@@ -170,7 +174,6 @@ fn test_mode_transition_to_installation() {
         let mut state = app_state.lock().unwrap();
         state.mode = AppMode::Installation;
         state.status_message = "Starting installation...".to_string();
-        state.installation_progress = 10;
     }
 
     let state = app_state.lock().unwrap();
@@ -184,7 +187,8 @@ fn test_mode_transition_to_complete() {
     {
         let mut state = app_state.lock().unwrap();
         state.mode = AppMode::Installation;
-        state.installation_progress = 100;
+        state.install_context.confirm_destructive_operations();
+        while state.install_context.advance().is_ok() {}
     }
 
     {
@@ -195,7 +199,7 @@ fn test_mode_transition_to_complete() {
 
     let state = app_state.lock().unwrap();
     assert_eq!(state.mode, AppMode::Complete);
-    assert_eq!(state.installation_progress, 100);
+    assert!(state.install_context.is_complete());
 }
 
 // =============================================================================
@@ -327,10 +331,57 @@ fn test_state_mutex_not_poisoned_on_normal_use() {
     // Normal lock/unlock cycle
     {
         let mut state = app_state.lock().unwrap();
-        state.installation_progress = 50;
+        state.status_message = "Installing packages...".to_string();
     }
 
     // Should still be lockable
     let result = app_state.lock();
     assert!(result.is_ok(), "Mutex should not be poisoned");
 }
+
+// =============================================================================
+// Package Pre-flight Tests
+// =============================================================================
+
+#[test]
+fn test_failed_package_check_stops_install_at_validating_config() {
+    use archtui::InstallStage;
+    use archtui::logic::availability::{AvailabilityReport, PackageIssue};
+
+    let app_state = Arc::new(Mutex::new(AppState::default()));
+    let report = AvailabilityReport {
+        targets: 1,
+        resolved: 0,
+        download_bytes: 0,
+        installed_bytes: 0,
+        issues: vec![PackageIssue::Missing {
+            name: "not-a-package".to_string(),
+            required_by: None,
+        }],
+    };
+    let mut installer = Installer::new(Configuration::default(), Arc::clone(&app_state))
+        .with_package_check(Box::new(move || Ok(report)));
+    installer
+        .start()
+        .expect("start returns before the check runs"); // test: known-good input
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while app_state.lock().unwrap().mode != AppMode::Complete {
+        assert!(std::time::Instant::now() < deadline, "check never finished");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    let state = app_state.lock().unwrap();
+    assert_eq!(state.install_context.current_stage(), InstallStage::Failed);
+    assert_eq!(
+        state.install_context.failed_at(),
+        Some(InstallStage::ValidatingConfig)
+    );
+    assert!(state.status_message.starts_with("Install not possible"));
+    assert!(
+        state
+            .installer_output
+            .iter()
+            .any(|line| line.contains("not-a-package"))
+    );
+}
@@ -97,6 +97,28 @@ fn test_binary_runs_without_crashing() {
     }
 }
 
+#[test]
+fn test_validate_reports_parse_cause() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("bad.json");
+    std::fs::write(&path, r#"{"hostname": 5}"#).unwrap();
+
+    let bin = env!("CARGO_BIN_EXE_archtui");
+    let output = Command::new(bin)
+        .arg("validate")
+        .arg(&path)
+        .output()
+        .expect("failed to run archtui validate"); // test: known-good input
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("invalid type: integer `5`, expected a string"),
+        "serde cause missing: {}",
+        stderr
+    );
+}
+
 #[test]
 fn test_config_structure() {
     // Test that we can load the configuration structure
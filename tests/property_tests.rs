@@ -106,6 +106,7 @@ fn app_mode_strategy() -> impl Strategy<Value = AppMode> {
         Just(AppMode::FileBrowser),
         Just(AppMode::ConfirmDialog),
         Just(AppMode::DryRunSummary),
+        Just(AppMode::HardwareSummary),
     ]
 }
 
@@ -259,6 +260,7 @@ proptest! {
             exit_code: Some(0),
             success: true,
             dry_run: false,
+            events: Vec::new(),
         };
 
         prop_assert!(output.success);
@@ -279,6 +281,7 @@ proptest! {
             exit_code: Some(exit_code),
             success: false,
             dry_run: false,
+            events: Vec::new(),
         };
 
         prop_assert!(!output.success);
@@ -296,6 +299,7 @@ proptest! {
             exit_code: Some(0),
             success: true,
             dry_run: true,
+            events: Vec::new(),
         };
 
         prop_assert!(output.dry_run);
@@ -24,6 +24,7 @@ fn test_script_output_success() {
         exit_code: Some(0),
         success: true,
         dry_run: false,
+        events: Vec::new(),
     };
 
     assert!(output.success);
@@ -41,6 +42,7 @@ fn test_script_output_failure() {
         exit_code: Some(1),
         success: false,
         dry_run: false,
+        events: Vec::new(),
     };
 
     assert!(!output.success);
@@ -56,6 +58,7 @@ fn test_script_output_signal_termination() {
         exit_code: None, // Terminated by signal
         success: false,
         dry_run: false,
+        events: Vec::new(),
     };
 
     assert!(!output.success);
@@ -70,6 +73,7 @@ fn test_script_output_dry_run() {
         exit_code: Some(0),
         success: true,
         dry_run: true,
+        events: Vec::new(),
     };
 
     assert!(output.dry_run);
@@ -85,6 +89,7 @@ fn test_script_output_ensure_success_ok() {
         exit_code: Some(0),
         success: true,
         dry_run: false,
+        events: Vec::new(),
     };
 
     let result = output.ensure_success("test operation");
@@ -99,6 +104,7 @@ fn test_script_output_ensure_success_err() {
         exit_code: Some(1),
         success: false,
         dry_run: false,
+        events: Vec::new(),
     };
 
     let result = output.ensure_success("disk wipe");
@@ -118,6 +124,7 @@ fn test_script_output_clone() {
         exit_code: Some(42),
         success: false,
         dry_run: true,
+        events: Vec::new(),
     };
 
     let cloned = output.clone();
@@ -201,6 +208,7 @@ fn test_format_partition_args_script_name() {
         filesystem: Filesystem::Ext4,
         label: None,
         force: false,
+        confirm: true,
     };
 
     assert_eq!(args.script_name(), "format_partition.sh");
@@ -213,6 +221,7 @@ fn test_format_partition_args_is_destructive() {
         filesystem: Filesystem::Btrfs,
         label: Some("root".to_string()),
         force: true,
+        confirm: true,
     };
 
     assert!(
@@ -228,6 +237,7 @@ fn test_format_partition_args_with_label() {
         filesystem: Filesystem::Ext4,
         label: Some("BOOT".to_string()),
         force: false,
+        confirm: true,
     };
 
     let cli_args = args.to_cli_args();
@@ -370,6 +380,7 @@ fn test_error_context_in_ensure_success() {
         exit_code: Some(126),
         success: false,
         dry_run: false,
+        events: Vec::new(),
     };
 
     let result = output.ensure_success("format /dev/sda1");
@@ -393,6 +404,7 @@ fn test_nonzero_exit_codes() {
             exit_code: Some(code),
             success: false,
             dry_run: false,
+            events: Vec::new(),
         };
 
         assert!(!output.success);
@@ -414,6 +426,7 @@ fn test_dry_run_output_format() {
         exit_code: Some(0),
         success: true,
         dry_run: true,
+        events: Vec::new(),
     };
 
     assert!(output.dry_run);
@@ -430,6 +443,7 @@ fn test_dry_run_is_always_success() {
         exit_code: Some(0),
         success: true,
         dry_run: true,
+        events: Vec::new(),
     };
 
     assert!(output.success);